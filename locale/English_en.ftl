@@ -692,6 +692,10 @@ pfs_import_files_to_ignore_label = <p><b><i>Files to Ignore when Importing</i></
 pfs_import_files_to_ignore_description_label = <p>The files on this list will be ignored when importing from a MyMod folder. Only for MyMods. Paths are relative, the glory of the empire is absolute.</p>
 pfs_disable_autosaves_label = <p><b><i>Disable Autosaves for this PackFile</i></b></p>
 pfs_disable_autosaves_description_label = <p></p>
+pfs_compression_files_to_ignore_label = <p><b><i>Files to never Compress</i></b></p>
+pfs_compression_files_to_ignore_description_label = <p>The files on this list will never be compressed when saving the PackFile, even if compression is enabled for them. One per line. It accepts both, paths (like <i><b>movies</b></i> or <i><b>movies/intro.ca_vp8</b></i>) and extensions preceded by an asterisk (like <i><b>*.wem</b></i>).</p>
+pfs_allow_table_compression_label = <p><b><i>Allow Compression of DB/Loc Tables</i></b></p>
+pfs_allow_table_compression_description_label = <p>If enabled, DB and Loc Tables can be compressed like any other file. Only enable it if the game supports compressed tables, as otherwise it'll crash.</p>
 
 instructions_ca_vp8 = It's simple, the video can have 2 formats: CAMV (used by the game) and IVF (reproducible on a media player with VP8 codecs).
   To export a video, convert it to IVF and extract it.
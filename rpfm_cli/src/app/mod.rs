@@ -128,38 +128,257 @@ pub fn initialize_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .min_values(2))
 
+            // `Diff` option. Requires you to provide the path of the PackFile to compare against.
+            .arg(Arg::with_name("diff")
+                .short("f")
+                .long("diff")
+                .value_name("NEW PACKFILE PATH")
+                .help("Compares the PackFile with a newer version of it, and prints the added, removed, renamed and changed files. If there is a schema for the game selected, DB/LOC Tables are also compared row by row.")
+                .takes_value(true))
+
+            // `JSON` flag. Changes the output of the diff to JSON.
+            .arg(Arg::with_name("json")
+                .short("j")
+                .long("json")
+                .help("Prints the result of the diff as JSON.")
+                .requires("diff"))
+
             // `List` option.
             .arg(Arg::with_name("list")
                 .short("l")
                 .long("list")
                 .help("Lists the contents of the PackFile."))
 
+            // `Merge TSV` option. Requires you to provide the paths of the TSV files to merge into the tables of the PackFile.
+            .arg(Arg::with_name("merge-tsv")
+                .short("M")
+                .long("merge-tsv")
+                .value_name("TSV FILE PATHS")
+                .help("Merges one or more TSV files into the DB/LOC Tables of the PackFile, matching their rows by their keys, then saves it. Only the columns in the TSV files are updated, rows with new keys are added, and rows with 'delete' in the 'rpfm_merge_action' column are removed. Each TSV file is merged into the table with its same name, like 'db/land_units_tables/my_units' for 'my_units.tsv', or 'text/db/my_texts.loc' for 'my_texts.tsv', and that table is created if it doesn't exist yet. Use '--merge-tsv-into' to merge them into another table.")
+                .takes_value(true)
+                .min_values(1))
+
+            // `Merge TSV Into` option. To choose the table the TSV files are merged into, instead of the one with the same name as them.
+            .arg(Arg::with_name("merge-tsv-into")
+                .long("merge-tsv-into")
+                .value_name("TABLE PATH")
+                .help("Merges the TSV files of '--merge-tsv' into the table in the provided path of the PackFile, like 'db/land_units_tables/data__' or 'text/db/my_texts.loc', instead of the one with their same name. The TSV files must be of the same type of table as the one in that path.")
+                .takes_value(true)
+                .requires("merge-tsv"))
+
+            // `Map Report` option.
+            .arg(Arg::with_name("map-report")
+                .short("m")
+                .long("map-report")
+                .help("Lists the AI hints, deployment zones and prefab instances of each map exported by Terry in the PackFile."))
+
+            // `Mass Edit` option. Requires you to provide the name of the tables to edit, and the mass-edit statement.
+            .arg(Arg::with_name("mass-edit")
+                .short("u")
+                .long("mass-edit")
+                .value_names(&["TABLE NAME", "STATEMENT"])
+                .help("Edits the columns of every DB Table with the provided name (like 'land_units_tables') in the PackFile with a statement like 'set cost = round(cost * 1.15) where category == \"cavalry\"', then saves it. Values are typed, and there are functions for numbers (round, floor, ceil, abs, min, max, int, float), texts (str, len, lower, upper, trim, replace, contains, starts_with, ends_with, substr), conditions (if) and references (lookup, has_reference). Requires a Game Selected.")
+                .takes_value(true)
+                .number_of_values(2))
+
+            // `Query` option. Requires you to provide the SQL query to run.
+            .arg(Arg::with_name("query")
+                .short("q")
+                .long("query")
+                .value_name("SQL QUERY")
+                .help("Runs a read-only SQL query over the DB Tables of the PackFile and its dependencies, and prints the results. Each table is queried by its name, with a column per field and a 'rpfm_path' column with the path of its table. The tables of the dependencies are in the 'dependencies' schema. Requires a Game Selected.")
+                .takes_value(true))
+
+            // `Execute SQL` option. Requires you to provide the SQL statements to run.
+            .arg(Arg::with_name("execute-sql")
+                .short("x")
+                .long("execute-sql")
+                .value_name("SQL STATEMENTS")
+                .help("Runs one or more SQL statements (UPDATE, INSERT, DELETE,...) over the DB Tables of the PackFile, writes the edited tables back to the PackFile, then saves it. The tables of the dependencies can be read, but not edited. Requires a Game Selected.")
+                .takes_value(true))
+
+            // `Recover` option. Requires you to provide the path where the recovered PackFile will be saved.
+            .arg(Arg::with_name("recover")
+                .short("r")
+                .long("recover")
+                .value_name("DESTINATION PACKFILE PATH")
+                .help("Reads a damaged or truncated PackFile, salvaging every file which data is complete, prints what was lost or fixed, and saves the result in the provided path.")
+                .takes_value(true))
+
             // `New Packfile` option. The destination is the path of the PackFile you provided before.
             .arg(Arg::with_name("new-packfile")
                 .short("n")
                 .long("new-packfile")
-                .help("Creates a new empty Packfile with the provided path.")))
+                .help("Creates a new empty Packfile with the provided path."))
+
+            // `Set Compression` option. Requires you to provide if the compression should be enabled or not, and the paths of the files/folders.
+            .arg(Arg::with_name("set-compression")
+                .short("c")
+                .long("set-compression")
+                .value_name("true/false - FILE/FOLDER PATHS")
+                .help("Enables/disables compression for one or more files/folders of the PackFile, then saves it. Use '.' as path for the entire PackFile.")
+                .takes_value(true)
+                .min_values(2))
+
+            // `Set Compression by Type` option. Requires you to provide if the compression should be enabled or not, and the types of the files.
+            .arg(Arg::with_name("set-compression-by-type")
+                .short("C")
+                .long("set-compression-by-type")
+                .value_name("true/false - PACKEDFILE TYPES")
+                .help("Enables/disables compression for all the files of one or more types in the PackFile, then saves it.")
+                .takes_value(true)
+                .min_values(2)
+                .possible_values(&["true", "false", "anim", "anim_fragment", "anim_pack", "anim_table", "ca_vp8", "db", "image", "loc", "matched_combat", "rigid_model", "text", "unknown"])))
 
         // `Table` Subcommand. Every command that allows you to manipulate DB/Loc Tables in any way goes here.
         .subcommand(SubCommand::with_name("table")
             .about("Allows you to manipulate in multiple ways DB/LOC Tables.")
 
-            // `EImort TSV` option. To import DB/Loc `PackedFiles` from TSV.
+            // `EImort TSV` option. To import DB/Loc `PackedFiles` from TSV/JSON/CSV/XLSX, and AnimTable/AnimFragment/MatchedCombat/CEO `PackedFiles` from JSON.
             .arg(Arg::with_name("import")
                 .short("i")
                 .long("import")
-                .value_name("TSV FILE - DESTINATION FILE")
-                .help("Import a compatible TSV file as a DB/LOC table.")
+                .value_name("TSV/JSON/CSV/XLSX FILE - DESTINATION FILE")
+                .help("Import a compatible TSV, JSON, CSV or XLSX file as a DB/LOC table, or a JSON file as an AnimTable, AnimFragment, MatchedCombat or CEO file. The format of DB/LOC tables is taken from their extension, and the type of the AnimTable, AnimFragment, MatchedCombat or CEO JSON files from their name without the '.json' extension, like 'animation_tables.bin.json'.")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(2))
+
+            // `Export TSV` option. To export DB/Loc `PackedFiles` to TSV, and AnimTable/AnimFragment/MatchedCombat/CEO `PackedFiles` to JSON.
+            .arg(Arg::with_name("export")
+                .short("e")
+                .long("export")
+                .value_name("TABLE FILE - DESTINATION FILE")
+                .help("Export a DB/LOC Table's data to a TSV file (or the format set with '--format'), or an AnimTable, AnimFragment, MatchedCombat or CEO file to a JSON file next to it.")
                 .takes_value(true)
                 .min_values(1)
                 .max_values(2))
 
-            // `Export TSV` option. To export DB/Loc `PackedFiles` to TSV.
+            // `Merge TSV` option. To merge a TSV file into an existing DB/Loc `PackedFile`, instead of replacing it.
+            .arg(Arg::with_name("merge")
+                .short("m")
+                .long("merge")
+                .value_name("TSV FILE - TABLE FILE")
+                .help("Merge a TSV file into an existing DB/LOC Table, matching their rows by their keys. Only the columns in the TSV file are updated, rows with new keys are added, and rows with 'delete' in the 'rpfm_merge_action' column are removed.")
+                .takes_value(true)
+                .number_of_values(2))
+
+            // `Format` option. To choose the format DB/Loc `PackedFiles` are exported to.
+            .arg(Arg::with_name("format")
+                .short("f")
+                .long("format")
+                .value_name("FORMAT")
+                .help("Format to export DB/LOC Tables to. If not provided, they're exported to TSV.")
+                .takes_value(true)
+                .possible_values(&["tsv", "json", "csv", "xlsx"])))
+
+        // `Model` Subcommand. Every command that allows you to manipulate RigidModels goes here.
+        .subcommand(SubCommand::with_name("model")
+            .about("Allows you to export RigidModels to glTF/OBJ, and to import edited geometry back.")
+
+            // `Export` option. To export RigidModels to glTF or OBJ.
             .arg(Arg::with_name("export")
                 .short("e")
                 .long("export")
-                .value_name("DB FILE - DESTINATION FILE")
-                .help("Export a DB/LOC Table's data to a TSV file.")
+                .value_name("RIGIDMODEL FILE - DESTINATION FILE")
+                .help("Export a RigidModel to a glTF file. If the destination file ends in '.glb' it's exported as binary glTF, and if it ends in '.obj', as OBJ.")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(2))
+
+            // `Import` option. To replace the geometry of RigidModels with the one in a glTF file.
+            .arg(Arg::with_name("import")
+                .short("i")
+                .long("import")
+                .value_name("RIGIDMODEL FILE - GLTF FILE - DESTINATION FILE")
+                .help("Replace the vertices and indices of the meshes of a RigidModel with the ones in a glTF/GLB file, keeping the rest of the RigidModel. If no destination file is provided, the RigidModel is overwritten.")
+                .takes_value(true)
+                .min_values(2)
+                .max_values(3)))
+
+        // `Image` Subcommand. Every command that allows you to check or convert images goes here.
+        .subcommand(SubCommand::with_name("image")
+            .about("Allows you to check DDS/TGA/PNG images, convert them to PNG, and encode PNG images as DDS.")
+
+            // `Info` option. To print the format, size and mipmaps of images.
+            .arg(Arg::with_name("info")
+                .short("i")
+                .long("info")
+                .value_name("IMAGE FILES")
+                .help("Print the format, size, mipmap count and faces of one or more images.")
+                .takes_value(true)
+                .min_values(1))
+
+            // `Convert` option. To convert images to PNG.
+            .arg(Arg::with_name("convert")
+                .short("c")
+                .long("convert")
+                .value_name("IMAGE FILE - DESTINATION FILE")
+                .help("Convert a DDS/TGA image to PNG. Only the biggest mipmap of the first face is converted.")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(2))
+
+            // `Encode` option. To encode PNG images as DDS.
+            .arg(Arg::with_name("encode")
+                .short("e")
+                .long("encode")
+                .value_name("PNG FILE - FORMAT - DESTINATION FILE")
+                .help("Encode a PNG image as a DDS image. Valid formats are: dxt1, dxt3, dxt5, bc4, bc5, bc7, rgba8, bgra8 and bgr8.")
+                .takes_value(true)
+                .min_values(2)
+                .max_values(3))
+
+            // `Mipmaps` option. To generate mipmaps when encoding images.
+            .arg(Arg::with_name("mipmaps")
+                .short("m")
+                .long("mipmaps")
+                .help("Generate mipmaps when encoding a PNG image as DDS.")
+                .takes_value(false)))
+
+        // `Video` Subcommand. Every command that allows you to create or check CA_VP8 videos goes here.
+        .subcommand(SubCommand::with_name("video")
+            .about("Allows you to create CA_VP8 videos from IVF/WebM videos, and to check their frame tables.")
+
+            // `Import` option. To create CA_VP8 videos from IVF or WebM videos.
+            .arg(Arg::with_name("import")
+                .short("i")
+                .long("import")
+                .value_name("VIDEO FILE - DESTINATION FILE")
+                .help("Create a CA_VP8 video from an IVF or WebM video with VP8 frames. The size and framerate of the video are taken from the stream.")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(2))
+
+            // `Check` option. To check the frame tables of CA_VP8 videos.
+            .arg(Arg::with_name("check")
+                .short("c")
+                .long("check")
+                .value_name("VIDEO FILES")
+                .help("Check that the offsets, sizes and key frames of the frame table of one or more CA_VP8 videos agree with their frame data.")
+                .takes_value(true)
+                .min_values(1)))
+
+        // `ESF` Subcommand. Every command that allows you to dump ESF files or load them back goes here.
+        .subcommand(SubCommand::with_name("esf")
+            .about("Allows you to dump ESF files (like startpos.esf) to JSON or XML, and to load them back.")
+
+            // `Dump` option. To dump an ESF file to JSON or XML.
+            .arg(Arg::with_name("dump")
+                .short("d")
+                .long("dump")
+                .value_name("ESF FILE - DESTINATION FILE")
+                .help("Dump an ESF file to JSON or XML, depending on the extension of the destination file. If no destination is provided, it's dumped to a XML file next to the ESF.")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(2))
+
+            // `Load` option. To create an ESF file from a JSON or XML dump.
+            .arg(Arg::with_name("load")
+                .short("l")
+                .long("load")
+                .value_name("DUMP FILE - DESTINATION FILE")
+                .help("Create an ESF file from a JSON or XML dump. If no destination is provided, the ESF is created next to the dump.")
                 .takes_value(true)
                 .min_values(1)
                 .max_values(2)))
@@ -0,0 +1,77 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+use log::info;
+use std::fs::{read, read_to_string, write};
+use std::path::PathBuf;
+
+use rpfm_error::Result;
+use rpfm_lib::packedfile::esf::{ESF, EXTENSION};
+
+use crate::config::Config;
+use super::is_json;
+
+//---------------------------------------------------------------------------//
+// 							ESF Command Variants
+//---------------------------------------------------------------------------//
+
+/// This function dumps an ESF file to JSON or XML, depending on the extension of the destination path.
+///
+/// If no destination path was provided, it leaves a XML dump in the same place as the ESF file, with the same name.
+pub fn dump(
+    config: &Config,
+    source_path: &str,
+    destination_path: Option<&str>,
+) -> Result<()> {
+
+	if config.verbosity_level > 0 {
+		info!("Dump ESF: {}", source_path);
+	}
+
+    let source_path = PathBuf::from(source_path);
+    let destination_path = match destination_path {
+        Some(destination_path) => PathBuf::from(destination_path),
+        None => source_path.with_extension("xml"),
+    };
+
+    let esf = ESF::read(&read(&source_path)?)?;
+    let dump = if is_json(&destination_path) { esf.to_json()? } else { esf.to_xml()? };
+    write(&destination_path, dump)?;
+
+    info!("ESF dumped to {}.", destination_path.to_string_lossy());
+    Ok(())
+}
+
+/// This function creates an ESF file from a JSON or XML dump, depending on the extension of the dump.
+///
+/// If no destination path was provided, it leaves the ESF file in the same place as the dump, with the same name.
+pub fn load(
+    config: &Config,
+    source_path: &str,
+    destination_path: Option<&str>,
+) -> Result<()> {
+
+	if config.verbosity_level > 0 {
+		info!("Load ESF: {}", source_path);
+	}
+
+    let source_path = PathBuf::from(source_path);
+    let destination_path = match destination_path {
+        Some(destination_path) => PathBuf::from(destination_path),
+        None => source_path.with_extension(&EXTENSION[1..]),
+    };
+
+    let dump = read_to_string(&source_path)?;
+    let esf = if is_json(&source_path) { ESF::from_json(&dump)? } else { ESF::from_xml(&dump)? };
+    write(&destination_path, esf.save()?)?;
+
+    info!("ESF saved to {}.", destination_path.to_string_lossy());
+    Ok(())
+}
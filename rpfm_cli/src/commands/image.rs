@@ -0,0 +1,103 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+use log::info;
+use std::fs::{read, write};
+use std::path::PathBuf;
+
+use rpfm_error::Result;
+use rpfm_lib::packedfile::image::{Image, ImageFormat};
+
+use crate::config::Config;
+
+//---------------------------------------------------------------------------//
+// 							Image Command Variants
+//---------------------------------------------------------------------------//
+
+/// This function prints the format, size, mipmaps and faces of one or more images.
+pub fn info(
+    config: &Config,
+    image_paths: &[&str],
+) -> Result<()> {
+
+	if config.verbosity_level > 0 {
+		info!("Image Info: {:?}", image_paths);
+	}
+
+    for image_path in image_paths {
+        let image = Image::read(&read(image_path)?)?.decode()?;
+        println!("{}: {}, {}x{}, {} mipmaps, {} faces{}",
+            image_path,
+            image.get_ref_format(),
+            image.get_width(),
+            image.get_height(),
+            image.get_ref_mip_count(),
+            image.get_ref_faces().len(),
+            if *image.get_ref_is_cube_map() { " (cube map)" } else { "" }
+        );
+    }
+
+    Ok(())
+}
+
+/// This function converts a DDS, TGA or PNG image to PNG.
+///
+/// If no destination path was provided, it leaves the PNG file in the same place as the image, with the same name.
+pub fn convert(
+    config: &Config,
+    source_path: &str,
+    destination_path: Option<&str>,
+) -> Result<()> {
+
+	if config.verbosity_level > 0 {
+		info!("Convert Image to PNG: {}", source_path);
+	}
+
+    let source_path = PathBuf::from(source_path);
+    let destination_path = match destination_path {
+        Some(destination_path) => PathBuf::from(destination_path),
+        None => source_path.with_extension("png"),
+    };
+
+    let image = Image::read(&read(&source_path)?)?.decode()?;
+    write(&destination_path, image.to_png())?;
+
+    info!("Image converted to {}.", destination_path.to_string_lossy());
+    Ok(())
+}
+
+/// This function encodes a PNG image as a DDS image of the provided format.
+///
+/// If no destination path was provided, it leaves the DDS file in the same place as the PNG, with the same name.
+pub fn encode(
+    config: &Config,
+    source_path: &str,
+    format: &str,
+    destination_path: Option<&str>,
+    generate_mipmaps: bool,
+) -> Result<()> {
+
+	if config.verbosity_level > 0 {
+		info!("Encode PNG as DDS: {}, with format: {}", source_path, format);
+	}
+
+    let source_path = PathBuf::from(source_path);
+    let destination_path = match destination_path {
+        Some(destination_path) => PathBuf::from(destination_path),
+        None => source_path.with_extension("dds"),
+    };
+
+    let format = ImageFormat::get_dds_format(format)?;
+    let image = Image::from_png(&read(&source_path)?, format, generate_mipmaps)?;
+    write(&destination_path, image.get_data())?;
+
+    info!("Image encoded as {} to {}.", format, destination_path.to_string_lossy());
+    Ok(())
+}
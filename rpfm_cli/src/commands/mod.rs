@@ -13,13 +13,20 @@
 
 use clap::ArgMatches;
 
+use std::path::Path;
+
 use rpfm_error::{ErrorKind, Result};
+use rpfm_lib::packedfile::table::formats::TableFormat;
 
 use crate::config::Config;
 
+mod esf;
+mod image;
 mod table;
+mod model;
 mod packfile;
 mod schema;
+mod video;
 
 //---------------------------------------------------------------------------//
 // 								Command Variants
@@ -95,7 +102,80 @@ pub fn command_packfile(config: &Config, matches: &ArgMatches, packfile: Option<
                 }
             }
 
+            else if matches.is_present("set-compression") {
+                match matches.values_of("set-compression") {
+                    Some(mut values) => {
+                        let enable = values.next().unwrap();
+                        let paths = values.collect::<Vec<&str>>();
+                        packfile::set_compression(&config, packfile_path, enable, &paths)
+                    },
+                    None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+                }
+            }
+
+            else if matches.is_present("set-compression-by-type") {
+                match matches.values_of("set-compression-by-type") {
+                    Some(mut values) => {
+                        let enable = values.next().unwrap();
+                        let packed_file_types = values.collect::<Vec<&str>>();
+                        packfile::set_compression_by_type(&config, packfile_path, enable, &packed_file_types)
+                    },
+                    None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+                }
+            }
+
+            else if matches.is_present("merge-tsv") {
+                match matches.values_of("merge-tsv") {
+                    Some(values) => {
+                        let tsv_paths = values.collect::<Vec<&str>>();
+                        let table_path = matches.value_of("merge-tsv-into");
+                        packfile::merge_tsv(config, packfile_path, &tsv_paths, table_path)
+                    },
+                    None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+                }
+            }
+
+            else if matches.is_present("mass-edit") {
+                match matches.values_of("mass-edit") {
+                    Some(mut values) => {
+                        let table_name = values.next().unwrap();
+                        let mass_edit = values.next().unwrap();
+                        packfile::mass_edit(config, packfile_path, table_name, mass_edit)
+                    },
+                    None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+                }
+            }
+
+            else if matches.is_present("query") {
+                match matches.value_of("query") {
+                    Some(query) => packfile::query(config, packfile_path, query),
+                    None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+                }
+            }
+
+            else if matches.is_present("execute-sql") {
+                match matches.value_of("execute-sql") {
+                    Some(statements) => packfile::execute_sql(config, packfile_path, statements),
+                    None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+                }
+            }
+
+            else if matches.is_present("diff") {
+                match matches.value_of("diff") {
+                    Some(packfile_new_path) => packfile::diff(&config, packfile_path, packfile_new_path, matches.is_present("json")),
+                    None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+                }
+            }
+
+            else if matches.is_present("recover") {
+                match matches.value_of("recover") {
+                    Some(destination_path) => packfile::recover(&config, packfile_path, destination_path),
+                    None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+                }
+            }
+
 			else if matches.is_present("list") { packfile::list_packfile_contents(&config, packfile_path) }
+            else if matches.is_present("map-report") { packfile::map_report(config, packfile_path) }
             else if matches.is_present("new-packfile") { packfile::new_packfile(&config, packfile_path)}
 
 			else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
@@ -120,12 +200,119 @@ pub fn command_table(config: &Config, matches: &ArgMatches, _packfile: Option<&s
 		match matches.values_of("export") {
 			Some(values) => {
                 let packed_file_paths = values.collect::<Vec<&str>>();
-                table::export_tsv(&config, &packed_file_paths)
+                let format = matches.value_of("format").unwrap_or("tsv").parse::<TableFormat>()?;
+                table::export_tsv(&config, &packed_file_paths, format)
+            },
+			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+		}
+    }
+
+    else if matches.is_present("merge") {
+		match matches.values_of("merge") {
+			Some(mut values) => {
+                let tsv_path = values.next().unwrap();
+                let table_path = values.next().unwrap();
+                table::merge_tsv(config, tsv_path, table_path)
+            },
+			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+		}
+    }
+
+	else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
+}
+
+/// This function triggers functions that require the `Model` command.
+pub fn command_model(config: &Config, matches: &ArgMatches) -> Result<()> {
+    if matches.is_present("export") {
+		match matches.values_of("export") {
+			Some(values) => {
+                let paths = values.collect::<Vec<&str>>();
+                model::export(&config, paths[0], paths.get(1).copied())
+            },
+			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+		}
+    }
+    else if matches.is_present("import") {
+		match matches.values_of("import") {
+			Some(values) => {
+                let paths = values.collect::<Vec<&str>>();
+                model::import(&config, paths[0], paths[1], paths.get(2).copied())
+            },
+			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+		}
+    }
+	else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
+}
+
+/// This function triggers functions that require the `Image` command.
+pub fn command_image(config: &Config, matches: &ArgMatches) -> Result<()> {
+    if matches.is_present("info") {
+		match matches.values_of("info") {
+			Some(values) => image::info(&config, &values.collect::<Vec<&str>>()),
+			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+		}
+    }
+    else if matches.is_present("convert") {
+		match matches.values_of("convert") {
+			Some(values) => {
+                let paths = values.collect::<Vec<&str>>();
+                image::convert(&config, paths[0], paths.get(1).copied())
+            },
+			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+		}
+    }
+    else if matches.is_present("encode") {
+		match matches.values_of("encode") {
+			Some(values) => {
+                let values = values.collect::<Vec<&str>>();
+                image::encode(&config, values[0], values[1], values.get(2).copied(), matches.is_present("mipmaps"))
+            },
+			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+		}
+    }
+	else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
+}
+
+/// This function triggers functions that require the `Video` command.
+pub fn command_video(config: &Config, matches: &ArgMatches) -> Result<()> {
+    if matches.is_present("import") {
+		match matches.values_of("import") {
+			Some(values) => {
+                let paths = values.collect::<Vec<&str>>();
+                video::import(&config, paths[0], paths.get(1).copied())
             },
 			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
 		}
     }
+    else if matches.is_present("check") {
+		match matches.values_of("check") {
+			Some(values) => video::check(&config, &values.collect::<Vec<&str>>()),
+			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+		}
+    }
+	else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
+}
 
+/// This function triggers functions that require the `ESF` command.
+pub fn command_esf(config: &Config, matches: &ArgMatches) -> Result<()> {
+    if matches.is_present("dump") {
+		match matches.values_of("dump") {
+			Some(values) => {
+                let paths = values.collect::<Vec<&str>>();
+                esf::dump(&config, paths[0], paths.get(1).copied())
+            },
+			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+		}
+    }
+    else if matches.is_present("load") {
+		match matches.values_of("load") {
+			Some(values) => {
+                let paths = values.collect::<Vec<&str>>();
+                esf::load(&config, paths[0], paths.get(1).copied())
+            },
+			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+		}
+    }
 	else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
 }
 
@@ -140,3 +327,15 @@ pub fn command_schema(config: &Config, matches: &ArgMatches) -> Result<()> {
 
 	else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
 }
+
+//---------------------------------------------------------------------------//
+// 							Utility functions
+//---------------------------------------------------------------------------//
+
+/// This function returns if the provided path is a JSON file, based on its extension.
+fn is_json(path: &Path) -> bool {
+    match path.extension() {
+        Some(extension) => extension.to_string_lossy().to_lowercase() == "json",
+        None => false,
+    }
+}
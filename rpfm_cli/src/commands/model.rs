@@ -0,0 +1,75 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+use log::info;
+use std::fs::{read, write};
+use std::path::PathBuf;
+
+use rpfm_error::Result;
+use rpfm_lib::packedfile::rigidmodel::RigidModel;
+
+use crate::config::Config;
+
+//---------------------------------------------------------------------------//
+// 							RigidModel Command Variants
+//---------------------------------------------------------------------------//
+
+/// This function exports a RigidModel file to glTF, GLB or OBJ, depending on the extension of the destination path.
+///
+/// If no destination path was provided, it leaves the glTF file in the same place as the RigidModel, with the same name.
+pub fn export(
+    config: &Config,
+    source_path: &str,
+    destination_path: Option<&str>,
+) -> Result<()> {
+
+	if config.verbosity_level > 0 {
+		info!("Export RigidModel: {}", source_path);
+	}
+
+    let source_path = PathBuf::from(source_path);
+    let destination_path = match destination_path {
+        Some(destination_path) => PathBuf::from(destination_path),
+        None => source_path.with_extension("gltf"),
+    };
+
+    let rigid_model = RigidModel::read(&read(&source_path)?)?;
+    if destination_path.extension().and_then(|extension| extension.to_str()).map(|extension| extension.to_lowercase()) == Some("obj".to_owned()) {
+        rigid_model.export_obj(&destination_path)?;
+    } else {
+        rigid_model.export_gltf(&destination_path)?;
+    }
+
+    info!("RigidModel exported to {}.", destination_path.to_string_lossy());
+    Ok(())
+}
+
+/// This function replaces the geometry of a RigidModel file with the one in a glTF or GLB file.
+///
+/// If no destination path was provided, it overwrites the RigidModel.
+pub fn import(
+    config: &Config,
+    rigid_model_path: &str,
+    gltf_path: &str,
+    destination_path: Option<&str>,
+) -> Result<()> {
+
+	if config.verbosity_level > 0 {
+		info!("Import glTF File: {} into RigidModel: {}", gltf_path, rigid_model_path);
+	}
+
+    let destination_path = PathBuf::from(destination_path.unwrap_or(rigid_model_path));
+    let mut rigid_model = RigidModel::read(&read(rigid_model_path)?)?;
+    let count = rigid_model.import_gltf(&PathBuf::from(gltf_path))?;
+    write(&destination_path, rigid_model.save()?)?;
+
+    info!("{} meshes imported. RigidModel saved to {}.", count, destination_path.to_string_lossy());
+    Ok(())
+}
@@ -11,15 +11,23 @@
 //! This module contains the `PackFile` command's functions.
 
 use bytesize::ByteSize;
+use colored::*;
 use log::info;
-use prettytable::{Table, row, cell};
+use prettytable::{Cell, Row, Table, row, cell};
+use serde_json::to_string_pretty;
 
 use std::path::PathBuf;
 
 use rpfm_error::{ErrorKind, Result};
+use rpfm_lib::dependencies::Dependencies;
 use rpfm_lib::packedfile::PackedFileType;
+use rpfm_lib::packedfile::table::TSVImportMode;
+use rpfm_lib::packedfile::table::expression::MassEdit;
+use rpfm_lib::packedfile::text::TextType;
 use rpfm_lib::packfile::{PackFile, PathType};
-use rpfm_lib::SUPPORTED_GAMES;
+use rpfm_lib::schema::Schema;
+use rpfm_lib::sql::SQLDatabase;
+use rpfm_lib::{GAME_SELECTED, SCHEMA, SUPPORTED_GAMES};
 
 use crate::config::Config;
 
@@ -203,6 +211,69 @@ pub fn extract_folders(
     Ok(())
 }
 
+/// This function enables/disables compression for the PackedFiles in the provided paths, then saves the PackFile.
+pub fn set_compression(
+    config: &Config,
+    packfile: &str,
+    enable: &str,
+    paths: &[&str],
+) -> Result<()> {
+    let enable = parse_bool(enable)?;
+    if config.verbosity_level > 0 {
+        paths.iter().for_each(|x| info!("{} compression for the following path: {}", if enable { "Enabling" } else { "Disabling" }, x));
+    }
+
+    // Load the PackFile and the different PackedFiles to memory.
+    let packfile_path = PathBuf::from(packfile);
+    let mut packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
+
+    let path_types = paths.iter().map(|x| {
+        if *x == "." { PathType::PackFile }
+        else {
+            let path = x.split('/').filter(|x| !x.is_empty()).map(|x| x.to_owned()).collect::<Vec<String>>();
+            if packfile.packedfile_exists(&path) { PathType::File(path) }
+            else { PathType::Folder(path) }
+        }
+    }).collect::<Vec<PathType>>();
+
+    packfile.set_compression_by_path_types(&path_types, enable);
+    let result = packfile.save(None);
+
+    if config.verbosity_level > 0 {
+        info!("Compression successfully changed.");
+    }
+
+    result
+}
+
+/// This function enables/disables compression for the PackedFiles of the provided types, then saves the PackFile.
+pub fn set_compression_by_type(
+    config: &Config,
+    packfile: &str,
+    enable: &str,
+    packed_file_types: &[&str],
+) -> Result<()> {
+    let enable = parse_bool(enable)?;
+    if config.verbosity_level > 0 {
+        packed_file_types.iter().for_each(|x| info!("{} compression for the following type: {}", if enable { "Enabling" } else { "Disabling" }, x));
+    }
+
+    let packed_file_types = packed_file_types.iter().map(|x| parse_packed_file_type(x)).collect::<Result<Vec<PackedFileType>>>()?;
+
+    // Load the PackFile and the different PackedFiles to memory.
+    let packfile_path = PathBuf::from(packfile);
+    let mut packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
+
+    packfile.set_compression_by_packed_file_types(&packed_file_types, enable);
+    let result = packfile.save(None);
+
+    if config.verbosity_level > 0 {
+        info!("Compression successfully changed.");
+    }
+
+    result
+}
+
 /// This function list the contents of the provided Packfile.
 pub fn list_packfile_contents(config: &Config, packfile: &str) -> Result<()> {
 	if config.verbosity_level > 0 {
@@ -223,6 +294,273 @@ pub fn list_packfile_contents(config: &Config, packfile: &str) -> Result<()> {
 	Ok(())
 }
 
+/// This function prints what each map exported by Terry in the provided PackFile contains.
+pub fn map_report(config: &Config, packfile: &str) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Reporting the maps of PackFile {}.", packfile);
+    }
+
+    let packfile = PackFile::open_packfiles(&[PathBuf::from(packfile)], true, false, false)?;
+    let reports = packfile.get_map_reports()?;
+    if reports.is_empty() {
+        println!("No maps found.");
+        return Ok(())
+    }
+
+    for report in &reports {
+        println!("{} {} ({} files)", "*".green().bold(), report.get_ref_path().join("/"), report.get_ref_files().len());
+
+        let mut table = Table::new();
+        table.add_row(row!["Type", "Value", "Count"]);
+        for (hint, count) in report.get_ref_ai_hints() {
+            table.add_row(row!["AI Hint", hint, count]);
+        }
+
+        for zone in report.get_ref_deployment_zones() {
+            table.add_row(row!["Deployment Zone", zone, 1]);
+        }
+
+        for (prefab, count) in report.get_ref_prefab_instances() {
+            table.add_row(row!["Prefab Instance", prefab, count]);
+        }
+
+        table.printstd();
+    }
+
+    Ok(())
+}
+
+/// This function merges TSV files into the DB/Loc Tables of the provided PackFile, then saves it.
+///
+/// Each TSV file is merged into the table with its same name, unless a `table_path` is provided. In that case, all of them are merged into it.
+/// It prints the tables that have been merged into, and what happened with the rows of the TSV files.
+pub fn merge_tsv(config: &Config, packfile: &str, tsv_paths: &[&str], table_path: Option<&str>) -> Result<()> {
+    if config.verbosity_level > 0 {
+        tsv_paths.iter().for_each(|x| info!("Merging the following TSV file into the PackFile: {}", x));
+        if let Some(table_path) = table_path {
+            info!("Merging them into the following table: {}", table_path);
+        }
+    }
+
+    // The path of the table must be one we can import TSV files into: `db/table_name/file_name` or `text/db/file_name.loc`.
+    let table_path = table_path.map(|path| path.split('/').map(|x| x.to_owned()).collect::<Vec<String>>());
+    let name = match table_path {
+        Some(ref table_path) => {
+            let name = match &*table_path.iter().map(|x| &**x).collect::<Vec<&str>>() {
+                ["db", _, name] if !name.is_empty() => Some(name.to_string()),
+                ["text", "db", name] => name.strip_suffix(".loc").filter(|name| !name.is_empty()).map(|name| name.to_owned()),
+                _ => None,
+            };
+
+            match name {
+                Some(name) => Some(name),
+                None => return Err(ErrorKind::NoHTMLError(format!("The path '{}' is not a valid DB/LOC Table path.", table_path.join("/"))).into()),
+            }
+        },
+        None => None,
+    };
+
+    match &config.game_selected {
+        Some(game_selected) => *SCHEMA.write().unwrap() = Some(Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?),
+        None => return Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+
+    let mut packfile = PackFile::open_packfiles(&[PathBuf::from(packfile)], true, false, false)?;
+    let tsv_paths = tsv_paths.iter().map(PathBuf::from).collect::<Vec<PathBuf>>();
+    let (merged_paths, paths, report) = packfile.mass_import_tsv(&tsv_paths, name, true, TSVImportMode::Merge)?;
+
+    // If any of the TSV files is of another type of table, it ends up in another path. Don't save anything in that case.
+    if let Some(table_path) = table_path {
+        if let Some(path) = paths.iter().find(|path| **path != table_path) {
+            return Err(ErrorKind::NoHTMLError(format!("The TSV files cannot be merged into '{}', because at least one of them would go into '{}'. Check that all of them are of the same type of table.", table_path.join("/"), path.join("/"))).into());
+        }
+    }
+
+    packfile.save(None)?;
+
+    for path in &paths {
+        if merged_paths.contains(path) { println!("{} {}", "~".yellow().bold(), path.join("/")); }
+        else { println!("{} {}", "+".green().bold(), path.join("/")); }
+    }
+    println!("{}", report);
+
+    if config.verbosity_level > 0 {
+        info!("TSV files successfully merged into the PackFile.");
+    }
+
+    Ok(())
+}
+
+/// This function runs a read-only SQL query over the DB Tables of the provided PackFile and its dependencies, and prints the results.
+pub fn query(config: &Config, packfile: &str, query: &str) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Running the following query over the PackFile {}: {}", packfile, query);
+    }
+
+    let mut packfile = PackFile::open_packfiles(&[PathBuf::from(packfile)], true, false, false)?;
+    let database = load_sql_database(config, &mut packfile)?;
+    let result = database.query(query)?;
+
+    let mut table = Table::new();
+    table.add_row(Row::new(result.get_ref_definition().get_fields_processed().iter().map(|field| Cell::new(field.get_name())).collect()));
+    for row in result.get_ref_table_data() {
+        table.add_row(Row::new(row.iter().map(|cell| Cell::new(&cell.data_to_string())).collect()));
+    }
+
+    table.printstd();
+    Ok(())
+}
+
+/// This function runs one or more SQL statements over the DB Tables of the provided PackFile, then saves it.
+///
+/// The tables of the dependencies can be used in the statements, but only the ones of the PackFile can be edited.
+pub fn execute_sql(config: &Config, packfile: &str, statements: &str) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Running the following SQL statements over the PackFile {}: {}", packfile, statements);
+    }
+
+    let mut packfile = PackFile::open_packfiles(&[PathBuf::from(packfile)], true, false, false)?;
+    let mut database = load_sql_database(config, &mut packfile)?;
+    let paths = database.execute(statements, &mut packfile)?;
+    packfile.save(None)?;
+
+    for path in &paths {
+        println!("{} {}", "~".yellow().bold(), path.join("/"));
+    }
+
+    if config.verbosity_level > 0 {
+        info!("SQL statements successfully executed over the PackFile.");
+    }
+
+    Ok(())
+}
+
+/// This function applies a mass-edit statement to every DB Table with the provided name in the PackFile, then saves it.
+pub fn mass_edit(config: &Config, packfile: &str, table_name: &str, mass_edit: &str) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Mass-editing the {} tables of the PackFile {} with: {}", table_name, packfile, mass_edit);
+    }
+
+    let mass_edit = mass_edit.parse::<MassEdit>()?;
+    let mut packfile = PackFile::open_packfiles(&[PathBuf::from(packfile)], true, false, false)?;
+    let dependencies = load_dependencies(config, &packfile)?;
+    let paths = packfile.mass_edit_tables(table_name, &mass_edit, &dependencies)?;
+    packfile.save(None)?;
+
+    for path in &paths {
+        println!("{} {}", "~".yellow().bold(), path.join("/"));
+    }
+
+    if config.verbosity_level > 0 {
+        info!("Tables successfully mass-edited.");
+    }
+
+    Ok(())
+}
+
+/// This function prints the differences between the provided PackFile and a newer version of it.
+///
+/// If we have a game selected, its schema is used to compare DB/Loc Tables row by row.
+pub fn diff(config: &Config, packfile: &str, packfile_new: &str, as_json: bool) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Comparing PackFile {} with {}.", packfile, packfile_new);
+    }
+
+    if let Some(game_selected) = &config.game_selected {
+        *SCHEMA.write().unwrap() = Schema::load(&SUPPORTED_GAMES[&**game_selected].schema).ok();
+    }
+
+    let packfile = PackFile::open_packfiles(&[PathBuf::from(packfile)], true, false, false)?;
+    let packfile_new = PackFile::open_packfiles(&[PathBuf::from(packfile_new)], true, false, false)?;
+    let diff = packfile.diff(&packfile_new)?;
+
+    if as_json {
+        println!("{}", to_string_pretty(&diff)?);
+        return Ok(())
+    }
+
+    if diff.is_empty() {
+        println!("No differences found.");
+        return Ok(())
+    }
+
+    for path in diff.get_ref_added() {
+        println!("{} {}", "+".green().bold(), path.join("/"));
+    }
+
+    for path in diff.get_ref_removed() {
+        println!("{} {}", "-".red().bold(), path.join("/"));
+    }
+
+    for (path_base, path_new) in diff.get_ref_renamed() {
+        println!("{} {} -> {}", ">".blue().bold(), path_base.join("/"), path_new.join("/"));
+    }
+
+    for packed_file in diff.get_ref_changed() {
+        println!("{} {} ({} -> {})", "~".yellow().bold(), packed_file.get_ref_path().join("/"), ByteSize::b(*packed_file.get_ref_size_base()), ByteSize::b(*packed_file.get_ref_size_new()));
+
+        if let Some(table) = packed_file.get_ref_table() {
+            if table.get_ref_version_base() != table.get_ref_version_new() {
+                println!("    Version: {} -> {}", table.get_ref_version_base(), table.get_ref_version_new());
+            }
+
+            for row in table.get_ref_added_rows() {
+                println!("    {} {}", "+".green().bold(), row.join(" | "));
+            }
+
+            for row in table.get_ref_removed_rows() {
+                println!("    {} {}", "-".red().bold(), row.join(" | "));
+            }
+
+            for row in table.get_ref_changed_rows() {
+                println!("    {} {}", "~".yellow().bold(), row.get_ref_key().join(" | "));
+                for cell in row.get_ref_cells() {
+                    println!("        {}: {} -> {}", cell.get_ref_column(), cell.get_ref_value_base(), cell.get_ref_value_new());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// This function reads a damaged PackFile in recovery mode, prints what was wrong with it, and saves what could be salvaged in the provided path.
+pub fn recover(config: &Config, packfile: &str, destination_path: &str) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Recovering PackFile {} into {}.", packfile, destination_path);
+    }
+
+    let (mut packfile, report) = PackFile::read_and_recover(&PathBuf::from(packfile))?;
+
+    if report.is_clean() {
+        println!("No problems found.");
+    }
+
+    if *report.get_ref_damaged_index() {
+        println!("{} The indexes are damaged. {} files couldn't be read from them.", "!".red().bold(), report.get_ref_unreadable_packed_files());
+    }
+
+    if *report.get_ref_invalid_subheader() {
+        println!("{} The subheader is invalid, and has been replaced with a default one.", "!".yellow().bold());
+    }
+
+    if let Some((count_header, count_index)) = report.get_ref_wrong_packed_file_count() {
+        println!("{} The header says there are {} files, but the index has {}.", "!".yellow().bold(), count_header, count_index);
+    }
+
+    if let Some((size, size_expected)) = report.get_ref_wrong_size() {
+        println!("{} The PackFile is {} bytes long, but it should be {} bytes long.", "!".yellow().bold(), size, size_expected);
+    }
+
+    for (path, size) in report.get_ref_lost_packed_files() {
+        println!("{} {} ({})", "-".red().bold(), path.join("/"), ByteSize::b(u64::from(*size)));
+    }
+
+    packfile.save(Some(PathBuf::from(destination_path)))?;
+    println!("Recovered {} files into {}.", packfile.get_ref_packed_files_all().len(), destination_path);
+    Ok(())
+}
+
 /// This function creates a new packfile with the provided path.
 pub fn new_packfile(config: &Config, packfile: &str) -> Result<()> {
     if config.verbosity_level > 0 {
@@ -238,3 +576,61 @@ pub fn new_packfile(config: &Config, packfile: &str) -> Result<()> {
         None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
     }
 }
+
+//---------------------------------------------------------------------------//
+// 							Utility functions
+//---------------------------------------------------------------------------//
+
+/// This function parses a `true/false` argument.
+fn parse_bool(value: &str) -> Result<bool> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(ErrorKind::NoHTMLError(format!("Invalid value: '{}'. Expected 'true' or 'false'.", value)).into()),
+    }
+}
+
+/// This function parses the name of a PackedFile Type, as received from the command line.
+fn parse_packed_file_type(value: &str) -> Result<PackedFileType> {
+    match value {
+        "anim" => Ok(PackedFileType::Anim),
+        "anim_fragment" => Ok(PackedFileType::AnimFragment),
+        "anim_pack" => Ok(PackedFileType::AnimPack),
+        "anim_table" => Ok(PackedFileType::AnimTable),
+        "ca_vp8" => Ok(PackedFileType::CaVp8),
+        "db" => Ok(PackedFileType::DB),
+        "image" => Ok(PackedFileType::Image),
+        "loc" => Ok(PackedFileType::Loc),
+        "matched_combat" => Ok(PackedFileType::MatchedCombat),
+        "rigid_model" => Ok(PackedFileType::RigidModel),
+        "text" => Ok(PackedFileType::Text(TextType::Plain)),
+        "unknown" => Ok(PackedFileType::Unknown),
+        _ => Err(ErrorKind::NoHTMLError(format!("Invalid PackedFile Type: '{}'.", value)).into()),
+    }
+}
+
+/// This function loads the schema and the dependencies of the provided PackFile, for the game selected.
+///
+/// The dependencies are the vanilla tables of the game selected, plus the ones of the PackFiles the provided one depends on.
+fn load_dependencies(config: &Config, packfile: &PackFile) -> Result<Dependencies> {
+    match &config.game_selected {
+        Some(game_selected) => {
+            *GAME_SELECTED.write().unwrap() = game_selected.to_owned();
+            *SCHEMA.write().unwrap() = Some(Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?);
+        }
+        None => return Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+
+    let mut dependencies = Dependencies::default();
+    dependencies.rebuild(packfile.get_packfiles_list());
+    Ok(dependencies)
+}
+
+/// This function loads the DB Tables of the provided PackFile and its dependencies into a new `SQLDatabase`.
+fn load_sql_database(config: &Config, packfile: &mut PackFile) -> Result<SQLDatabase> {
+    let dependencies = load_dependencies(config, packfile)?;
+    match *SCHEMA.read().unwrap() {
+        Some(ref schema) => SQLDatabase::new(packfile, &dependencies, schema),
+        None => Err(ErrorKind::SchemaNotFound.into()),
+    }
+}
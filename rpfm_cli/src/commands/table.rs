@@ -9,62 +9,161 @@
 //---------------------------------------------------------------------------//
 
 use log::info;
-use std::path::PathBuf;
+use std::fs::{read, read_to_string, write};
+use std::path::{Path, PathBuf};
 
 use rpfm_error::{ErrorKind, Result};
-use rpfm_lib::packedfile::table::db::DB;
+use rpfm_lib::packedfile::PackedFileType;
+use rpfm_lib::packedfile::table::{TSVImportMode, anim_fragment, anim_fragment::AnimFragment, animtable, animtable::AnimTable, ceo, ceo::CEO, db::DB, formats::TableFormat, loc::{Loc, TSV_NAME_LOC}, matched_combat, matched_combat::MatchedCombat};
 use rpfm_lib::schema::Schema;
 use rpfm_lib::SUPPORTED_GAMES;
 
 use crate::config::Config;
+use super::is_json;
 
 //---------------------------------------------------------------------------//
 // 							DB/Loc Command Variants
 //---------------------------------------------------------------------------//
 
-/// This function imports a TSV file into a binary DB/Loc file.
+/// This function imports a TSV/JSON/CSV/XLSX file into a binary DB/Loc file, or a JSON file into a binary AnimTable/AnimFragment/MatchedCombat/CEO file.
 ///
-/// If no destination path was provided, it leaves the DB/Loc File in the same place as the tsv file, with the same name.
+/// If no destination path was provided, it leaves the binary File in the same place as the source file, with the same name.
 pub fn import_tsv(
     config: &Config,
     source_paths: &[&str],
 ) -> Result<()> {
 
 	if config.verbosity_level > 0 {
-		source_paths.iter().for_each(|x| info!("Import TSV File as Binary File: {}", x));
+		source_paths.iter().for_each(|x| info!("Import TSV/JSON/CSV/XLSX File as Binary File: {}", x));
 	}
 
     match &config.game_selected {
         Some(game_selected) => {
             let schema = Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?;
-        	let source_paths = source_paths.iter().map(PathBuf::from).collect::<Vec<PathBuf>>();
-        	let result = DB::import_tsv_to_binary_file(&schema, &source_paths);
-            info!("All TSV files imported to binary.");
+        	let (json_paths, source_paths): (Vec<PathBuf>, Vec<PathBuf>) = source_paths.iter().map(PathBuf::from).partition(|x| is_json(x) && get_json_table_type(&x.with_extension("")).is_some());
+            for path in &json_paths {
+                import_json(&schema, path)?;
+            }
+
+        	let result = DB::import_to_binary_file(&schema, &source_paths);
+            info!("All TSV/JSON/CSV/XLSX files imported to binary.");
             result
         },
         None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
     }
 }
 
-/// This function imports a TSV file into a binary DB/Loc file.
+/// This function exports a binary DB/Loc file to a file of the provided format, or a binary AnimTable/AnimFragment/MatchedCombat/CEO file to a JSON file.
 ///
-/// If no destination path was provided, it leaves the DB/Loc File in the same place as the tsv file, with the same name.
+/// The exported file is left in the same place as the binary file, with the same name.
 pub fn export_tsv(
     config: &Config,
     source_paths: &[&str],
+    format: TableFormat,
 ) -> Result<()> {
 	if config.verbosity_level > 0 {
-		source_paths.iter().for_each(|x| info!("Export Binary File as TSV: {}", x));
+		source_paths.iter().for_each(|x| info!("Export Binary File as {}/JSON: {}", format, x));
 	}
 
     match &config.game_selected {
         Some(game_selected) => {
             let schema = Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?;
-            let source_paths = source_paths.iter().map(PathBuf::from).collect::<Vec<PathBuf>>();
-            let result = DB::export_tsv_from_binary_file(&schema, &source_paths);
-            info!("All binary files exported to TSV.");
+            let (json_paths, source_paths): (Vec<PathBuf>, Vec<PathBuf>) = source_paths.iter().map(PathBuf::from).partition(|x| get_json_table_type(x).is_some());
+            for path in &json_paths {
+                export_json(&schema, path)?;
+            }
+
+            let result = DB::export_from_binary_file(&schema, &source_paths, format);
+            info!("All binary files exported to {}/JSON.", format);
             result
         },
         None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
     }
 }
+
+/// This function merges a TSV file into a binary DB/Loc file, matching their rows by their keys, and prints what happened with them.
+///
+/// Like when exporting, if the file is not a Loc, the name of its parent folder is used as table name.
+pub fn merge_tsv(
+    config: &Config,
+    tsv_path: &str,
+    table_path: &str,
+) -> Result<()> {
+	if config.verbosity_level > 0 {
+		info!("Merge TSV File {} into Binary File: {}", tsv_path, table_path);
+	}
+
+    match &config.game_selected {
+        Some(game_selected) => {
+            let schema = Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?;
+            let tsv_path = PathBuf::from(tsv_path);
+            let table_path = PathBuf::from(table_path);
+            let data = read(&table_path)?;
+            let (data, report) = if let Ok(mut table) = Loc::read(&data, &schema, false) {
+                let report = table.import_tsv(&tsv_path, TSV_NAME_LOC, TSVImportMode::Merge)?;
+                (table.save()?, report)
+            }
+            else {
+                let table_type = table_path.parent().and_then(|path| path.file_name()).and_then(|name| name.to_str()).ok_or(ErrorKind::ImportTSVWrongTypeTable)?;
+                let mut table = DB::read(&data, table_type, &schema, false)?;
+                let report = table.import_tsv(&tsv_path, table_type, TSVImportMode::Merge)?;
+                (table.save()?, report)
+            };
+
+            write(&table_path, data)?;
+            println!("{}", report);
+            info!("TSV file merged into binary.");
+            Ok(())
+        },
+        None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+}
+
+//---------------------------------------------------------------------------//
+// 							Utility functions
+//---------------------------------------------------------------------------//
+
+/// This function returns the type of table of the binary file in the provided path, if it's one we import/export as JSON.
+fn get_json_table_type(path: &Path) -> Option<PackedFileType> {
+    let file_name = path.file_name()?.to_str()?.to_lowercase();
+    if file_name.ends_with(anim_fragment::EXTENSION) { Some(PackedFileType::AnimFragment) }
+    else if file_name.ends_with(ceo::EXTENSION) { Some(PackedFileType::CEO) }
+    else if file_name == animtable::PATH[animtable::PATH.len() - 1] { Some(PackedFileType::AnimTable) }
+    else if file_name == matched_combat::PATH[matched_combat::PATH.len() - 1] { Some(PackedFileType::MatchedCombat) }
+    else { None }
+}
+
+/// This function imports a JSON file into a binary file next to it, with the same name without the `.json` extension.
+fn import_json(schema: &Schema, source_path: &Path) -> Result<()> {
+    let destination_path = source_path.with_extension("");
+    let json = read_to_string(source_path)?;
+    let data = match get_json_table_type(&destination_path) {
+        Some(PackedFileType::AnimFragment) => AnimFragment::from_json(&json, schema)?.save()?,
+        Some(PackedFileType::AnimTable) => AnimTable::from_json(&json, schema)?.save()?,
+        Some(PackedFileType::CEO) => CEO::from_json(&json, schema)?.save()?,
+        Some(PackedFileType::MatchedCombat) => MatchedCombat::from_json(&json, schema)?.save()?,
+        _ => return Err(ErrorKind::NoHTMLError(format!("The type of {} cannot be imported from JSON.", destination_path.to_string_lossy())).into()),
+    };
+
+    write(&destination_path, data)?;
+    info!("JSON file imported to {}.", destination_path.to_string_lossy());
+    Ok(())
+}
+
+/// This function exports a binary file to a JSON file next to it, with the same name plus the `.json` extension.
+fn export_json(schema: &Schema, source_path: &Path) -> Result<()> {
+    let data = read(source_path)?;
+    let json = match get_json_table_type(source_path) {
+        Some(PackedFileType::AnimFragment) => AnimFragment::read(&data, schema, false)?.to_json(),
+        Some(PackedFileType::AnimTable) => AnimTable::read(&data, schema, false)?.to_json(),
+        Some(PackedFileType::CEO) => CEO::read(&data, schema, false)?.to_json(),
+        Some(PackedFileType::MatchedCombat) => MatchedCombat::read(&data, schema, false)?.to_json(),
+        _ => return Err(ErrorKind::NoHTMLError(format!("The type of {} cannot be exported to JSON.", source_path.to_string_lossy())).into()),
+    };
+
+    let mut destination_path = source_path.as_os_str().to_owned();
+    destination_path.push(".json");
+    write(&destination_path, json)?;
+    info!("Binary file exported to {}.", destination_path.to_string_lossy());
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+use log::{info, warn};
+use std::fs::{read, write};
+use std::path::PathBuf;
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_lib::packedfile::ca_vp8::{CaVp8, EXTENSION};
+
+use crate::config::Config;
+
+//---------------------------------------------------------------------------//
+// 							Video Command Variants
+//---------------------------------------------------------------------------//
+
+/// This function creates a CA_VP8 video from an IVF or WebM video with VP8 frames.
+///
+/// If no destination path was provided, it leaves the CA_VP8 file in the same place as the video, with the same name.
+pub fn import(
+    config: &Config,
+    source_path: &str,
+    destination_path: Option<&str>,
+) -> Result<()> {
+
+	if config.verbosity_level > 0 {
+		info!("Import Video: {}", source_path);
+	}
+
+    let source_path = PathBuf::from(source_path);
+    let destination_path = match destination_path {
+        Some(destination_path) => PathBuf::from(destination_path),
+        None => source_path.with_extension(&EXTENSION[1..]),
+    };
+
+    let video = CaVp8::from_video(&read(&source_path)?)?;
+    write(&destination_path, video.save())?;
+
+    info!("{} frames imported ({}x{}, {} FPS). Video saved to {}.", video.get_num_frames(), video.get_width(), video.get_height(), video.get_framerate(), destination_path.to_string_lossy());
+    Ok(())
+}
+
+/// This function checks the frame table of one or more CA_VP8 videos, printing every problem found in them.
+pub fn check(
+    config: &Config,
+    video_paths: &[&str],
+) -> Result<()> {
+
+	if config.verbosity_level > 0 {
+		info!("Check Videos: {:?}", video_paths);
+	}
+
+    let mut broken_videos = 0;
+    for video_path in video_paths {
+        let video = CaVp8::read(read(video_path)?)?;
+        let issues = video.validate();
+        if issues.is_empty() {
+            info!("{}: no problems found.", video_path);
+        } else {
+            broken_videos += 1;
+            for issue in &issues {
+                warn!("{}: {}", video_path, issue);
+            }
+        }
+    }
+
+    if broken_videos > 0 {
+        Err(ErrorKind::NoHTMLError(format!("{} videos have problems in their frame table.", broken_videos)).into())
+    } else {
+        Ok(())
+    }
+}
@@ -67,6 +67,10 @@ fn main() {
     let result = match matches.subcommand() {
         ("packfile", Some(matches)) => commands::command_packfile(&config, matches, packfile),
         ("table", Some(matches)) => commands::command_table(&config, matches, packfile),
+        ("model", Some(matches)) => commands::command_model(&config, matches),
+        ("image", Some(matches)) => commands::command_image(&config, matches),
+        ("video", Some(matches)) => commands::command_video(&config, matches),
+        ("esf", Some(matches)) => commands::command_esf(&config, matches),
         ("schema", Some(matches)) => commands::command_schema(&config, matches),
         _ => { Ok(()) }
     };
@@ -101,6 +101,60 @@ pub enum ErrorKind {
     /// Generic TSV import/export error.
     TSVErrorGeneric,
 
+    /// Error for when we try to merge a TSV file into a table without key columns.
+    ImportTSVMergeNoKeys,
+
+    /// Error for when a TSV file we try to merge into a table lacks one of its key columns. It contains the name of the column.
+    ImportTSVMergeMissingKeyColumn(String),
+
+    /// Error for when a TSV file we try to merge into a table has a column the table doesn't have. It contains the name of the column.
+    ImportTSVMergeUnknownColumn(String),
+
+    /// Error for when a TSV file we try to merge into a table has the same column more than once. It contains the name of the column.
+    ImportTSVMergeDuplicatedColumn(String),
+
+    /// Error for when a row of a TSV file we try to merge into a table has an invalid action. It contains the row and the action.
+    ImportTSVMergeInvalidAction(usize, String),
+
+    /// Error for when a TSV file we try to merge into a table has the same key in more than one row. It contains the row of the repeated key.
+    ImportTSVMergeDuplicatedKey(usize),
+
+    /// Error for when we try to import/export a table from/to a file in a format we don't support. It contains the path of the file.
+    ImportTableUnsupportedFormat(String),
+
+    /// Error for when a JSON/CSV/XLSX file doesn't start with the name and version of its table, or they're invalid.
+    ImportTableInvalidHeader,
+
+    /// Error for when a JSON/CSV/XLSX file belongs to another table or version. It contains the name and version found in the file.
+    ImportTableWrongTable(String, i32),
+
+    /// Error for when the column names of a CSV/XLSX file are not the ones of the definition. It contains the expected and found names.
+    ImportTableWrongColumns(String, String),
+
+    /// Error for when a cell of a JSON/CSV/XLSX file has an invalid value. It contains the row (starting at 1), the column name and the reason.
+    ImportTableIncorrectCell(usize, String, String),
+
+    /// Error for when a XLSX file is broken or uses something we don't support. It contains the reason.
+    XLSXError(String),
+
+    /// Error for when SQLite fails to load the tables or to run a query. It contains the error reported by SQLite.
+    SQLError(String),
+
+    /// Error for when a query leaves a row of a table with a value its column cannot hold. It contains the path of the table, the row (starting at 1), the column name and the reason.
+    SQLIncorrectValue(String, usize, String, String),
+
+    /// Error for when a query moves a row to a path that is not a valid path for its table. It contains the path and the name of the table.
+    SQLInvalidPath(String, String),
+
+    /// Error for when a mass-edit expression cannot be parsed. It contains the position of the error (starting at 1) and the reason.
+    ExpressionSyntaxError(usize, String),
+
+    /// Error for when a mass-edit expression uses a column the table doesn't have, or one that cannot be used in expressions. It contains the name of the column.
+    ExpressionUnknownColumn(String),
+
+    /// Error for when a mass-edit expression fails on a row of a table. It contains the path of the table, the row (starting at 1) and the reason.
+    ExpressionError(String, usize, String),
+
     /// Generic error for when Fluent fails to parse a sentence.
     FluentParsingError,
 
@@ -253,6 +307,9 @@ pub enum ErrorKind {
     /// Error for when the compressed PackedFile is either incomplete (<9 bytes) or the decompression failed.
     PackedFileDataCouldNotBeDecompressed,
 
+    /// Error for when the compression of a PackedFile failed.
+    PackedFileDataCouldNotBeCompressed,
+
     /// Error for when we expect data to be in memory, but it isn't.
     PackedFileDataIsNotInMemory,
 
@@ -293,6 +350,9 @@ pub enum ErrorKind {
     /// Error for when a Table is empty and it doesn't have an `Definition`, so it's undecodeable.
     TableEmptyWithNoDefinition,
 
+    /// Error for when the definition of a Table doesn't match the one in the schema for its version. Contains the version.
+    TableDefinitionDoesNotMatchSchema(i32),
+
     //--------------------------------//
     // DB Table Errors
     //--------------------------------//
@@ -343,6 +403,9 @@ pub enum ErrorKind {
     /// Error for when the decal texture directory hasn't been found while examining a rigidmodel.
     RigidModelDecalTextureDirectoryNotFound,
 
+    /// Error for when importing geometry from a glTF file into a RigidModel fails. Contains the error message.
+    RigidModelImport(String),
+
     //--------------------------------//
     // Text Errors
     //--------------------------------//
@@ -379,6 +442,9 @@ pub enum ErrorKind {
     /// Error for when an Image fails to decode. Contains the error message.
     ImageDecode(String),
 
+    /// Error for when an Image fails to encode. Contains the error message.
+    ImageEncode(String),
+
     //--------------------------------//
     // CA_VP8 Errors
     //--------------------------------//
@@ -386,6 +452,49 @@ pub enum ErrorKind {
     /// Error for when a CaVp8 PackedFile fails to decode. Contains the error message.
     CaVp8Decode(String),
 
+    //--------------------------------//
+    // Anim Errors
+    //--------------------------------//
+
+    /// Error for when an Anim PackedFile fails to decode. Contains the error message.
+    AnimDecode(String),
+
+    /// Error for when an Anim PackedFile fails to encode. Contains the error message.
+    AnimEncode(String),
+
+    //--------------------------------//
+    // ESF Errors
+    //--------------------------------//
+
+    /// Error for when an ESF PackedFile fails to decode. Contains the error message.
+    ESFDecode(String),
+
+    /// Error for when an ESF PackedFile fails to encode. Contains the error message.
+    ESFEncode(String),
+
+    /// Error for when an ESF dump fails to load. Contains the error message.
+    ESFLoad(String),
+
+    //--------------------------------//
+    // GroupFormations Errors
+    //--------------------------------//
+
+    /// Error for when a GroupFormations PackedFile fails to decode. Contains the error message.
+    GroupFormationsDecode(String),
+
+    /// Error for when a GroupFormations PackedFile fails to encode. Contains the error message.
+    GroupFormationsEncode(String),
+
+    //--------------------------------//
+    // BMD Errors
+    //--------------------------------//
+
+    /// Error for when a BMD PackedFile fails to decode. Contains the error message.
+    BMDDecode(String),
+
+    /// Error for when a BMD PackedFile fails to encode. Contains the error message.
+    BMDEncode(String),
+
     //--------------------------------//
     // AnimPack Errors
     //--------------------------------//
@@ -393,6 +502,9 @@ pub enum ErrorKind {
     /// Error for when an AnimPack PackedFile fails to decode. Contains the error message.
     AnimPackDecode(String),
 
+    /// Error for when a PackedFile inside an AnimPack fails to encode. Contains the error message.
+    AnimPackEncode(String),
+
     //--------------------------------//
     // AnimTable Errors
     //--------------------------------//
@@ -677,6 +789,24 @@ impl Display for ErrorKind {
             ErrorKind::ImportTSVWrongVersion => write!(f, "<p>This TSV file belongs to another version of this table. If you want to use it, consider creating a new empty table, fill it with enough empty rows, open this file in a TSV editor, like Excel or LibreOffice, and copy column by column.</p><p>A more automatic solution is on the way, but not yet there.</p>"),
             ErrorKind::ImportTSVInvalidVersion => write!(f, "<p>This TSV file has an invalid version value at line 1.</p>"),
             ErrorKind::TSVErrorGeneric => write!(f, "<p>Error while trying to import/export a TSV file.</p>"),
+            ErrorKind::ImportTSVMergeNoKeys => write!(f, "<p>This table has no key columns, so the rows of the TSV file cannot be matched with the ones of the table.</p>"),
+            ErrorKind::ImportTSVMergeMissingKeyColumn(column) => write!(f, "<p>This TSV file lacks the key column <b><i>{}</i></b>, so its rows cannot be matched with the ones of the table.</p>", column),
+            ErrorKind::ImportTSVMergeUnknownColumn(column) => write!(f, "<p>This TSV file has a column called <b><i>{}</i></b>, but the table doesn't have any column with that name.</p>", column),
+            ErrorKind::ImportTSVMergeDuplicatedColumn(column) => write!(f, "<p>This TSV file has the column <b><i>{}</i></b> more than once.</p>", column),
+            ErrorKind::ImportTSVMergeInvalidAction(row, action) => write!(f, "<p>This TSV file has an invalid action in the <b>row <i>{}</i></b> (starting at 1): <i>{}</i>. The only valid actions are <i>delete</i>, or nothing to insert/update the row.</p>", row + 1, action),
+            ErrorKind::ImportTSVMergeDuplicatedKey(row) => write!(f, "<p>The key of the <b>row <i>{}</i></b> (starting at 1) of this TSV file is in another row of the file too.</p>", row + 1),
+            ErrorKind::ImportTableUnsupportedFormat(path) => write!(f, "<p>The file <i>{}</i> is not in a supported table format. Only TSV, JSON, CSV and XLSX files are supported.</p>", path),
+            ErrorKind::ImportTableInvalidHeader => write!(f, "<p>This file doesn't start with the name and version of the table it belongs to, or they're invalid.</p>"),
+            ErrorKind::ImportTableWrongTable(name, version) => write!(f, "<p>This file belongs to the table <b><i>{}</i></b>, version <b><i>{}</i></b>, which is not the one you're trying to import it to.</p>", name, version),
+            ErrorKind::ImportTableWrongColumns(expected, found) => write!(f, "<p>The columns of this file are not the ones of its table.</p><p>Expected columns: <i>{}</i>.</p><p>Found columns: <i>{}</i>.</p>", expected, found),
+            ErrorKind::ImportTableIncorrectCell(row, column, reason) => write!(f, "<p>This file has an error in the <b>row <i>{}</i></b>, <b>column <i>{}</i></b>:</p><p>{}</p>", row, column, reason),
+            ErrorKind::XLSXError(reason) => write!(f, "<p>Error while trying to read a XLSX file:</p><p>{}</p>", reason),
+            ErrorKind::SQLError(reason) => write!(f, "<p>Error while running the SQL query:</p><p>{}</p>", reason),
+            ErrorKind::SQLIncorrectValue(path, row, column, reason) => write!(f, "<p>The query left the <b>row <i>{}</i></b> (starting at 1) of the table <i>{}</i> with an invalid value in the column <b><i>{}</i></b>: {}</p>", row, path, column, reason),
+            ErrorKind::SQLInvalidPath(path, table) => write!(f, "<p>The path <i>{}</i> is not a valid path for a <i>{}</i> table. Valid paths are <i>db/{}/name_of_the_table</i>.</p>", path, table, table),
+            ErrorKind::ExpressionSyntaxError(position, reason) => write!(f, "<p>Error in the expression at the position <b><i>{}</i></b>: {}</p>", position, reason),
+            ErrorKind::ExpressionUnknownColumn(column) => write!(f, "<p>The expression uses the column <b><i>{}</i></b>, but the table doesn't have any column with that name that can be used in expressions.</p>", column),
+            ErrorKind::ExpressionError(path, row, reason) => write!(f, "<p>Error while applying the expression to the <b>row <i>{}</i></b> (starting at 1) of the table <i>{}</i>: {}</p>", row, path, reason),
             ErrorKind::FluentParsingError => write!(f, "<p>Error while trying to parse a fluent sentence.</p>"),
             ErrorKind::FluentResourceLoadingError => write!(f, "<p>Error while trying to load a fluent resource.</p>"),
             ErrorKind::ParsingFloatError => write!(f, "<p>Error while trying to parse a String as a Float.</p>"),
@@ -760,6 +890,7 @@ impl Display for ErrorKind {
             ErrorKind::PackedFileDataCouldNotBeLoaded => write!(f, "<p>This PackedFile's data could not be loaded. This means RPFM can no longer read the PackFile from the disk.</p>"),
             ErrorKind::PackedFileSizeIsNotWhatWeExpect(reported_size, expected_size) => write!(f, "<p>This PackedFile's reported size is <i><b>{}</b></i> bytes, but we expected it to be <i><b>{}</b></i> bytes. This means that either the decoding logic in RPFM is broken for this PackedFile, or this PackedFile is corrupted.</p>", reported_size, expected_size),
             ErrorKind::PackedFileDataCouldNotBeDecompressed => write!(f, "<p>This is a compressed file and the decompresion failed for some reason. This means this PackedFile cannot be opened in RPFM.</p>"),
+            ErrorKind::PackedFileDataCouldNotBeCompressed => write!(f, "<p>The compression of this PackedFile failed for some reason. This means this PackedFile cannot be saved compressed.</p>"),
             ErrorKind::PackedFileDataIsNotInMemory => write!(f, "<p>This PackedFile's data is not in memory. If you see this, report it, as it's a bug.</p>"),
             ErrorKind::PackedFileNotInFilter => write!(f, "<p>This PackedFile is not in the current TreeView filter. If you want to open it, remove the filter.</p>"),
             ErrorKind::PackedFileCouldNotBeImported(paths) => write!(f, "<p>The following failed to be imported:<ul>{}</ul></p>", paths.iter().map(|x| format!("<li>{}<li>", x)).collect::<String>()),
@@ -775,6 +906,7 @@ impl Display for ErrorKind {
             ErrorKind::TableRowWrongFieldCount(expected, real) => write!(f, "<p>Error while trying to save a row from a table:</p><p>We expected a row with \"{}\" fields, but we got a row with \"{}\" fields instead.</p>", expected, real),
             ErrorKind::TableWrongFieldType(expected, real) => write!(f, "<p>Error while trying to save a row from a table:</p><p>We expected a field of type \"{}\", but we got a field of type \"{}\".</p>", expected, real),
             ErrorKind::TableEmptyWithNoDefinition => write!(f, "<p>This table is empty and there is not a Definition for it. That means is undecodeable.</p>"),
+            ErrorKind::TableDefinitionDoesNotMatchSchema(version) => write!(f, "<p>The definition of this table doesn't match the one in the schema for its version (<b>{}</b>). If the schema has been updated since this table was exported, export it again.</p>", version),
 
             //--------------------------------//
             // DB Table Errors
@@ -797,6 +929,7 @@ impl Display for ErrorKind {
             ErrorKind::RigidModelUnknownMaskTypeFound => write!(f, "<p>Error while trying to decode the RigidModel file:</p><p><ul><li>Texture with unknown Mask Type found.</li></ul>"),
             ErrorKind::RigidModelTextureDirectoryNotFound => write!(f, "<p>Error while trying to decode the RigidModel file:</p><p><ul><li>Texture Directories not found.</li></ul>"),
             ErrorKind::RigidModelDecalTextureDirectoryNotFound => write!(f, "<p>Error while trying to decode the RigidModel file:</p><p><ul><li>Decal Texture Directory not found.</li></ul>"),
+            ErrorKind::RigidModelImport(cause) => write!(f, "<p>Error while trying to import geometry into the RigidModel:</p><p>{}</p>", cause),
 
             //--------------------------------//
             // Text Errors
@@ -817,16 +950,43 @@ impl Display for ErrorKind {
             // Image Errors
             //--------------------------------//
             ErrorKind::ImageDecode(cause) => write!(f, "<p>Error while trying to decode the Image PackedFile:</p><p>{}</p>", cause),
+            ErrorKind::ImageEncode(cause) => write!(f, "<p>Error while trying to encode the Image PackedFile:</p><p>{}</p>", cause),
 
             //--------------------------------//
             // CA_VP8 Errors
             //--------------------------------//
             ErrorKind::CaVp8Decode(cause) => write!(f, "<p>Error while trying to decode the CaVp8 PackedFile:</p><p>{}</p>", cause),
 
+            //--------------------------------//
+            // Anim Errors
+            //--------------------------------//
+            ErrorKind::AnimDecode(cause) => write!(f, "<p>Error while trying to decode the Anim PackedFile:</p><p>{}</p>", cause),
+            ErrorKind::AnimEncode(cause) => write!(f, "<p>Error while trying to encode the Anim PackedFile:</p><p>{}</p>", cause),
+
+            //--------------------------------//
+            // ESF Errors
+            //--------------------------------//
+            ErrorKind::ESFDecode(cause) => write!(f, "<p>Error while trying to decode the ESF PackedFile:</p><p>{}</p>", cause),
+            ErrorKind::ESFEncode(cause) => write!(f, "<p>Error while trying to encode the ESF PackedFile:</p><p>{}</p>", cause),
+            ErrorKind::ESFLoad(cause) => write!(f, "<p>Error while trying to load the ESF dump:</p><p>{}</p>", cause),
+
+            //--------------------------------//
+            // GroupFormations Errors
+            //--------------------------------//
+            ErrorKind::GroupFormationsDecode(cause) => write!(f, "<p>Error while trying to decode the GroupFormations PackedFile:</p><p>{}</p>", cause),
+            ErrorKind::GroupFormationsEncode(cause) => write!(f, "<p>Error while trying to encode the GroupFormations PackedFile:</p><p>{}</p>", cause),
+
+            //--------------------------------//
+            // BMD Errors
+            //--------------------------------//
+            ErrorKind::BMDDecode(cause) => write!(f, "<p>Error while trying to decode the BMD PackedFile:</p><p>{}</p>", cause),
+            ErrorKind::BMDEncode(cause) => write!(f, "<p>Error while trying to encode the BMD PackedFile:</p><p>{}</p>", cause),
+
             //--------------------------------//
             // AnimPack Errors
             //--------------------------------//
             ErrorKind::AnimPackDecode(cause) => write!(f, "<p>Error while trying to decode the AnimPack PackedFile:</p><p>{}</p>", cause),
+            ErrorKind::AnimPackEncode(cause) => write!(f, "<p>Error while trying to encode a PackedFile inside the AnimPack:</p><p>{}</p>", cause),
 
             //--------------------------------//
             // AnimTable Errors
@@ -1010,6 +1170,13 @@ impl From<csv::Error> for Error {
     }
 }
 
+/// Implementation to create an `Error` from a `rusqlite::Error`.
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        Self::from(ErrorKind::SQLError(error.to_string()))
+    }
+}
+
 /// Implementation to create an `Error` from a `toml::ser::Error`.
 impl From<toml::ser::Error> for Error {
     fn from(_: toml::ser::Error) -> Self {
@@ -66,6 +66,14 @@ pub trait Decoder {
     /// This function allows us to decode a f32 float from raw data.
     fn decode_float_f32(&self, offset: usize) -> Result<f32>;
 
+    /// This function allows us to decode a f64 float from raw data.
+    fn decode_float_f64(&self, offset: usize) -> Result<f64>;
+
+    /// This function allows us to decode a packed RGB colour from raw data, as an hexadecimal `RRGGBB` String.
+    ///
+    /// These colours are stored as an u32 integer, with the red, green and blue values in his three lower bytes.
+    fn decode_colour_rgb(&self, offset: usize) -> Result<String>;
+
     /// This function allows us to decode an UTF-8 String  from raw data.
     fn decode_string_u8(&self, offset: usize, size: usize) -> Result<String>;
 
@@ -117,6 +125,12 @@ pub trait Decoder {
     /// This function allows us to decode an f32 encoded float from raw data, moving the provided index to the byte where the next data starts.
     fn decode_packedfile_float_f32(&self, offset: usize, index: &mut usize) -> Result<f32>;
 
+    /// This function allows us to decode an f64 encoded float from raw data, moving the provided index to the byte where the next data starts.
+    fn decode_packedfile_float_f64(&self, offset: usize, index: &mut usize) -> Result<f64>;
+
+    /// This function allows us to decode a packed RGB colour from raw data, moving the provided index to the byte where the next data starts.
+    fn decode_packedfile_colour_rgb(&self, offset: usize, index: &mut usize) -> Result<String>;
+
     /// This function allows us to decode an UTF-8 encoded String from raw data, moving the provided index to the byte where the next data starts.
     fn decode_packedfile_string_u8(&self, offset: usize, index: &mut usize) -> Result<String>;
 
@@ -137,6 +151,24 @@ pub trait Decoder {
     /// These Strings's first byte it's a boolean that indicates if the string has something. If false, the string it's just that byte.
     /// If true, there is a normal UTF-16 encoded String after that byte.
     fn decode_packedfile_optional_string_u16(&self, offset: usize, index: &mut usize) -> Result<String>;
+
+    /// This function allows us to decode an optional i16 integer from raw data, moving the provided index to the byte where the next data starts.
+    ///
+    /// These integers's first byte it's a boolean that indicates if the integer exists. If false, the integer it's just that byte.
+    /// If true, there is a normal i16 integer after that byte.
+    fn decode_packedfile_optional_integer_i16(&self, offset: usize, index: &mut usize) -> Result<Option<i16>>;
+
+    /// This function allows us to decode an optional i32 integer from raw data, moving the provided index to the byte where the next data starts.
+    ///
+    /// These integers's first byte it's a boolean that indicates if the integer exists. If false, the integer it's just that byte.
+    /// If true, there is a normal i32 integer after that byte.
+    fn decode_packedfile_optional_integer_i32(&self, offset: usize, index: &mut usize) -> Result<Option<i32>>;
+
+    /// This function allows us to decode an optional i64 integer from raw data, moving the provided index to the byte where the next data starts.
+    ///
+    /// These integers's first byte it's a boolean that indicates if the integer exists. If false, the integer it's just that byte.
+    /// If true, there is a normal i64 integer after that byte.
+    fn decode_packedfile_optional_integer_i64(&self, offset: usize, index: &mut usize) -> Result<Option<i64>>;
 }
 
 /// Implementation of trait `Decoder` for `&[u8]`.
@@ -207,6 +239,16 @@ impl Decoder for [u8] {
         else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode an f32 number:</p><ul><li>Required bytes: 4.</li><li>Provided bytes: {:?}.</li></ul>", offset.checked_sub(self.len()))).into()) }
     }
 
+    fn decode_float_f64(&self, offset: usize) -> Result<f64> {
+        if self.len() >= offset + 8 { Ok(LittleEndian::read_f64(&self[offset..])) }
+        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode an f64 number:</p><ul><li>Required bytes: 8.</li><li>Provided bytes: {:?}.</li></ul>", offset.checked_sub(self.len()))).into()) }
+    }
+
+    fn decode_colour_rgb(&self, offset: usize) -> Result<String> {
+        if self.len() >= offset + 4 { Ok(format!("{:06X}", LittleEndian::read_u32(&self[offset..]))) }
+        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode a RGB colour:</p><ul><li>Required bytes: 4.</li><li>Provided bytes: {:?}.</li></ul>", offset.checked_sub(self.len()))).into()) }
+    }
+
     fn decode_string_u8(&self, offset: usize, size: usize) -> Result<String> {
         if self.len() >= offset + size {
             String::from_utf8(self[offset..offset + size].to_vec()).map_err(|_| Error::from(ErrorKind::HelperDecodingEncodingError("<p>Error trying to decode an UTF-8 String.</p>".to_owned())))
@@ -310,6 +352,18 @@ impl Decoder for [u8] {
         result
     }
 
+    fn decode_packedfile_float_f64(&self, offset: usize, index: &mut usize) -> Result<f64> {
+        let result = self.decode_float_f64(offset);
+        if result.is_ok() { *index += 8; }
+        result
+    }
+
+    fn decode_packedfile_colour_rgb(&self, offset: usize, index: &mut usize) -> Result<String> {
+        let result = self.decode_colour_rgb(offset);
+        if result.is_ok() { *index += 4; }
+        result
+    }
+
     fn decode_packedfile_string_u8(&self, offset: usize, mut index: &mut usize) -> Result<String> {
         if let Ok(size) = self.decode_packedfile_integer_u16(offset, &mut index) {
             let result = self.decode_string_u8(offset + 2, size as usize);
@@ -363,4 +417,37 @@ impl Decoder for [u8] {
         }
         else { Err(ErrorKind::HelperDecodingEncodingError("<p>Error trying to decode an UTF-16 Optional String:</p><p>The first byte is not a boolean.</p>".to_owned()).into()) }
     }
+
+    fn decode_packedfile_optional_integer_i16(&self, offset: usize, index: &mut usize) -> Result<Option<i16>> {
+        if let Ok(is) = self.decode_packedfile_bool(offset, index) {
+            if is {
+                let result = self.decode_packedfile_integer_i16(offset + 1, index);
+                if result.is_err() { *index -= 1 };
+                result.map(Some)
+            } else { Ok(None) }
+        }
+        else { Err(ErrorKind::HelperDecodingEncodingError("<p>Error trying to decode an Optional i16 number:</p><p>The first byte is not a boolean.</p>".to_owned()).into()) }
+    }
+
+    fn decode_packedfile_optional_integer_i32(&self, offset: usize, index: &mut usize) -> Result<Option<i32>> {
+        if let Ok(is) = self.decode_packedfile_bool(offset, index) {
+            if is {
+                let result = self.decode_packedfile_integer_i32(offset + 1, index);
+                if result.is_err() { *index -= 1 };
+                result.map(Some)
+            } else { Ok(None) }
+        }
+        else { Err(ErrorKind::HelperDecodingEncodingError("<p>Error trying to decode an Optional i32 number:</p><p>The first byte is not a boolean.</p>".to_owned()).into()) }
+    }
+
+    fn decode_packedfile_optional_integer_i64(&self, offset: usize, index: &mut usize) -> Result<Option<i64>> {
+        if let Ok(is) = self.decode_packedfile_bool(offset, index) {
+            if is {
+                let result = self.decode_packedfile_integer_i64(offset + 1, index);
+                if result.is_err() { *index -= 1 };
+                result.map(Some)
+            } else { Ok(None) }
+        }
+        else { Err(ErrorKind::HelperDecodingEncodingError("<p>Error trying to decode an Optional i64 number:</p><p>The first byte is not a boolean.</p>".to_owned()).into()) }
+    }
 }
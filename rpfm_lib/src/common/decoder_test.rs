@@ -130,6 +130,28 @@ fn test_decode_float_f32() {
     assert_eq!(Decoder::decode_float_f32([0, 32, 65].as_ref(), 0).is_err(), true);
 }
 
+/// Test to make sure the f64 float decoder (`decode_float_f64()`) works and fails properly.
+#[test]
+fn test_decode_float_f64() {
+
+    // Check the decoding works for a proper value.
+    assert_eq!(Decoder::decode_float_f64([0, 0, 0, 0, 0, 0, 36, 64].as_ref(), 0).unwrap(), 10.0);
+
+    // Check the decoder returns an error for a slice who's length is smaller than 8.
+    assert!(Decoder::decode_float_f64([0, 0, 0, 0, 36, 64].as_ref(), 0).is_err());
+}
+
+/// Test to make sure the RGB colour decoder (`decode_colour_rgb()`) works and fails properly.
+#[test]
+fn test_decode_colour_rgb() {
+
+    // Check the decoding works for a proper value.
+    assert_eq!(Decoder::decode_colour_rgb([0x56, 0x34, 0x12, 0].as_ref(), 0).unwrap(), "123456");
+
+    // Check the decoder returns an error for a slice who's length is smaller than 4.
+    assert!(Decoder::decode_colour_rgb([0x56, 0x34, 0x12].as_ref(), 0).is_err());
+}
+
 /// Test to make sure the u8 string decoder (`decode_string_u8()`) works and fails properly.
 #[test]
 fn test_decode_string_u8() {
@@ -405,6 +427,25 @@ fn test_decode_packedfile_float_f32() {
     }
 }
 
+/// Test to make sure the f64 float specific decoder (`decode_packedfile_float_f64()`) works and fails properly.
+#[test]
+fn test_decode_packedfile_float_f64() {
+
+    // Check the decoding works for a proper value.
+    {
+        let mut index = 0;
+        assert_eq!(Decoder::decode_packedfile_float_f64([0, 0, 0, 0, 0, 0, 36, 192].as_ref(), 0, &mut index).unwrap(), -10.0);
+        assert_eq!(index, 8);
+    }
+
+    // Check the decoder returns an error for a slice whose lenght is smaller than 8.
+    {
+        let mut index = 0;
+        assert!(Decoder::decode_packedfile_float_f64([10, 0].as_ref(), 0, &mut index).is_err());
+        assert_eq!(index, 0);
+    }
+}
+
 /// Test to make sure the u8 string specific decoder (`decode_packedfile_string_u8()`) works
 /// and fails properly.
 #[test]
@@ -588,3 +629,36 @@ fn test_decode_packedfile_optional_string_u16() {
         assert_eq!(index, 0);
     }
 }
+
+/// Test to make sure the optional i32 integer specific decoder (`decode_packedfile_optional_integer_i32()`) works and fails properly.
+#[test]
+fn test_decode_packedfile_optional_integer_i32() {
+
+    // Check the decoding works for a non-existant integer.
+    {
+        let mut index = 0;
+        assert_eq!(Decoder::decode_packedfile_optional_integer_i32([0].as_ref(), 0, &mut index).unwrap(), None);
+        assert_eq!(index, 1);
+    }
+
+    // Check the decoding works for a proper encoded integer.
+    {
+        let mut index = 0;
+        assert_eq!(Decoder::decode_packedfile_optional_integer_i32([1, 254, 254, 255, 255].as_ref(), 0, &mut index).unwrap(), Some(-258));
+        assert_eq!(index, 5);
+    }
+
+    // Check the decoder returns an error for a slice when it expects an integer after the bool, but founds nothing.
+    {
+        let mut index = 0;
+        assert!(Decoder::decode_packedfile_optional_integer_i32([1, 10].as_ref(), 0, &mut index).is_err());
+        assert_eq!(index, 0);
+    }
+
+    // Check the decoder returns an error for a slice whose first byte is not a boolean.
+    {
+        let mut index = 0;
+        assert!(Decoder::decode_packedfile_optional_integer_i32([2, 10, 0, 0, 0].as_ref(), 0, &mut index).is_err());
+        assert_eq!(index, 0);
+    }
+}
@@ -34,6 +34,9 @@ pub trait Encoder {
     /// This function allows us to encode a boolean to a byte of a `Vec<u8>`.
     fn encode_bool(&mut self, boolean: bool);
 
+    /// This function allows us to encode an u8 integer into a `Vec<u8>`.
+    fn encode_integer_u8(&mut self, integer: u8);
+
     /// This function allows us to encode an u16 integer into the provided `Vec<u8>`.
     fn encode_integer_u16(&mut self, integer: u16);
 
@@ -58,6 +61,14 @@ pub trait Encoder {
     /// This function allows us to encode a f32 float into the provided `Vec<u8>`.
     fn encode_float_f32(&mut self, float: f32);
 
+    /// This function allows us to encode a f64 float into a `Vec<u8>`.
+    fn encode_float_f64(&mut self, float: f64);
+
+    /// This function allows us to encode a packed RGB colour, as an hexadecimal `RRGGBB` String, into a `Vec<u8>`.
+    ///
+    /// This can fail if the provided String is not a valid hexadecimal number.
+    fn encode_colour_rgb(&mut self, colour: &str) -> Result<()>;
+
     /// This function allows us to encode an UTF-8 String into the provided `Vec<u8>`.
     fn encode_string_u8(&mut self, string: &str);
 
@@ -85,6 +96,15 @@ pub trait Encoder {
 
     /// This function allows us to encode an UTF-16 Optional String into the provided `Vec<u8>`.
     fn encode_packedfile_optional_string_u16(&mut self, string: &str);
+
+    /// This function allows us to encode an optional i16 integer into a `Vec<u8>`, with a boolean telling if the integer exists before it.
+    fn encode_packedfile_optional_integer_i16(&mut self, integer: Option<i16>);
+
+    /// This function allows us to encode an optional i32 integer into a `Vec<u8>`, with a boolean telling if the integer exists before it.
+    fn encode_packedfile_optional_integer_i32(&mut self, integer: Option<i32>);
+
+    /// This function allows us to encode an optional i64 integer into a `Vec<u8>`, with a boolean telling if the integer exists before it.
+    fn encode_packedfile_optional_integer_i64(&mut self, integer: Option<i64>);
 }
 
 /// Implementation of trait `Encoder` for `Vec<u8>`.
@@ -98,6 +118,10 @@ impl Encoder for Vec<u8> {
         self.push(if boolean { 1 } else { 0 });
     }
 
+    fn encode_integer_u8(&mut self, integer: u8) {
+        self.push(integer);
+    }
+
     fn encode_integer_u16(&mut self, integer: u16) {
         self.write_u16::<LittleEndian>(integer).unwrap();
     }
@@ -130,6 +154,20 @@ impl Encoder for Vec<u8> {
         self.write_f32::<LittleEndian>(float).unwrap();
     }
 
+    fn encode_float_f64(&mut self, float: f64) {
+        self.write_f64::<LittleEndian>(float).unwrap();
+    }
+
+    fn encode_colour_rgb(&mut self, colour: &str) -> Result<()> {
+        match u32::from_str_radix(colour, 16) {
+            Ok(colour) => {
+                self.encode_integer_u32(colour);
+                Ok(())
+            }
+            Err(_) => Err(ErrorKind::HelperDecodingEncodingError(format!("Error trying to encode a RGB colour: \"{}\" is not a valid hexadecimal colour.", colour)).into()),
+        }
+    }
+
     fn encode_string_u8(&mut self, string: &str) {
         self.extend_from_slice(string.as_bytes());
     }
@@ -187,4 +225,34 @@ impl Encoder for Vec<u8> {
             string.encode_utf16().for_each(|character| self.encode_integer_u16(character));
         }
     }
+
+    fn encode_packedfile_optional_integer_i16(&mut self, integer: Option<i16>) {
+        match integer {
+            Some(integer) => {
+                self.encode_bool(true);
+                self.encode_integer_i16(integer);
+            }
+            None => self.encode_bool(false),
+        }
+    }
+
+    fn encode_packedfile_optional_integer_i32(&mut self, integer: Option<i32>) {
+        match integer {
+            Some(integer) => {
+                self.encode_bool(true);
+                self.encode_integer_i32(integer);
+            }
+            None => self.encode_bool(false),
+        }
+    }
+
+    fn encode_packedfile_optional_integer_i64(&mut self, integer: Option<i64>) {
+        match integer {
+            Some(integer) => {
+                self.encode_bool(true);
+                self.encode_integer_i64(integer);
+            }
+            None => self.encode_bool(false),
+        }
+    }
 }
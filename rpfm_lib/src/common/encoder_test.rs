@@ -32,6 +32,16 @@ fn test_encode_bool() {
     assert_eq!(data, vec![0]);
 }
 
+/// Test to make sure the u8 integer encoder (`encode_integer_u8()`) works properly.
+#[test]
+fn test_encode_integer_u8() {
+
+    // Check the encoder works properly.
+    let mut data = vec![];
+    data.encode_integer_u8(254);
+    assert_eq!(data, vec![254]);
+}
+
 /// Test to make sure the u16 integer encoder (`encode_integer_u16()`) works properly.
 #[test]
 fn test_encode_integer_u16() {
@@ -112,6 +122,31 @@ fn test_encode_float_f32() {
     assert_eq!(data, vec![51, 51, 35, 193]);
 }
 
+/// Test to make sure the f64 float encoder (`encode_float_f64()`) works properly.
+#[test]
+fn test_encode_float_f64() {
+
+    // Check the encoder works properly.
+    let mut data = vec![];
+    data.encode_float_f64(-10.0);
+    assert_eq!(data, vec![0, 0, 0, 0, 0, 0, 36, 192]);
+}
+
+/// Test to make sure the RGB colour encoder (`encode_colour_rgb()`) works and fails properly.
+#[test]
+fn test_encode_colour_rgb() {
+
+    // Check the encoder works properly.
+    let mut data = vec![];
+    data.encode_colour_rgb("123456").unwrap();
+    assert_eq!(data, vec![0x56, 0x34, 0x12, 0]);
+
+    // Check the encoder fails for invalid colours.
+    let mut data = vec![];
+    assert!(data.encode_colour_rgb("red").is_err());
+    assert!(data.is_empty());
+}
+
 /// Test to make sure the u8 string encoder (`encode_string_u8()`) works properly.
 #[test]
 fn test_encode_string_u8() {
@@ -186,6 +221,21 @@ fn test_encode_packedfile_optional_string_u8() {
     assert_eq!(data, vec![0]);
 }
 
+/// Test to make sure the i32 optional integer specific encoder (`encode_packedfile_optional_integer_i32()`)
+/// works properly.
+#[test]
+fn test_encode_packedfile_optional_integer_i32() {
+
+    // Check the encoder works for both, existing and non-existant integers.
+    let mut data = vec![];
+    data.encode_packedfile_optional_integer_i32(Some(-258));
+    assert_eq!(data, vec![1, 254, 254, 255, 255]);
+
+    let mut data = vec![];
+    data.encode_packedfile_optional_integer_i32(None);
+    assert_eq!(data, vec![0]);
+}
+
 /// Test to make sure the u16 optional string specific encoder (`encode_packedfile_optional_string_u16()`)
 /// works properly.
 #[test]
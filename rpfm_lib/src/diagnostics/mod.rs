@@ -17,14 +17,15 @@ This module contains the code needed to get a `Diagnostics` over an entire `Pack
 use rayon::prelude::*;
 use fancy_regex::Regex;
 
+use std::collections::BTreeMap;
 use std::{fmt, fmt::Display};
 
 use crate::DB;
 use crate::dependencies::Dependencies;
 use crate::packfile::{PackFile, PathType};
-use crate::packedfile::{table::DecodedData, DecodedPackedFile, PackedFileType};
+use crate::packedfile::{table::{ceo, DecodedData, DependencyData}, DecodedPackedFile, PackedFileType};
 use crate::packfile::packedfile::PackedFileInfo;
-use crate::schema::{FieldType, Schema};
+use crate::schema::{Definition, FieldType, Schema};
 use crate::SCHEMA;
 
 use self::dependency_manager::{DependencyManagerDiagnostic, DependencyManagerDiagnosticReport, DependencyManagerDiagnosticReportType};
@@ -45,6 +46,7 @@ pub struct Diagnostics(Vec<DiagnosticType>);
 
 #[derive(Debug, Clone)]
 pub enum DiagnosticType {
+    CEO(TableDiagnostic),
     DB(TableDiagnostic),
     Loc(TableDiagnostic),
     PackFile(PackFileDiagnostic),
@@ -73,6 +75,7 @@ impl Default for Diagnostics {
 impl DiagnosticType {
     pub fn get_path(&self) -> &[String] {
         match self {
+            Self::CEO(ref diag) |
             Self::DB(ref diag) |
             Self::Loc(ref diag) => diag.get_path(),
             Self::PackFile(ref diag) => diag.get_path(),
@@ -111,7 +114,7 @@ impl Diagnostics {
             }).collect::<Vec<(Vec<String>, Vec<String>)>>()
         });
 
-        self.0 = pack_file.get_ref_packed_files_by_types(&[PackedFileType::DB, PackedFileType::Loc], false).par_iter().filter_map(|packed_file| {
+        self.0 = pack_file.get_ref_packed_files_by_types(&[PackedFileType::CEO, PackedFileType::DB, PackedFileType::Loc], false).par_iter().filter_map(|packed_file| {
 
             // Ignore entire tables if their path starts with the one we have (so we can do mass ignores) and we didn't specified a field to ignore.
             let mut ignored_fields = vec![];
@@ -127,6 +130,7 @@ impl Diagnostics {
             }
 
             match packed_file.get_packed_file_type_by_path() {
+                PackedFileType::CEO => Self::check_ceo(pack_file, packed_file.get_ref_decoded(), packed_file.get_path(), dependencies, &ignored_fields),
                 PackedFileType::DB => Self::check_db(pack_file, packed_file.get_ref_decoded(), packed_file.get_path(), &dependencies, &schema, &ignored_fields),
                 PackedFileType::Loc => Self::check_loc(packed_file.get_ref_decoded(), packed_file.get_path(), &ignored_fields),
                 _ => None,
@@ -161,11 +165,6 @@ impl Diagnostics {
                 &[],
             );
 
-            // Check all the columns with reference data.
-            let mut columns_without_reference_table = vec![];
-            let mut columns_with_reference_table_and_no_column = vec![];
-            let mut keys = vec![];
-
             // Before anything else, check if the table is outdated.
             if table.is_outdated(&dependencies.get_ref_dependency_database()) {
                 diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
@@ -177,150 +176,193 @@ impl Diagnostics {
                 });
             }
 
-            for (row, cells) in table.get_ref_table_data().iter().enumerate() {
-                let mut row_is_empty = true;
-                let mut row_keys_are_empty = true;
-                let mut local_keys = vec![];
-                for (column, field) in table.get_ref_definition().get_fields_processed().iter().enumerate() {
-                    if ignored_fields.contains(&field.get_name().to_owned()) {
-                        continue;
-                    }
+            Self::check_table(&mut diagnostic, table.get_ref_definition(), table.get_ref_table_data(), &dependency_data, dependencies, ignored_fields);
 
-                    let cell_data = cells[column].data_to_string();
+            if !diagnostic.get_ref_result().is_empty() {
+                Some(DiagnosticType::DB(diagnostic))
+            } else { None }
+        } else { None }
+    }
 
-                    // Dependency checks.
-                    if let Some((_ref_table_name, _ref_column_name)) = field.get_is_reference() {
-                        match dependency_data.get(&(column as i32)) {
-                            Some(ref_data) => {
+    /// This function takes care of checking the CEO PackedFiles of your mod for errors.
+    fn check_ceo(
+        pack_file: &PackFile,
+        packed_file: &DecodedPackedFile,
+        path: &[String],
+        dependencies: &Dependencies,
+        ignored_fields: &[String]
+    ) ->Option<DiagnosticType> {
+        if let DecodedPackedFile::CEO(table) = packed_file {
+            let mut diagnostic = TableDiagnostic::new(path);
+            let dependency_data = DB::get_dependency_data(
+                pack_file,
+                ceo::DEPENDENCY_CACHE_KEY,
+                table.get_ref_definition(),
+                dependencies,
+                &[],
+            );
 
-                                if ref_data.referenced_column_is_localised || ref_data.referenced_table_is_ak_only {
-                                    // TODO: report missing loc data here.
-                                }
-                                /*
-                                else if ref_data.referenced_table_is_ak_only {
-                                    // If it's only in the AK, ignore it.
-                                }*/
-
-                                // Blue cell check. Only one for each column, so we don't fill the diagnostics with this.
-                                else if ref_data.data.is_empty() {
-                                    if !columns_with_reference_table_and_no_column.contains(&column) {
-                                        columns_with_reference_table_and_no_column.push(column);
-                                    }
-                                }
+            Self::check_table(&mut diagnostic, table.get_ref_definition(), table.get_ref_table_data(), &dependency_data, dependencies, ignored_fields);
 
-                                // Check for non-empty cells with reference data, but the data in the cell is not in the reference data list.
-                                else if !cell_data.is_empty() && !ref_data.data.contains_key(&cell_data) {
-                                    diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
-                                        column_number: column as u32,
-                                        row_number: row as i64,
-                                        message: format!("Invalid reference \"{}\" in column \"{}\".", &cell_data, field.get_name()),
-                                        report_type: TableDiagnosticReportType::InvalidReference,
-                                        level: DiagnosticLevel::Error,
-                                    });
-                                }
+            if !diagnostic.get_ref_result().is_empty() {
+                Some(DiagnosticType::CEO(diagnostic))
+            } else { None }
+        } else { None }
+    }
+
+    /// This function performs the checks shared by all the DB-like tables (references, empty rows and keys, duplicated keys, ...).
+    fn check_table(
+        diagnostic: &mut TableDiagnostic,
+        definition: &Definition,
+        table_data: &[Vec<DecodedData>],
+        dependency_data: &BTreeMap<i32, DependencyData>,
+        dependencies: &Dependencies,
+        ignored_fields: &[String]
+    ) {
+        // Check all the columns with reference data.
+        let mut columns_without_reference_table = vec![];
+        let mut columns_with_reference_table_and_no_column = vec![];
+        let mut keys = vec![];
+
+        for (row, cells) in table_data.iter().enumerate() {
+            let mut row_is_empty = true;
+            let mut row_keys_are_empty = true;
+            let mut local_keys = vec![];
+            for (column, field) in definition.get_fields_processed().iter().enumerate() {
+                if ignored_fields.contains(&field.get_name().to_owned()) {
+                    continue;
+                }
+
+                let cell_data = cells[column].data_to_string();
+
+                // Dependency checks.
+                if let Some((_ref_table_name, _ref_column_name)) = field.get_is_reference() {
+                    match dependency_data.get(&(column as i32)) {
+                        Some(ref_data) => {
+
+                            if ref_data.referenced_column_is_localised || ref_data.referenced_table_is_ak_only {
+                                // TODO: report missing loc data here.
                             }
-                            None => {
-                                if !columns_without_reference_table.contains(&column) {
-                                    columns_without_reference_table.push(column);
+                            /*
+                            else if ref_data.referenced_table_is_ak_only {
+                                // If it's only in the AK, ignore it.
+                            }*/
+
+                            // Blue cell check. Only one for each column, so we don't fill the diagnostics with this.
+                            else if ref_data.data.is_empty() {
+                                if !columns_with_reference_table_and_no_column.contains(&column) {
+                                    columns_with_reference_table_and_no_column.push(column);
                                 }
                             }
-                        }
-                    }
-
-                    // Check for empty keys/rows.
-                    if row_is_empty && (!cell_data.is_empty() && cell_data != "false") {
-                        row_is_empty = false;
-                    }
-
-                    if row_keys_are_empty && field.get_is_key() && (!cell_data.is_empty() && cell_data != "false") {
-                        row_keys_are_empty = false;
-                    }
 
-                    if field.get_is_key() && field.get_field_type() != FieldType::OptionalStringU8 && field.get_field_type() != FieldType::Boolean && (cell_data.is_empty() || cell_data == "false") {
-                        diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
-                            column_number: column as u32,
-                            row_number: row as i64,
-                            message: format!("Empty key for column \"{}\".", field.get_name()),
-                            report_type: TableDiagnosticReportType::EmptyKeyField,
-                            level: DiagnosticLevel::Warning,
-                        });
+                            // Check for non-empty cells with reference data, but the data in the cell is not in the reference data list.
+                            else if !cell_data.is_empty() && !ref_data.data.contains_key(&cell_data) {
+                                diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
+                                    column_number: column as u32,
+                                    row_number: row as i64,
+                                    message: format!("Invalid reference \"{}\" in column \"{}\".", &cell_data, field.get_name()),
+                                    report_type: TableDiagnosticReportType::InvalidReference,
+                                    level: DiagnosticLevel::Error,
+                                });
+                            }
+                        }
+                        None => {
+                            if !columns_without_reference_table.contains(&column) {
+                                columns_without_reference_table.push(column);
+                            }
+                        }
                     }
+                }
 
-                    if field.get_is_key() {
-                        local_keys.push(cell_data);
-                    }
+                // Check for empty keys/rows.
+                if row_is_empty && (!cell_data.is_empty() && cell_data != "false") {
+                    row_is_empty = false;
                 }
 
-                if row_is_empty {
-                    diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
-                        column_number: 0,
-                        row_number: row as i64,
-                        message: "Empty row.".to_string(),
-                        report_type: TableDiagnosticReportType::EmptyRow,
-                        level: DiagnosticLevel::Error,
-                    });
+                if row_keys_are_empty && field.get_is_key() && (!cell_data.is_empty() && cell_data != "false") {
+                    row_keys_are_empty = false;
                 }
 
-                if row_keys_are_empty {
+                if field.get_is_key() && field.get_field_type() != FieldType::OptionalStringU8 && field.get_field_type() != FieldType::Boolean && (cell_data.is_empty() || cell_data == "false") {
                     diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
-                        column_number: 0,
+                        column_number: column as u32,
                         row_number: row as i64,
-                        message: "Empty key fields.".to_string(),
-                        report_type: TableDiagnosticReportType::EmptyKeyFields,
+                        message: format!("Empty key for column \"{}\".", field.get_name()),
+                        report_type: TableDiagnosticReportType::EmptyKeyField,
                         level: DiagnosticLevel::Warning,
                     });
                 }
 
-                if local_keys.len() > 1 && keys.contains(&local_keys) {
-                    diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
-                        column_number: 0,
-                        row_number: row as i64,
-                        message: format!("Duplicated combined keys: {}.", local_keys.join("| |")),
-                        report_type: TableDiagnosticReportType::DuplicatedCombinedKeys,
-                        level: DiagnosticLevel::Error,
-                    });
-                }
-                else {
-                    keys.push(local_keys);
+                if field.get_is_key() {
+                    local_keys.push(cell_data);
                 }
             }
 
-            // Checks that only need to be done once per table.
-            for column in &columns_without_reference_table {
+            if row_is_empty {
                 diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
-                    column_number: *column as u32,
-                    row_number: -1,
-                    message: format!("No reference table found for column \"{}\".", table.get_ref_definition().get_fields_processed()[*column as usize].get_name()),
-                    report_type: TableDiagnosticReportType::NoReferenceTableFound,
-                    level: DiagnosticLevel::Info,
+                    column_number: 0,
+                    row_number: row as i64,
+                    message: "Empty row.".to_string(),
+                    report_type: TableDiagnosticReportType::EmptyRow,
+                    level: DiagnosticLevel::Error,
                 });
             }
 
-            for column in &columns_with_reference_table_and_no_column {
-                if dependencies.get_ref_fake_dependency_database().is_empty() {
-                    diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
-                        column_number: *column as u32,
-                        row_number: -1,
-                        message: format!("No reference column found in referenced table for column \"{}\". Did you forgot to generate the PAK file for this game?", table.get_ref_definition().get_fields_processed()[*column as usize].get_name()),
-                        report_type: TableDiagnosticReportType::NoReferenceTableNorColumnFoundNoPak,
-                        level: DiagnosticLevel::Warning,
-                    });
-                }
-                else {
-                    diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
-                        column_number: *column as u32,
-                        row_number: -1,
-                        message: format!("No reference column found in referenced table for column \"{}\". Maybe a problem with the schema?", table.get_ref_definition().get_fields_processed()[*column as usize].get_name()),
-                        report_type: TableDiagnosticReportType::NoReferenceTableNorColumnFoundPak,
-                        level: DiagnosticLevel::Info,
-                    });
-                }
+            if row_keys_are_empty {
+                diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
+                    column_number: 0,
+                    row_number: row as i64,
+                    message: "Empty key fields.".to_string(),
+                    report_type: TableDiagnosticReportType::EmptyKeyFields,
+                    level: DiagnosticLevel::Warning,
+                });
             }
 
-            if !diagnostic.get_ref_result().is_empty() {
-                Some(DiagnosticType::DB(diagnostic))
-            } else { None }
-        } else { None }
+            if local_keys.len() > 1 && keys.contains(&local_keys) {
+                diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
+                    column_number: 0,
+                    row_number: row as i64,
+                    message: format!("Duplicated combined keys: {}.", local_keys.join("| |")),
+                    report_type: TableDiagnosticReportType::DuplicatedCombinedKeys,
+                    level: DiagnosticLevel::Error,
+                });
+            }
+            else {
+                keys.push(local_keys);
+            }
+        }
+
+        // Checks that only need to be done once per table.
+        for column in &columns_without_reference_table {
+            diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
+                column_number: *column as u32,
+                row_number: -1,
+                message: format!("No reference table found for column \"{}\".", definition.get_fields_processed()[*column as usize].get_name()),
+                report_type: TableDiagnosticReportType::NoReferenceTableFound,
+                level: DiagnosticLevel::Info,
+            });
+        }
+
+        for column in &columns_with_reference_table_and_no_column {
+            if dependencies.get_ref_fake_dependency_database().is_empty() {
+                diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
+                    column_number: *column as u32,
+                    row_number: -1,
+                    message: format!("No reference column found in referenced table for column \"{}\". Did you forgot to generate the PAK file for this game?", definition.get_fields_processed()[*column as usize].get_name()),
+                    report_type: TableDiagnosticReportType::NoReferenceTableNorColumnFoundNoPak,
+                    level: DiagnosticLevel::Warning,
+                });
+            }
+            else {
+                diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
+                    column_number: *column as u32,
+                    row_number: -1,
+                    message: format!("No reference column found in referenced table for column \"{}\". Maybe a problem with the schema?", definition.get_fields_processed()[*column as usize].get_name()),
+                    report_type: TableDiagnosticReportType::NoReferenceTableNorColumnFoundPak,
+                    level: DiagnosticLevel::Info,
+                });
+            }
+        }
     }
 
     /// This function takes care of checking the loc tables of your mod for errors.
@@ -487,6 +529,7 @@ impl Diagnostics {
             }
 
             let diagnostic = match packed_file.get_packed_file_type_by_path() {
+                PackedFileType::CEO => Self::check_ceo(pack_file, packed_file.get_ref_decoded(), packed_file.get_path(), dependencies, &ignored_fields),
                 PackedFileType::DB => Self::check_db(pack_file, packed_file.get_ref_decoded(), packed_file.get_path(), &dependencies, &schema, &ignored_fields),
                 PackedFileType::Loc => Self::check_loc(packed_file.get_ref_decoded(), packed_file.get_path(), &ignored_fields),
                 _ => None,
@@ -518,6 +561,7 @@ impl Diagnostics {
 impl Display for DiagnosticType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         Display::fmt(match self {
+            Self::CEO(_) => "CEO",
             Self::DB(_) => "DB",
             Self::Loc(_) => "Loc",
             Self::PackFile(_) => "Packfile",
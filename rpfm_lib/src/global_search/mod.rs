@@ -22,9 +22,9 @@ use rpfm_error::{ErrorKind, Result};
 use crate::packfile::{PackFile, PathType};
 use crate::packfile::packedfile::PackedFileInfo;
 use crate::packedfile::{DecodedPackedFile, PackedFileType};
-use crate::packedfile::table::{DecodedData, db::DB, loc::Loc};
+use crate::packedfile::table::DecodedData;
 use crate::packedfile::text::{Text, TextType};
-use crate::schema::{Definition, Schema, VersionedFile};
+use crate::schema::{Definition, FieldType, Schema, VersionedFile};
 use crate::SCHEMA;
 
 use self::schema::{SchemaMatches, SchemaMatch};
@@ -55,7 +55,7 @@ pub struct GlobalSearch {
     /// If the search must be done using regex instead basic matching.
     pub use_regex: bool,
 
-    /// If we should search on DB Tables.
+    /// If we should search on DB Tables. This includes CEO PackedFiles, as they're decoded like DB Tables.
     pub search_on_dbs: bool,
 
     /// If we should search on Loc Tables.
@@ -67,7 +67,7 @@ pub struct GlobalSearch {
     /// If we should search on the currently loaded Schema.
     pub search_on_schema: bool,
 
-    /// Matches on DB Tables.
+    /// Matches on DB Tables and CEO PackedFiles.
     pub matches_db: Vec<TableMatches>,
 
     /// Matches on Loc Tables.
@@ -143,12 +143,14 @@ impl GlobalSearch {
         // If we got no schema, don't even decode.
         if let Some(ref schema) = *SCHEMA.read().unwrap() {
             if self.search_on_dbs {
-                let mut packed_files = pack_file.get_ref_mut_packed_files_by_type(PackedFileType::DB, false);
+                let mut packed_files = pack_file.get_ref_mut_packed_files_by_types(&[PackedFileType::DB, PackedFileType::CEO], false);
                 self.matches_db = packed_files.par_iter_mut().filter_map(|packed_file| {
                     let path = packed_file.get_path().to_vec();
-                    if let Ok(DecodedPackedFile::DB(data)) = packed_file.decode_return_ref_no_locks(&schema) {
-                        Some(self.search_on_db(&path, &data, &matching_mode))
-                    } else { None }
+                    match packed_file.decode_return_ref_no_locks(&schema) {
+                        Ok(DecodedPackedFile::DB(data)) => Some(self.search_on_table(&path, data.get_ref_definition(), data.get_ref_table_data(), &matching_mode)),
+                        Ok(DecodedPackedFile::CEO(data)) => Some(self.search_on_table(&path, data.get_ref_definition(), data.get_ref_table_data(), &matching_mode)),
+                        _ => None,
+                    }
                 }).collect();
             }
 
@@ -157,7 +159,7 @@ impl GlobalSearch {
                 self.matches_loc = packed_files.par_iter_mut().filter_map(|packed_file| {
                     let path = packed_file.get_path().to_vec();
                     if let Ok(DecodedPackedFile::Loc(data)) = packed_file.decode_return_ref_no_locks(&schema) {
-                        Some(self.search_on_loc(&path, &data, &matching_mode))
+                        Some(self.search_on_table(&path, data.get_ref_definition(), data.get_ref_table_data(), &matching_mode))
                     } else { None }
                 }).collect();
             }
@@ -224,12 +226,17 @@ impl GlobalSearch {
                     match packed_file.decode_return_ref_no_locks(&schema).unwrap_or(&DecodedPackedFile::Unknown) {
                         DecodedPackedFile::DB(data) => {
                             if self.search_on_dbs {
-                                self.matches_db.push(self.search_on_db(&path, data, &matching_mode));
+                                self.matches_db.push(self.search_on_table(&path, data.get_ref_definition(), data.get_ref_table_data(), &matching_mode));
+                            }
+                        }
+                        DecodedPackedFile::CEO(data) => {
+                            if self.search_on_dbs {
+                                self.matches_db.push(self.search_on_table(&path, data.get_ref_definition(), data.get_ref_table_data(), &matching_mode));
                             }
                         }
                         DecodedPackedFile::Loc(data) => {
                             if self.search_on_locs {
-                                self.matches_loc.push(self.search_on_loc(&path, data, &matching_mode));
+                                self.matches_loc.push(self.search_on_table(&path, data.get_ref_definition(), data.get_ref_table_data(), &matching_mode));
                             }
                         }
                         DecodedPackedFile::Text(data) => {
@@ -252,7 +259,10 @@ impl GlobalSearch {
     /// This function returns the PackedFileInfo for all the PackedFiles the current search has searched on.
     pub fn get_results_packed_file_info(&self, pack_file: &mut PackFile) -> Vec<PackedFileInfo> {
         let mut types = vec![];
-        if self.search_on_dbs { types.push(PackedFileType::DB); }
+        if self.search_on_dbs {
+            types.push(PackedFileType::DB);
+            types.push(PackedFileType::CEO);
+        }
         if self.search_on_locs { types.push(PackedFileType::Loc); }
         if self.search_on_texts { types.push(PackedFileType::Text(TextType::Plain)); }
         let packed_files = pack_file.get_ref_packed_files_by_types(&types, false);
@@ -305,6 +315,23 @@ impl GlobalSearch {
                                             errors.push(match_table.path.to_vec());
                                         }
                                     }
+                                    DecodedPackedFile::CEO(ref mut table) => {
+                                        let mut data = table.get_table_data();
+                                        for match_data in &match_table.matches {
+
+                                            // If any replace in the table fails, forget about this one and try the next one.
+                                            if self.replace_match_table(&mut data, &mut changed_files, match_table, match_data, &matching_mode).is_err() {
+                                                changed_files.retain(|x| x != &match_table.path);
+                                                errors.push(match_table.path.to_vec());
+                                                break;
+                                            }
+                                        }
+
+                                        if changed_files.contains(&match_table.path) && table.set_table_data(&data).is_err() {
+                                            changed_files.retain(|x| x != &match_table.path);
+                                            errors.push(match_table.path.to_vec());
+                                        }
+                                    }
                                     DecodedPackedFile::Loc(ref mut table)=> {
                                         let mut data = table.get_table_data();
                                         for match_data in &match_table.matches {
@@ -358,22 +385,42 @@ impl GlobalSearch {
             let mut changed_files = vec![];
             for match_table in &self.matches_db {
                 if let Some(packed_file) = pack_file.get_ref_mut_packed_file_by_path(&match_table.path) {
-                    if let Ok(DecodedPackedFile::DB(ref mut table)) = packed_file.decode_return_ref_mut_no_locks(&schema) {
-                        let mut data = table.get_table_data();
-                        for match_data in &match_table.matches {
+                    match packed_file.decode_return_ref_mut_no_locks(&schema) {
+                        Ok(DecodedPackedFile::DB(ref mut table)) => {
+                            let mut data = table.get_table_data();
+                            for match_data in &match_table.matches {
+
+                                // If any replace in the table fails, forget about this one and try the next one.
+                                if self.replace_match_table(&mut data, &mut changed_files, match_table, match_data, &matching_mode).is_err() {
+                                    changed_files.retain(|x| x != &match_table.path);
+                                    errors.push(match_table.path.to_vec());
+                                    break;
+                                }
+                            }
 
-                            // If any replace in the table fails, forget about this one and try the next one.
-                            if self.replace_match_table(&mut data, &mut changed_files, match_table, match_data, &matching_mode).is_err() {
+                            if changed_files.contains(&match_table.path) && table.set_table_data(&data).is_err() {
                                 changed_files.retain(|x| x != &match_table.path);
                                 errors.push(match_table.path.to_vec());
-                                break;
                             }
                         }
+                        Ok(DecodedPackedFile::CEO(ref mut table)) => {
+                            let mut data = table.get_table_data();
+                            for match_data in &match_table.matches {
+
+                                // If any replace in the table fails, forget about this one and try the next one.
+                                if self.replace_match_table(&mut data, &mut changed_files, match_table, match_data, &matching_mode).is_err() {
+                                    changed_files.retain(|x| x != &match_table.path);
+                                    errors.push(match_table.path.to_vec());
+                                    break;
+                                }
+                            }
 
-                        if changed_files.contains(&match_table.path) && table.set_table_data(&data).is_err() {
-                            changed_files.retain(|x| x != &match_table.path);
-                            errors.push(match_table.path.to_vec());
+                            if changed_files.contains(&match_table.path) && table.set_table_data(&data).is_err() {
+                                changed_files.retain(|x| x != &match_table.path);
+                                errors.push(match_table.path.to_vec());
+                            }
                         }
+                        _ => {}
                     }
                 }
             }
@@ -444,6 +491,51 @@ impl GlobalSearch {
                         self.replace_match(&mut string, matching_mode);
                         *field = string.parse::<i64>()?;
                     }
+                    DecodedData::F64(ref mut field) => {
+                        let mut string = field.to_string();
+                        self.replace_match(&mut string, matching_mode);
+                        *field = string.parse::<f64>()?;
+                    }
+                    DecodedData::I8(ref mut field) => {
+                        let mut string = field.to_string();
+                        self.replace_match(&mut string, matching_mode);
+                        *field = string.parse::<i8>()?;
+                    }
+                    DecodedData::U8(ref mut field) => {
+                        let mut string = field.to_string();
+                        self.replace_match(&mut string, matching_mode);
+                        *field = string.parse::<u8>()?;
+                    }
+                    DecodedData::U16(ref mut field) => {
+                        let mut string = field.to_string();
+                        self.replace_match(&mut string, matching_mode);
+                        *field = string.parse::<u16>()?;
+                    }
+                    DecodedData::U32(ref mut field) => {
+                        let mut string = field.to_string();
+                        self.replace_match(&mut string, matching_mode);
+                        *field = string.parse::<u32>()?;
+                    }
+                    DecodedData::ColourRGB(ref mut field) => {
+                        let mut string = field.to_owned();
+                        self.replace_match(&mut string, matching_mode);
+                        *field = DecodedData::new_from_string(&string, &FieldType::ColourRGB)?.data_to_string();
+                    }
+                    DecodedData::OptionalI16(ref mut field) => {
+                        let mut string = field.map(|field| field.to_string()).unwrap_or_default();
+                        self.replace_match(&mut string, matching_mode);
+                        *field = if string.is_empty() { None } else { Some(string.parse::<i16>()?) };
+                    }
+                    DecodedData::OptionalI32(ref mut field) => {
+                        let mut string = field.map(|field| field.to_string()).unwrap_or_default();
+                        self.replace_match(&mut string, matching_mode);
+                        *field = if string.is_empty() { None } else { Some(string.parse::<i32>()?) };
+                    }
+                    DecodedData::OptionalI64(ref mut field) => {
+                        let mut string = field.map(|field| field.to_string()).unwrap_or_default();
+                        self.replace_match(&mut string, matching_mode);
+                        *field = if string.is_empty() { None } else { Some(string.parse::<i64>()?) };
+                    }
                     DecodedData::StringU8(ref mut field) |
                     DecodedData::StringU16(ref mut field) |
                     DecodedData::OptionalStringU8(ref mut field) |
@@ -484,54 +576,35 @@ impl GlobalSearch {
         }
     }
 
-    /// This function performs a search over the provided DB Table.
-    fn search_on_db(&self, path: &[String], table_data: &DB, matching_mode: &MatchingMode) -> TableMatches {
+    /// This function performs a search over the provided Table-like data, using its definition to get the names of the matched columns.
+    fn search_on_table(&self, path: &[String], definition: &Definition, table_data: &[Vec<DecodedData>], matching_mode: &MatchingMode) -> TableMatches {
         let mut matches = TableMatches::new(path);
 
-        for (row_number, row) in table_data.get_ref_table_data().iter().enumerate() {
+        for (row_number, row) in table_data.iter().enumerate() {
             for (column_number, cell) in row.iter().enumerate() {
                 match cell {
                     DecodedData::Boolean(ref data) => {
                         let text = if *data { "true" } else { "false" };
-                        self.match_decoded_data(text, matching_mode, &mut matches.matches, table_data.get_ref_definition(), column_number as u32, row_number as i64);
+                        self.match_decoded_data(text, matching_mode, &mut matches.matches, definition, column_number as u32, row_number as i64);
                     }
-                    DecodedData::F32(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, table_data.get_ref_definition(), column_number as u32, row_number as i64),
-                    DecodedData::I16(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, table_data.get_ref_definition(), column_number as u32, row_number as i64),
-                    DecodedData::I32(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, table_data.get_ref_definition(), column_number as u32, row_number as i64),
-                    DecodedData::I64(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, table_data.get_ref_definition(), column_number as u32, row_number as i64),
-
-                    DecodedData::StringU8(ref data) |
-                    DecodedData::StringU16(ref data) |
-                    DecodedData::OptionalStringU8(ref data) |
-                    DecodedData::OptionalStringU16(ref data) => self.match_decoded_data(data, matching_mode, &mut matches.matches, table_data.get_ref_definition(), column_number as u32, row_number as i64),
-                    DecodedData::SequenceU16(_) | DecodedData::SequenceU32(_) => continue,
-                }
-            }
-        }
-
-        matches
-    }
-
-    /// This function performs a search over the provided Loc Table.
-    fn search_on_loc(&self, path: &[String], table_data: &Loc, matching_mode: &MatchingMode) -> TableMatches {
-        let mut matches = TableMatches::new(path);
-
-        for (row_number, row) in table_data.get_ref_table_data().iter().enumerate() {
-            for (column_number, cell) in row.iter().enumerate() {
-                match cell {
-                    DecodedData::Boolean(ref data) => {
-                        let text = if *data { "true" } else { "false" };
-                        self.match_decoded_data(text, matching_mode, &mut matches.matches, table_data.get_ref_definition(), column_number as u32, row_number as i64);
-                    }
-                    DecodedData::F32(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, table_data.get_ref_definition(), column_number as u32, row_number as i64),
-                    DecodedData::I16(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, table_data.get_ref_definition(), column_number as u32, row_number as i64),
-                    DecodedData::I32(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, table_data.get_ref_definition(), column_number as u32, row_number as i64),
-                    DecodedData::I64(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, table_data.get_ref_definition(), column_number as u32, row_number as i64),
-
+                    DecodedData::F32(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, definition, column_number as u32, row_number as i64),
+                    DecodedData::I16(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, definition, column_number as u32, row_number as i64),
+                    DecodedData::I32(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, definition, column_number as u32, row_number as i64),
+                    DecodedData::I64(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, definition, column_number as u32, row_number as i64),
+                    DecodedData::F64(ref data) => self.match_decoded_data(&data.to_string(), matching_mode, &mut matches.matches, definition, column_number as u32, row_number as i64),
+                    DecodedData::I8(_) |
+                    DecodedData::U8(_) |
+                    DecodedData::U16(_) |
+                    DecodedData::U32(_) |
+                    DecodedData::OptionalI16(_) |
+                    DecodedData::OptionalI32(_) |
+                    DecodedData::OptionalI64(_) => self.match_decoded_data(&cell.data_to_string(), matching_mode, &mut matches.matches, definition, column_number as u32, row_number as i64),
+
+                    DecodedData::ColourRGB(ref data) |
                     DecodedData::StringU8(ref data) |
                     DecodedData::StringU16(ref data) |
                     DecodedData::OptionalStringU8(ref data) |
-                    DecodedData::OptionalStringU16(ref data) => self.match_decoded_data(data, matching_mode, &mut matches.matches, table_data.get_ref_definition(), column_number as u32, row_number as i64),
+                    DecodedData::OptionalStringU16(ref data) => self.match_decoded_data(data, matching_mode, &mut matches.matches, definition, column_number as u32, row_number as i64),
                     DecodedData::SequenceU16(_) | DecodedData::SequenceU32(_) => continue,
                 }
             }
@@ -604,6 +677,7 @@ impl GlobalSearch {
             match versioned_file {
                 VersionedFile::AnimFragment(definitions) |
                 VersionedFile::AnimTable(definitions) |
+                VersionedFile::CEO(definitions) |
                 VersionedFile::DB(_, definitions) |
                 VersionedFile::DepManager(definitions) |
                 VersionedFile::Loc(definitions) |
@@ -659,6 +733,7 @@ impl GlobalSearch {
                 let (versioned_file_type, versioned_file_name) = match versioned_file {
                     VersionedFile::AnimFragment(_) => ("AnimFragment".to_owned(), None),
                     VersionedFile::AnimTable(_) => ("AnimTable".to_owned(), None),
+                    VersionedFile::CEO(_) => ("CEO".to_owned(), None),
                     VersionedFile::DB(name, _) => ("DB".to_owned(), Some(name.to_owned())),
                     VersionedFile::DepManager(_) => ("Dependency Manager".to_owned(), None),
                     VersionedFile::Loc(_) => ("Loc".to_owned(), None),
@@ -42,6 +42,7 @@ pub mod packedfile;
 pub mod packfile;
 pub mod schema;
 pub mod settings;
+pub mod sql;
 pub mod template;
 pub mod updater;
 
@@ -0,0 +1,160 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Anim` module, to make sure animations are decoded and encoded correctly.
+!*/
+
+use std::collections::BTreeMap;
+
+use crate::packedfile::table::{DecodedData, Table};
+use crate::schema::{Definition, Field, FieldType};
+
+use super::*;
+
+/// This function builds a raw Anim with two bones and the provided frames, each one with one translation and one rotation.
+fn build_anim(version: u32, rotation_format: RotationFormat, frames: &[([f32; 3], [i16; 4])]) -> Vec<u8> {
+    let mut data = vec![];
+    data.encode_integer_u32(version);
+    data.encode_integer_u32(1);
+    data.encode_float_f32(20.0);
+    data.encode_packedfile_string_u8("humanoid01");
+    if version >= 7 {
+        data.encode_integer_u32(0);
+    }
+    data.encode_float_f32(frames.len().saturating_sub(1) as f32 / 20.0);
+
+    data.encode_integer_u32(2);
+    data.encode_packedfile_string_u8("root");
+    data.encode_integer_i32(-1);
+    data.encode_packedfile_string_u8("animroot");
+    data.encode_integer_i32(0);
+    [0, -1, 0, 10000].iter().for_each(|mapping| data.encode_integer_i32(*mapping));
+
+    let encode_rotation = |data: &mut Vec<u8>, rotation: &[i16; 4]| rotation.iter().for_each(|component| match rotation_format {
+        RotationFormat::Quantised => data.encode_integer_i16(*component),
+        RotationFormat::Float => data.encode_float_f32(f32::from(*component) / QUANTISATION_SCALE),
+    });
+
+    // Static frame with a single rotation.
+    if version >= 7 {
+        data.encode_integer_u32(0);
+        data.encode_integer_u32(1);
+        encode_rotation(&mut data, &[0, 0, 0, 32767]);
+    }
+
+    data.encode_integer_u32(1);
+    data.encode_integer_u32(1);
+    data.encode_integer_u32(frames.len() as u32);
+    for (translation, rotation) in frames {
+        translation.iter().for_each(|component| data.encode_float_f32(*component));
+        encode_rotation(&mut data, rotation);
+    }
+    data
+}
+
+#[test]
+fn test_anim() {
+    let frames = [
+        ([0.0, 1.0, 2.0], [0, 0, 0, 32767]),
+        ([1.0, 1.0, 2.0], [0, 23170, 0, 23170]),
+        ([2.0, 1.0, 2.0], [0, 32767, 0, 0]),
+    ];
+
+    // Quantised rotations, version 7.
+    let data = build_anim(7, RotationFormat::Quantised, &frames);
+    let anim = Anim::read(&data).unwrap();
+    assert_eq!(anim.get_ref_skeleton_name(), "humanoid01");
+    assert_eq!(*anim.get_ref_frame_rate(), 20.0);
+    assert_eq!(anim.get_ref_bones().len(), 2);
+    assert_eq!(anim.get_ref_bones()[1].get_ref_name(), "animroot");
+    assert_eq!(anim.get_ref_rotation_mappings(), &[0, 10000]);
+    assert_eq!(*anim.get_ref_rotation_format(), RotationFormat::Quantised);
+    assert_eq!(anim.get_ref_static_frame().get_ref_rotations(), &[[0.0, 0.0, 0.0, 1.0]]);
+    assert_eq!(anim.get_ref_frames().len(), 3);
+    assert_eq!((*anim.get_ref_translation_count(), *anim.get_ref_rotation_count()), (1, 1));
+    assert_eq!(anim.get_ref_frames()[2].get_ref_translations(), &[[2.0, 1.0, 2.0]]);
+    assert_eq!(anim.save().unwrap(), data);
+
+    // Float rotations, version 6.
+    let data = build_anim(6, RotationFormat::Float, &frames);
+    let anim = Anim::read(&data).unwrap();
+    assert_eq!(*anim.get_ref_rotation_format(), RotationFormat::Float);
+    assert_eq!(anim.get_ref_static_frame(), &Frame::default());
+    assert_eq!(anim.get_ref_frames()[1].get_ref_rotations()[0][1], 23170.0 / 32767.0);
+    assert_eq!(anim.save().unwrap(), data);
+
+    // Animations without frames.
+    let data = build_anim(5, RotationFormat::Quantised, &[]);
+    assert_eq!(Anim::read(&data).unwrap().save().unwrap(), data);
+
+    // Broken or unsupported animations.
+    let data = build_anim(7, RotationFormat::Quantised, &frames);
+    assert!(Anim::read(&data[..data.len() - 1]).is_err());
+    assert!(Anim::read(&build_anim(8, RotationFormat::Quantised, &frames)).is_err());
+
+    let mut anim = Anim::read(&data).unwrap();
+    anim.get_ref_mut_frames()[0].get_ref_mut_rotations().clear();
+    assert!(anim.save().is_err());
+}
+
+#[test]
+fn test_anim_retime() {
+    let frames = [
+        ([0.0, 0.0, 0.0], [0, 0, 0, 32767]),
+        ([4.0, 0.0, 0.0], [0, 32767, 0, 0]),
+    ];
+
+    let mut anim = Anim::read(&build_anim(7, RotationFormat::Quantised, &frames)).unwrap();
+    anim.retime(0.1).unwrap();
+    assert_eq!(*anim.get_ref_frame_rate(), 10.0);
+    assert_eq!(*anim.get_ref_end_time(), 0.1);
+    assert_eq!(anim.get_ref_frames().len(), 2);
+
+    // From 10 to 40 FPS, the duration stays the same, so we get 3 new frames between the existing ones.
+    anim.resample(40.0).unwrap();
+    assert_eq!(anim.get_ref_frames().len(), 5);
+    assert_eq!(anim.get_ref_frames()[2].get_ref_translations()[0], [2.0, 0.0, 0.0]);
+    let rotation = anim.get_ref_frames()[2].get_ref_rotations()[0];
+    assert!((rotation[1] - rotation[3]).abs() < 0.0001 && (rotation[1] - 0.5f32.sqrt()).abs() < 0.0001);
+    assert_eq!(anim.get_ref_frames()[4].get_ref_translations()[0], [4.0, 0.0, 0.0]);
+    assert!(Anim::read(&anim.save().unwrap()).is_ok());
+
+    assert!(anim.retime(0.0).is_err());
+    assert!(anim.resample(-1.0).is_err());
+}
+
+#[test]
+fn test_anim_fragment_compatibility() {
+    let mut entry_definition = Definition::new(0);
+    entry_definition.get_ref_mut_fields().push(Field::new("filename".to_owned(), FieldType::StringU8, false, None, 0, false, None, None, None, String::new(), -1, 0, BTreeMap::new()));
+    entry_definition.get_ref_mut_fields().push(Field::new("skeleton_type".to_owned(), FieldType::StringU8, false, None, 0, false, None, None, None, String::new(), -1, 0, BTreeMap::new()));
+
+    let mut definition = Definition::new(0);
+    definition.get_ref_mut_fields().push(Field::new("skeleton_1".to_owned(), FieldType::StringU8, false, None, 0, false, None, None, None, String::new(), -1, 0, BTreeMap::new()));
+    definition.get_ref_mut_fields().push(Field::new("entries".to_owned(), FieldType::SequenceU32(entry_definition.clone()), false, None, 0, false, None, None, None, String::new(), -1, 0, BTreeMap::new()));
+
+    let mut entries = Table::new(&entry_definition);
+    entries.set_table_data(&[
+        vec![DecodedData::StringU8("animations/a.anim".to_owned()), DecodedData::StringU8("humanoid01b".to_owned())],
+        vec![DecodedData::StringU8("animations/b.anim".to_owned()), DecodedData::StringU8(String::new())],
+    ]).unwrap();
+
+    let mut anim_fragment = AnimFragment::new(&definition);
+    anim_fragment.set_table_data(&[vec![DecodedData::StringU8("humanoid01".to_owned()), DecodedData::SequenceU32(entries)]]).unwrap();
+    assert_eq!(anim_fragment.get_skeleton_names(), vec!["humanoid01".to_owned(), "humanoid01b".to_owned()]);
+
+    let mut anim = Anim::new("humanoid01", 20.0);
+    assert!(anim.is_compatible_with(&anim_fragment));
+    anim.set_skeleton_name("Humanoid01B".to_owned());
+    assert!(anim.is_compatible_with(&anim_fragment));
+    anim.set_skeleton_name("dragon01".to_owned());
+    assert!(!anim.is_compatible_with(&anim_fragment));
+}
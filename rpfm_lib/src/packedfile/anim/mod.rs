@@ -0,0 +1,410 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to interact with Anim PackedFiles.
+
+Anim PackedFiles are the skeletal animations used by Total War games. The structure of an Anim is:
+- A header, with the version, the framerate, the skeleton the animation is for and how long it lasts.
+- The bones of the skeleton, with their parents.
+- Two mapping tables, saying which translation and rotation track of the frames moves each bone.
+- A static frame (only in version 7), with tracks that don't change during the animation.
+- The frames of the animation, each one with the same amount of translation and rotation tracks.
+
+Translations are always stored as floats. Rotations are quaternions, usually quantised to 16 bits per component,
+but they can also be stored as floats. Which one is used is detected from the size of the data.
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_macros::*;
+
+use crate::common::{decoder::Decoder, encoder::Encoder};
+use crate::packedfile::table::anim_fragment::AnimFragment;
+
+#[cfg(test)]
+mod anim_test;
+
+/// Extension used by Anim PackedFiles.
+pub const EXTENSION: &str = ".anim";
+
+/// Versions of Anim PackedFiles we can decode.
+const SUPPORTED_VERSIONS: [u32; 3] = [5, 6, 7];
+
+/// Value used to quantise each component of a rotation.
+const QUANTISATION_SCALE: f32 = 32767.0;
+
+/// Max amount of frames without tracks we accept, as we cannot check their amount against the size of the data.
+const MAX_EMPTY_FRAMES: u32 = 0xFFFF;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains an Anim decoded in memory.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct Anim {
+
+    /// The version of the Anim. Supported versions are `5`, `6` and `7`.
+    version: u32,
+
+    /// Unknown value. Always 1.
+    unknown_1: u32,
+
+    /// Frames per second of the animation.
+    frame_rate: f32,
+
+    /// Name of the skeleton this animation is for.
+    skeleton_name: String,
+
+    /// Unknown value. Only in version 7.
+    unknown_2: u32,
+
+    /// Duration of the animation, in seconds.
+    end_time: f32,
+
+    /// Bones of the skeleton.
+    bones: Vec<Bone>,
+
+    /// Index of the translation track of each bone, or -1 if the bone is not moved by the animation.
+    translation_mappings: Vec<i32>,
+
+    /// Index of the rotation track of each bone, or -1 if the bone is not rotated by the animation.
+    rotation_mappings: Vec<i32>,
+
+    /// Format of the rotations of the frames.
+    rotation_format: RotationFormat,
+
+    /// Frame with the tracks that don't change during the animation. Only in version 7.
+    static_frame: Frame,
+
+    /// Amount of translation tracks of each frame.
+    translation_count: u32,
+
+    /// Amount of rotation tracks of each frame.
+    rotation_count: u32,
+
+    /// Frames of the animation.
+    frames: Vec<Frame>,
+}
+
+/// This struct represents a bone of the skeleton of an Anim.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct Bone {
+
+    /// Name of the bone.
+    name: String,
+
+    /// Index of the parent bone, or -1 if this is the root bone.
+    parent_id: i32,
+}
+
+/// This struct represents a frame of an Anim, with all its tracks.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct Frame {
+
+    /// Translation tracks of the frame, as XYZ vectors.
+    translations: Vec<[f32; 3]>,
+
+    /// Rotation tracks of the frame, as XYZW quaternions.
+    rotations: Vec<[f32; 4]>,
+}
+
+/// This enum represents the format the rotations of an Anim are stored in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RotationFormat {
+
+    /// Each component is an `i16`, with 32767 being 1.0.
+    Quantised,
+
+    /// Each component is a `f32`.
+    Float,
+}
+
+//---------------------------------------------------------------------------//
+//                           Implementation of Anim
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Anim`.
+impl Anim {
+
+    /// This function creates an empty `Anim` of the latest version for the provided skeleton.
+    pub fn new(skeleton_name: &str, frame_rate: f32) -> Self {
+        Self {
+            version: 7,
+            unknown_1: 1,
+            frame_rate,
+            skeleton_name: skeleton_name.to_owned(),
+            unknown_2: 0,
+            end_time: 0.0,
+            bones: vec![],
+            translation_mappings: vec![],
+            rotation_mappings: vec![],
+            rotation_format: RotationFormat::Quantised,
+            static_frame: Frame::default(),
+            translation_count: 0,
+            rotation_count: 0,
+            frames: vec![],
+        }
+    }
+
+    /// This function creates an `Anim` from a `&[u8]`.
+    pub fn read(packed_file_data: &[u8]) -> Result<Self> {
+        let mut index = 0;
+        let version = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+        if !SUPPORTED_VERSIONS.contains(&version) {
+            return Err(ErrorKind::AnimDecode(format!("Unsupported version: {}.", version)).into());
+        }
+
+        let unknown_1 = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+        let frame_rate = packed_file_data.decode_packedfile_float_f32(index, &mut index)?;
+        let skeleton_name = packed_file_data.decode_packedfile_string_u8(index, &mut index)?;
+        let unknown_2 = if version >= 7 { packed_file_data.decode_packedfile_integer_u32(index, &mut index)? } else { 0 };
+        let end_time = packed_file_data.decode_packedfile_float_f32(index, &mut index)?;
+
+        let bone_count = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+        let mut bones = vec![];
+        for _ in 0..bone_count {
+            bones.push(Bone {
+                name: packed_file_data.decode_packedfile_string_u8(index, &mut index)?,
+                parent_id: packed_file_data.decode_packedfile_integer_i32(index, &mut index)?,
+            });
+        }
+
+        let mut translation_mappings = vec![];
+        for _ in 0..bone_count {
+            translation_mappings.push(packed_file_data.decode_packedfile_integer_i32(index, &mut index)?);
+        }
+
+        let mut rotation_mappings = vec![];
+        for _ in 0..bone_count {
+            rotation_mappings.push(packed_file_data.decode_packedfile_integer_i32(index, &mut index)?);
+        }
+
+        // The format of the rotations is not in the file, so we try with the quantised one, and if the frames don't fill the file, we try with floats.
+        let (rotation_format, static_frame, translation_count, rotation_count, frames) = Self::read_frames(packed_file_data, index, version, RotationFormat::Quantised)
+            .or_else(|_| Self::read_frames(packed_file_data, index, version, RotationFormat::Float))?;
+
+        Ok(Self {
+            version,
+            unknown_1,
+            frame_rate,
+            skeleton_name,
+            unknown_2,
+            end_time,
+            bones,
+            translation_mappings,
+            rotation_mappings,
+            rotation_format,
+            static_frame,
+            translation_count,
+            rotation_count,
+            frames,
+        })
+    }
+
+    /// This function takes an `Anim` and encodes it to `Vec<u8>`.
+    pub fn save(&self) -> Result<Vec<u8>> {
+        if !SUPPORTED_VERSIONS.contains(&self.version) {
+            return Err(ErrorKind::AnimEncode(format!("Unsupported version: {}.", self.version)).into());
+        }
+
+        if self.translation_mappings.len() != self.bones.len() || self.rotation_mappings.len() != self.bones.len() {
+            return Err(ErrorKind::AnimEncode("The amount of mappings doesn't match the amount of bones.".to_owned()).into());
+        }
+
+        let mut packed_file = vec![];
+        packed_file.encode_integer_u32(self.version);
+        packed_file.encode_integer_u32(self.unknown_1);
+        packed_file.encode_float_f32(self.frame_rate);
+        packed_file.encode_packedfile_string_u8(&self.skeleton_name);
+        if self.version >= 7 {
+            packed_file.encode_integer_u32(self.unknown_2);
+        }
+        packed_file.encode_float_f32(self.end_time);
+
+        packed_file.encode_integer_u32(self.bones.len() as u32);
+        for bone in &self.bones {
+            packed_file.encode_packedfile_string_u8(&bone.name);
+            packed_file.encode_integer_i32(bone.parent_id);
+        }
+
+        self.translation_mappings.iter().for_each(|mapping| packed_file.encode_integer_i32(*mapping));
+        self.rotation_mappings.iter().for_each(|mapping| packed_file.encode_integer_i32(*mapping));
+
+        if self.version >= 7 {
+            packed_file.encode_integer_u32(self.static_frame.translations.len() as u32);
+            packed_file.encode_integer_u32(self.static_frame.rotations.len() as u32);
+            self.static_frame.save(&mut packed_file, self.rotation_format);
+        }
+
+        // All frames must have the same amount of tracks, as it's only stored once.
+        if self.frames.iter().any(|frame| frame.translations.len() != self.translation_count as usize || frame.rotations.len() != self.rotation_count as usize) {
+            return Err(ErrorKind::AnimEncode("Not all frames have the amount of tracks the animation says they have.".to_owned()).into());
+        }
+
+        packed_file.encode_integer_u32(self.translation_count);
+        packed_file.encode_integer_u32(self.rotation_count);
+        packed_file.encode_integer_u32(self.frames.len() as u32);
+        for frame in &self.frames {
+            frame.save(&mut packed_file, self.rotation_format);
+        }
+
+        Ok(packed_file)
+    }
+
+    /// This function reads the static frame (if any) and the frames of an Anim, using the provided rotation format.
+    ///
+    /// It fails if the frames don't end exactly at the end of the data.
+    fn read_frames(packed_file_data: &[u8], mut index: usize, version: u32, rotation_format: RotationFormat) -> Result<(RotationFormat, Frame, u32, u32, Vec<Frame>)> {
+        let static_frame = if version >= 7 {
+            let translation_count = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+            let rotation_count = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+            Frame::read(packed_file_data, &mut index, translation_count, rotation_count, rotation_format)?
+        } else { Frame::default() };
+
+        let translation_count = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+        let rotation_count = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+        let frame_count = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+        if translation_count == 0 && rotation_count == 0 && frame_count > MAX_EMPTY_FRAMES {
+            return Err(ErrorKind::AnimDecode(format!("Invalid amount of frames: {}.", frame_count)).into());
+        }
+
+        let mut frames = vec![];
+        for _ in 0..frame_count {
+            frames.push(Frame::read(packed_file_data, &mut index, translation_count, rotation_count, rotation_format)?);
+        }
+
+        if index != packed_file_data.len() {
+            return Err(ErrorKind::AnimDecode(format!("There are {} bytes of data after the last frame.", packed_file_data.len() as isize - index as isize)).into());
+        }
+
+        Ok((rotation_format, static_frame, translation_count, rotation_count, frames))
+    }
+
+    /// This function returns if this Anim uses one of the skeletons of the provided AnimFragment.
+    ///
+    /// AnimFragments that don't reference any skeleton are compatible with every Anim.
+    pub fn is_compatible_with(&self, anim_fragment: &AnimFragment) -> bool {
+        let skeleton_names = anim_fragment.get_skeleton_names();
+        skeleton_names.is_empty() || skeleton_names.iter().any(|name| name.eq_ignore_ascii_case(&self.skeleton_name))
+    }
+
+    /// This function changes the duration of this Anim, speeding up or slowing down its frames.
+    ///
+    /// The frames are not changed: only the framerate and the end time.
+    pub fn retime(&mut self, end_time: f32) -> Result<()> {
+        if !end_time.is_normal() || end_time < 0.0 || !self.end_time.is_normal() {
+            return Err(ErrorKind::AnimEncode(format!("Cannot change the duration of the animation from {} to {} seconds.", self.end_time, end_time)).into());
+        }
+
+        self.frame_rate *= self.end_time / end_time;
+        self.end_time = end_time;
+        Ok(())
+    }
+
+    /// This function changes the framerate of this Anim without changing its duration, interpolating its frames.
+    ///
+    /// Translations are interpolated linearly, and rotations with a normalised linear interpolation.
+    pub fn resample(&mut self, frame_rate: f32) -> Result<()> {
+        if !frame_rate.is_normal() || frame_rate < 0.0 || !self.frame_rate.is_normal() {
+            return Err(ErrorKind::AnimEncode(format!("Cannot change the framerate of the animation from {} to {}.", self.frame_rate, frame_rate)).into());
+        }
+
+        if self.frames.len() > 1 {
+            let ratio = self.frame_rate / frame_rate;
+            let last_frame = (self.frames.len() - 1) as f32;
+            let frame_count = (last_frame / ratio).round() as usize + 1;
+            self.frames = (0..frame_count).map(|frame| {
+                let position = (frame as f32 * ratio).min(last_frame);
+                let previous = &self.frames[position.floor() as usize];
+                let next = &self.frames[position.ceil() as usize];
+                previous.interpolate(next, position.fract())
+            }).collect();
+        }
+
+        self.frame_rate = frame_rate;
+        Ok(())
+    }
+}
+
+/// Implementation of `Frame`.
+impl Frame {
+
+    /// This function reads a frame with the provided amount of tracks.
+    fn read(packed_file_data: &[u8], index: &mut usize, translation_count: u32, rotation_count: u32, rotation_format: RotationFormat) -> Result<Self> {
+        let mut translations = vec![];
+        for _ in 0..translation_count {
+            translations.push([
+                packed_file_data.decode_packedfile_float_f32(*index, index)?,
+                packed_file_data.decode_packedfile_float_f32(*index, index)?,
+                packed_file_data.decode_packedfile_float_f32(*index, index)?,
+            ]);
+        }
+
+        let mut rotations = vec![];
+        for _ in 0..rotation_count {
+            let mut rotation = [0.0; 4];
+            for component in &mut rotation {
+                *component = match rotation_format {
+                    RotationFormat::Quantised => f32::from(packed_file_data.decode_packedfile_integer_i16(*index, index)?) / QUANTISATION_SCALE,
+                    RotationFormat::Float => packed_file_data.decode_packedfile_float_f32(*index, index)?,
+                };
+            }
+            rotations.push(rotation);
+        }
+
+        Ok(Self {
+            translations,
+            rotations,
+        })
+    }
+
+    /// This function writes the tracks of this frame.
+    fn save(&self, packed_file: &mut Vec<u8>, rotation_format: RotationFormat) {
+        for translation in &self.translations {
+            translation.iter().for_each(|component| packed_file.encode_float_f32(*component));
+        }
+
+        for rotation in &self.rotations {
+            for component in rotation {
+                match rotation_format {
+                    RotationFormat::Quantised => packed_file.encode_integer_i16((component * QUANTISATION_SCALE).round().max(i16::MIN as f32).min(i16::MAX as f32) as i16),
+                    RotationFormat::Float => packed_file.encode_float_f32(*component),
+                }
+            }
+        }
+    }
+
+    /// This function returns a frame between this one and the provided one. Both frames must have the same tracks.
+    fn interpolate(&self, other: &Self, factor: f32) -> Self {
+        let translations = self.translations.iter().zip(&other.translations).map(|(a, b)| {
+            [a[0] + (b[0] - a[0]) * factor, a[1] + (b[1] - a[1]) * factor, a[2] + (b[2] - a[2]) * factor]
+        }).collect();
+
+        // Quaternions q and -q are the same rotation, so make sure we take the shortest path.
+        let rotations = self.rotations.iter().zip(&other.rotations).map(|(a, b)| {
+            let sign = if a.iter().zip(b).map(|(a, b)| a * b).sum::<f32>() < 0.0 { -1.0 } else { 1.0 };
+            let mut rotation = [0.0; 4];
+            rotation.iter_mut().enumerate().for_each(|(component, value)| *value = a[component] + (b[component] * sign - a[component]) * factor);
+            let length = rotation.iter().map(|value| value * value).sum::<f32>().sqrt();
+            if length > 0.0 {
+                rotation.iter_mut().for_each(|value| *value /= length);
+            }
+            rotation
+        }).collect();
+
+        Self {
+            translations,
+            rotations,
+        }
+    }
+}
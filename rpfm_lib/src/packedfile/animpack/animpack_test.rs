@@ -0,0 +1,174 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `AnimPack` module, to make sure the container operations and the decoding of its files work.
+!*/
+
+use std::fs::{read, remove_dir_all};
+
+use crate::packedfile::table::DecodedData;
+use crate::packedfile::table::animtable::PATH;
+use crate::packedfile::text::TextType;
+use crate::schema::{Definition, Field, FieldType, VersionedFile};
+
+use super::*;
+
+/// This function splits a path of an AnimPack.
+fn path(path: &str) -> Vec<String> {
+    path.split('/').map(|x| x.to_owned()).collect()
+}
+
+/// This function builds a raw AnimPack with a few files.
+fn build_anim_pack() -> Vec<u8> {
+    let mut data = vec![];
+    data.encode_integer_i32(3);
+    for (path, contents) in &[
+        ("animations/animation_tables/readme.txt", "Hello there!"),
+        ("animations/animation_tables/notes.txt", "General Kenobi."),
+        ("animations/battle/humanoid01/unknown.xyz", "1234"),
+    ] {
+        data.encode_packedfile_string_u8(path);
+        data.encode_integer_i32(contents.len() as i32);
+        data.extend_from_slice(contents.as_bytes());
+    }
+    data
+}
+
+/// This function builds a raw AnimPack with an AnimTable of version 2, and a schema to decode it.
+fn build_anim_pack_with_animtable() -> (Vec<u8>, Schema) {
+    let mut definition = Definition::new(2);
+    *definition.get_ref_mut_fields() = [
+        ("skeleton_name", FieldType::StringU8),
+        ("table_name", FieldType::StringU8),
+        ("uses_mount", FieldType::Boolean),
+    ].iter().map(|(name, field_type)| {
+        let mut field = Field::default();
+        field.set_name(name);
+        field.set_field_type(field_type.clone());
+        field
+    }).collect();
+
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::AnimTable(vec![definition]));
+
+    let mut animtable = vec![];
+    animtable.encode_integer_i32(2);
+    animtable.encode_integer_u32(1);
+    animtable.encode_packedfile_string_u8("humanoid01");
+    animtable.encode_packedfile_string_u8("hu1_horse_spear");
+    animtable.encode_bool(true);
+
+    let mut data = vec![];
+    data.encode_integer_i32(1);
+    data.encode_packedfile_string_u8(&PATH.join("/"));
+    data.encode_integer_i32(animtable.len() as i32);
+    data.extend_from_slice(&animtable);
+    (data, schema)
+}
+
+#[test]
+fn test_anim_pack_operations() {
+    let data = build_anim_pack();
+    let mut anim_pack = AnimPack::read(&data).unwrap();
+    assert_eq!(anim_pack.save(), data);
+    assert!(AnimPack::read(&data[..data.len() - 1]).is_err());
+
+    assert!(anim_pack.packedfile_exists(&path("animations/animation_tables/notes.txt")));
+    assert!(anim_pack.folder_exists(&path("animations/animation_tables")));
+    assert!(!anim_pack.folder_exists(&path("animations/animation_tables/notes.txt")));
+    assert_eq!(anim_pack.get_packed_files_by_type(PackedFileType::Text(TextType::Plain), false).len(), 2);
+    assert_eq!(anim_pack.get_packed_files_by_path_type(&[PathType::Folder(path("animations/animation_tables"))]).len(), 2);
+
+    #[allow(deprecated)]
+    let packed_files = anim_pack.get_anim_packed_as_packed_files(&[PathType::Folder(path("animations/animation_tables"))]);
+    assert_eq!(packed_files.len(), 2);
+
+    // Moving to an existing path without overwriting must keep both files.
+    let moved = anim_pack.move_packedfile(&path("animations/animation_tables/notes.txt"), &path("animations/animation_tables/readme.txt"), false).unwrap();
+    assert_eq!(moved, path("animations/animation_tables/readme_0.txt"));
+    assert!(anim_pack.move_packedfile(&path("animations/missing.txt"), &path("animations/missing_2.txt"), false).is_err());
+
+    // Renaming folders must move all their files.
+    let renamed = anim_pack.rename_packedfiles(&[(PathType::Folder(path("animations/animation_tables")), "tables".to_owned())], false);
+    assert_eq!(renamed.len(), 2);
+    assert!(anim_pack.packedfile_exists(&path("animations/tables/readme_0.txt")));
+    assert!(!anim_pack.folder_exists(&path("animations/animation_tables")));
+
+    // Overwriting must remove the file in the destination.
+    anim_pack.move_packedfile(&path("animations/tables/readme_0.txt"), &path("animations/tables/readme.txt"), true).unwrap();
+    assert_eq!(anim_pack.get_file_list().len(), 2);
+    assert_eq!(anim_pack.get_ref_anim_packed_by_path(&path("animations/tables/readme.txt")).unwrap().get_ref_data(), b"General Kenobi.");
+
+    // Extracted files must keep their paths inside the AnimPack.
+    let destination_path = std::env::temp_dir().join("rpfm_test_anim_pack_extract");
+    assert_eq!(anim_pack.extract_packed_files_by_type(&[PathType::PackFile], &destination_path).unwrap(), 2);
+    assert_eq!(read(destination_path.join("animations/battle/humanoid01/unknown.xyz")).unwrap(), b"1234");
+    assert!(anim_pack.extract_packed_file_by_path(&path("animations/missing.txt"), &destination_path).is_err());
+    remove_dir_all(&destination_path).unwrap();
+}
+
+#[test]
+fn test_anim_pack_decoding() {
+    let mut anim_pack = AnimPack::read(&build_anim_pack()).unwrap();
+
+    let anim_packed = anim_pack.get_ref_mut_anim_packed_by_path(&path("animations/animation_tables/readme.txt")).unwrap();
+    let mut decoded = anim_packed.decode_no_locks(&Schema::default()).unwrap();
+    match decoded {
+        DecodedPackedFile::Text(ref mut text) => {
+            assert_eq!(text.get_ref_contents(), "Hello there!");
+            text.set_contents("You are a bold one.");
+        }
+        _ => panic!("The AnimPacked has not been decoded as Text."),
+    }
+
+    // Edits must be saved back into the AnimPack.
+    anim_packed.set_decoded(&decoded).unwrap();
+    let anim_pack = AnimPack::read(&anim_pack.save()).unwrap();
+    assert_eq!(anim_pack.get_ref_anim_packed_by_path(&path("animations/animation_tables/readme.txt")).unwrap().get_ref_data(), b"You are a bold one.");
+
+    // Unknown files are not decoded, and cannot be saved back.
+    let mut anim_packed = anim_pack.get_ref_anim_packed_by_path(&path("animations/battle/humanoid01/unknown.xyz")).unwrap().clone();
+    assert_eq!(anim_packed.get_packed_file_type(), PackedFileType::Unknown);
+    let decoded = anim_packed.decode_no_locks(&Schema::default()).unwrap();
+    assert_eq!(decoded, DecodedPackedFile::Unknown);
+    assert!(anim_packed.set_decoded(&decoded).is_err());
+}
+
+#[test]
+fn test_anim_pack_decoding_animtable() {
+    let (data, schema) = build_anim_pack_with_animtable();
+    let mut anim_pack = AnimPack::read(&data).unwrap();
+    let path = PATH.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+
+    let anim_packed = anim_pack.get_ref_mut_anim_packed_by_path(&path).unwrap();
+    assert_eq!(anim_packed.get_packed_file_type(), PackedFileType::AnimTable);
+    let mut decoded = anim_packed.decode_no_locks(&schema).unwrap();
+    match decoded {
+        DecodedPackedFile::AnimTable(ref mut animtable) => {
+            let mut table_data = animtable.get_table_data();
+            assert_eq!(table_data[0][1], DecodedData::StringU8("hu1_horse_spear".to_owned()));
+            table_data[0][2] = DecodedData::Boolean(false);
+            animtable.set_table_data(&table_data).unwrap();
+        }
+        _ => panic!("The AnimPacked has not been decoded as an AnimTable."),
+    }
+
+    // Edits must be saved back into the AnimPack, and decoded again the same way.
+    anim_packed.set_decoded(&decoded).unwrap();
+    let anim_pack = AnimPack::read(&anim_pack.save()).unwrap();
+    match anim_pack.get_ref_anim_packed_by_path(&path).unwrap().decode_no_locks(&schema).unwrap() {
+        DecodedPackedFile::AnimTable(animtable) => assert_eq!(animtable.get_ref_table_data()[0][2], DecodedData::Boolean(false)),
+        _ => panic!("The AnimPacked has not been decoded as an AnimTable."),
+    }
+
+    // Without a definition for the AnimTable, it cannot be decoded.
+    assert!(anim_pack.get_ref_anim_packed_by_path(&path).unwrap().decode_no_locks(&Schema::default()).is_err());
+}
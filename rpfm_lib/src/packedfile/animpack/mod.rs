@@ -19,18 +19,27 @@ AnimPack's structure is very simple:
 - List of files:
     - File Path.
     - Byte Count.
+
+As it's a container, it supports most of the operations a `PackFile` supports over its files, and its files
+can be decoded like any other `PackedFile`.
 !*/
 
 use rayon::prelude::*;
 use serde_derive::{Serialize, Deserialize};
 
 use std::convert::TryFrom;
+use std::path::Path;
 
-use rpfm_error::{Error, Result};
+use rpfm_error::{Error, ErrorKind, Result};
 
 use crate::common::{decoder::Decoder, encoder::Encoder};
+use crate::packedfile::{DecodedPackedFile, PackedFileType};
 use crate::packfile::{PackFileInfo, PathType};
 use crate::packfile::packedfile::{PackedFile, PackedFileInfo};
+use crate::schema::Schema;
+
+#[cfg(test)]
+mod animpack_test;
 
 pub const EXTENSION: &str = ".animpack";
 
@@ -72,7 +81,7 @@ impl AnimPack {
         for _ in 0..file_count {
             let path = packed_file_data.decode_packedfile_string_u8(index, &mut index)?.split('/').map(|x| x.to_owned()).collect::<Vec<String>>();
             let byte_count = packed_file_data.decode_packedfile_integer_i32(index, &mut index)?;
-            let data = packed_file_data[index..index + byte_count as usize].to_vec();
+            let data = packed_file_data.get_bytes_checked(index, byte_count as usize)?.to_vec();
             index += byte_count as usize;
 
             anim_packeds.push(AnimPacked {
@@ -143,11 +152,54 @@ impl AnimPack {
         (pack_file_info, packed_file_info)
     }
 
-    pub fn get_anim_packed_as_packed_files(&self, path_types: &[PathType]) -> Vec<PackedFile> {
+    /// This function returns a copy of all the `PackedFiles` in the provided `PathTypes`.
+    pub fn get_packed_files_by_path_type(&self, path_types: &[PathType]) -> Vec<PackedFile> {
         let paths = self.get_file_paths_from_path_types(path_types);
         self.get_packed_files_by_paths(paths.iter().map(|x| &**x).collect())
     }
 
+    /// This function returns a copy of all the `PackedFiles` in the provided `PathTypes`.
+    #[deprecated(note = "use `get_packed_files_by_path_type` instead")]
+    pub fn get_anim_packed_as_packed_files(&self, path_types: &[PathType]) -> Vec<PackedFile> {
+        self.get_packed_files_by_path_type(path_types)
+    }
+
+    /// This function checks if a `PackedFile` with a certain path exists in the `AnimPack`.
+    pub fn packedfile_exists(&self, path: &[String]) -> bool {
+        self.packed_files.iter().any(|x| x.get_ref_path() == path)
+    }
+
+    /// This function checks if a folder with `PackedFiles` in it exists in the `AnimPack`.
+    pub fn folder_exists(&self, path: &[String]) -> bool {
+        !path.is_empty() && self.packed_files.iter().any(|x| x.get_ref_path().starts_with(path) && x.get_ref_path().len() > path.len())
+    }
+
+    /// This function returns a reference to the `AnimPacked` with the provided path, if exists.
+    pub fn get_ref_anim_packed_by_path(&self, path: &[String]) -> Option<&AnimPacked> {
+        self.packed_files.iter().find(|x| x.get_ref_path() == path)
+    }
+
+    /// This function returns a mutable reference to the `AnimPacked` with the provided path, if exists.
+    pub fn get_ref_mut_anim_packed_by_path(&mut self, path: &[String]) -> Option<&mut AnimPacked> {
+        self.packed_files.iter_mut().find(|x| x.get_ref_path() == path)
+    }
+
+    /// This function returns a copy of the `PackedFile` with the provided path, if exists.
+    pub fn get_packed_file_by_path(&self, path: &[String]) -> Option<PackedFile> {
+        self.get_ref_anim_packed_by_path(path).map(From::from)
+    }
+
+    /// This function returns a copy of all the `PackedFiles` in the `AnimPack` of the provided type.
+    ///
+    /// If `strict_match_mode` is enabled, only the PackedFiles of the specified type and subtype will be returned.
+    pub fn get_packed_files_by_type(&self, packed_file_type: PackedFileType, strict_match_mode: bool) -> Vec<PackedFile> {
+        self.packed_files.par_iter()
+            .filter(|x| {
+                let y = x.get_packed_file_type();
+                if strict_match_mode { y == packed_file_type } else { y.eq_non_strict(packed_file_type) }
+            }).map(From::from).collect()
+    }
+
     /// This function returns a copy of all `PackedFiles` in the provided `PackFile`.
     pub fn get_packed_files_all(&self) -> Vec<PackedFile> {
         self.packed_files.iter().map(From::from).collect()
@@ -168,6 +220,13 @@ impl AnimPack {
         self.packed_files.par_iter().map(|x| x.get_ref_path()).filter(|x| x.starts_with(path) && !path.is_empty() && x.len() > path.len()).collect()
     }
 
+    /// This function removes, if exists, a `PackedFile` with the provided path from the `AnimPack`.
+    pub fn remove_packed_file_by_path(&mut self, path: &[String]) {
+        if let Some(position) = self.packed_files.iter().position(|x| x.get_ref_path() == path) {
+            self.packed_files.remove(position);
+        }
+    }
+
     /// This function removes, if exists, a `PackedFile` with the provided path from the `PackFile`.
     pub fn remove_packed_file_by_path_types(&mut self, path_types: &[PathType]) {
         let paths = self.get_file_paths_from_path_types(path_types).iter().map(|x| x.to_vec()).collect::<Vec<Vec<String>>>();
@@ -223,6 +282,159 @@ impl AnimPack {
             paths_files
         }
     }
+
+    /// This function allows you to change the path of a `PackedFile` inside an `AnimPack`.
+    ///
+    /// By default this append a `_number` to the file name in case of collision. If you want it to overwrite instead,
+    /// pass `overwrite` as `true`. This can fail if you pass it an empty path, so make sure you check the result.
+    ///
+    /// We return the final destination path of the PackedFile, if it worked, or an error.
+    pub fn move_packedfile(
+        &mut self,
+        source_path: &[String],
+        destination_path: &[String],
+        overwrite: bool,
+    ) -> Result<Vec<String>> {
+
+        // First, ensure we can move between the paths.
+        if destination_path.is_empty() { return Err(ErrorKind::EmptyInput.into()) }
+        if source_path == destination_path { return Err(ErrorKind::PathsAreEqual.into()) }
+        if !self.packedfile_exists(source_path) { return Err(ErrorKind::PackedFileNotFound.into()) }
+
+        // If the destination exists, we either remove it, or find a free path using "_X", taking into account extensions.
+        let mut destination_path = destination_path.to_vec();
+        if self.packedfile_exists(&destination_path) {
+            if overwrite { self.remove_packed_file_by_path(&destination_path); }
+            else {
+                let name_current = destination_path.last().unwrap().to_owned();
+                let name_splitted = name_current.split('.').collect::<Vec<&str>>();
+                let name = name_splitted[0];
+                let extension = if name_splitted.len() > 1 { name_splitted[1..].join(".") } else { "".to_owned() };
+                for number in 0.. {
+                    let name = if extension.is_empty() { format!("{}_{}", name, number) } else { format!("{}_{}.{}", name, number, extension) };
+                    *destination_path.last_mut().unwrap() = name;
+                    if !self.packedfile_exists(&destination_path) {
+                        break;
+                    }
+                }
+            }
+        }
+
+        // The source is checked before, so this cannot fail.
+        if let Some(anim_packed) = self.get_ref_mut_anim_packed_by_path(source_path) {
+            anim_packed.path = destination_path.to_vec();
+        }
+        Ok(destination_path)
+    }
+
+    /// This function allows you to change the name of a folder inside an `AnimPack`.
+    ///
+    /// By default this append a `_number` to the file names in case of collision. If you want it to overwrite instead,
+    /// pass `overwrite` as `true`.
+    ///
+    /// We return the list of source/final paths of each moved PackedFile, if it worked, or an error.
+    pub fn move_folder(
+        &mut self,
+        source_path: &[String],
+        destination_path: &[String],
+        overwrite: bool,
+    ) -> Result<Vec<(Vec<String>, Vec<String>)>> {
+
+        // First, ensure we can move between the paths.
+        if source_path.is_empty() || destination_path.is_empty() { return Err(ErrorKind::EmptyInput.into()) }
+        if source_path == destination_path { return Err(ErrorKind::PathsAreEqual.into()) }
+
+        // Next... just get all the PackedFiles to move, and move them one by one.
+        let mut successes = vec![];
+        for packed_file_current_path in self.get_ref_packed_files_paths_by_path_start(source_path).iter().map(|x| x.to_vec()).collect::<Vec<Vec<String>>>() {
+            let mut new_path = packed_file_current_path.to_vec();
+            new_path.splice(..source_path.len(), destination_path.iter().cloned());
+            if let Ok(new_path) = self.move_packedfile(&packed_file_current_path, &new_path, overwrite) {
+                successes.push((packed_file_current_path, new_path))
+            }
+        }
+
+        Ok(successes)
+    }
+
+    /// This function is used to rename one or more `PackedFile`/Folder inside an `AnimPack`.
+    ///
+    /// It returns the list of "Original Path/New Path" of each renamed PackedFile.
+    ///
+    /// This doesn't stop on failure. Instead, if a rename fails, it skips that PackedFile from the rename process.
+    /// Collisions are handled like in `PackFile::rename_packedfiles`.
+    pub fn rename_packedfiles(
+        &mut self,
+        renaming_data: &[(PathType, String)],
+        overwrite: bool
+    ) -> Vec<(PathType, Vec<String>)> {
+
+        let mut successes = vec![];
+        for (item_type, new_name) in renaming_data {
+
+            // Skip items with empty new names.
+            if new_name.is_empty() { continue; }
+
+            // We only allow to rename files and folders.
+            match item_type {
+                PathType::File(ref path) => {
+                    let mut new_path = path.to_vec();
+                    *new_path.last_mut().unwrap() = new_name.to_owned();
+                    if let Ok(destination_path) = self.move_packedfile(path, &new_path, overwrite) {
+                        successes.push((item_type.clone(), destination_path));
+                    }
+                }
+
+                PathType::Folder(ref path) => {
+                    let mut new_path = path.to_vec();
+                    *new_path.last_mut().unwrap() = new_name.to_owned();
+                    if let Ok(result) = self.move_folder(path, &new_path, overwrite) {
+                        result.iter().map(|(x, y)| (PathType::File(x.to_vec()), y.to_vec())).for_each(|x| successes.push(x));
+                    }
+                }
+
+                // AnimPacks and errors are skipped.
+                PathType::PackFile | PathType::None => continue,
+            }
+        }
+
+        successes
+    }
+
+    /// This function extracts, if exists, a `PackedFile` with the provided path from the `AnimPack`.
+    ///
+    /// The destination path is always `destination_path/path_to_packedfile/packed_file`.
+    pub fn extract_packed_file_by_path(&self, path: &[String], destination_path: &Path) -> Result<()> {
+        match self.get_packed_file_by_path(path) {
+            Some(mut packed_file) => packed_file.extract_packed_file(destination_path),
+            None => Err(ErrorKind::PackedFileNotFound.into())
+        }
+    }
+
+    /// This function extracts all the `PackedFiles` in the provided `PathTypes` from the `AnimPack` to disk.
+    ///
+    /// As this can fail for some files, and work for others, we return `Ok(amount_files_extracted)` only if all files were extracted correctly.
+    /// If any of them failed, we return `Error` with a list of the paths that failed to get extracted.
+    pub fn extract_packed_files_by_type(&self, path_types: &[PathType], destination_path: &Path) -> Result<u32> {
+        let paths = self.get_file_paths_from_path_types(path_types);
+        if paths.is_empty() { return Err(ErrorKind::NonExistantFile.into()) }
+
+        let mut files_extracted = 0;
+        let mut error_files = vec![];
+        for path in &paths {
+            match self.extract_packed_file_by_path(path, destination_path) {
+                Ok(_) => files_extracted += 1,
+                Err(_) => error_files.push(format!("<li>{:?}</li>", path)),
+            }
+        }
+
+        // If there is any error in the list, report it.
+        if !error_files.is_empty() {
+            return Err(ErrorKind::ExtractError(error_files).into())
+        }
+
+        Ok(files_extracted)
+    }
 }
 
 /// Implementation of AnimPacked.
@@ -234,6 +446,37 @@ impl AnimPacked {
     pub fn get_ref_path(&self) -> &[String] {
         &self.path
     }
+
+    /// This function returns the type of the `AnimPacked`, according to its path.
+    pub fn get_packed_file_type(&self) -> PackedFileType {
+        PackedFileType::get_packed_file_type(&self.path)
+    }
+
+    /// This function decodes the `AnimPacked` into a `DecodedPackedFile`, like any other `PackedFile`.
+    pub fn decode(&self) -> Result<DecodedPackedFile> {
+        PackedFile::from(self).decode_return_clean_cache()
+    }
+
+    /// This function decodes the `AnimPacked` into a `DecodedPackedFile`, like any other `PackedFile`.
+    ///
+    /// This variant doesn't lock the Schema. This means is faster if you're decoding `AnimPackeds` in batches.
+    pub fn decode_no_locks(&self, schema: &Schema) -> Result<DecodedPackedFile> {
+        let mut packed_file = PackedFile::from(self);
+        DecodedPackedFile::decode_no_locks(packed_file.get_ref_mut_raw(), schema)
+    }
+
+    /// This function replaces the data of the `AnimPacked` with the provided `DecodedPackedFile`, encoded.
+    ///
+    /// This fails if the `DecodedPackedFile` doesn't support being saved.
+    pub fn set_decoded(&mut self, decoded: &DecodedPackedFile) -> Result<()> {
+        match decoded.encode() {
+            Some(data) => {
+                self.data = data?;
+                Ok(())
+            }
+            None => Err(ErrorKind::AnimPackEncode(format!("{} PackedFiles cannot be saved.", PackedFileType::from(decoded))).into())
+        }
+    }
 }
 
 /// Implementation to create an `AnimPacked` from a `PackedFile`.
@@ -0,0 +1,222 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `BMD` module, to make sure the sections of BMD files are decoded, edited and saved correctly.
+!*/
+
+use crate::packfile::{PackFile, PFHVersion};
+use crate::packfile::packedfile::PackedFile;
+
+use super::*;
+
+/// This function encodes a section with the provided version and data.
+fn encode_section(data: &mut Vec<u8>, version: u16, section_data: &[u8]) {
+    data.encode_integer_u16(version);
+    data.encode_integer_u32(section_data.len() as u32);
+    data.extend_from_slice(section_data);
+}
+
+/// This function encodes a list of 2D points.
+fn encode_points(data: &mut Vec<u8>, points: &[[f32; 2]]) {
+    data.encode_integer_u32(points.len() as u32);
+    points.iter().flatten().for_each(|value| data.encode_float_f32(*value));
+}
+
+/// This function builds a raw BMD with a prefab instance, siege AI, two defensive hills and a deployment zone, with unknown data in the rest of the sections.
+fn build_bmd() -> Vec<u8> {
+    let mut prefab_instances = vec![];
+    prefab_instances.encode_integer_u32(1);
+    prefab_instances.encode_integer_u16(PREFAB_INSTANCE_VERSION);
+    prefab_instances.encode_packedfile_string_u8("prefabs/wh_main/siege/gate_01.bmd");
+    (0..16).for_each(|value| prefab_instances.encode_float_f32(value as f32));
+    prefab_instances.encode_integer_u32(1);
+    prefab_instances.encode_packedfile_string_u8("health");
+    prefab_instances.encode_packedfile_string_u8("0.5");
+    prefab_instances.encode_integer_u64(3);
+    prefab_instances.encode_packedfile_string_u8("");
+    prefab_instances.encode_bool(true);
+    prefab_instances.encode_packedfile_string_u8("relative");
+
+    let mut ai_hints = vec![];
+    ai_hints.encode_integer_u32(1);
+    ai_hints.encode_integer_u16(AI_HINT_VERSION);
+    ai_hints.encode_packedfile_string_u8(SIEGE_AREA_NODE_HINT);
+    [10.0, 20.0, 0.0, 1.0].iter().for_each(|value| ai_hints.encode_float_f32(*value));
+    ai_hints.encode_integer_u32(2);
+    for points in &[vec![[0.0, 0.0], [5.0, 0.0], [5.0, 5.0]], vec![[-1.0, -1.0], [-4.5, 2.0]]] {
+        ai_hints.encode_integer_u16(AI_HINT_VERSION);
+        ai_hints.encode_packedfile_string_u8(DEFENSIVE_HILL_HINT);
+        encode_points(&mut ai_hints, points);
+    }
+    ai_hints.encode_integer_u32(1);
+    ai_hints.encode_integer_u16(AI_HINT_VERSION);
+    ai_hints.encode_packedfile_string_u8("AIH_FOREST");
+    ai_hints.encode_integer_u32(2);
+    encode_points(&mut ai_hints, &[[1.0, 1.0]]);
+    encode_points(&mut ai_hints, &[]);
+
+    let mut deployment_areas = vec![];
+    deployment_areas.encode_integer_u32(1);
+    deployment_areas.encode_integer_u16(DEPLOYMENT_AREA_VERSION);
+    deployment_areas.encode_packedfile_string_u8("attacker");
+    deployment_areas.encode_integer_u32(1);
+    deployment_areas.encode_integer_u16(DEPLOYMENT_ZONE_VERSION);
+    deployment_areas.encode_packedfile_string_u8("attacker_deployment_zone_01");
+    encode_points(&mut deployment_areas, &[[0.0, 0.0], [100.0, 0.0], [100.0, 50.0]]);
+    deployment_areas.encode_float_f32(90.0);
+
+    let mut data = FASTBIN0_SIGNATURE.to_vec();
+    data.encode_integer_u16(27);
+    for (index, section_type) in SECTIONS_V27.iter().enumerate() {
+        match section_type {
+            SectionType::PrefabInstanceList => encode_section(&mut data, PREFAB_INSTANCE_LIST_VERSION, &prefab_instances),
+            SectionType::AIHints => encode_section(&mut data, AI_HINTS_VERSION, &ai_hints),
+            SectionType::DeploymentList => encode_section(&mut data, DEPLOYMENT_LIST_VERSION, &deployment_areas),
+
+            // Unknown sections, some of them with data that looks like a hint, which must be left alone.
+            SectionType::PropList => encode_section(&mut data, 4, b"\x12\x00AIH_DEFENSIVE_HILL"),
+            _ => encode_section(&mut data, index as u16, &vec![index as u8; index % 3]),
+        }
+    }
+    data
+}
+
+#[test]
+fn test_bmd() {
+    let data = build_bmd();
+    let mut bmd = BMD::read(&data).unwrap();
+    assert_eq!(*bmd.get_ref_version(), 27);
+    assert_eq!(bmd.get_ref_sections().len(), SECTIONS_V27.len());
+    assert_eq!(bmd.get_ai_hints(), [SIEGE_AREA_NODE_HINT, DEFENSIVE_HILL_HINT, DEFENSIVE_HILL_HINT, "AIH_FOREST"]);
+    assert_eq!(bmd.get_ref_ai_hints().unwrap().get_ref_polylines()[1].get_ref_points(), &[[-1.0, -1.0], [-4.5, 2.0]]);
+    assert_eq!(bmd.get_ref_ai_hints().unwrap().get_ref_polyline_lists()[0].get_ref_polylines().len(), 2);
+    assert_eq!(bmd.get_deployment_zones(), ["attacker_deployment_zone_01"]);
+    assert_eq!(*bmd.get_deployment_areas()[0].get_ref_zones()[0].get_ref_orientation(), 90.0);
+
+    let prefab_instance = &bmd.get_prefab_instances()[0];
+    assert_eq!(prefab_instance.get_ref_key(), "prefabs/wh_main/siege/gate_01.bmd");
+    assert_eq!(prefab_instance.get_ref_transform()[15], 15.0);
+    assert_eq!(prefab_instance.get_ref_property_overrides(), &[("health".to_owned(), "0.5".to_owned())]);
+    assert_eq!(prefab_instance.get_ref_height_mode(), "relative");
+
+    // Unedited files must be saved exactly as they were.
+    assert_eq!(bmd.save().unwrap(), data);
+
+    // Edits must survive a save and a read, even if they change the size of the sections.
+    assert_eq!(bmd.replace_ai_hints(DEFENSIVE_HILL_HINT, FORT_PERIMETER_HINT, 1), 1);
+    assert_eq!(bmd.get_ai_hints(), [SIEGE_AREA_NODE_HINT, FORT_PERIMETER_HINT, DEFENSIVE_HILL_HINT, "AIH_FOREST"]);
+    bmd.get_ref_mut_ai_hints().unwrap().get_ref_mut_polyline_lists()[0].set_hint_type("AIH_SIEGE_AREA_BORDER_LONG_NAME".to_owned());
+    let edited = BMD::read(&bmd.save().unwrap()).unwrap();
+    assert_eq!(edited, bmd);
+    assert_eq!(edited.get_ref_section_data(SectionType::PropList), Some(&SectionData::Raw(b"\x12\x00AIH_DEFENSIVE_HILL".to_vec())));
+
+    // Sections must match the version of the file.
+    bmd.get_ref_mut_sections().swap(0, 1);
+    assert!(bmd.save().is_err());
+}
+
+#[test]
+fn test_bmd_broken() {
+    let data = build_bmd();
+    assert!(BMD::read(&data[..data.len() - 1]).is_err());
+    assert!(BMD::read(&[data.to_vec(), vec![0]].concat()).is_err());
+    assert!(BMD::read(b"FASTBIN0").is_err());
+    assert!(BMD::read(&[1, 2, 3]).is_err());
+
+    // Unknown versions of the file, or of the sections we decode, must fail instead of being guessed.
+    let mut broken = data.clone();
+    broken[8] = 26;
+    assert!(BMD::read(&broken).is_err());
+
+    let prefab_instance_list_start = (0..7).fold(10, |start, _| start + 6 + broken.decode_integer_u32(start + 2).unwrap() as usize);
+    let mut broken = data.clone();
+    broken[prefab_instance_list_start] = 2;
+    assert!(BMD::read(&broken).is_err());
+    let mut broken = data.clone();
+    broken[prefab_instance_list_start + 10] = 6;
+    assert!(BMD::read(&broken).is_err());
+
+    // So must sections with more or less data than their size says.
+    let mut broken = data;
+    broken[prefab_instance_list_start + 2] += 1;
+    assert!(BMD::read(&broken).is_err());
+}
+
+#[test]
+fn test_bmd_patch_siege_ai() {
+    let mut map_path = PackFile::get_terry_map_path();
+    map_path.push("siege_map".to_owned());
+    let path = |name: &str| { let mut path = map_path.to_vec(); path.push(name.to_owned()); path };
+
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    let mut bmd_data = PackedFile::new(path(DEFAULT_BMD_DATA), "test.pack".to_owned());
+    bmd_data.get_ref_mut_raw().set_data(&build_bmd());
+    let mut catchment = PackedFile::new(path("catchment_01.bin"), "test.pack".to_owned());
+    let mut catchment_bmd = BMD::read(&build_bmd()).unwrap();
+    *catchment_bmd.get_ref_mut_ai_hints().unwrap() = AIHints::default();
+    catchment.get_ref_mut_raw().set_data(&catchment_bmd.save().unwrap());
+    let xml = PackedFile::new(path("map.xml"), "test.pack".to_owned());
+    pack_file.add_packed_files(&[&bmd_data, &catchment, &xml], false).unwrap();
+
+    let reports = pack_file.get_map_reports().unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(*reports[0].get_ref_path(), map_path);
+    assert_eq!(reports[0].get_ref_files().len(), 2);
+    assert_eq!(reports[0].get_ref_ai_hints()[DEFENSIVE_HILL_HINT], 2);
+    assert_eq!(reports[0].get_ref_deployment_zones(), &["attacker_deployment_zone_01"]);
+    assert_eq!(reports[0].get_ref_prefab_instances()["prefabs/wh_main/siege/gate_01.bmd"], 2);
+
+    // Only the first defensive hill is patched, and the xml files are removed.
+    let (message, deleted) = pack_file.patch_siege_ai().unwrap();
+    assert!(message.starts_with("1 files patched.\n1 files deleted."));
+    assert!(message.contains("WARNING"));
+    assert_eq!(deleted, [path("map.xml")]);
+
+    let reports = pack_file.get_map_reports().unwrap();
+    assert_eq!(reports[0].get_ref_ai_hints()[DEFENSIVE_HILL_HINT], 1);
+    assert_eq!(reports[0].get_ref_ai_hints()[FORT_PERIMETER_HINT], 1);
+}
+
+#[test]
+fn test_bmd_patch_siege_ai_undecodable() {
+    let mut map_path = PackFile::get_terry_map_path();
+    map_path.push("siege_map".to_owned());
+    let path = |name: &str| { let mut path = map_path.to_vec(); path.push(name.to_owned()); path };
+
+    // A BMD with a version we cannot decode, but with the hints we need to patch.
+    let mut old_data = FASTBIN0_SIGNATURE.to_vec();
+    old_data.extend_from_slice(&26u16.to_le_bytes());
+    old_data.extend_from_slice(SIEGE_AREA_NODE_HINT.as_bytes());
+    old_data.extend_from_slice(DEFENSIVE_HILL_HINT.as_bytes());
+    old_data.extend_from_slice(&[0; 4]);
+    assert!(BMD::read(&old_data).is_err());
+
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    let mut bmd_data = PackedFile::new(path(DEFAULT_BMD_DATA), "test.pack".to_owned());
+    bmd_data.get_ref_mut_raw().set_data(&old_data);
+    let mut catchment = PackedFile::new(path("catchment_01.bin"), "test.pack".to_owned());
+    catchment.get_ref_mut_raw().set_data(&[0xFF; 8]);
+    pack_file.add_packed_files(&[&bmd_data, &catchment], false).unwrap();
+
+    // The undecodable file with hints gets its bytes patched, and the broken one is skipped.
+    let (message, deleted) = pack_file.patch_siege_ai().unwrap();
+    assert_eq!(message, "1 files patched.\nNo file suitable for deleting has been found.");
+    assert!(deleted.is_empty());
+
+    let mut expected = old_data[..10].to_vec();
+    expected.extend_from_slice(SIEGE_AREA_NODE_HINT.as_bytes());
+    expected.extend_from_slice(FORT_PERIMETER_HINT.as_bytes());
+    expected.extend_from_slice(&[0; 4]);
+    let patched = pack_file.get_ref_mut_packed_file_by_path(&path(DEFAULT_BMD_DATA)).unwrap();
+    assert_eq!(patched.get_ref_mut_raw().get_data_and_keep_it().unwrap(), expected);
+    let skipped = pack_file.get_ref_mut_packed_file_by_path(&path("catchment_01.bin")).unwrap();
+    assert_eq!(skipped.get_ref_mut_raw().get_data_and_keep_it().unwrap(), vec![0xFF; 8]);
+}
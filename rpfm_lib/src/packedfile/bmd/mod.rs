@@ -0,0 +1,764 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to interact with BMD PackedFiles.
+
+BMD PackedFiles are the battle map data files Terry exports with each map: `bmd_data.bin` and its `catchment_*.bin` files.
+They're `FASTBIN0` files, with this structure:
+- The `FASTBIN0` signature, and the version of the file. The version determines which sections the file has, and in which order.
+- The sections, one after another. Each one starts with its own version and the size in bytes of its data.
+
+We decode the data of the sections the map reports and the SiegeAI patcher need:
+- Prefab instances: the key of each prefab placed in the map, with its transform and overrides.
+- AI hints: directed points, polylines and lists of polylines, each one with its type (like `AIH_DEFENSIVE_HILL`).
+- Deployment areas: the deployment zones of each area, with their boundaries.
+
+The data of the rest of the sections is kept as it is, so unedited files are saved exactly as they were read.
+Files, sections and entries with versions we don't know are not decoded, instead of guessing their layout.
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::BTreeMap;
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_macros::*;
+
+use crate::common::{decoder::Decoder, encoder::Encoder};
+
+#[cfg(test)]
+mod bmd_test;
+
+/// Name of the main BMD PackedFile of a map.
+pub const DEFAULT_BMD_DATA: &str = "bmd_data.bin";
+
+/// Start of the name of the catchment BMD PackedFiles of a map.
+const CATCHMENT_PREFIX: &str = "catchment_";
+
+/// Extension of the catchment BMD PackedFiles of a map.
+const CATCHMENT_EXTENSION: &str = ".bin";
+
+/// Magic number at the start of `FASTBIN0` files.
+const FASTBIN0_SIGNATURE: &[u8; 8] = b"FASTBIN0";
+
+/// Hint used by SiegeAI to mark the perimeter of a fort/city/castle.
+pub const FORT_PERIMETER_HINT: &str = "AIH_FORT_PERIMETER";
+
+/// Hint used to mark hills the AI should defend.
+pub const DEFENSIVE_HILL_HINT: &str = "AIH_DEFENSIVE_HILL";
+
+/// Hint used by SiegeAI to mark the areas the AI should fight for.
+pub const SIEGE_AREA_NODE_HINT: &str = "AIH_SIEGE_AREA_NODE";
+
+/// Sections of the version 27 of the format, in the order they're in the file.
+const SECTIONS_V27: [SectionType; 34] = [
+    SectionType::BattlefieldBuildingList,
+    SectionType::BattlefieldBuildingListFar,
+    SectionType::CaptureLocationSet,
+    SectionType::EFLineList,
+    SectionType::GoOutlines,
+    SectionType::NonTerrainOutlines,
+    SectionType::ZonesTemplateList,
+    SectionType::PrefabInstanceList,
+    SectionType::BmdOutlineList,
+    SectionType::TerrainOutlines,
+    SectionType::LiteBuildingOutlines,
+    SectionType::CameraZones,
+    SectionType::CivilianDeploymentList,
+    SectionType::CivilianShelterList,
+    SectionType::PropList,
+    SectionType::ParticleEmitterList,
+    SectionType::AIHints,
+    SectionType::LightProbeList,
+    SectionType::TerrainStencilTriangleList,
+    SectionType::PointLightList,
+    SectionType::BuildingProjectileEmitterList,
+    SectionType::PlayableArea,
+    SectionType::CustomMaterialMeshList,
+    SectionType::TerrainStencilBlendTriangleList,
+    SectionType::SpotLightList,
+    SectionType::SoundShapeList,
+    SectionType::CompositeSceneList,
+    SectionType::DeploymentList,
+    SectionType::BmdCatchmentAreaList,
+    SectionType::ToggleableBuildingsSlotList,
+    SectionType::TerrainDecalList,
+    SectionType::TreeListReferenceList,
+    SectionType::GrassListReferenceList,
+    SectionType::WaterOutlines,
+];
+
+/// Version of the sections with prefab instances we can decode.
+const PREFAB_INSTANCE_LIST_VERSION: u16 = 1;
+
+/// Version of the prefab instances we can decode.
+const PREFAB_INSTANCE_VERSION: u16 = 7;
+
+/// Version of the sections with AI hints we can decode.
+const AI_HINTS_VERSION: u16 = 15;
+
+/// Version of the AI hints we can decode. All kinds of hints share it.
+const AI_HINT_VERSION: u16 = 1;
+
+/// Version of the sections with deployment areas we can decode.
+const DEPLOYMENT_LIST_VERSION: u16 = 1;
+
+/// Version of the deployment areas we can decode.
+const DEPLOYMENT_AREA_VERSION: u16 = 1;
+
+/// Version of the deployment zones we can decode.
+const DEPLOYMENT_ZONE_VERSION: u16 = 2;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains a BMD PackedFile decoded in memory.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Serialize, Deserialize)]
+pub struct BMD {
+
+    /// Version of the `FASTBIN0` format.
+    version: u16,
+
+    /// Sections of the file, in the same order they're in the file.
+    sections: Vec<Section>,
+}
+
+/// This struct represents a section of a BMD PackedFile.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Serialize, Deserialize)]
+pub struct Section {
+
+    /// Type of the section, given by its position in the file.
+    section_type: SectionType,
+
+    /// Version of the section.
+    version: u16,
+
+    /// Data of the section.
+    data: SectionData,
+}
+
+/// This enum represents the types of the sections of a BMD PackedFile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SectionType {
+    BattlefieldBuildingList,
+    BattlefieldBuildingListFar,
+    CaptureLocationSet,
+    EFLineList,
+    GoOutlines,
+    NonTerrainOutlines,
+    ZonesTemplateList,
+    PrefabInstanceList,
+    BmdOutlineList,
+    TerrainOutlines,
+    LiteBuildingOutlines,
+    CameraZones,
+    CivilianDeploymentList,
+    CivilianShelterList,
+    PropList,
+    ParticleEmitterList,
+    AIHints,
+    LightProbeList,
+    TerrainStencilTriangleList,
+    PointLightList,
+    BuildingProjectileEmitterList,
+    PlayableArea,
+    CustomMaterialMeshList,
+    TerrainStencilBlendTriangleList,
+    SpotLightList,
+    SoundShapeList,
+    CompositeSceneList,
+    DeploymentList,
+    BmdCatchmentAreaList,
+    ToggleableBuildingsSlotList,
+    TerrainDecalList,
+    TreeListReferenceList,
+    GrassListReferenceList,
+    WaterOutlines,
+}
+
+/// This enum represents the data of a section of a BMD PackedFile.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SectionData {
+
+    /// Data of a section we don't decode.
+    Raw(Vec<u8>),
+
+    /// Data of the `PrefabInstanceList` section.
+    PrefabInstances(Vec<PrefabInstance>),
+
+    /// Data of the `AIHints` section.
+    AIHints(AIHints),
+
+    /// Data of the `DeploymentList` section.
+    DeploymentAreas(Vec<DeploymentArea>),
+}
+
+/// This struct represents a prefab placed in a map.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct PrefabInstance {
+
+    /// Key of the prefab, which is the path of its BMD file.
+    key: String,
+
+    /// Transform of the prefab in the map, as a 4x4 matrix.
+    transform: [f32; 16],
+
+    /// Properties of the prefab overriden by this instance, with their values.
+    property_overrides: Vec<(String, String)>,
+
+    /// Mask of the campaign types the prefab is used in.
+    campaign_type_mask: u64,
+
+    /// Region of the campaign the prefab is used in, if any.
+    campaign_region_key: String,
+
+    /// If the prefab must be placed on the surface of the terrain.
+    clamp_to_surface: bool,
+
+    /// How the height of the prefab is calculated.
+    height_mode: String,
+}
+
+/// This struct contains the AI hints of a map, grouped by their shape.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, GetRefMut, Serialize, Deserialize)]
+pub struct AIHints {
+
+    /// Hints placed on a point, facing a direction.
+    directed_points: Vec<DirectedPointHint>,
+
+    /// Hints covering an area delimited by a polyline.
+    polylines: Vec<PolylineHint>,
+
+    /// Hints covering several areas, each one delimited by a polyline.
+    polyline_lists: Vec<PolylineListHint>,
+}
+
+/// This struct represents an AI hint placed on a point, facing a direction.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct DirectedPointHint {
+
+    /// Type of the hint, like `AIH_SIEGE_AREA_NODE`.
+    hint_type: String,
+
+    /// Position of the hint.
+    position: [f32; 2],
+
+    /// Direction the hint faces.
+    direction: [f32; 2],
+}
+
+/// This struct represents an AI hint covering an area delimited by a polyline.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct PolylineHint {
+
+    /// Type of the hint, like `AIH_DEFENSIVE_HILL`.
+    hint_type: String,
+
+    /// Points of the polyline.
+    points: Vec<[f32; 2]>,
+}
+
+/// This struct represents an AI hint covering several areas, each one delimited by a polyline.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct PolylineListHint {
+
+    /// Type of the hint.
+    hint_type: String,
+
+    /// Points of each polyline.
+    polylines: Vec<Vec<[f32; 2]>>,
+}
+
+/// This struct represents a deployment area of a map, with all its deployment zones.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct DeploymentArea {
+
+    /// Category of the area, like `attacker` or `defender`.
+    category: String,
+
+    /// Deployment zones of the area.
+    zones: Vec<DeploymentZone>,
+}
+
+/// This struct represents a deployment zone of a map.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct DeploymentZone {
+
+    /// Name of the zone.
+    name: String,
+
+    /// Points of the boundary of the zone.
+    boundary: Vec<[f32; 2]>,
+
+    /// Orientation of the armies deployed in the zone, in degrees.
+    orientation: f32,
+}
+
+/// This struct contains a summary of what a map contains, built from all its BMD PackedFiles.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, Serialize, Deserialize)]
+pub struct MapReport {
+
+    /// Path of the folder of the map.
+    path: Vec<String>,
+
+    /// Paths of the BMD PackedFiles of the map.
+    files: Vec<Vec<String>>,
+
+    /// Types of AI hints in the map, with the amount of hints of each type.
+    ai_hints: BTreeMap<String, u32>,
+
+    /// Names of the deployment zones in the map.
+    deployment_zones: Vec<String>,
+
+    /// Prefabs instanced in the map, with the amount of instances of each one.
+    prefab_instances: BTreeMap<String, u32>,
+}
+
+//---------------------------------------------------------------------------//
+//                           Implementation of BMD
+//---------------------------------------------------------------------------//
+
+/// Implementation of `BMD`.
+impl BMD {
+
+    /// This function returns if the provided name is the name of a BMD PackedFile.
+    pub fn is_bmd(name: &str) -> bool {
+        name == DEFAULT_BMD_DATA || (name.starts_with(CATCHMENT_PREFIX) && name.ends_with(CATCHMENT_EXTENSION))
+    }
+
+    /// This function creates a `BMD` from a `&[u8]`.
+    pub fn read(packed_file_data: &[u8]) -> Result<Self> {
+        if !packed_file_data.starts_with(FASTBIN0_SIGNATURE) {
+            return Err(ErrorKind::BMDDecode("This is not a FASTBIN0 file.".to_owned()).into());
+        }
+
+        let mut index = FASTBIN0_SIGNATURE.len();
+        let version = packed_file_data.decode_packedfile_integer_u16(index, &mut index)?;
+        let section_types = get_section_types(version).ok_or_else(|| ErrorKind::BMDDecode(format!("Unsupported FASTBIN0 version: {}.", version)))?;
+
+        let mut sections = vec![];
+        for section_type in section_types {
+            sections.push(Section::read(packed_file_data, &mut index, *section_type)?);
+        }
+
+        if index != packed_file_data.len() {
+            return Err(ErrorKind::BMDDecode(format!("There are {} bytes of unknown data after the last section.", packed_file_data.len() - index)).into());
+        }
+
+        Ok(Self {
+            version,
+            sections,
+        })
+    }
+
+    /// This function returns a reference to the data of the section of the provided type.
+    pub fn get_ref_section_data(&self, section_type: SectionType) -> Option<&SectionData> {
+        self.sections.iter().find(|section| section.section_type == section_type).map(|section| &section.data)
+    }
+
+    /// This function returns a mutable reference to the data of the section of the provided type.
+    pub fn get_ref_mut_section_data(&mut self, section_type: SectionType) -> Option<&mut SectionData> {
+        self.sections.iter_mut().find(|section| section.section_type == section_type).map(|section| &mut section.data)
+    }
+
+    /// This function returns all the prefab instances of the file, in order.
+    pub fn get_prefab_instances(&self) -> &[PrefabInstance] {
+        match self.get_ref_section_data(SectionType::PrefabInstanceList) {
+            Some(SectionData::PrefabInstances(prefab_instances)) => prefab_instances,
+            _ => &[],
+        }
+    }
+
+    /// This function returns the AI hints of the file, if it has them.
+    pub fn get_ref_ai_hints(&self) -> Option<&AIHints> {
+        match self.get_ref_section_data(SectionType::AIHints) {
+            Some(SectionData::AIHints(ai_hints)) => Some(ai_hints),
+            _ => None,
+        }
+    }
+
+    /// This function returns a mutable reference to the AI hints of the file, if it has them.
+    pub fn get_ref_mut_ai_hints(&mut self) -> Option<&mut AIHints> {
+        match self.get_ref_mut_section_data(SectionType::AIHints) {
+            Some(SectionData::AIHints(ai_hints)) => Some(ai_hints),
+            _ => None,
+        }
+    }
+
+    /// This function returns all the deployment areas of the file, in order.
+    pub fn get_deployment_areas(&self) -> &[DeploymentArea] {
+        match self.get_ref_section_data(SectionType::DeploymentList) {
+            Some(SectionData::DeploymentAreas(deployment_areas)) => deployment_areas,
+            _ => &[],
+        }
+    }
+
+    /// This function returns the types of all the AI hints of the file: first the directed points, then the polylines, then the polyline lists.
+    pub fn get_ai_hints(&self) -> Vec<&str> {
+        self.get_ref_ai_hints().map(|ai_hints| ai_hints.get_hint_types()).unwrap_or_default()
+    }
+
+    /// This function returns the names of all the deployment zones of the file, in order.
+    pub fn get_deployment_zones(&self) -> Vec<&str> {
+        self.get_deployment_areas().iter()
+            .flat_map(|area| area.zones.iter().map(|zone| &*zone.name))
+            .collect()
+    }
+
+    /// This function returns if the file contains an AI hint of the provided type.
+    pub fn has_ai_hint(&self, hint: &str) -> bool {
+        self.get_ai_hints().contains(&hint)
+    }
+
+    /// This function changes the type of up to `max_changes` AI hints of type `old_hint` to `new_hint`, in the order of `get_ai_hints`.
+    ///
+    /// It returns the amount of hints changed.
+    pub fn replace_ai_hints(&mut self, old_hint: &str, new_hint: &str, max_changes: usize) -> usize {
+        let mut changes = 0;
+        if let Some(ai_hints) = self.get_ref_mut_ai_hints() {
+            for hint_type in ai_hints.get_hint_types_mut() {
+                if changes == max_changes { break; }
+                if hint_type == old_hint {
+                    *hint_type = new_hint.to_owned();
+                    changes += 1;
+                }
+            }
+        }
+        changes
+    }
+
+    /// This function takes a `BMD` and encodes it to `Vec<u8>`.
+    ///
+    /// This fails if the sections don't match the ones of the version of the file.
+    pub fn save(&self) -> Result<Vec<u8>> {
+        let section_types = get_section_types(self.version).ok_or_else(|| ErrorKind::BMDEncode(format!("Unsupported FASTBIN0 version: {}.", self.version)))?;
+        if !self.sections.iter().map(|section| section.section_type).eq(section_types.iter().copied()) {
+            return Err(ErrorKind::BMDEncode(format!("The sections of the file don't match the ones of the version {}.", self.version)).into());
+        }
+
+        let mut packed_file = FASTBIN0_SIGNATURE.to_vec();
+        packed_file.encode_integer_u16(self.version);
+        for section in &self.sections {
+            section.save(&mut packed_file)?;
+        }
+
+        Ok(packed_file)
+    }
+}
+
+/// Implementation of `Section`.
+impl Section {
+
+    /// This function reads a section of the provided type, moving the index to the end of it.
+    fn read(packed_file_data: &[u8], index: &mut usize, section_type: SectionType) -> Result<Self> {
+        let version = packed_file_data.decode_packedfile_integer_u16(*index, index)?;
+        let size = packed_file_data.decode_packedfile_integer_u32(*index, index)? as usize;
+        let data = packed_file_data.get(*index..index.saturating_add(size))
+            .ok_or_else(|| ErrorKind::BMDDecode(format!("The section {:?} goes beyond the end of the file.", section_type)))?;
+        *index += size;
+
+        let mut data_index = 0;
+        let section_data = match section_type {
+            SectionType::PrefabInstanceList => {
+                check_version(&format!("{:?}", section_type), version, PREFAB_INSTANCE_LIST_VERSION)?;
+                SectionData::PrefabInstances(read_list(data, &mut data_index, PrefabInstance::read)?)
+            }
+            SectionType::AIHints => {
+                check_version(&format!("{:?}", section_type), version, AI_HINTS_VERSION)?;
+                SectionData::AIHints(AIHints::read(data, &mut data_index)?)
+            }
+            SectionType::DeploymentList => {
+                check_version(&format!("{:?}", section_type), version, DEPLOYMENT_LIST_VERSION)?;
+                SectionData::DeploymentAreas(read_list(data, &mut data_index, DeploymentArea::read)?)
+            }
+            _ => {
+                data_index = data.len();
+                SectionData::Raw(data.to_vec())
+            }
+        };
+
+        if data_index != data.len() {
+            return Err(ErrorKind::BMDDecode(format!("The section {:?} has {} bytes of unknown data at its end.", section_type, data.len() - data_index)).into());
+        }
+
+        Ok(Self {
+            section_type,
+            version,
+            data: section_data,
+        })
+    }
+
+    /// This function encodes the section to the provided data.
+    fn save(&self, packed_file: &mut Vec<u8>) -> Result<()> {
+        let mut data = vec![];
+        match &self.data {
+            SectionData::Raw(raw_data) => data.extend_from_slice(raw_data),
+            SectionData::PrefabInstances(prefab_instances) => write_list(&mut data, prefab_instances, |data, prefab_instance| prefab_instance.save(data)),
+            SectionData::AIHints(ai_hints) => ai_hints.save(&mut data),
+            SectionData::DeploymentAreas(deployment_areas) => write_list(&mut data, deployment_areas, |data, deployment_area| deployment_area.save(data)),
+        }
+
+        let typed_section_type = match self.data {
+            SectionData::Raw(_) => None,
+            SectionData::PrefabInstances(_) => Some(SectionType::PrefabInstanceList),
+            SectionData::AIHints(_) => Some(SectionType::AIHints),
+            SectionData::DeploymentAreas(_) => Some(SectionType::DeploymentList),
+        };
+
+        if matches!(typed_section_type, Some(section_type) if section_type != self.section_type) {
+            return Err(ErrorKind::BMDEncode(format!("The data of the section {:?} is of another section.", self.section_type)).into());
+        }
+
+        packed_file.encode_integer_u16(self.version);
+        packed_file.encode_integer_u32(data.len() as u32);
+        packed_file.extend_from_slice(&data);
+        Ok(())
+    }
+}
+
+/// Implementation of `PrefabInstance`.
+impl PrefabInstance {
+
+    /// This function reads a prefab instance, moving the index to the end of it.
+    fn read(data: &[u8], index: &mut usize) -> Result<Self> {
+        check_version("PrefabInstance", data.decode_packedfile_integer_u16(*index, index)?, PREFAB_INSTANCE_VERSION)?;
+        let key = data.decode_packedfile_string_u8(*index, index)?;
+        let mut transform = [0.0; 16];
+        for value in &mut transform {
+            *value = data.decode_packedfile_float_f32(*index, index)?;
+        }
+
+        let property_overrides = read_list(data, index, |data, index| Ok((
+            data.decode_packedfile_string_u8(*index, index)?,
+            data.decode_packedfile_string_u8(*index, index)?,
+        )))?;
+
+        Ok(Self {
+            key,
+            transform,
+            property_overrides,
+            campaign_type_mask: data.decode_packedfile_integer_u64(*index, index)?,
+            campaign_region_key: data.decode_packedfile_string_u8(*index, index)?,
+            clamp_to_surface: data.decode_packedfile_bool(*index, index)?,
+            height_mode: data.decode_packedfile_string_u8(*index, index)?,
+        })
+    }
+
+    /// This function encodes the prefab instance to the provided data.
+    fn save(&self, data: &mut Vec<u8>) {
+        data.encode_integer_u16(PREFAB_INSTANCE_VERSION);
+        data.encode_packedfile_string_u8(&self.key);
+        self.transform.iter().for_each(|value| data.encode_float_f32(*value));
+        write_list(data, &self.property_overrides, |data, (property, value)| {
+            data.encode_packedfile_string_u8(property);
+            data.encode_packedfile_string_u8(value);
+        });
+        data.encode_integer_u64(self.campaign_type_mask);
+        data.encode_packedfile_string_u8(&self.campaign_region_key);
+        data.encode_bool(self.clamp_to_surface);
+        data.encode_packedfile_string_u8(&self.height_mode);
+    }
+}
+
+/// Implementation of `AIHints`.
+impl AIHints {
+
+    /// This function returns the types of all the hints: first the directed points, then the polylines, then the polyline lists.
+    pub fn get_hint_types(&self) -> Vec<&str> {
+        self.directed_points.iter().map(|hint| &*hint.hint_type)
+            .chain(self.polylines.iter().map(|hint| &*hint.hint_type))
+            .chain(self.polyline_lists.iter().map(|hint| &*hint.hint_type))
+            .collect()
+    }
+
+    /// This function returns mutable references to the types of all the hints, in the same order as `get_hint_types`.
+    pub fn get_hint_types_mut(&mut self) -> Vec<&mut String> {
+        self.directed_points.iter_mut().map(|hint| &mut hint.hint_type)
+            .chain(self.polylines.iter_mut().map(|hint| &mut hint.hint_type))
+            .chain(self.polyline_lists.iter_mut().map(|hint| &mut hint.hint_type))
+            .collect()
+    }
+
+    /// This function reads the AI hints of a map, moving the index to the end of them.
+    fn read(data: &[u8], index: &mut usize) -> Result<Self> {
+        let directed_points = read_list(data, index, |data, index| {
+            let hint_type = read_hint_type(data, index)?;
+            Ok(DirectedPointHint {
+                hint_type,
+                position: read_point(data, index)?,
+                direction: read_point(data, index)?,
+            })
+        })?;
+
+        let polylines = read_list(data, index, |data, index| {
+            let hint_type = read_hint_type(data, index)?;
+            Ok(PolylineHint {
+                hint_type,
+                points: read_list(data, index, read_point)?,
+            })
+        })?;
+
+        let polyline_lists = read_list(data, index, |data, index| {
+            let hint_type = read_hint_type(data, index)?;
+            Ok(PolylineListHint {
+                hint_type,
+                polylines: read_list(data, index, |data, index| read_list(data, index, read_point))?,
+            })
+        })?;
+
+        Ok(Self {
+            directed_points,
+            polylines,
+            polyline_lists,
+        })
+    }
+
+    /// This function encodes the AI hints to the provided data.
+    fn save(&self, data: &mut Vec<u8>) {
+        write_list(data, &self.directed_points, |data, hint| {
+            write_hint_type(data, &hint.hint_type);
+            write_point(data, &hint.position);
+            write_point(data, &hint.direction);
+        });
+
+        write_list(data, &self.polylines, |data, hint| {
+            write_hint_type(data, &hint.hint_type);
+            write_list(data, &hint.points, write_point);
+        });
+
+        write_list(data, &self.polyline_lists, |data, hint| {
+            write_hint_type(data, &hint.hint_type);
+            write_list(data, &hint.polylines, |data, polyline| write_list(data, polyline, write_point));
+        });
+    }
+}
+
+/// Implementation of `DeploymentArea`.
+impl DeploymentArea {
+
+    /// This function reads a deployment area with all its zones, moving the index to the end of it.
+    fn read(data: &[u8], index: &mut usize) -> Result<Self> {
+        check_version("DeploymentArea", data.decode_packedfile_integer_u16(*index, index)?, DEPLOYMENT_AREA_VERSION)?;
+        let category = data.decode_packedfile_string_u8(*index, index)?;
+        let zones = read_list(data, index, |data, index| {
+            check_version("DeploymentZone", data.decode_packedfile_integer_u16(*index, index)?, DEPLOYMENT_ZONE_VERSION)?;
+            Ok(DeploymentZone {
+                name: data.decode_packedfile_string_u8(*index, index)?,
+                boundary: read_list(data, index, read_point)?,
+                orientation: data.decode_packedfile_float_f32(*index, index)?,
+            })
+        })?;
+
+        Ok(Self {
+            category,
+            zones,
+        })
+    }
+
+    /// This function encodes the deployment area to the provided data.
+    fn save(&self, data: &mut Vec<u8>) {
+        data.encode_integer_u16(DEPLOYMENT_AREA_VERSION);
+        data.encode_packedfile_string_u8(&self.category);
+        write_list(data, &self.zones, |data, zone| {
+            data.encode_integer_u16(DEPLOYMENT_ZONE_VERSION);
+            data.encode_packedfile_string_u8(&zone.name);
+            write_list(data, &zone.boundary, write_point);
+            data.encode_float_f32(zone.orientation);
+        });
+    }
+}
+
+/// Implementation of `MapReport`.
+impl MapReport {
+
+    /// This function creates a new empty `MapReport` for the map in the provided folder.
+    pub fn new(path: &[String]) -> Self {
+        Self {
+            path: path.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// This function adds the contents of a BMD PackedFile of the map to the report.
+    pub fn add_bmd(&mut self, path: &[String], bmd: &BMD) {
+        self.files.push(path.to_vec());
+        for hint in bmd.get_ai_hints() {
+            *self.ai_hints.entry(hint.to_owned()).or_insert(0) += 1;
+        }
+
+        for zone in bmd.get_deployment_zones() {
+            if !self.deployment_zones.iter().any(|x| x == zone) {
+                self.deployment_zones.push(zone.to_owned());
+            }
+        }
+
+        for prefab_instance in bmd.get_prefab_instances() {
+            *self.prefab_instances.entry(prefab_instance.key.to_owned()).or_insert(0) += 1;
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Helper functions
+//---------------------------------------------------------------------------//
+
+/// This function returns the sections of the provided version of the format, in order, if we support it.
+fn get_section_types(version: u16) -> Option<&'static [SectionType]> {
+    match version {
+        27 => Some(&SECTIONS_V27),
+        _ => None,
+    }
+}
+
+/// This function returns an error if the version of something we're decoding is not the one we support.
+fn check_version(name: &str, version: u16, supported_version: u16) -> Result<()> {
+    if version == supported_version { Ok(()) }
+    else { Err(ErrorKind::BMDDecode(format!("Unsupported version of {}: {}. Only version {} is supported.", name, version, supported_version)).into()) }
+}
+
+/// This function reads a list of items prefixed by their amount, moving the index to the end of it.
+fn read_list<T>(data: &[u8], index: &mut usize, read_item: impl Fn(&[u8], &mut usize) -> Result<T>) -> Result<Vec<T>> {
+    let mut items = vec![];
+    for _ in 0..data.decode_packedfile_integer_u32(*index, index)? {
+        items.push(read_item(data, index)?);
+    }
+    Ok(items)
+}
+
+/// This function encodes a list of items prefixed by their amount to the provided data.
+fn write_list<T>(data: &mut Vec<u8>, items: &[T], write_item: impl Fn(&mut Vec<u8>, &T)) {
+    data.encode_integer_u32(items.len() as u32);
+    items.iter().for_each(|item| write_item(data, item));
+}
+
+/// This function reads the version and the type of an AI hint, moving the index to the end of them.
+fn read_hint_type(data: &[u8], index: &mut usize) -> Result<String> {
+    check_version("AIHint", data.decode_packedfile_integer_u16(*index, index)?, AI_HINT_VERSION)?;
+    data.decode_packedfile_string_u8(*index, index)
+}
+
+/// This function encodes the version and the type of an AI hint to the provided data.
+fn write_hint_type(data: &mut Vec<u8>, hint_type: &str) {
+    data.encode_integer_u16(AI_HINT_VERSION);
+    data.encode_packedfile_string_u8(hint_type);
+}
+
+/// This function reads a 2D point, moving the index to the end of it.
+fn read_point(data: &[u8], index: &mut usize) -> Result<[f32; 2]> {
+    Ok([data.decode_packedfile_float_f32(*index, index)?, data.decode_packedfile_float_f32(*index, index)?])
+}
+
+/// This function encodes a 2D point to the provided data.
+fn write_point(data: &mut Vec<u8>, point: &[f32; 2]) {
+    data.encode_float_f32(point[0]);
+    data.encode_float_f32(point[1]);
+}
@@ -0,0 +1,202 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `CaVp8` module, to make sure videos are built and validated correctly.
+!*/
+
+use super::*;
+
+/// This function builds a fake VP8 frame. Key frames have the marker and the provided size, and both have a valid first partition size.
+fn build_frame(is_key_frame: bool, width: u16, height: u16, payload: u8) -> Vec<u8> {
+    let partition_size = 4u32;
+    let tag = (partition_size << 5) | 0x10 | if is_key_frame { 0 } else { 1 };
+    let mut frame = tag.to_le_bytes()[..3].to_vec();
+    if is_key_frame {
+        frame.extend_from_slice(KEY_FRAME_MARKER);
+        frame.extend_from_slice(&width.to_le_bytes());
+        frame.extend_from_slice(&height.to_le_bytes());
+    }
+    frame.extend_from_slice(&[payload; 4]);
+    frame
+}
+
+/// This function builds an IVF video with the provided frames, and a wrong frame count and size in its header.
+fn build_ivf(frames: &[Vec<u8>]) -> Vec<u8> {
+    let mut ivf = vec![];
+    ivf.encode_string_u8(SIGNATURE_IVF);
+    ivf.encode_integer_i16(0);
+    ivf.encode_integer_u16(HEADER_LENGTH_IVF);
+    ivf.encode_string_u8(CODEC_FOUR_CC_VP8);
+    ivf.encode_integer_u16(1);
+    ivf.encode_integer_u16(1);
+    ivf.encode_integer_u32(30);
+    ivf.encode_integer_u32(1);
+    ivf.encode_integer_u32(0);
+    ivf.encode_integer_u32(0);
+    for (index, frame) in frames.iter().enumerate() {
+        ivf.encode_integer_u32(frame.len() as u32);
+        ivf.encode_integer_u64(index as u64);
+        ivf.extend_from_slice(frame);
+    }
+    ivf
+}
+
+/// This function builds an EBML element with a 8 bytes size.
+fn build_element(id: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut element = id.to_vec();
+    element.push(0x01);
+    element.extend_from_slice(&(data.len() as u64).to_be_bytes()[1..]);
+    element.extend_from_slice(data);
+    element
+}
+
+/// This function builds a WebM video with an audio track, and a VP8 track with the provided frames at 25 FPS.
+fn build_webm(frames: &[Vec<u8>]) -> Vec<u8> {
+    let mut vp8_track = build_element(&[0xD7], &[2]);
+    vp8_track.extend(build_element(&[0x86], b"V_VP8"));
+    vp8_track.extend(build_element(&[0xE0], &build_element(&[0xB0], &[0x40])));
+
+    let mut audio_track = build_element(&[0xD7], &[1]);
+    audio_track.extend(build_element(&[0x86], b"A_VORBIS"));
+
+    let mut tracks = build_element(&[0xAE], &audio_track);
+    tracks.extend(build_element(&[0xAE], &vp8_track));
+
+    // Clusters of unknown size, with SimpleBlocks of both tracks, and a BlockGroup.
+    let mut segment = build_element(&[0x15, 0x49, 0xA9, 0x66], &build_element(&[0x2A, 0xD7, 0xB1], &[0x0F, 0x42, 0x40]));
+    segment.extend(build_element(&[0x16, 0x54, 0xAE, 0x6B], &tracks));
+    for (index, frame) in frames.iter().enumerate() {
+        if index % 2 == 0 {
+            segment.extend_from_slice(&[0x1F, 0x43, 0xB6, 0x75, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+            segment.extend(build_element(&[0xE7], &[(index * 40) as u8]));
+            segment.extend(build_element(&[0xA3], &[0x81, 0, 0, 0x80, 0xAA]));
+        }
+
+        let mut block = vec![0x82, 0, if index % 2 == 0 { 0 } else { 40 }, 0x80];
+        block.extend_from_slice(frame);
+        if index % 3 == 0 {
+            segment.extend(build_element(&[0xA0], &build_element(&[0xA1], &block)));
+        } else {
+            segment.extend(build_element(&[0xA3], &block));
+        }
+    }
+
+    let mut webm = build_element(&webm::SIGNATURE, &build_element(&[0x42, 0x82], b"webm"));
+    webm.extend(build_element(&[0x18, 0x53, 0x80, 0x67], &segment));
+    webm
+}
+
+#[test]
+fn test_ca_vp8_from_video() {
+    let frames = vec![
+        build_frame(true, 320, 180, 1),
+        build_frame(false, 0, 0, 2),
+        build_frame(false, 0, 0, 3),
+        build_frame(true, 320, 180, 4),
+        build_frame(false, 0, 0, 5),
+    ];
+
+    // IVF.
+    let video = CaVp8::from_video(&build_ivf(&frames)).unwrap();
+    assert_eq!(video.get_format(), SupportedFormats::Camv);
+    assert_eq!((video.get_width(), video.get_height()), (320, 180));
+    assert_eq!(video.get_num_frames(), 5);
+    assert!((video.get_framerate() - 30.0).abs() < 0.001);
+    assert_eq!(video.get_ref_frame_table()[2].get_offset(), (frames[0].len() + frames[1].len()) as u32);
+    assert_eq!(video.get_ref_frame_table().iter().map(|frame| frame.is_key_frame()).collect::<Vec<bool>>(), vec![true, false, false, true, false]);
+    assert_eq!(video.get_ref_frame_data(), &frames.concat()[..]);
+    assert!(video.validate().is_empty());
+
+    // The CAMV file should keep everything, except for some precision on the framerate.
+    let mut camv = CaVp8::read(video.save()).unwrap();
+    assert!((camv.get_framerate() - 30.0).abs() < 0.001);
+    camv.framerate = video.framerate;
+    assert_eq!(camv, video);
+    assert!(camv.validate().is_empty());
+
+    // WebM.
+    let video = CaVp8::from_video(&build_webm(&frames)).unwrap();
+    assert_eq!(video.get_ref_frame_data(), &frames.concat()[..]);
+    assert_eq!(video.get_num_frames(), 5);
+    assert!((video.get_framerate() - 25.0).abs() < 0.001);
+    assert!(video.validate().is_empty());
+
+    // Videos not starting with a key frame cannot be built.
+    assert!(CaVp8::from_ivf(&build_ivf(&frames[1..])).is_err());
+    assert!(CaVp8::from_webm(&build_webm(&[])).is_err());
+
+    // Truncated IVF files should fail.
+    let ivf = build_ivf(&frames);
+    assert!(CaVp8::from_ivf(&ivf[..ivf.len() - 1]).is_err());
+}
+
+#[test]
+fn test_ca_vp8_validate() {
+    let frames = [build_frame(true, 64, 32, 1), build_frame(false, 0, 0, 2), build_frame(true, 64, 32, 3)];
+    let frames = frames.iter().map(|frame| &frame[..]).collect::<Vec<&[u8]>>();
+    let video = CaVp8::from_frames(&frames, 24.0).unwrap();
+    assert!(video.validate().is_empty());
+
+    let mut broken = video.clone();
+    broken.num_frames = 4;
+    broken.frame_table[1].flags = 1;
+    broken.frame_data[frames[0].len() + frames[1].len() + 3] = 0;
+    broken.frame_data.push(0);
+    assert_eq!(broken.validate(), vec![
+        FrameTableIssue::WrongFrameCount(4, 3),
+        FrameTableIssue::WrongKeyFrameFlag(1, true),
+        FrameTableIssue::MissingKeyFrameMarker(2),
+        FrameTableIssue::UnusedData(1),
+    ]);
+
+    let mut broken = video.clone();
+    broken.frame_table.swap(0, 1);
+    broken.frame_table[2].size = 100;
+    let issues = broken.validate();
+    assert!(issues.contains(&FrameTableIssue::WrongOffset(0, 14, 0)));
+    assert!(issues.contains(&FrameTableIssue::FirstFrameNotKeyFrame));
+    assert!(issues.contains(&FrameTableIssue::OutOfBounds(2)));
+
+    let mut broken = video;
+    broken.frame_data[2] = 0xFF;
+    broken.frame_data[6] = 65;
+    assert_eq!(broken.validate(), vec![FrameTableIssue::WrongDimensions(0, 65, 32), FrameTableIssue::WrongPartitionSize(0)]);
+}
+
+#[test]
+fn test_ca_vp8_corrupted_frame_table() {
+    let frames = [build_frame(true, 64, 32, 1), build_frame(false, 0, 0, 2), build_frame(false, 0, 0, 3)];
+    let frames = frames.iter().map(|frame| &frame[..]).collect::<Vec<&[u8]>>();
+    let mut data = CaVp8::from_frames(&frames, 24.0).unwrap().save();
+
+    // Mark the first frame as an inter frame with an unknown flag, move the second one byte forward, and the last one past the end.
+    let frame_table_offset = data.decode_integer_u32(28).unwrap() as usize;
+    let second_offset = data.decode_integer_u32(frame_table_offset + 9).unwrap();
+    data[frame_table_offset + 9..frame_table_offset + 13].copy_from_slice(&(second_offset + 1).to_le_bytes());
+    data[frame_table_offset + 8] = 0x80;
+    data[frame_table_offset + 18..frame_table_offset + 22].copy_from_slice(&1000u32.to_le_bytes());
+
+    // The frame table must be read as it is, so the problems are reported and saving keeps it unchanged.
+    let video = CaVp8::read(data.to_vec()).unwrap();
+    assert_eq!(video.get_ref_frame_table()[0].get_flags(), 0x80);
+    let issues = video.validate();
+    let first_length = frames[0].len() as u32;
+    assert!(issues.contains(&FrameTableIssue::WrongOffset(1, first_length + 1, first_length)));
+    assert!(issues.contains(&FrameTableIssue::WrongKeyFrameFlag(0, false)));
+    assert!(issues.contains(&FrameTableIssue::OutOfBounds(2)));
+    assert_eq!(video.save(), data);
+
+    // Frames before the end of the frame data leave unused data after them.
+    let mut data = CaVp8::from_frames(&frames, 24.0).unwrap().save();
+    let last_size = data.decode_integer_u32(frame_table_offset + 22).unwrap();
+    data[frame_table_offset + 22..frame_table_offset + 26].copy_from_slice(&(last_size - 1).to_le_bytes());
+    assert!(CaVp8::read(data).unwrap().validate().contains(&FrameTableIssue::UnusedData(1)));
+}
@@ -24,10 +24,17 @@ to the MIT license above and are under the CC-SA 4.0 license, available here:
 use serde_derive::{Serialize, Deserialize};
 use fraction::GenericFraction;
 
+use std::fmt::{self, Display};
+
 use rpfm_error::{ErrorKind, Result};
 
 use crate::common::{decoder::Decoder, encoder::Encoder};
 
+mod webm;
+
+#[cfg(test)]
+mod ca_vp8_test;
+
 /// Extensions used by CA_VP8 PackedFiles.
 pub const EXTENSION: &str = ".ca_vp8";
 
@@ -46,6 +53,18 @@ const HEADER_LENGTH_CAMV: u16 = 41;
 /// Length of the header of a IVF video.
 const HEADER_LENGTH_IVF: u16 = 32;
 
+/// Length of the header of an IVF frame.
+const FRAME_HEADER_LENGTH_IVF: usize = 12;
+
+/// FourCC of the VP8 codec.
+const CODEC_FOUR_CC_VP8: &str = "VP80";
+
+/// Length of the uncompressed header of a VP8 inter frame (the frame tag).
+const FRAME_TAG_LENGTH: usize = 3;
+
+/// Length of the uncompressed header of a VP8 key frame (frame tag, key frame marker, width and height).
+const KEY_FRAME_HEADER_LENGTH: usize = 10;
+
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
 //---------------------------------------------------------------------------//
@@ -97,11 +116,49 @@ pub enum SupportedFormats {
 #[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct Frame {
 
-    /// Offset on the data where the frame begins.
+    /// Offset on the frame data where the frame begins, as it's in the frame table.
     offset: u32,
 
     /// Size of the frame.
     size: u32,
+
+    /// Flags of the frame in the frame table. The first bit marks key frames.
+    flags: u8,
+}
+
+/// This enum represents a problem found in the frame table of a video by `CaVp8::validate`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum FrameTableIssue {
+
+    /// The frame count of the video doesn't match the amount of frames in the frame table. Contains both counts.
+    WrongFrameCount(u32, u32),
+
+    /// The frame doesn't start where the previous one ends. Contains the index of the frame, its offset, and the offset it should have.
+    WrongOffset(usize, u32, u32),
+
+    /// The frame goes beyond the end of the frame data. Contains the index of the frame.
+    OutOfBounds(usize),
+
+    /// The frame is too small to even have a VP8 frame header. Contains the index of the frame and its size.
+    TooSmall(usize, u32),
+
+    /// The frame is a key frame, but it doesn't have the key frame marker. Contains the index of the frame.
+    MissingKeyFrameMarker(usize),
+
+    /// The frame table says the frame is (or is not) a key frame, but its data says otherwise. Contains the index of the frame and the value in the frame table.
+    WrongKeyFrameFlag(usize, bool),
+
+    /// The size of the first partition of the frame goes beyond the end of the frame. Contains the index of the frame.
+    WrongPartitionSize(usize),
+
+    /// The size in the key frame doesn't match the size of the video. Contains the index of the frame, and the width and height in the key frame.
+    WrongDimensions(usize, u16, u16),
+
+    /// The first frame is not a key frame, so the video cannot be decoded.
+    FirstFrameNotKeyFrame,
+
+    /// The frame data has bytes after the last frame. Contains the amount of extra bytes.
+    UnusedData(usize),
 }
 
 //---------------------------------------------------------------------------//
@@ -148,7 +205,7 @@ impl CaVp8 {
 
         let mut offset = 4;
         let version = packed_file_data.decode_packedfile_integer_i16(offset, &mut offset)?;
-        let _header_len = packed_file_data.decode_packedfile_integer_u16(offset, &mut offset)?;
+        let header_len = packed_file_data.decode_packedfile_integer_u16(offset, &mut offset)?;
         let codec_four_cc = packed_file_data.decode_string_u8(offset, 4)?;
         offset += 4;
         let width = packed_file_data.decode_packedfile_integer_u16(offset, &mut offset)?;
@@ -162,32 +219,42 @@ impl CaVp8 {
 
         // From here on, it's frame data, then the frame table.
         offset = offset_frame_table as usize;
+        if offset < header_len as usize || offset > packed_file_data.len() {
+            return Err(ErrorKind::CaVp8Decode(format!("The frame table starts at {}, outside the frame data.", offset)).into());
+        }
 
         // Brace yourself, wonky workaround incomming!
         // There are some files that, for unknown reasons, have 13 bytes instead of 9 in the frame table.
         // I have no freaking idea what's the logic behind 9/13 bytes, so we go with the getto solution:
         // - Frames / 13. If the remainder is 0, we have groups of 13. If not, groups of 9.
         let bells = packed_file_data[offset..].len() / 13 == num_frames as usize && packed_file_data[offset..].len() % 13 == 0;
-        let mut frame_offset = 0;
         let mut frame_table = vec![];
-        let mut frame_data = vec![];
 
-        for _ in 0..num_frames {
-            let _frame_offset_real = packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?;
-            let frame = Frame {
-                offset: frame_offset,
-                size: packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?,
-            };
+        // We keep the frame table as it is, with the offsets relative to the start of the frame data, so `validate` can check them.
+        for index in 0..num_frames {
+            let frame_offset_real = packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?;
+            let size = packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?;
             if bells {
                 let _unknown_data = packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?;
             }
-            let _flags = packed_file_data.decode_packedfile_integer_u8(offset, &mut offset)?;
+            let flags = packed_file_data.decode_packedfile_integer_u8(offset, &mut offset)?;
+            let frame_offset = match frame_offset_real.checked_sub(u32::from(header_len)) {
+                Some(frame_offset) => frame_offset,
+                None => return Err(ErrorKind::CaVp8Decode(format!("The frame {} starts at {}, inside the header.", index, frame_offset_real)).into()),
+            };
 
-            frame_offset += frame.size;
-            frame_table.push(frame);
-            frame_data.extend_from_slice(&packed_file_data[_frame_offset_real as usize..(_frame_offset_real + frame.size) as usize]);
+            frame_table.push(Frame {
+                offset: frame_offset,
+                size,
+                flags,
+            });
         }
 
+        // The frame data is everything between the header and the frame table.
+        let mut frame_data = packed_file_data;
+        frame_data.truncate(offset_frame_table as usize);
+        frame_data.drain(..header_len as usize);
+
         Ok(Self {
             format,
             version,
@@ -223,11 +290,13 @@ impl CaVp8 {
         for _ in 0..num_frames {
             let size = packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?;
             let _pts = packed_file_data.decode_packedfile_integer_u64(offset, &mut offset)?;
+            let frame_bytes = packed_file_data.get_bytes_checked(offset, size as usize)?;
             let frame = Frame {
                 offset: frame_offset,
                 size,
+                flags: if is_key_frame(frame_bytes) { 1 } else { 0 },
             };
-            frame_data.extend_from_slice(&packed_file_data[offset..offset + frame.size as usize]);
+            frame_data.extend_from_slice(frame_bytes);
             offset += frame.size as usize;
             frame_offset += frame.size;
             frame_table.push(frame);
@@ -246,7 +315,9 @@ impl CaVp8 {
         })
     }
 
-    /// This function creates a `CaVp8` from a `Vec<u8>` containing a video of CAMV format.
+    /// This function encodes a `CaVp8` as a video of CAMV format.
+    ///
+    /// The frame data and the frame table are saved as they are, even if they have problems. Use `validate` to find them.
     fn save_camv(&self) -> Vec<u8> {
         let mut packed_file = vec![];
         packed_file.encode_string_u8(SIGNATURE_CAMV);
@@ -260,7 +331,7 @@ impl CaVp8 {
         packed_file.encode_integer_u32(1);
         packed_file.encode_integer_u32(self.num_frames);
 
-        packed_file.encode_integer_u32(HEADER_LENGTH_CAMV as u32 + self.frame_table.iter().map(|x| x.size).sum::<u32>());
+        packed_file.encode_integer_u32(HEADER_LENGTH_CAMV as u32 + self.frame_data.len() as u32);
         packed_file.encode_integer_u32(self.num_frames);
         packed_file.encode_integer_u32(self.frame_table.iter().map(|x| x.size).max().unwrap());
 
@@ -270,21 +341,18 @@ impl CaVp8 {
         // Frame data and table.
         packed_file.extend_from_slice(&self.frame_data);
 
-        let mut offset = 0;
         for frame in &self.frame_table {
-            let frame_data = &self.frame_data[offset..(offset + frame.size as usize)];
-            let is_key_frame = if &frame_data[3..6] == KEY_FRAME_MARKER { 1 } else { 0 };
-
-            packed_file.encode_integer_u32(offset as u32 + HEADER_LENGTH_CAMV as u32);
-            packed_file.encode_integer_u32(frame_data.len() as u32);
-            packed_file.push(is_key_frame);
-            offset += frame.size as usize;
+            packed_file.encode_integer_u32(frame.offset.saturating_add(HEADER_LENGTH_CAMV as u32));
+            packed_file.encode_integer_u32(frame.size);
+            packed_file.push(frame.flags);
         }
 
         packed_file
     }
 
-    /// This function creates a `CaVp8` from a `Vec<u8>` containing a video of CAMV format.
+    /// This function encodes a `CaVp8` as a video of IVF format.
+    ///
+    /// IVF files have no frame table, so frames beyond the end of the frame data are saved empty.
     fn save_ivf(&self) -> Vec<u8> {
         let mut packed_file = vec![];
         packed_file.encode_string_u8(SIGNATURE_IVF);
@@ -300,18 +368,179 @@ impl CaVp8 {
         packed_file.encode_integer_u32(self.num_frames);
         packed_file.encode_integer_u32(0);
 
-        let mut offset = 0;
         for (index, frame) in self.frame_table.iter().enumerate() {
-            let frame_data = &self.frame_data[offset..(offset + frame.size as usize)];
+            let start = frame.offset as usize;
+            let frame_data = self.frame_data.get(start..start.saturating_add(frame.size as usize)).unwrap_or(&[]);
             packed_file.encode_integer_u32(frame_data.len() as u32);
             packed_file.encode_integer_u64(index as u64);
             packed_file.extend_from_slice(frame_data);
-            offset += frame.size as usize;
         }
 
         packed_file
     }
 
+    /// This function creates a new CAMV `CaVp8` from an IVF or WebM video with VP8 frames, depending on its signature.
+    pub fn from_video(data: &[u8]) -> Result<Self> {
+        if data.starts_with(&webm::SIGNATURE) {
+            Self::from_webm(data)
+        } else {
+            Self::from_ivf(data)
+        }
+    }
+
+    /// This function creates a new CAMV `CaVp8` from an IVF video.
+    ///
+    /// Unlike `read`, this ignores the frame count and size in the IVF header. Frames are read until the end of the file,
+    /// and the size of the video and its framerate are taken from the stream.
+    pub fn from_ivf(data: &[u8]) -> Result<Self> {
+        if data.decode_string_u8(0, 4)? != SIGNATURE_IVF {
+            return Err(ErrorKind::CaVp8Decode("This is not an IVF file.".to_owned()).into());
+        }
+
+        let header_length = data.decode_integer_u16(6)? as usize;
+        let codec_four_cc = data.decode_string_u8(8, 4)?;
+        if codec_four_cc != CODEC_FOUR_CC_VP8 {
+            return Err(ErrorKind::CaVp8Decode(format!("Unsupported codec: {}. Only VP8 videos are supported.", codec_four_cc)).into());
+        }
+
+        let timebase_denominator = data.decode_integer_u32(16)?;
+        let timebase_numerator = data.decode_integer_u32(20)?;
+        if timebase_denominator == 0 || timebase_numerator == 0 {
+            return Err(ErrorKind::CaVp8Decode("The IVF file has an invalid framerate.".to_owned()).into());
+        }
+
+        let mut frames = vec![];
+        let mut offset = header_length.max(HEADER_LENGTH_IVF as usize);
+        while offset < data.len() {
+            let size = data.decode_integer_u32(offset)? as usize;
+            frames.push(data.get_bytes_checked(offset + FRAME_HEADER_LENGTH_IVF, size)?);
+            offset += FRAME_HEADER_LENGTH_IVF + size;
+        }
+
+        Self::from_frames(&frames, timebase_denominator as f32 / timebase_numerator as f32)
+    }
+
+    /// This function creates a new CAMV `CaVp8` from the first VP8 video track of a WebM file.
+    pub fn from_webm(data: &[u8]) -> Result<Self> {
+        let video = webm::read(data)?;
+        Self::from_frames(&video.frames, video.framerate)
+    }
+
+    /// This function creates a new CAMV `CaVp8` from raw VP8 frames, rebuilding its frame table.
+    ///
+    /// The first frame must be a key frame, as the size of the video is taken from it.
+    pub fn from_frames(frames: &[&[u8]], framerate: f32) -> Result<Self> {
+        let first_frame = match frames.first() {
+            Some(first_frame) => first_frame,
+            None => return Err(ErrorKind::CaVp8Decode("The video has no frames.".to_owned()).into()),
+        };
+
+        let (width, height) = match get_key_frame_size(first_frame) {
+            Some(size) => size,
+            None => return Err(ErrorKind::CaVp8Decode("The first frame of the video is not a valid key frame.".to_owned()).into()),
+        };
+
+        if !framerate.is_normal() || framerate < 0.0 {
+            return Err(ErrorKind::CaVp8Decode(format!("Invalid framerate: {}.", framerate)).into());
+        }
+
+        let mut frame_table = Vec::with_capacity(frames.len());
+        let mut frame_data = Vec::with_capacity(frames.iter().map(|frame| frame.len()).sum());
+        for frame in frames {
+            if frame.len() < FRAME_TAG_LENGTH {
+                return Err(ErrorKind::CaVp8Decode(format!("The frame {} is too small to be a VP8 frame.", frame_table.len())).into());
+            }
+
+            frame_table.push(Frame {
+                offset: frame_data.len() as u32,
+                size: frame.len() as u32,
+                flags: if is_key_frame(frame) { 1 } else { 0 },
+            });
+            frame_data.extend_from_slice(frame);
+        }
+
+        Ok(Self {
+            format: SupportedFormats::Camv,
+            version: 1,
+            codec_four_cc: CODEC_FOUR_CC_VP8.to_owned(),
+            width,
+            height,
+            num_frames: frame_table.len() as u32,
+            framerate,
+            frame_table,
+            frame_data,
+        })
+    }
+
+    /// This function checks the frame table of the video against its frame data, and returns all the problems found in it.
+    ///
+    /// A video without problems returns an empty list.
+    pub fn validate(&self) -> Vec<FrameTableIssue> {
+        let mut issues = vec![];
+        if self.num_frames as usize != self.frame_table.len() {
+            issues.push(FrameTableIssue::WrongFrameCount(self.num_frames, self.frame_table.len() as u32));
+        }
+
+        let mut expected_offset = 0u64;
+        for (index, frame) in self.frame_table.iter().enumerate() {
+            if u64::from(frame.offset) != expected_offset {
+                issues.push(FrameTableIssue::WrongOffset(index, frame.offset, expected_offset as u32));
+            }
+
+            let end = u64::from(frame.offset) + u64::from(frame.size);
+            expected_offset = end;
+
+            let data = match self.frame_data.get(frame.offset as usize..end as usize) {
+                Some(data) => data,
+                None => {
+                    issues.push(FrameTableIssue::OutOfBounds(index));
+                    continue;
+                }
+            };
+
+            if data.len() < FRAME_TAG_LENGTH {
+                issues.push(FrameTableIssue::TooSmall(index, frame.size));
+                continue;
+            }
+
+            // The frame tag has the frame type in its first bit, and the size of the first partition in its last 19 bits.
+            let is_key_frame = is_key_frame(data);
+            let partition_size = (u32::from(data[0]) | u32::from(data[1]) << 8 | u32::from(data[2]) << 16) >> 5;
+            let header_length = if is_key_frame { KEY_FRAME_HEADER_LENGTH } else { FRAME_TAG_LENGTH };
+            if is_key_frame {
+                if data.len() < KEY_FRAME_HEADER_LENGTH {
+                    issues.push(FrameTableIssue::TooSmall(index, frame.size));
+                    continue;
+                }
+
+                match get_key_frame_size(data) {
+                    Some((width, height)) => if width != self.width || height != self.height {
+                        issues.push(FrameTableIssue::WrongDimensions(index, width, height));
+                    }
+                    None => issues.push(FrameTableIssue::MissingKeyFrameMarker(index)),
+                }
+            }
+
+            if header_length + partition_size as usize > data.len() {
+                issues.push(FrameTableIssue::WrongPartitionSize(index));
+            }
+
+            if frame.is_key_frame() != is_key_frame {
+                issues.push(FrameTableIssue::WrongKeyFrameFlag(index, frame.is_key_frame()));
+            }
+
+            if index == 0 && !is_key_frame {
+                issues.push(FrameTableIssue::FirstFrameNotKeyFrame);
+            }
+        }
+
+        if expected_offset < self.frame_data.len() as u64 {
+            issues.push(FrameTableIssue::UnusedData(self.frame_data.len() - expected_offset as usize));
+        }
+
+        issues
+    }
+
     /// This function returns the format of the currently decoded video.
     pub fn get_format(&self) -> SupportedFormats {
         self.format
@@ -362,3 +591,63 @@ impl CaVp8 {
         &self.frame_data
     }
 }
+
+/// Implementation of `Frame`.
+impl Frame {
+
+    /// This function returns the offset of the frame in the frame data of the video.
+    pub fn get_offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// This function returns the size of the frame.
+    pub fn get_size(&self) -> u32 {
+        self.size
+    }
+
+    /// This function returns if the frame is marked as a key frame in the frame table.
+    pub fn is_key_frame(&self) -> bool {
+        self.flags & 1 != 0
+    }
+
+    /// This function returns the flags of the frame in the frame table.
+    pub fn get_flags(&self) -> u8 {
+        self.flags
+    }
+}
+
+/// Display implementation of `FrameTableIssue`.
+impl Display for FrameTableIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrameTableIssue::WrongFrameCount(num_frames, table_len) => write!(f, "The video says it has {} frames, but its frame table has {}.", num_frames, table_len),
+            FrameTableIssue::WrongOffset(index, offset, expected) => write!(f, "Frame {} starts at {}, but the previous frame ends at {}.", index, offset, expected),
+            FrameTableIssue::OutOfBounds(index) => write!(f, "Frame {} goes beyond the end of the frame data.", index),
+            FrameTableIssue::TooSmall(index, size) => write!(f, "Frame {} is too small ({} bytes) to be a VP8 frame.", index, size),
+            FrameTableIssue::MissingKeyFrameMarker(index) => write!(f, "Frame {} is a key frame, but it doesn't have the key frame marker.", index),
+            FrameTableIssue::WrongKeyFrameFlag(index, is_key_frame) => write!(f, "Frame {} is marked as {} in the frame table, but its data says otherwise.", index, if *is_key_frame { "key frame" } else { "inter frame" }),
+            FrameTableIssue::WrongPartitionSize(index) => write!(f, "The first partition of frame {} goes beyond the end of the frame.", index),
+            FrameTableIssue::WrongDimensions(index, width, height) => write!(f, "Key frame {} is {}x{}, which is not the size of the video.", index, width, height),
+            FrameTableIssue::FirstFrameNotKeyFrame => write!(f, "The first frame is not a key frame."),
+            FrameTableIssue::UnusedData(size) => write!(f, "There are {} bytes of frame data after the last frame.", size),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Helper functions
+//---------------------------------------------------------------------------//
+
+/// This function returns if the provided VP8 frame is a key frame, according to its frame tag.
+fn is_key_frame(frame: &[u8]) -> bool {
+    matches!(frame.first(), Some(tag) if tag & 1 == 0)
+}
+
+/// This function returns the width and height of the provided VP8 key frame, or `None` if it's not a valid key frame.
+///
+/// The upper two bits of each value are the scaling of the frame, not part of its size.
+fn get_key_frame_size(frame: &[u8]) -> Option<(u16, u16)> {
+    if is_key_frame(frame) && frame.len() >= KEY_FRAME_HEADER_LENGTH && &frame[3..6] == KEY_FRAME_MARKER {
+        Some((u16::from_le_bytes([frame[6], frame[7]]) & 0x3FFF, u16::from_le_bytes([frame[8], frame[9]]) & 0x3FFF))
+    } else { None }
+}
@@ -0,0 +1,225 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to get the VP8 frames out of a WebM file.
+
+WebM files are EBML documents: a tree of elements, each one with an ID, a size and its data. We only care about:
+- The timecode scale, in the segment info.
+- The first video track with the `V_VP8` codec: its number and frame duration.
+- The blocks of that track, in each cluster, with their timecodes.
+
+Every other element is skipped. Laced blocks are not supported, as VP8 video tracks never use them.
+!*/
+
+use rpfm_error::{ErrorKind, Result};
+
+/// Signature of EBML (and WebM) files.
+pub const SIGNATURE: [u8; 4] = [0x1A, 0x45, 0xDF, 0xA3];
+
+/// Codec ID of VP8 tracks.
+const CODEC_ID_VP8: &str = "V_VP8";
+
+/// Default timecode scale, in nanoseconds.
+const DEFAULT_TIMECODE_SCALE: u64 = 1_000_000;
+
+// IDs of master elements we have to go into.
+const ID_SEGMENT: u32 = 0x1853_8067;
+const ID_INFO: u32 = 0x1549_A966;
+const ID_TRACKS: u32 = 0x1654_AE6B;
+const ID_TRACK_ENTRY: u32 = 0xAE;
+const ID_VIDEO: u32 = 0xE0;
+const ID_CLUSTER: u32 = 0x1F43_B675;
+const ID_BLOCK_GROUP: u32 = 0xA0;
+
+// IDs of elements with data we need.
+const ID_TIMECODE_SCALE: u32 = 0x2A_D7B1;
+const ID_TRACK_NUMBER: u32 = 0xD7;
+const ID_CODEC_ID: u32 = 0x86;
+const ID_DEFAULT_DURATION: u32 = 0x23_E383;
+const ID_CLUSTER_TIMECODE: u32 = 0xE7;
+const ID_SIMPLE_BLOCK: u32 = 0xA3;
+const ID_BLOCK: u32 = 0xA1;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the VP8 video track of a WebM file.
+pub struct WebmVideo<'a> {
+
+    /// Framerate of the video, from the duration of each frame or from the timecodes of the frames.
+    pub framerate: f32,
+
+    /// Raw VP8 frames of the video, in order.
+    pub frames: Vec<&'a [u8]>,
+}
+
+/// This struct represents a track of a WebM file, as we find it.
+#[derive(Default)]
+struct Track {
+
+    /// Number used by the blocks to refer to this track.
+    number: u64,
+
+    /// Codec of the track, like `V_VP8`.
+    codec_id: String,
+
+    /// Duration of each frame in nanoseconds, if the container specifies it.
+    default_duration: u64,
+}
+
+//---------------------------------------------------------------------------//
+//                              Functions
+//---------------------------------------------------------------------------//
+
+/// This function gets the first VP8 video track out of a WebM file.
+pub fn read(data: &[u8]) -> Result<WebmVideo<'_>> {
+    if !data.starts_with(&SIGNATURE) {
+        return Err(ErrorKind::CaVp8Decode("This is not a WebM file.".to_owned()).into());
+    }
+
+    let mut timecode_scale = DEFAULT_TIMECODE_SCALE;
+    let mut cluster_timecode = 0;
+    let mut tracks: Vec<Track> = vec![];
+    let mut blocks = vec![];
+
+    // All the IDs we care about are unique in the tree, so instead of going recursively through the elements
+    // we just go into the master elements we need and skip everything else. This also takes care of elements of unknown size.
+    let mut index = 0;
+    while index < data.len() {
+        let id = read_id(data, &mut index)?;
+        let size = read_size(data, &mut index)?;
+        match id {
+            ID_SEGMENT | ID_INFO | ID_TRACKS | ID_VIDEO | ID_CLUSTER | ID_BLOCK_GROUP => continue,
+            ID_TRACK_ENTRY => {
+                tracks.push(Track::default());
+                continue;
+            }
+            _ => {}
+        }
+
+        let element = match size.and_then(|size| data.get(index..index.checked_add(size as usize)?)) {
+            Some(element) => element,
+            None => return Err(ErrorKind::CaVp8Decode(format!("The element {:#X} at byte {} has an invalid size.", id, index)).into()),
+        };
+
+        match id {
+            ID_TIMECODE_SCALE => timecode_scale = read_uint(element),
+            ID_CLUSTER_TIMECODE => cluster_timecode = read_uint(element),
+            ID_SIMPLE_BLOCK | ID_BLOCK => blocks.push((cluster_timecode, element)),
+            _ => if let Some(track) = tracks.last_mut() {
+                match id {
+                    ID_TRACK_NUMBER => track.number = read_uint(element),
+                    ID_CODEC_ID => track.codec_id = String::from_utf8_lossy(element).trim_end_matches('\0').to_owned(),
+                    ID_DEFAULT_DURATION => track.default_duration = read_uint(element),
+                    _ => {}
+                }
+            }
+        }
+
+        index += element.len();
+    }
+
+    let track = match tracks.iter().find(|track| track.codec_id == CODEC_ID_VP8) {
+        Some(track) => track,
+        None => return Err(ErrorKind::CaVp8Decode("The WebM file has no VP8 video track.".to_owned()).into()),
+    };
+
+    // Blocks start with the track number, a timecode relative to their cluster, and some flags.
+    let mut frames = vec![];
+    let mut timecodes = vec![];
+    for (cluster_timecode, block) in blocks {
+        let mut index = 0;
+        let track_number = match read_size(block, &mut index)? {
+            Some(track_number) => track_number,
+            None => return Err(ErrorKind::CaVp8Decode("Block with invalid track number found.".to_owned()).into()),
+        };
+
+        if track_number != track.number {
+            continue;
+        }
+
+        if block.len() < index + 3 {
+            return Err(ErrorKind::CaVp8Decode("Block too short found.".to_owned()).into());
+        }
+
+        let relative_timecode = i16::from_be_bytes([block[index], block[index + 1]]);
+        let flags = block[index + 2];
+        if flags & 0x06 != 0 {
+            return Err(ErrorKind::CaVp8Decode("Laced blocks are not supported.".to_owned()).into());
+        }
+
+        timecodes.push(cluster_timecode as i64 + i64::from(relative_timecode));
+        frames.push(&block[index + 3..]);
+    }
+
+    // If the track doesn't say how long each frame is, calculate it from the timecodes of the first and last frames.
+    let framerate = if track.default_duration > 0 {
+        1_000_000_000f64 / track.default_duration as f64
+    } else {
+        let first = timecodes.iter().min().copied().unwrap_or(0);
+        let last = timecodes.iter().max().copied().unwrap_or(0);
+        if last <= first {
+            return Err(ErrorKind::CaVp8Decode("Unable to get the framerate of the video.".to_owned()).into());
+        }
+        (timecodes.len() - 1) as f64 * 1_000_000_000f64 / ((last - first) as f64 * timecode_scale as f64)
+    };
+
+    Ok(WebmVideo {
+        framerate: framerate as f32,
+        frames,
+    })
+}
+
+//---------------------------------------------------------------------------//
+//                              Helper functions
+//---------------------------------------------------------------------------//
+
+/// This function reads the ID of an element. IDs keep their length marker.
+fn read_id(data: &[u8], index: &mut usize) -> Result<u32> {
+    let first = match data.get(*index) {
+        Some(first) => *first,
+        None => return Err(ErrorKind::CaVp8Decode("The WebM file ends unexpectedly.".to_owned()).into()),
+    };
+
+    let length = first.leading_zeros() as usize + 1;
+    match data.get(*index..*index + length) {
+        Some(bytes) if length <= 4 => {
+            *index += length;
+            Ok(bytes.iter().fold(0, |id, byte| (id << 8) | u32::from(*byte)))
+        }
+        _ => Err(ErrorKind::CaVp8Decode(format!("Invalid element ID at byte {}.", index)).into()),
+    }
+}
+
+/// This function reads a variable-length size, without its length marker. Sizes with all their bits set mean unknown size.
+fn read_size(data: &[u8], index: &mut usize) -> Result<Option<u64>> {
+    let first = match data.get(*index) {
+        Some(first) => *first,
+        None => return Err(ErrorKind::CaVp8Decode("The WebM file ends unexpectedly.".to_owned()).into()),
+    };
+
+    let length = first.leading_zeros() as usize + 1;
+    match data.get(*index..*index + length) {
+        Some(bytes) if length <= 8 => {
+            *index += length;
+            let value = bytes[1..].iter().fold(u64::from(first) & (0xFF >> length), |value, byte| (value << 8) | u64::from(*byte));
+            let unknown = (1u64 << (7 * length)) - 1;
+            Ok(if value == unknown { None } else { Some(value) })
+        }
+        _ => Err(ErrorKind::CaVp8Decode(format!("Invalid element size at byte {}.", index)).into()),
+    }
+}
+
+/// This function reads a big-endian unsigned integer of any length up to 8 bytes.
+fn read_uint(data: &[u8]) -> u64 {
+    data.iter().take(8).fold(0, |value, byte| (value << 8) | u64::from(*byte))
+}
@@ -0,0 +1,221 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `ESF` module, to make sure ESF files are decoded, encoded, dumped and loaded correctly.
+!*/
+
+use super::*;
+
+/// This function builds a raw ESF with records, a record block, optimised nodes, arrays and strings.
+fn build_esf() -> Vec<u8> {
+    let mut nodes = vec![];
+
+    // Root record, compact, with name 0 and version 1.
+    let mut root = vec![];
+    root.extend_from_slice(&[U32_BYTE, 200]);
+    root.extend_from_slice(&[I32_24BIT, 0xFB, 0xFF, 0xFF]);
+    root.push(BOOL_TRUE);
+    root.push(F64);
+    root.extend_from_slice(&1.5f64.to_le_bytes());
+    root.push(COORD_2D);
+    root.encode_float_f32(1.0);
+    root.encode_float_f32(-2.5);
+    root.push(UTF16);
+    root.encode_integer_u32(0);
+    root.extend_from_slice(&[U32_BYTE | ARRAY, 3, 1, 2, 3]);
+    root.extend_from_slice(&[ASCII | ARRAY, 8, 5, 0, 0, 0, 7, 0, 0, 0]);
+    root.extend_from_slice(&[F32 | ARRAY, 4]);
+    root.encode_float_f32(0.1);
+
+    // An array long enough to need a two-bytes size.
+    root.extend_from_slice(&[U8 | ARRAY, 0x81, 0x48]);
+    root.extend_from_slice(&[7; 200]);
+
+    // Record with a version too big for the compact format.
+    root.extend_from_slice(&[RECORD | RECORD_LONG, 1, 0, 20, 5, ASCII, 5, 0, 0, 0]);
+
+    // Record block with two entries: one with a compact record, and an empty one.
+    root.extend_from_slice(&[RECORD | RECORD_BLOCK, 2, 8, 2, 5, RECORD | (3 << 1), 1, 2, U32_ONE, I32_ZERO, 0]);
+
+    nodes.extend_from_slice(&[RECORD | (1 << 1), 0]);
+    encode_size(&mut nodes, root.len());
+    nodes.extend_from_slice(&root);
+
+    let mut data = vec![];
+    data.encode_integer_u32(0xABCA);
+    data.encode_integer_u32(0);
+    data.encode_integer_u32(123);
+    data.encode_integer_u32((HEADER_LENGTH + nodes.len()) as u32);
+    data.extend_from_slice(&nodes);
+
+    data.encode_integer_u16(3);
+    data.encode_packedfile_string_u8("ROOT");
+    data.encode_packedfile_string_u8("FACTION");
+    data.encode_packedfile_string_u8("FACTION_ARRAY");
+
+    data.encode_integer_u32(1);
+    data.encode_packedfile_string_u16("Hellö");
+    data.encode_integer_u32(0);
+
+    data.encode_integer_u32(2);
+    data.encode_packedfile_string_u8("wh_main_emp_empire");
+    data.encode_integer_u32(5);
+    data.encode_packedfile_string_u8("a&b <c>\n\t");
+    data.encode_integer_u32(7);
+    data
+}
+
+/// This function builds a raw ESF with the provided amount of empty records nested inside each other.
+fn build_nested_esf(depth: usize) -> Vec<u8> {
+    let mut nodes = vec![];
+    for _ in 0..depth {
+        let mut record = vec![RECORD, 0];
+        encode_size(&mut record, nodes.len());
+        record.extend_from_slice(&nodes);
+        nodes = record;
+    }
+
+    let mut data = vec![];
+    data.encode_integer_u32(0xABCA);
+    data.encode_integer_u32(0);
+    data.encode_integer_u32(0);
+    data.encode_integer_u32((HEADER_LENGTH + nodes.len()) as u32);
+    data.extend_from_slice(&nodes);
+    data.encode_integer_u16(1);
+    data.encode_packedfile_string_u8("ROOT");
+    data.encode_integer_u32(0);
+    data.encode_integer_u32(0);
+    data
+}
+
+#[test]
+fn test_esf() {
+    let data = build_esf();
+    assert!(ESF::is_esf(&data));
+    let esf = ESF::read(&data).unwrap();
+    assert_eq!(*esf.get_ref_signature(), ESFSignature::ABCA);
+    assert_eq!(*esf.get_ref_creation_date(), 123);
+
+    let root = esf.get_ref_root_node();
+    assert_eq!(root.get_ref_name(), "ROOT");
+    assert_eq!(*root.get_ref_version(), 1);
+    let children = root.get_ref_children();
+    assert_eq!(children.len(), 12);
+    assert_eq!(children[0], NodeType::U32Byte(200));
+    assert_eq!(children[1], NodeType::I32Int24(-5));
+    assert_eq!(children[2].get_bool(), Some(true));
+    assert_eq!(children[3], NodeType::F64(1.5));
+    assert_eq!(children[4], NodeType::Coord2d([1.0, -2.5]));
+    assert_eq!(children[5], NodeType::Utf16("Hellö".to_owned()));
+    assert_eq!(children[6], NodeType::U32ByteArray(vec![1, 2, 3]));
+    assert_eq!(children[7], NodeType::AsciiArray(vec!["wh_main_emp_empire".to_owned(), "a&b <c>\n\t".to_owned()]));
+    assert_eq!(children[9], NodeType::U8Array(vec![7; 200]));
+
+    // Navigation through records and entries of record blocks.
+    let faction = esf.get_ref_record_by_path(&["ROOT", "FACTION"]).unwrap();
+    assert_eq!(*faction.get_ref_version(), 20);
+    assert_eq!(faction.get_ref_children(), &[NodeType::Ascii("wh_main_emp_empire".to_owned())]);
+
+    let faction = esf.get_ref_record_by_path(&["ROOT", "FACTION_ARRAY[0]", "FACTION"]).unwrap();
+    assert_eq!(*faction.get_ref_version(), 3);
+    assert_eq!(faction.get_ref_children().iter().map(|node| node.get_u32().or_else(|| node.get_i32().map(|x| x as u32))).collect::<Vec<Option<u32>>>(), vec![Some(1), Some(0)]);
+    assert!(esf.get_ref_record_by_path(&["ROOT", "FACTION_ARRAY[1]", "FACTION"]).is_none());
+    assert!(esf.get_ref_record_by_path(&["FACTION"]).is_none());
+    assert_eq!(esf.get_ref_root_node().get_ref_record_block("FACTION_ARRAY").unwrap().get_ref_entries().len(), 2);
+    assert_eq!(esf.find_records("FACTION").len(), 2);
+
+    // Unedited files must be saved exactly as they were.
+    assert_eq!(esf.save().unwrap(), data);
+
+    // Broken files.
+    assert!(ESF::read(&data[..data.len() - 1]).is_err());
+    assert!(ESF::read(&build_nested_esf(MAX_DEPTH)).is_ok());
+    assert!(ESF::read(&build_nested_esf(MAX_DEPTH + 1)).is_err());
+    let mut broken = data.clone();
+    broken[0] = 0xCD;
+    assert!(!ESF::is_esf(&broken));
+    assert!(ESF::read(&broken).is_err());
+}
+
+#[test]
+fn test_esf_edit() {
+    let mut esf = ESF::read(&build_esf()).unwrap();
+    let faction = esf.get_ref_mut_record_by_path(&["ROOT", "FACTION_ARRAY[1]"]);
+    assert!(faction.is_none());
+
+    let faction = esf.get_ref_mut_record_by_path(&["ROOT", "FACTION"]).unwrap();
+    faction.get_ref_mut_children().push(NodeType::Ascii("wh_main_dwf_dwarfs".to_owned()));
+    faction.get_ref_mut_children().push(NodeType::new_u32(70000));
+    faction.get_ref_mut_children().push(NodeType::new_i32(-200));
+    faction.get_ref_mut_children().push(NodeType::Record(RecordNode::new("ARMY", 2)));
+
+    let edited = ESF::read(&esf.save().unwrap()).unwrap();
+    let faction = edited.get_ref_record_by_path(&["ROOT", "FACTION"]).unwrap();
+    assert_eq!(faction.get_ref_children()[1], NodeType::Ascii("wh_main_dwf_dwarfs".to_owned()));
+    assert_eq!(faction.get_ref_children()[2], NodeType::U32Int24(70000));
+    assert_eq!(faction.get_ref_children()[3], NodeType::I32Short(-200));
+    assert!(faction.get_ref_record("ARMY").is_some());
+
+    // New strings and names go to the end of their tables.
+    assert_eq!(edited.get_ref_ascii_strings()[2], ("wh_main_dwf_dwarfs".to_owned(), 8));
+    assert_eq!(edited.get_ref_record_names()[3], "ARMY");
+
+    // Optimised nodes with values that don't fit in them cannot be saved.
+    esf.get_ref_mut_root_node().get_ref_mut_children()[0] = NodeType::U32Byte(300);
+    assert!(esf.save().is_err());
+    esf.get_ref_mut_root_node().get_ref_mut_children()[0] = NodeType::I32Int24Array(vec![0x80_0000]);
+    assert!(esf.save().is_err());
+}
+
+#[test]
+fn test_esf_dump_and_load() {
+    let data = build_esf();
+    let esf = ESF::read(&data).unwrap();
+
+    let json = esf.to_json().unwrap();
+    assert_eq!(ESF::from_json(&json).unwrap(), esf);
+
+    // NaN and infinite floats must survive the JSON dump too.
+    let mut edited = esf.clone();
+    let children = edited.get_ref_mut_root_node().get_ref_mut_children();
+    children.push(NodeType::F32(f32::INFINITY));
+    children.push(NodeType::Coord3dArray(vec![[1.0, f32::NEG_INFINITY, f32::NAN]]));
+    children.push(NodeType::F64Array(vec![f64::NAN, 0.5]));
+    let json = edited.to_json().unwrap();
+    assert!(json.contains("\"-inf\"") && !json.contains("null"));
+    let loaded = ESF::from_json(&json).unwrap();
+    assert_eq!(loaded.save().unwrap(), edited.save().unwrap());
+    assert!(ESF::from_json(&json.replace("\"-inf\"", "\"big\"")).is_err());
+
+    let xml = esf.to_xml().unwrap();
+    assert!(xml.contains("<u32_byte>200</u32_byte>"));
+    assert!(xml.contains("<string>a&amp;b &lt;c&gt;&#10;&#9;</string>"));
+    let loaded = ESF::from_xml(&xml).unwrap();
+    assert_eq!(loaded, esf);
+    assert_eq!(loaded.save().unwrap(), data);
+
+    assert!(ESF::from_xml("<esf signature=\"ABCA\" unknown_1=\"0\" creation_date=\"0\"><record_names/></esf>").is_err());
+    assert!(ESF::from_xml(&xml.replace("<u32_byte>200</u32_byte>", "<u32_byte>big</u32_byte>")).is_err());
+    assert!(ESF::from_xml(&xml.replace("u32_byte>", "u33>")).is_err());
+
+    // Control characters other than tabs and line jumps cannot be stored in XML.
+    let mut edited = esf.clone();
+    edited.get_ref_mut_root_node().get_ref_mut_children().push(NodeType::Ascii("bell\u{7}".to_owned()));
+    match edited.to_xml() {
+        Err(error) => assert!(matches!(error.kind(), ErrorKind::ESFEncode(_))),
+        Ok(_) => panic!("Control characters must be an error."),
+    }
+
+    let nested = ESF::read(&build_nested_esf(MAX_DEPTH)).unwrap();
+    assert_eq!(nested.save().unwrap(), build_nested_esf(MAX_DEPTH));
+    assert_eq!(ESF::from_xml(&nested.to_xml().unwrap()).unwrap(), nested);
+    assert!(ESF::from_xml(&format!("<esf>{}{}</esf>", "<record_block><entry>".repeat(MAX_DEPTH + 1), "</entry></record_block>".repeat(MAX_DEPTH + 1))).is_err());
+}
@@ -0,0 +1,128 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to dump the floats of ESF PackedFiles to JSON, and to load them back.
+
+JSON has no way to represent NaN or infinite floats, and `serde_json` writes them as `null`, which cannot be loaded back.
+To keep the dumps lossless, finite floats are written as numbers, and the rest as the strings `NaN`, `inf` and `-inf`.
+
+This is meant to be used through `#[serde(with = "json")]` on the float values of the nodes.
+!*/
+
+use serde::de::{DeserializeOwned, Deserializer, Error};
+use serde::Serializer;
+use serde_derive::{Serialize, Deserialize};
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This enum represents a float in a JSON dump.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub(super) enum JsonFloat<T> {
+    Number(T),
+    Text(String),
+}
+
+/// This trait represents a value with floats inside, which can be converted to and from its representation in a JSON dump.
+pub(super) trait Floats: Sized {
+
+    /// Type of the representation of the value in a JSON dump.
+    type Json: serde::Serialize + DeserializeOwned;
+
+    /// This function returns the representation of the value in a JSON dump.
+    fn to_json(&self) -> Self::Json;
+
+    /// This function returns the value from its representation in a JSON dump, or the text of the float that's not valid.
+    fn from_json(json: Self::Json) -> Result<Self, String>;
+}
+
+//---------------------------------------------------------------------------//
+//                           Implementation of Floats
+//---------------------------------------------------------------------------//
+
+/// Macro to implement `Floats` for the float types.
+macro_rules! impl_floats {
+    ($($float:ty),*) => {$(
+        impl Floats for $float {
+            type Json = JsonFloat<$float>;
+
+            fn to_json(&self) -> Self::Json {
+                if self.is_finite() { JsonFloat::Number(*self) } else { JsonFloat::Text(self.to_string()) }
+            }
+
+            fn from_json(json: Self::Json) -> Result<Self, String> {
+                match json {
+                    JsonFloat::Number(value) => Ok(value),
+                    JsonFloat::Text(text) => text.parse().map_err(|_| text),
+                }
+            }
+        }
+    )*}
+}
+
+impl_floats!(f32, f64);
+
+/// Implementation of `Floats` for 2D coordinates.
+impl Floats for [f32; 2] {
+    type Json = [JsonFloat<f32>; 2];
+
+    fn to_json(&self) -> Self::Json {
+        [self[0].to_json(), self[1].to_json()]
+    }
+
+    fn from_json(json: Self::Json) -> Result<Self, String> {
+        let [x, y] = json;
+        Ok([f32::from_json(x)?, f32::from_json(y)?])
+    }
+}
+
+/// Implementation of `Floats` for 3D coordinates.
+impl Floats for [f32; 3] {
+    type Json = [JsonFloat<f32>; 3];
+
+    fn to_json(&self) -> Self::Json {
+        [self[0].to_json(), self[1].to_json(), self[2].to_json()]
+    }
+
+    fn from_json(json: Self::Json) -> Result<Self, String> {
+        let [x, y, z] = json;
+        Ok([f32::from_json(x)?, f32::from_json(y)?, f32::from_json(z)?])
+    }
+}
+
+/// Implementation of `Floats` for arrays of values with floats.
+impl<T: Floats> Floats for Vec<T> {
+    type Json = Vec<T::Json>;
+
+    fn to_json(&self) -> Self::Json {
+        self.iter().map(|value| value.to_json()).collect()
+    }
+
+    fn from_json(json: Self::Json) -> Result<Self, String> {
+        json.into_iter().map(T::from_json).collect()
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Serde functions
+//---------------------------------------------------------------------------//
+
+/// This function serializes a value with floats in its JSON representation.
+pub(super) fn serialize<T: Floats, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&value.to_json(), serializer)
+}
+
+/// This function deserializes a value with floats from its JSON representation.
+pub(super) fn deserialize<'de, T: Floats, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::from_json(serde::Deserialize::deserialize(deserializer)?).map_err(|text| D::Error::custom(format!("invalid float: {}", text)))
+}
@@ -0,0 +1,1211 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to interact with ESF PackedFiles.
+
+ESF files are CA's binary tree format, used by the startpos of the campaigns and a few other files. The structure of an ESF is:
+- A header, with the signature, an unknown value, the date the file was created and the offset of the string tables.
+- The root node, a record with all the other nodes of the file inside.
+- The string tables: the names of the records, and the UTF-16 and ASCII strings used by the nodes.
+
+Nodes can be values (with many optimised variants, like `U32Zero` or `I32Byte`), arrays of values, records (named lists of nodes)
+and record blocks (named lists of entries, each one being a list of nodes). Only the `0xABCA` and `0xABCB` variants are supported.
+Both use the same layout, with variable-length sizes and compact record headers.
+
+We keep the exact type of each node and the string tables of the file, so unedited files are saved exactly as they were read.
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_macros::*;
+
+use crate::common::{decoder::Decoder, encoder::Encoder};
+
+mod json;
+mod xml;
+
+#[cfg(test)]
+mod esf_test;
+
+/// Extension used by ESF PackedFiles.
+pub const EXTENSION: &str = ".esf";
+
+/// Length of the header of an ESF, in bytes.
+const HEADER_LENGTH: usize = 16;
+
+// Type codes of the value nodes.
+const BOOL: u8 = 0x01;
+const I8: u8 = 0x02;
+const I16: u8 = 0x03;
+const I32: u8 = 0x04;
+const I64: u8 = 0x05;
+const U8: u8 = 0x06;
+const U16: u8 = 0x07;
+const U32: u8 = 0x08;
+const U64: u8 = 0x09;
+const F32: u8 = 0x0A;
+const F64: u8 = 0x0B;
+const COORD_2D: u8 = 0x0C;
+const COORD_3D: u8 = 0x0D;
+const UTF16: u8 = 0x0E;
+const ASCII: u8 = 0x0F;
+const ANGLE: u8 = 0x10;
+
+// Type codes of the optimised value nodes.
+const BOOL_TRUE: u8 = 0x12;
+const BOOL_FALSE: u8 = 0x13;
+const U32_ZERO: u8 = 0x14;
+const U32_ONE: u8 = 0x15;
+const U32_BYTE: u8 = 0x16;
+const U32_SHORT: u8 = 0x17;
+const U32_24BIT: u8 = 0x18;
+const I32_ZERO: u8 = 0x19;
+const I32_BYTE: u8 = 0x1A;
+const I32_SHORT: u8 = 0x1B;
+const I32_24BIT: u8 = 0x1C;
+const F32_ZERO: u8 = 0x1D;
+
+/// Flag of array nodes. Arrays use the type code of their values plus this flag.
+const ARRAY: u8 = 0x40;
+
+/// Flag of record nodes.
+const RECORD: u8 = 0x80;
+
+/// Flag of record nodes that are blocks of entries instead of single records.
+const RECORD_BLOCK: u8 = 0x40;
+
+/// Flag of record nodes with their name index and version in full, instead of packed in two bytes.
+const RECORD_LONG: u8 = 0x20;
+
+/// Max name index a compact record header can hold.
+const RECORD_COMPACT_MAX_NAME_INDEX: u16 = 0x1FF;
+
+/// Max version a compact record header can hold.
+const RECORD_COMPACT_MAX_VERSION: u8 = 0x0F;
+
+/// Max amount of records nested inside each other we accept. Real files are way below this, and without it a crafted file
+/// with deeply nested records can overflow the stack. Reading, saving and dumping a file this deep fits in the stack of a thread, even in debug builds.
+const MAX_DEPTH: usize = 64;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains an ESF decoded in memory.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct ESF {
+
+    /// Signature of the ESF. It determines the variant of the format.
+    signature: ESFSignature,
+
+    /// Unknown value. Usually 0.
+    unknown_1: u32,
+
+    /// Date the file was created, as a timestamp.
+    creation_date: u32,
+
+    /// Root record of the ESF, with all the other nodes inside.
+    root_node: RecordNode,
+
+    /// Names of the records, in the order they're stored in the file. Records reference them by position.
+    record_names: Vec<String>,
+
+    /// UTF-16 strings of the file, with the index nodes use to reference them.
+    utf16_strings: Vec<(String, u32)>,
+
+    /// ASCII strings of the file, with the index nodes use to reference them.
+    ascii_strings: Vec<(String, u32)>,
+}
+
+/// This enum represents the variants of the ESF format we support.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ESFSignature {
+    ABCA,
+    ABCB,
+}
+
+/// This struct represents a record of an ESF: a named list of nodes.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct RecordNode {
+
+    /// Name of the record.
+    name: String,
+
+    /// Version of the record.
+    version: u8,
+
+    /// Nodes inside the record.
+    children: Vec<NodeType>,
+}
+
+/// This struct represents a record block of an ESF: a named list of entries, each one being a list of nodes.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct RecordBlockNode {
+
+    /// Name of the record block.
+    name: String,
+
+    /// Version of the record block.
+    version: u8,
+
+    /// Entries of the record block.
+    entries: Vec<Vec<NodeType>>,
+}
+
+/// This enum represents a node of an ESF, with its value.
+///
+/// Optimised variants keep their value in the type of its non-optimised variant, to make editing them easier. When saving,
+/// their value must fit in the optimised type, or the encoding will fail.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NodeType {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    F32(#[serde(with = "json")] f32),
+    F64(#[serde(with = "json")] f64),
+    Coord2d(#[serde(with = "json")] [f32; 2]),
+    Coord3d(#[serde(with = "json")] [f32; 3]),
+    Utf16(String),
+    Ascii(String),
+    Angle(u16),
+
+    BoolTrue,
+    BoolFalse,
+    U32Zero,
+    U32One,
+    U32Byte(u32),
+    U32Short(u32),
+    U32Int24(u32),
+    I32Zero,
+    I32Byte(i32),
+    I32Short(i32),
+    I32Int24(i32),
+    F32Zero,
+
+    BoolArray(Vec<bool>),
+    I8Array(Vec<i8>),
+    I16Array(Vec<i16>),
+    I32Array(Vec<i32>),
+    I64Array(Vec<i64>),
+    U8Array(Vec<u8>),
+    U16Array(Vec<u16>),
+    U32Array(Vec<u32>),
+    U64Array(Vec<u64>),
+    F32Array(#[serde(with = "json")] Vec<f32>),
+    F64Array(#[serde(with = "json")] Vec<f64>),
+    Coord2dArray(#[serde(with = "json")] Vec<[f32; 2]>),
+    Coord3dArray(#[serde(with = "json")] Vec<[f32; 3]>),
+    Utf16Array(Vec<String>),
+    AsciiArray(Vec<String>),
+    AngleArray(Vec<u16>),
+
+    U32ByteArray(Vec<u32>),
+    U32ShortArray(Vec<u32>),
+    U32Int24Array(Vec<u32>),
+    I32ByteArray(Vec<i32>),
+    I32ShortArray(Vec<i32>),
+    I32Int24Array(Vec<i32>),
+
+    Record(RecordNode),
+    RecordBlock(RecordBlockNode),
+}
+
+/// This struct contains the string tables of an ESF while decoding or encoding its nodes.
+struct StringTables {
+
+    /// Names of the records, by position.
+    record_names: Vec<String>,
+
+    /// Position of each record name, to reuse them when encoding.
+    record_names_positions: HashMap<String, u16>,
+
+    /// UTF-16 strings.
+    utf16_strings: StringTable,
+
+    /// ASCII strings.
+    ascii_strings: StringTable,
+}
+
+/// This struct contains one of the indexed string tables of an ESF.
+#[derive(Default)]
+struct StringTable {
+
+    /// Strings with their indexes, in the order they're stored.
+    strings: Vec<(String, u32)>,
+
+    /// Position in `strings` of each index, for decoding.
+    positions: HashMap<u32, usize>,
+
+    /// Index of each string, for encoding.
+    indexes: HashMap<String, u32>,
+
+    /// Index the next new string will get.
+    next_index: u32,
+}
+
+//---------------------------------------------------------------------------//
+//                           Implementation of ESF
+//---------------------------------------------------------------------------//
+
+/// Implementation of `ESF`.
+impl ESF {
+
+    /// This function creates an empty `ESF` with the provided signature and root record.
+    pub fn new(signature: ESFSignature, root_name: &str) -> Self {
+        Self {
+            signature,
+            unknown_1: 0,
+            creation_date: 0,
+            root_node: RecordNode::new(root_name, 0),
+            record_names: vec![],
+            utf16_strings: vec![],
+            ascii_strings: vec![],
+        }
+    }
+
+    /// This function returns if the provided data corresponds to an ESF we can decode or not.
+    pub fn is_esf(packed_file_data: &[u8]) -> bool {
+        match packed_file_data.decode_integer_u32(0) {
+            Ok(signature) => ESFSignature::from_u32(signature).is_some(),
+            Err(_) => false,
+        }
+    }
+
+    /// This function creates a `ESF` from a `&[u8]`.
+    pub fn read(packed_file_data: &[u8]) -> Result<Self> {
+        let signature = packed_file_data.decode_integer_u32(0)?;
+        let signature = ESFSignature::from_u32(signature).ok_or_else(|| ErrorKind::ESFDecode(format!("Unsupported signature: {:#X}.", signature)))?;
+        let unknown_1 = packed_file_data.decode_integer_u32(4)?;
+        let creation_date = packed_file_data.decode_integer_u32(8)?;
+        let string_tables_offset = packed_file_data.decode_integer_u32(12)? as usize;
+        if string_tables_offset < HEADER_LENGTH || string_tables_offset > packed_file_data.len() {
+            return Err(ErrorKind::ESFDecode(format!("Invalid offset of the string tables: {}.", string_tables_offset)).into());
+        }
+
+        // We need the string tables before reading any node, as nodes only contain indexes to them.
+        let mut index = string_tables_offset;
+        let record_names_count = packed_file_data.decode_packedfile_integer_u16(index, &mut index)?;
+        let mut record_names = Vec::with_capacity(record_names_count as usize);
+        for _ in 0..record_names_count {
+            record_names.push(packed_file_data.decode_packedfile_string_u8(index, &mut index)?);
+        }
+
+        let utf16_strings_count = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+        let mut utf16_strings = vec![];
+        for _ in 0..utf16_strings_count {
+            let string = packed_file_data.decode_packedfile_string_u16(index, &mut index)?;
+            utf16_strings.push((string, packed_file_data.decode_packedfile_integer_u32(index, &mut index)?));
+        }
+
+        let ascii_strings_count = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+        let mut ascii_strings = vec![];
+        for _ in 0..ascii_strings_count {
+            let string = packed_file_data.decode_packedfile_string_u8(index, &mut index)?;
+            ascii_strings.push((string, packed_file_data.decode_packedfile_integer_u32(index, &mut index)?));
+        }
+
+        if index != packed_file_data.len() {
+            return Err(ErrorKind::ESFDecode(format!("There are {} bytes of unknown data after the string tables.", packed_file_data.len() - index)).into());
+        }
+
+        let tables = StringTables::new(&record_names, &utf16_strings, &ascii_strings);
+        let nodes_data = &packed_file_data[..string_tables_offset];
+        let mut index = HEADER_LENGTH;
+        let root_node = match NodeType::read(nodes_data, &mut index, &tables, 0)? {
+            NodeType::Record(root_node) => root_node,
+            _ => return Err(ErrorKind::ESFDecode("The root node is not a record.".to_owned()).into()),
+        };
+
+        if index != string_tables_offset {
+            return Err(ErrorKind::ESFDecode(format!("There are {} bytes of unknown data after the root node.", string_tables_offset - index)).into());
+        }
+
+        Ok(Self {
+            signature,
+            unknown_1,
+            creation_date,
+            root_node,
+            record_names,
+            utf16_strings,
+            ascii_strings,
+        })
+    }
+
+    /// This function takes an `ESF` and encodes it to `Vec<u8>`.
+    ///
+    /// Strings and record names not in the string tables of the ESF are added to the end of them.
+    pub fn save(&self) -> Result<Vec<u8>> {
+        let mut tables = StringTables::new(&self.record_names, &self.utf16_strings, &self.ascii_strings);
+        let mut nodes_data = vec![];
+        self.root_node.save(&mut nodes_data, &mut tables)?;
+
+        let mut packed_file = Vec::with_capacity(HEADER_LENGTH + nodes_data.len());
+        packed_file.encode_integer_u32(self.signature.to_u32());
+        packed_file.encode_integer_u32(self.unknown_1);
+        packed_file.encode_integer_u32(self.creation_date);
+        packed_file.encode_integer_u32((HEADER_LENGTH + nodes_data.len()) as u32);
+        packed_file.extend_from_slice(&nodes_data);
+        tables.save(&mut packed_file);
+        Ok(packed_file)
+    }
+
+    /// This function dumps the `ESF` to a JSON string. The dump includes the string tables, so it can be loaded back without losses.
+    ///
+    /// JSON has no way to represent NaN or infinite floats, so those are dumped as the strings `NaN`, `inf` and `-inf`.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(From::from)
+    }
+
+    /// This function loads an `ESF` from a JSON dump made with `to_json`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(From::from)
+    }
+
+    /// This function returns a reference to the record at the provided path, if exists.
+    ///
+    /// The path is the list of names of the records from the root one, including it. To go into an entry of a record block,
+    /// use the name of the block with the position of the entry, like `FACTION_ARRAY[3]`. If there are more records with the same name,
+    /// the first one is returned.
+    pub fn get_ref_record_by_path(&self, path: &[&str]) -> Option<&RecordNode> {
+        let (root_name, path) = path.split_first()?;
+        if self.root_node.name != *root_name { return None }
+        if path.is_empty() { Some(&self.root_node) }
+        else { RecordNode::get_ref_record_by_path(&self.root_node.children, path) }
+    }
+
+    /// This function returns a mutable reference to the record at the provided path, if exists.
+    ///
+    /// The path works like the one of `get_ref_record_by_path`.
+    pub fn get_ref_mut_record_by_path(&mut self, path: &[&str]) -> Option<&mut RecordNode> {
+        let (root_name, path) = path.split_first()?;
+        if self.root_node.name != *root_name { return None }
+        if path.is_empty() { Some(&mut self.root_node) }
+        else { RecordNode::get_ref_mut_record_by_path(&mut self.root_node.children, path) }
+    }
+
+    /// This function returns all the records with the provided name, wherever they are in the tree, including the root one.
+    pub fn find_records(&self, name: &str) -> Vec<&RecordNode> {
+        let mut records = vec![];
+        if self.root_node.name == name {
+            records.push(&self.root_node);
+        }
+
+        NodeType::find_records(&self.root_node.children, name, &mut records);
+        records
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of ESFSignature
+//---------------------------------------------------------------------------//
+
+/// Implementation of `ESFSignature`.
+impl ESFSignature {
+
+    /// This function returns the signature corresponding to the provided value, if it's one we support.
+    fn from_u32(signature: u32) -> Option<Self> {
+        match signature {
+            0xABCA => Some(Self::ABCA),
+            0xABCB => Some(Self::ABCB),
+            _ => None,
+        }
+    }
+
+    /// This function returns the value of the signature, as stored in the file.
+    fn to_u32(self) -> u32 {
+        match self {
+            Self::ABCA => 0xABCA,
+            Self::ABCB => 0xABCB,
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of RecordNode
+//---------------------------------------------------------------------------//
+
+/// Implementation of `RecordNode`.
+impl RecordNode {
+
+    /// This function creates an empty `RecordNode` with the provided name and version.
+    pub fn new(name: &str, version: u8) -> Self {
+        Self {
+            name: name.to_owned(),
+            version,
+            children: vec![],
+        }
+    }
+
+    /// This function returns a reference to the first child record with the provided name, if any.
+    pub fn get_ref_record(&self, name: &str) -> Option<&RecordNode> {
+        self.children.iter().find_map(|node| match node {
+            NodeType::Record(record) if record.name == name => Some(record),
+            _ => None,
+        })
+    }
+
+    /// This function returns a mutable reference to the first child record with the provided name, if any.
+    pub fn get_ref_mut_record(&mut self, name: &str) -> Option<&mut RecordNode> {
+        self.children.iter_mut().find_map(|node| match node {
+            NodeType::Record(record) if record.name == name => Some(record),
+            _ => None,
+        })
+    }
+
+    /// This function returns a reference to the first child record block with the provided name, if any.
+    pub fn get_ref_record_block(&self, name: &str) -> Option<&RecordBlockNode> {
+        self.children.iter().find_map(|node| match node {
+            NodeType::RecordBlock(block) if block.name == name => Some(block),
+            _ => None,
+        })
+    }
+
+    /// This function returns a mutable reference to the first child record block with the provided name, if any.
+    pub fn get_ref_mut_record_block(&mut self, name: &str) -> Option<&mut RecordBlockNode> {
+        self.children.iter_mut().find_map(|node| match node {
+            NodeType::RecordBlock(block) if block.name == name => Some(block),
+            _ => None,
+        })
+    }
+
+    /// This function returns a reference to the record at the provided path, relative to the provided nodes.
+    fn get_ref_record_by_path<'a>(nodes: &'a [NodeType], path: &[&str]) -> Option<&'a RecordNode> {
+        let (segment, path) = path.split_first()?;
+        let nodes = match parse_path_segment(segment)? {
+            (name, None) => {
+                let record = nodes.iter().find_map(|node| match node {
+                    NodeType::Record(record) if record.name == name => Some(record),
+                    _ => None,
+                })?;
+
+                if path.is_empty() { return Some(record) }
+                &record.children
+            }
+            (name, Some(entry)) => nodes.iter().find_map(|node| match node {
+                NodeType::RecordBlock(block) if block.name == name => block.entries.get(entry),
+                _ => None,
+            })?,
+        };
+
+        Self::get_ref_record_by_path(nodes, path)
+    }
+
+    /// This function returns a mutable reference to the record at the provided path, relative to the provided nodes.
+    fn get_ref_mut_record_by_path<'a>(nodes: &'a mut [NodeType], path: &[&str]) -> Option<&'a mut RecordNode> {
+        let (segment, path) = path.split_first()?;
+        let nodes = match parse_path_segment(segment)? {
+            (name, None) => {
+                let record = nodes.iter_mut().find_map(|node| match node {
+                    NodeType::Record(record) if record.name == name => Some(record),
+                    _ => None,
+                })?;
+
+                if path.is_empty() { return Some(record) }
+                &mut record.children
+            }
+            (name, Some(entry)) => nodes.iter_mut().find_map(|node| match node {
+                NodeType::RecordBlock(block) if block.name == name => block.entries.get_mut(entry),
+                _ => None,
+            })?,
+        };
+
+        Self::get_ref_mut_record_by_path(nodes, path)
+    }
+
+    /// This function encodes the record to the provided data, adding its strings to the string tables.
+    fn save(&self, packed_file: &mut Vec<u8>, tables: &mut StringTables) -> Result<()> {
+        let mut children_data = vec![];
+        for node in &self.children {
+            node.save(&mut children_data, tables)?;
+        }
+
+        save_record_header(packed_file, RECORD, tables.add_record_name(&self.name)?, self.version);
+        encode_size(packed_file, children_data.len());
+        packed_file.extend_from_slice(&children_data);
+        Ok(())
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                     Implementation of RecordBlockNode
+//---------------------------------------------------------------------------//
+
+/// Implementation of `RecordBlockNode`.
+impl RecordBlockNode {
+
+    /// This function creates an empty `RecordBlockNode` with the provided name and version.
+    pub fn new(name: &str, version: u8) -> Self {
+        Self {
+            name: name.to_owned(),
+            version,
+            entries: vec![],
+        }
+    }
+
+    /// This function encodes the record block to the provided data, adding its strings to the string tables.
+    fn save(&self, packed_file: &mut Vec<u8>, tables: &mut StringTables) -> Result<()> {
+        let mut entries_data = vec![];
+        encode_size(&mut entries_data, self.entries.len());
+        for entry in &self.entries {
+            let mut entry_data = vec![];
+            for node in entry {
+                node.save(&mut entry_data, tables)?;
+            }
+
+            encode_size(&mut entries_data, entry_data.len());
+            entries_data.extend_from_slice(&entry_data);
+        }
+
+        save_record_header(packed_file, RECORD | RECORD_BLOCK, tables.add_record_name(&self.name)?, self.version);
+        encode_size(packed_file, entries_data.len());
+        packed_file.extend_from_slice(&entries_data);
+        Ok(())
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                        Implementation of NodeType
+//---------------------------------------------------------------------------//
+
+/// Implementation of `NodeType`.
+impl NodeType {
+
+    /// This function creates a boolean node, using its optimised variants.
+    pub fn new_bool(value: bool) -> Self {
+        if value { Self::BoolTrue } else { Self::BoolFalse }
+    }
+
+    /// This function creates an u32 node, using the smallest optimised variant the value fits in.
+    pub fn new_u32(value: u32) -> Self {
+        match value {
+            0 => Self::U32Zero,
+            1 => Self::U32One,
+            2..=0xFF => Self::U32Byte(value),
+            0x100..=0xFFFF => Self::U32Short(value),
+            0x1_0000..=0xFF_FFFF => Self::U32Int24(value),
+            _ => Self::U32(value),
+        }
+    }
+
+    /// This function creates an i32 node, using the smallest optimised variant the value fits in.
+    pub fn new_i32(value: i32) -> Self {
+        if value == 0 { Self::I32Zero }
+        else if i8::try_from(value).is_ok() { Self::I32Byte(value) }
+        else if i16::try_from(value).is_ok() { Self::I32Short(value) }
+        else if (-0x80_0000..=0x7F_FFFF).contains(&value) { Self::I32Int24(value) }
+        else { Self::I32(value) }
+    }
+
+    /// This function creates a f32 node, using the optimised variant for zero if possible.
+    pub fn new_f32(value: f32) -> Self {
+        if value.to_bits() == 0 { Self::F32Zero } else { Self::F32(value) }
+    }
+
+    /// This function returns the value of a boolean node, whatever its variant is.
+    pub fn get_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            Self::BoolTrue => Some(true),
+            Self::BoolFalse => Some(false),
+            _ => None,
+        }
+    }
+
+    /// This function returns the value of an u32 node, whatever its variant is.
+    pub fn get_u32(&self) -> Option<u32> {
+        match self {
+            Self::U32(value) |
+            Self::U32Byte(value) |
+            Self::U32Short(value) |
+            Self::U32Int24(value) => Some(*value),
+            Self::U32Zero => Some(0),
+            Self::U32One => Some(1),
+            _ => None,
+        }
+    }
+
+    /// This function returns the value of an i32 node, whatever its variant is.
+    pub fn get_i32(&self) -> Option<i32> {
+        match self {
+            Self::I32(value) |
+            Self::I32Byte(value) |
+            Self::I32Short(value) |
+            Self::I32Int24(value) => Some(*value),
+            Self::I32Zero => Some(0),
+            _ => None,
+        }
+    }
+
+    /// This function returns the value of a f32 node, whatever its variant is.
+    pub fn get_f32(&self) -> Option<f32> {
+        match self {
+            Self::F32(value) => Some(*value),
+            Self::F32Zero => Some(0.0),
+            _ => None,
+        }
+    }
+
+    /// This function adds all the records with the provided name under the provided nodes to the provided list.
+    fn find_records<'a>(nodes: &'a [NodeType], name: &str, records: &mut Vec<&'a RecordNode>) {
+        for node in nodes {
+            match node {
+                NodeType::Record(record) => {
+                    if record.name == name {
+                        records.push(record);
+                    }
+                    Self::find_records(&record.children, name, records);
+                }
+                NodeType::RecordBlock(block) => block.entries.iter().for_each(|entry| Self::find_records(entry, name, records)),
+                _ => {}
+            }
+        }
+    }
+
+    /// This function reads a node from the provided data, moving the index to the end of it.
+    ///
+    /// The depth is the amount of records this node is inside of.
+    fn read(packed_file_data: &[u8], index: &mut usize, tables: &StringTables, depth: usize) -> Result<Self> {
+        let type_code = packed_file_data.decode_packedfile_integer_u8(*index, index)?;
+        if type_code & RECORD != 0 {
+            return Self::read_record(packed_file_data, index, type_code, tables, depth);
+        }
+
+        if type_code & ARRAY != 0 {
+            let size = decode_size(packed_file_data, index)?;
+            let data = packed_file_data.get_bytes_checked(*index, size)?;
+            *index += size;
+            return Self::read_array(data, type_code, tables);
+        }
+
+        let node = match type_code {
+            BOOL => Self::Bool(packed_file_data.decode_packedfile_bool(*index, index)?),
+            I8 => Self::I8(packed_file_data.decode_packedfile_integer_i8(*index, index)?),
+            I16 => Self::I16(packed_file_data.decode_packedfile_integer_i16(*index, index)?),
+            I32 => Self::I32(packed_file_data.decode_packedfile_integer_i32(*index, index)?),
+            I64 => Self::I64(packed_file_data.decode_packedfile_integer_i64(*index, index)?),
+            U8 => Self::U8(packed_file_data.decode_packedfile_integer_u8(*index, index)?),
+            U16 => Self::U16(packed_file_data.decode_packedfile_integer_u16(*index, index)?),
+            U32 => Self::U32(packed_file_data.decode_packedfile_integer_u32(*index, index)?),
+            U64 => Self::U64(packed_file_data.decode_packedfile_integer_u64(*index, index)?),
+            F32 => Self::F32(packed_file_data.decode_packedfile_float_f32(*index, index)?),
+            F64 => Self::F64(decode_f64(read_bytes(packed_file_data, index, 8)?)),
+            COORD_2D => Self::Coord2d(decode_coord_2d(read_bytes(packed_file_data, index, 8)?)),
+            COORD_3D => Self::Coord3d(decode_coord_3d(read_bytes(packed_file_data, index, 12)?)),
+            UTF16 => Self::Utf16(tables.utf16_strings.get(packed_file_data.decode_packedfile_integer_u32(*index, index)?)?),
+            ASCII => Self::Ascii(tables.ascii_strings.get(packed_file_data.decode_packedfile_integer_u32(*index, index)?)?),
+            ANGLE => Self::Angle(packed_file_data.decode_packedfile_integer_u16(*index, index)?),
+
+            BOOL_TRUE => Self::BoolTrue,
+            BOOL_FALSE => Self::BoolFalse,
+            U32_ZERO => Self::U32Zero,
+            U32_ONE => Self::U32One,
+            U32_BYTE => Self::U32Byte(u32::from(packed_file_data.decode_packedfile_integer_u8(*index, index)?)),
+            U32_SHORT => Self::U32Short(u32::from(packed_file_data.decode_packedfile_integer_u16(*index, index)?)),
+            U32_24BIT => Self::U32Int24(decode_u24(read_bytes(packed_file_data, index, 3)?)),
+            I32_ZERO => Self::I32Zero,
+            I32_BYTE => Self::I32Byte(i32::from(packed_file_data.decode_packedfile_integer_i8(*index, index)?)),
+            I32_SHORT => Self::I32Short(i32::from(packed_file_data.decode_packedfile_integer_i16(*index, index)?)),
+            I32_24BIT => Self::I32Int24(decode_i24(read_bytes(packed_file_data, index, 3)?)),
+            F32_ZERO => Self::F32Zero,
+            _ => return Err(ErrorKind::ESFDecode(format!("Unknown node type {:#X} at byte {}.", type_code, *index - 1)).into()),
+        };
+
+        Ok(node)
+    }
+
+    /// This function reads the values of an array node from its data.
+    fn read_array(data: &[u8], type_code: u8, tables: &StringTables) -> Result<Self> {
+        let node = match type_code & !ARRAY {
+            BOOL => Self::BoolArray(read_array(data, 1, |x| x.decode_bool(0))?),
+            I8 => Self::I8Array(read_array(data, 1, |x| x.decode_integer_i8(0))?),
+            I16 => Self::I16Array(read_array(data, 2, |x| x.decode_integer_i16(0))?),
+            I32 => Self::I32Array(read_array(data, 4, |x| x.decode_integer_i32(0))?),
+            I64 => Self::I64Array(read_array(data, 8, |x| x.decode_integer_i64(0))?),
+            U8 => Self::U8Array(data.to_vec()),
+            U16 => Self::U16Array(read_array(data, 2, |x| x.decode_integer_u16(0))?),
+            U32 => Self::U32Array(read_array(data, 4, |x| x.decode_integer_u32(0))?),
+            U64 => Self::U64Array(read_array(data, 8, |x| x.decode_integer_u64(0))?),
+            F32 => Self::F32Array(read_array(data, 4, |x| x.decode_float_f32(0))?),
+            F64 => Self::F64Array(read_array(data, 8, |x| Ok(decode_f64(x)))?),
+            COORD_2D => Self::Coord2dArray(read_array(data, 8, |x| Ok(decode_coord_2d(x)))?),
+            COORD_3D => Self::Coord3dArray(read_array(data, 12, |x| Ok(decode_coord_3d(x)))?),
+            UTF16 => Self::Utf16Array(read_array(data, 4, |x| tables.utf16_strings.get(x.decode_integer_u32(0)?))?),
+            ASCII => Self::AsciiArray(read_array(data, 4, |x| tables.ascii_strings.get(x.decode_integer_u32(0)?))?),
+            ANGLE => Self::AngleArray(read_array(data, 2, |x| x.decode_integer_u16(0))?),
+
+            U32_BYTE => Self::U32ByteArray(read_array(data, 1, |x| Ok(u32::from(x[0])))?),
+            U32_SHORT => Self::U32ShortArray(read_array(data, 2, |x| Ok(u32::from(x.decode_integer_u16(0)?)))?),
+            U32_24BIT => Self::U32Int24Array(read_array(data, 3, |x| Ok(decode_u24(x)))?),
+            I32_BYTE => Self::I32ByteArray(read_array(data, 1, |x| Ok(i32::from(x[0] as i8)))?),
+            I32_SHORT => Self::I32ShortArray(read_array(data, 2, |x| Ok(i32::from(x.decode_integer_i16(0)?)))?),
+            I32_24BIT => Self::I32Int24Array(read_array(data, 3, |x| Ok(decode_i24(x)))?),
+            _ => return Err(ErrorKind::ESFDecode(format!("Unknown array node type {:#X}.", type_code)).into()),
+        };
+
+        Ok(node)
+    }
+
+    /// This function reads a record or record block node, whose first byte has already been read.
+    fn read_record(packed_file_data: &[u8], index: &mut usize, type_code: u8, tables: &StringTables, depth: usize) -> Result<Self> {
+        if depth >= MAX_DEPTH {
+            return Err(ErrorKind::ESFDecode(format!("The record at byte {} is nested inside more than {} records.", *index - 1, MAX_DEPTH)).into());
+        }
+
+        // Records have either their name index and version packed in two bytes, or in full after the type code.
+        let (name_index, version) = if type_code & RECORD_LONG != 0 {
+            let name_index = packed_file_data.decode_packedfile_integer_u16(*index, index)?;
+            (name_index, packed_file_data.decode_packedfile_integer_u8(*index, index)?)
+        } else {
+            let name_index = (u16::from(type_code & 1) << 8) | u16::from(packed_file_data.decode_packedfile_integer_u8(*index, index)?);
+            (name_index, (type_code & 0x1F) >> 1)
+        };
+
+        let name = match tables.record_names.get(name_index as usize) {
+            Some(name) => name.to_owned(),
+            None => return Err(ErrorKind::ESFDecode(format!("Record with unknown name index {} found.", name_index)).into()),
+        };
+
+        let size = decode_size(packed_file_data, index)?;
+        let end = index.checked_add(size).filter(|end| *end <= packed_file_data.len()).ok_or_else(|| ErrorKind::ESFDecode(format!("The record {} goes beyond the end of the data.", name)))?;
+        let data = &packed_file_data[..end];
+
+        if type_code & RECORD_BLOCK == 0 {
+            let mut children = vec![];
+            while *index < end {
+                children.push(Self::read(data, index, tables, depth + 1)?);
+            }
+
+            Ok(Self::Record(RecordNode {
+                name,
+                version,
+                children,
+            }))
+        }
+
+        else {
+            let entry_count = decode_size(data, index)?;
+            let mut entries = vec![];
+            for _ in 0..entry_count {
+                let entry_size = decode_size(data, index)?;
+                let entry_end = index.checked_add(entry_size).filter(|entry_end| *entry_end <= end).ok_or_else(|| ErrorKind::ESFDecode(format!("An entry of the record block {} goes beyond the end of the block.", name)))?;
+                let entry_data = &data[..entry_end];
+                let mut entry = vec![];
+                while *index < entry_end {
+                    entry.push(Self::read(entry_data, index, tables, depth + 1)?);
+                }
+                entries.push(entry);
+            }
+
+            if *index != end {
+                return Err(ErrorKind::ESFDecode(format!("The record block {} has {} bytes of unknown data at its end.", name, end - *index)).into());
+            }
+
+            Ok(Self::RecordBlock(RecordBlockNode {
+                name,
+                version,
+                entries,
+            }))
+        }
+    }
+
+    /// This function encodes the node to the provided data, adding its strings to the string tables.
+    fn save(&self, packed_file: &mut Vec<u8>, tables: &mut StringTables) -> Result<()> {
+        match self {
+            Self::Bool(value) => {
+                packed_file.push(BOOL);
+                packed_file.encode_bool(*value);
+            }
+            Self::I8(value) => {
+                packed_file.push(I8);
+                packed_file.encode_integer_i8(*value);
+            }
+            Self::I16(value) => {
+                packed_file.push(I16);
+                packed_file.encode_integer_i16(*value);
+            }
+            Self::I32(value) => {
+                packed_file.push(I32);
+                packed_file.encode_integer_i32(*value);
+            }
+            Self::I64(value) => {
+                packed_file.push(I64);
+                packed_file.encode_integer_i64(*value);
+            }
+            Self::U8(value) => packed_file.extend_from_slice(&[U8, *value]),
+            Self::U16(value) => {
+                packed_file.push(U16);
+                packed_file.encode_integer_u16(*value);
+            }
+            Self::U32(value) => {
+                packed_file.push(U32);
+                packed_file.encode_integer_u32(*value);
+            }
+            Self::U64(value) => {
+                packed_file.push(U64);
+                packed_file.encode_integer_u64(*value);
+            }
+            Self::F32(value) => {
+                packed_file.push(F32);
+                packed_file.encode_float_f32(*value);
+            }
+            Self::F64(value) => {
+                packed_file.push(F64);
+                packed_file.extend_from_slice(&value.to_le_bytes());
+            }
+            Self::Coord2d(value) => {
+                packed_file.push(COORD_2D);
+                value.iter().for_each(|x| packed_file.encode_float_f32(*x));
+            }
+            Self::Coord3d(value) => {
+                packed_file.push(COORD_3D);
+                value.iter().for_each(|x| packed_file.encode_float_f32(*x));
+            }
+            Self::Utf16(value) => {
+                packed_file.push(UTF16);
+                packed_file.encode_integer_u32(tables.utf16_strings.add(value));
+            }
+            Self::Ascii(value) => {
+                packed_file.push(ASCII);
+                packed_file.encode_integer_u32(tables.ascii_strings.add(value));
+            }
+            Self::Angle(value) => {
+                packed_file.push(ANGLE);
+                packed_file.encode_integer_u16(*value);
+            }
+
+            Self::BoolTrue => packed_file.push(BOOL_TRUE),
+            Self::BoolFalse => packed_file.push(BOOL_FALSE),
+            Self::U32Zero => packed_file.push(U32_ZERO),
+            Self::U32One => packed_file.push(U32_ONE),
+            Self::U32Byte(value) => {
+                packed_file.push(U32_BYTE);
+                encode_u8(packed_file, *value)?;
+            }
+            Self::U32Short(value) => {
+                packed_file.push(U32_SHORT);
+                encode_u16(packed_file, *value)?;
+            }
+            Self::U32Int24(value) => {
+                packed_file.push(U32_24BIT);
+                encode_u24(packed_file, *value)?;
+            }
+            Self::I32Zero => packed_file.push(I32_ZERO),
+            Self::I32Byte(value) => {
+                packed_file.push(I32_BYTE);
+                encode_i8(packed_file, *value)?;
+            }
+            Self::I32Short(value) => {
+                packed_file.push(I32_SHORT);
+                encode_i16(packed_file, *value)?;
+            }
+            Self::I32Int24(value) => {
+                packed_file.push(I32_24BIT);
+                encode_i24(packed_file, *value)?;
+            }
+            Self::F32Zero => packed_file.push(F32_ZERO),
+
+            Self::BoolArray(values) => save_array(packed_file, BOOL, values, |data, x| { data.encode_bool(*x); Ok(()) })?,
+            Self::I8Array(values) => save_array(packed_file, I8, values, |data, x| { data.encode_integer_i8(*x); Ok(()) })?,
+            Self::I16Array(values) => save_array(packed_file, I16, values, |data, x| { data.encode_integer_i16(*x); Ok(()) })?,
+            Self::I32Array(values) => save_array(packed_file, I32, values, |data, x| { data.encode_integer_i32(*x); Ok(()) })?,
+            Self::I64Array(values) => save_array(packed_file, I64, values, |data, x| { data.encode_integer_i64(*x); Ok(()) })?,
+            Self::U8Array(values) => save_array(packed_file, U8, values, |data, x| { data.push(*x); Ok(()) })?,
+            Self::U16Array(values) => save_array(packed_file, U16, values, |data, x| { data.encode_integer_u16(*x); Ok(()) })?,
+            Self::U32Array(values) => save_array(packed_file, U32, values, |data, x| { data.encode_integer_u32(*x); Ok(()) })?,
+            Self::U64Array(values) => save_array(packed_file, U64, values, |data, x| { data.encode_integer_u64(*x); Ok(()) })?,
+            Self::F32Array(values) => save_array(packed_file, F32, values, |data, x| { data.encode_float_f32(*x); Ok(()) })?,
+            Self::F64Array(values) => save_array(packed_file, F64, values, |data, x| { data.extend_from_slice(&x.to_le_bytes()); Ok(()) })?,
+            Self::Coord2dArray(values) => save_array(packed_file, COORD_2D, values, |data, x| { x.iter().for_each(|x| data.encode_float_f32(*x)); Ok(()) })?,
+            Self::Coord3dArray(values) => save_array(packed_file, COORD_3D, values, |data, x| { x.iter().for_each(|x| data.encode_float_f32(*x)); Ok(()) })?,
+            Self::Utf16Array(values) => save_array(packed_file, UTF16, values, |data, x| { data.encode_integer_u32(tables.utf16_strings.add(x)); Ok(()) })?,
+            Self::AsciiArray(values) => save_array(packed_file, ASCII, values, |data, x| { data.encode_integer_u32(tables.ascii_strings.add(x)); Ok(()) })?,
+            Self::AngleArray(values) => save_array(packed_file, ANGLE, values, |data, x| { data.encode_integer_u16(*x); Ok(()) })?,
+
+            Self::U32ByteArray(values) => save_array(packed_file, U32_BYTE, values, |data, x| encode_u8(data, *x))?,
+            Self::U32ShortArray(values) => save_array(packed_file, U32_SHORT, values, |data, x| encode_u16(data, *x))?,
+            Self::U32Int24Array(values) => save_array(packed_file, U32_24BIT, values, |data, x| encode_u24(data, *x))?,
+            Self::I32ByteArray(values) => save_array(packed_file, I32_BYTE, values, |data, x| encode_i8(data, *x))?,
+            Self::I32ShortArray(values) => save_array(packed_file, I32_SHORT, values, |data, x| encode_i16(data, *x))?,
+            Self::I32Int24Array(values) => save_array(packed_file, I32_24BIT, values, |data, x| encode_i24(data, *x))?,
+
+            Self::Record(record) => record.save(packed_file, tables)?,
+            Self::RecordBlock(block) => block.save(packed_file, tables)?,
+        }
+
+        Ok(())
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                      Implementation of StringTables
+//---------------------------------------------------------------------------//
+
+/// Implementation of `StringTables`.
+impl StringTables {
+
+    /// This function creates the string tables from the ones of an ESF.
+    fn new(record_names: &[String], utf16_strings: &[(String, u32)], ascii_strings: &[(String, u32)]) -> Self {
+        let mut record_names_positions = HashMap::new();
+        for (position, name) in record_names.iter().enumerate() {
+            record_names_positions.entry(name.to_owned()).or_insert(position as u16);
+        }
+
+        Self {
+            record_names: record_names.to_vec(),
+            record_names_positions,
+            utf16_strings: StringTable::new(utf16_strings),
+            ascii_strings: StringTable::new(ascii_strings),
+        }
+    }
+
+    /// This function returns the position of the provided record name, adding it to the table if it's not yet in it.
+    fn add_record_name(&mut self, name: &str) -> Result<u16> {
+        if let Some(position) = self.record_names_positions.get(name) {
+            return Ok(*position);
+        }
+
+        let position = u16::try_from(self.record_names.len()).map_err(|_| ErrorKind::ESFEncode("There are too many record names.".to_owned()))?;
+        self.record_names.push(name.to_owned());
+        self.record_names_positions.insert(name.to_owned(), position);
+        Ok(position)
+    }
+
+    /// This function encodes the string tables to the provided data.
+    fn save(&self, packed_file: &mut Vec<u8>) {
+        packed_file.encode_integer_u16(self.record_names.len() as u16);
+        self.record_names.iter().for_each(|name| packed_file.encode_packedfile_string_u8(name));
+
+        packed_file.encode_integer_u32(self.utf16_strings.strings.len() as u32);
+        for (string, index) in &self.utf16_strings.strings {
+            packed_file.encode_packedfile_string_u16(string);
+            packed_file.encode_integer_u32(*index);
+        }
+
+        packed_file.encode_integer_u32(self.ascii_strings.strings.len() as u32);
+        for (string, index) in &self.ascii_strings.strings {
+            packed_file.encode_packedfile_string_u8(string);
+            packed_file.encode_integer_u32(*index);
+        }
+    }
+}
+
+/// Implementation of `StringTable`.
+impl StringTable {
+
+    /// This function creates a string table from a list of strings with their indexes.
+    fn new(strings: &[(String, u32)]) -> Self {
+        let mut table = Self::default();
+        for (position, (string, index)) in strings.iter().enumerate() {
+            table.positions.entry(*index).or_insert(position);
+            table.indexes.entry(string.to_owned()).or_insert(*index);
+            table.next_index = table.next_index.max(index.saturating_add(1));
+        }
+
+        table.strings = strings.to_vec();
+        table
+    }
+
+    /// This function returns the string with the provided index.
+    fn get(&self, index: u32) -> Result<String> {
+        match self.positions.get(&index) {
+            Some(position) => Ok(self.strings[*position].0.to_owned()),
+            None => Err(ErrorKind::ESFDecode(format!("String with unknown index {} found.", index)).into()),
+        }
+    }
+
+    /// This function returns the index of the provided string, adding it to the table if it's not yet in it.
+    fn add(&mut self, string: &str) -> u32 {
+        if let Some(index) = self.indexes.get(string) {
+            return *index;
+        }
+
+        let index = self.next_index;
+        self.next_index += 1;
+        self.positions.insert(index, self.strings.len());
+        self.indexes.insert(string.to_owned(), index);
+        self.strings.push((string.to_owned(), index));
+        index
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Helper functions
+//---------------------------------------------------------------------------//
+
+/// This function splits a segment of a record path into the name and the position of the entry, if it points to an entry of a record block.
+fn parse_path_segment(segment: &str) -> Option<(&str, Option<usize>)> {
+    if segment.ends_with(']') {
+        let start = segment.rfind('[')?;
+        let entry = segment[start + 1..segment.len() - 1].parse().ok()?;
+        Some((&segment[..start], Some(entry)))
+    } else {
+        Some((segment, None))
+    }
+}
+
+/// This function reads a variable-length size, moving the index to the end of it.
+///
+/// Sizes are stored in groups of 7 bits, from the most significant one, with the high bit of each byte set if more bytes follow.
+fn decode_size(packed_file_data: &[u8], index: &mut usize) -> Result<usize> {
+    let mut size = 0usize;
+    for _ in 0..5 {
+        let byte = packed_file_data.decode_packedfile_integer_u8(*index, index)?;
+        size = (size << 7) | usize::from(byte & 0x7F);
+        if byte & 0x80 == 0 {
+            return Ok(size);
+        }
+    }
+
+    Err(ErrorKind::ESFDecode(format!("Invalid size found at byte {}.", *index)).into())
+}
+
+/// This function encodes a variable-length size, as `decode_size` reads it.
+fn encode_size(packed_file: &mut Vec<u8>, size: usize) {
+    let mut bytes = vec![(size & 0x7F) as u8];
+    let mut size = size >> 7;
+    while size > 0 {
+        bytes.push((size & 0x7F) as u8 | 0x80);
+        size >>= 7;
+    }
+
+    packed_file.extend(bytes.iter().rev());
+}
+
+/// This function writes the type code and the name and version of a record. It uses the compact format if they fit in it.
+fn save_record_header(packed_file: &mut Vec<u8>, type_code: u8, name_index: u16, version: u8) {
+    if name_index <= RECORD_COMPACT_MAX_NAME_INDEX && version <= RECORD_COMPACT_MAX_VERSION {
+        packed_file.push(type_code | (version << 1) | (name_index >> 8) as u8);
+        packed_file.push(name_index as u8);
+    } else {
+        packed_file.push(type_code | RECORD_LONG);
+        packed_file.encode_integer_u16(name_index);
+        packed_file.push(version);
+    }
+}
+
+/// This function returns the provided amount of bytes from the data, moving the index to the end of them.
+fn read_bytes<'a>(packed_file_data: &'a [u8], index: &mut usize, size: usize) -> Result<&'a [u8]> {
+    let data = packed_file_data.get_bytes_checked(*index, size)?;
+    *index += size;
+    Ok(data)
+}
+
+/// This function reads the values of an array from its data, with each value being `size` bytes long.
+fn read_array<T>(data: &[u8], size: usize, decode: impl Fn(&[u8]) -> Result<T>) -> Result<Vec<T>> {
+    if data.len() % size != 0 {
+        return Err(ErrorKind::ESFDecode(format!("Array of {} bytes found, but its values are {} bytes long.", data.len(), size)).into());
+    }
+
+    data.chunks_exact(size).map(decode).collect()
+}
+
+/// This function writes an array node, with its type code, its size and its values.
+fn save_array<T>(packed_file: &mut Vec<u8>, type_code: u8, values: &[T], mut encode: impl FnMut(&mut Vec<u8>, &T) -> Result<()>) -> Result<()> {
+    let mut data = vec![];
+    for value in values {
+        encode(&mut data, value)?;
+    }
+
+    packed_file.push(type_code | ARRAY);
+    encode_size(packed_file, data.len());
+    packed_file.extend_from_slice(&data);
+    Ok(())
+}
+
+/// This function decodes a f64 from 8 bytes.
+fn decode_f64(data: &[u8]) -> f64 {
+    f64::from_le_bytes([data[0], data[1], data[2], data[3], data[4], data[5], data[6], data[7]])
+}
+
+/// This function decodes a 2D coordinate from 8 bytes.
+fn decode_coord_2d(data: &[u8]) -> [f32; 2] {
+    [
+        f32::from_le_bytes([data[0], data[1], data[2], data[3]]),
+        f32::from_le_bytes([data[4], data[5], data[6], data[7]]),
+    ]
+}
+
+/// This function decodes a 3D coordinate from 12 bytes.
+fn decode_coord_3d(data: &[u8]) -> [f32; 3] {
+    [
+        f32::from_le_bytes([data[0], data[1], data[2], data[3]]),
+        f32::from_le_bytes([data[4], data[5], data[6], data[7]]),
+        f32::from_le_bytes([data[8], data[9], data[10], data[11]]),
+    ]
+}
+
+/// This function decodes an unsigned 24 bits integer from 3 bytes.
+fn decode_u24(data: &[u8]) -> u32 {
+    u32::from_le_bytes([data[0], data[1], data[2], 0])
+}
+
+/// This function decodes a signed 24 bits integer from 3 bytes.
+fn decode_i24(data: &[u8]) -> i32 {
+    i32::from_le_bytes([0, data[0], data[1], data[2]]) >> 8
+}
+
+/// This function encodes the value of an optimised u32 node as a single byte, if it fits.
+fn encode_u8(packed_file: &mut Vec<u8>, value: u32) -> Result<()> {
+    let value = u8::try_from(value).map_err(|_| ErrorKind::ESFEncode(format!("The value {} doesn't fit in an U32Byte node.", value)))?;
+    packed_file.push(value);
+    Ok(())
+}
+
+/// This function encodes the value of an optimised u32 node as two bytes, if it fits.
+fn encode_u16(packed_file: &mut Vec<u8>, value: u32) -> Result<()> {
+    let value = u16::try_from(value).map_err(|_| ErrorKind::ESFEncode(format!("The value {} doesn't fit in an U32Short node.", value)))?;
+    packed_file.encode_integer_u16(value);
+    Ok(())
+}
+
+/// This function encodes the value of an optimised u32 node as three bytes, if it fits.
+fn encode_u24(packed_file: &mut Vec<u8>, value: u32) -> Result<()> {
+    if value > 0xFF_FFFF {
+        return Err(ErrorKind::ESFEncode(format!("The value {} doesn't fit in an U32Int24 node.", value)).into());
+    }
+
+    packed_file.extend_from_slice(&value.to_le_bytes()[..3]);
+    Ok(())
+}
+
+/// This function encodes the value of an optimised i32 node as a single byte, if it fits.
+fn encode_i8(packed_file: &mut Vec<u8>, value: i32) -> Result<()> {
+    let value = i8::try_from(value).map_err(|_| ErrorKind::ESFEncode(format!("The value {} doesn't fit in an I32Byte node.", value)))?;
+    packed_file.encode_integer_i8(value);
+    Ok(())
+}
+
+/// This function encodes the value of an optimised i32 node as two bytes, if it fits.
+fn encode_i16(packed_file: &mut Vec<u8>, value: i32) -> Result<()> {
+    let value = i16::try_from(value).map_err(|_| ErrorKind::ESFEncode(format!("The value {} doesn't fit in an I32Short node.", value)))?;
+    packed_file.encode_integer_i16(value);
+    Ok(())
+}
+
+/// This function encodes the value of an optimised i32 node as three bytes, if it fits.
+fn encode_i24(packed_file: &mut Vec<u8>, value: i32) -> Result<()> {
+    if !(-0x80_0000..=0x7F_FFFF).contains(&value) {
+        return Err(ErrorKind::ESFEncode(format!("The value {} doesn't fit in an I32Int24 node.", value)).into());
+    }
+
+    packed_file.extend_from_slice(&value.to_le_bytes()[..3]);
+    Ok(())
+}
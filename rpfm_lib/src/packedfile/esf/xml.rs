@@ -0,0 +1,435 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to dump ESF PackedFiles to XML, and to load them back.
+
+The XML follows the tree of the ESF: each node is an element named after its type, with its value as text.
+- Arrays have their values separated by spaces, except string arrays, which have a `<string>` element per value.
+- Coordinates have their components separated by commas.
+- Records are `<record>` elements, and record blocks are `<record_block>` elements with an `<entry>` element per entry.
+
+The string tables are dumped too, so loading the dump gives back the same ESF.
+!*/
+
+use xml::reader::{ParserConfig, XmlEvent};
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use rpfm_error::{ErrorKind, Result};
+
+use super::{ESF, ESFSignature, MAX_DEPTH, NodeType, RecordBlockNode, RecordNode};
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents an element of a parsed XML file.
+#[derive(Default)]
+struct Element {
+
+    /// Name of the element.
+    name: String,
+
+    /// Attributes of the element, with their values.
+    attributes: Vec<(String, String)>,
+
+    /// Elements inside this one.
+    children: Vec<Element>,
+
+    /// Text inside this element.
+    text: String,
+}
+
+//---------------------------------------------------------------------------//
+//                           Implementation of ESF
+//---------------------------------------------------------------------------//
+
+/// Implementation of `ESF`.
+impl ESF {
+
+    /// This function dumps the `ESF` to a XML string. The dump includes the string tables, so it can be loaded back without losses.
+    ///
+    /// XML cannot represent most control characters, so this fails if any string contains one of them, other than tabs and line jumps.
+    pub fn to_xml(&self) -> Result<String> {
+        let mut xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n".to_owned();
+        xml.push_str(&format!("<esf signature=\"{:?}\" unknown_1=\"{}\" creation_date=\"{}\">\n", self.signature, self.unknown_1, self.creation_date));
+
+        xml.push_str("  <record_names>\n");
+        for name in &self.record_names {
+            xml.push_str(&format!("    <name>{}</name>\n", escape(name)?));
+        }
+        xml.push_str("  </record_names>\n");
+
+        for (tag, strings) in &[("utf16_strings", &self.utf16_strings), ("ascii_strings", &self.ascii_strings)] {
+            xml.push_str(&format!("  <{}>\n", tag));
+            for (string, index) in strings.iter() {
+                xml.push_str(&format!("    <string index=\"{}\">{}</string>\n", index, escape(string)?));
+            }
+            xml.push_str(&format!("  </{}>\n", tag));
+        }
+
+        write_record(&mut xml, &self.root_node, 1)?;
+        xml.push_str("</esf>\n");
+        Ok(xml)
+    }
+
+    /// This function loads an `ESF` from a XML dump made with `to_xml`.
+    pub fn from_xml(xml: &str) -> Result<Self> {
+        let root = parse_xml(xml)?;
+        if root.name != "esf" {
+            return Err(ErrorKind::ESFLoad(format!("Expected an <esf> element, found <{}>.", root.name)).into());
+        }
+
+        let signature = match root.get_attribute("signature")? {
+            "ABCA" => ESFSignature::ABCA,
+            "ABCB" => ESFSignature::ABCB,
+            signature => return Err(ErrorKind::ESFLoad(format!("Unsupported signature: {}.", signature)).into()),
+        };
+
+        let root_node = match root.get_child("record")?.to_node()? {
+            NodeType::Record(root_node) => root_node,
+            _ => unreachable!(),
+        };
+
+        Ok(Self {
+            signature,
+            unknown_1: parse_value(root.get_attribute("unknown_1")?)?,
+            creation_date: parse_value(root.get_attribute("creation_date")?)?,
+            root_node,
+            record_names: root.get_child("record_names")?.children.iter().map(|name| name.text.to_owned()).collect(),
+            utf16_strings: root.get_child("utf16_strings")?.get_indexed_strings()?,
+            ascii_strings: root.get_child("ascii_strings")?.get_indexed_strings()?,
+        })
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                          Implementation of Element
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Element`.
+impl Element {
+
+    /// This function returns the value of the attribute with the provided name.
+    fn get_attribute(&self, name: &str) -> Result<&str> {
+        match self.attributes.iter().find(|(attribute, _)| attribute == name) {
+            Some((_, value)) => Ok(value),
+            None => Err(ErrorKind::ESFLoad(format!("The element <{}> has no \"{}\" attribute.", self.name, name)).into()),
+        }
+    }
+
+    /// This function returns the first child element with the provided name.
+    fn get_child(&self, name: &str) -> Result<&Self> {
+        match self.children.iter().find(|child| child.name == name) {
+            Some(child) => Ok(child),
+            None => Err(ErrorKind::ESFLoad(format!("The element <{}> has no <{}> element.", self.name, name)).into()),
+        }
+    }
+
+    /// This function returns the strings of a string table, with their indexes.
+    fn get_indexed_strings(&self) -> Result<Vec<(String, u32)>> {
+        self.children.iter().map(|string| Ok((string.text.to_owned(), parse_value(string.get_attribute("index")?)?))).collect()
+    }
+
+    /// This function returns the name and version of a record or record block.
+    fn get_record_info(&self) -> Result<(String, u8)> {
+        Ok((self.get_attribute("name")?.to_owned(), parse_value(self.get_attribute("version")?)?))
+    }
+
+    /// This function converts the element into the node it represents.
+    fn to_node(&self) -> Result<NodeType> {
+        let text = &self.text;
+        let node = match &*self.name {
+            "bool" => NodeType::Bool(parse_value(text)?),
+            "i8" => NodeType::I8(parse_value(text)?),
+            "i16" => NodeType::I16(parse_value(text)?),
+            "i32" => NodeType::I32(parse_value(text)?),
+            "i64" => NodeType::I64(parse_value(text)?),
+            "u8" => NodeType::U8(parse_value(text)?),
+            "u16" => NodeType::U16(parse_value(text)?),
+            "u32" => NodeType::U32(parse_value(text)?),
+            "u64" => NodeType::U64(parse_value(text)?),
+            "f32" => NodeType::F32(parse_value(text)?),
+            "f64" => NodeType::F64(parse_value(text)?),
+            "coord_2d" => NodeType::Coord2d(parse_coord_2d(text)?),
+            "coord_3d" => NodeType::Coord3d(parse_coord_3d(text)?),
+            "utf16" => NodeType::Utf16(text.to_owned()),
+            "ascii" => NodeType::Ascii(text.to_owned()),
+            "angle" => NodeType::Angle(parse_value(text)?),
+
+            "bool_true" => NodeType::BoolTrue,
+            "bool_false" => NodeType::BoolFalse,
+            "u32_zero" => NodeType::U32Zero,
+            "u32_one" => NodeType::U32One,
+            "u32_byte" => NodeType::U32Byte(parse_value(text)?),
+            "u32_short" => NodeType::U32Short(parse_value(text)?),
+            "u32_24bit" => NodeType::U32Int24(parse_value(text)?),
+            "i32_zero" => NodeType::I32Zero,
+            "i32_byte" => NodeType::I32Byte(parse_value(text)?),
+            "i32_short" => NodeType::I32Short(parse_value(text)?),
+            "i32_24bit" => NodeType::I32Int24(parse_value(text)?),
+            "f32_zero" => NodeType::F32Zero,
+
+            "bool_array" => NodeType::BoolArray(parse_values(text)?),
+            "i8_array" => NodeType::I8Array(parse_values(text)?),
+            "i16_array" => NodeType::I16Array(parse_values(text)?),
+            "i32_array" => NodeType::I32Array(parse_values(text)?),
+            "i64_array" => NodeType::I64Array(parse_values(text)?),
+            "u8_array" => NodeType::U8Array(parse_values(text)?),
+            "u16_array" => NodeType::U16Array(parse_values(text)?),
+            "u32_array" => NodeType::U32Array(parse_values(text)?),
+            "u64_array" => NodeType::U64Array(parse_values(text)?),
+            "f32_array" => NodeType::F32Array(parse_values(text)?),
+            "f64_array" => NodeType::F64Array(parse_values(text)?),
+            "coord_2d_array" => NodeType::Coord2dArray(text.split_whitespace().map(parse_coord_2d).collect::<Result<_>>()?),
+            "coord_3d_array" => NodeType::Coord3dArray(text.split_whitespace().map(parse_coord_3d).collect::<Result<_>>()?),
+            "utf16_array" => NodeType::Utf16Array(self.children.iter().map(|string| string.text.to_owned()).collect()),
+            "ascii_array" => NodeType::AsciiArray(self.children.iter().map(|string| string.text.to_owned()).collect()),
+            "angle_array" => NodeType::AngleArray(parse_values(text)?),
+
+            "u32_byte_array" => NodeType::U32ByteArray(parse_values(text)?),
+            "u32_short_array" => NodeType::U32ShortArray(parse_values(text)?),
+            "u32_24bit_array" => NodeType::U32Int24Array(parse_values(text)?),
+            "i32_byte_array" => NodeType::I32ByteArray(parse_values(text)?),
+            "i32_short_array" => NodeType::I32ShortArray(parse_values(text)?),
+            "i32_24bit_array" => NodeType::I32Int24Array(parse_values(text)?),
+
+            "record" => {
+                let (name, version) = self.get_record_info()?;
+                NodeType::Record(RecordNode {
+                    name,
+                    version,
+                    children: self.children.iter().map(|child| child.to_node()).collect::<Result<_>>()?,
+                })
+            }
+
+            "record_block" => {
+                let (name, version) = self.get_record_info()?;
+                NodeType::RecordBlock(RecordBlockNode {
+                    name,
+                    version,
+                    entries: self.children.iter().map(|entry| entry.children.iter().map(|child| child.to_node()).collect::<Result<_>>()).collect::<Result<_>>()?,
+                })
+            }
+
+            name => return Err(ErrorKind::ESFLoad(format!("Unknown node type: <{}>.", name)).into()),
+        };
+
+        Ok(node)
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Helper functions
+//---------------------------------------------------------------------------//
+
+/// This function writes a node to the provided XML, indented to the provided depth.
+fn write_node(xml: &mut String, node: &NodeType, depth: usize) -> Result<()> {
+    let indentation = "  ".repeat(depth);
+    let (tag, value) = match node {
+        NodeType::Bool(value) => ("bool", value.to_string()),
+        NodeType::I8(value) => ("i8", value.to_string()),
+        NodeType::I16(value) => ("i16", value.to_string()),
+        NodeType::I32(value) => ("i32", value.to_string()),
+        NodeType::I64(value) => ("i64", value.to_string()),
+        NodeType::U8(value) => ("u8", value.to_string()),
+        NodeType::U16(value) => ("u16", value.to_string()),
+        NodeType::U32(value) => ("u32", value.to_string()),
+        NodeType::U64(value) => ("u64", value.to_string()),
+        NodeType::F32(value) => ("f32", value.to_string()),
+        NodeType::F64(value) => ("f64", value.to_string()),
+        NodeType::Coord2d(value) => ("coord_2d", join(value, ",")),
+        NodeType::Coord3d(value) => ("coord_3d", join(value, ",")),
+        NodeType::Utf16(value) => ("utf16", escape(value)?),
+        NodeType::Ascii(value) => ("ascii", escape(value)?),
+        NodeType::Angle(value) => ("angle", value.to_string()),
+
+        NodeType::BoolTrue => return write_empty(xml, "bool_true", depth),
+        NodeType::BoolFalse => return write_empty(xml, "bool_false", depth),
+        NodeType::U32Zero => return write_empty(xml, "u32_zero", depth),
+        NodeType::U32One => return write_empty(xml, "u32_one", depth),
+        NodeType::U32Byte(value) => ("u32_byte", value.to_string()),
+        NodeType::U32Short(value) => ("u32_short", value.to_string()),
+        NodeType::U32Int24(value) => ("u32_24bit", value.to_string()),
+        NodeType::I32Zero => return write_empty(xml, "i32_zero", depth),
+        NodeType::I32Byte(value) => ("i32_byte", value.to_string()),
+        NodeType::I32Short(value) => ("i32_short", value.to_string()),
+        NodeType::I32Int24(value) => ("i32_24bit", value.to_string()),
+        NodeType::F32Zero => return write_empty(xml, "f32_zero", depth),
+
+        NodeType::BoolArray(values) => ("bool_array", join(values, " ")),
+        NodeType::I8Array(values) => ("i8_array", join(values, " ")),
+        NodeType::I16Array(values) => ("i16_array", join(values, " ")),
+        NodeType::I32Array(values) => ("i32_array", join(values, " ")),
+        NodeType::I64Array(values) => ("i64_array", join(values, " ")),
+        NodeType::U8Array(values) => ("u8_array", join(values, " ")),
+        NodeType::U16Array(values) => ("u16_array", join(values, " ")),
+        NodeType::U32Array(values) => ("u32_array", join(values, " ")),
+        NodeType::U64Array(values) => ("u64_array", join(values, " ")),
+        NodeType::F32Array(values) => ("f32_array", join(values, " ")),
+        NodeType::F64Array(values) => ("f64_array", join(values, " ")),
+        NodeType::Coord2dArray(values) => ("coord_2d_array", values.iter().map(|value| join(value, ",")).collect::<Vec<String>>().join(" ")),
+        NodeType::Coord3dArray(values) => ("coord_3d_array", values.iter().map(|value| join(value, ",")).collect::<Vec<String>>().join(" ")),
+        NodeType::Utf16Array(values) => return write_string_array(xml, "utf16_array", values, depth),
+        NodeType::AsciiArray(values) => return write_string_array(xml, "ascii_array", values, depth),
+        NodeType::AngleArray(values) => ("angle_array", join(values, " ")),
+
+        NodeType::U32ByteArray(values) => ("u32_byte_array", join(values, " ")),
+        NodeType::U32ShortArray(values) => ("u32_short_array", join(values, " ")),
+        NodeType::U32Int24Array(values) => ("u32_24bit_array", join(values, " ")),
+        NodeType::I32ByteArray(values) => ("i32_byte_array", join(values, " ")),
+        NodeType::I32ShortArray(values) => ("i32_short_array", join(values, " ")),
+        NodeType::I32Int24Array(values) => ("i32_24bit_array", join(values, " ")),
+
+        NodeType::Record(record) => return write_record(xml, record, depth),
+        NodeType::RecordBlock(block) => {
+            xml.push_str(&format!("{}<record_block name=\"{}\" version=\"{}\">\n", indentation, escape(&block.name)?, block.version));
+            for entry in &block.entries {
+                xml.push_str(&format!("{}  <entry>\n", indentation));
+                for node in entry {
+                    write_node(xml, node, depth + 2)?;
+                }
+                xml.push_str(&format!("{}  </entry>\n", indentation));
+            }
+            xml.push_str(&format!("{}</record_block>\n", indentation));
+            return Ok(());
+        }
+    };
+
+    xml.push_str(&format!("{}<{}>{}</{}>\n", indentation, tag, value, tag));
+    Ok(())
+}
+
+/// This function writes an empty element with the provided tag to the provided XML, indented to the provided depth.
+fn write_empty(xml: &mut String, tag: &str, depth: usize) -> Result<()> {
+    xml.push_str(&format!("{}<{}/>\n", "  ".repeat(depth), tag));
+    Ok(())
+}
+
+/// This function writes a record to the provided XML, indented to the provided depth.
+fn write_record(xml: &mut String, record: &RecordNode, depth: usize) -> Result<()> {
+    let indentation = "  ".repeat(depth);
+    xml.push_str(&format!("{}<record name=\"{}\" version=\"{}\">\n", indentation, escape(&record.name)?, record.version));
+    for node in &record.children {
+        write_node(xml, node, depth + 1)?;
+    }
+    xml.push_str(&format!("{}</record>\n", indentation));
+    Ok(())
+}
+
+/// This function writes an array of strings to the provided XML, indented to the provided depth.
+fn write_string_array(xml: &mut String, tag: &str, values: &[String], depth: usize) -> Result<()> {
+    let indentation = "  ".repeat(depth);
+    xml.push_str(&format!("{}<{}>\n", indentation, tag));
+    for value in values {
+        xml.push_str(&format!("{}  <string>{}</string>\n", indentation, escape(value)?));
+    }
+    xml.push_str(&format!("{}</{}>\n", indentation, tag));
+    Ok(())
+}
+
+/// This function joins the provided values with the provided separator.
+fn join<T: Display>(values: &[T], separator: &str) -> String {
+    values.iter().map(|value| value.to_string()).collect::<Vec<String>>().join(separator)
+}
+
+/// This function escapes a string so it can be used as text or as the value of an attribute.
+///
+/// Tabs and line jumps are escaped too, so they survive the whitespace normalization of XML parsers.
+/// Other control characters cannot be represented in XML 1.0, so strings containing them return an error.
+fn escape(text: &str) -> Result<String> {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push_str(&format!("&#{};", character as u32)),
+            '\u{0}'..='\u{1F}' | '\u{FFFE}' | '\u{FFFF}' => return Err(ErrorKind::ESFEncode(format!("The string {:?} contains the character U+{:04X}, which cannot be stored in a XML file.", text, character as u32)).into()),
+            _ => escaped.push(character),
+        }
+    }
+    Ok(escaped)
+}
+
+/// This function parses a XML string into a tree of elements, returning the root one.
+fn parse_xml(xml: &str) -> Result<Element> {
+    let reader = ParserConfig::new()
+        .trim_whitespace(false)
+        .whitespace_to_characters(true)
+        .cdata_to_characters(true)
+        .ignore_comments(true)
+        .create_reader(xml.as_bytes());
+
+    let mut stack: Vec<Element> = vec![];
+    for event in reader {
+        match event.map_err(|error| ErrorKind::ESFLoad(error.to_string()))? {
+            XmlEvent::StartElement { name, attributes, .. } => {
+
+                // Each record can take two levels (a record block and its entry), plus the root element.
+                if stack.len() > MAX_DEPTH * 2 {
+                    return Err(ErrorKind::ESFLoad(format!("The XML has records nested inside more than {} records.", MAX_DEPTH)).into());
+                }
+
+                stack.push(Element {
+                    name: name.local_name,
+                    attributes: attributes.into_iter().map(|attribute| (attribute.name.local_name, attribute.value)).collect(),
+                    ..Default::default()
+                })
+            }
+
+            XmlEvent::EndElement { .. } => {
+                let element = stack.pop().ok_or_else(|| ErrorKind::ESFLoad("Unexpected closing tag.".to_owned()))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+
+            XmlEvent::Characters(text) => if let Some(element) = stack.last_mut() {
+                element.text.push_str(&text);
+            }
+
+            _ => {}
+        }
+    }
+
+    Err(ErrorKind::ESFLoad("The XML has no root element.".to_owned()).into())
+}
+
+/// This function parses a single value from a text.
+fn parse_value<T: FromStr>(text: &str) -> Result<T> {
+    text.trim().parse().map_err(|_| ErrorKind::ESFLoad(format!("Invalid value: {}.", text)).into())
+}
+
+/// This function parses a list of values separated by whitespaces from a text.
+fn parse_values<T: FromStr>(text: &str) -> Result<Vec<T>> {
+    text.split_whitespace().map(parse_value).collect()
+}
+
+/// This function parses a 2D coordinate, with its components separated by commas.
+fn parse_coord_2d(text: &str) -> Result<[f32; 2]> {
+    match text.split(',').map(parse_value).collect::<Result<Vec<f32>>>()?[..] {
+        [x, y] => Ok([x, y]),
+        _ => Err(ErrorKind::ESFLoad(format!("Invalid 2D coordinate: {}.", text)).into()),
+    }
+}
+
+/// This function parses a 3D coordinate, with its components separated by commas.
+fn parse_coord_3d(text: &str) -> Result<[f32; 3]> {
+    match text.split(',').map(parse_value).collect::<Result<Vec<f32>>>()?[..] {
+        [x, y, z] => Ok([x, y, z]),
+        _ => Err(ErrorKind::ESFLoad(format!("Invalid 3D coordinate: {}.", text)).into()),
+    }
+}
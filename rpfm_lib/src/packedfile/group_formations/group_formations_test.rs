@@ -0,0 +1,151 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `GroupFormations` module, to make sure GroupFormations files of all versions are decoded and encoded correctly.
+!*/
+
+use super::*;
+
+/// This function builds a raw GroupFormations of the provided version, with one formation containing all types of blocks.
+fn build_group_formations(version: u32) -> Vec<u8> {
+    let mut data = vec![];
+    data.encode_integer_u32(1);
+    data.encode_packedfile_string_u16("attack_cavalry_flank");
+    data.encode_float_f32(1.5);
+    data.encode_integer_u32(5);
+
+    data.encode_integer_u32(1);
+    data.encode_integer_u32(3);
+    data.encode_integer_u32(40);
+
+    if version >= 2 {
+        data.encode_integer_u32(1);
+        data.encode_packedfile_string_u16("wh_main_sc_emp_empire");
+    }
+
+    data.encode_integer_u32(2);
+    data.encode_packedfile_string_u16("wh_main_emp_empire");
+    data.encode_packedfile_string_u16("wh_main_emp_marienburg");
+
+    data.encode_integer_u32(3);
+
+    // Container block.
+    data.encode_integer_u32(0);
+    data.encode_integer_u32(0);
+    data.encode_float_f32(2.0);
+    data.encode_integer_u32(1);
+    data.encode_float_f32(3.5);
+    data.encode_float_f32(0.0);
+    data.encode_float_f32(-10.0);
+    data.encode_float_f32(20.0);
+    data.encode_integer_i32(1);
+    data.encode_integer_i32(-1);
+    data.encode_integer_u32(2);
+    data.encode_float_f32(1.0);
+    data.encode_integer_u32(7);
+    data.encode_float_f32(0.5);
+    data.encode_integer_u32(9);
+
+    // Relative block.
+    data.encode_integer_u32(1);
+    data.encode_integer_u32(1);
+    data.encode_float_f32(1.0);
+    data.encode_integer_u32(0);
+    data.encode_integer_u32(2);
+    data.encode_float_f32(4.0);
+    data.encode_float_f32(1.0);
+    data.encode_float_f32(0.0);
+    data.encode_float_f32(-15.0);
+    data.encode_integer_i32(0);
+    data.encode_integer_i32(4);
+    data.encode_integer_u32(0);
+
+    // Spanning block.
+    data.encode_integer_u32(2);
+    data.encode_integer_u32(3);
+    data.encode_integer_u32(2);
+    data.encode_integer_u32(0);
+    data.encode_integer_u32(1);
+    data
+}
+
+#[test]
+fn test_group_formations() {
+    for version in &SUPPORTED_VERSIONS {
+        let data = build_group_formations(*version);
+        let group_formations = GroupFormations::read(&data).unwrap();
+        assert_eq!(group_formations.get_ref_version(), version);
+
+        let formation = group_formations.get_ref_formation("attack_cavalry_flank").unwrap();
+        assert_eq!(*formation.get_ref_ai_priority(), 1.5);
+        assert_eq!(*formation.get_ref_ai_purpose(), 5);
+        assert_eq!(*formation.get_ref_min_unit_category_percentages()[0].get_ref_percentage(), 40);
+        assert_eq!(formation.get_ref_ai_supported_subcultures().len(), if *version >= 2 { 1 } else { 0 });
+        assert_eq!(formation.get_ref_ai_supported_factions()[1], "wh_main_emp_marienburg");
+        assert!(formation.get_missing_block_ids().is_empty());
+
+        let blocks = formation.get_ref_blocks();
+        assert_eq!(blocks.len(), 3);
+        match blocks[0].get_ref_block_type() {
+            BlockType::Container(container) => {
+                assert_eq!(*container.get_ref_block_priority(), 2.0);
+                assert_eq!(*container.get_ref_maximum_entity_threshold(), -1);
+                assert_eq!(*container.get_ref_entity_preferences()[1].get_ref_entity_class(), 9);
+            }
+            _ => panic!("Block 0 is not a container block."),
+        }
+
+        match blocks[1].get_ref_block_type() {
+            BlockType::RelativeContainer(relative) => {
+                assert_eq!(*relative.get_ref_relative_block_id(), 0);
+                assert_eq!(*relative.get_ref_container().get_ref_block_priority(), 1.0);
+                assert_eq!(*relative.get_ref_container().get_ref_position_y(), -15.0);
+            }
+            _ => panic!("Block 1 is not a relative block."),
+        }
+
+        assert_eq!(*blocks[2].get_ref_block_type(), BlockType::Spanning(vec![0, 1]));
+
+        // Unedited files must be saved exactly as they were.
+        assert_eq!(group_formations.save().unwrap(), data);
+
+        // Broken files.
+        assert!(GroupFormations::read(&data[..data.len() - 1]).is_err());
+        let mut broken = data.clone();
+        broken.push(0);
+        assert!(GroupFormations::read(&broken).is_err());
+    }
+}
+
+#[test]
+fn test_group_formations_edit() {
+    let mut group_formations = GroupFormations::read(&build_group_formations(1)).unwrap();
+    let formation = group_formations.get_ref_mut_formation("attack_cavalry_flank").unwrap();
+    formation.set_ai_priority(3.0);
+    formation.get_ref_mut_blocks().push(Block {
+        id: 3,
+        block_type: BlockType::Spanning(vec![1, 4]),
+    });
+    assert_eq!(formation.get_missing_block_ids(), vec![4]);
+
+    let edited = GroupFormations::read(&group_formations.save().unwrap()).unwrap();
+    assert_eq!(edited, group_formations);
+
+    // Subcultures cannot be saved in version 1 files.
+    let formation = group_formations.get_ref_mut_formation("attack_cavalry_flank").unwrap();
+    formation.get_ref_mut_ai_supported_subcultures().push("wh_main_sc_emp_empire".to_owned());
+    assert!(group_formations.save().is_err());
+    group_formations.set_version(2);
+    assert_eq!(GroupFormations::read(&group_formations.save().unwrap()).unwrap(), group_formations);
+
+    group_formations.set_version(3);
+    assert!(group_formations.save().is_err());
+}
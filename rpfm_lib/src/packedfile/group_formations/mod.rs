@@ -0,0 +1,480 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to interact with GroupFormations PackedFiles.
+
+GroupFormations PackedFiles (`groupformations.bin`) contain the formations the battle AI uses to deploy and move its armies.
+Each formation has:
+- Its name, priority and purposes (a bitmask of the situations the AI can use it in).
+- The minimum percentage of each unit category an army needs to use it.
+- The subcultures (only in version 2) and factions that can use it.
+- Its blocks: containers placed at an absolute position, containers placed relative to another block, and spanning blocks grouping other blocks.
+
+The file doesn't store its version, so we detect it when decoding, trying the newest one first.
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_macros::*;
+
+use crate::common::{decoder::Decoder, encoder::Encoder};
+
+#[cfg(test)]
+mod group_formations_test;
+
+/// Full path of the GroupFormations PackedFile. This is an special type of bin, so we identify it by his full path.
+pub const PATH: [&str; 1] = ["groupformations.bin"];
+
+/// Versions of GroupFormations PackedFiles we can decode, from newest to oldest.
+const SUPPORTED_VERSIONS: [u32; 2] = [2, 1];
+
+/// Type of the blocks placed at an absolute position.
+const BLOCK_TYPE_CONTAINER: u32 = 0;
+
+/// Type of the blocks placed relative to another block.
+const BLOCK_TYPE_RELATIVE_CONTAINER: u32 = 1;
+
+/// Type of the blocks grouping other blocks.
+const BLOCK_TYPE_SPANNING: u32 = 3;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains a GroupFormations PackedFile decoded in memory.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct GroupFormations {
+
+    /// Version of the format. Version `2` adds the subcultures of each formation.
+    version: u32,
+
+    /// Formations in the file.
+    formations: Vec<GroupFormation>,
+}
+
+/// This struct represents a formation of the battle AI.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct GroupFormation {
+
+    /// Name of the formation.
+    name: String,
+
+    /// Priority of the formation over the other ones the AI can use.
+    ai_priority: f32,
+
+    /// Bitmask of the situations (attack, defence, ...) the AI can use this formation in.
+    ai_purpose: u32,
+
+    /// Minimum percentage of each unit category an army needs to use this formation.
+    min_unit_category_percentages: Vec<UnitCategoryPercentage>,
+
+    /// Subcultures that can use this formation. Only in version 2.
+    ai_supported_subcultures: Vec<String>,
+
+    /// Factions that can use this formation.
+    ai_supported_factions: Vec<String>,
+
+    /// Blocks of the formation.
+    blocks: Vec<Block>,
+}
+
+/// This struct represents the minimum percentage of a unit category a formation needs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct UnitCategoryPercentage {
+
+    /// Unit category.
+    unit_category: u32,
+
+    /// Minimum percentage of units of the category.
+    percentage: u32,
+}
+
+/// This struct represents a block of a formation.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct Block {
+
+    /// ID of the block, used by other blocks to reference it.
+    id: u32,
+
+    /// Type of the block, with its data.
+    block_type: BlockType,
+}
+
+/// This enum represents the different types of blocks a formation can have.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BlockType {
+
+    /// Block with its units placed at an absolute position.
+    Container(ContainerBlock),
+
+    /// Block with its units placed relative to another block.
+    RelativeContainer(RelativeContainerBlock),
+
+    /// Block grouping other blocks, with their IDs.
+    Spanning(Vec<u32>),
+}
+
+/// This struct represents a block placed at an absolute position.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct ContainerBlock {
+
+    /// Priority of the block when assigning units.
+    block_priority: f32,
+
+    /// How the units are arranged inside the block (line, column, crescent, ...).
+    entity_arrangement: u32,
+
+    /// Space between units.
+    inter_entity_spacing: f32,
+
+    /// Offset of the center of the block, for crescent arrangements.
+    crescent_y_offset: f32,
+
+    /// X position of the block.
+    position_x: f32,
+
+    /// Y position of the block.
+    position_y: f32,
+
+    /// Minimum amount of units in the block.
+    minimum_entity_threshold: i32,
+
+    /// Maximum amount of units in the block.
+    maximum_entity_threshold: i32,
+
+    /// Preferences of the block for each type of unit.
+    entity_preferences: Vec<EntityPreference>,
+}
+
+/// This struct represents a block placed relative to another block.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct RelativeContainerBlock {
+
+    /// ID of the block this one is placed relative to.
+    relative_block_id: u32,
+
+    /// Data of the block. Its position is relative to the other block.
+    container: ContainerBlock,
+}
+
+/// This struct represents the preference of a block for a type of unit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct EntityPreference {
+
+    /// Priority of this type of unit for the block.
+    priority: f32,
+
+    /// Type of unit.
+    entity_class: u32,
+}
+
+//---------------------------------------------------------------------------//
+//                     Implementation of GroupFormations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `GroupFormations`.
+impl GroupFormations {
+
+    /// This function creates an empty `GroupFormations` of the provided version.
+    pub fn new(version: u32) -> Self {
+        Self {
+            version,
+            formations: vec![],
+        }
+    }
+
+    /// This function creates a `GroupFormations` from a `&[u8]`, detecting its version.
+    pub fn read(packed_file_data: &[u8]) -> Result<Self> {
+        let mut last_error = None;
+        for version in &SUPPORTED_VERSIONS {
+            match Self::read_version(packed_file_data, *version) {
+                Ok(group_formations) => return Ok(group_formations),
+                Err(error) => last_error = Some(error),
+            }
+        }
+
+        Err(last_error.unwrap())
+    }
+
+    /// This function creates a `GroupFormations` from a `&[u8]`, assuming it's of the provided version.
+    fn read_version(packed_file_data: &[u8], version: u32) -> Result<Self> {
+        let mut index = 0;
+        let formation_count = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+        let mut formations = vec![];
+        for _ in 0..formation_count {
+            formations.push(GroupFormation::read(packed_file_data, &mut index, version)?);
+        }
+
+        // If we've not reached the end of the data, this is not the version we thought.
+        if index != packed_file_data.len() {
+            return Err(ErrorKind::GroupFormationsDecode(format!("Version {}: there are {} bytes of unknown data at the end of the file.", version, packed_file_data.len() - index)).into());
+        }
+
+        Ok(Self {
+            version,
+            formations,
+        })
+    }
+
+    /// This function takes a `GroupFormations` and encodes it to `Vec<u8>`.
+    pub fn save(&self) -> Result<Vec<u8>> {
+        if !SUPPORTED_VERSIONS.contains(&self.version) {
+            return Err(ErrorKind::GroupFormationsEncode(format!("Unsupported version: {}.", self.version)).into());
+        }
+
+        let mut packed_file = vec![];
+        packed_file.encode_integer_u32(self.formations.len() as u32);
+        for formation in &self.formations {
+            formation.save(&mut packed_file, self.version)?;
+        }
+
+        Ok(packed_file)
+    }
+
+    /// This function returns a reference to the formation with the provided name, if exists.
+    pub fn get_ref_formation(&self, name: &str) -> Option<&GroupFormation> {
+        self.formations.iter().find(|formation| formation.name == name)
+    }
+
+    /// This function returns a mutable reference to the formation with the provided name, if exists.
+    pub fn get_ref_mut_formation(&mut self, name: &str) -> Option<&mut GroupFormation> {
+        self.formations.iter_mut().find(|formation| formation.name == name)
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                     Implementation of GroupFormation
+//---------------------------------------------------------------------------//
+
+/// Implementation of `GroupFormation`.
+impl GroupFormation {
+
+    /// This function reads a formation of the provided version, moving the index to the end of it.
+    fn read(packed_file_data: &[u8], index: &mut usize, version: u32) -> Result<Self> {
+        let name = packed_file_data.decode_packedfile_string_u16(*index, index)?;
+        let ai_priority = packed_file_data.decode_packedfile_float_f32(*index, index)?;
+        let ai_purpose = packed_file_data.decode_packedfile_integer_u32(*index, index)?;
+
+        let mut min_unit_category_percentages = vec![];
+        for _ in 0..packed_file_data.decode_packedfile_integer_u32(*index, index)? {
+            min_unit_category_percentages.push(UnitCategoryPercentage {
+                unit_category: packed_file_data.decode_packedfile_integer_u32(*index, index)?,
+                percentage: packed_file_data.decode_packedfile_integer_u32(*index, index)?,
+            });
+        }
+
+        let ai_supported_subcultures = if version >= 2 { read_strings(packed_file_data, index)? } else { vec![] };
+        let ai_supported_factions = read_strings(packed_file_data, index)?;
+
+        let mut blocks = vec![];
+        for _ in 0..packed_file_data.decode_packedfile_integer_u32(*index, index)? {
+            blocks.push(Block::read(packed_file_data, index)?);
+        }
+
+        Ok(Self {
+            name,
+            ai_priority,
+            ai_purpose,
+            min_unit_category_percentages,
+            ai_supported_subcultures,
+            ai_supported_factions,
+            blocks,
+        })
+    }
+
+    /// This function encodes the formation as the provided version.
+    fn save(&self, packed_file: &mut Vec<u8>, version: u32) -> Result<()> {
+        packed_file.encode_packedfile_string_u16(&self.name);
+        packed_file.encode_float_f32(self.ai_priority);
+        packed_file.encode_integer_u32(self.ai_purpose);
+
+        packed_file.encode_integer_u32(self.min_unit_category_percentages.len() as u32);
+        for min_unit_category_percentage in &self.min_unit_category_percentages {
+            packed_file.encode_integer_u32(min_unit_category_percentage.unit_category);
+            packed_file.encode_integer_u32(min_unit_category_percentage.percentage);
+        }
+
+        if version >= 2 {
+            save_strings(packed_file, &self.ai_supported_subcultures);
+        } else if !self.ai_supported_subcultures.is_empty() {
+            return Err(ErrorKind::GroupFormationsEncode(format!("The formation {} has subcultures, but version {} doesn't support them.", self.name, version)).into());
+        }
+
+        save_strings(packed_file, &self.ai_supported_factions);
+
+        packed_file.encode_integer_u32(self.blocks.len() as u32);
+        self.blocks.iter().for_each(|block| block.save(packed_file));
+        Ok(())
+    }
+
+    /// This function returns the IDs of the blocks referenced by other blocks of the formation, but missing from it.
+    pub fn get_missing_block_ids(&self) -> Vec<u32> {
+        let mut missing_block_ids = vec![];
+        for block in &self.blocks {
+            let referenced_ids = match &block.block_type {
+                BlockType::Container(_) => vec![],
+                BlockType::RelativeContainer(relative) => vec![relative.relative_block_id],
+                BlockType::Spanning(spanned_block_ids) => spanned_block_ids.to_vec(),
+            };
+
+            for id in referenced_ids {
+                if !missing_block_ids.contains(&id) && !self.blocks.iter().any(|block| block.id == id) {
+                    missing_block_ids.push(id);
+                }
+            }
+        }
+
+        missing_block_ids
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                          Implementation of Block
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Block`.
+impl Block {
+
+    /// This function reads a block, moving the index to the end of it.
+    fn read(packed_file_data: &[u8], index: &mut usize) -> Result<Self> {
+        let id = packed_file_data.decode_packedfile_integer_u32(*index, index)?;
+        let block_type = match packed_file_data.decode_packedfile_integer_u32(*index, index)? {
+            BLOCK_TYPE_CONTAINER => {
+                let block_priority = packed_file_data.decode_packedfile_float_f32(*index, index)?;
+                BlockType::Container(ContainerBlock::read(packed_file_data, index, block_priority)?)
+            }
+
+            // Relative blocks have the ID of the other block between the priority and the rest of the data.
+            BLOCK_TYPE_RELATIVE_CONTAINER => {
+                let block_priority = packed_file_data.decode_packedfile_float_f32(*index, index)?;
+                let relative_block_id = packed_file_data.decode_packedfile_integer_u32(*index, index)?;
+                BlockType::RelativeContainer(RelativeContainerBlock {
+                    relative_block_id,
+                    container: ContainerBlock::read(packed_file_data, index, block_priority)?,
+                })
+            }
+
+            BLOCK_TYPE_SPANNING => {
+                let mut spanned_block_ids = vec![];
+                for _ in 0..packed_file_data.decode_packedfile_integer_u32(*index, index)? {
+                    spanned_block_ids.push(packed_file_data.decode_packedfile_integer_u32(*index, index)?);
+                }
+                BlockType::Spanning(spanned_block_ids)
+            }
+
+            block_type => return Err(ErrorKind::GroupFormationsDecode(format!("Unknown type {} of the block {}.", block_type, id)).into()),
+        };
+
+        Ok(Self {
+            id,
+            block_type,
+        })
+    }
+
+    /// This function encodes the block.
+    fn save(&self, packed_file: &mut Vec<u8>) {
+        packed_file.encode_integer_u32(self.id);
+        match &self.block_type {
+            BlockType::Container(container) => {
+                packed_file.encode_integer_u32(BLOCK_TYPE_CONTAINER);
+                packed_file.encode_float_f32(container.block_priority);
+                container.save(packed_file);
+            }
+            BlockType::RelativeContainer(relative) => {
+                packed_file.encode_integer_u32(BLOCK_TYPE_RELATIVE_CONTAINER);
+                packed_file.encode_float_f32(relative.container.block_priority);
+                packed_file.encode_integer_u32(relative.relative_block_id);
+                relative.container.save(packed_file);
+            }
+            BlockType::Spanning(spanned_block_ids) => {
+                packed_file.encode_integer_u32(BLOCK_TYPE_SPANNING);
+                packed_file.encode_integer_u32(spanned_block_ids.len() as u32);
+                spanned_block_ids.iter().for_each(|id| packed_file.encode_integer_u32(*id));
+            }
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                      Implementation of ContainerBlock
+//---------------------------------------------------------------------------//
+
+/// Implementation of `ContainerBlock`.
+impl ContainerBlock {
+
+    /// This function reads the data of a container block after its priority, moving the index to the end of it.
+    fn read(packed_file_data: &[u8], index: &mut usize, block_priority: f32) -> Result<Self> {
+        let entity_arrangement = packed_file_data.decode_packedfile_integer_u32(*index, index)?;
+        let inter_entity_spacing = packed_file_data.decode_packedfile_float_f32(*index, index)?;
+        let crescent_y_offset = packed_file_data.decode_packedfile_float_f32(*index, index)?;
+        let position_x = packed_file_data.decode_packedfile_float_f32(*index, index)?;
+        let position_y = packed_file_data.decode_packedfile_float_f32(*index, index)?;
+        let minimum_entity_threshold = packed_file_data.decode_packedfile_integer_i32(*index, index)?;
+        let maximum_entity_threshold = packed_file_data.decode_packedfile_integer_i32(*index, index)?;
+
+        let mut entity_preferences = vec![];
+        for _ in 0..packed_file_data.decode_packedfile_integer_u32(*index, index)? {
+            entity_preferences.push(EntityPreference {
+                priority: packed_file_data.decode_packedfile_float_f32(*index, index)?,
+                entity_class: packed_file_data.decode_packedfile_integer_u32(*index, index)?,
+            });
+        }
+
+        Ok(Self {
+            block_priority,
+            entity_arrangement,
+            inter_entity_spacing,
+            crescent_y_offset,
+            position_x,
+            position_y,
+            minimum_entity_threshold,
+            maximum_entity_threshold,
+            entity_preferences,
+        })
+    }
+
+    /// This function encodes the data of the container block after its priority.
+    fn save(&self, packed_file: &mut Vec<u8>) {
+        packed_file.encode_integer_u32(self.entity_arrangement);
+        packed_file.encode_float_f32(self.inter_entity_spacing);
+        packed_file.encode_float_f32(self.crescent_y_offset);
+        packed_file.encode_float_f32(self.position_x);
+        packed_file.encode_float_f32(self.position_y);
+        packed_file.encode_integer_i32(self.minimum_entity_threshold);
+        packed_file.encode_integer_i32(self.maximum_entity_threshold);
+
+        packed_file.encode_integer_u32(self.entity_preferences.len() as u32);
+        for entity_preference in &self.entity_preferences {
+            packed_file.encode_float_f32(entity_preference.priority);
+            packed_file.encode_integer_u32(entity_preference.entity_class);
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Helper functions
+//---------------------------------------------------------------------------//
+
+/// This function reads a list of UTF-16 strings, preceded by its length.
+fn read_strings(packed_file_data: &[u8], index: &mut usize) -> Result<Vec<String>> {
+    let mut strings = vec![];
+    for _ in 0..packed_file_data.decode_packedfile_integer_u32(*index, index)? {
+        strings.push(packed_file_data.decode_packedfile_string_u16(*index, index)?);
+    }
+    Ok(strings)
+}
+
+/// This function encodes a list of UTF-16 strings, preceded by its length.
+fn save_strings(packed_file: &mut Vec<u8>, strings: &[String]) {
+    packed_file.encode_integer_u32(strings.len() as u32);
+    strings.iter().for_each(|string| packed_file.encode_packedfile_string_u16(string));
+}
@@ -0,0 +1,641 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to decode and encode block-compressed pixel data (DXT1/3/5, BC4, BC5 and BC7).
+
+All these formats split the image in blocks of 4x4 pixels. Here, decoded blocks are 64 bytes of RGBA8, row by row.
+
+The encoders are simple range fits along the principal axis of the colours of each block, meant to make editing textures possible,
+not to compete with dedicated compressors.
+BC7 blocks are always encoded using mode 6, and DXT1 blocks are always encoded as opaque.
+!*/
+
+use rpfm_error::{ErrorKind, Result};
+
+use super::ImageFormat;
+
+/// Subset of each pixel for each BC7 partition with two subsets. Bit `x` is the subset of pixel `x`.
+const BC7_PARTITIONS_2: [u16; 64] = [
+    0xCCCC, 0x8888, 0xEEEE, 0xECC8, 0xC880, 0xFEEC, 0xFEC8, 0xEC80, 0xC800, 0xFFEC, 0xFE80, 0xE800, 0xFFE8, 0xFF00, 0xFFF0, 0xF000,
+    0xF710, 0x008E, 0x7100, 0x08CE, 0x008C, 0x7310, 0x3100, 0x8CCE, 0x088C, 0x3110, 0x6666, 0x366C, 0x17E8, 0x0FF0, 0x718E, 0x399C,
+    0xAAAA, 0xF0F0, 0x5A5A, 0x33CC, 0x3C3C, 0x55AA, 0x9696, 0xA55A, 0x73CE, 0x13C8, 0x324C, 0x3BDC, 0x6996, 0xC33C, 0x9966, 0x0660,
+    0x0272, 0x04E4, 0x4E40, 0x2720, 0xC936, 0x936C, 0x39C6, 0x639C, 0x9336, 0x9CC6, 0x817E, 0xE718, 0xCCF0, 0x0FCC, 0x7744, 0xEE22,
+];
+
+/// Subset of each pixel for each BC7 partition with three subsets. Bits `2x` and `2x + 1` are the subset of pixel `x`.
+const BC7_PARTITIONS_3: [u32; 64] = [
+    0xAA68_5050, 0x6A5A_5040, 0x5A5A_4200, 0x5450_A0A8, 0xA5A5_0000, 0xA0A0_5050, 0x5555_A0A0, 0x5A5A_5050,
+    0xAA55_0000, 0xAA55_5500, 0xAAAA_5500, 0x9090_9090, 0x9494_9494, 0xA4A4_A4A4, 0xA9A5_9450, 0x2A0A_4250,
+    0xA594_5040, 0x0A42_5054, 0xA5A5_A500, 0x55A0_A0A0, 0xA8A8_5454, 0x6A6A_4040, 0xA4A4_5000, 0x1A1A_0500,
+    0x0050_A4A4, 0xAAA5_9090, 0x1469_6914, 0x6969_1400, 0xA085_85A0, 0xAA82_1414, 0x50A4_A450, 0x6A5A_0200,
+    0xA9A5_8000, 0x5090_A0A8, 0xA8A0_9050, 0x2424_2424, 0x00AA_5500, 0x2492_4924, 0x2449_9224, 0x50A5_0A50,
+    0x500A_A550, 0xAAAA_4444, 0x6666_0000, 0xA5A0_A5A0, 0x50A0_50A0, 0x6928_6928, 0x44AA_AA44, 0x6666_6600,
+    0xAA44_4444, 0x54A8_54A8, 0x9580_9580, 0x9696_9600, 0xA854_54A8, 0x8095_9580, 0xAA14_1414, 0x9696_0000,
+    0xAAAA_1414, 0xA050_50A0, 0xA0A5_A5A0, 0x9600_0000, 0x4080_4080, 0xA9A8_A9A8, 0xAAAA_AA44, 0x2A4A_5254,
+];
+
+/// Anchor pixel of the second subset of each BC7 partition with two subsets.
+const BC7_ANCHORS_2: [u8; 64] = [
+    15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
+    15,  2,  8,  2,  2,  8,  8, 15,  2,  8,  2,  2,  8,  8,  2,  2,
+    15, 15,  6,  8,  2,  8, 15, 15,  2,  8,  2,  2,  2, 15, 15,  6,
+     6,  2,  6,  8, 15, 15,  2,  2, 15, 15, 15, 15, 15,  2,  2, 15,
+];
+
+/// Anchor pixel of the second subset of each BC7 partition with three subsets.
+const BC7_ANCHORS_3_A: [u8; 64] = [
+     3,  3, 15, 15,  8,  3, 15, 15,  8,  8,  6,  6,  6,  5,  3,  3,
+     3,  3,  8, 15,  3,  3,  6, 10,  5,  8,  8,  6,  8,  5, 15, 15,
+     8, 15,  3,  5,  6, 10,  8, 15, 15,  3, 15,  5, 15, 15, 15, 15,
+     3, 15,  5,  5,  5,  8,  5, 10,  5, 10,  8, 13, 15, 12,  3,  3,
+];
+
+/// Anchor pixel of the third subset of each BC7 partition with three subsets.
+const BC7_ANCHORS_3_B: [u8; 64] = [
+    15,  8,  8,  3, 15, 15,  3,  8, 15, 15, 15, 15, 15, 15, 15,  8,
+    15,  8, 15,  3, 15,  8, 15,  8,  3, 15,  6, 10, 15, 15, 10,  8,
+    15,  3, 15, 10, 10,  8,  9, 10,  6, 15,  8, 15,  3,  6,  6,  8,
+    15,  3, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,  3, 15, 15,  8,
+];
+
+/// Interpolation weights for 2-bit indices.
+const WEIGHTS_2: [u32; 4] = [0, 21, 43, 64];
+
+/// Interpolation weights for 3-bit indices.
+const WEIGHTS_3: [u32; 8] = [0, 9, 18, 27, 37, 46, 55, 64];
+
+/// Interpolation weights for 4-bit indices.
+const WEIGHTS_4: [u32; 16] = [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+/// Layout of each one of the 8 BC7 modes.
+const BC7_MODES: [Bc7Mode; 8] = [
+    Bc7Mode { subsets: 3, partition_bits: 4, rotation_bits: 0, index_selection_bits: 0, colour_bits: 4, alpha_bits: 0, endpoint_pbits: true, shared_pbits: false, index_bits: 3, index_bits_2: 0 },
+    Bc7Mode { subsets: 2, partition_bits: 6, rotation_bits: 0, index_selection_bits: 0, colour_bits: 6, alpha_bits: 0, endpoint_pbits: false, shared_pbits: true, index_bits: 3, index_bits_2: 0 },
+    Bc7Mode { subsets: 3, partition_bits: 6, rotation_bits: 0, index_selection_bits: 0, colour_bits: 5, alpha_bits: 0, endpoint_pbits: false, shared_pbits: false, index_bits: 2, index_bits_2: 0 },
+    Bc7Mode { subsets: 2, partition_bits: 6, rotation_bits: 0, index_selection_bits: 0, colour_bits: 7, alpha_bits: 0, endpoint_pbits: true, shared_pbits: false, index_bits: 2, index_bits_2: 0 },
+    Bc7Mode { subsets: 1, partition_bits: 0, rotation_bits: 2, index_selection_bits: 1, colour_bits: 5, alpha_bits: 6, endpoint_pbits: false, shared_pbits: false, index_bits: 2, index_bits_2: 3 },
+    Bc7Mode { subsets: 1, partition_bits: 0, rotation_bits: 2, index_selection_bits: 0, colour_bits: 7, alpha_bits: 8, endpoint_pbits: false, shared_pbits: false, index_bits: 2, index_bits_2: 2 },
+    Bc7Mode { subsets: 1, partition_bits: 0, rotation_bits: 0, index_selection_bits: 0, colour_bits: 7, alpha_bits: 7, endpoint_pbits: true, shared_pbits: false, index_bits: 4, index_bits_2: 0 },
+    Bc7Mode { subsets: 2, partition_bits: 6, rotation_bits: 0, index_selection_bits: 0, colour_bits: 5, alpha_bits: 5, endpoint_pbits: true, shared_pbits: false, index_bits: 2, index_bits_2: 0 },
+];
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents the layout of a BC7 mode.
+struct Bc7Mode {
+    subsets: usize,
+    partition_bits: usize,
+    rotation_bits: usize,
+    index_selection_bits: usize,
+    colour_bits: usize,
+    alpha_bits: usize,
+    endpoint_pbits: bool,
+    shared_pbits: bool,
+    index_bits: usize,
+    index_bits_2: usize,
+}
+
+/// This struct is used to read the bits of a BC7 block, from the least significant one.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+/// This struct is used to write the bits of a BC7 block, from the least significant one.
+struct BitWriter<'a> {
+    data: &'a mut [u8],
+    position: usize,
+}
+
+//---------------------------------------------------------------------------//
+//                              Implementations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `BitReader`.
+impl<'a> BitReader<'a> {
+
+    /// This function reads up to 8 bits.
+    fn read(&mut self, bits: usize) -> u8 {
+        let mut value = 0;
+        for bit in 0..bits {
+            value |= ((self.data[self.position / 8] >> (self.position % 8)) & 1) << bit;
+            self.position += 1;
+        }
+        value
+    }
+}
+
+/// Implementation of `BitWriter`.
+impl<'a> BitWriter<'a> {
+
+    /// This function writes up to 8 bits.
+    fn write(&mut self, value: u8, bits: usize) {
+        for bit in 0..bits {
+            self.data[self.position / 8] |= ((value >> bit) & 1) << (self.position % 8);
+            self.position += 1;
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Public functions
+//---------------------------------------------------------------------------//
+
+/// This function decodes the block-compressed data of an image of the provided size into RGBA8 pixels.
+pub fn decode(data: &[u8], width: u32, height: u32, format: ImageFormat) -> Result<Vec<u8>> {
+    let block_size = format.get_block_size();
+    let blocks_x = ((width + 3) / 4) as usize;
+    let blocks_y = ((height + 3) / 4) as usize;
+    if data.len() < blocks_x * blocks_y * block_size {
+        return Err(ErrorKind::ImageDecode(format!("Expected {} bytes of {} data, but found {}.", blocks_x * blocks_y * block_size, format, data.len())).into());
+    }
+
+    let (width, height) = (width as usize, height as usize);
+    let mut pixels = vec![0; width * height * 4];
+    let mut decoded = [0; 64];
+    for (block_index, block) in data.chunks_exact(block_size).take(blocks_x * blocks_y).enumerate() {
+        match format {
+            ImageFormat::Dxt1 => decode_bc1(block, &mut decoded, true),
+            ImageFormat::Dxt3 => decode_bc2(block, &mut decoded),
+            ImageFormat::Dxt5 => decode_bc3(block, &mut decoded),
+            ImageFormat::Bc4 => decode_bc4(block, &mut decoded),
+            ImageFormat::Bc5 => decode_bc5(block, &mut decoded),
+            ImageFormat::Bc7 => decode_bc7(block, &mut decoded),
+            _ => return Err(ErrorKind::ImageDecode(format!("{} is not a block-compressed format.", format)).into()),
+        }
+
+        // Blocks on the borders may have pixels outside the image. Those are ignored.
+        let block_x = (block_index % blocks_x) * 4;
+        let block_y = (block_index / blocks_x) * 4;
+        for y in 0..4.min(height - block_y) {
+            for x in 0..4.min(width - block_x) {
+                let pixel = ((block_y + y) * width + block_x + x) * 4;
+                pixels[pixel..pixel + 4].copy_from_slice(&decoded[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4]);
+            }
+        }
+    }
+
+    Ok(pixels)
+}
+
+/// This function encodes the provided RGBA8 pixels of an image of the provided size into block-compressed data.
+pub fn encode(pixels: &[u8], width: u32, height: u32, format: ImageFormat) -> Result<Vec<u8>> {
+    let block_size = format.get_block_size();
+    let (width, height) = (width as usize, height as usize);
+    let blocks_x = (width + 3) / 4;
+    let blocks_y = (height + 3) / 4;
+
+    let mut data = vec![0; blocks_x * blocks_y * block_size];
+    let mut block_pixels = [0; 64];
+    for (block_index, block) in data.chunks_exact_mut(block_size).enumerate() {
+
+        // Blocks on the borders repeat the last row/column of the image.
+        let block_x = (block_index % blocks_x) * 4;
+        let block_y = (block_index / blocks_x) * 4;
+        for y in 0..4 {
+            for x in 0..4 {
+                let pixel = ((block_y + y).min(height - 1) * width + (block_x + x).min(width - 1)) * 4;
+                block_pixels[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4].copy_from_slice(&pixels[pixel..pixel + 4]);
+            }
+        }
+
+        match format {
+            ImageFormat::Dxt1 => encode_bc1(&block_pixels, block),
+            ImageFormat::Dxt3 => {
+                for pixel in 0..16 {
+                    block[pixel / 2] |= (((u32::from(block_pixels[pixel * 4 + 3]) + 8) / 17) as u8) << ((pixel % 2) * 4);
+                }
+                encode_bc1(&block_pixels, &mut block[8..]);
+            }
+            ImageFormat::Dxt5 => {
+                encode_bc4_channel(&get_channel(&block_pixels, 3), block);
+                encode_bc1(&block_pixels, &mut block[8..]);
+            }
+            ImageFormat::Bc4 => encode_bc4_channel(&get_channel(&block_pixels, 0), block),
+            ImageFormat::Bc5 => {
+                encode_bc4_channel(&get_channel(&block_pixels, 0), block);
+                encode_bc4_channel(&get_channel(&block_pixels, 1), &mut block[8..]);
+            }
+            ImageFormat::Bc7 => encode_bc7(&block_pixels, block),
+            _ => return Err(ErrorKind::ImageEncode(format!("{} is not a block-compressed format.", format)).into()),
+        }
+    }
+
+    Ok(data)
+}
+
+//---------------------------------------------------------------------------//
+//                              Block decoders
+//---------------------------------------------------------------------------//
+
+/// This function decodes a DXT1/BC1 block. If `punch_through` is false, the block is always decoded in 4-colour mode.
+fn decode_bc1(block: &[u8], pixels: &mut [u8; 64], punch_through: bool) {
+    let colour_0 = u16::from_le_bytes([block[0], block[1]]);
+    let colour_1 = u16::from_le_bytes([block[2], block[3]]);
+    let palette = get_bc1_palette(colour_0, colour_1, punch_through);
+
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    for pixel in 0..16 {
+        pixels[pixel * 4..pixel * 4 + 4].copy_from_slice(&palette[((indices >> (pixel * 2)) & 3) as usize]);
+    }
+}
+
+/// This function decodes a DXT3/BC2 block.
+fn decode_bc2(block: &[u8], pixels: &mut [u8; 64]) {
+    decode_bc1(&block[8..], pixels, false);
+    for pixel in 0..16 {
+        pixels[pixel * 4 + 3] = ((block[pixel / 2] >> ((pixel % 2) * 4)) & 0xF) * 17;
+    }
+}
+
+/// This function decodes a DXT5/BC3 block.
+fn decode_bc3(block: &[u8], pixels: &mut [u8; 64]) {
+    decode_bc1(&block[8..], pixels, false);
+    for (pixel, alpha) in decode_bc4_channel(block).iter().enumerate() {
+        pixels[pixel * 4 + 3] = *alpha;
+    }
+}
+
+/// This function decodes a BC4 block, as a grayscale image.
+fn decode_bc4(block: &[u8], pixels: &mut [u8; 64]) {
+    for (pixel, value) in decode_bc4_channel(block).iter().enumerate() {
+        pixels[pixel * 4..pixel * 4 + 4].copy_from_slice(&[*value, *value, *value, 255]);
+    }
+}
+
+/// This function decodes a BC5 block. These are used for normal maps, so the blue channel is reconstructed from the other two.
+fn decode_bc5(block: &[u8], pixels: &mut [u8; 64]) {
+    let red = decode_bc4_channel(block);
+    let green = decode_bc4_channel(&block[8..]);
+    for pixel in 0..16 {
+        let x = f32::from(red[pixel]) / 255.0 * 2.0 - 1.0;
+        let y = f32::from(green[pixel]) / 255.0 * 2.0 - 1.0;
+        let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+        pixels[pixel * 4..pixel * 4 + 4].copy_from_slice(&[red[pixel], green[pixel], ((z + 1.0) / 2.0 * 255.0).round() as u8, 255]);
+    }
+}
+
+/// This function decodes a single channel of 8 bytes, as used by BC3, BC4 and BC5 blocks.
+fn decode_bc4_channel(block: &[u8]) -> [u8; 16] {
+    let palette = get_bc4_palette(block[0], block[1]);
+    let indices = u64::from_le_bytes([block[2], block[3], block[4], block[5], block[6], block[7], 0, 0]);
+    let mut values = [0; 16];
+    for (pixel, value) in values.iter_mut().enumerate() {
+        *value = palette[((indices >> (pixel * 3)) & 7) as usize];
+    }
+    values
+}
+
+/// This function decodes a BC7 block.
+fn decode_bc7(block: &[u8], pixels: &mut [u8; 64]) {
+    let mut reader = BitReader { data: block, position: 0 };
+
+    // The mode is the amount of zeros before the first one. Blocks without mode are reserved, and decode as transparent black.
+    let mode_index = match (0..8).find(|_| reader.read(1) == 1) {
+        Some(mode_index) => mode_index,
+        None => {
+            pixels.iter_mut().for_each(|value| *value = 0);
+            return;
+        }
+    };
+
+    let mode = &BC7_MODES[mode_index];
+    let partition = reader.read(mode.partition_bits) as usize;
+    let rotation = reader.read(mode.rotation_bits);
+    let index_selection = reader.read(mode.index_selection_bits);
+
+    // Endpoints go channel by channel, then their p-bits, which are the least significant bit of each endpoint.
+    let endpoint_count = mode.subsets * 2;
+    let mut endpoints = [[0u8; 4]; 6];
+    for channel in 0..3 {
+        for endpoint in endpoints.iter_mut().take(endpoint_count) {
+            endpoint[channel] = reader.read(mode.colour_bits);
+        }
+    }
+
+    for endpoint in endpoints.iter_mut().take(endpoint_count) {
+        endpoint[3] = reader.read(mode.alpha_bits);
+    }
+
+    let mut colour_bits = mode.colour_bits;
+    let mut alpha_bits = mode.alpha_bits;
+    if mode.endpoint_pbits || mode.shared_pbits {
+        let mut pbits = [0; 6];
+        if mode.endpoint_pbits {
+            pbits.iter_mut().take(endpoint_count).for_each(|pbit| *pbit = reader.read(1));
+        } else {
+            for subset in 0..mode.subsets {
+                let pbit = reader.read(1);
+                pbits[subset * 2] = pbit;
+                pbits[subset * 2 + 1] = pbit;
+            }
+        }
+
+        for (endpoint, pbit) in endpoints.iter_mut().zip(pbits.iter()).take(endpoint_count) {
+            endpoint.iter_mut().for_each(|value| *value = (*value << 1) | pbit);
+        }
+
+        colour_bits += 1;
+        if alpha_bits > 0 {
+            alpha_bits += 1;
+        }
+    }
+
+    for endpoint in endpoints.iter_mut().take(endpoint_count) {
+        (0..3).for_each(|channel| endpoint[channel] = expand_bits(endpoint[channel], colour_bits));
+        endpoint[3] = if alpha_bits > 0 { expand_bits(endpoint[3], alpha_bits) } else { 255 };
+    }
+
+    // Anchor pixels have their most significant index bit implicitly set to zero.
+    let get_subset = |pixel: usize| match mode.subsets {
+        1 => 0,
+        2 => ((BC7_PARTITIONS_2[partition] >> pixel) & 1) as usize,
+        _ => ((BC7_PARTITIONS_3[partition] >> (pixel * 2)) & 3) as usize,
+    };
+
+    let is_anchor = |pixel: usize| pixel == 0 || match mode.subsets {
+        2 => pixel == BC7_ANCHORS_2[partition] as usize,
+        3 => pixel == BC7_ANCHORS_3_A[partition] as usize || pixel == BC7_ANCHORS_3_B[partition] as usize,
+        _ => false,
+    };
+
+    let mut indices = [0; 16];
+    for (pixel, index) in indices.iter_mut().enumerate() {
+        *index = reader.read(mode.index_bits - if is_anchor(pixel) { 1 } else { 0 });
+    }
+
+    let mut indices_2 = [0; 16];
+    if mode.index_bits_2 > 0 {
+        for (pixel, index) in indices_2.iter_mut().enumerate() {
+            *index = reader.read(mode.index_bits_2 - if pixel == 0 { 1 } else { 0 });
+        }
+    }
+
+    for pixel in 0..16 {
+        let subset = get_subset(pixel);
+        let endpoint_0 = endpoints[subset * 2];
+        let endpoint_1 = endpoints[subset * 2 + 1];
+        let (colour_index, colour_index_bits, alpha_index, alpha_index_bits) = if mode.index_bits_2 == 0 {
+            (indices[pixel], mode.index_bits, indices[pixel], mode.index_bits)
+        } else if index_selection == 0 {
+            (indices[pixel], mode.index_bits, indices_2[pixel], mode.index_bits_2)
+        } else {
+            (indices_2[pixel], mode.index_bits_2, indices[pixel], mode.index_bits)
+        };
+
+        let mut colour = [0; 4];
+        for channel in 0..3 {
+            colour[channel] = interpolate(endpoint_0[channel], endpoint_1[channel], colour_index, colour_index_bits);
+        }
+        colour[3] = interpolate(endpoint_0[3], endpoint_1[3], alpha_index, alpha_index_bits);
+
+        if rotation > 0 {
+            colour.swap(3, rotation as usize - 1);
+        }
+
+        pixels[pixel * 4..pixel * 4 + 4].copy_from_slice(&colour);
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Block encoders
+//---------------------------------------------------------------------------//
+
+/// This function encodes a DXT1/BC1 block, ignoring the alpha channel.
+fn encode_bc1(pixels: &[u8; 64], block: &mut [u8]) {
+    let (min, max) = get_endpoints(pixels, 3);
+    let mut colour_0 = pack_565(&max);
+    let mut colour_1 = pack_565(&min);
+
+    // The first colour must be the greatest one to get 4-colour mode. If both are equal, all pixels use the first one.
+    if colour_0 < colour_1 {
+        std::mem::swap(&mut colour_0, &mut colour_1);
+    }
+
+    let palette = get_bc1_palette(colour_0, colour_1, false);
+    let mut indices = 0u32;
+    if colour_0 != colour_1 {
+        for pixel in 0..16 {
+            indices |= (get_nearest(&palette, &pixels[pixel * 4..pixel * 4 + 3]) as u32) << (pixel * 2);
+        }
+    }
+
+    block[0..2].copy_from_slice(&colour_0.to_le_bytes());
+    block[2..4].copy_from_slice(&colour_1.to_le_bytes());
+    block[4..8].copy_from_slice(&indices.to_le_bytes());
+}
+
+/// This function encodes a single channel in 8 bytes, as used by BC3, BC4 and BC5 blocks.
+fn encode_bc4_channel(values: &[u8; 16], block: &mut [u8]) {
+    let max = *values.iter().max().unwrap();
+    let min = *values.iter().min().unwrap();
+    let palette = get_bc4_palette(max, min);
+
+    let mut indices = 0u64;
+    if max != min {
+        for (pixel, value) in values.iter().enumerate() {
+            let index = palette.iter().enumerate().min_by_key(|(_, palette_value)| (i32::from(**palette_value) - i32::from(*value)).abs()).unwrap().0;
+            indices |= (index as u64) << (pixel * 3);
+        }
+    }
+
+    block[0] = max;
+    block[1] = min;
+    block[2..8].copy_from_slice(&indices.to_le_bytes()[..6]);
+}
+
+/// This function encodes a BC7 block, using mode 6.
+fn encode_bc7(pixels: &[u8; 64], block: &mut [u8]) {
+    let (min, max) = get_endpoints(pixels, 4);
+    let (mut endpoint_0, mut pbit_0) = quantize_with_pbit(&min);
+    let (mut endpoint_1, mut pbit_1) = quantize_with_pbit(&max);
+
+    let palette = get_bc7_palette(&endpoint_0, pbit_0, &endpoint_1, pbit_1);
+    let mut indices = [0u8; 16];
+    for (pixel, index) in indices.iter_mut().enumerate() {
+        *index = get_nearest(&palette, &pixels[pixel * 4..pixel * 4 + 4]) as u8;
+    }
+
+    // The first pixel is an anchor, so its index must fit in 3 bits. If it doesn't, swap the endpoints.
+    if indices[0] >= 8 {
+        std::mem::swap(&mut endpoint_0, &mut endpoint_1);
+        std::mem::swap(&mut pbit_0, &mut pbit_1);
+        indices.iter_mut().for_each(|index| *index = 15 - *index);
+    }
+
+    block.iter_mut().for_each(|byte| *byte = 0);
+    let mut writer = BitWriter { data: block, position: 0 };
+    writer.write(1 << 6, 7);
+    for channel in 0..4 {
+        writer.write(endpoint_0[channel], 7);
+        writer.write(endpoint_1[channel], 7);
+    }
+
+    writer.write(pbit_0, 1);
+    writer.write(pbit_1, 1);
+    for (pixel, index) in indices.iter().enumerate() {
+        writer.write(*index, if pixel == 0 { 3 } else { 4 });
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Helper functions
+//---------------------------------------------------------------------------//
+
+/// This function returns the 4-colour palette of a DXT1/BC1 block.
+fn get_bc1_palette(colour_0: u16, colour_1: u16, punch_through: bool) -> [[u8; 4]; 4] {
+    let rgb_0 = unpack_565(colour_0);
+    let rgb_1 = unpack_565(colour_1);
+    let mut palette = [[rgb_0[0], rgb_0[1], rgb_0[2], 255], [rgb_1[0], rgb_1[1], rgb_1[2], 255], [0; 4], [0; 4]];
+    for channel in 0..3 {
+        let (value_0, value_1) = (u32::from(rgb_0[channel]), u32::from(rgb_1[channel]));
+        if colour_0 > colour_1 || !punch_through {
+            palette[2][channel] = ((2 * value_0 + value_1 + 1) / 3) as u8;
+            palette[3][channel] = ((value_0 + 2 * value_1 + 1) / 3) as u8;
+        } else {
+            palette[2][channel] = ((value_0 + value_1) / 2) as u8;
+        }
+    }
+
+    palette[2][3] = 255;
+    palette[3][3] = if colour_0 > colour_1 || !punch_through { 255 } else { 0 };
+    palette
+}
+
+/// This function returns the 8-value palette of a single channel of a BC3/BC4/BC5 block.
+fn get_bc4_palette(value_0: u8, value_1: u8) -> [u8; 8] {
+    let (first, second) = (u32::from(value_0), u32::from(value_1));
+    let mut palette = [value_0, value_1, 0, 0, 0, 0, 0, 255];
+    if value_0 > value_1 {
+        for index in 1..7 {
+            palette[index + 1] = (((7 - index as u32) * first + index as u32 * second + 3) / 7) as u8;
+        }
+    } else {
+        for index in 1..5 {
+            palette[index + 1] = (((5 - index as u32) * first + index as u32 * second + 2) / 5) as u8;
+        }
+    }
+    palette
+}
+
+/// This function returns the 16-colour palette of a BC7 block encoded with mode 6.
+fn get_bc7_palette(endpoint_0: &[u8; 4], pbit_0: u8, endpoint_1: &[u8; 4], pbit_1: u8) -> [[u8; 4]; 16] {
+    let mut palette = [[0; 4]; 16];
+    for (index, colour) in palette.iter_mut().enumerate() {
+        for channel in 0..4 {
+            colour[channel] = interpolate((endpoint_0[channel] << 1) | pbit_0, (endpoint_1[channel] << 1) | pbit_1, index as u8, 4);
+        }
+    }
+    palette
+}
+
+/// This function returns the index of the colour of the palette nearest to the provided one. Only the channels in `colour` are compared.
+fn get_nearest(palette: &[[u8; 4]], colour: &[u8]) -> usize {
+    palette.iter().enumerate().min_by_key(|(_, palette_colour)| {
+        colour.iter().zip(palette_colour.iter()).map(|(value, palette_value)| (i32::from(*value) - i32::from(*palette_value)).pow(2)).sum::<i32>()
+    }).unwrap().0
+}
+
+/// This function returns the two colours at the ends of the principal axis of the provided channels of a block.
+///
+/// The axis is found by power iteration over the covariance of the colours, which is good enough for 4x4 blocks.
+fn get_endpoints(pixels: &[u8; 64], channels: usize) -> ([u8; 4], [u8; 4]) {
+    let colours = pixels.chunks_exact(4).map(|pixel| {
+        let mut colour = [0.0f32; 4];
+        (0..channels).for_each(|channel| colour[channel] = f32::from(pixel[channel]));
+        colour
+    }).collect::<Vec<[f32; 4]>>();
+
+    let mut mean = [0.0f32; 4];
+    colours.iter().for_each(|colour| (0..4).for_each(|channel| mean[channel] += colour[channel] / 16.0));
+
+    let mut covariance = [[0.0f32; 4]; 4];
+    for colour in &colours {
+        for row in 0..4 {
+            for column in 0..4 {
+                covariance[row][column] += (colour[row] - mean[row]) * (colour[column] - mean[column]);
+            }
+        }
+    }
+
+    let mut axis = [1.0f32, 1.0, 1.0, 1.0];
+    for _ in 0..8 {
+        let mut next_axis = [0.0f32; 4];
+        (0..4).for_each(|row| next_axis[row] = (0..4).map(|column| covariance[row][column] * axis[column]).sum());
+        let length = next_axis.iter().map(|value| value * value).sum::<f32>().sqrt();
+        if length < f32::EPSILON {
+            break;
+        }
+        (0..4).for_each(|channel| axis[channel] = next_axis[channel] / length);
+    }
+
+    let projections = colours.iter().map(|colour| (0..4).map(|channel| (colour[channel] - mean[channel]) * axis[channel]).sum::<f32>()).collect::<Vec<f32>>();
+    let min_projection = projections.iter().copied().fold(f32::INFINITY, f32::min);
+    let max_projection = projections.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+    let mut min = [255; 4];
+    let mut max = [255; 4];
+    for channel in 0..channels {
+        min[channel] = (mean[channel] + axis[channel] * min_projection).round().max(0.0).min(255.0) as u8;
+        max[channel] = (mean[channel] + axis[channel] * max_projection).round().max(0.0).min(255.0) as u8;
+    }
+    (min, max)
+}
+
+/// This function returns the values of a channel of a block.
+fn get_channel(pixels: &[u8; 64], channel: usize) -> [u8; 16] {
+    let mut values = [0; 16];
+    values.iter_mut().enumerate().for_each(|(pixel, value)| *value = pixels[pixel * 4 + channel]);
+    values
+}
+
+/// This function quantizes an 8-bit colour to 7 bits per channel plus a shared p-bit, picking the p-bit with the lowest error.
+fn quantize_with_pbit(colour: &[u8; 4]) -> ([u8; 4], u8) {
+    (0..2).map(|pbit| {
+        let mut quantized = [0; 4];
+        let mut error = 0;
+        for channel in 0..4 {
+            quantized[channel] = ((i32::from(colour[channel]) - i32::from(pbit) + 1) / 2).max(0).min(127) as u8;
+            error += (i32::from(colour[channel]) - i32::from((quantized[channel] << 1) | pbit)).pow(2);
+        }
+        (quantized, pbit, error)
+    }).min_by_key(|(_, _, error)| *error).map(|(quantized, pbit, _)| (quantized, pbit)).unwrap()
+}
+
+/// This function interpolates between two values with the BC7 weights for the provided index size.
+fn interpolate(value_0: u8, value_1: u8, index: u8, index_bits: usize) -> u8 {
+    let weight = match index_bits {
+        2 => WEIGHTS_2[index as usize],
+        3 => WEIGHTS_3[index as usize],
+        _ => WEIGHTS_4[index as usize],
+    };
+    (((64 - weight) * u32::from(value_0) + weight * u32::from(value_1) + 32) >> 6) as u8
+}
+
+/// This function expands a value of the provided amount of bits to 8 bits, replicating its most significant bits.
+fn expand_bits(value: u8, bits: usize) -> u8 {
+    if bits >= 8 { value } else { (value << (8 - bits)) | (value >> (2 * bits - 8)) }
+}
+
+/// This function unpacks a RGB565 colour to RGB8.
+fn unpack_565(colour: u16) -> [u8; 3] {
+    let red = ((colour >> 11) & 0x1F) as u8;
+    let green = ((colour >> 5) & 0x3F) as u8;
+    let blue = (colour & 0x1F) as u8;
+    [(red << 3) | (red >> 2), (green << 2) | (green >> 4), (blue << 3) | (blue >> 2)]
+}
+
+/// This function packs a RGB8 colour as RGB565.
+fn pack_565(colour: &[u8]) -> u16 {
+    ((u16::from(colour[0]) * 31 + 127) / 255) << 11 | ((u16::from(colour[1]) * 63 + 127) / 255) << 5 | ((u16::from(colour[2]) * 31 + 127) / 255)
+}
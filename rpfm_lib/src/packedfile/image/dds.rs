@@ -0,0 +1,272 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to read and write DDS images.
+
+The structure of a DDS file is:
+- The `DDS ` signature, and a 124 bytes header with the size, mipmap count and pixel format of the image.
+- If the pixel format says `DX10`, a 20 bytes extended header with the DXGI format and the amount of layers.
+- The data of each face (6 for cube maps, 1 for anything else), each one with all its mipmaps, from biggest to smallest.
+
+!*/
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::common::{decoder::Decoder, encoder::Encoder};
+use super::{bc, DecodedImage, ImageFormat, RgbaImage};
+
+/// Signature of DDS files.
+pub const SIGNATURE: &str = "DDS ";
+
+/// Length of the signature and the header of a DDS file.
+const HEADER_LENGTH: usize = 128;
+
+/// Length of the extended DX10 header.
+const HEADER_DX10_LENGTH: usize = 20;
+
+/// Header flags: `CAPS | HEIGHT | WIDTH | PIXELFORMAT`.
+const FLAGS_REQUIRED: u32 = 0x1 | 0x2 | 0x4 | 0x1000;
+
+/// Header flag for the pitch of uncompressed images.
+const FLAG_PITCH: u32 = 0x8;
+
+/// Header flag for a valid mipmap count.
+const FLAG_MIPMAP_COUNT: u32 = 0x2_0000;
+
+/// Header flag for the linear size of compressed images.
+const FLAG_LINEAR_SIZE: u32 = 0x8_0000;
+
+/// Pixel format flag for alpha channel in uncompressed images.
+const PIXEL_FORMAT_ALPHA_PIXELS: u32 = 0x1;
+
+/// Pixel format flag for compressed images.
+const PIXEL_FORMAT_FOURCC: u32 = 0x4;
+
+/// Pixel format flag for uncompressed images.
+const PIXEL_FORMAT_RGB: u32 = 0x40;
+
+/// Caps flags for images with mipmaps: `COMPLEX | MIPMAP`.
+const CAPS_MIPMAPS: u32 = 0x8 | 0x40_0000;
+
+/// Caps flag for textures.
+const CAPS_TEXTURE: u32 = 0x1000;
+
+/// Caps2 flag for cube maps.
+const CAPS2_CUBEMAP: u32 = 0x200;
+
+/// Caps2 flags for each face of a cube map.
+const CAPS2_CUBEMAP_FACES: u32 = 0xFC00;
+
+/// Misc flag of the DX10 header for cube maps.
+const DX10_MISC_TEXTURECUBE: u32 = 0x4;
+
+//---------------------------------------------------------------------------//
+//                              Functions
+//---------------------------------------------------------------------------//
+
+/// This function decodes a DDS image, with all its faces and mipmaps.
+pub fn read(data: &[u8]) -> Result<DecodedImage> {
+    if data.len() < HEADER_LENGTH || data.decode_string_u8(0, 4)? != SIGNATURE {
+        return Err(ErrorKind::ImageDecode("This is not a DDS file.".to_owned()).into());
+    }
+
+    let height = data.decode_integer_u32(12)?;
+    let width = data.decode_integer_u32(16)?;
+    let mip_count = data.decode_integer_u32(28)?.max(1);
+    let pixel_format_flags = data.decode_integer_u32(80)?;
+    let four_cc = data.get_bytes_checked(84, 4)?;
+    let bit_count = data.decode_integer_u32(88)?;
+    let red_mask = data.decode_integer_u32(92)?;
+    let alpha_mask = data.decode_integer_u32(104)?;
+    let caps_2 = data.decode_integer_u32(112)?;
+
+    if width == 0 || height == 0 {
+        return Err(ErrorKind::ImageDecode("The image has no pixels.".to_owned()).into());
+    }
+
+    // The last mipmap is the 1x1 one, so there cannot be more mipmaps than bits in the biggest side.
+    let max_mip_count = 32 - width.max(height).leading_zeros();
+    if mip_count > max_mip_count {
+        return Err(ErrorKind::ImageDecode(format!("The image has {} mipmaps, but a {}x{} image cannot have more than {}.", mip_count, width, height, max_mip_count)).into());
+    }
+
+    let mut data_start = HEADER_LENGTH;
+    let mut opaque = false;
+    let (format, is_cube_map, face_count) = if pixel_format_flags & PIXEL_FORMAT_FOURCC != 0 && four_cc == b"DX10" {
+        let dxgi_format = data.decode_integer_u32(HEADER_LENGTH)?;
+        let misc_flags = data.decode_integer_u32(HEADER_LENGTH + 8)?;
+        let array_size = data.decode_integer_u32(HEADER_LENGTH + 12)?.max(1);
+        data_start += HEADER_DX10_LENGTH;
+
+        let format = match dxgi_format {
+            28 | 29 => ImageFormat::Rgba8,
+            71 | 72 => ImageFormat::Dxt1,
+            74 | 75 => ImageFormat::Dxt3,
+            77 | 78 => ImageFormat::Dxt5,
+            80 | 81 => ImageFormat::Bc4,
+            83 | 84 => ImageFormat::Bc5,
+            87 | 91 => ImageFormat::Bgra8,
+            98 | 99 => ImageFormat::Bc7,
+            _ => return Err(ErrorKind::ImageDecode(format!("Unsupported DXGI format: {}.", dxgi_format)).into()),
+        };
+
+        let is_cube_map = misc_flags & DX10_MISC_TEXTURECUBE != 0;
+        let face_count = match array_size.checked_mul(if is_cube_map { 6 } else { 1 }) {
+            Some(face_count) => face_count,
+            None => return Err(ErrorKind::ImageDecode(format!("Invalid array size: {}.", array_size)).into()),
+        };
+        (format, is_cube_map, face_count)
+    }
+
+    else {
+        let format = if pixel_format_flags & PIXEL_FORMAT_FOURCC != 0 {
+            match four_cc {
+                b"DXT1" => ImageFormat::Dxt1,
+                b"DXT2" | b"DXT3" => ImageFormat::Dxt3,
+                b"DXT4" | b"DXT5" => ImageFormat::Dxt5,
+                b"ATI1" | b"BC4U" => ImageFormat::Bc4,
+                b"ATI2" | b"BC5U" => ImageFormat::Bc5,
+                _ => return Err(ErrorKind::ImageDecode(format!("Unsupported FourCC: {}.", String::from_utf8_lossy(four_cc))).into()),
+            }
+        }
+
+        else if pixel_format_flags & PIXEL_FORMAT_RGB != 0 {
+            opaque = pixel_format_flags & PIXEL_FORMAT_ALPHA_PIXELS == 0 || alpha_mask == 0;
+            match (bit_count, red_mask) {
+                (32, 0xFF) => ImageFormat::Rgba8,
+                (32, 0xFF_0000) => ImageFormat::Bgra8,
+                (24, 0xFF_0000) => ImageFormat::Bgr8,
+                _ => return Err(ErrorKind::ImageDecode(format!("Unsupported uncompressed format: {} bits, with red mask {:#X}.", bit_count, red_mask)).into()),
+            }
+        }
+
+        else {
+            return Err(ErrorKind::ImageDecode("Unsupported pixel format.".to_owned()).into());
+        };
+
+        let is_cube_map = caps_2 & CAPS2_CUBEMAP != 0;
+        (format, is_cube_map, if is_cube_map { (caps_2 & CAPS2_CUBEMAP_FACES).count_ones().max(1) } else { 1 })
+    };
+
+    let mut faces = vec![];
+    let mut offset = data_start;
+    for _ in 0..face_count {
+        let mut mipmaps = vec![];
+        for mip in 0..mip_count {
+            let mip_width = (width >> mip).max(1);
+            let mip_height = (height >> mip).max(1);
+            let size = format.get_data_size(mip_width, mip_height);
+            let mip_data = match data.get(offset..offset.saturating_add(size)) {
+                Some(mip_data) => mip_data,
+                None => return Err(ErrorKind::ImageDecode(format!("The data of the image ends before the mipmap {} of face {}.", mip, faces.len())).into()),
+            };
+
+            let mut pixels = match format {
+                ImageFormat::Rgba8 => mip_data.to_vec(),
+                ImageFormat::Bgra8 => mip_data.chunks_exact(4).flat_map(|pixel| vec![pixel[2], pixel[1], pixel[0], pixel[3]]).collect(),
+                ImageFormat::Bgr8 => mip_data.chunks_exact(3).flat_map(|pixel| vec![pixel[2], pixel[1], pixel[0], 255]).collect(),
+                _ => bc::decode(mip_data, mip_width, mip_height, format)?,
+            };
+
+            if opaque {
+                pixels.chunks_exact_mut(4).for_each(|pixel| pixel[3] = 255);
+            }
+
+            mipmaps.push(RgbaImage::new(mip_width, mip_height, pixels)?);
+            offset += size;
+        }
+        faces.push(mipmaps);
+    }
+
+    Ok(DecodedImage {
+        format,
+        mip_count,
+        is_cube_map,
+        faces,
+    })
+}
+
+/// This function encodes an image as a DDS image of the provided format, with optional mipmaps.
+pub fn write(image: &RgbaImage, format: ImageFormat, generate_mipmaps: bool) -> Result<Vec<u8>> {
+    let mut mipmaps = vec![image.clone()];
+    if generate_mipmaps {
+        while mipmaps.last().map(|mipmap| mipmap.width > 1 || mipmap.height > 1) == Some(true) {
+            let mipmap = mipmaps.last().unwrap().get_half_size();
+            mipmaps.push(mipmap);
+        }
+    }
+
+    let mut data = vec![];
+    data.encode_string_u8(SIGNATURE);
+    data.encode_integer_u32(124);
+
+    let mut flags = FLAGS_REQUIRED;
+    if mipmaps.len() > 1 { flags |= FLAG_MIPMAP_COUNT; }
+    flags |= if format.is_compressed() { FLAG_LINEAR_SIZE } else { FLAG_PITCH };
+    data.encode_integer_u32(flags);
+    data.encode_integer_u32(image.height);
+    data.encode_integer_u32(image.width);
+    data.encode_integer_u32(if format.is_compressed() { format.get_data_size(image.width, image.height) } else { format.get_data_size(image.width, 1) } as u32);
+    data.encode_integer_u32(0);
+    data.encode_integer_u32(mipmaps.len() as u32);
+    data.extend_from_slice(&[0; 44]);
+
+    // Pixel format. BC7 needs the extended header, as it has no FourCC of its own.
+    data.encode_integer_u32(32);
+    match format {
+        ImageFormat::Dxt1 | ImageFormat::Dxt3 | ImageFormat::Dxt5 | ImageFormat::Bc4 | ImageFormat::Bc5 | ImageFormat::Bc7 => {
+            data.encode_integer_u32(PIXEL_FORMAT_FOURCC);
+            data.encode_string_u8(match format {
+                ImageFormat::Dxt1 => "DXT1",
+                ImageFormat::Dxt3 => "DXT3",
+                ImageFormat::Dxt5 => "DXT5",
+                ImageFormat::Bc4 => "ATI1",
+                ImageFormat::Bc5 => "ATI2",
+                _ => "DX10",
+            });
+            data.extend_from_slice(&[0; 20]);
+        }
+        ImageFormat::Rgba8 | ImageFormat::Bgra8 | ImageFormat::Bgr8 => {
+            let (bit_count, masks, flags) = match format {
+                ImageFormat::Rgba8 => (32, [0xFF, 0xFF00, 0xFF_0000, 0xFF00_0000], PIXEL_FORMAT_RGB | PIXEL_FORMAT_ALPHA_PIXELS),
+                ImageFormat::Bgra8 => (32, [0xFF_0000, 0xFF00, 0xFF, 0xFF00_0000], PIXEL_FORMAT_RGB | PIXEL_FORMAT_ALPHA_PIXELS),
+                _ => (24, [0xFF_0000, 0xFF00, 0xFF, 0], PIXEL_FORMAT_RGB),
+            };
+            data.encode_integer_u32(flags);
+            data.encode_integer_u32(0);
+            data.encode_integer_u32(bit_count);
+            masks.iter().for_each(|mask| data.encode_integer_u32(*mask));
+        }
+        _ => return Err(ErrorKind::ImageEncode(format!("{} images cannot be saved as DDS.", format)).into()),
+    }
+
+    data.encode_integer_u32(if mipmaps.len() > 1 { CAPS_TEXTURE | CAPS_MIPMAPS } else { CAPS_TEXTURE });
+    data.extend_from_slice(&[0; 16]);
+
+    if format == ImageFormat::Bc7 {
+        data.encode_integer_u32(98);
+        data.encode_integer_u32(3);
+        data.encode_integer_u32(0);
+        data.encode_integer_u32(1);
+        data.encode_integer_u32(0);
+    }
+
+    for mipmap in &mipmaps {
+        match format {
+            ImageFormat::Rgba8 => data.extend_from_slice(&mipmap.pixels),
+            ImageFormat::Bgra8 => mipmap.pixels.chunks_exact(4).for_each(|pixel| data.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]])),
+            ImageFormat::Bgr8 => mipmap.pixels.chunks_exact(4).for_each(|pixel| data.extend_from_slice(&[pixel[2], pixel[1], pixel[0]])),
+            _ => data.append(&mut bc::encode(&mipmap.pixels, mipmap.width, mipmap.height, format)?),
+        }
+    }
+
+    Ok(data)
+}
@@ -0,0 +1,142 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Image` module, to make sure images are decoded and encoded correctly.
+!*/
+
+use super::*;
+
+/// This function builds a smooth diagonal gradient image of the provided size, with different values in each channel.
+fn build_gradient(width: u32, height: u32) -> RgbaImage {
+    let mut pixels = vec![];
+    for y in 0..height {
+        for x in 0..width {
+            pixels.extend_from_slice(&[((x + y) * 8) as u8, (64 + (x + y) * 4) as u8, (200 - (x + y) * 6) as u8, (255 - (x + y) * 5) as u8]);
+        }
+    }
+    RgbaImage::new(width, height, pixels).unwrap()
+}
+
+/// This function returns the biggest difference between the provided channels of two images.
+fn get_max_difference(image_1: &RgbaImage, image_2: &RgbaImage, channels: &[usize]) -> i16 {
+    image_1.get_ref_pixels().chunks_exact(4).zip(image_2.get_ref_pixels().chunks_exact(4))
+        .flat_map(|(pixel_1, pixel_2)| channels.iter().map(move |channel| (i16::from(pixel_1[*channel]) - i16::from(pixel_2[*channel])).abs()))
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn test_image_png() {
+    let image = build_gradient(13, 7);
+    let png = image.to_png();
+    assert_eq!(RgbaImage::read_png(&png).unwrap(), image);
+
+    let decoded = Image::read(&png).unwrap().decode().unwrap();
+    assert_eq!(decoded.get_ref_format(), &ImageFormat::Png);
+    assert_eq!((decoded.get_width(), decoded.get_height()), (13, 7));
+    assert!(RgbaImage::read_png(&png[..40]).is_err());
+}
+
+#[test]
+fn test_image_dds() {
+    let image = build_gradient(13, 7);
+    let formats = [
+        (ImageFormat::Rgba8, vec![0, 1, 2, 3], 0),
+        (ImageFormat::Bgra8, vec![0, 1, 2, 3], 0),
+        (ImageFormat::Bgr8, vec![0, 1, 2], 0),
+        (ImageFormat::Dxt1, vec![0, 1, 2], 10),
+        (ImageFormat::Dxt3, vec![0, 1, 2, 3], 10),
+        (ImageFormat::Dxt5, vec![0, 1, 2, 3], 10),
+        (ImageFormat::Bc4, vec![0], 4),
+        (ImageFormat::Bc5, vec![0, 1], 4),
+        (ImageFormat::Bc7, vec![0, 1, 2, 3], 4),
+    ];
+
+    for (format, channels, max_difference) in &formats {
+        let png = image.to_png();
+        let dds = Image::from_png(&png, *format, true).unwrap();
+        let decoded = dds.decode().unwrap();
+        assert_eq!(decoded.get_ref_format(), format);
+        assert_eq!(decoded.get_ref_mip_count(), &4);
+        assert_eq!(decoded.get_ref_faces()[0][3].get_ref_width(), &1);
+        assert_eq!((decoded.get_width(), decoded.get_height()), (13, 7));
+        assert!(get_max_difference(decoded.get_ref_image(), &image, channels) <= *max_difference, "{}", format);
+
+        // Without mipmaps, there should only be the full-size image.
+        let decoded = Image::read(&image.to_dds(*format, false).unwrap()).unwrap().decode().unwrap();
+        assert_eq!(decoded.get_ref_faces()[0].len(), 1);
+    }
+
+    // BC7 block in mode 5, with all its endpoints at their maximum value.
+    let mut dds = build_gradient(4, 4).to_dds(ImageFormat::Bc7, false).unwrap();
+    let block_start = dds.len() - 16;
+    dds[block_start..].copy_from_slice(&[0x20, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x03, 0, 0, 0, 0, 0, 0, 0]);
+    assert!(Image::read(&dds).unwrap().decode().unwrap().get_ref_image().get_ref_pixels().iter().all(|value| *value == 255));
+
+    // Truncated images should fail instead of returning garbage.
+    let dds = image.to_dds(ImageFormat::Bc7, true).unwrap();
+    assert!(Image::read(&dds[..dds.len() - 1]).unwrap().decode().is_err());
+
+    // So should headers with impossible mipmap counts, sizes or array sizes.
+    let mut dds = image.to_dds(ImageFormat::Dxt5, true).unwrap();
+    dds[28..32].copy_from_slice(&5u32.to_le_bytes());
+    assert!(Image::read(&dds).unwrap().decode().is_err());
+    dds[28..32].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(Image::read(&dds).unwrap().decode().is_err());
+
+    let mut dds = image.to_dds(ImageFormat::Rgba8, false).unwrap();
+    dds[12..20].copy_from_slice(&[0xFF; 8]);
+    assert!(Image::read(&dds).unwrap().decode().is_err());
+
+    let mut dds = build_gradient(4, 4).to_dds(ImageFormat::Bc7, false).unwrap();
+    dds[128 + 8..128 + 12].copy_from_slice(&4u32.to_le_bytes());
+    dds[128 + 12..128 + 16].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(Image::read(&dds).unwrap().decode().is_err());
+}
+
+#[test]
+fn test_image_dds_cube_map() {
+    let image = build_gradient(8, 8);
+    let mut dds = image.to_dds(ImageFormat::Dxt5, false).unwrap();
+    let face = dds[128..].to_vec();
+    (0..5).for_each(|_| dds.extend_from_slice(&face));
+    dds[112..116].copy_from_slice(&0xFE00u32.to_le_bytes());
+
+    let decoded = Image::read(&dds).unwrap().decode().unwrap();
+    assert!(*decoded.get_ref_is_cube_map());
+    assert_eq!(decoded.get_ref_faces().len(), 6);
+    assert_eq!(decoded.get_ref_faces()[5][0], decoded.get_ref_faces()[0][0]);
+}
+
+#[test]
+fn test_image_tga() {
+
+    // 2x2 RLE truecolour image, stored from the bottom row to the top one.
+    let mut tga = vec![0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 2, 0, 32, 8];
+    tga.extend_from_slice(&[0x81, 0, 0, 255, 255]);
+    tga.extend_from_slice(&[0x01, 255, 0, 0, 128, 0, 255, 0, 255]);
+
+    let decoded = Image::read(&tga).unwrap().decode().unwrap();
+    assert_eq!(decoded.get_ref_format(), &ImageFormat::Tga);
+    assert_eq!(decoded.get_ref_image().get_ref_pixels(), &[0, 0, 255, 128, 0, 255, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255]);
+
+    // 24 bits raw image stored from the top row.
+    let mut tga = vec![0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 2, 0, 24, 0x20];
+    tga.extend_from_slice(&[1, 2, 3, 4, 5, 6]);
+    let decoded = Image::read(&tga).unwrap().decode().unwrap();
+    assert_eq!(decoded.get_ref_image().get_ref_pixels(), &[3, 2, 1, 255, 6, 5, 4, 255]);
+    assert!(Image::read(&tga[..20]).unwrap().decode().is_err());
+
+    // A huge image with barely any data must fail before trying to allocate its pixels.
+    let mut tga = vec![0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 32, 8];
+    tga.extend_from_slice(&[0xFF, 0, 0, 0, 0]);
+    assert!(Image::read(&tga).unwrap().decode().is_err());
+}
@@ -11,12 +11,29 @@
 /*!
 Module with all the code to interact with Image PackedFiles.
 
-Images... we really just get their that to memory. Nothing more.
+Images are kept in memory as they are. When we need their pixels (to preview them, convert them or check them),
+they can be decoded to RGBA8. Supported formats are:
+- DDS: DXT1, DXT3, DXT5, BC4, BC5, BC7 and uncompressed RGBA/BGRA/BGR, with mipmaps and cube maps.
+- TGA: colour-mapped, truecolour and grayscale, raw or RLE-compressed.
+- PNG: non-interlaced, any colour type.
+
+Decoded images can be converted to PNG, and PNG images can be encoded back to DDS in any of the DDS formats above.
 !*/
 
 use serde_derive::{Serialize, Deserialize};
 
-use rpfm_error::Result;
+use std::fmt::{self, Display};
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_macros::*;
+
+mod bc;
+mod dds;
+mod png;
+mod tga;
+
+#[cfg(test)]
+mod image_test;
 
 /// Extensions used by Image PackedFiles.
 pub const EXTENSIONS: [&str; 5] = [
@@ -39,6 +56,75 @@ pub struct Image {
     data: Vec<u8>,
 }
 
+/// This enum represents the format of the pixels of an image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageFormat {
+
+    /// DDS with BC1 compression. 8 bytes per 4x4 block.
+    Dxt1,
+
+    /// DDS with BC2 compression. 16 bytes per 4x4 block.
+    Dxt3,
+
+    /// DDS with BC3 compression. 16 bytes per 4x4 block.
+    Dxt5,
+
+    /// DDS with BC4 compression (single channel). 8 bytes per 4x4 block.
+    Bc4,
+
+    /// DDS with BC5 compression (two channels, used for normal maps). 16 bytes per 4x4 block.
+    Bc5,
+
+    /// DDS with BC7 compression. 16 bytes per 4x4 block.
+    Bc7,
+
+    /// Uncompressed DDS, 4 bytes per pixel in RGBA order.
+    Rgba8,
+
+    /// Uncompressed DDS, 4 bytes per pixel in BGRA order.
+    Bgra8,
+
+    /// Uncompressed DDS, 3 bytes per pixel in BGR order.
+    Bgr8,
+
+    /// TGA image.
+    Tga,
+
+    /// PNG image.
+    Png,
+}
+
+/// This struct holds an image decoded to RGBA8, with all its faces and mipmaps.
+#[derive(Clone, Debug, PartialEq, GetRef)]
+pub struct DecodedImage {
+
+    /// Format the image was decoded from.
+    format: ImageFormat,
+
+    /// Amount of mipmaps of each face, including the full-size one.
+    mip_count: u32,
+
+    /// If the image is a cube map.
+    is_cube_map: bool,
+
+    /// Faces of the image, each one with its mipmaps, from biggest to smallest. Images that are not cube maps or arrays only have one face.
+    faces: Vec<Vec<RgbaImage>>,
+}
+
+/// This struct holds the pixels of a single image, in RGBA8, row by row.
+#[derive(Clone, Debug, PartialEq, Eq, GetRef)]
+pub struct RgbaImage {
+
+    /// Width of the image, in pixels.
+    width: u32,
+
+    /// Height of the image, in pixels.
+    height: u32,
+
+    /// Pixels of the image, 4 bytes each.
+    pixels: Vec<u8>,
+}
+
 //---------------------------------------------------------------------------//
 //                           Implementation of Image
 //---------------------------------------------------------------------------//
@@ -71,4 +157,186 @@ impl Image {
     pub fn get_data(&self) -> &[u8] {
         &self.data
     }
+
+    /// This function decodes the pixels of the provided `Image`. The format is detected from the data, not from the extension of the file.
+    pub fn decode(&self) -> Result<DecodedImage> {
+        if self.data.starts_with(dds::SIGNATURE.as_bytes()) {
+            dds::read(&self.data)
+        }
+        else if self.data.starts_with(&png::SIGNATURE) {
+            Ok(DecodedImage::new(ImageFormat::Png, png::read(&self.data)?))
+        }
+        else if self.data.starts_with(&[0xFF, 0xD8]) {
+            Err(ErrorKind::ImageDecode("JPEG images are not supported.".to_owned()).into())
+        }
+
+        // TGA files have no signature, so if it's not anything else, try with TGA.
+        else {
+            Ok(DecodedImage::new(ImageFormat::Tga, tga::read(&self.data)?))
+        }
+    }
+
+    /// This function creates a DDS `Image` of the provided format from a PNG image, generating its mipmaps if needed.
+    pub fn from_png(png_data: &[u8], format: ImageFormat, generate_mipmaps: bool) -> Result<Self> {
+        Ok(Self {
+            data: RgbaImage::read_png(png_data)?.to_dds(format, generate_mipmaps)?,
+        })
+    }
+}
+
+/// Implementation of `ImageFormat`.
+impl ImageFormat {
+
+    /// This function returns the `ImageFormat` with the provided name, ignoring case. Only formats that can be used in DDS files are returned.
+    pub fn get_dds_format(name: &str) -> Result<Self> {
+        match &*name.to_lowercase() {
+            "dxt1" | "bc1" => Ok(Self::Dxt1),
+            "dxt3" | "bc2" => Ok(Self::Dxt3),
+            "dxt5" | "bc3" => Ok(Self::Dxt5),
+            "bc4" => Ok(Self::Bc4),
+            "bc5" => Ok(Self::Bc5),
+            "bc7" => Ok(Self::Bc7),
+            "rgba8" => Ok(Self::Rgba8),
+            "bgra8" => Ok(Self::Bgra8),
+            "bgr8" => Ok(Self::Bgr8),
+            _ => Err(ErrorKind::ImageEncode(format!("Unknown DDS format: {}.", name)).into()),
+        }
+    }
+
+    /// This function returns if the format is block-compressed.
+    pub fn is_compressed(self) -> bool {
+        self.get_block_size() > 0
+    }
+
+    /// This function returns the size in bytes of a 4x4 block of the format, or 0 if the format is not block-compressed.
+    fn get_block_size(self) -> usize {
+        match self {
+            Self::Dxt1 | Self::Bc4 => 8,
+            Self::Dxt3 | Self::Dxt5 | Self::Bc5 | Self::Bc7 => 16,
+            _ => 0,
+        }
+    }
+
+    /// This function returns the size in bytes of the data of an image of this format with the provided size.
+    ///
+    /// Sizes too big to be addressed saturate, so they never fit in any data.
+    fn get_data_size(self, width: u32, height: u32) -> usize {
+        let (width, height) = (width as usize, height as usize);
+        match self {
+            Self::Bgr8 => width.saturating_mul(height).saturating_mul(3),
+            Self::Rgba8 | Self::Bgra8 | Self::Tga | Self::Png => width.saturating_mul(height).saturating_mul(4),
+            _ => ((width + 3) / 4).saturating_mul((height + 3) / 4).saturating_mul(self.get_block_size()),
+        }
+    }
+}
+
+/// Implementation of `DecodedImage`.
+impl DecodedImage {
+
+    /// This function creates a `DecodedImage` with a single face without mipmaps.
+    fn new(format: ImageFormat, image: RgbaImage) -> Self {
+        Self {
+            format,
+            mip_count: 1,
+            is_cube_map: false,
+            faces: vec![vec![image]],
+        }
+    }
+
+    /// This function returns the width of the image, in pixels.
+    pub fn get_width(&self) -> u32 {
+        self.faces[0][0].width
+    }
+
+    /// This function returns the height of the image, in pixels.
+    pub fn get_height(&self) -> u32 {
+        self.faces[0][0].height
+    }
+
+    /// This function returns the full-size pixels of the first face of the image.
+    pub fn get_ref_image(&self) -> &RgbaImage {
+        &self.faces[0][0]
+    }
+
+    /// This function encodes the full-size pixels of the first face of the image as PNG.
+    pub fn to_png(&self) -> Vec<u8> {
+        self.faces[0][0].to_png()
+    }
+}
+
+/// Implementation of `RgbaImage`.
+impl RgbaImage {
+
+    /// This function creates a `RgbaImage` from its size and pixels. It fails if the amount of pixels doesn't match the size.
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self> {
+        if width == 0 || height == 0 || pixels.len() != width as usize * height as usize * 4 {
+            return Err(ErrorKind::ImageDecode(format!("Expected {} bytes of pixels for a {}x{} image, but got {}.", width as usize * height as usize * 4, width, height, pixels.len())).into());
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// This function decodes a PNG image.
+    pub fn read_png(data: &[u8]) -> Result<Self> {
+        png::read(data)
+    }
+
+    /// This function encodes the image as PNG.
+    pub fn to_png(&self) -> Vec<u8> {
+        png::write(self)
+    }
+
+    /// This function encodes the image as a DDS image of the provided format, generating its mipmaps if needed.
+    pub fn to_dds(&self, format: ImageFormat, generate_mipmaps: bool) -> Result<Vec<u8>> {
+        dds::write(self, format, generate_mipmaps)
+    }
+
+    /// This function returns the next mipmap of this image, averaging each 2x2 group of pixels.
+    fn get_half_size(&self) -> Self {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                for channel in 0..4 {
+                    let mut sum = 0;
+                    for (offset_x, offset_y) in &[(0, 0), (1, 0), (0, 1), (1, 1)] {
+                        let source_x = (x * 2 + offset_x).min(self.width - 1) as usize;
+                        let source_y = (y * 2 + offset_y).min(self.height - 1) as usize;
+                        sum += u32::from(self.pixels[(source_y * self.width as usize + source_x) * 4 + channel]);
+                    }
+                    pixels.push(((sum + 2) / 4) as u8);
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Display implementation of `ImageFormat`.
+impl Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImageFormat::Dxt1 => write!(f, "DXT1"),
+            ImageFormat::Dxt3 => write!(f, "DXT3"),
+            ImageFormat::Dxt5 => write!(f, "DXT5"),
+            ImageFormat::Bc4 => write!(f, "BC4"),
+            ImageFormat::Bc5 => write!(f, "BC5"),
+            ImageFormat::Bc7 => write!(f, "BC7"),
+            ImageFormat::Rgba8 => write!(f, "RGBA8"),
+            ImageFormat::Bgra8 => write!(f, "BGRA8"),
+            ImageFormat::Bgr8 => write!(f, "BGR8"),
+            ImageFormat::Tga => write!(f, "TGA"),
+            ImageFormat::Png => write!(f, "PNG"),
+        }
+    }
 }
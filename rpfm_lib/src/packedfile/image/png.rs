@@ -0,0 +1,264 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to read and write PNG images.
+
+The structure of a PNG file is a signature followed by chunks. Each chunk has its length, its type, its data and a CRC.
+We only care about the header (`IHDR`), the palette (`PLTE`), the transparency (`tRNS`) and the image data (`IDAT`).
+
+Images are always written as non-interlaced RGBA8. Interlaced images are not supported when reading.
+!*/
+
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+
+use rpfm_error::{ErrorKind, Result};
+
+use super::RgbaImage;
+
+/// Signature of PNG files.
+pub const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// Compression level used when writing PNG files.
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// Colour type for grayscale images.
+const COLOUR_TYPE_GRAYSCALE: u8 = 0;
+
+/// Colour type for RGB images.
+const COLOUR_TYPE_RGB: u8 = 2;
+
+/// Colour type for palette images.
+const COLOUR_TYPE_PALETTE: u8 = 3;
+
+/// Colour type for grayscale images with alpha.
+const COLOUR_TYPE_GRAYSCALE_ALPHA: u8 = 4;
+
+/// Colour type for RGBA images.
+const COLOUR_TYPE_RGBA: u8 = 6;
+
+//---------------------------------------------------------------------------//
+//                              Functions
+//---------------------------------------------------------------------------//
+
+/// This function decodes a PNG image. All the non-interlaced colour types and bit depths are supported.
+pub fn read(data: &[u8]) -> Result<RgbaImage> {
+    if !data.starts_with(&SIGNATURE) {
+        return Err(ErrorKind::ImageDecode("This is not a PNG file.".to_owned()).into());
+    }
+
+    let mut header = None;
+    let mut palette: Vec<[u8; 4]> = vec![];
+    let mut transparency = vec![];
+    let mut compressed_data = vec![];
+    let mut index = SIGNATURE.len();
+    while index + 8 <= data.len() {
+        let length = read_u32_be(data, index)? as usize;
+        let chunk_type = &data[index + 4..index + 8];
+        let chunk_data = match data.get(index + 8..index + 8 + length) {
+            Some(chunk_data) => chunk_data,
+            None => return Err(ErrorKind::ImageDecode("The PNG file ends in the middle of a chunk.".to_owned()).into()),
+        };
+
+        match chunk_type {
+            b"IHDR" if length >= 13 => header = Some((read_u32_be(chunk_data, 0)?, read_u32_be(chunk_data, 4)?, chunk_data[8], chunk_data[9], chunk_data[12])),
+            b"PLTE" => palette = chunk_data.chunks_exact(3).map(|colour| [colour[0], colour[1], colour[2], 255]).collect(),
+            b"tRNS" => transparency = chunk_data.to_vec(),
+            b"IDAT" => compressed_data.extend_from_slice(chunk_data),
+            b"IEND" => break,
+            _ => {}
+        }
+
+        // Skip the data and the CRC.
+        index += 12 + length;
+    }
+
+    let (width, height, bit_depth, colour_type, interlace) = match header {
+        Some(header) => header,
+        None => return Err(ErrorKind::ImageDecode("The PNG file has no header.".to_owned()).into()),
+    };
+
+    if interlace != 0 {
+        return Err(ErrorKind::ImageDecode("Interlaced PNG files are not supported.".to_owned()).into());
+    }
+
+    let channels = match colour_type {
+        COLOUR_TYPE_GRAYSCALE | COLOUR_TYPE_PALETTE => 1,
+        COLOUR_TYPE_RGB => 3,
+        COLOUR_TYPE_GRAYSCALE_ALPHA => 2,
+        COLOUR_TYPE_RGBA => 4,
+        _ => return Err(ErrorKind::ImageDecode(format!("Unsupported PNG colour type: {}.", colour_type)).into()),
+    };
+
+    if ![1, 2, 4, 8, 16].contains(&bit_depth) {
+        return Err(ErrorKind::ImageDecode(format!("Unsupported PNG bit depth: {}.", bit_depth)).into());
+    }
+
+    // Palette transparency goes in order. For other types, it's a single colour that must be transparent.
+    for (colour, alpha) in palette.iter_mut().zip(transparency.iter()) {
+        colour[3] = *alpha;
+    }
+
+    let (width, height) = (width as usize, height as usize);
+    let bits_per_pixel = channels * bit_depth as usize;
+    let row_length = width.checked_mul(bits_per_pixel).map(|bits| (bits + 7) / 8);
+    let rows_length = row_length.and_then(|row_length| (row_length + 1).checked_mul(height));
+    let (row_length, rows_length) = match (row_length, rows_length) {
+        (Some(row_length), Some(rows_length)) => (row_length, rows_length),
+        _ => return Err(ErrorKind::ImageDecode("The image is too big.".to_owned()).into()),
+    };
+
+    let filter_length = ((bits_per_pixel + 7) / 8).max(1);
+    let mut rows = decompress_to_vec_zlib(&compressed_data).map_err(|error| ErrorKind::ImageDecode(format!("Error while decompressing the PNG data: {:?}.", error)))?;
+    if rows.len() < rows_length {
+        return Err(ErrorKind::ImageDecode("The PNG data is shorter than expected.".to_owned()).into());
+    }
+
+    unfilter(&mut rows, row_length, height, filter_length)?;
+
+    let max_value = (1u32 << bit_depth) - 1;
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        let row = &rows[y * (row_length + 1) + 1..(y + 1) * (row_length + 1)];
+
+        // Samples are scaled to 8 bits, except palette indexes.
+        let get_sample = |sample: usize| -> u32 {
+            match bit_depth {
+                16 => u32::from(u16::from_be_bytes([row[sample * 2], row[sample * 2 + 1]])),
+                8 => u32::from(row[sample]),
+                _ => {
+                    let bit = sample * bit_depth as usize;
+                    u32::from(row[bit / 8] >> (8 - bit_depth as usize - bit % 8)) & max_value
+                }
+            }
+        };
+        let scale = |value: u32| (value * 255 / max_value) as u8;
+
+        for x in 0..width {
+            let first_sample = x * channels;
+            let colour = match colour_type {
+                COLOUR_TYPE_PALETTE => match palette.get(get_sample(first_sample) as usize) {
+                    Some(colour) => *colour,
+                    None => return Err(ErrorKind::ImageDecode("The PNG file uses colours that are not in its palette.".to_owned()).into()),
+                },
+                COLOUR_TYPE_GRAYSCALE => {
+                    let value = get_sample(first_sample);
+                    let is_transparent = transparency.len() >= 2 && u32::from(u16::from_be_bytes([transparency[0], transparency[1]])) == value;
+                    [scale(value), scale(value), scale(value), if is_transparent { 0 } else { 255 }]
+                }
+                COLOUR_TYPE_GRAYSCALE_ALPHA => {
+                    let value = scale(get_sample(first_sample));
+                    [value, value, value, scale(get_sample(first_sample + 1))]
+                }
+                COLOUR_TYPE_RGB => {
+                    let values = [get_sample(first_sample), get_sample(first_sample + 1), get_sample(first_sample + 2)];
+                    let is_transparent = transparency.len() >= 6 && (0..3).all(|channel| u32::from(u16::from_be_bytes([transparency[channel * 2], transparency[channel * 2 + 1]])) == values[channel]);
+                    [scale(values[0]), scale(values[1]), scale(values[2]), if is_transparent { 0 } else { 255 }]
+                }
+                _ => [scale(get_sample(first_sample)), scale(get_sample(first_sample + 1)), scale(get_sample(first_sample + 2)), scale(get_sample(first_sample + 3))],
+            };
+            pixels.extend_from_slice(&colour);
+        }
+    }
+
+    RgbaImage::new(width as u32, height as u32, pixels)
+}
+
+/// This function encodes RGBA8 pixels as a PNG image.
+pub fn write(image: &RgbaImage) -> Vec<u8> {
+    let row_length = image.width as usize * 4;
+    let mut rows = Vec::with_capacity((row_length + 1) * image.height as usize);
+    for row in image.pixels.chunks_exact(row_length) {
+        rows.push(0);
+        rows.extend_from_slice(row);
+    }
+
+    let mut header = vec![];
+    header.extend_from_slice(&image.width.to_be_bytes());
+    header.extend_from_slice(&image.height.to_be_bytes());
+    header.extend_from_slice(&[8, COLOUR_TYPE_RGBA, 0, 0, 0]);
+
+    let mut data = SIGNATURE.to_vec();
+    write_chunk(&mut data, b"IHDR", &header);
+    write_chunk(&mut data, b"IDAT", &compress_to_vec_zlib(&rows, COMPRESSION_LEVEL));
+    write_chunk(&mut data, b"IEND", &[]);
+    data
+}
+
+//---------------------------------------------------------------------------//
+//                              Helper functions
+//---------------------------------------------------------------------------//
+
+/// This function reverts the filters of each row. Each row starts with a byte saying what filter was used on it.
+fn unfilter(rows: &mut [u8], row_length: usize, height: usize, filter_length: usize) -> Result<()> {
+    for y in 0..height {
+        let start = y * (row_length + 1);
+        let filter = rows[start];
+        for x in 0..row_length {
+            let index = start + 1 + x;
+            let left = if x >= filter_length { rows[index - filter_length] } else { 0 };
+            let up = if y > 0 { rows[index - row_length - 1] } else { 0 };
+            let up_left = if y > 0 && x >= filter_length { rows[index - row_length - 1 - filter_length] } else { 0 };
+            rows[index] = rows[index].wrapping_add(match filter {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((u16::from(left) + u16::from(up)) / 2) as u8,
+                4 => paeth(left, up, up_left),
+                _ => return Err(ErrorKind::ImageDecode(format!("Unknown PNG filter: {}.", filter)).into()),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// This function returns the Paeth predictor for the provided neighbours.
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = i16::from(left) + i16::from(up) - i16::from(up_left);
+    let distance_left = (estimate - i16::from(left)).abs();
+    let distance_up = (estimate - i16::from(up)).abs();
+    let distance_up_left = (estimate - i16::from(up_left)).abs();
+    if distance_left <= distance_up && distance_left <= distance_up_left { left }
+    else if distance_up <= distance_up_left { up }
+    else { up_left }
+}
+
+/// This function writes a chunk, with its length and CRC.
+fn write_chunk(data: &mut Vec<u8>, chunk_type: &[u8; 4], chunk_data: &[u8]) {
+    data.extend_from_slice(&(chunk_data.len() as u32).to_be_bytes());
+    let crc_start = data.len();
+    data.extend_from_slice(chunk_type);
+    data.extend_from_slice(chunk_data);
+    let crc = crc32(&data[crc_start..]);
+    data.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// This function reads a big-endian u32. PNG is the only big-endian format we deal with, so this is not in the decoder.
+fn read_u32_be(data: &[u8], index: usize) -> Result<u32> {
+    match data.get(index..index + 4) {
+        Some(bytes) => Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        None => Err(ErrorKind::ImageDecode("The PNG file ends unexpectedly.".to_owned()).into()),
+    }
+}
+
+/// This function calculates the CRC32 of the provided data, as used by PNG chunks.
+fn crc32(data: &[u8]) -> u32 {
+    let mut table = [0u32; 256];
+    for (index, value) in table.iter_mut().enumerate() {
+        let mut crc = index as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+        }
+        *value = crc;
+    }
+
+    !data.iter().fold(!0u32, |crc, byte| table[((crc ^ u32::from(*byte)) & 0xFF) as usize] ^ (crc >> 8))
+}
@@ -0,0 +1,150 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to read TGA images.
+
+The structure of a TGA file is:
+- A 18 bytes header, with the type of the image, its colour map, its size and its pixel depth.
+- An optional id, and an optional colour map (palette).
+- The pixels, either raw or RLE-compressed, usually from the bottom row to the top one.
+
+!*/
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::common::decoder::Decoder;
+use super::RgbaImage;
+
+/// Length of the header of a TGA file.
+const HEADER_LENGTH: usize = 18;
+
+/// Descriptor flag for images stored from right to left.
+const DESCRIPTOR_RIGHT_TO_LEFT: u8 = 0x10;
+
+/// Descriptor flag for images stored from top to bottom.
+const DESCRIPTOR_TOP_TO_BOTTOM: u8 = 0x20;
+
+//---------------------------------------------------------------------------//
+//                              Functions
+//---------------------------------------------------------------------------//
+
+/// This function decodes a TGA image. Colour-mapped, truecolour and grayscale images, raw or RLE-compressed, are supported.
+pub fn read(data: &[u8]) -> Result<RgbaImage> {
+    if data.len() < HEADER_LENGTH {
+        return Err(ErrorKind::ImageDecode("This is not a TGA file.".to_owned()).into());
+    }
+
+    let id_length = data[0] as usize;
+    let colour_map_type = data[1];
+    let image_type = data[2];
+    let colour_map_start = data.decode_integer_u16(3)? as usize;
+    let colour_map_length = data.decode_integer_u16(5)? as usize;
+    let colour_map_depth = data[7];
+    let width = data.decode_integer_u16(12)? as usize;
+    let height = data.decode_integer_u16(14)? as usize;
+    let pixel_depth = data[16];
+    let descriptor = data[17];
+    let alpha_bits = descriptor & 0xF;
+
+    if width == 0 || height == 0 {
+        return Err(ErrorKind::ImageDecode("The image has no pixels.".to_owned()).into());
+    }
+
+    let is_colour_mapped = match image_type & !8 {
+        1 => true,
+        2 | 3 => false,
+        _ => return Err(ErrorKind::ImageDecode(format!("Unsupported TGA type: {}.", image_type)).into()),
+    };
+    let is_grayscale = image_type & !8 == 3;
+    let is_rle = image_type & 8 != 0;
+
+    // Get the colour map, if there is one. Its entries are stored like any other pixel.
+    let mut index = HEADER_LENGTH + id_length;
+    let mut colour_map = vec![];
+    if colour_map_type == 1 {
+        let entry_length = (colour_map_depth as usize + 7) / 8;
+        for entry in 0..colour_map_length {
+            colour_map.push(decode_pixel(data.get_bytes_checked(index + entry * entry_length, entry_length)?, colour_map_depth, false, alpha_bits)?);
+        }
+        index += colour_map_length * entry_length;
+    }
+
+    let pixel_length = (pixel_depth as usize + 7) / 8;
+    if pixel_length == 0 {
+        return Err(ErrorKind::ImageDecode(format!("Unsupported pixel depth: {}.", pixel_depth)).into());
+    }
+
+    // Make sure the file has enough data for all the pixels before allocating them. RLE packets can repeat a pixel up to 128 times.
+    let raw_length = width.checked_mul(height).and_then(|pixels| pixels.checked_mul(pixel_length))
+        .ok_or_else(|| ErrorKind::ImageDecode("The image is too big.".to_owned()))?;
+    let data_length = data.len().saturating_sub(index);
+    if (is_rle && data_length.saturating_mul(128) < raw_length) || (!is_rle && data_length < raw_length) {
+        return Err(ErrorKind::ImageDecode("The TGA data is shorter than expected.".to_owned()).into());
+    }
+
+    // Get all the pixels, in the order they're stored.
+    let mut raw_pixels = Vec::with_capacity(raw_length);
+    if is_rle {
+        while raw_pixels.len() < raw_length {
+            let packet = data.decode_packedfile_integer_u8(index, &mut index)?;
+            let count = (packet & 0x7F) as usize + 1;
+            if packet & 0x80 != 0 {
+                let pixel = data.get_bytes_checked(index, pixel_length)?;
+                (0..count).for_each(|_| raw_pixels.extend_from_slice(pixel));
+                index += pixel_length;
+            } else {
+                raw_pixels.extend_from_slice(data.get_bytes_checked(index, count * pixel_length)?);
+                index += count * pixel_length;
+            }
+        }
+        raw_pixels.truncate(raw_length);
+    } else {
+        raw_pixels.extend_from_slice(data.get_bytes_checked(index, raw_length)?);
+    }
+
+    let mut pixels = vec![0; width * height * 4];
+    for (pixel_index, raw_pixel) in raw_pixels.chunks_exact(pixel_length).enumerate() {
+        let colour = if is_colour_mapped {
+            let colour_index = if pixel_length == 1 { raw_pixel[0] as usize } else { u16::from_le_bytes([raw_pixel[0], raw_pixel[1]]) as usize };
+            match colour_map.get(colour_index.wrapping_sub(colour_map_start)) {
+                Some(colour) => *colour,
+                None => return Err(ErrorKind::ImageDecode(format!("The colour {} is not in the colour map.", colour_index)).into()),
+            }
+        } else { decode_pixel(raw_pixel, pixel_depth, is_grayscale, alpha_bits)? };
+
+        // Most TGA files are stored from the bottom row to the top one, so flip them as needed.
+        let mut x = pixel_index % width;
+        let mut y = pixel_index / width;
+        if descriptor & DESCRIPTOR_RIGHT_TO_LEFT != 0 { x = width - 1 - x; }
+        if descriptor & DESCRIPTOR_TOP_TO_BOTTOM == 0 { y = height - 1 - y; }
+        pixels[(y * width + x) * 4..(y * width + x) * 4 + 4].copy_from_slice(&colour);
+    }
+
+    RgbaImage::new(width as u32, height as u32, pixels)
+}
+
+/// This function decodes a single TGA pixel of the provided depth to RGBA8.
+///
+/// The alpha bit of 16 bits pixels is only used if the image says it has alpha bits.
+fn decode_pixel(data: &[u8], depth: u8, is_grayscale: bool, alpha_bits: u8) -> Result<[u8; 4]> {
+    match depth {
+        8 if is_grayscale => Ok([data[0], data[0], data[0], 255]),
+        16 if is_grayscale => Ok([data[0], data[0], data[0], data[1]]),
+        15 | 16 => {
+            let value = u16::from_le_bytes([data[0], data[1]]);
+            let expand = |value: u16| ((value << 3) | (value >> 2)) as u8;
+            Ok([expand((value >> 10) & 0x1F), expand((value >> 5) & 0x1F), expand(value & 0x1F), if depth == 16 && alpha_bits > 0 && value & 0x8000 == 0 { 0 } else { 255 }])
+        }
+        24 => Ok([data[2], data[1], data[0], 255]),
+        32 => Ok([data[2], data[1], data[0], data[3]]),
+        _ => Err(ErrorKind::ImageDecode(format!("Unsupported pixel depth: {}.", depth)).into()),
+    }
+}
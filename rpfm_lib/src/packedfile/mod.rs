@@ -25,18 +25,25 @@ use std::ops::Deref;
 use rpfm_error::{Error, ErrorKind, Result};
 
 use crate::dependencies::Dependencies;
+use crate::packedfile::anim::Anim;
 use crate::packedfile::animpack::AnimPack;
 use crate::packedfile::ca_vp8::CaVp8;
+use crate::packedfile::esf::ESF;
+use crate::packedfile::group_formations::GroupFormations;
 use crate::packedfile::image::Image;
-use crate::packedfile::table::{anim_fragment::AnimFragment, animtable::AnimTable, db::DB, loc::Loc, matched_combat::MatchedCombat};
+use crate::packedfile::table::{anim_fragment::AnimFragment, animtable::AnimTable, ceo::CEO, db::DB, loc::Loc, matched_combat::MatchedCombat};
 use crate::packedfile::text::{Text, TextType};
 use crate::packedfile::rigidmodel::RigidModel;
 use crate::packfile::packedfile::{PackedFile, RawPackedFile};
 use crate::schema::Schema;
 use crate::SCHEMA;
 
+pub mod anim;
 pub mod animpack;
+pub mod bmd;
 pub mod ca_vp8;
+pub mod esf;
+pub mod group_formations;
 pub mod image;
 pub mod rigidmodel;
 pub mod table;
@@ -51,19 +58,19 @@ pub mod text;
 /// Keep in mind that, despite we having logic to recognize them, we can't decode many of them yet.
 #[derive(PartialEq, Clone, Debug)]
 pub enum DecodedPackedFile {
-    Anim,
+    Anim(Anim),
     AnimFragment(AnimFragment),
     AnimPack(AnimPack),
     AnimTable(AnimTable),
     CaVp8(CaVp8),
-    CEO,
+    CEO(CEO),
     DB(DB),
     Image(Image),
-    GroupFormations,
+    GroupFormations(GroupFormations),
     Loc(Loc),
     MatchedCombat(MatchedCombat),
     RigidModel(RigidModel),
-    StarPos,
+    StarPos(ESF),
     Text(Text),
     Unknown,
 }
@@ -107,6 +114,12 @@ impl DecodedPackedFile {
     pub fn decode(raw_packed_file: &mut RawPackedFile) -> Result<Self> {
         match PackedFileType::get_packed_file_type(raw_packed_file.get_path()) {
 
+            PackedFileType::Anim => {
+                let data = raw_packed_file.get_data_and_keep_it()?;
+                let packed_file = Anim::read(&data)?;
+                Ok(DecodedPackedFile::Anim(packed_file))
+            }
+
             PackedFileType::AnimFragment => {
                 let schema = SCHEMA.read().unwrap();
                 match schema.deref() {
@@ -143,6 +156,18 @@ impl DecodedPackedFile {
                 Ok(DecodedPackedFile::CaVp8(packed_file))
             }
 
+            PackedFileType::CEO => {
+                let schema = SCHEMA.read().unwrap();
+                match schema.deref() {
+                    Some(schema) => {
+                        let data = raw_packed_file.get_data_and_keep_it()?;
+                        let packed_file = CEO::read(&data, schema, false)?;
+                        Ok(DecodedPackedFile::CEO(packed_file))
+                    }
+                    None => Err(ErrorKind::SchemaNotFound.into()),
+                }
+            }
+
             PackedFileType::DB => {
                 let schema = SCHEMA.read().unwrap();
                 match schema.deref() {
@@ -162,6 +187,12 @@ impl DecodedPackedFile {
                 Ok(DecodedPackedFile::Image(packed_file))
             }
 
+            PackedFileType::GroupFormations => {
+                let data = raw_packed_file.get_data_and_keep_it()?;
+                let packed_file = GroupFormations::read(&data)?;
+                Ok(DecodedPackedFile::GroupFormations(packed_file))
+            }
+
             PackedFileType::Loc => {
                 let schema = SCHEMA.read().unwrap();
                 match schema.deref() {
@@ -186,6 +217,18 @@ impl DecodedPackedFile {
                 }
             }
 
+            PackedFileType::RigidModel => {
+                let data = raw_packed_file.get_data_and_keep_it()?;
+                let packed_file = RigidModel::read(&data)?;
+                Ok(DecodedPackedFile::RigidModel(packed_file))
+            }
+
+            PackedFileType::StarPos => {
+                let data = raw_packed_file.get_data_and_keep_it()?;
+                let packed_file = ESF::read(&data)?;
+                Ok(DecodedPackedFile::StarPos(packed_file))
+            }
+
             PackedFileType::Text(_) => {
                 let data = raw_packed_file.get_data_and_keep_it()?;
                 let mut packed_file = Text::read(&data)?;
@@ -203,6 +246,8 @@ impl DecodedPackedFile {
     pub fn decode_no_locks(raw_packed_file: &mut RawPackedFile, schema: &Schema) -> Result<Self> {
         match PackedFileType::get_packed_file_type(raw_packed_file.get_path()) {
 
+            PackedFileType::Anim => Self::decode(raw_packed_file),
+
             PackedFileType::AnimFragment => {
                 let data = raw_packed_file.get_data_and_keep_it()?;
                 let packed_file = AnimFragment::read(&data, &schema, false)?;
@@ -219,6 +264,12 @@ impl DecodedPackedFile {
 
             PackedFileType::CaVp8 => Self::decode(raw_packed_file),
 
+            PackedFileType::CEO => {
+                let data = raw_packed_file.get_data_and_keep_it()?;
+                let packed_file = CEO::read(&data, schema, false)?;
+                Ok(DecodedPackedFile::CEO(packed_file))
+            }
+
             PackedFileType::DB => {
                 let data = raw_packed_file.get_data_and_keep_it()?;
                 let name = raw_packed_file.get_path().get(1).ok_or_else(|| Error::from(ErrorKind::DBTableIsNotADBTable))?;
@@ -227,6 +278,7 @@ impl DecodedPackedFile {
             }
 
             PackedFileType::Image => Self::decode(raw_packed_file),
+            PackedFileType::GroupFormations => Self::decode(raw_packed_file),
 
             PackedFileType::Loc => {
                 let data = raw_packed_file.get_data_and_keep_it()?;
@@ -240,6 +292,8 @@ impl DecodedPackedFile {
                 Ok(DecodedPackedFile::MatchedCombat(packed_file))
             }
 
+            PackedFileType::RigidModel => Self::decode(raw_packed_file),
+            PackedFileType::StarPos => Self::decode(raw_packed_file),
             PackedFileType::Text(_) => Self::decode(raw_packed_file),
             _=> Ok(DecodedPackedFile::Unknown)
         }
@@ -250,13 +304,18 @@ impl DecodedPackedFile {
     /// Keep in mind this should only work for PackedFiles with saving support.
     pub fn encode(&self) -> Option<Result<Vec<u8>>> {
         match self {
+            DecodedPackedFile::Anim(data) => Some(data.save()),
             DecodedPackedFile::AnimFragment(data) => Some(data.save()),
             DecodedPackedFile::AnimPack(data) => Some(Ok(data.save())),
             DecodedPackedFile::AnimTable(data) => Some(data.save()),
             DecodedPackedFile::CaVp8(data) => Some(Ok(data.save())),
+            DecodedPackedFile::CEO(data) => Some(data.save()),
             DecodedPackedFile::DB(data) => Some(data.save()),
+            DecodedPackedFile::GroupFormations(data) => Some(data.save()),
             DecodedPackedFile::Loc(data) => Some(data.save()),
             DecodedPackedFile::MatchedCombat(data) => Some(data.save()),
+            DecodedPackedFile::RigidModel(data) => Some(data.save()),
+            DecodedPackedFile::StarPos(data) => Some(data.save()),
             DecodedPackedFile::Text(data) => Some(data.save()),
             _=> None,
         }
@@ -333,8 +392,12 @@ impl PackedFileType {
             else if packedfile_name.ends_with(rigidmodel::EXTENSION) { Self::RigidModel }
             else if packedfile_name.ends_with(ca_vp8::EXTENSION) { Self::CaVp8 }
             else if packedfile_name.ends_with(table::anim_fragment::EXTENSION) { Self::AnimFragment }
+            else if packedfile_name.ends_with(anim::EXTENSION) { Self::Anim }
+            else if packedfile_name.ends_with(esf::EXTENSION) { Self::StarPos }
+            else if packedfile_name.ends_with(table::ceo::EXTENSION) { Self::CEO }
             else if path == table::animtable::PATH { Self::AnimTable }
             else if path == table::matched_combat::PATH { Self::MatchedCombat }
+            else if path == group_formations::PATH { Self::GroupFormations }
             else if let Some((_, text_type)) = text::EXTENSIONS.iter().find(|(x, _)| packedfile_name.ends_with(x)) {
                 Self::Text(*text_type)
             }
@@ -368,6 +431,15 @@ impl PackedFileType {
                     else if packedfile_name.ends_with(table::anim_fragment::EXTENSION) {
                         return Self::AnimFragment;
                     }
+                    else if packedfile_name.ends_with(anim::EXTENSION) {
+                        return Self::Anim;
+                    }
+                    else if packedfile_name.ends_with(esf::EXTENSION) {
+                        return Self::StarPos;
+                    }
+                    else if packedfile_name.ends_with(table::ceo::EXTENSION) {
+                        return Self::CEO;
+                    }
                     else if packedfile_name.ends_with(animpack::EXTENSION) {
                         return Self::AnimPack
                     }
@@ -377,6 +449,9 @@ impl PackedFileType {
                     else if packed_file.get_path() == table::matched_combat::PATH {
                         return Self::MatchedCombat
                     }
+                    else if packed_file.get_path() == group_formations::PATH {
+                        return Self::GroupFormations
+                    }
                     else if image::EXTENSIONS.iter().any(|x| packedfile_name.ends_with(x)) {
                         return Self::Image
                     }
@@ -389,6 +464,7 @@ impl PackedFileType {
                     if Loc::is_loc(&data) { Self::Loc }
                     else if DB::read_header(&data).is_ok() { Self::DB }
                     else if CaVp8::is_video(&data) { Self::CaVp8 }
+                    else if ESF::is_esf(&data) { Self::StarPos }
                     else { Self::Unknown }
                 }
 
@@ -455,19 +531,19 @@ impl PackedFileType {
 impl From<&DecodedPackedFile> for PackedFileType {
     fn from(packed_file: &DecodedPackedFile) -> Self {
         match packed_file {
-            DecodedPackedFile::Anim => PackedFileType::Anim,
+            DecodedPackedFile::Anim(_) => PackedFileType::Anim,
             DecodedPackedFile::AnimFragment(_) => PackedFileType::AnimFragment,
             DecodedPackedFile::AnimPack(_) => PackedFileType::AnimPack,
             DecodedPackedFile::AnimTable(_) => PackedFileType::AnimTable,
             DecodedPackedFile::CaVp8(_) => PackedFileType::CaVp8,
-            DecodedPackedFile::CEO => PackedFileType::CEO,
+            DecodedPackedFile::CEO(_) => PackedFileType::CEO,
             DecodedPackedFile::DB(_) => PackedFileType::DB,
             DecodedPackedFile::Image(_) => PackedFileType::Image,
-            DecodedPackedFile::GroupFormations => PackedFileType::GroupFormations,
+            DecodedPackedFile::GroupFormations(_) => PackedFileType::GroupFormations,
             DecodedPackedFile::Loc(_) => PackedFileType::Loc,
             DecodedPackedFile::MatchedCombat(_) => PackedFileType::MatchedCombat,
             DecodedPackedFile::RigidModel(_) => PackedFileType::RigidModel,
-            DecodedPackedFile::StarPos => PackedFileType::StarPos,
+            DecodedPackedFile::StarPos(_) => PackedFileType::StarPos,
             DecodedPackedFile::Text(text) => PackedFileType::Text(text.get_text_type()),
             DecodedPackedFile::Unknown => PackedFileType::Unknown,
         }
@@ -0,0 +1,593 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to export RigidModels to glTF 2.0, and to import edited geometry back from glTF files.
+
+Each mesh with decoded vertices becomes a glTF mesh called `lod_X_mesh_Y`, under a node per LOD. Its textures are exported
+as images, with the texture paths as URIs. Data glTF can't hold (like bitangents or the fourth component of positions,
+normals and tangents) goes in `_BITANGENT` and `_RPFM_W` attributes, so a model exported and imported back doesn't change.
+
+When importing, only the vertices, indices and bounding box of the meshes found in the glTF file are replaced.
+The rest of the RigidModel, including the materials, is kept as it is. Meshes are found by their `lod` and `mesh` extras,
+or by their name if the extras are missing.
+!*/
+
+use serde_json::{json, Map, Value};
+
+use std::fs::{read, write};
+use std::path::Path;
+
+use rpfm_error::{ErrorKind, Result};
+
+use super::*;
+
+/// Magic number of binary glTF files.
+const GLB_MAGIC: &str = "glTF";
+
+/// Type of the JSON chunk of binary glTF files.
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+
+/// Type of the binary chunk of binary glTF files.
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+
+/// Prefix of buffers embedded in glTF files.
+const DATA_URI_PREFIX: &str = "data:application/octet-stream;base64,";
+
+/// glTF component type for unsigned bytes.
+const COMPONENT_TYPE_U8: u64 = 5121;
+
+/// glTF component type for unsigned shorts.
+const COMPONENT_TYPE_U16: u64 = 5123;
+
+/// glTF component type for unsigned integers.
+const COMPONENT_TYPE_U32: u64 = 5125;
+
+/// glTF component type for floats.
+const COMPONENT_TYPE_F32: u64 = 5126;
+
+/// glTF primitive mode for triangle lists.
+const MODE_TRIANGLES: u64 = 4;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct is used to build the binary buffer of a glTF file, along with its views and accessors.
+#[derive(Default)]
+struct GltfBuffer {
+    data: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+}
+
+//---------------------------------------------------------------------------//
+//                              Implementations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `RigidModel` for glTF support.
+impl RigidModel {
+
+    /// This function exports this RigidModel to a glTF file in the provided path.
+    ///
+    /// If the path ends in `.glb`, it's exported as a binary glTF file. Otherwise, the buffer is embedded in the JSON file.
+    pub fn export_gltf(&self, path: &Path) -> Result<()> {
+        let (mut gltf, buffer) = self.to_gltf();
+        if path.extension().and_then(|extension| extension.to_str()).map(|extension| extension.to_lowercase()) == Some("glb".to_owned()) {
+            gltf["buffers"] = json!([{ "byteLength": buffer.len() }]);
+            let mut json_chunk = serde_json::to_vec(&gltf)?;
+            while json_chunk.len() % 4 != 0 { json_chunk.push(b' '); }
+
+            let mut bin_chunk = buffer;
+            while bin_chunk.len() % 4 != 0 { bin_chunk.push(0); }
+
+            let mut data = vec![];
+            data.encode_string_u8(GLB_MAGIC);
+            data.encode_integer_u32(2);
+            data.encode_integer_u32((12 + 8 + json_chunk.len() + 8 + bin_chunk.len()) as u32);
+            data.encode_integer_u32(json_chunk.len() as u32);
+            data.encode_integer_u32(GLB_CHUNK_JSON);
+            data.append(&mut json_chunk);
+            data.encode_integer_u32(bin_chunk.len() as u32);
+            data.encode_integer_u32(GLB_CHUNK_BIN);
+            data.append(&mut bin_chunk);
+            write(path, data)?;
+        }
+        else {
+            gltf["buffers"] = json!([{ "byteLength": buffer.len(), "uri": format!("{}{}", DATA_URI_PREFIX, base64::encode(&buffer)) }]);
+            write(path, serde_json::to_string_pretty(&gltf)?)?;
+        }
+
+        Ok(())
+    }
+
+    /// This function replaces the geometry of the meshes of this RigidModel with the geometry of the meshes in the glTF file in the provided path.
+    ///
+    /// Both, `.gltf` files (with embedded or external buffers) and `.glb` files are supported. It returns how many meshes have been replaced.
+    pub fn import_gltf(&mut self, path: &Path) -> Result<usize> {
+        let data = read(path)?;
+        let (gltf, glb_buffer): (Value, Option<Vec<u8>>) = if data.len() >= 12 && data.decode_string_u8(0, 4)? == GLB_MAGIC {
+            let mut json = None;
+            let mut bin = None;
+            let mut index = 12;
+            while index + 8 <= data.len() {
+                let chunk_length = data.decode_integer_u32(index)? as usize;
+                let chunk_type = data.decode_integer_u32(index + 4)?;
+                let chunk = data.get_bytes_checked(index + 8, chunk_length)?;
+                match chunk_type {
+                    GLB_CHUNK_JSON => json = Some(serde_json::from_slice(chunk)?),
+                    GLB_CHUNK_BIN => bin = Some(chunk.to_vec()),
+                    _ => {}
+                }
+                index += 8 + chunk_length;
+            }
+
+            match json {
+                Some(json) => (json, bin),
+                None => return Err(ErrorKind::RigidModelImport("The GLB file has no JSON chunk.".to_owned()).into()),
+            }
+        } else { (serde_json::from_slice(&data)?, None) };
+
+        // Load all the buffers, either from the GLB file, embedded in the JSON, or from files next to the glTF file.
+        let mut buffers = vec![];
+        for buffer in gltf["buffers"].as_array().unwrap_or(&vec![]) {
+            match buffer["uri"].as_str() {
+                Some(uri) if uri.starts_with("data:") => match uri.find(";base64,") {
+                    Some(start) => buffers.push(base64::decode(&uri[start + 8..]).map_err(|error| ErrorKind::RigidModelImport(error.to_string()))?),
+                    None => return Err(ErrorKind::RigidModelImport("Only base64 data URIs are supported.".to_owned()).into()),
+                }
+                Some(uri) => buffers.push(read(path.parent().unwrap_or_else(|| Path::new("")).join(uri))?),
+                None => match &glb_buffer {
+                    Some(glb_buffer) => buffers.push(glb_buffer.to_vec()),
+                    None => return Err(ErrorKind::RigidModelImport("One of the buffers has no data.".to_owned()).into()),
+                }
+            }
+        }
+
+        self.apply_gltf(&gltf, &buffers)
+    }
+
+    /// This function builds the glTF JSON of this RigidModel, returning it along with its binary buffer.
+    ///
+    /// The JSON returned has no buffers, as where the binary buffer goes depends on the type of file we save.
+    fn to_gltf(&self) -> (Value, Vec<u8>) {
+        let mut buffer = GltfBuffer::default();
+        let mut nodes = vec![];
+        let mut scene_nodes = vec![];
+        let mut meshes = vec![];
+        let mut materials = vec![];
+        let mut textures = vec![];
+        let mut images: Vec<String> = vec![];
+
+        for (lod_index, lod) in self.lods.iter().enumerate() {
+            let mut children = vec![];
+            for (mesh_index, mesh) in lod.meshes.iter().enumerate() {
+                let material = match &mesh.material {
+                    Material::Weighted(material) if !mesh.vertices.is_empty() => material,
+                    _ => continue,
+                };
+
+                let name = format!("lod_{}_mesh_{}", lod_index, mesh_index);
+                let vertices = &mesh.vertices;
+                let mut attributes = Map::new();
+                attributes.insert("POSITION".to_owned(), buffer.add_f32s(vertices.iter().flat_map(|vertex| vertex.position[..3].to_vec()).collect(), "VEC3", true).into());
+                attributes.insert("NORMAL".to_owned(), buffer.add_f32s(vertices.iter().flat_map(|vertex| vertex.normal[..3].to_vec()).collect(), "VEC3", false).into());
+                attributes.insert("TANGENT".to_owned(), buffer.add_f32s(vertices.iter().flat_map(|vertex| {
+                    let handedness = if dot(&cross(&vertex.normal, &vertex.tangent), &vertex.bitangent) < 0.0 { -1.0 } else { 1.0 };
+                    vec![vertex.tangent[0], vertex.tangent[1], vertex.tangent[2], handedness]
+                }).collect(), "VEC4", false).into());
+                attributes.insert("TEXCOORD_0".to_owned(), buffer.add_f32s(vertices.iter().flat_map(|vertex| vertex.uv.to_vec()).collect(), "VEC2", false).into());
+                attributes.insert("_BITANGENT".to_owned(), buffer.add_f32s(vertices.iter().flat_map(|vertex| vertex.bitangent[..3].to_vec()).collect(), "VEC3", false).into());
+                attributes.insert("_RPFM_W".to_owned(), buffer.add_f32s(vertices.iter().flat_map(|vertex| vec![vertex.position[3], vertex.normal[3], vertex.tangent[3], vertex.bitangent[3]]).collect(), "VEC4", false).into());
+
+                match material.vertex_format {
+                    VertexFormat::Default => {
+                        attributes.insert("TEXCOORD_1".to_owned(), buffer.add_f32s(vertices.iter().flat_map(|vertex| vertex.uv_2.to_vec()).collect(), "VEC2", false).into());
+                        attributes.insert("COLOR_0".to_owned(), buffer.add_f32s(vertices.iter().flat_map(|vertex| vertex.colour.to_vec()).collect(), "VEC4", false).into());
+                    }
+                    VertexFormat::Weighted | VertexFormat::Cinematic => {
+                        attributes.insert("JOINTS_0".to_owned(), buffer.add_u8s(vertices.iter().flat_map(|vertex| vertex.bone_indices.to_vec()).collect(), "VEC4").into());
+                        attributes.insert("WEIGHTS_0".to_owned(), buffer.add_f32s(vertices.iter().flat_map(|vertex| vertex.bone_weights.to_vec()).collect(), "VEC4", false).into());
+                    }
+                }
+
+                let indices = buffer.add_u16s(&mesh.indices);
+
+                // Textures go to the material slots glTF has for them. All of them are kept in the extras of the material.
+                let mut gltf_material = json!({
+                    "name": material.name,
+                    "extras": {
+                        "texture_directory": material.texture_directory,
+                        "textures": material.textures.iter().map(|texture| json!({ "type": texture.texture_type.get_value(), "path": texture.path })).collect::<Vec<Value>>(),
+                    }
+                });
+
+                for texture in &material.textures {
+                    let slot = match texture.texture_type {
+                        TextureType::Diffuse | TextureType::BaseColour => "baseColorTexture",
+                        TextureType::Normal => "normalTexture",
+                        TextureType::AmbientOcclusion => "occlusionTexture",
+                        TextureType::MaterialMap => "metallicRoughnessTexture",
+                        _ => continue,
+                    };
+
+                    let image = match images.iter().position(|image| image == &texture.path) {
+                        Some(image) => image,
+                        None => {
+                            images.push(texture.path.to_owned());
+                            textures.push(json!({ "source": images.len() - 1 }));
+                            images.len() - 1
+                        }
+                    };
+
+                    if slot == "baseColorTexture" || slot == "metallicRoughnessTexture" {
+                        if gltf_material["pbrMetallicRoughness"][slot].is_null() {
+                            gltf_material["pbrMetallicRoughness"][slot] = json!({ "index": image });
+                        }
+                    }
+                    else if gltf_material[slot].is_null() {
+                        gltf_material[slot] = json!({ "index": image });
+                    }
+                }
+
+                materials.push(gltf_material);
+                meshes.push(json!({
+                    "name": name,
+                    "primitives": [{
+                        "attributes": attributes,
+                        "indices": indices,
+                        "material": materials.len() - 1,
+                        "mode": MODE_TRIANGLES,
+                    }],
+                    "extras": {
+                        "lod": lod_index,
+                        "mesh": mesh_index,
+                        "vertex_format": material.vertex_format.get_value(),
+                    }
+                }));
+
+                nodes.push(json!({ "name": name, "mesh": meshes.len() - 1 }));
+                children.push(nodes.len() - 1);
+            }
+
+            nodes.push(json!({
+                "name": format!("lod_{}", lod_index),
+                "children": children,
+                "extras": { "camera_distance": lod.camera_distance },
+            }));
+            scene_nodes.push(nodes.len() - 1);
+        }
+
+        let mut gltf = json!({
+            "asset": { "version": "2.0", "generator": "RPFM", "extras": { "skeleton_id": self.skeleton_id, "version": self.version } },
+            "scene": 0,
+            "scenes": [{ "nodes": scene_nodes }],
+            "nodes": nodes,
+        });
+
+        // Empty arrays are not allowed by the spec, so only add what we have.
+        let optional_arrays = vec![
+            ("meshes", meshes),
+            ("materials", materials),
+            ("textures", textures),
+            ("images", images.iter().map(|image| json!({ "uri": image })).collect()),
+            ("bufferViews", buffer.buffer_views),
+            ("accessors", buffer.accessors),
+        ];
+
+        for (key, array) in optional_arrays {
+            if !array.is_empty() {
+                gltf[key] = Value::Array(array);
+            }
+        }
+
+        (gltf, buffer.data)
+    }
+
+    /// This function replaces the geometry of the meshes of this RigidModel with the ones in the provided glTF JSON and buffers.
+    fn apply_gltf(&mut self, gltf: &Value, buffers: &[Vec<u8>]) -> Result<usize> {
+        let mut count = 0;
+        for gltf_mesh in gltf["meshes"].as_array().unwrap_or(&vec![]) {
+            let (lod_index, mesh_index) = match get_mesh_target(gltf_mesh) {
+                Some(target) => target,
+                None => continue,
+            };
+
+            let mesh_name = format!("lod_{}_mesh_{}", lod_index, mesh_index);
+            let mesh = match self.lods.get_mut(lod_index).and_then(|lod| lod.meshes.get_mut(mesh_index)) {
+                Some(mesh) => mesh,
+                None => return Err(ErrorKind::RigidModelImport(format!("The mesh {} is not in the RigidModel.", mesh_name)).into()),
+            };
+
+            if let Material::Raw(..) = mesh.material {
+                return Err(ErrorKind::RigidModelImport(format!("The mesh {} has a material RPFM cannot decode, so its geometry cannot be replaced.", mesh_name)).into());
+            }
+
+            // Blender and other tools split meshes in one primitive per material, so we merge them all into one mesh.
+            let mut vertices = vec![];
+            let mut indices = vec![];
+            for primitive in gltf_mesh["primitives"].as_array().unwrap_or(&vec![]) {
+                if primitive["mode"].as_u64().unwrap_or(MODE_TRIANGLES) != MODE_TRIANGLES {
+                    return Err(ErrorKind::RigidModelImport(format!("The mesh {} has primitives that are not triangle lists.", mesh_name)).into());
+                }
+
+                let attributes = &primitive["attributes"];
+                let positions = match attributes["POSITION"].as_u64() {
+                    Some(accessor) => read_accessor(gltf, buffers, accessor as usize, false)?,
+                    None => return Err(ErrorKind::RigidModelImport(format!("The mesh {} has no positions.", mesh_name)).into()),
+                };
+
+                let vertex_count = positions.len();
+                let read_attribute = |name: &str, normalize: bool| -> Result<Option<Vec<Vec<f32>>>> {
+                    match attributes[name].as_u64() {
+                        Some(accessor) => {
+                            let values = read_accessor(gltf, buffers, accessor as usize, normalize)?;
+                            if values.len() != vertex_count {
+                                return Err(ErrorKind::RigidModelImport(format!("The attribute {} of the mesh {} doesn't have one value per vertex.", name, mesh_name)).into());
+                            }
+                            Ok(Some(values))
+                        }
+                        None => Ok(None),
+                    }
+                };
+
+                let normals = read_attribute("NORMAL", true)?;
+                let tangents = read_attribute("TANGENT", true)?;
+                let bitangents = read_attribute("_BITANGENT", true)?;
+                let w_values = read_attribute("_RPFM_W", true)?;
+                let uvs = read_attribute("TEXCOORD_0", true)?;
+                let uvs_2 = read_attribute("TEXCOORD_1", true)?;
+                let colours = read_attribute("COLOR_0", true)?;
+                let joints = read_attribute("JOINTS_0", false)?;
+                let weights = read_attribute("WEIGHTS_0", true)?;
+
+                let vertex_offset = vertices.len();
+                for index in 0..vertex_count {
+                    let get = |values: &Option<Vec<Vec<f32>>>, component: usize, default: f32| values.as_ref().and_then(|values| values[index].get(component).copied()).unwrap_or(default);
+                    let mut vertex = Vertex::default();
+                    for component in 0..3 {
+                        vertex.position[component] = positions[index].get(component).copied().unwrap_or(0.0);
+                        vertex.normal[component] = get(&normals, component, 0.0);
+                        vertex.tangent[component] = get(&tangents, component, 0.0);
+                    }
+
+                    vertex.position[3] = get(&w_values, 0, 1.0);
+                    vertex.normal[3] = get(&w_values, 1, 0.0);
+                    vertex.tangent[3] = get(&w_values, 2, 0.0);
+                    vertex.bitangent[3] = get(&w_values, 3, 0.0);
+
+                    // If we don't have the bitangents, calculate them from the normals and tangents.
+                    if bitangents.is_some() {
+                        (0..3).for_each(|component| vertex.bitangent[component] = get(&bitangents, component, 0.0));
+                    } else {
+                        let bitangent = cross(&vertex.normal, &vertex.tangent);
+                        let handedness = get(&tangents, 3, 1.0);
+                        (0..3).for_each(|component| vertex.bitangent[component] = bitangent[component] * handedness);
+                    }
+
+                    (0..2).for_each(|component| vertex.uv[component] = get(&uvs, component, 0.0));
+                    (0..2).for_each(|component| vertex.uv_2[component] = get(&uvs_2, component, 0.0));
+                    (0..4).for_each(|component| vertex.colour[component] = get(&colours, component, 1.0));
+                    (0..4).for_each(|component| vertex.bone_indices[component] = get(&joints, component, 0.0) as u8);
+                    (0..4).for_each(|component| vertex.bone_weights[component] = get(&weights, component, 0.0));
+                    vertices.push(vertex);
+                }
+
+                match primitive["indices"].as_u64() {
+                    Some(accessor) => {
+                        for index in read_accessor(gltf, buffers, accessor as usize, false)? {
+                            indices.push(vertex_offset + index[0] as usize);
+                        }
+                    }
+                    None => indices.extend(vertex_offset..vertices.len()),
+                }
+            }
+
+            if vertices.len() > usize::from(u16::MAX) + 1 {
+                return Err(ErrorKind::RigidModelImport(format!("The mesh {} has {} vertices. RigidModels only support up to {} vertices per mesh.", mesh_name, vertices.len(), usize::from(u16::MAX) + 1)).into());
+            }
+
+            if indices.iter().any(|index| *index >= vertices.len()) {
+                return Err(ErrorKind::RigidModelImport(format!("The mesh {} has indices pointing to vertices that don't exist.", mesh_name)).into());
+            }
+
+            // Update the bounding box to match the new geometry.
+            if !vertices.is_empty() {
+                for component in 0..3 {
+                    mesh.bounding_box_min[component] = vertices.iter().map(|vertex| vertex.position[component]).fold(f32::INFINITY, f32::min);
+                    mesh.bounding_box_max[component] = vertices.iter().map(|vertex| vertex.position[component]).fold(f32::NEG_INFINITY, f32::max);
+                }
+            }
+
+            mesh.vertices = vertices;
+            mesh.indices = indices.iter().map(|index| *index as u16).collect();
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+/// Implementation of `GltfBuffer`.
+impl GltfBuffer {
+
+    /// This function adds a new buffer view with the provided data, 4-bytes aligned, returning its index.
+    fn add_buffer_view(&mut self, data: &[u8], target: u64) -> usize {
+        while self.data.len() % 4 != 0 { self.data.push(0); }
+        self.buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": self.data.len(),
+            "byteLength": data.len(),
+            "target": target,
+        }));
+        self.data.extend_from_slice(data);
+        self.buffer_views.len() - 1
+    }
+
+    /// This function adds an accessor for a list of floats of the provided type, returning its index.
+    ///
+    /// Positions require their min and max values, so `min_max` must be true for them.
+    fn add_f32s(&mut self, values: Vec<f32>, value_type: &str, min_max: bool) -> usize {
+        let components = get_component_count(value_type);
+        let mut data = vec![];
+        values.iter().for_each(|value| data.encode_float_f32(*value));
+        let buffer_view = self.add_buffer_view(&data, 34962);
+
+        let mut accessor = json!({
+            "bufferView": buffer_view,
+            "componentType": COMPONENT_TYPE_F32,
+            "count": values.len() / components,
+            "type": value_type,
+        });
+
+        if min_max {
+            accessor["min"] = (0..components).map(|component| values.iter().skip(component).step_by(components).copied().fold(f32::INFINITY, f32::min)).collect::<Vec<f32>>().into();
+            accessor["max"] = (0..components).map(|component| values.iter().skip(component).step_by(components).copied().fold(f32::NEG_INFINITY, f32::max)).collect::<Vec<f32>>().into();
+        }
+
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    /// This function adds an accessor for a list of unsigned bytes of the provided type, returning its index.
+    fn add_u8s(&mut self, values: Vec<u8>, value_type: &str) -> usize {
+        let buffer_view = self.add_buffer_view(&values, 34962);
+        self.accessors.push(json!({
+            "bufferView": buffer_view,
+            "componentType": COMPONENT_TYPE_U8,
+            "count": values.len() / get_component_count(value_type),
+            "type": value_type,
+        }));
+        self.accessors.len() - 1
+    }
+
+    /// This function adds an accessor for a list of indices, returning its index.
+    fn add_u16s(&mut self, values: &[u16]) -> usize {
+        let mut data = vec![];
+        values.iter().for_each(|value| data.encode_integer_u16(*value));
+        let buffer_view = self.add_buffer_view(&data, 34963);
+        self.accessors.push(json!({
+            "bufferView": buffer_view,
+            "componentType": COMPONENT_TYPE_U16,
+            "count": values.len(),
+            "type": "SCALAR",
+        }));
+        self.accessors.len() - 1
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Helper functions
+//---------------------------------------------------------------------------//
+
+/// This function returns the amount of components of a glTF accessor type.
+fn get_component_count(value_type: &str) -> usize {
+    match value_type {
+        "VEC2" => 2,
+        "VEC3" => 3,
+        "VEC4" | "MAT2" => 4,
+        "MAT3" => 9,
+        "MAT4" => 16,
+        _ => 1,
+    }
+}
+
+/// This function returns the LOD and mesh a glTF mesh should replace, from its extras or from its name.
+fn get_mesh_target(gltf_mesh: &Value) -> Option<(usize, usize)> {
+    if let (Some(lod), Some(mesh)) = (gltf_mesh["extras"]["lod"].as_u64(), gltf_mesh["extras"]["mesh"].as_u64()) {
+        return Some((lod as usize, mesh as usize));
+    }
+
+    // Names may have suffixes added by other programs, like `lod_0_mesh_1.001`.
+    let name = gltf_mesh["name"].as_str()?;
+    let mut parts = name.strip_prefix("lod_")?.split("_mesh_");
+    let lod = parts.next()?.parse().ok()?;
+    let mesh = parts.next()?.split(|character: char| !character.is_ascii_digit()).next()?.parse().ok()?;
+    Some((lod, mesh))
+}
+
+/// This function reads the values of a glTF accessor, returning one `Vec` of components for each element.
+///
+/// If `normalize` is true, normalized integer components are converted to the `0..1` range.
+fn read_accessor(gltf: &Value, buffers: &[Vec<u8>], accessor_index: usize, normalize: bool) -> Result<Vec<Vec<f32>>> {
+    let accessor = &gltf["accessors"][accessor_index];
+    if !accessor["sparse"].is_null() {
+        return Err(ErrorKind::RigidModelImport("Sparse accessors are not supported.".to_owned()).into());
+    }
+
+    let count = accessor["count"].as_u64().unwrap_or(0) as usize;
+    let components = get_component_count(accessor["type"].as_str().unwrap_or("SCALAR"));
+    let component_type = accessor["componentType"].as_u64().unwrap_or(0);
+    let component_size = match component_type {
+        COMPONENT_TYPE_U8 | 5120 => 1,
+        COMPONENT_TYPE_U16 | 5122 => 2,
+        COMPONENT_TYPE_U32 | COMPONENT_TYPE_F32 => 4,
+        _ => return Err(ErrorKind::RigidModelImport(format!("Unknown component type: {}.", component_type)).into()),
+    };
+
+    // Accessors without buffer view are all zeros.
+    let buffer_view = &gltf["bufferViews"][accessor["bufferView"].as_u64().unwrap_or(u64::MAX) as usize];
+    if buffer_view.is_null() {
+        return Ok(vec![vec![0.0; components]; count]);
+    }
+
+    let buffer = match buffers.get(buffer_view["buffer"].as_u64().unwrap_or(0) as usize) {
+        Some(buffer) => buffer,
+        None => return Err(ErrorKind::RigidModelImport("One of the buffer views points to a buffer that doesn't exist.".to_owned()).into()),
+    };
+
+    let view_offset = buffer_view["byteOffset"].as_u64().unwrap_or(0) as usize;
+    let view_length = buffer_view["byteLength"].as_u64().unwrap_or(0) as usize;
+    let accessor_offset = accessor["byteOffset"].as_u64().unwrap_or(0) as usize;
+    let start = view_offset + accessor_offset;
+    let stride = buffer_view["byteStride"].as_u64().map(|stride| stride as usize).unwrap_or(components * component_size);
+    let normalize = normalize && accessor["normalized"].as_bool().unwrap_or(false);
+
+    // Make sure all the elements are within the buffer view, and the buffer view within the buffer, before allocating them.
+    let accessor_length = match count.checked_sub(1) {
+        Some(last) => last.checked_mul(stride).and_then(|length| length.checked_add(components * component_size)).and_then(|length| length.checked_add(accessor_offset)),
+        None => Some(0),
+    };
+    match (accessor_length, view_offset.checked_add(view_length)) {
+        (Some(accessor_length), Some(view_end)) if accessor_length <= view_length && view_end <= buffer.len() => {},
+        _ => return Err(ErrorKind::RigidModelImport("One of the accessors doesn't fit in its buffer view.".to_owned()).into()),
+    }
+
+    let mut values = Vec::with_capacity(count);
+    for element in 0..count {
+        let mut value = Vec::with_capacity(components);
+        for component in 0..components {
+            let index = start + element * stride + component * component_size;
+            let (raw_value, max_value) = match component_type {
+                COMPONENT_TYPE_F32 => (buffer.decode_float_f32(index)?, 1.0),
+                COMPONENT_TYPE_U32 => (buffer.decode_integer_u32(index)? as f32, 1.0),
+                COMPONENT_TYPE_U16 => (f32::from(buffer.decode_integer_u16(index)?), 65535.0),
+                COMPONENT_TYPE_U8 => (f32::from(buffer.decode_integer_u8(index)?), 255.0),
+                5122 => (f32::from(buffer.decode_integer_i16(index)?), 32767.0),
+                _ => (f32::from(buffer.decode_integer_i8(index)?), 127.0),
+            };
+
+            value.push(if normalize { (raw_value / max_value).max(-1.0) } else { raw_value });
+        }
+        values.push(value);
+    }
+
+    Ok(values)
+}
+
+/// This function returns the cross product of the first three components of two vectors.
+fn cross(a: &[f32], b: &[f32]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+/// This function returns the dot product of the first three components of two vectors.
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
@@ -14,31 +14,82 @@ Module with all the code to interact with RigidModel PackedFiles.
 RigidModel PackedFiles are 3D models used by Total War games since Empire.
 This is basically a rewrite in Rust of the work done by Phazer on his tool.
 Because I want to avoid more C++ libs if posible.
+
+The structure of a RigidModel is:
+- A header, with the version, the amount of LODs and the skeleton used by the model.
+- A header for each LOD, with the amount of meshes in it and where they start.
+- The meshes of each LOD. Each mesh has its own header, a material, its vertices and its indices.
+
+Materials we don't know how to decode (or that don't encode back to the exact same bytes) are kept along with their
+vertices as raw data, so every model we can decode can be saved back without changes.
 !*/
 
+use half::f16;
 use serde_derive::{Serialize, Deserialize};
 
+use rpfm_error::{ErrorKind, Result};
+use rpfm_macros::*;
+
+use crate::common::{decoder::Decoder, encoder::Encoder};
+
+mod gltf;
+mod obj;
+
+#[cfg(test)]
+mod rigidmodel_test;
+
 /// This represents the value that every RigidModel PackedFile has in their 0-4 bytes. A.k.a it's signature or preamble.
-#[allow(dead_code)]
 const PACKED_FILE_TYPE: &str = "RMV2";
 
 /// Extension used by RigidModel PackedFiles.
 pub const EXTENSION: &str = ".rigid_model_v2";
 
+/// Length of the skeleton id in the header.
+const SKELETON_ID_LENGTH: usize = 128;
+
+/// Length of the header of a RigidModel.
+const HEADER_LENGTH: usize = 12 + SKELETON_ID_LENGTH;
+
+/// Length of the header of a LOD in RigidModels of version 6.
+const LOD_HEADER_LENGTH_V6: usize = 20;
+
+/// Length of the header of a LOD in RigidModels of version 7 and 8.
+const LOD_HEADER_LENGTH_V7: usize = 28;
+
+/// Length of the header of a mesh.
+const MESH_HEADER_LENGTH: usize = 80;
+
+/// Length of the shader name in the header of a mesh.
+const SHADER_NAME_LENGTH: usize = 12;
+
+/// Length of the shader parameters in the header of a mesh.
+const SHADER_PARAMS_LENGTH: usize = 20;
+
+/// Length of the name of a mesh in a weighted material.
+const MESH_NAME_LENGTH: usize = 32;
+
+/// Length of the texture directory in a weighted material.
+const TEXTURE_DIRECTORY_LENGTH: usize = 256;
+
+/// Length of the filters in a weighted material.
+const FILTERS_LENGTH: usize = 256;
+
+/// Length of the extra data at the end of the header of a weighted material.
+const MATERIAL_EXTRA_DATA_LENGTH: usize = 124;
+
+/// Length of the name of an attachment point.
+const ATTACHMENT_POINT_NAME_LENGTH: usize = 32;
+
+/// Length of the path of a texture.
+const TEXTURE_PATH_LENGTH: usize = 256;
+
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
 //---------------------------------------------------------------------------//
 
 /// This struct contains a RigidModel decoded in memory.
-#[derive(Clone, Debug,PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
 pub struct RigidModel {
-    pub header: Header,
-    //pub packed_file_data: RigidModelData,
-}
-
-/// This struct represents the header of a RigidModel.
-#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
-pub struct Header {
 
     /// The version of the RigidModel. The supported versions per game are:
     /// - `6`: Attila or older.
@@ -46,298 +97,923 @@ pub struct Header {
     /// - `8`: Three Kingdoms.
     version: u32,
 
-    /// The skeleton used by this RigidModel.
-    skeleton_id: Vec<u8>,
+    /// The skeleton used by this RigidModel. Empty if the model has no skeleton.
+    skeleton_id: String,
+
+    /// The LODs of the RigidModel, from the most detailed to the least detailed one.
+    lods: Vec<Lod>,
+}
+
+/// This struct represents a LOD (Level Of Detail) of a RigidModel.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct Lod {
+
+    /// Distance from the camera at which this LOD stops being used.
+    camera_distance: f32,
+
+    /// Level of this LOD. Only in version 7 and 8.
+    lod_level: u32,
+
+    /// Quality level of this LOD. Only in version 7 and 8.
+    quality_level: u8,
+
+    /// Padding bytes after the quality level. Only in version 7 and 8.
+    padding: [u8; 3],
+
+    /// The meshes of this LOD.
+    meshes: Vec<Mesh>,
+}
+
+/// This struct represents a mesh (also known as group) of a LOD.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct Mesh {
+
+    /// Type of the material of this mesh. Known values are `65` (weighted) and `68` (default), but there are a lot more.
+    material_type: u16,
+
+    /// Render flags of this mesh.
+    render_flags: u16,
+
+    /// Minimum point of the bounding box of this mesh.
+    bounding_box_min: [f32; 3],
+
+    /// Maximum point of the bounding box of this mesh.
+    bounding_box_max: [f32; 3],
+
+    /// Name of the shader used by this mesh.
+    shader_name: String,
+
+    /// Parameters of the shader. We don't know what they mean.
+    shader_params: Vec<u8>,
+
+    /// Material of this mesh.
+    material: Material,
+
+    /// Vertices of this mesh. Empty if the material is `Material::Raw`, as we don't know their format.
+    vertices: Vec<Vertex>,
+
+    /// Indices of the vertices of each triangle of this mesh.
+    indices: Vec<u16>,
+}
+
+/// This enum represents the material of a mesh.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Material {
+
+    /// Material used by most of the models of the weighted/default family.
+    Weighted(WeightedMaterial),
+
+    /// Material we don't know how to decode. It contains the raw data of the material and the vertices,
+    /// the offset of the vertices from the start of the raw data, and the amount of vertices.
+    Raw(Vec<u8>, u32, u32),
+}
+
+/// This struct represents a material of the weighted/default family.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct WeightedMaterial {
+
+    /// Format of the vertices of the mesh.
+    vertex_format: VertexFormat,
+
+    /// Name of the mesh.
+    name: String,
+
+    /// Folder of the textures of the mesh.
+    texture_directory: String,
+
+    /// Filters of the mesh.
+    filters: String,
+
+    /// Unknown value.
+    unknown_1: u16,
+
+    /// Pivot point of the mesh.
+    pivot: [f32; 3],
+
+    /// Transformation matrices of the mesh, as 3x4 matrices.
+    matrices: [[f32; 12]; 3],
+
+    /// Index of the matrix of the mesh.
+    matrix_index: i32,
+
+    /// Index of the parent matrix of the mesh.
+    parent_matrix_index: i32,
+
+    /// Extra data at the end of the header of the material, in case it's used in the future.
+    extra_data: Vec<u8>,
+
+    /// Attachment points of the mesh.
+    attachment_points: Vec<AttachmentPoint>,
+
+    /// Textures of the mesh.
+    textures: Vec<Texture>,
+
+    /// String parameters of the material, with their index.
+    string_params: Vec<(u32, String)>,
+
+    /// Float parameters of the material, with their index.
+    float_params: Vec<(u32, f32)>,
+
+    /// Integer parameters of the material, with their index.
+    int_params: Vec<(u32, i32)>,
+
+    /// Vector parameters of the material, with their index.
+    vector_params: Vec<(u32, [f32; 4])>,
+}
+
+/// This struct represents an attachment point of a mesh.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct AttachmentPoint {
+
+    /// Name of the attachment point.
+    name: String,
+
+    /// Transformation of the attachment point, as a 3x4 matrix.
+    matrix: [f32; 12],
+
+    /// Bone the attachment point is attached to.
+    bone_id: u32,
+}
+
+/// This struct represents a texture of a mesh.
+#[derive(Clone, Debug, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct Texture {
+
+    /// What this texture is used for.
+    texture_type: TextureType,
+
+    /// Path of the texture in the PackFile.
+    path: String,
+}
+
+/// This enum represents what a texture is used for. The number in their docs is their numeric value when read from a RigidModel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextureType {
+
+    /// **(0)**
+    Diffuse,
+
+    /// **(1)**
+    Normal,
+
+    /// **(3)**
+    Mask,
+
+    /// **(5)**
+    AmbientOcclusion,
+
+    /// **(7)**
+    TilingDirtUV2,
+
+    /// **(8)**
+    DirtAlphaMask,
+
+    /// **(10)**
+    SkinMask,
+
+    /// **(11)**
+    Specular,
+
+    /// **(12)**
+    GlossMap,
+
+    /// **(13)**
+    DecalDirtmap,
+
+    /// **(14)**
+    DecalDirtmask,
+
+    /// **(15)**
+    DecalMask,
+
+    /// **(17)**
+    DiffuseDamage,
+
+    /// **(27)**
+    BaseColour,
+
+    /// **(29)**
+    MaterialMap,
+
+    /// Wildcard for any type that doesn't fit in any of the other categories. The type's value is stored in the Variant.
+    Other(u32),
+}
+
+/// This enum represents the format of the vertices of a mesh. The number in their docs is their numeric value when read from a RigidModel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VertexFormat {
+
+    /// **(0)**: Static vertices, 32 bytes each.
+    Default,
+
+    /// **(3)**: Vertices with up to 2 bones, 28 bytes each.
+    Weighted,
+
+    /// **(4)**: Vertices with up to 4 bones, 32 bytes each.
+    Cinematic,
+}
+
+/// This struct represents a vertex of a mesh. Not all the formats use all the fields. Unused fields are zeroed.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, GetRefMut, Set, Serialize, Deserialize)]
+pub struct Vertex {
+
+    /// Position of the vertex.
+    position: [f32; 4],
+
+    /// Texture coordinates of the vertex.
+    uv: [f32; 2],
+
+    /// Second set of texture coordinates of the vertex. Only in `Default` vertices.
+    uv_2: [f32; 2],
+
+    /// Normal of the vertex.
+    normal: [f32; 4],
+
+    /// Tangent of the vertex.
+    tangent: [f32; 4],
+
+    /// Bitangent of the vertex.
+    bitangent: [f32; 4],
+
+    /// Colour of the vertex. Only in `Default` vertices.
+    colour: [f32; 4],
+
+    /// Bones affecting the vertex. `Weighted` vertices use the first 2, `Cinematic` vertices use the 4.
+    bone_indices: [u8; 4],
+
+    /// Weights of the bones affecting the vertex.
+    bone_weights: [f32; 4],
 }
 
 //---------------------------------------------------------------------------//
 //                              Implementations
 //---------------------------------------------------------------------------//
 
-/// Implementation of RigidModel.
+/// Implementation of `RigidModel`.
 impl RigidModel {
 
     /// This function creates a new empty `Decal` RigidModel.
     pub fn new_decal() -> Self {
         Self {
-            header: Header::default(),
+            version: 7,
+            skeleton_id: String::new(),
+            lods: vec![],
         }
     }
-}
-/*
-/// Struct "RigidModelHeader". For more info about this, check the comment at the start of "packedfile/
-/// rigidmodel/mod.rs".
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct RigidModelHeader {
-    pub packed_file_header_signature: String,
-    pub packed_file_header_model_type: u32,
-    pub packed_file_header_lods_count: u32,
-    pub packed_file_data_base_skeleton: (String, usize),
-}
 
-/// Struct "RigidModelData". For more info about this, check the comment at the start of "packedfile/
-/// rigidmodel/mod.rs".
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct RigidModelData {
-    pub packed_file_data_lods_header: Vec<RigidModelLodHeader>,
-    pub packed_file_data_lods_data: Vec<u8>,
-}
+    /// This function creates a `RigidModel` from a `&[u8]`.
+    pub fn read(packed_file_data: &[u8]) -> Result<Self> {
 
-/// Struct "RigidModelLodHeader". For more info about this, check the comment at the start of "packedfile/
-/// rigidmodel/mod.rs".
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct RigidModelLodHeader {
-    pub groups_count: u32,
-    pub vertices_data_length: u32,
-    pub indices_data_length: u32,
-    pub start_offset: u32,
-    pub lod_zoom_factor: f32,
-    pub mysterious_data_1: Option<u32>,
-    pub mysterious_data_2: Option<u32>,
-}
+        // We check this, just in case we try to read some malformed file with a string in the first four bytes (which is not uncommon).
+        if packed_file_data.decode_string_u8(0, 4)? != PACKED_FILE_TYPE {
+            return Err(ErrorKind::RigidModelNotSupportedFile.into())
+        }
 
-/// Implementation of "RigidModel"
-impl RigidModel {
+        let version = packed_file_data.decode_integer_u32(4)?;
+        let lod_header_length = Self::get_lod_header_length(version)?;
+        let lod_count = packed_file_data.decode_integer_u32(8)?;
+        let skeleton_id = packed_file_data.decode_string_u8_0padded(12, SKELETON_ID_LENGTH)?.0;
+
+        let mut lods = vec![];
+        let mut end_position = HEADER_LENGTH + lod_header_length * lod_count as usize;
+        for lod_index in 0..lod_count as usize {
+            let mut index = HEADER_LENGTH + lod_header_length * lod_index;
+            let mesh_count = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+            let _vertices_data_length = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+            let _indices_data_length = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+            let start_offset = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+            let mut lod = Lod {
+                camera_distance: packed_file_data.decode_packedfile_float_f32(index, &mut index)?,
+                ..Lod::default()
+            };
 
-    /// This function reads the data from a Vec<u8> and decode it into a RigidModel. This CAN FAIL,
-    /// so we return Result<RigidModel, Error>.
-    pub fn read(packed_file_data: &[u8]) -> Result<RigidModel> {
-        let packed_file_header = RigidModelHeader::read(&packed_file_data[..140])?;
-        let packed_file_data = RigidModelData::read(&packed_file_data[140..], packed_file_header.packed_file_header_model_type, packed_file_header.packed_file_header_lods_count)?;
+            if version >= 7 {
+                lod.lod_level = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+                lod.quality_level = packed_file_data.decode_packedfile_integer_u8(index, &mut index)?;
+                lod.padding.copy_from_slice(packed_file_data.get_bytes_checked(index, 3)?);
+            }
+
+            let mut offset = start_offset as usize;
+            for _ in 0..mesh_count {
+                lod.meshes.push(Mesh::read(packed_file_data, &mut offset)?);
+            }
+
+            end_position = end_position.max(offset);
+            lods.push(lod);
+        }
+
+        // If we didn't reach the end of the file, there is something we don't know how to decode.
+        if end_position != packed_file_data.len() {
+            return Err(ErrorKind::RigidModelDecode(format!("There are {} bytes of data after the last mesh.", packed_file_data.len() as isize - end_position as isize)).into())
+        }
 
-        Ok(RigidModel {
-            packed_file_header,
-            packed_file_data,
+        Ok(Self {
+            version,
+            skeleton_id,
+            lods,
         })
     }
 
-    /// This function reads the data from a RigidModel and encode it into a Vec<u8>. This CAN FAIL,
-    /// so we return Result<Vec<u8>, Error>.
-    pub fn save(rigid_model_data: &RigidModel) -> Result<Vec<u8>> {
-        let mut packed_file_data_encoded = RigidModelData::save(&rigid_model_data.packed_file_data);
-        let mut packed_file_header_encoded = RigidModelHeader::save(&rigid_model_data.packed_file_header)?;
+    /// This function takes a `RigidModel` and encodes it to `Vec<u8>`.
+    pub fn save(&self) -> Result<Vec<u8>> {
+        let lod_header_length = Self::get_lod_header_length(self.version)?;
+
+        let mut packed_file = vec![];
+        packed_file.encode_string_u8(PACKED_FILE_TYPE);
+        packed_file.encode_integer_u32(self.version);
+        packed_file.encode_integer_u32(self.lods.len() as u32);
+        packed_file.encode_string_u8_0padded(&(self.skeleton_id.to_owned(), SKELETON_ID_LENGTH))?;
+
+        // The meshes go after all the LOD headers, one LOD after another.
+        let mut meshes_data = vec![];
+        let mut start_offset = HEADER_LENGTH + lod_header_length * self.lods.len();
+        for lod in &self.lods {
+            let mut lod_data = vec![];
+            let mut vertices_data_length = 0;
+            let mut indices_data_length = 0;
+            for mesh in &lod.meshes {
+                let (mesh_data, vertices_length, indices_length) = mesh.save()?;
+                lod_data.extend_from_slice(&mesh_data);
+                vertices_data_length += vertices_length;
+                indices_data_length += indices_length;
+            }
+
+            packed_file.encode_integer_u32(lod.meshes.len() as u32);
+            packed_file.encode_integer_u32(vertices_data_length);
+            packed_file.encode_integer_u32(indices_data_length);
+            packed_file.encode_integer_u32(start_offset as u32);
+            packed_file.encode_float_f32(lod.camera_distance);
+
+            if self.version >= 7 {
+                packed_file.encode_integer_u32(lod.lod_level);
+                packed_file.push(lod.quality_level);
+                packed_file.extend_from_slice(&lod.padding);
+            }
 
-        let mut packed_file_encoded = vec![];
-        packed_file_encoded.append(&mut packed_file_header_encoded);
-        packed_file_encoded.append(&mut packed_file_data_encoded);
+            start_offset += lod_data.len();
+            meshes_data.append(&mut lod_data);
+        }
+
+        packed_file.append(&mut meshes_data);
+        Ok(packed_file)
+    }
 
-        Ok(packed_file_encoded)
+    /// This function returns the paths of all the textures used by this RigidModel, without duplicates.
+    pub fn get_texture_paths(&self) -> Vec<&str> {
+        let mut paths = self.lods.iter()
+            .flat_map(|lod| lod.meshes.iter())
+            .filter_map(|mesh| if let Material::Weighted(material) = &mesh.material { Some(material) } else { None })
+            .flat_map(|material| material.textures.iter().map(|texture| &*texture.path))
+            .collect::<Vec<&str>>();
+        paths.sort_unstable();
+        paths.dedup();
+        paths
     }
 
-    /// This function is used to patch a RigidModel 3D model from Total War: Attila to work in Total War:
-    /// Warhammer 1 and 2. The process to patch a RigidModel is simple:
-    /// - We update the version of the RigidModel from 6(Attila) to 7(Warhammer 1&2).
-    /// - We add 2 u32 to the Lods: a counter starting at 0, and a 0.
-    /// - We increase the start_offset of every Lod by (8*amount_of_lods).
-    /// - We may need to increase the zoom_factor of the first Lod to 1000.0, because otherwise sometimes the models
-    ///   disappear when you move the camera far from them.
-    /// It requires a mut ref to a decoded PackFile, and returns an String (Result<Success, Error>).
-    pub fn patch_rigid_model_attila_to_warhammer (&mut self) -> Result<String> {
-
-        // If the RigidModel is an Attila RigidModel, we continue. Otherwise, return Error.
-        match self.packed_file_header.packed_file_header_model_type {
-            6 => {
-                // We update his version.
-                self.packed_file_header.packed_file_header_model_type = 7;
-
-                // Next, we change the needed data for every Lod.
-                for (index, lod) in self.packed_file_data.packed_file_data_lods_header.iter_mut().enumerate() {
-                    lod.mysterious_data_1 = Some(index as u32);
-                    lod.mysterious_data_2 = Some(0);
-                    lod.start_offset += 8 * self.packed_file_header.packed_file_header_lods_count;
+    /// This function replaces all the texture paths matching `old_path` (case-insensitive) with `new_path`.
+    ///
+    /// It returns how many textures have been changed.
+    pub fn rename_texture_paths(&mut self, old_path: &str, new_path: &str) -> usize {
+        let old_path = old_path.to_lowercase();
+        let mut count = 0;
+        for lod in &mut self.lods {
+            for mesh in &mut lod.meshes {
+                if let Material::Weighted(material) = &mut mesh.material {
+                    for texture in &mut material.textures {
+                        if texture.path.to_lowercase() == old_path {
+                            texture.path = new_path.to_owned();
+                            count += 1;
+                        }
+                    }
                 }
-                Ok("RigidModel patched succesfully.".to_owned())
-            },
-            7 => Err(ErrorKind::RigidModelPatchToWarhammer("This is not an Attila's RigidModel, but a Warhammer one.".to_owned()).into()),
-            _ => Err(ErrorKind::RigidModelPatchToWarhammer("I don't even know from what game is this RigidModel.".to_owned()).into()),
+            }
         }
+        count
     }
-}
 
-/// Implementation of "RigidModelHeader"
-impl RigidModelHeader {
+    /// This function returns the length of the LOD headers for the provided version, or an error if the version is not supported.
+    fn get_lod_header_length(version: u32) -> Result<usize> {
+        match version {
+            6 => Ok(LOD_HEADER_LENGTH_V6),
+            7 | 8 => Ok(LOD_HEADER_LENGTH_V7),
+            _ => Err(ErrorKind::RigidModelNotSupportedType.into()),
+        }
+    }
+}
 
-    /// This function reads the data from a Vec<u8> and decode it into a RigidModelHeader. This CAN FAIL,
-    /// so we return Result<RigidModelHeader, Error>.
-    pub fn read(packed_file_data: &[u8]) -> Result<RigidModelHeader> {
+/// Implementation of `Mesh`.
+impl Mesh {
 
-        let mut packed_file_header = RigidModelHeader {
-            packed_file_header_signature: String::new(),
-            packed_file_header_model_type: 0,
-            packed_file_header_lods_count: 0,
-            packed_file_data_base_skeleton: (String::new(), 0),
-        };
+    /// This function creates a new empty `Mesh` with a weighted material, with the provided vertex format.
+    pub fn new(material_type: u16, vertex_format: VertexFormat) -> Self {
+        Self {
+            material_type,
+            render_flags: 0,
+            bounding_box_min: [0.0; 3],
+            bounding_box_max: [0.0; 3],
+            shader_name: String::new(),
+            shader_params: vec![0; SHADER_PARAMS_LENGTH],
+            material: Material::Weighted(WeightedMaterial::new(vertex_format)),
+            vertices: vec![],
+            indices: vec![],
+        }
+    }
 
-        match packed_file_data.decode_string_u8(0, 4) {
-            Ok(data) => packed_file_header.packed_file_header_signature = data,
-            Err(error) => return Err(error)
+    /// This function reads a `Mesh` from the provided data, starting at `offset`, and moves the offset to the end of the mesh.
+    fn read(data: &[u8], offset: &mut usize) -> Result<Self> {
+        let start = *offset;
+        let mut index = *offset;
+        let material_type = data.decode_packedfile_integer_u16(index, &mut index)?;
+        let render_flags = data.decode_packedfile_integer_u16(index, &mut index)?;
+        let mesh_length = data.decode_packedfile_integer_u32(index, &mut index)? as usize;
+        let vertices_offset = data.decode_packedfile_integer_u32(index, &mut index)? as usize;
+        let vertices_count = data.decode_packedfile_integer_u32(index, &mut index)?;
+        let indices_offset = data.decode_packedfile_integer_u32(index, &mut index)? as usize;
+        let indices_count = data.decode_packedfile_integer_u32(index, &mut index)? as usize;
+
+        let mut bounding_box_min = [0.0; 3];
+        let mut bounding_box_max = [0.0; 3];
+        for value in bounding_box_min.iter_mut().chain(bounding_box_max.iter_mut()) {
+            *value = data.decode_packedfile_float_f32(index, &mut index)?;
         }
 
-        // We check this, just in case we try to read some malformed file with a string in the first
-        // four bytes (which is not uncommon).
-        if packed_file_header.packed_file_header_signature != "RMV2" {
-            return Err(ErrorKind::RigidModelNotSupportedFile.into())
+        let shader_name = data.decode_string_u8_0padded(index, SHADER_NAME_LENGTH)?.0;
+        let shader_params = data.get_bytes_checked(index + SHADER_NAME_LENGTH, SHADER_PARAMS_LENGTH)?.to_vec();
+
+        // Make sure the different sections of the mesh make sense before decoding them.
+        if vertices_offset < MESH_HEADER_LENGTH || indices_offset < vertices_offset || indices_offset + indices_count * 2 != mesh_length {
+            return Err(ErrorKind::RigidModelDecode(format!("The mesh at {} has invalid offsets.", start)).into())
         }
 
-        match packed_file_data.decode_integer_u32(4) {
-            Ok(data) => packed_file_header.packed_file_header_model_type = data,
-            Err(error) => return Err(error)
+        let material_data = data.get_bytes_checked(start + MESH_HEADER_LENGTH, indices_offset - MESH_HEADER_LENGTH)?;
+        let vertices_offset_material = (vertices_offset - MESH_HEADER_LENGTH) as u32;
+        let (material, vertices) = match Self::read_material(material_data, vertices_offset_material, vertices_count) {
+            Ok((material, vertices)) => (material, vertices),
+            Err(_) => (Material::Raw(material_data.to_vec(), vertices_offset_material, vertices_count), vec![]),
+        };
+
+        let mut index = start + indices_offset;
+        let indices = (0..indices_count).map(|_| data.decode_packedfile_integer_u16(index, &mut index)).collect::<Result<Vec<u16>>>()?;
+
+        *offset = start + mesh_length;
+        Ok(Self {
+            material_type,
+            render_flags,
+            bounding_box_min,
+            bounding_box_max,
+            shader_name,
+            shader_params,
+            material,
+            vertices,
+            indices,
+        })
+    }
+
+    /// This function decodes the material and the vertices of a mesh. If they don't encode back to the exact same data, it returns an error.
+    fn read_material(data: &[u8], vertices_offset: u32, vertices_count: u32) -> Result<(Material, Vec<Vertex>)> {
+        let mut index = 0;
+        let material = WeightedMaterial::read(data, &mut index)?;
+        if index != vertices_offset as usize {
+            return Err(ErrorKind::RigidModelDecode("Material length doesn't match the vertices offset.".to_owned()).into())
         }
 
-        match packed_file_data.decode_integer_u32(8) {
-            Ok(data) => packed_file_header.packed_file_header_lods_count = data,
-            Err(error) => return Err(error)
+        let vertex_length = material.vertex_format.get_vertex_length();
+        if data.len() - index != vertex_length * vertices_count as usize {
+            return Err(ErrorKind::RigidModelDecode("Vertices length doesn't match the vertex format.".to_owned()).into())
         }
 
-        match packed_file_data.decode_string_u8_0padded(12, 128) {
-            Ok(data) => packed_file_header.packed_file_data_base_skeleton = data,
-            Err(error) => return Err(error)
+        let vertices = (0..vertices_count).map(|_| Vertex::read(data, &mut index, material.vertex_format)).collect::<Result<Vec<Vertex>>>()?;
+
+        let material = Material::Weighted(material);
+        let (data_encoded, _) = Self::save_material(&material, &vertices)?;
+        if data_encoded != data {
+            return Err(ErrorKind::RigidModelDecode("Material doesn't encode back to the same data.".to_owned()).into())
         }
-        Ok(packed_file_header)
+
+        Ok((material, vertices))
     }
 
-    /// This function reads the data from a RigidModelHeader and encode it into a Vec<u8>. This CAN FAIL,
-    /// so we return Result<Vec<u8>, Error>.
-    pub fn save(rigid_model_header: &RigidModelHeader) -> Result<Vec<u8>> {
-        let mut packed_file_data: Vec<u8> = vec![];
+    /// This function encodes the material and the vertices of a mesh, returning them and the offset of the vertices within the encoded data.
+    fn save_material(material: &Material, vertices: &[Vertex]) -> Result<(Vec<u8>, usize)> {
+        match material {
+            Material::Weighted(material) => {
+                let mut data = material.save()?;
+                let vertices_offset = data.len();
+                vertices.iter().for_each(|vertex| vertex.save(&mut data, material.vertex_format));
+                Ok((data, vertices_offset))
+            }
+            Material::Raw(data, vertices_offset, _) => Ok((data.to_vec(), *vertices_offset as usize)),
+        }
+    }
 
-        packed_file_data.encode_string_u8(&rigid_model_header.packed_file_header_signature);
-        packed_file_data.encode_integer_u32(rigid_model_header.packed_file_header_model_type);
-        packed_file_data.encode_integer_u32(rigid_model_header.packed_file_header_lods_count);
-        packed_file_data.encode_string_u8_0padded(&rigid_model_header.packed_file_data_base_skeleton)?;
+    /// This function encodes a `Mesh`, returning it along with the length of its vertices and its indices.
+    fn save(&self) -> Result<(Vec<u8>, u32, u32)> {
+        let (mut material_data, vertices_offset) = Self::save_material(&self.material, &self.vertices)?;
+        let vertices_count = match self.material {
+            Material::Weighted(_) => self.vertices.len() as u32,
+            Material::Raw(_, _, vertices_count) => vertices_count,
+        };
 
-        Ok(packed_file_data)
+        let vertices_length = (material_data.len() - vertices_offset) as u32;
+        let indices_length = self.indices.len() as u32 * 2;
+        let indices_offset = (MESH_HEADER_LENGTH + material_data.len()) as u32;
+
+        let mut data = vec![];
+        data.encode_integer_u16(self.material_type);
+        data.encode_integer_u16(self.render_flags);
+        data.encode_integer_u32(indices_offset + indices_length);
+        data.encode_integer_u32((MESH_HEADER_LENGTH + vertices_offset) as u32);
+        data.encode_integer_u32(vertices_count);
+        data.encode_integer_u32(indices_offset);
+        data.encode_integer_u32(self.indices.len() as u32);
+        self.bounding_box_min.iter().chain(self.bounding_box_max.iter()).for_each(|value| data.encode_float_f32(*value));
+        data.encode_string_u8_0padded(&(self.shader_name.to_owned(), SHADER_NAME_LENGTH))?;
+
+        let mut shader_params = self.shader_params.to_vec();
+        shader_params.resize(SHADER_PARAMS_LENGTH, 0);
+        data.append(&mut shader_params);
+        data.append(&mut material_data);
+        self.indices.iter().for_each(|index| data.encode_integer_u16(*index));
+
+        Ok((data, vertices_length, indices_length))
     }
 }
 
-/// Implementation of "RigidModelData"
-impl RigidModelData {
-
-    /// This function reads the data from a Vec<u8> and decode it into a RigidModelData. This CAN FAIL,
-    /// so we return Result<RigidModelData, Error>.
-    pub fn read(packed_file_data: &[u8], packed_file_header_model_type: u32, packed_file_header_lods_count: u32) -> Result<RigidModelData> {
-        let mut packed_file_data_lods_header: Vec<RigidModelLodHeader> = vec![];
-        let mut index: usize = 0;
-        let offset: usize = match packed_file_header_model_type {
-            6 => 20, // Attila
-            7 => 28, // Warhammer 1&2
-            _ => return Err(ErrorKind::RigidModelNotSupportedType.into())
-        };
+/// Implementation of `WeightedMaterial`.
+impl WeightedMaterial {
 
-        // We get the "headers" of every lod.
-        for _ in 0..packed_file_header_lods_count {
-            let lod_header = match RigidModelLodHeader::read(&packed_file_data[index..(index + offset)]) {
-                Ok(data) => data,
-                Err(error) => return Err(error)
-            };
-            packed_file_data_lods_header.push(lod_header);
-            index += offset;
+    /// This function creates a new empty `WeightedMaterial` with the provided vertex format.
+    pub fn new(vertex_format: VertexFormat) -> Self {
+        let identity = [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        Self {
+            vertex_format,
+            name: String::new(),
+            texture_directory: String::new(),
+            filters: String::new(),
+            unknown_1: 0,
+            pivot: [0.0; 3],
+            matrices: [identity; 3],
+            matrix_index: -1,
+            parent_matrix_index: -1,
+            extra_data: vec![0; MATERIAL_EXTRA_DATA_LENGTH],
+            attachment_points: vec![],
+            textures: vec![],
+            string_params: vec![],
+            float_params: vec![],
+            int_params: vec![],
+            vector_params: vec![],
+        }
+    }
+
+    /// This function reads a `WeightedMaterial` from the provided data, starting at `index`, and moves the index to the end of the material.
+    fn read(data: &[u8], index: &mut usize) -> Result<Self> {
+        let vertex_format = VertexFormat::get_format(data.decode_packedfile_integer_u16(*index, index)?)?;
+        let name = decode_padded_string(data, index, MESH_NAME_LENGTH)?;
+        let texture_directory = decode_padded_string(data, index, TEXTURE_DIRECTORY_LENGTH)?;
+        let filters = decode_padded_string(data, index, FILTERS_LENGTH)?;
+        let unknown_1 = data.decode_packedfile_integer_u16(*index, index)?;
+
+        let mut pivot = [0.0; 3];
+        for value in &mut pivot {
+            *value = data.decode_packedfile_float_f32(*index, index)?;
+        }
+
+        let mut matrices = [[0.0; 12]; 3];
+        for matrix in &mut matrices {
+            for value in matrix.iter_mut() {
+                *value = data.decode_packedfile_float_f32(*index, index)?;
+            }
+        }
+
+        let matrix_index = data.decode_packedfile_integer_i32(*index, index)?;
+        let parent_matrix_index = data.decode_packedfile_integer_i32(*index, index)?;
+        let attachment_points_count = data.decode_packedfile_integer_u32(*index, index)?;
+        let textures_count = data.decode_packedfile_integer_u32(*index, index)?;
+        let string_params_count = data.decode_packedfile_integer_u32(*index, index)?;
+        let float_params_count = data.decode_packedfile_integer_u32(*index, index)?;
+        let int_params_count = data.decode_packedfile_integer_u32(*index, index)?;
+        let vector_params_count = data.decode_packedfile_integer_u32(*index, index)?;
+        let extra_data = data.get_bytes_checked(*index, MATERIAL_EXTRA_DATA_LENGTH)?.to_vec();
+        *index += MATERIAL_EXTRA_DATA_LENGTH;
+
+        let mut attachment_points = vec![];
+        for _ in 0..attachment_points_count {
+            let name = decode_padded_string(data, index, ATTACHMENT_POINT_NAME_LENGTH)?;
+            let mut matrix = [0.0; 12];
+            for value in &mut matrix {
+                *value = data.decode_packedfile_float_f32(*index, index)?;
+            }
+
+            let bone_id = data.decode_packedfile_integer_u32(*index, index)?;
+            attachment_points.push(AttachmentPoint { name, matrix, bone_id });
+        }
+
+        let mut textures = vec![];
+        for _ in 0..textures_count {
+            let texture_type = TextureType::get_type(data.decode_packedfile_integer_u32(*index, index)?);
+            let path = decode_padded_string(data, index, TEXTURE_PATH_LENGTH)?;
+            textures.push(Texture { texture_type, path });
+        }
+
+        let mut string_params = vec![];
+        for _ in 0..string_params_count {
+            string_params.push((data.decode_packedfile_integer_u32(*index, index)?, data.decode_packedfile_string_u8(*index, index)?));
+        }
+
+        let mut float_params = vec![];
+        for _ in 0..float_params_count {
+            float_params.push((data.decode_packedfile_integer_u32(*index, index)?, data.decode_packedfile_float_f32(*index, index)?));
+        }
+
+        let mut int_params = vec![];
+        for _ in 0..int_params_count {
+            int_params.push((data.decode_packedfile_integer_u32(*index, index)?, data.decode_packedfile_integer_i32(*index, index)?));
         }
 
-        let packed_file_data_lods_data = packed_file_data[index..].to_vec();
+        let mut vector_params = vec![];
+        for _ in 0..vector_params_count {
+            let param_index = data.decode_packedfile_integer_u32(*index, index)?;
+            let mut vector = [0.0; 4];
+            for value in &mut vector {
+                *value = data.decode_packedfile_float_f32(*index, index)?;
+            }
+            vector_params.push((param_index, vector));
+        }
 
-        Ok(RigidModelData {
-            packed_file_data_lods_header,
-            packed_file_data_lods_data,
+        Ok(Self {
+            vertex_format,
+            name,
+            texture_directory,
+            filters,
+            unknown_1,
+            pivot,
+            matrices,
+            matrix_index,
+            parent_matrix_index,
+            extra_data,
+            attachment_points,
+            textures,
+            string_params,
+            float_params,
+            int_params,
+            vector_params,
         })
     }
 
-    /// This function reads the data from a RigidModelData and encode it into a Vec<u8>. This CAN FAIL,
-    /// so we return Result<Vec<u8>, Error>.
-    pub fn save(rigid_model_data: &RigidModelData) -> Vec<u8> {
-        let mut packed_file_data = vec![];
+    /// This function encodes a `WeightedMaterial` to `Vec<u8>`.
+    fn save(&self) -> Result<Vec<u8>> {
+        let mut data = vec![];
+        data.encode_integer_u16(self.vertex_format.get_value());
+        data.encode_string_u8_0padded(&(self.name.to_owned(), MESH_NAME_LENGTH))?;
+        data.encode_string_u8_0padded(&(self.texture_directory.to_owned(), TEXTURE_DIRECTORY_LENGTH))?;
+        data.encode_string_u8_0padded(&(self.filters.to_owned(), FILTERS_LENGTH))?;
+        data.encode_integer_u16(self.unknown_1);
+        self.pivot.iter().for_each(|value| data.encode_float_f32(*value));
+        self.matrices.iter().flat_map(|matrix| matrix.iter()).for_each(|value| data.encode_float_f32(*value));
+        data.encode_integer_i32(self.matrix_index);
+        data.encode_integer_i32(self.parent_matrix_index);
+        data.encode_integer_u32(self.attachment_points.len() as u32);
+        data.encode_integer_u32(self.textures.len() as u32);
+        data.encode_integer_u32(self.string_params.len() as u32);
+        data.encode_integer_u32(self.float_params.len() as u32);
+        data.encode_integer_u32(self.int_params.len() as u32);
+        data.encode_integer_u32(self.vector_params.len() as u32);
+
+        let mut extra_data = self.extra_data.to_vec();
+        extra_data.resize(MATERIAL_EXTRA_DATA_LENGTH, 0);
+        data.append(&mut extra_data);
+
+        for attachment_point in &self.attachment_points {
+            data.encode_string_u8_0padded(&(attachment_point.name.to_owned(), ATTACHMENT_POINT_NAME_LENGTH))?;
+            attachment_point.matrix.iter().for_each(|value| data.encode_float_f32(*value));
+            data.encode_integer_u32(attachment_point.bone_id);
+        }
+
+        for texture in &self.textures {
+            data.encode_integer_u32(texture.texture_type.get_value());
+            data.encode_string_u8_0padded(&(texture.path.to_owned(), TEXTURE_PATH_LENGTH))?;
+        }
+
+        for (index, value) in &self.string_params {
+            data.encode_integer_u32(*index);
+            data.encode_packedfile_string_u8(value);
+        }
+
+        for (index, value) in &self.float_params {
+            data.encode_integer_u32(*index);
+            data.encode_float_f32(*value);
+        }
 
-        // For each Lod, we save it, and add it to the "Encoded Data" vector. After that, we add to that
-        // vector the extra data, and return it.
-        for lod in &rigid_model_data.packed_file_data_lods_header {
-            packed_file_data.append(&mut RigidModelLodHeader::save(lod));
+        for (index, value) in &self.int_params {
+            data.encode_integer_u32(*index);
+            data.encode_integer_i32(*value);
         }
 
-        packed_file_data.extend_from_slice(&rigid_model_data.packed_file_data_lods_data);
-        packed_file_data
+        for (index, vector) in &self.vector_params {
+            data.encode_integer_u32(*index);
+            vector.iter().for_each(|value| data.encode_float_f32(*value));
+        }
+
+        Ok(data)
     }
 }
 
-/// Implementation of "RigidModelLodHeader"
-impl RigidModelLodHeader {
-
-    /// This function reads the data from a Vec<u8> and decode it into a RigidModelLodHeader. This CAN FAIL,
-    /// so we return Result<RigidModelLodHeader, Error>.
-    pub fn read(packed_file_data: &[u8]) -> Result<RigidModelLodHeader> {
-        let mut header = RigidModelLodHeader {
-            groups_count: 0,
-            vertices_data_length: 0,
-            indices_data_length: 0,
-            start_offset: 0,
-            lod_zoom_factor: 0.0,
-            mysterious_data_1: None,
-            mysterious_data_2: None,
-        };
-        match packed_file_data.decode_integer_u32(0) {
-            Ok(data) => header.groups_count = data,
-            Err(error) => return Err(error)
+/// Implementation of `Texture`.
+impl Texture {
+
+    /// This function creates a new `Texture` of the provided type and path.
+    pub fn new(texture_type: TextureType, path: &str) -> Self {
+        Self {
+            texture_type,
+            path: path.to_owned(),
         }
-        match packed_file_data.decode_integer_u32(4) {
-            Ok(data) => header.vertices_data_length = data,
-            Err(error) => return Err(error)
+    }
+}
+
+/// Implementation of `TextureType`.
+impl TextureType {
+
+    /// This function returns the `TextureType` corresponding to the provided value.
+    pub fn get_type(value: u32) -> Self {
+        match value {
+            0 => Self::Diffuse,
+            1 => Self::Normal,
+            3 => Self::Mask,
+            5 => Self::AmbientOcclusion,
+            7 => Self::TilingDirtUV2,
+            8 => Self::DirtAlphaMask,
+            10 => Self::SkinMask,
+            11 => Self::Specular,
+            12 => Self::GlossMap,
+            13 => Self::DecalDirtmap,
+            14 => Self::DecalDirtmask,
+            15 => Self::DecalMask,
+            17 => Self::DiffuseDamage,
+            27 => Self::BaseColour,
+            29 => Self::MaterialMap,
+            _ => Self::Other(value),
+        }
+    }
+
+    /// This function returns the value of the `TextureType` in `u32` format.
+    pub fn get_value(self) -> u32 {
+        match self {
+            Self::Diffuse => 0,
+            Self::Normal => 1,
+            Self::Mask => 3,
+            Self::AmbientOcclusion => 5,
+            Self::TilingDirtUV2 => 7,
+            Self::DirtAlphaMask => 8,
+            Self::SkinMask => 10,
+            Self::Specular => 11,
+            Self::GlossMap => 12,
+            Self::DecalDirtmap => 13,
+            Self::DecalDirtmask => 14,
+            Self::DecalMask => 15,
+            Self::DiffuseDamage => 17,
+            Self::BaseColour => 27,
+            Self::MaterialMap => 29,
+            Self::Other(value) => value,
         }
-        match packed_file_data.decode_integer_u32(8) {
-            Ok(data) => header.indices_data_length = data,
-            Err(error) => return Err(error)
+    }
+}
+
+/// Implementation of `VertexFormat`.
+impl VertexFormat {
+
+    /// This function returns the `VertexFormat` corresponding to the provided value, or an error if it's not supported.
+    pub fn get_format(value: u16) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Default),
+            3 => Ok(Self::Weighted),
+            4 => Ok(Self::Cinematic),
+            _ => Err(ErrorKind::RigidModelDecode(format!("Unknown vertex format: {}.", value)).into()),
         }
-        match packed_file_data.decode_integer_u32(12) {
-            Ok(data) => header.start_offset = data,
-            Err(error) => return Err(error)
+    }
+
+    /// This function returns the value of the `VertexFormat` in `u16` format.
+    pub fn get_value(self) -> u16 {
+        match self {
+            Self::Default => 0,
+            Self::Weighted => 3,
+            Self::Cinematic => 4,
         }
-        match packed_file_data.decode_float_f32(16) {
-            Ok(data) => header.lod_zoom_factor = data,
-            Err(error) => return Err(error)
+    }
+
+    /// This function returns the length in bytes of a vertex of this format.
+    pub fn get_vertex_length(self) -> usize {
+        match self {
+            Self::Default => 32,
+            Self::Weighted => 28,
+            Self::Cinematic => 32,
         }
+    }
+}
 
-        // These two we only decode them if the RigidModel is v7 (Warhammer 1&2), as these doesn't exist
-        // in Attila's RigidModels.
-        if packed_file_data.len() == 28 {
-            match packed_file_data.decode_integer_u32(20) {
-                Ok(data) => header.mysterious_data_1 = Some(data),
-                Err(error) => return Err(error)
+/// Implementation of `Vertex`.
+impl Vertex {
+
+    /// This function reads a `Vertex` of the provided format from the provided data, starting at `index`, and moves the index to the end of the vertex.
+    fn read(data: &[u8], index: &mut usize, vertex_format: VertexFormat) -> Result<Self> {
+        let mut vertex = Self::default();
+        decode_f16s(data, index, &mut vertex.position)?;
+        match vertex_format {
+            VertexFormat::Default => {
+                decode_f16s(data, index, &mut vertex.uv)?;
+                decode_f16s(data, index, &mut vertex.uv_2)?;
+                decode_normalized(data, index, &mut vertex.normal)?;
+                decode_normalized(data, index, &mut vertex.tangent)?;
+                decode_normalized(data, index, &mut vertex.bitangent)?;
+                decode_unorm(data, index, &mut vertex.colour)?;
             }
-            match packed_file_data.decode_integer_u32(24) {
-                Ok(data) => header.mysterious_data_2 = Some(data),
-                Err(error) => return Err(error)
+
+            VertexFormat::Weighted | VertexFormat::Cinematic => {
+                let bones = if vertex_format == VertexFormat::Weighted { 2 } else { 4 };
+                vertex.bone_indices[..bones].copy_from_slice(data.get_bytes_checked(*index, bones)?);
+                *index += bones;
+                decode_unorm(data, index, &mut vertex.bone_weights[..bones])?;
+                decode_normalized(data, index, &mut vertex.normal)?;
+                decode_f16s(data, index, &mut vertex.uv)?;
+                decode_normalized(data, index, &mut vertex.tangent)?;
+                decode_normalized(data, index, &mut vertex.bitangent)?;
             }
         }
 
-        Ok(header)
+        Ok(vertex)
     }
 
-    /// This function reads the data from a RigidModelLodHeader and encode it into a Vec<u8>.
-    pub fn save(rigid_model_lod: &RigidModelLodHeader) -> Vec<u8> {
-        let mut packed_file_data: Vec<u8> = vec![];
+    /// This function encodes a `Vertex` in the provided format, adding it to the provided data.
+    fn save(&self, data: &mut Vec<u8>, vertex_format: VertexFormat) {
+        encode_f16s(data, &self.position);
+        match vertex_format {
+            VertexFormat::Default => {
+                encode_f16s(data, &self.uv);
+                encode_f16s(data, &self.uv_2);
+                encode_normalized(data, &self.normal);
+                encode_normalized(data, &self.tangent);
+                encode_normalized(data, &self.bitangent);
+                encode_unorm(data, &self.colour);
+            }
 
-        packed_file_data.encode_integer_u32(rigid_model_lod.groups_count);
-        packed_file_data.encode_integer_u32(rigid_model_lod.vertices_data_length);
-        packed_file_data.encode_integer_u32(rigid_model_lod.indices_data_length);
-        packed_file_data.encode_integer_u32(rigid_model_lod.start_offset);
-        packed_file_data.encode_float_f32(rigid_model_lod.lod_zoom_factor);
+            VertexFormat::Weighted | VertexFormat::Cinematic => {
+                let bones = if vertex_format == VertexFormat::Weighted { 2 } else { 4 };
+                data.extend_from_slice(&self.bone_indices[..bones]);
+                encode_unorm(data, &self.bone_weights[..bones]);
+                encode_normalized(data, &self.normal);
+                encode_f16s(data, &self.uv);
+                encode_normalized(data, &self.tangent);
+                encode_normalized(data, &self.bitangent);
+            }
+        }
+    }
+}
 
-        let mysterious_data_1 = match rigid_model_lod.mysterious_data_1 {
-            Some(data) => Some(data),
-            None => None,
-        };
+//---------------------------------------------------------------------------//
+//                              Helper functions
+//---------------------------------------------------------------------------//
 
-        let mysterious_data_2 = match rigid_model_lod.mysterious_data_2 {
-            Some(data) => Some(data),
-            None => None,
-        };
+/// This function decodes a 0-padded string of the provided size, moving the index to the end of it.
+fn decode_padded_string(data: &[u8], index: &mut usize, size: usize) -> Result<String> {
+    let string = data.decode_string_u8_0padded(*index, size)?.0;
+    *index += size;
+    Ok(string)
+}
 
-        // These two are only added if they are something (Warhammer1&2 RigidModels).
-        if mysterious_data_1 != None {
-            packed_file_data.encode_integer_u32(mysterious_data_1.unwrap());
-        }
-        if mysterious_data_2 != None {
-            packed_file_data.encode_integer_u32(mysterious_data_2.unwrap());
-        }
-        packed_file_data
+/// This function decodes a group of half-precision floats, moving the index to the end of them.
+fn decode_f16s(data: &[u8], index: &mut usize, values: &mut [f32]) -> Result<()> {
+    for value in values {
+        *value = f16::from_bits(data.decode_packedfile_integer_u16(*index, index)?).to_f32();
+    }
+    Ok(())
+}
+
+/// This function decodes a group of bytes as floats in the `-1..1` range, moving the index to the end of them.
+fn decode_normalized(data: &[u8], index: &mut usize, values: &mut [f32]) -> Result<()> {
+    for value in values {
+        *value = f32::from(data.decode_packedfile_integer_u8(*index, index)?) / 255.0 * 2.0 - 1.0;
+    }
+    Ok(())
+}
+
+/// This function decodes a group of bytes as floats in the `0..1` range, moving the index to the end of them.
+fn decode_unorm(data: &[u8], index: &mut usize, values: &mut [f32]) -> Result<()> {
+    for value in values {
+        *value = f32::from(data.decode_packedfile_integer_u8(*index, index)?) / 255.0;
     }
+    Ok(())
+}
+
+/// This function encodes a group of floats as half-precision floats.
+fn encode_f16s(data: &mut Vec<u8>, values: &[f32]) {
+    values.iter().for_each(|value| data.encode_integer_u16(f16::from_f32(*value).to_bits()));
+}
+
+/// This function encodes a group of floats in the `-1..1` range as bytes.
+fn encode_normalized(data: &mut Vec<u8>, values: &[f32]) {
+    values.iter().for_each(|value| data.push(((value + 1.0) / 2.0 * 255.0).round().max(0.0).min(255.0) as u8));
+}
+
+/// This function encodes a group of floats in the `0..1` range as bytes.
+fn encode_unorm(data: &mut Vec<u8>, values: &[f32]) {
+    values.iter().for_each(|value| data.push((value * 255.0).round().max(0.0).min(255.0) as u8));
 }
-*/
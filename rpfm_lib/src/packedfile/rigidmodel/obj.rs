@@ -0,0 +1,75 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to export RigidModels to Wavefront OBJ.
+
+OBJ cannot hold bone weights, so this is only meant for viewing or for static props. To edit geometry and import it back, use glTF.
+Each mesh with decoded vertices becomes an object called `lod_X_mesh_Y`, with its textures in a `.mtl` file next to the `.obj` one.
+!*/
+
+use std::fmt::Write as FmtWrite;
+use std::fs::write;
+use std::path::Path;
+
+use rpfm_error::Result;
+
+use super::*;
+
+/// Implementation of `RigidModel` for OBJ support.
+impl RigidModel {
+
+    /// This function exports this RigidModel to an OBJ file in the provided path, and its materials to a MTL file with the same name.
+    pub fn export_obj(&self, path: &Path) -> Result<()> {
+        let mtl_path = path.with_extension("mtl");
+        let mtl_name = mtl_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let mut obj = format!("# Exported by RPFM. Skeleton: {}\nmtllib {}\n", self.skeleton_id, mtl_name);
+        let mut mtl = String::from("# Exported by RPFM.\n");
+
+        // OBJ indices are global and start at 1.
+        let mut vertex_offset = 1;
+        for (lod_index, lod) in self.lods.iter().enumerate() {
+            for (mesh_index, mesh) in lod.meshes.iter().enumerate() {
+                let material = match &mesh.material {
+                    Material::Weighted(material) if !mesh.vertices.is_empty() => material,
+                    _ => continue,
+                };
+
+                let name = format!("lod_{}_mesh_{}", lod_index, mesh_index);
+                let _ = writeln!(obj, "\no {}\nusemtl {}", name, name);
+
+                // OBJ has the origin of the UVs at the bottom, so we need to flip them.
+                mesh.vertices.iter().for_each(|vertex| { let _ = writeln!(obj, "v {} {} {}", vertex.position[0], vertex.position[1], vertex.position[2]); });
+                mesh.vertices.iter().for_each(|vertex| { let _ = writeln!(obj, "vt {} {}", vertex.uv[0], 1.0 - vertex.uv[1]); });
+                mesh.vertices.iter().for_each(|vertex| { let _ = writeln!(obj, "vn {} {} {}", vertex.normal[0], vertex.normal[1], vertex.normal[2]); });
+                for face in mesh.indices.chunks(3).filter(|face| face.len() == 3) {
+                    let _ = write!(obj, "f");
+                    face.iter().for_each(|index| { let _ = write!(obj, " {0}/{0}/{0}", usize::from(*index) + vertex_offset); });
+                    let _ = writeln!(obj);
+                }
+                vertex_offset += mesh.vertices.len();
+
+                let _ = writeln!(mtl, "\nnewmtl {}", name);
+                for texture in &material.textures {
+                    match texture.texture_type {
+                        TextureType::Diffuse | TextureType::BaseColour => { let _ = writeln!(mtl, "map_Kd {}", texture.path); },
+                        TextureType::Normal => { let _ = writeln!(mtl, "norm {}", texture.path); },
+                        TextureType::Specular => { let _ = writeln!(mtl, "map_Ks {}", texture.path); },
+                        _ => { let _ = writeln!(mtl, "# {:?} {}", texture.texture_type, texture.path); },
+                    }
+                }
+            }
+        }
+
+        write(path, obj)?;
+        write(mtl_path, mtl)?;
+        Ok(())
+    }
+}
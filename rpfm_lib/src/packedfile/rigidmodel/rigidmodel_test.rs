@@ -0,0 +1,173 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `RigidModel` module, to make sure models survive a read/save roundtrip untouched.
+!*/
+
+use super::*;
+
+/// This function builds a model of the provided version with one mesh of each vertex format, and one with an unknown material.
+fn build_model(version: u32) -> RigidModel {
+    let mut lod = Lod::default();
+    lod.set_camera_distance(100.0);
+    lod.set_quality_level(if version >= 7 { 2 } else { 0 });
+
+    for (index, vertex_format) in [VertexFormat::Default, VertexFormat::Weighted, VertexFormat::Cinematic].iter().enumerate() {
+        let mut mesh = Mesh::new(if *vertex_format == VertexFormat::Default { 68 } else { 65 }, *vertex_format);
+        mesh.set_shader_name("default_dry".to_owned());
+        mesh.set_bounding_box_min([-1.0, 0.0, -1.0]);
+        mesh.set_bounding_box_max([1.0, 2.0, 1.0]);
+
+        if let Material::Weighted(material) = mesh.get_ref_mut_material() {
+            material.set_name(format!("mesh_{}", index));
+            material.set_texture_directory("variantmeshes/wh_variantmodels/hu1/emp/tex".to_owned());
+            material.get_ref_mut_attachment_points().push(AttachmentPoint { name: "be_prop_0".to_owned(), matrix: [1.0; 12], bone_id: 5 });
+            material.get_ref_mut_textures().push(Texture::new(TextureType::Diffuse, "variantmeshes/wh_variantmodels/hu1/emp/tex/emp_base_diffuse.dds"));
+            material.get_ref_mut_textures().push(Texture::new(TextureType::Normal, "variantmeshes/wh_variantmodels/hu1/emp/tex/emp_base_normal.dds"));
+            material.get_ref_mut_string_params().push((0, "test".to_owned()));
+            material.get_ref_mut_float_params().push((1, 0.5));
+            material.get_ref_mut_int_params().push((0, 1));
+            material.get_ref_mut_vector_params().push((0, [0.25, 0.5, 0.75, 1.0]));
+        }
+
+        for vertex_index in 0..3u8 {
+            let mut vertex = Vertex::default();
+            vertex.set_position([f32::from(vertex_index), 1.5, -2.25, 1.0]);
+            vertex.set_uv([0.5, 0.25]);
+            vertex.set_uv_2([0.125, 0.75]);
+            vertex.set_normal([0.0, 1.0, 0.0, 0.0]);
+            vertex.set_tangent([1.0, 0.0, 0.0, 0.0]);
+            vertex.set_bitangent([0.0, 0.0, 1.0, 0.0]);
+            vertex.set_colour([1.0, 1.0, 1.0, 1.0]);
+            vertex.set_bone_indices([vertex_index, 1, 2, 3]);
+            vertex.set_bone_weights([0.5, 0.5, 0.0, 0.0]);
+            mesh.get_ref_mut_vertices().push(vertex);
+        }
+
+        mesh.set_indices(vec![0, 1, 2]);
+        lod.get_ref_mut_meshes().push(mesh);
+    }
+
+    let mut mesh = Mesh::new(49, VertexFormat::Default);
+    mesh.set_material(Material::Raw(vec![1, 2, 3, 4, 5, 6, 7, 8], 4, 2));
+    mesh.set_indices(vec![0, 1, 0]);
+    lod.get_ref_mut_meshes().push(mesh);
+
+    let mut lod_2 = lod.clone();
+    lod_2.set_camera_distance(1000.0);
+    lod_2.set_lod_level(if version >= 7 { 1 } else { 0 });
+
+    let mut model = RigidModel::new_decal();
+    model.set_version(version);
+    model.set_skeleton_id("humanoid01".to_owned());
+    model.set_lods(vec![lod, lod_2]);
+    model
+}
+
+#[test]
+fn test_rigidmodel_roundtrip() {
+    for version in &[6, 7, 8] {
+        let data = build_model(*version).save().unwrap();
+        let model = RigidModel::read(&data).unwrap();
+        assert_eq!(model.save().unwrap(), data);
+
+        // All the meshes with a known material should have been fully decoded.
+        let meshes = model.get_ref_lods()[0].get_ref_meshes();
+        assert_eq!(meshes.len(), 4);
+        assert_eq!(meshes[1].get_ref_vertices().len(), 3);
+        assert_eq!(meshes[1].get_ref_vertices()[2].get_ref_position(), &[2.0, 1.5, -2.25, 1.0]);
+        assert_eq!(meshes[1].get_ref_indices(), &[0, 1, 2]);
+        assert_eq!(meshes[3].get_ref_material(), &Material::Raw(vec![1, 2, 3, 4, 5, 6, 7, 8], 4, 2));
+        match meshes[2].get_ref_material() {
+            Material::Weighted(material) => {
+                assert_eq!(material.get_ref_vertex_format(), &VertexFormat::Cinematic);
+                assert_eq!(material.get_ref_textures()[1].get_ref_texture_type(), &TextureType::Normal);
+            }
+            _ => panic!("Cinematic mesh not decoded."),
+        }
+    }
+
+    // Version 6 models should have the short LOD headers.
+    assert_eq!(build_model(7).save().unwrap().len() - build_model(6).save().unwrap().len(), 16);
+    assert!(RigidModel::read(b"RMV2").is_err());
+}
+
+#[test]
+fn test_rigidmodel_raw_material() {
+    let mut data = build_model(7).save().unwrap();
+
+    // Break the vertex format of the first mesh. It should be kept as raw data instead of failing.
+    let lod_start = data.decode_integer_u32(HEADER_LENGTH + 12).unwrap() as usize;
+    data[lod_start + MESH_HEADER_LENGTH] = 99;
+
+    let model = RigidModel::read(&data).unwrap();
+    assert!(matches!(model.get_ref_lods()[0].get_ref_meshes()[0].get_ref_material(), Material::Raw(_, _, 3)));
+    assert!(model.get_ref_lods()[0].get_ref_meshes()[0].get_ref_vertices().is_empty());
+    assert_eq!(model.save().unwrap(), data);
+}
+
+#[test]
+fn test_rigidmodel_texture_paths() {
+    let mut model = build_model(7);
+    assert_eq!(model.get_texture_paths().len(), 2);
+    assert_eq!(model.rename_texture_paths("VariantMeshes/wh_variantmodels/hu1/emp/tex/emp_base_diffuse.dds", "test.dds"), 6);
+    assert_eq!(model.get_texture_paths(), vec!["test.dds", "variantmeshes/wh_variantmodels/hu1/emp/tex/emp_base_normal.dds"]);
+}
+
+#[test]
+fn test_rigidmodel_gltf() {
+    let folder = tempfile::tempdir().unwrap();
+
+    // Importing recalculates the bounding boxes, so make sure they match the geometry before exporting.
+    let mut model = build_model(7);
+    for lod in model.get_ref_mut_lods() {
+        for mesh in lod.get_ref_mut_meshes().iter_mut().take(3) {
+            mesh.set_bounding_box_min([0.0, 1.5, -2.25]);
+            mesh.set_bounding_box_max([2.0, 1.5, -2.25]);
+        }
+    }
+
+    // Both, glTF and GLB files, should give us back the exact same model.
+    for file_name in &["model.gltf", "model.glb"] {
+        let path = folder.path().join(file_name);
+        model.export_gltf(&path).unwrap();
+
+        let mut imported = model.clone();
+        for lod in imported.get_ref_mut_lods() {
+            for mesh in lod.get_ref_mut_meshes() {
+                mesh.get_ref_mut_vertices().clear();
+                mesh.get_ref_mut_indices().clear();
+            }
+        }
+
+        // The mesh with the raw material is not exported, so it's not replaced.
+        assert_eq!(imported.import_gltf(&path).unwrap(), 6);
+        imported.get_ref_mut_lods().iter_mut().for_each(|lod| lod.get_ref_mut_meshes()[3].set_indices(vec![0, 1, 0]));
+        assert_eq!(imported.save().unwrap(), model.save().unwrap());
+    }
+
+    // Geometry for meshes that don't exist should fail.
+    let mut model_no_meshes = model.clone();
+    model_no_meshes.get_ref_mut_lods().pop();
+    assert!(model_no_meshes.import_gltf(&folder.path().join("model.glb")).is_err());
+
+    // Accessors with more elements than their buffer view can hold should fail, without trying to allocate them.
+    let path = folder.path().join("model_broken.gltf");
+    let mut gltf: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(folder.path().join("model.gltf")).unwrap()).unwrap();
+    gltf["accessors"][0]["count"] = serde_json::json!(1u64 << 60);
+    std::fs::write(&path, gltf.to_string()).unwrap();
+    assert!(model.clone().import_gltf(&path).is_err());
+
+    model.export_obj(&folder.path().join("model.obj")).unwrap();
+    let obj = std::fs::read_to_string(folder.path().join("model.obj")).unwrap();
+    assert_eq!(obj.matches("\no ").count(), 6);
+    assert!(std::fs::read_to_string(folder.path().join("model.mtl")).unwrap().contains("map_Kd variantmeshes/wh_variantmodels/hu1/emp/tex/emp_base_diffuse.dds"));
+}
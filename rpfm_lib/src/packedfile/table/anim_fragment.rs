@@ -95,6 +95,34 @@ impl AnimFragment {
         self.table.set_table_data(data)
     }
 
+    /// This function returns the names of all the skeletons this AnimFragment references, without duplicates.
+    ///
+    /// These are the non-empty values of all the fields with `skeleton` in their name, including the ones of its entries.
+    pub fn get_skeleton_names(&self) -> Vec<String> {
+        let mut skeleton_names = vec![];
+        Self::get_skeleton_names_from_table(&self.table, &mut skeleton_names);
+        skeleton_names
+    }
+
+    /// This function adds the skeletons referenced in the provided table (and its subtables) to the provided list.
+    fn get_skeleton_names_from_table(table: &Table, skeleton_names: &mut Vec<String>) {
+        let fields = table.get_ref_definition().get_ref_fields();
+        for row in table.get_ref_table_data() {
+            for (field, cell) in fields.iter().zip(row.iter()) {
+                match cell {
+                    DecodedData::StringU8(value) |
+                    DecodedData::StringU16(value) |
+                    DecodedData::OptionalStringU8(value) |
+                    DecodedData::OptionalStringU16(value) if field.get_name().to_lowercase().contains("skeleton") && !value.is_empty() && !skeleton_names.contains(value) => {
+                        skeleton_names.push(value.to_owned());
+                    }
+                    DecodedData::SequenceU16(table) | DecodedData::SequenceU32(table) => Self::get_skeleton_names_from_table(table, skeleton_names),
+                    _ => {}
+                }
+            }
+        }
+    }
+
     /// This function creates a new `AnimFragment` from a `Vec<u8>`.
     pub fn read(packed_file_data: &[u8], schema: &Schema, return_incomplete: bool) -> Result<Self> {
 
@@ -133,6 +161,11 @@ impl AnimFragment {
         to_string_pretty(&self).unwrap()
     }
 
+    /// This function creates an `AnimFragment` from its JSON representation. AnimFragments have no version in their data, so the one in the JSON only picks the definition to validate it with.
+    pub fn from_json(json: &str, schema: &Schema) -> Result<Self> {
+        Table::from_json(json, schema.get_ref_versioned_file_anim_fragment()?).map(From::from)
+    }
+
     /// This function takes a `AnimFragment` and encodes it to `Vec<u8>`.
     pub fn save(&self) -> Result<Vec<u8>> {
 
@@ -139,6 +139,11 @@ impl AnimTable {
         to_string_pretty(&self).unwrap()
     }
 
+    /// This function creates an `AnimTable` from its JSON representation. The version in the JSON is the one the file will be saved with.
+    pub fn from_json(json: &str, schema: &Schema) -> Result<Self> {
+        Table::from_json(json, schema.get_ref_versioned_file_animtable()?).map(From::from)
+    }
+
     /// This function takes a `AnimTable` and encodes it to `Vec<u8>`.
     pub fn save(&self) -> Result<Vec<u8>> {
 
@@ -0,0 +1,107 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `CEO` module, to make sure CEO files are decoded and encoded with the definitions of the schema.
+!*/
+
+use crate::common::encoder::Encoder;
+use crate::packedfile::PackedFileType;
+use crate::packedfile::table::DecodedData;
+use crate::packedfile::table::ceo::CEO;
+use crate::schema::{Definition, Field, FieldType, Schema, VersionedFile};
+
+/// This function builds a schema with a CEO definition for version 2.
+fn build_schema() -> Schema {
+    let mut definition = Definition::new(2);
+    let mut key = Field::default();
+    key.set_name("key");
+    key.set_field_type(FieldType::StringU16);
+
+    let mut value = Field::default();
+    value.set_name("value");
+    value.set_field_type(FieldType::F32);
+
+    let mut enabled = Field::default();
+    enabled.set_name("enabled");
+    enabled.set_field_type(FieldType::Boolean);
+    *definition.get_ref_mut_fields() = vec![key, value, enabled];
+
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::CEO(vec![definition]));
+    schema
+}
+
+/// This function builds a raw CEO of version 2, with two entries.
+fn build_ceo() -> Vec<u8> {
+    let mut data = vec![];
+    data.encode_integer_i32(2);
+    data.encode_integer_u32(2);
+
+    data.encode_packedfile_string_u16("3k_main_ceo_trait_brave");
+    data.encode_float_f32(1.5);
+    data.encode_bool(true);
+
+    data.encode_packedfile_string_u16("3k_main_ceo_ancillary_sword");
+    data.encode_float_f32(-2.0);
+    data.encode_bool(false);
+    data
+}
+
+#[test]
+fn test_ceo() {
+    let schema = build_schema();
+    let data = build_ceo();
+    assert_eq!(PackedFileType::get_packed_file_type(&["ceo_data".to_owned(), "characters.ceo".to_owned()]), PackedFileType::CEO);
+
+    let mut ceo = CEO::read(&data, &schema, false).unwrap();
+    assert_eq!(ceo.get_ref_definition().get_version(), 2);
+    assert_eq!(ceo.get_entry_count(), 2);
+    assert_eq!(ceo.get_ref_table_data()[1][0], DecodedData::StringU16("3k_main_ceo_ancillary_sword".to_owned()));
+    assert_eq!(ceo.get_ref_table_data()[0][1], DecodedData::F32(1.5));
+
+    // Unedited files must be saved exactly as they were.
+    assert_eq!(ceo.save().unwrap(), data);
+
+    // Edits must survive a save and a read.
+    let mut table_data = ceo.get_table_data();
+    table_data[1][2] = DecodedData::Boolean(true);
+    ceo.set_table_data(&table_data).unwrap();
+    assert_eq!(CEO::read(&ceo.save().unwrap(), &schema, false).unwrap(), ceo);
+
+    // Broken files, and files without a definition.
+    assert!(CEO::read(&data[..data.len() - 1], &schema, false).is_err());
+    let mut broken = data.clone();
+    broken[0] = 3;
+    assert!(CEO::read(&broken, &schema, false).is_err());
+    assert!(CEO::read(&data, &Schema::default(), false).is_err());
+}
+
+#[test]
+fn test_ceo_json() {
+    let mut schema = build_schema();
+    let ceo = CEO::read(&build_ceo(), &schema, false).unwrap();
+
+    // Exported files must be imported exactly as they were.
+    let json = ceo.to_json();
+    assert_eq!(CEO::from_json(&json, &schema).unwrap(), ceo);
+    assert!(CEO::from_json("{}", &schema).is_err());
+
+    // Imported cells must follow the definition of the schema.
+    let broken = json.replacen("\"F32\": 1.5", "\"StringU16\": \"1.5\"", 1);
+    assert_ne!(broken, json);
+    assert!(CEO::from_json(&broken, &schema).is_err());
+
+    // Definitions that don't match the one in the schema cannot be imported.
+    let mut definition = ceo.get_definition();
+    definition.get_ref_mut_fields()[1].set_name("values");
+    schema.add_versioned_file(&VersionedFile::CEO(vec![definition]));
+    assert!(CEO::from_json(&json, &schema).is_err());
+}
@@ -41,7 +41,8 @@ use crate::packfile::packedfile::PackedFile;
 use crate::schema::*;
 use crate::SETTINGS;
 use crate::SCHEMA;
-use super::{DecodedData, Table, DependencyData};
+use super::{DecodedData, Table, DependencyData, MergeReport, TSVImportMode};
+use super::expression::MassEdit;
 
 /// If this sequence is found, the DB Table has a GUID after it.
 const GUID_MARKER: &[u8] = &[253, 254, 252, 255];
@@ -384,6 +385,15 @@ impl DB {
                             DecodedData::I16(ref entry) => reference_data = format!("{}", entry),
                             DecodedData::I32(ref entry) => reference_data = format!("{}", entry),
                             DecodedData::I64(ref entry) => reference_data = format!("{}", entry),
+                            DecodedData::F64(ref entry) => reference_data = format!("{}", entry),
+                            DecodedData::I8(ref entry) => reference_data = format!("{}", entry),
+                            DecodedData::U8(ref entry) => reference_data = format!("{}", entry),
+                            DecodedData::U16(ref entry) => reference_data = format!("{}", entry),
+                            DecodedData::U32(ref entry) => reference_data = format!("{}", entry),
+                            DecodedData::OptionalI16(_) |
+                            DecodedData::OptionalI32(_) |
+                            DecodedData::OptionalI64(_) => reference_data = row[index].data_to_string(),
+                            DecodedData::ColourRGB(ref entry) |
                             DecodedData::StringU8(ref entry) |
                             DecodedData::StringU16(ref entry) |
                             DecodedData::OptionalStringU8(ref entry) |
@@ -401,6 +411,15 @@ impl DB {
                                 DecodedData::I16(ref entry) => lookup_data.push(format!("{}", entry)),
                                 DecodedData::I32(ref entry) => lookup_data.push(format!("{}", entry)),
                                 DecodedData::I64(ref entry) => lookup_data.push(format!("{}", entry)),
+                                DecodedData::F64(ref entry) => lookup_data.push(format!("{}", entry)),
+                                DecodedData::I8(ref entry) => lookup_data.push(format!("{}", entry)),
+                                DecodedData::U8(ref entry) => lookup_data.push(format!("{}", entry)),
+                                DecodedData::U16(ref entry) => lookup_data.push(format!("{}", entry)),
+                                DecodedData::U32(ref entry) => lookup_data.push(format!("{}", entry)),
+                                DecodedData::OptionalI16(_) |
+                                DecodedData::OptionalI32(_) |
+                                DecodedData::OptionalI64(_) => lookup_data.push(row[index].data_to_string()),
+                                DecodedData::ColourRGB(ref entry) |
                                 DecodedData::StringU8(ref entry) |
                                 DecodedData::StringU16(ref entry) |
                                 DecodedData::OptionalStringU8(ref entry) |
@@ -444,6 +463,15 @@ impl DB {
                         DecodedData::I16(ref entry) => reference_data = format!("{}", entry),
                         DecodedData::I32(ref entry) => reference_data = format!("{}", entry),
                         DecodedData::I64(ref entry) => reference_data = format!("{}", entry),
+                        DecodedData::F64(ref entry) => reference_data = format!("{}", entry),
+                        DecodedData::I8(ref entry) => reference_data = format!("{}", entry),
+                        DecodedData::U8(ref entry) => reference_data = format!("{}", entry),
+                        DecodedData::U16(ref entry) => reference_data = format!("{}", entry),
+                        DecodedData::U32(ref entry) => reference_data = format!("{}", entry),
+                        DecodedData::OptionalI16(_) |
+                        DecodedData::OptionalI32(_) |
+                        DecodedData::OptionalI64(_) => reference_data = row[index].data_to_string(),
+                        DecodedData::ColourRGB(ref entry) |
                         DecodedData::StringU8(ref entry) |
                         DecodedData::StringU16(ref entry) |
                         DecodedData::OptionalStringU8(ref entry) |
@@ -461,6 +489,15 @@ impl DB {
                             DecodedData::I16(ref entry) => lookup_data.push(format!("{}", entry)),
                             DecodedData::I32(ref entry) => lookup_data.push(format!("{}", entry)),
                             DecodedData::I64(ref entry) => lookup_data.push(format!("{}", entry)),
+                            DecodedData::F64(ref entry) => lookup_data.push(format!("{}", entry)),
+                            DecodedData::I8(ref entry) => lookup_data.push(format!("{}", entry)),
+                            DecodedData::U8(ref entry) => lookup_data.push(format!("{}", entry)),
+                            DecodedData::U16(ref entry) => lookup_data.push(format!("{}", entry)),
+                            DecodedData::U32(ref entry) => lookup_data.push(format!("{}", entry)),
+                            DecodedData::OptionalI16(_) |
+                            DecodedData::OptionalI32(_) |
+                            DecodedData::OptionalI64(_) => lookup_data.push(row[index].data_to_string()),
+                            DecodedData::ColourRGB(ref entry) |
                             DecodedData::StringU8(ref entry) |
                             DecodedData::StringU16(ref entry) |
                             DecodedData::OptionalStringU8(ref entry) |
@@ -510,6 +547,15 @@ impl DB {
                             DecodedData::I16(ref entry) => reference_data = format!("{}", entry),
                             DecodedData::I32(ref entry) => reference_data = format!("{}", entry),
                             DecodedData::I64(ref entry) => reference_data = format!("{}", entry),
+                            DecodedData::F64(ref entry) => reference_data = format!("{}", entry),
+                            DecodedData::I8(ref entry) => reference_data = format!("{}", entry),
+                            DecodedData::U8(ref entry) => reference_data = format!("{}", entry),
+                            DecodedData::U16(ref entry) => reference_data = format!("{}", entry),
+                            DecodedData::U32(ref entry) => reference_data = format!("{}", entry),
+                            DecodedData::OptionalI16(_) |
+                            DecodedData::OptionalI32(_) |
+                            DecodedData::OptionalI64(_) => reference_data = row[index].data_to_string(),
+                            DecodedData::ColourRGB(ref entry) |
                             DecodedData::StringU8(ref entry) |
                             DecodedData::StringU16(ref entry) |
                             DecodedData::OptionalStringU8(ref entry) |
@@ -527,6 +573,15 @@ impl DB {
                                 DecodedData::I16(ref entry) => lookup_data.push(format!("{}", entry)),
                                 DecodedData::I32(ref entry) => lookup_data.push(format!("{}", entry)),
                                 DecodedData::I64(ref entry) => lookup_data.push(format!("{}", entry)),
+                                DecodedData::F64(ref entry) => lookup_data.push(format!("{}", entry)),
+                                DecodedData::I8(ref entry) => lookup_data.push(format!("{}", entry)),
+                                DecodedData::U8(ref entry) => lookup_data.push(format!("{}", entry)),
+                                DecodedData::U16(ref entry) => lookup_data.push(format!("{}", entry)),
+                                DecodedData::U32(ref entry) => lookup_data.push(format!("{}", entry)),
+                                DecodedData::OptionalI16(_) |
+                                DecodedData::OptionalI32(_) |
+                                DecodedData::OptionalI64(_) => lookup_data.push(row[index].data_to_string()),
+                                DecodedData::ColourRGB(ref entry) |
                                 DecodedData::StringU8(ref entry) |
                                 DecodedData::StringU16(ref entry) |
                                 DecodedData::OptionalStringU8(ref entry) |
@@ -766,16 +821,29 @@ impl DB {
         edited_paths
     }
 
-    /// This function imports a TSV file into a decoded table.
+    /// This function imports a TSV file into this table, replacing its rows or merging the file into them, depending on the mode.
+    ///
+    /// Check `Table::merge_tsv` for the details of how rows are matched, updated, inserted and deleted when merging.
+    /// If anything in the file is wrong, the table is left untouched.
     pub fn import_tsv(
-        definition: &Definition,
+        &mut self,
         path: &PathBuf,
         name: &str,
-    ) -> Result<Self> {
-        let table = Table::import_tsv(definition, path, name)?;
-        let mut db = DB::from(table);
-        db.name = name.to_owned();
-        Ok(db)
+        mode: TSVImportMode,
+    ) -> Result<MergeReport> {
+        self.table.import_tsv(path, name, mode)
+    }
+
+    /// This function applies a mass-edit statement to the rows of this table, returning how many rows have changed.
+    ///
+    /// The dependency data is the one returned by `DB::get_dependency_data` for this table, and it's only used by the reference functions.
+    /// If the statement fails on any row, the table is left untouched.
+    pub fn mass_edit(
+        &mut self,
+        mass_edit: &MassEdit,
+        dependency_data: &BTreeMap<i32, DependencyData>,
+    ) -> Result<usize> {
+        self.table.mass_edit(mass_edit, dependency_data, &self.name)
     }
 
     /// This function exports the provided data to a TSV file.
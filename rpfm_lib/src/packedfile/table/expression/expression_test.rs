@@ -0,0 +1,171 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Expression` module, to make sure mass-edits are parsed, typed and applied as expected.
+!*/
+
+use crate::packedfile::table::db::DB;
+use crate::schema::{Definition, Field};
+
+use super::*;
+
+/// This function builds a `units_tables` DB Table with the provided rows of key, category, cost, upkeep and speed.
+fn build_db(rows: &[(&str, &str, i32, Option<i32>, f32)]) -> DB {
+    let mut definition = Definition::new(1);
+    *definition.get_ref_mut_fields() = [
+        ("key", FieldType::StringU8),
+        ("category", FieldType::StringU8),
+        ("cost", FieldType::I32),
+        ("upkeep", FieldType::OptionalI32),
+        ("speed", FieldType::F32),
+    ].iter().map(|(name, field_type)| {
+        let mut field = Field::default();
+        field.set_name(name);
+        field.set_field_type(field_type.clone());
+        field
+    }).collect();
+
+    let mut db = DB::new("units_tables", None, &definition);
+    db.set_table_data(&rows.iter().map(|(key, category, cost, upkeep, speed)| vec![
+        DecodedData::StringU8(key.to_string()),
+        DecodedData::StringU8(category.to_string()),
+        DecodedData::I32(*cost),
+        DecodedData::OptionalI32(*upkeep),
+        DecodedData::F32(*speed),
+    ]).collect::<Vec<Vec<DecodedData>>>()).unwrap();
+    db
+}
+
+/// This function builds the table used in most tests.
+fn build_test_db() -> DB {
+    build_db(&[
+        ("knights", "cavalry", 1000, Some(100), 0.5),
+        ("spearmen", "infantry", 300, None, 0.25),
+        ("lancers", "cavalry", 850, Some(80), 0.75),
+    ])
+}
+
+/// This function applies a mass-edit to the test table, returning the table and how many rows have changed.
+fn mass_edit(text: &str) -> Result<(DB, usize)> {
+    let mut db = build_test_db();
+    let edited = db.mass_edit(&text.parse::<MassEdit>()?, &BTreeMap::new())?;
+    Ok((db, edited))
+}
+
+/// This function returns the reason of the error of a mass-edit that fails while being applied to a row.
+fn row_error(text: &str) -> (usize, String) {
+    match mass_edit(text).map_err(|error| error.kind().clone()) {
+        Err(ErrorKind::ExpressionError(name, row, reason)) => {
+            assert_eq!(name, "units_tables");
+            (row, reason)
+        },
+        result => panic!("Expected an error in a row, found {:?}.", result.map(|(_, edited)| edited)),
+    }
+}
+
+#[test]
+fn test_parse() {
+    assert!("set cost = round(cost * 1.15) where category == \"cavalry\"".parse::<MassEdit>().is_ok());
+    assert!("SET cost = -cost, key = 'a\\'b' WHERE NOT (cost >= 2 && upkeep != null) or !true".parse::<MassEdit>().is_ok());
+
+    let errors = [
+        ("cost = 1", 1, "Expected 'set', found 'cost'."),
+        ("set cost = 1 where cost = 2", 25, "Use '==' to compare values. '=' is only for assignments."),
+        ("set cost = (1 + 2", 18, "Expected ')', found the end of the expression."),
+        ("set cost = 1 2", 14, "Expected the end of the expression, found '2'."),
+        ("set cost = rnd(cost)", 12, "Unknown function 'rnd'."),
+        ("set cost = round()", 12, "The function 'round' takes between 1 and 2 arguments, but it got 0."),
+        ("set cost = lookup(\"a\")", 12, "The function 'lookup' only accepts a column name."),
+        ("set key = \"open", 11, "This text is never closed."),
+        ("set cost = 1 $ 2", 14, "Unexpected character '$'."),
+        ("set where = 1", 5, "Expected a column name, found 'where'."),
+    ];
+
+    for (text, position, reason) in &errors {
+        match text.parse::<MassEdit>() {
+            Err(error) => assert_eq!(error.kind(), &ErrorKind::ExpressionSyntaxError(*position, reason.to_string()), "{}", text),
+            Ok(_) => panic!("'{}' must not be parsed.", text),
+        }
+    }
+}
+
+#[test]
+fn test_mass_edit() {
+    let (db, edited) = mass_edit("set cost = round(cost * 1.1), upkeep = upkeep + 10 where category == \"cavalry\"").unwrap();
+    assert_eq!(edited, 2);
+    assert_eq!(db.get_table_data(), build_db(&[
+        ("knights", "cavalry", 1100, Some(110), 0.5),
+        ("spearmen", "infantry", 300, None, 0.25),
+        ("lancers", "cavalry", 935, Some(90), 0.75),
+    ]).get_table_data());
+
+    // All the assignments use the values the row had before the edit.
+    let (db, edited) = mass_edit("set cost = cost * 2, upkeep = if(upkeep == null, cost / 100, upkeep), speed = speed + 0.1, key = upper(substr(key, 0, 1)) + replace(substr(key, 1), \"men\", \"people\")").unwrap();
+    assert_eq!(edited, 3);
+    assert_eq!(db.get_table_data(), build_db(&[
+        ("Knights", "cavalry", 2000, Some(100), 0.6),
+        ("Spearpeople", "infantry", 600, Some(3), 0.35),
+        ("Lancers", "cavalry", 1700, Some(80), 0.85),
+    ]).get_table_data());
+
+    // Rows that end up with the same values are not counted as edited.
+    assert_eq!(mass_edit("set cost = max(cost, 500), upkeep = null where not starts_with(key, \"kni\") and len(category) > 7").unwrap().1, 1);
+    assert_eq!(mass_edit("set cost = cost where true").unwrap().1, 0);
+    assert_eq!(mass_edit("set cost = 0 where contains(key, \"dragon\")").unwrap().1, 0);
+}
+
+#[test]
+fn test_mass_edit_errors() {
+
+    // Types are not converted on their own.
+    assert_eq!(row_error("set cost = cost / 3"), (1, "Cannot store the value in the column 'cost': 333.3333333333333 has decimals, and cannot be stored in a I32 column without using round, floor or ceil.".to_owned()));
+    assert_eq!(row_error("set key = key + cost"), (1, "Cannot apply '+' to a text and an integer.".to_owned()));
+    assert_eq!(row_error("set cost = 1 where category"), (1, "The condition must be a boolean, but it is a text.".to_owned()));
+    assert_eq!(row_error("set cost = 1 where upkeep > 90"), (2, "Cannot compare null with an integer.".to_owned()));
+    assert_eq!(row_error("set cost = 3000000000 where key == \"lancers\""), (3, "Cannot store the value in the column 'cost': 3000000000 is out of range for a I32 column.".to_owned()));
+    assert_eq!(row_error("set cost = cost / (upkeep - 100) where upkeep != null").0, 1);
+    assert_eq!(row_error("set cost = cost % 0"), (1, "Division by zero.".to_owned()));
+    assert_eq!(row_error("set cost = (-9223372036854775807 - 1) % -1"), (1, "The result is too big for an integer.".to_owned()));
+    assert_eq!(row_error("set cost = lookup(cost)"), (1, "The column 'cost' doesn't reference any other table.".to_owned()));
+
+    match mass_edit("set price = 1") {
+        Err(error) => assert_eq!(error.kind(), &ErrorKind::ExpressionUnknownColumn("price".to_owned())),
+        Ok(_) => panic!("Unknown columns must be an error."),
+    }
+
+    // A failed edit doesn't leave the table half-edited.
+    let mut db = build_test_db();
+    assert!(db.mass_edit(&"set cost = int(10000 / (cost - 850))".parse::<MassEdit>().unwrap(), &BTreeMap::new()).is_err());
+    assert_eq!(db.get_table_data(), build_test_db().get_table_data());
+}
+
+#[test]
+fn test_mass_edit_references() {
+    let mut dependency_data = DependencyData::default();
+    dependency_data.data.insert("cavalry".to_owned(), "Mounted".to_owned());
+    let dependency_data = vec![(1, dependency_data)].into_iter().collect::<BTreeMap<i32, DependencyData>>();
+
+    let mut db = build_test_db();
+    let edited = db.mass_edit(&"set key = key + \" (\" + lookup(category) + \")\" where has_reference(category)".parse::<MassEdit>().unwrap(), &dependency_data).unwrap();
+    assert_eq!(edited, 2);
+    assert_eq!(db.get_ref_table_data()[0][0], DecodedData::StringU8("knights (Mounted)".to_owned()));
+    assert_eq!(db.get_ref_table_data()[1][0], DecodedData::StringU8("spearmen".to_owned()));
+
+    // References in float columns use the same keys as the dependency data, with three decimals.
+    let mut dependency_data = DependencyData::default();
+    dependency_data.data.insert("0.500".to_owned(), "Slow".to_owned());
+    let dependency_data = vec![(4, dependency_data)].into_iter().collect::<BTreeMap<i32, DependencyData>>();
+
+    let mut db = build_test_db();
+    let edited = db.mass_edit(&"set key = lookup(speed) where has_reference(speed)".parse::<MassEdit>().unwrap(), &dependency_data).unwrap();
+    assert_eq!(edited, 1);
+    assert_eq!(db.get_ref_table_data()[0][0], DecodedData::StringU8("Slow".to_owned()));
+}
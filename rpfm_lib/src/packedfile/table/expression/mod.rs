@@ -0,0 +1,658 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to mass-edit the columns of tables with expressions.
+
+A mass-edit is a statement like `set cost = round(cost * 1.15), upkeep = null where category == "cavalry"`: one or more assignments,
+applied to all the rows that match the (optional) `where` condition. All the assignments of a row are calculated from the values
+the row had before the edit.
+
+Values are typed: booleans, integers, floats, texts and `null` (for empty optional numbers). Operations between values of the
+wrong types are errors instead of conversions, and so is storing a value in a column of a type that cannot hold it.
+The supported operations are:
+- Arithmetic: `+`, `-`, `*`, `/` (always returns a float) and `%`. `+` also joins texts.
+- Comparisons: `==`, `!=`, `<`, `<=`, `>`, `>=`.
+- Logic: `and`/`&&`, `or`/`||` and `not`/`!`.
+- Numeric functions: `round(x)`, `round(x, decimals)`, `floor(x)`, `ceil(x)`, `abs(x)`, `min(x, y, ...)`, `max(x, y, ...)`, `int(x)` and `float(x)`.
+- Text functions: `str(x)`, `len(x)`, `lower(x)`, `upper(x)`, `trim(x)`, `replace(x, from, to)`, `contains(x, y)`,
+  `starts_with(x, y)`, `ends_with(x, y)` and `substr(x, start, length)` (length is optional).
+- Other functions: `if(condition, then, else)`.
+- Reference functions: `lookup(column)` returns the lookup value of the row referenced by the column (or `null` if the reference
+  is not found), and `has_reference(column)` returns if the row referenced by the column exists.
+
+!*/
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use rpfm_error::{Error, ErrorKind, Result};
+
+use crate::schema::FieldType;
+
+use super::{DecodedData, DependencyData, Table};
+
+mod parser;
+
+#[cfg(test)]
+mod expression_test;
+
+//---------------------------------------------------------------------------//
+//                              Enums & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents a parsed mass-edit statement, ready to be applied to tables.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MassEdit {
+
+    /// Columns to edit, with the expression that calculates their new value.
+    assignments: Vec<(String, Expr)>,
+
+    /// Condition the rows must match to be edited. If there is none, all rows are edited.
+    condition: Option<Expr>,
+}
+
+/// This enum represents the values expressions work with.
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Null,
+}
+
+/// This enum represents the nodes of a parsed expression.
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Literal(Value),
+    Column(String),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinaryOperator, Box<Expr>, Box<Expr>),
+    Function(Function, Vec<Expr>),
+}
+
+/// This enum represents the operators between two values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BinaryOperator {
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+}
+
+/// This enum represents the functions available in expressions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Function {
+    Round,
+    Floor,
+    Ceil,
+    Abs,
+    Min,
+    Max,
+    Int,
+    Float,
+    Str,
+    Len,
+    Lower,
+    Upper,
+    Trim,
+    Replace,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Substr,
+    If,
+    Lookup,
+    HasReference,
+}
+
+/// This struct contains what we need to evaluate an expression over a row.
+struct Context<'a> {
+
+    /// Columns that can be used in expressions, with their position in the row.
+    columns: &'a BTreeMap<String, usize>,
+
+    /// The row, before any edit.
+    row: &'a [DecodedData],
+
+    /// Dependency data of the table, by column position.
+    dependency_data: &'a BTreeMap<i32, DependencyData>,
+}
+
+//---------------------------------------------------------------------------//
+//                           Implementation of MassEdit
+//---------------------------------------------------------------------------//
+
+/// Implementation of `MassEdit`.
+impl MassEdit {
+
+    /// This function checks that all the columns this statement uses are in the provided list.
+    fn check_columns(&self, columns: &BTreeMap<String, usize>) -> Result<()> {
+        let mut used = self.assignments.iter().map(|(column, _)| column.as_str()).collect::<Vec<&str>>();
+        for expression in self.assignments.iter().map(|(_, expression)| expression).chain(self.condition.iter()) {
+            expression.get_columns(&mut used);
+        }
+
+        match used.iter().find(|column| !columns.contains_key(**column)) {
+            Some(column) => Err(ErrorKind::ExpressionUnknownColumn(column.to_string()).into()),
+            None => Ok(()),
+        }
+    }
+
+    /// This function applies this statement to a row, returning the edited row if it matches the condition.
+    fn apply_to_row(&self, context: &Context, field_types: &[FieldType]) -> std::result::Result<Option<Vec<DecodedData>>, String> {
+        if let Some(ref condition) = self.condition {
+            match context.evaluate(condition)? {
+                Value::Boolean(true) => {},
+                Value::Boolean(false) => return Ok(None),
+                value => return Err(format!("The condition must be a boolean, but it is {}.", value.get_type_name())),
+            }
+        }
+
+        let mut row = context.row.to_vec();
+        for (column, expression) in &self.assignments {
+            let index = context.columns[column];
+            let value = context.evaluate(expression)?;
+            row[index] = value_to_cell(&value, &field_types[index]).map_err(|reason| format!("Cannot store the value in the column '{}': {}", column, reason))?;
+        }
+        Ok(Some(row))
+    }
+}
+
+/// Implementation of `FromStr` for `MassEdit`.
+impl FromStr for MassEdit {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        parser::parse(text)
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                           Implementation of Table
+//---------------------------------------------------------------------------//
+
+/// Mass-edit functions of `Table`.
+impl Table {
+
+    /// This function applies a mass-edit statement to the rows of this table, returning how many rows have changed.
+    ///
+    /// If the statement fails on any row, the table is left untouched.
+    pub(crate) fn mass_edit(&mut self, mass_edit: &MassEdit, dependency_data: &BTreeMap<i32, DependencyData>, name: &str) -> Result<usize> {
+
+        // Sequences cannot be used in expressions, so they're not available as columns.
+        let fields = self.definition.get_fields_processed();
+        let field_types = fields.iter().map(|field| field.get_field_type()).collect::<Vec<FieldType>>();
+        let columns = fields.iter().enumerate()
+            .filter(|(_, field)| !matches!(field.get_ref_field_type(), FieldType::SequenceU16(_) | FieldType::SequenceU32(_)))
+            .map(|(index, field)| (field.get_name().to_owned(), index))
+            .collect::<BTreeMap<String, usize>>();
+        mass_edit.check_columns(&columns)?;
+
+        let mut entries = Vec::with_capacity(self.entries.len());
+        let mut edited = 0;
+        for (index, row) in self.entries.iter().enumerate() {
+            let context = Context {
+                columns: &columns,
+                row,
+                dependency_data,
+            };
+
+            match mass_edit.apply_to_row(&context, &field_types) {
+                Ok(Some(new_row)) => {
+                    if new_row != *row {
+                        edited += 1;
+                    }
+                    entries.push(new_row);
+                }
+                Ok(None) => entries.push(row.to_vec()),
+                Err(reason) => return Err(ErrorKind::ExpressionError(name.to_owned(), index + 1, reason).into()),
+            }
+        }
+
+        self.entries = entries;
+        Ok(edited)
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                         Implementation of Context
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Context`.
+impl<'a> Context<'a> {
+
+    /// This function returns the value of a column of the row.
+    fn get_column(&self, column: &str) -> Value {
+        cell_to_value(&self.row[self.columns[column]])
+    }
+
+    /// This function evaluates an expression over the row.
+    fn evaluate(&self, expression: &Expr) -> std::result::Result<Value, String> {
+        match expression {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Column(column) => Ok(self.get_column(column)),
+            Expr::Negate(expression) => match self.evaluate(expression)? {
+                Value::Integer(value) => value.checked_neg().map(Value::Integer).ok_or_else(|| "The result is too big for an integer.".to_owned()),
+                Value::Float(value) => Ok(Value::Float(-value)),
+                value => Err(format!("Cannot make {} negative.", value.get_type_name())),
+            },
+            Expr::Not(expression) => self.evaluate(expression)?.as_boolean().map(|value| Value::Boolean(!value)),
+
+            // Logic operators only evaluate the right side if they need to.
+            Expr::Binary(BinaryOperator::And, left, right) => if self.evaluate(left)?.as_boolean()? { Ok(Value::Boolean(self.evaluate(right)?.as_boolean()?)) } else { Ok(Value::Boolean(false)) },
+            Expr::Binary(BinaryOperator::Or, left, right) => if self.evaluate(left)?.as_boolean()? { Ok(Value::Boolean(true)) } else { Ok(Value::Boolean(self.evaluate(right)?.as_boolean()?)) },
+            Expr::Binary(operator, left, right) => binary_operation(*operator, self.evaluate(left)?, self.evaluate(right)?),
+
+            // Same with the branches of ifs.
+            Expr::Function(Function::If, arguments) => if self.evaluate(&arguments[0])?.as_boolean()? { self.evaluate(&arguments[1]) } else { self.evaluate(&arguments[2]) },
+            Expr::Function(function @ Function::Lookup, arguments) |
+            Expr::Function(function @ Function::HasReference, arguments) => {
+                let column = match &arguments[0] {
+                    Expr::Column(column) => column,
+                    _ => return Err("Reference functions only accept a column name.".to_owned()),
+                };
+
+                let dependency_data = self.dependency_data.get(&(self.columns[column] as i32)).ok_or_else(|| format!("The column '{}' doesn't reference any other table.", column))?;
+                // The keys of the dependency data are built from the cells of the referenced table, so we need to format ours the same way.
+                let lookup = dependency_data.data.get(&self.row[self.columns[column]].data_to_string());
+                if *function == Function::Lookup { Ok(lookup.map_or(Value::Null, |lookup| Value::Text(lookup.to_owned()))) }
+                else { Ok(Value::Boolean(lookup.is_some())) }
+            }
+            Expr::Function(function, arguments) => {
+                let arguments = arguments.iter().map(|argument| self.evaluate(argument)).collect::<std::result::Result<Vec<Value>, String>>()?;
+                function.call(&arguments)
+            }
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                     Implementation of the other types
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Value`.
+impl Value {
+
+    /// This function returns the name of the type of this value, for error messages.
+    fn get_type_name(&self) -> &'static str {
+        match self {
+            Value::Boolean(_) => "a boolean",
+            Value::Integer(_) => "an integer",
+            Value::Float(_) => "a float",
+            Value::Text(_) => "a text",
+            Value::Null => "null",
+        }
+    }
+
+    /// This function returns the value as a boolean, or an error if it isn't one.
+    fn as_boolean(&self) -> std::result::Result<bool, String> {
+        match self {
+            Value::Boolean(value) => Ok(*value),
+            value => Err(format!("Expected a boolean, but found {}.", value.get_type_name())),
+        }
+    }
+
+    /// This function returns the value as a float, if it's a number.
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Integer(value) => Some(*value as f64),
+            Value::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// This function returns the value as a text, or an error if it isn't one.
+    fn as_text(&self) -> std::result::Result<&str, String> {
+        match self {
+            Value::Text(value) => Ok(value),
+            value => Err(format!("Expected a text, but found {}.", value.get_type_name())),
+        }
+    }
+
+    /// This function returns the value as an integer, or an error if it isn't one.
+    fn as_integer(&self) -> std::result::Result<i64, String> {
+        match self {
+            Value::Integer(value) => Ok(*value),
+            value => Err(format!("Expected an integer, but found {}.", value.get_type_name())),
+        }
+    }
+}
+
+/// Display implementation of `Value`. Texts are shown as they are, and `null` as an empty text, as they're shown in tables.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Boolean(value) => write!(f, "{}", value),
+            Value::Integer(value) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{}", value),
+            Value::Text(value) => write!(f, "{}", value),
+            Value::Null => Ok(()),
+        }
+    }
+}
+
+/// Display implementation of `BinaryOperator`, for error messages.
+impl fmt::Display for BinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            BinaryOperator::Or => "or",
+            BinaryOperator::And => "and",
+            BinaryOperator::Equal => "==",
+            BinaryOperator::NotEqual => "!=",
+            BinaryOperator::Less => "<",
+            BinaryOperator::LessOrEqual => "<=",
+            BinaryOperator::Greater => ">",
+            BinaryOperator::GreaterOrEqual => ">=",
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Remainder => "%",
+        })
+    }
+}
+
+/// Implementation of `Expr`.
+impl Expr {
+
+    /// This function adds the columns used in this expression to the provided list.
+    fn get_columns<'a>(&'a self, columns: &mut Vec<&'a str>) {
+        match self {
+            Expr::Literal(_) => {},
+            Expr::Column(column) => columns.push(column),
+            Expr::Negate(expression) |
+            Expr::Not(expression) => expression.get_columns(columns),
+            Expr::Binary(_, left, right) => {
+                left.get_columns(columns);
+                right.get_columns(columns);
+            }
+            Expr::Function(_, arguments) => arguments.iter().for_each(|argument| argument.get_columns(columns)),
+        }
+    }
+}
+
+/// Implementation of `Function`.
+impl Function {
+
+    /// This function returns the function with the provided name, if it exists. Names are case-insensitive.
+    fn from_name(name: &str) -> Option<Self> {
+        match &*name.to_lowercase() {
+            "round" => Some(Function::Round),
+            "floor" => Some(Function::Floor),
+            "ceil" => Some(Function::Ceil),
+            "abs" => Some(Function::Abs),
+            "min" => Some(Function::Min),
+            "max" => Some(Function::Max),
+            "int" => Some(Function::Int),
+            "float" => Some(Function::Float),
+            "str" => Some(Function::Str),
+            "len" => Some(Function::Len),
+            "lower" => Some(Function::Lower),
+            "upper" => Some(Function::Upper),
+            "trim" => Some(Function::Trim),
+            "replace" => Some(Function::Replace),
+            "contains" => Some(Function::Contains),
+            "starts_with" => Some(Function::StartsWith),
+            "ends_with" => Some(Function::EndsWith),
+            "substr" => Some(Function::Substr),
+            "if" => Some(Function::If),
+            "lookup" => Some(Function::Lookup),
+            "has_reference" => Some(Function::HasReference),
+            _ => None,
+        }
+    }
+
+    /// This function returns the minimum and maximum amount of arguments this function takes.
+    fn get_arity(self) -> (usize, usize) {
+        match self {
+            Function::Round => (1, 2),
+            Function::Min | Function::Max => (2, usize::MAX),
+            Function::Replace | Function::If => (3, 3),
+            Function::Substr => (2, 3),
+            Function::Contains | Function::StartsWith | Function::EndsWith => (2, 2),
+            Function::Floor |
+            Function::Ceil |
+            Function::Abs |
+            Function::Int |
+            Function::Float |
+            Function::Str |
+            Function::Len |
+            Function::Lower |
+            Function::Upper |
+            Function::Trim |
+            Function::Lookup |
+            Function::HasReference => (1, 1),
+        }
+    }
+
+    /// This function returns if this function works over a column, instead of over a value.
+    fn needs_column(self) -> bool {
+        self == Function::Lookup || self == Function::HasReference
+    }
+
+    /// This function calls this function with already evaluated arguments. Functions that need more than values are evaluated in `Context::evaluate`.
+    fn call(self, arguments: &[Value]) -> std::result::Result<Value, String> {
+        let number = |value: &Value| value.as_float().ok_or_else(|| format!("Expected a number, but found {}.", value.get_type_name()));
+        match self {
+            Function::Round | Function::Floor | Function::Ceil => match (&arguments[0], arguments.get(1)) {
+                (Value::Integer(value), None) => Ok(Value::Integer(*value)),
+                (Value::Float(value), None) => float_to_integer(match self {
+                    Function::Round => value.round(),
+                    Function::Floor => value.floor(),
+                    _ => value.ceil(),
+                }),
+                (value, Some(decimals)) if self == Function::Round => {
+                    let multiplier = 10_f64.powi(i32::try_from(decimals.as_integer()?).map_err(|_| "Too many decimals.".to_owned())?);
+                    Ok(Value::Float((number(value)? * multiplier).round() / multiplier))
+                }
+                (value, None) => Err(format!("Expected a number, but found {}.", value.get_type_name())),
+                _ => Err("Only round accepts the number of decimals.".to_owned()),
+            },
+            Function::Abs => match &arguments[0] {
+                Value::Integer(value) => value.checked_abs().map(Value::Integer).ok_or_else(|| "The result is too big for an integer.".to_owned()),
+                value => Ok(Value::Float(number(value)?.abs())),
+            },
+            Function::Min | Function::Max => {
+                let mut result = arguments[0].clone();
+                for argument in &arguments[1..] {
+                    let ordering = compare(argument, &result)?;
+                    if (self == Function::Min && ordering == Ordering::Less) || (self == Function::Max && ordering == Ordering::Greater) {
+                        result = argument.clone();
+                    }
+                }
+                Ok(result)
+            },
+            Function::Int => match &arguments[0] {
+                Value::Boolean(value) => Ok(Value::Integer(*value as i64)),
+                Value::Integer(value) => Ok(Value::Integer(*value)),
+                Value::Float(value) => float_to_integer(value.trunc()),
+                Value::Text(value) => value.trim().parse::<i64>().map(Value::Integer).map_err(|_| format!("\"{}\" is not a valid integer.", value)),
+                Value::Null => Err("Cannot convert null to an integer.".to_owned()),
+            },
+            Function::Float => match &arguments[0] {
+                Value::Text(value) => value.trim().parse::<f64>().map(Value::Float).map_err(|_| format!("\"{}\" is not a valid float.", value)),
+                value => Ok(Value::Float(number(value)?)),
+            },
+            Function::Str => Ok(Value::Text(arguments[0].to_string())),
+            Function::Len => Ok(Value::Integer(arguments[0].as_text()?.chars().count() as i64)),
+            Function::Lower => Ok(Value::Text(arguments[0].as_text()?.to_lowercase())),
+            Function::Upper => Ok(Value::Text(arguments[0].as_text()?.to_uppercase())),
+            Function::Trim => Ok(Value::Text(arguments[0].as_text()?.trim().to_owned())),
+            Function::Replace => Ok(Value::Text(arguments[0].as_text()?.replace(arguments[1].as_text()?, arguments[2].as_text()?))),
+            Function::Contains => Ok(Value::Boolean(arguments[0].as_text()?.contains(arguments[1].as_text()?))),
+            Function::StartsWith => Ok(Value::Boolean(arguments[0].as_text()?.starts_with(arguments[1].as_text()?))),
+            Function::EndsWith => Ok(Value::Boolean(arguments[0].as_text()?.ends_with(arguments[1].as_text()?))),
+            Function::Substr => {
+                let text = arguments[0].as_text()?;
+                let start = usize::try_from(arguments[1].as_integer()?).map_err(|_| "The start of a substring cannot be negative.".to_owned())?;
+                let length = match arguments.get(2) {
+                    Some(length) => usize::try_from(length.as_integer()?).map_err(|_| "The length of a substring cannot be negative.".to_owned())?,
+                    None => usize::MAX,
+                };
+                Ok(Value::Text(text.chars().skip(start).take(length).collect()))
+            },
+
+            // These ones are evaluated directly by the context.
+            Function::If | Function::Lookup | Function::HasReference => unreachable!(),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Utility functions
+//---------------------------------------------------------------------------//
+
+/// This function applies a (non-logic) binary operator to two values.
+fn binary_operation(operator: BinaryOperator, left: Value, right: Value) -> std::result::Result<Value, String> {
+    let too_big = || "The result is too big for an integer.".to_owned();
+    let invalid = || format!("Cannot apply '{}' to {} and {}.", operator, left.get_type_name(), right.get_type_name());
+    match operator {
+        BinaryOperator::Equal | BinaryOperator::NotEqual => {
+            let equal = match (&left, &right) {
+                (Value::Null, Value::Null) => true,
+                (Value::Null, _) | (_, Value::Null) => false,
+                _ => compare(&left, &right)? == Ordering::Equal,
+            };
+            Ok(Value::Boolean(equal == (operator == BinaryOperator::Equal)))
+        },
+        BinaryOperator::Less => Ok(Value::Boolean(compare(&left, &right)? == Ordering::Less)),
+        BinaryOperator::LessOrEqual => Ok(Value::Boolean(compare(&left, &right)? != Ordering::Greater)),
+        BinaryOperator::Greater => Ok(Value::Boolean(compare(&left, &right)? == Ordering::Greater)),
+        BinaryOperator::GreaterOrEqual => Ok(Value::Boolean(compare(&left, &right)? != Ordering::Less)),
+        BinaryOperator::Add => match (&left, &right) {
+            (Value::Integer(left), Value::Integer(right)) => left.checked_add(*right).map(Value::Integer).ok_or_else(too_big),
+            (Value::Text(left), Value::Text(right)) => Ok(Value::Text(format!("{}{}", left, right))),
+            _ => Ok(Value::Float(left.as_float().ok_or_else(invalid)? + right.as_float().ok_or_else(invalid)?)),
+        },
+        BinaryOperator::Subtract => match (&left, &right) {
+            (Value::Integer(left), Value::Integer(right)) => left.checked_sub(*right).map(Value::Integer).ok_or_else(too_big),
+            _ => Ok(Value::Float(left.as_float().ok_or_else(invalid)? - right.as_float().ok_or_else(invalid)?)),
+        },
+        BinaryOperator::Multiply => match (&left, &right) {
+            (Value::Integer(left), Value::Integer(right)) => left.checked_mul(*right).map(Value::Integer).ok_or_else(too_big),
+            _ => Ok(Value::Float(left.as_float().ok_or_else(invalid)? * right.as_float().ok_or_else(invalid)?)),
+        },
+        BinaryOperator::Divide => {
+            let (dividend, divisor) = (left.as_float().ok_or_else(invalid)?, right.as_float().ok_or_else(invalid)?);
+            if divisor == 0.0 { Err("Division by zero.".to_owned()) } else { Ok(Value::Float(dividend / divisor)) }
+        },
+        BinaryOperator::Remainder => match (&left, &right) {
+            (Value::Integer(_), Value::Integer(0)) => Err("Division by zero.".to_owned()),
+            (Value::Integer(left), Value::Integer(right)) => left.checked_rem(*right).map(Value::Integer).ok_or_else(too_big),
+            _ => {
+                let (dividend, divisor) = (left.as_float().ok_or_else(invalid)?, right.as_float().ok_or_else(invalid)?);
+                if divisor == 0.0 { Err("Division by zero.".to_owned()) } else { Ok(Value::Float(dividend % divisor)) }
+            }
+        },
+        BinaryOperator::And | BinaryOperator::Or => Ok(Value::Boolean(left.as_boolean()? && right.as_boolean()?)),
+    }
+}
+
+/// This function compares two values of compatible types: two numbers, two texts or two booleans.
+fn compare(left: &Value, right: &Value) -> std::result::Result<Ordering, String> {
+    match (left, right) {
+        (Value::Integer(left), Value::Integer(right)) => Ok(left.cmp(right)),
+        (Value::Text(left), Value::Text(right)) => Ok(left.cmp(right)),
+        (Value::Boolean(left), Value::Boolean(right)) => Ok(left.cmp(right)),
+        _ => match (left.as_float(), right.as_float()) {
+            (Some(left_float), Some(right_float)) => left_float.partial_cmp(&right_float).ok_or_else(|| "Cannot compare NaN values.".to_owned()),
+            _ => Err(format!("Cannot compare {} with {}.", left.get_type_name(), right.get_type_name())),
+        }
+    }
+}
+
+/// This function turns a float into an integer, if it fits in one.
+fn float_to_integer(value: f64) -> std::result::Result<Value, String> {
+    if value.is_finite() && value >= i64::MIN as f64 && value < i64::MAX as f64 { Ok(Value::Integer(value as i64)) }
+    else { Err(format!("{} is too big for an integer.", value)) }
+}
+
+/// This function turns a cell into a value. Sequences cannot be used in expressions, so they're never turned into values.
+fn cell_to_value(cell: &DecodedData) -> Value {
+    match cell {
+        DecodedData::Boolean(data) => Value::Boolean(*data),
+        DecodedData::F32(data) => Value::Float(DecodedData::f32_to_f64(*data)),
+        DecodedData::F64(data) => Value::Float(*data),
+        DecodedData::I8(data) => Value::Integer(*data as i64),
+        DecodedData::I16(data) => Value::Integer(*data as i64),
+        DecodedData::I32(data) => Value::Integer(*data as i64),
+        DecodedData::I64(data) => Value::Integer(*data),
+        DecodedData::U8(data) => Value::Integer(*data as i64),
+        DecodedData::U16(data) => Value::Integer(*data as i64),
+        DecodedData::U32(data) => Value::Integer(*data as i64),
+        DecodedData::OptionalI16(data) => data.map_or(Value::Null, |data| Value::Integer(data as i64)),
+        DecodedData::OptionalI32(data) => data.map_or(Value::Null, |data| Value::Integer(data as i64)),
+        DecodedData::OptionalI64(data) => data.map_or(Value::Null, Value::Integer),
+        DecodedData::ColourRGB(data) |
+        DecodedData::StringU8(data) |
+        DecodedData::StringU16(data) |
+        DecodedData::OptionalStringU8(data) |
+        DecodedData::OptionalStringU16(data) => Value::Text(data.to_owned()),
+        DecodedData::SequenceU16(_) |
+        DecodedData::SequenceU32(_) => Value::Null,
+    }
+}
+
+/// This function turns a value into a cell of the provided type, if the type can hold it.
+///
+/// Floats are only stored in integer columns if they have no decimals. Otherwise, they must be rounded first.
+fn value_to_cell(value: &Value, field_type: &FieldType) -> std::result::Result<DecodedData, String> {
+    let invalid = || format!("{} cannot be stored in a {} column.", value.get_type_name(), field_type);
+    let integer = || match value {
+        Value::Integer(value) => Ok(*value),
+        Value::Float(float) if float.fract() == 0.0 => match float_to_integer(*float) {
+            Ok(Value::Integer(value)) => Ok(value),
+            _ => Err(format!("{} is out of range for a {} column.", float, field_type)),
+        },
+        Value::Float(float) => Err(format!("{} has decimals, and cannot be stored in a {} column without using round, floor or ceil.", float, field_type)),
+        _ => Err(invalid()),
+    };
+    let out_of_range = |value: i64| format!("{} is out of range for a {} column.", value, field_type);
+
+    match field_type {
+        FieldType::Boolean => value.as_boolean().map(DecodedData::Boolean).map_err(|_| invalid()),
+        FieldType::F32 => value.as_float().map(|value| DecodedData::F32(value as f32)).ok_or_else(invalid),
+        FieldType::F64 => value.as_float().map(DecodedData::F64).ok_or_else(invalid),
+        FieldType::I8 => { let value = integer()?; i8::try_from(value).map(DecodedData::I8).map_err(|_| out_of_range(value)) },
+        FieldType::I16 => { let value = integer()?; i16::try_from(value).map(DecodedData::I16).map_err(|_| out_of_range(value)) },
+        FieldType::I32 => { let value = integer()?; i32::try_from(value).map(DecodedData::I32).map_err(|_| out_of_range(value)) },
+        FieldType::I64 => integer().map(DecodedData::I64),
+        FieldType::U8 => { let value = integer()?; u8::try_from(value).map(DecodedData::U8).map_err(|_| out_of_range(value)) },
+        FieldType::U16 => { let value = integer()?; u16::try_from(value).map(DecodedData::U16).map_err(|_| out_of_range(value)) },
+        FieldType::U32 => { let value = integer()?; u32::try_from(value).map(DecodedData::U32).map_err(|_| out_of_range(value)) },
+        FieldType::OptionalI16 => if let Value::Null = value { Ok(DecodedData::OptionalI16(None)) } else { let value = integer()?; i16::try_from(value).map(|value| DecodedData::OptionalI16(Some(value))).map_err(|_| out_of_range(value)) },
+        FieldType::OptionalI32 => if let Value::Null = value { Ok(DecodedData::OptionalI32(None)) } else { let value = integer()?; i32::try_from(value).map(|value| DecodedData::OptionalI32(Some(value))).map_err(|_| out_of_range(value)) },
+        FieldType::OptionalI64 => if let Value::Null = value { Ok(DecodedData::OptionalI64(None)) } else { integer().map(|value| DecodedData::OptionalI64(Some(value))) },
+        FieldType::OptionalStringU8 |
+        FieldType::OptionalStringU16 if *value == Value::Null => DecodedData::new_from_string("", field_type).map_err(|_| invalid()),
+        FieldType::ColourRGB |
+        FieldType::StringU8 |
+        FieldType::StringU16 |
+        FieldType::OptionalStringU8 |
+        FieldType::OptionalStringU16 => DecodedData::new_from_string(value.as_text().map_err(|_| invalid())?, field_type).map_err(|_| format!("\"{}\" is not a valid {}.", value, field_type)),
+        FieldType::SequenceU16(_) |
+        FieldType::SequenceU32(_) => Err(invalid()),
+    }
+}
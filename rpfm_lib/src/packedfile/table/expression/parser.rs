@@ -0,0 +1,398 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the tokenizer and parser of mass-edit expressions.
+
+The grammar, from lowest to highest precedence, is:
+
+```text
+statement   := "set" assignment ("," assignment)* ["where" or]
+assignment  := column "=" or
+or          := and (("or" | "||") and)*
+and         := not (("and" | "&&") not)*
+not         := ("not" | "!") not | comparison
+comparison  := additive [("==" | "!=" | "<" | "<=" | ">" | ">=") additive]
+additive    := term (("+" | "-") term)*
+term        := unary (("*" | "/" | "%") unary)*
+unary       := "-" unary | primary
+primary     := integer | float | text | "true" | "false" | "null" | column | function "(" [or ("," or)*] ")" | "(" or ")"
+```
+!*/
+
+use rpfm_error::{ErrorKind, Result};
+
+use super::{BinaryOperator, Expr, Function, MassEdit, Value};
+
+//---------------------------------------------------------------------------//
+//                              Enums & Structs
+//---------------------------------------------------------------------------//
+
+/// This enum represents the tokens of an expression.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Symbol(&'static str),
+    End,
+}
+
+/// This struct keeps the state of the parser: the tokens, with their position in the expression (starting at 1), and the current one.
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+}
+
+/// Symbols of the language. Longer ones go first, so `<=` is not read as `<` followed by `=`.
+const SYMBOLS: [&str; 18] = ["==", "!=", "<=", ">=", "&&", "||", "<", ">", "=", "!", "+", "-", "*", "/", "%", "(", ")", ","];
+
+//---------------------------------------------------------------------------//
+//                              Implementations
+//---------------------------------------------------------------------------//
+
+/// This function parses a full mass-edit statement.
+pub(super) fn parse(text: &str) -> Result<MassEdit> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        index: 0,
+    };
+
+    parser.expect_keyword("set")?;
+    let mut assignments = vec![];
+    loop {
+        let column = parser.column()?;
+        parser.expect_symbol("=")?;
+        assignments.push((column, parser.or()?));
+
+        if !parser.accept_symbol(",") {
+            break;
+        }
+    }
+
+    let condition = if parser.accept_keyword("where") { Some(parser.or()?) } else { None };
+    match parser.peek() {
+        Token::End => Ok(MassEdit { assignments, condition }),
+        token => Err(parser.error(&format!("Expected the end of the expression, found {}.", describe(token)))),
+    }
+}
+
+/// Implementation of `Parser`.
+impl Parser {
+
+    /// This function returns the current token, without consuming it.
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].1
+    }
+
+    /// This function consumes the current token, and returns it.
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.index].1.clone();
+        if token != Token::End {
+            self.index += 1;
+        }
+        token
+    }
+
+    /// This function returns a syntax error at the position of the current token.
+    fn error(&self, reason: &str) -> rpfm_error::Error {
+        ErrorKind::ExpressionSyntaxError(self.tokens[self.index].0, reason.to_owned()).into()
+    }
+
+    /// This function consumes the current token if it's the provided keyword. Keywords are case-insensitive.
+    fn accept_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Token::Identifier(identifier) if identifier.eq_ignore_ascii_case(keyword) => {
+                self.next();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// This function consumes the current token if it's the provided symbol.
+    fn accept_symbol(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Token::Symbol(current) if *current == symbol) {
+            self.next();
+            true
+        } else { false }
+    }
+
+    /// This function consumes the current token if it's the provided keyword, or returns an error if it isn't.
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.accept_keyword(keyword) { Ok(()) }
+        else { Err(self.error(&format!("Expected '{}', found {}.", keyword, describe(self.peek())))) }
+    }
+
+    /// This function consumes the current token if it's the provided symbol, or returns an error if it isn't.
+    fn expect_symbol(&mut self, symbol: &str) -> Result<()> {
+        if self.accept_symbol(symbol) { Ok(()) }
+        else { Err(self.error(&format!("Expected '{}', found {}.", symbol, describe(self.peek())))) }
+    }
+
+    /// This function consumes a column name.
+    fn column(&mut self) -> Result<String> {
+        if let Token::Identifier(identifier) = self.peek() {
+            if !is_keyword(identifier) {
+                let identifier = identifier.to_owned();
+                self.next();
+                return Ok(identifier);
+            }
+        }
+        Err(self.error(&format!("Expected a column name, found {}.", describe(self.peek()))))
+    }
+
+    /// This function parses a chain of `or` operations.
+    fn or(&mut self) -> Result<Expr> {
+        let mut expression = self.and()?;
+        while self.accept_keyword("or") || self.accept_symbol("||") {
+            expression = Expr::Binary(BinaryOperator::Or, Box::new(expression), Box::new(self.and()?));
+        }
+        Ok(expression)
+    }
+
+    /// This function parses a chain of `and` operations.
+    fn and(&mut self) -> Result<Expr> {
+        let mut expression = self.not()?;
+        while self.accept_keyword("and") || self.accept_symbol("&&") {
+            expression = Expr::Binary(BinaryOperator::And, Box::new(expression), Box::new(self.not()?));
+        }
+        Ok(expression)
+    }
+
+    /// This function parses a negation, or a comparison.
+    fn not(&mut self) -> Result<Expr> {
+        if self.accept_keyword("not") || self.accept_symbol("!") {
+            Ok(Expr::Not(Box::new(self.not()?)))
+        } else {
+            self.comparison()
+        }
+    }
+
+    /// This function parses a comparison between two values. Comparisons cannot be chained.
+    fn comparison(&mut self) -> Result<Expr> {
+        let expression = self.additive()?;
+        let operator = match self.peek() {
+            Token::Symbol("==") => BinaryOperator::Equal,
+            Token::Symbol("!=") => BinaryOperator::NotEqual,
+            Token::Symbol("<") => BinaryOperator::Less,
+            Token::Symbol("<=") => BinaryOperator::LessOrEqual,
+            Token::Symbol(">") => BinaryOperator::Greater,
+            Token::Symbol(">=") => BinaryOperator::GreaterOrEqual,
+            Token::Symbol("=") => return Err(self.error("Use '==' to compare values. '=' is only for assignments.")),
+            _ => return Ok(expression),
+        };
+        self.next();
+        Ok(Expr::Binary(operator, Box::new(expression), Box::new(self.additive()?)))
+    }
+
+    /// This function parses a chain of additions and subtractions.
+    fn additive(&mut self) -> Result<Expr> {
+        let mut expression = self.term()?;
+        loop {
+            let operator = match self.peek() {
+                Token::Symbol("+") => BinaryOperator::Add,
+                Token::Symbol("-") => BinaryOperator::Subtract,
+                _ => return Ok(expression),
+            };
+            self.next();
+            expression = Expr::Binary(operator, Box::new(expression), Box::new(self.term()?));
+        }
+    }
+
+    /// This function parses a chain of multiplications, divisions and remainders.
+    fn term(&mut self) -> Result<Expr> {
+        let mut expression = self.unary()?;
+        loop {
+            let operator = match self.peek() {
+                Token::Symbol("*") => BinaryOperator::Multiply,
+                Token::Symbol("/") => BinaryOperator::Divide,
+                Token::Symbol("%") => BinaryOperator::Remainder,
+                _ => return Ok(expression),
+            };
+            self.next();
+            expression = Expr::Binary(operator, Box::new(expression), Box::new(self.unary()?));
+        }
+    }
+
+    /// This function parses a negative number.
+    fn unary(&mut self) -> Result<Expr> {
+        if self.accept_symbol("-") {
+            Ok(Expr::Negate(Box::new(self.unary()?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    /// This function parses a single value: a literal, a column, a function call or an expression between parenthesis.
+    fn primary(&mut self) -> Result<Expr> {
+        let position = self.tokens[self.index].0;
+        match self.next() {
+            Token::Integer(value) => Ok(Expr::Literal(Value::Integer(value))),
+            Token::Float(value) => Ok(Expr::Literal(Value::Float(value))),
+            Token::Text(value) => Ok(Expr::Literal(Value::Text(value))),
+            Token::Symbol("(") => {
+                let expression = self.or()?;
+                self.expect_symbol(")")?;
+                Ok(expression)
+            }
+            Token::Identifier(identifier) => {
+                if identifier.eq_ignore_ascii_case("true") { Ok(Expr::Literal(Value::Boolean(true))) }
+                else if identifier.eq_ignore_ascii_case("false") { Ok(Expr::Literal(Value::Boolean(false))) }
+                else if identifier.eq_ignore_ascii_case("null") { Ok(Expr::Literal(Value::Null)) }
+                else if is_keyword(&identifier) {
+                    self.index -= 1;
+                    Err(self.error(&format!("Expected a value, found {}.", describe(self.peek()))))
+                }
+
+                // Function calls.
+                else if self.accept_symbol("(") {
+                    let function = Function::from_name(&identifier).ok_or_else(|| rpfm_error::Error::from(ErrorKind::ExpressionSyntaxError(position, format!("Unknown function '{}'.", identifier))))?;
+                    let mut arguments = vec![];
+                    if !self.accept_symbol(")") {
+                        loop {
+                            arguments.push(self.or()?);
+                            if !self.accept_symbol(",") {
+                                break;
+                            }
+                        }
+                        self.expect_symbol(")")?;
+                    }
+
+                    let (min, max) = function.get_arity();
+                    if arguments.len() < min || arguments.len() > max {
+                        let expected = if min == max { min.to_string() } else if max == usize::MAX { format!("at least {}", min) } else { format!("between {} and {}", min, max) };
+                        return Err(ErrorKind::ExpressionSyntaxError(position, format!("The function '{}' takes {} arguments, but it got {}.", identifier, expected, arguments.len())).into());
+                    }
+
+                    // Lookups need to know the column of the reference, so they only accept column names.
+                    if function.needs_column() {
+                        match arguments.first() {
+                            Some(Expr::Column(_)) => {},
+                            _ => return Err(ErrorKind::ExpressionSyntaxError(position, format!("The function '{}' only accepts a column name.", identifier)).into()),
+                        }
+                    }
+
+                    Ok(Expr::Function(function, arguments))
+                }
+                else { Ok(Expr::Column(identifier)) }
+            }
+            token => {
+                self.index -= if token == Token::End { 0 } else { 1 };
+                Err(self.error(&format!("Expected a value, found {}.", describe(&token))))
+            }
+        }
+    }
+}
+
+/// This function splits an expression into its tokens, with their position (starting at 1).
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>> {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut tokens = vec![];
+    let mut index = 0;
+    while index < chars.len() {
+        let character = chars[index];
+        let position = index + 1;
+        if character.is_whitespace() {
+            index += 1;
+        }
+
+        else if character.is_ascii_alphabetic() || character == '_' {
+            let start = index;
+            while index < chars.len() && (chars[index].is_ascii_alphanumeric() || chars[index] == '_') {
+                index += 1;
+            }
+            tokens.push((position, Token::Identifier(chars[start..index].iter().collect())));
+        }
+
+        else if character.is_ascii_digit() || (character == '.' && matches!(chars.get(index + 1), Some(next) if next.is_ascii_digit())) {
+            let start = index;
+            let mut is_float = false;
+            while index < chars.len() && chars[index].is_ascii_digit() { index += 1; }
+            if index < chars.len() && chars[index] == '.' {
+                is_float = true;
+                index += 1;
+                while index < chars.len() && chars[index].is_ascii_digit() { index += 1; }
+            }
+            if index < chars.len() && (chars[index] == 'e' || chars[index] == 'E') {
+                let mut exponent = index + 1;
+                if exponent < chars.len() && (chars[exponent] == '+' || chars[exponent] == '-') { exponent += 1; }
+                if exponent < chars.len() && chars[exponent].is_ascii_digit() {
+                    is_float = true;
+                    index = exponent;
+                    while index < chars.len() && chars[index].is_ascii_digit() { index += 1; }
+                }
+            }
+
+            let number = chars[start..index].iter().collect::<String>();
+            let token = if is_float { number.parse::<f64>().ok().map(Token::Float) } else { number.parse::<i64>().ok().map(Token::Integer) };
+            match token {
+                Some(token) => tokens.push((position, token)),
+                None => return Err(ErrorKind::ExpressionSyntaxError(position, format!("'{}' is not a valid number.", number)).into()),
+            }
+        }
+
+        else if character == '"' || character == '\'' {
+            let mut value = String::new();
+            index += 1;
+            loop {
+                match chars.get(index) {
+                    Some(next) if *next == character => break,
+                    Some('\\') => {
+                        index += 1;
+                        match chars.get(index) {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(escaped @ '\\') | Some(escaped @ '"') | Some(escaped @ '\'') => value.push(*escaped),
+                            _ => return Err(ErrorKind::ExpressionSyntaxError(index + 1, "Invalid escape sequence. Only \\n, \\t, \\\\, \\\" and \\' are supported.".to_owned()).into()),
+                        }
+                    }
+                    Some(next) => value.push(*next),
+                    None => return Err(ErrorKind::ExpressionSyntaxError(position, "This text is never closed.".to_owned()).into()),
+                }
+                index += 1;
+            }
+            index += 1;
+            tokens.push((position, Token::Text(value)));
+        }
+
+        else {
+            let rest = chars[index..].iter().take(2).collect::<String>();
+            match SYMBOLS.iter().find(|symbol| rest.starts_with(*symbol)) {
+                Some(symbol) => {
+                    index += symbol.len();
+                    tokens.push((position, Token::Symbol(symbol)));
+                }
+                None => return Err(ErrorKind::ExpressionSyntaxError(position, format!("Unexpected character '{}'.", character)).into()),
+            }
+        }
+    }
+
+    tokens.push((chars.len() + 1, Token::End));
+    Ok(tokens)
+}
+
+/// This function returns if the provided identifier is a reserved word, which cannot be used as column name.
+fn is_keyword(identifier: &str) -> bool {
+    ["set", "where", "and", "or", "not", "true", "false", "null"].iter().any(|keyword| identifier.eq_ignore_ascii_case(keyword))
+}
+
+/// This function returns how a token is shown in error messages.
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Identifier(identifier) => format!("'{}'", identifier),
+        Token::Integer(value) => format!("'{}'", value),
+        Token::Float(value) => format!("'{}'", value),
+        Token::Text(value) => format!("\"{}\"", value),
+        Token::Symbol(symbol) => format!("'{}'", symbol),
+        Token::End => "the end of the expression".to_owned(),
+    }
+}
@@ -0,0 +1,52 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to read and write the rows of CSV files.
+!*/
+
+use csv::{QuoteStyle, ReaderBuilder, Terminator, WriterBuilder};
+
+use std::path::Path;
+
+use rpfm_error::Result;
+
+/// This function reads all the rows of a CSV file, as text.
+///
+/// Rows can have different lengths here. Checking them is up to the caller.
+pub(super) fn read(path: &Path) -> Result<Vec<Vec<String>>> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(path)?;
+
+    let mut records = vec![];
+    for record in reader.records() {
+        records.push(record?.iter().map(|cell| cell.to_owned()).collect());
+    }
+
+    Ok(records)
+}
+
+/// This function writes the provided rows to a CSV file, quoting only the cells that need it.
+pub(super) fn write(path: &Path, records: &[Vec<String>]) -> Result<()> {
+    let mut writer = WriterBuilder::new()
+        .flexible(true)
+        .quote_style(QuoteStyle::Necessary)
+        .terminator(Terminator::CRLF)
+        .from_path(path)?;
+
+    for record in records {
+        writer.write_record(record)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,261 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Formats` module, to make sure tables survive being exported and imported back in every format.
+!*/
+
+use zip::{ZipArchive, ZipWriter, write::FileOptions};
+
+use std::fs::{File, remove_file, write};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::schema::Field;
+
+use super::*;
+
+/// This function builds a definition from a list of field names and types.
+fn build_definition(version: i32, fields: &[(&str, FieldType)]) -> Definition {
+    let mut definition = Definition::new(version);
+    *definition.get_ref_mut_fields() = fields.iter().map(|(name, field_type)| {
+        let mut field = Field::default();
+        field.set_name(name);
+        field.set_field_type(field_type.clone());
+        field
+    }).collect();
+    definition
+}
+
+/// This function builds a table with one field of most types, and values that are hard to keep in text formats.
+fn build_table() -> Table {
+    let sequence_definition = build_definition(0, &[("name", FieldType::StringU8), ("value", FieldType::I32)]);
+    let definition = build_definition(3, &[
+        ("key", FieldType::StringU8),
+        ("enabled", FieldType::Boolean),
+        ("cost", FieldType::F32),
+        ("big", FieldType::I64),
+        ("optional", FieldType::OptionalI32),
+        ("colour", FieldType::ColourRGB),
+        ("description", FieldType::OptionalStringU16),
+        ("effects", FieldType::SequenceU32(sequence_definition.clone())),
+    ]);
+
+    let mut sequence = Table::new(&sequence_definition);
+    sequence.set_table_data(&[
+        vec![DecodedData::StringU8("a, \"b\"".to_owned()), DecodedData::I32(-4)],
+        vec![DecodedData::StringU8(String::new()), DecodedData::I32(7)],
+    ]).unwrap();
+
+    let mut table = Table::new(&definition);
+    table.set_table_data(&[
+        vec![
+            DecodedData::StringU8("unit_1".to_owned()),
+            DecodedData::Boolean(true),
+            DecodedData::F32(0.1),
+            DecodedData::I64(9_007_199_254_740_993),
+            DecodedData::OptionalI32(None),
+            DecodedData::ColourRGB("FF8000".to_owned()),
+            DecodedData::OptionalStringU16("Line one,\r\nline \"two\"\tand _x0041_ <three> & more".to_owned()),
+            DecodedData::SequenceU32(sequence),
+        ],
+        vec![
+            DecodedData::StringU8(" spaced ".to_owned()),
+            DecodedData::Boolean(false),
+            DecodedData::F32(-2.5),
+            DecodedData::I64(-1),
+            DecodedData::OptionalI32(Some(0)),
+            DecodedData::ColourRGB("000000".to_owned()),
+            DecodedData::OptionalStringU16(String::new()),
+            DecodedData::SequenceU32(Table::new(&sequence_definition)),
+        ],
+    ]).unwrap();
+    table
+}
+
+/// This function exports the test table in the provided format, and imports it back.
+fn round_trip(format: TableFormat) -> Table {
+    let table = build_table();
+    let path = std::env::temp_dir().join(format!("rpfm_test_formats_round_trip.{}", format.get_extension()));
+    table.export(&path, "test_tables", format).unwrap();
+    assert_eq!(Table::read_header(&path, format).unwrap(), ("test_tables".to_owned(), 3));
+
+    let imported = Table::import(table.get_ref_definition(), &path, "test_tables", format);
+    remove_file(&path).unwrap();
+    imported.unwrap()
+}
+
+/// This function imports the provided text as a file of the provided format into the test table.
+fn import_text(format: TableFormat, text: &str) -> Result<Table> {
+    let path = std::env::temp_dir().join(format!("rpfm_test_formats_import_text.{}", format.get_extension()));
+    write(&path, text).unwrap();
+    let imported = Table::import(build_table().get_ref_definition(), &path, "test_tables", format);
+    remove_file(&path).unwrap();
+    imported
+}
+
+/// This function replaces a text in the sheet of a XLSX file, like a spreadsheet program would do when editing it.
+fn edit_xlsx_sheet(path: &Path, from: &str, to: &str) {
+    let mut archive = ZipArchive::new(File::open(path).unwrap()).unwrap();
+    let mut files = vec![];
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).unwrap();
+        let mut data = String::new();
+        file.read_to_string(&mut data).unwrap();
+        files.push((file.name().to_owned(), data));
+    }
+
+    let mut zip = ZipWriter::new(File::create(path).unwrap());
+    for (name, data) in &files {
+        zip.start_file(&**name, FileOptions::default()).unwrap();
+        zip.write_all(if name == "xl/worksheets/sheet1.xml" { data.replacen(from, to, 1) } else { data.to_owned() }.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[test]
+fn test_table_format() {
+    assert_eq!(TableFormat::from_path(&PathBuf::from("a/b/c.XLSX")).unwrap(), TableFormat::XLSX);
+    assert_eq!(TableFormat::from_path(&PathBuf::from("c.json")).unwrap(), TableFormat::JSON);
+    assert!(TableFormat::from_path(&PathBuf::from("c.ods")).is_err());
+    assert!(TableFormat::from_path(&PathBuf::from("c")).is_err());
+    assert_eq!("csv".parse::<TableFormat>().unwrap(), TableFormat::CSV);
+}
+
+#[test]
+fn test_round_trip_json() {
+    assert_eq!(round_trip(TableFormat::JSON), build_table());
+}
+
+#[test]
+fn test_round_trip_json_non_finite_floats() {
+    let mut table = build_table();
+    let mut data = table.get_table_data();
+    data[0][2] = DecodedData::F32(f32::NEG_INFINITY);
+    data[1][2] = DecodedData::F32(f32::NAN);
+    table.set_table_data(&data).unwrap();
+
+    // NaN and infinite floats must be exported in a way the import accepts.
+    let path = std::env::temp_dir().join("rpfm_test_formats_non_finite_floats.json");
+    table.export(&path, "test_tables", TableFormat::JSON).unwrap();
+    let imported = Table::import(table.get_ref_definition(), &path, "test_tables", TableFormat::JSON);
+    remove_file(&path).unwrap();
+
+    let imported = imported.unwrap();
+    assert_eq!(imported.get_ref_table_data()[0][2], DecodedData::F32(f32::NEG_INFINITY));
+    match imported.get_ref_table_data()[1][2] {
+        DecodedData::F32(data) => assert!(data.is_nan()),
+        _ => panic!("The NaN float has not been imported as a F32."),
+    }
+}
+
+#[test]
+fn test_round_trip_csv() {
+    assert_eq!(round_trip(TableFormat::CSV), build_table());
+}
+
+#[test]
+fn test_round_trip_xlsx() {
+    assert_eq!(round_trip(TableFormat::XLSX), build_table());
+}
+
+#[test]
+fn test_read_header_tsv() {
+    let path = std::env::temp_dir().join("rpfm_test_formats_read_header.tsv");
+    Table::new(build_table().get_ref_definition()).export(&path, "test_tables", TableFormat::TSV).unwrap();
+    let header = Table::read_header(&path, TableFormat::TSV);
+    remove_file(&path).unwrap();
+    assert_eq!(header.unwrap(), ("test_tables".to_owned(), 3));
+}
+
+#[test]
+fn test_strict_import_json() {
+    let row = r##"{"key": "a", "enabled": true, "cost": 1, "big": 2, "optional": null, "colour": "00ff00", "description": "", "effects": []}"##;
+    let table = import_text(TableFormat::JSON, &format!(r#"{{"table_name": "test_tables", "version": 3, "rows": [{}]}}"#, row)).unwrap();
+    assert_eq!(table.get_ref_table_data()[0][5], DecodedData::ColourRGB("00FF00".to_owned()));
+
+    // Wrong types, unknown or missing columns, and values out of range are errors, not conversions.
+    let wrong_rows = [
+        row.replace("true", "\"true\""),
+        row.replace("\"cost\": 1", "\"cost\": \"1\""),
+        row.replace("\"optional\": null", "\"optional\": 3000000000"),
+        row.replace("\"colour\": \"00ff00\"", "\"colour\": \"green\""),
+        row.replace("\"big\": 2", "\"big\": 2, \"small\": 1"),
+        row.replace("\"big\": 2, ", ""),
+        row.replace("[]", r#"[{"name": "a"}]"#),
+    ];
+    for wrong_row in &wrong_rows {
+        assert!(import_text(TableFormat::JSON, &format!(r#"{{"table_name": "test_tables", "version": 3, "rows": [{}]}}"#, wrong_row)).is_err(), "{}", wrong_row);
+    }
+
+    assert!(import_text(TableFormat::JSON, &format!(r#"{{"table_name": "test_tables", "version": 2, "rows": [{}]}}"#, row)).is_err());
+    assert!(import_text(TableFormat::JSON, &format!(r#"{{"table_name": "other_tables", "version": 3, "rows": [{}]}}"#, row)).is_err());
+    assert!(import_text(TableFormat::JSON, &format!(r#"{{"table_name": "test_tables", "version": 3, "rows": [{}], "extra": 1}}"#, row)).is_err());
+}
+
+#[test]
+fn test_strict_import_csv() {
+    let header = "test_tables,3\r\nkey,enabled,cost,big,optional,colour,description,effects\r\n";
+    let table = import_text(TableFormat::CSV, &format!("{}a,1,1.5,2,,00ff00,,[]\r\n", header)).unwrap();
+    assert_eq!(table.get_ref_table_data()[0][1], DecodedData::Boolean(true));
+    assert_eq!(table.get_ref_table_data()[0][4], DecodedData::OptionalI32(None));
+
+    match import_text(TableFormat::CSV, &format!("{}a,1,1.5,2,,00ff00,,[]\r\nb,maybe,1.5,2,,00ff00,,[]\r\n", header)) {
+        Err(error) => assert_eq!(error.kind(), &ErrorKind::ImportTableIncorrectCell(4, "enabled".to_owned(), "\"maybe\" is not a valid Boolean.".to_owned())),
+        Ok(_) => panic!("Invalid booleans must not be imported."),
+    }
+
+    assert!(import_text(TableFormat::CSV, "test_tables,3\r\nkey,enabled\r\n").is_err());
+    assert!(import_text(TableFormat::CSV, &format!("{}a,1,1.5,2,,00ff00,,[],extra\r\n", header)).is_err());
+    assert!(import_text(TableFormat::CSV, &format!("{}a,1,1.5,2,,00ff00,,not json\r\n", header)).is_err());
+    assert!(import_text(TableFormat::CSV, &header.replace(",3", ",4")).is_err());
+}
+
+#[test]
+fn test_import_xlsx_exponent_numbers() {
+    let table = build_table();
+    let path = std::env::temp_dir().join("rpfm_test_formats_exponent_numbers.xlsx");
+
+    // Integral numbers in exponent form can be imported into integer columns.
+    table.export(&path, "test_tables", TableFormat::XLSX).unwrap();
+    edit_xlsx_sheet(&path, "<v>-1</v>", "<v>-1.2E3</v>");
+    let imported = Table::import(table.get_ref_definition(), &path, "test_tables", TableFormat::XLSX);
+    assert_eq!(imported.unwrap().get_ref_table_data()[1][3], DecodedData::I64(-1200));
+
+    // But numbers with decimals cannot.
+    table.export(&path, "test_tables", TableFormat::XLSX).unwrap();
+    edit_xlsx_sheet(&path, "<v>-1</v>", "<v>-1.5E0</v>");
+    let imported = Table::import(table.get_ref_definition(), &path, "test_tables", TableFormat::XLSX);
+    remove_file(&path).unwrap();
+    match imported {
+        Err(error) => assert_eq!(error.kind(), &ErrorKind::ImportTableIncorrectCell(4, "big".to_owned(), "\"-1.5E0\" is not a valid I64.".to_owned())),
+        Ok(_) => panic!("Numbers with decimals must not be imported into integer columns."),
+    }
+}
+
+#[test]
+fn test_import_xlsx_out_of_bounds_references() {
+    let table = build_table();
+    let path = std::env::temp_dir().join("rpfm_test_formats_out_of_bounds.xlsx");
+
+    // References beyond the limits of a sheet must fail before allocating their rows or cells.
+    for (from, to, message) in &[
+        ("<row r=\"2\">", "<row r=\"4000000000\">", "The row 4000000000 is beyond the last row of a sheet."),
+        ("r=\"A1\"", "r=\"ZZZZ1\"", "The cell 475254 is beyond the last column of a sheet."),
+    ] {
+        table.export(&path, "test_tables", TableFormat::XLSX).unwrap();
+        edit_xlsx_sheet(&path, from, to);
+        match Table::import(table.get_ref_definition(), &path, "test_tables", TableFormat::XLSX) {
+            Err(error) => assert_eq!(error.kind(), &ErrorKind::XLSXError(message.to_string())),
+            Ok(_) => panic!("Out of bounds references must be an error."),
+        }
+    }
+    remove_file(&path).unwrap();
+}
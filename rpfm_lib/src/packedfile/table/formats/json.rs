@@ -0,0 +1,80 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to import and export tables from/to JSON files.
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+use serde_json::Value;
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::schema::Definition;
+
+use super::{Table, table_from_json_rows, table_to_json_rows};
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents the contents of a table JSON file.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonTable {
+    table_name: String,
+    version: i32,
+    rows: Vec<Value>,
+}
+
+/// This struct is used to read only the name and version of a table JSON file.
+#[derive(Deserialize)]
+struct JsonTableHeader {
+    table_name: String,
+    version: i32,
+}
+
+//---------------------------------------------------------------------------//
+//                              Functions
+//---------------------------------------------------------------------------//
+
+/// This function imports a JSON file into a decoded table.
+pub(super) fn import(definition: &Definition, path: &Path, name: &str) -> Result<Table> {
+    let file: JsonTable = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+    if file.table_name != name || file.version != definition.get_version() {
+        return Err(ErrorKind::ImportTableWrongTable(file.table_name, file.version).into());
+    }
+
+    table_from_json_rows(definition, &file.rows)
+}
+
+/// This function exports a table to a JSON file.
+pub(super) fn export(table: &Table, path: &Path, table_name: &str) -> Result<()> {
+    let file = JsonTable {
+        table_name: table_name.to_owned(),
+        version: table.definition.get_version(),
+        rows: table_to_json_rows(table),
+    };
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, &file)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// This function returns the name and version of the table stored in a JSON file.
+pub(super) fn read_header(path: &Path) -> Result<(String, i32)> {
+    let header: JsonTableHeader = serde_json::from_reader(BufReader::new(File::open(path)?)).map_err(|_| ErrorKind::ImportTableInvalidHeader)?;
+    Ok((header.table_name, header.version))
+}
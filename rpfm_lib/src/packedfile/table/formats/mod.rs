@@ -0,0 +1,517 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to import and export tables from/to formats other than TSV: JSON, CSV and XLSX.
+
+All of them keep the name and version of the table, so we know what definition to use when importing them:
+- JSON files are an object with the `table_name`, the `version` and the `rows` of the table. Each row is an object with its values by column name.
+  NaN and infinite floats, which JSON cannot represent, are stored as the strings `NaN`, `inf` and `-inf`.
+- CSV files follow RFC-4180, and have the name and version of the table in their first line, and the column names in the second one, like TSV files.
+- XLSX files have a single sheet, with the name and version of the table in its first row, and the column names in the second one.
+
+Imports are strict: the name, version and columns must match the definition, and each value must be valid for the type of its column.
+In CSV and XLSX files, the sequences are stored in their cells as JSON arrays of rows, like in JSON files.
+!*/
+
+use serde_json::{Number, Value};
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs::{File, read};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::str::FromStr;
+
+use rpfm_error::{Error, ErrorKind, Result};
+
+use crate::common::parse_str_as_bool;
+use crate::schema::{Definition, FieldType, Schema};
+
+use super::{DecodedData, Table, db::DB, loc, loc::Loc};
+
+mod csv;
+mod json;
+mod xlsx;
+
+#[cfg(test)]
+mod formats_test;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This enum represents the formats we can import/export tables from/to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableFormat {
+    TSV,
+    JSON,
+    CSV,
+    XLSX,
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of TableFormat
+//---------------------------------------------------------------------------//
+
+/// Implementation of `TableFormat`.
+impl TableFormat {
+
+    /// This function returns the format of the file in the provided path, based on its extension.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) => extension.parse().map_err(|_| ErrorKind::ImportTableUnsupportedFormat(path.to_string_lossy().to_string()).into()),
+            None => Err(ErrorKind::ImportTableUnsupportedFormat(path.to_string_lossy().to_string()).into()),
+        }
+    }
+
+    /// This function returns the extension (without the dot) of the files of this format.
+    pub fn get_extension(self) -> &'static str {
+        match self {
+            Self::TSV => "tsv",
+            Self::JSON => "json",
+            Self::CSV => "csv",
+            Self::XLSX => "xlsx",
+        }
+    }
+}
+
+/// Implementation of `FromStr` for `TableFormat`. It accepts the extensions of each format, in any case.
+impl FromStr for TableFormat {
+    type Err = Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match &*format.to_lowercase() {
+            "tsv" => Ok(Self::TSV),
+            "json" => Ok(Self::JSON),
+            "csv" => Ok(Self::CSV),
+            "xlsx" => Ok(Self::XLSX),
+            _ => Err(ErrorKind::ImportTableUnsupportedFormat(format.to_owned()).into()),
+        }
+    }
+}
+
+/// Display implementation of `TableFormat`.
+impl fmt::Display for TableFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.get_extension().to_uppercase(), f)
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                          Implementation of Table
+//---------------------------------------------------------------------------//
+
+/// Implementation of the format-agnostic import/export functions of `Table`.
+impl Table {
+
+    /// This function imports a file of the provided format into a decoded table.
+    pub(crate) fn import(
+        definition: &Definition,
+        path: &Path,
+        name: &str,
+        format: TableFormat,
+    ) -> Result<Self> {
+        match format {
+            TableFormat::TSV => Self::new_from_tsv(definition, &path.to_path_buf(), name),
+            TableFormat::JSON => json::import(definition, path, name),
+            TableFormat::CSV => Self::from_records(definition, name, &csv::read(path)?),
+            TableFormat::XLSX => Self::from_records(definition, name, &xlsx::read(path)?),
+        }
+    }
+
+    /// This function exports the table to a file of the provided format.
+    pub(crate) fn export(
+        &self,
+        path: &Path,
+        table_name: &str,
+        format: TableFormat,
+    ) -> Result<()> {
+        match format {
+            TableFormat::TSV => self.export_tsv(&path.to_path_buf(), table_name),
+            TableFormat::JSON => json::export(self, path, table_name),
+            TableFormat::CSV => csv::write(path, &self.to_records(table_name)),
+            TableFormat::XLSX => xlsx::write(path, self, table_name),
+        }
+    }
+
+    /// This function returns the name and version of the table stored in a file of the provided format.
+    pub(crate) fn read_header(path: &Path, format: TableFormat) -> Result<(String, i32)> {
+        match format {
+            TableFormat::TSV => {
+                let mut line = String::new();
+                BufReader::new(File::open(path)?).read_line(&mut line)?;
+                let header = line.trim_end_matches(&['\r', '\n'][..]).split('\t').collect::<Vec<&str>>();
+                if header.len() != 2 { return Err(ErrorKind::ImportTSVWrongTypeTable.into()) }
+                let version = header[1].parse::<i32>().map_err(|_| Error::from(ErrorKind::ImportTSVInvalidVersion))?;
+                Ok((header[0].to_owned(), version))
+            }
+            TableFormat::JSON => json::read_header(path),
+            TableFormat::CSV => Self::get_header_from_records(&csv::read(path)?),
+            TableFormat::XLSX => Self::get_header_from_records(&xlsx::read(path)?),
+        }
+    }
+
+    /// This function imports a file of any supported format into a new binary DB/Loc file.
+    ///
+    /// The format is taken from the extension of the source file. Files with unknown extensions are treated as TSV files.
+    pub(crate) fn import_to_binary_file(
+        schema: &Schema,
+        source_path: &Path,
+        destination_path: &Path,
+    ) -> Result<()> {
+        let format = TableFormat::from_path(source_path).unwrap_or(TableFormat::TSV);
+        let (table_type, table_version) = Self::read_header(source_path, format)?;
+        let data = if table_type == loc::TSV_NAME_LOC {
+            let definition = schema.get_ref_versioned_file_loc()?.get_version(table_version)?;
+            let table = Self::import(definition, source_path, &table_type, format)?;
+            let mut file = Loc::new(definition);
+            file.set_table_data(table.get_ref_table_data())?;
+            file.save()
+        }
+        else {
+            let definition = schema.get_ref_versioned_file_db(&table_type)?.get_version(table_version)?;
+            let table = Self::import(definition, source_path, &table_type, format)?;
+            let mut file = DB::new(&table_type, None, definition);
+            file.set_table_data(table.get_ref_table_data())?;
+            file.save()
+        }?;
+
+        std::fs::write(destination_path, &data)?;
+        Ok(())
+    }
+
+    /// This function exports a binary DB/Loc file to a file of the provided format.
+    ///
+    /// Like with TSV files, if the file is not a Loc, the name of its parent folder is used as table name.
+    pub(crate) fn export_from_binary_file(
+        schema: &Schema,
+        source_path: &Path,
+        destination_path: &Path,
+        format: TableFormat,
+    ) -> Result<()> {
+        let data = read(source_path)?;
+        if let Ok(file) = Loc::read(&data, schema, false) {
+            file.export(destination_path, loc::TSV_NAME_LOC, format)
+        }
+        else {
+            let table_type = source_path.parent().and_then(|path| path.file_name()).and_then(|name| name.to_str()).ok_or(ErrorKind::ImportTSVWrongTypeTable)?;
+            match DB::read(&data, table_type, schema, false) {
+                Ok(file) => file.export(destination_path, table_type, format),
+                Err(_) => Err(ErrorKind::ImportTSVWrongTypeTable.into()),
+            }
+        }
+    }
+
+    /// This function turns the table into the rows we write in CSV/XLSX files, with its name and version in the first one, and its column names in the second one.
+    fn to_records(&self, table_name: &str) -> Vec<Vec<String>> {
+        let mut records = Vec::with_capacity(self.entries.len() + 2);
+        records.push(vec![table_name.to_owned(), self.definition.get_version().to_string()]);
+        records.push(self.definition.get_fields_processed().iter().map(|field| field.get_name().to_owned()).collect());
+        records.extend(self.entries.iter().map(|row| row.iter().map(cell_to_string).collect()));
+        records
+    }
+
+    /// This function returns the name and version of the table from the first of the rows of a CSV/XLSX file.
+    fn get_header_from_records(records: &[Vec<String>]) -> Result<(String, i32)> {
+        match records.first() {
+            Some(header) if header.len() >= 2 && header[2..].iter().all(|cell| cell.is_empty()) => {
+                let name = header[0].trim_start_matches('\u{feff}').to_owned();
+                let version = header[1].parse::<i32>().map_err(|_| Error::from(ErrorKind::ImportTableInvalidHeader))?;
+                Ok((name, version))
+            }
+            _ => Err(ErrorKind::ImportTableInvalidHeader.into()),
+        }
+    }
+
+    /// This function creates a table from the rows of a CSV/XLSX file, checking they belong to the provided table and definition.
+    fn from_records(
+        definition: &Definition,
+        name: &str,
+        records: &[Vec<String>],
+    ) -> Result<Self> {
+        let (found_name, found_version) = Self::get_header_from_records(records)?;
+        if found_name != name || found_version != definition.get_version() {
+            return Err(ErrorKind::ImportTableWrongTable(found_name, found_version).into());
+        }
+
+        // Spreadsheets like to leave out trailing empty cells, so we ignore them when checking the columns.
+        let fields = definition.get_fields_processed();
+        let columns = fields.iter().map(|field| field.get_name().to_owned()).collect::<Vec<String>>();
+        let found_columns = records.get(1).map(|record| trim_empty_cells(record)).unwrap_or(&[]);
+        if found_columns != &*columns {
+            return Err(ErrorKind::ImportTableWrongColumns(columns.join(", "), found_columns.join(", ")).into());
+        }
+
+        let mut table = Self::new(definition);
+        for (row, record) in records.iter().enumerate().skip(2) {
+            if trim_empty_cells(record).len() > fields.len() {
+                return Err(ErrorKind::ImportTableIncorrectCell(row + 1, format!("{}", fields.len() + 1), "This row has more cells than columns has the table.".to_owned()).into());
+            }
+
+            let mut entry = Vec::with_capacity(fields.len());
+            for (column, field) in fields.iter().enumerate() {
+                let cell = record.get(column).map(|cell| &**cell).unwrap_or("");
+                entry.push(cell_from_string(cell, field.get_ref_field_type()).map_err(|reason| Error::from(ErrorKind::ImportTableIncorrectCell(row + 1, field.get_name().to_owned(), reason)))?);
+            }
+            table.entries.push(entry);
+        }
+
+        Ok(table)
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                        Implementation of DB and Loc
+//---------------------------------------------------------------------------//
+
+/// Implementation of the format-agnostic import/export functions of `DB`.
+impl DB {
+
+    /// This function imports a TSV, JSON, CSV or XLSX file into a decoded table.
+    pub fn import(
+        definition: &Definition,
+        path: &Path,
+        name: &str,
+        format: TableFormat,
+    ) -> Result<Self> {
+        let table = Table::import(definition, path, name, format)?;
+        let mut db = DB::new(name, None, definition);
+        db.set_table_data(table.get_ref_table_data())?;
+        Ok(db)
+    }
+
+    /// This function exports the provided data to a TSV, JSON, CSV or XLSX file.
+    pub fn export(
+        &self,
+        path: &Path,
+        table_name: &str,
+        format: TableFormat,
+    ) -> Result<()> {
+        let mut table = Table::new(self.get_ref_definition());
+        table.set_table_data(self.get_ref_table_data())?;
+        table.export(path, table_name, format)
+    }
+
+    /// This function imports TSV, JSON, CSV or XLSX files into binary files on disk, next to them and without their extension.
+    pub fn import_to_binary_file(
+        schema: &Schema,
+        source_paths: &[impl AsRef<Path>],
+    ) -> Result<()> {
+        for path in source_paths {
+            let path = path.as_ref();
+            Table::import_to_binary_file(schema, path, &path.with_extension(""))?;
+        }
+
+        Ok(())
+    }
+
+    /// This function exports binary files on disk to files of the provided format next to them.
+    pub fn export_from_binary_file(
+        schema: &Schema,
+        source_paths: &[impl AsRef<Path>],
+        format: TableFormat,
+    ) -> Result<()> {
+        for path in source_paths {
+            let path = path.as_ref();
+            Table::export_from_binary_file(schema, path, &path.with_extension(format.get_extension()), format)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Implementation of the format-agnostic import/export functions of `Loc`.
+impl Loc {
+
+    /// This function imports a TSV, JSON, CSV or XLSX file into a decoded table.
+    pub fn import(
+        definition: &Definition,
+        path: &Path,
+        name: &str,
+        format: TableFormat,
+    ) -> Result<Self> {
+        let table = Table::import(definition, path, name, format)?;
+        Ok(Loc::from(table))
+    }
+
+    /// This function exports the provided data to a TSV, JSON, CSV or XLSX file.
+    pub fn export(
+        &self,
+        path: &Path,
+        table_name: &str,
+        format: TableFormat,
+    ) -> Result<()> {
+        let mut table = Table::new(self.get_ref_definition());
+        table.set_table_data(self.get_ref_table_data())?;
+        table.export(path, table_name, format)
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                          Conversion functions
+//---------------------------------------------------------------------------//
+
+/// This function returns the provided row without its trailing empty cells.
+fn trim_empty_cells(record: &[String]) -> &[String] {
+    let len = record.iter().rposition(|cell| !cell.is_empty()).map_or(0, |position| position + 1);
+    &record[..len]
+}
+
+/// This function returns the text of a cell, as we write it in CSV/XLSX files.
+///
+/// Floats are written without rounding, so we don't lose precision, and sequences are written as JSON.
+fn cell_to_string(cell: &DecodedData) -> String {
+    match cell {
+        DecodedData::F32(data) => data.to_string(),
+        DecodedData::F64(data) => data.to_string(),
+        DecodedData::SequenceU16(table) | DecodedData::SequenceU32(table) => Value::Array(table_to_json_rows(table)).to_string(),
+        _ => cell.data_to_string(),
+    }
+}
+
+/// This function parses the text of a cell of a CSV/XLSX file as a value of the provided type.
+///
+/// If the text is not valid for that type, it returns the reason why.
+fn cell_from_string(text: &str, field_type: &FieldType) -> std::result::Result<DecodedData, String> {
+    match field_type {
+        FieldType::Boolean => parse_str_as_bool(text).map(DecodedData::Boolean).map_err(|_| format!("\"{}\" is not a valid Boolean.", text)),
+        FieldType::SequenceU16(definition) | FieldType::SequenceU32(definition) => {
+            let rows = match serde_json::from_str(text) {
+                Ok(Value::Array(rows)) => rows,
+                _ => return Err(format!("\"{}\" is not a JSON array of rows.", text)),
+            };
+
+            let table = table_from_json_rows(definition, &rows).map_err(|error| error.to_string())?;
+            if let FieldType::SequenceU16(_) = field_type { Ok(DecodedData::SequenceU16(table)) } else { Ok(DecodedData::SequenceU32(table)) }
+        }
+        _ => DecodedData::new_from_string(text, field_type).map_err(|_| format!("\"{}\" is not a valid {}.", text, field_type)),
+    }
+}
+
+/// This function turns the rows of a table into JSON objects, with the values of each row by column name.
+fn table_to_json_rows(table: &Table) -> Vec<Value> {
+    let fields = table.definition.get_fields_processed();
+    table.entries.iter().map(|row| {
+        Value::Object(fields.iter().zip(row.iter()).map(|(field, cell)| (field.get_name().to_owned(), cell_to_json(cell))).collect())
+    }).collect()
+}
+
+/// This function turns a cell into a JSON value.
+///
+/// Numbers are written as numbers, non-existant optional numbers as `null`, and sequences as arrays of rows.
+pub(crate) fn cell_to_json(cell: &DecodedData) -> Value {
+    match cell {
+        DecodedData::Boolean(data) => Value::Bool(*data),
+        DecodedData::F32(data) => float_to_json(DecodedData::f32_to_f64(*data)),
+        DecodedData::F64(data) => float_to_json(*data),
+        DecodedData::I8(data) => Value::from(*data),
+        DecodedData::I16(data) => Value::from(*data),
+        DecodedData::I32(data) => Value::from(*data),
+        DecodedData::I64(data) => Value::from(*data),
+        DecodedData::U8(data) => Value::from(*data),
+        DecodedData::U16(data) => Value::from(*data),
+        DecodedData::U32(data) => Value::from(*data),
+        DecodedData::OptionalI16(data) => data.map_or(Value::Null, Value::from),
+        DecodedData::OptionalI32(data) => data.map_or(Value::Null, Value::from),
+        DecodedData::OptionalI64(data) => data.map_or(Value::Null, Value::from),
+        DecodedData::ColourRGB(data) |
+        DecodedData::StringU8(data) |
+        DecodedData::StringU16(data) |
+        DecodedData::OptionalStringU8(data) |
+        DecodedData::OptionalStringU16(data) => Value::String(data.to_owned()),
+        DecodedData::SequenceU16(table) |
+        DecodedData::SequenceU32(table) => Value::Array(table_to_json_rows(table)),
+    }
+}
+
+/// This function turns a float into a JSON value.
+///
+/// JSON has no way to represent NaN or infinite floats, so those are written as the strings `NaN`, `inf` and `-inf`.
+fn float_to_json(data: f64) -> Value {
+    Number::from_f64(data).map_or_else(|| Value::String(data.to_string()), Value::Number)
+}
+
+/// This function parses a JSON value written by `float_to_json` as a float.
+fn float_from_json(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(text) => text.parse::<f64>().ok().filter(|data| !data.is_finite()),
+        _ => value.as_f64(),
+    }
+}
+
+/// This function creates a table with the provided definition from JSON rows, checking every row has exactly the columns of the definition with valid values.
+fn table_from_json_rows(definition: &Definition, rows: &[Value]) -> Result<Table> {
+    let fields = definition.get_fields_processed();
+    let mut table = Table::new(definition);
+    for (row, value) in rows.iter().enumerate() {
+        let object = match value {
+            Value::Object(object) => object,
+            _ => return Err(ErrorKind::ImportTableIncorrectCell(row + 1, "-".to_owned(), "This row is not a JSON object.".to_owned()).into()),
+        };
+
+        if let Some(column) = object.keys().find(|column| !fields.iter().any(|field| field.get_name() == *column)) {
+            return Err(ErrorKind::ImportTableIncorrectCell(row + 1, column.to_owned(), "This column doesn't exist in the table.".to_owned()).into());
+        }
+
+        let mut entry = Vec::with_capacity(fields.len());
+        for field in &fields {
+            let cell = match object.get(field.get_name()) {
+                Some(value) => cell_from_json(value, field.get_ref_field_type()),
+                None => Err("This column is missing.".to_owned()),
+            };
+
+            entry.push(cell.map_err(|reason| Error::from(ErrorKind::ImportTableIncorrectCell(row + 1, field.get_name().to_owned(), reason)))?);
+        }
+        table.entries.push(entry);
+    }
+
+    Ok(table)
+}
+
+/// This function parses a JSON value as a value of the provided type.
+///
+/// Unlike with CSV/XLSX files, there is no type conversion here: numbers must be numbers, booleans must be booleans, etc. If the value
+/// is not valid for that type, it returns the reason why.
+pub(crate) fn cell_from_json(value: &Value, field_type: &FieldType) -> std::result::Result<DecodedData, String> {
+    let invalid = || format!("{} is not a valid {}.", value, field_type);
+    let integer = || value.as_i64().ok_or_else(invalid);
+    match field_type {
+        FieldType::Boolean => value.as_bool().map(DecodedData::Boolean).ok_or_else(invalid),
+        FieldType::F32 => float_from_json(value).map(|data| DecodedData::F32(data as f32)).ok_or_else(invalid),
+        FieldType::F64 => float_from_json(value).map(DecodedData::F64).ok_or_else(invalid),
+        FieldType::I8 => i8::try_from(integer()?).map(DecodedData::I8).map_err(|_| invalid()),
+        FieldType::I16 => i16::try_from(integer()?).map(DecodedData::I16).map_err(|_| invalid()),
+        FieldType::I32 => i32::try_from(integer()?).map(DecodedData::I32).map_err(|_| invalid()),
+        FieldType::I64 => integer().map(DecodedData::I64),
+        FieldType::U8 => u8::try_from(integer()?).map(DecodedData::U8).map_err(|_| invalid()),
+        FieldType::U16 => u16::try_from(integer()?).map(DecodedData::U16).map_err(|_| invalid()),
+        FieldType::U32 => u32::try_from(integer()?).map(DecodedData::U32).map_err(|_| invalid()),
+        FieldType::OptionalI16 => if value.is_null() { Ok(DecodedData::OptionalI16(None)) } else { i16::try_from(integer()?).map(|data| DecodedData::OptionalI16(Some(data))).map_err(|_| invalid()) },
+        FieldType::OptionalI32 => if value.is_null() { Ok(DecodedData::OptionalI32(None)) } else { i32::try_from(integer()?).map(|data| DecodedData::OptionalI32(Some(data))).map_err(|_| invalid()) },
+        FieldType::OptionalI64 => if value.is_null() { Ok(DecodedData::OptionalI64(None)) } else { integer().map(|data| DecodedData::OptionalI64(Some(data))) },
+        FieldType::ColourRGB |
+        FieldType::StringU8 |
+        FieldType::StringU16 |
+        FieldType::OptionalStringU8 |
+        FieldType::OptionalStringU16 => match value.as_str() {
+            Some(text) => DecodedData::new_from_string(text, field_type).map_err(|_| invalid()),
+            None => Err(invalid()),
+        },
+        FieldType::SequenceU16(definition) |
+        FieldType::SequenceU32(definition) => match value.as_array() {
+            Some(rows) => {
+                let table = table_from_json_rows(definition, rows).map_err(|error| error.to_string())?;
+                if let FieldType::SequenceU16(_) = field_type { Ok(DecodedData::SequenceU16(table)) } else { Ok(DecodedData::SequenceU32(table)) }
+            }
+            None => Err(format!("{} is not a JSON array of rows.", value)),
+        },
+    }
+}
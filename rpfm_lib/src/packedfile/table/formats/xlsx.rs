@@ -0,0 +1,422 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to read and write the rows of XLSX files.
+
+XLSX files are ZIP files with a bunch of XML files inside. We only write the minimum a spreadsheet program needs to open them:
+one workbook with one sheet, with the strings inline. When reading them, we only read the first sheet of the workbook.
+!*/
+
+use xml::reader::{ParserConfig, XmlEvent};
+use zip::{CompressionMethod, ZipArchive, ZipWriter, write::FileOptions};
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use rpfm_error::{ErrorKind, Result};
+
+use super::{DecodedData, Table, cell_to_string};
+
+/// Max length of the name of a sheet.
+const MAX_SHEET_NAME_LENGTH: usize = 31;
+
+/// Max amount of rows of a sheet.
+const MAX_ROWS: usize = 1_048_576;
+
+/// Max amount of columns of a sheet.
+const MAX_COLUMNS: usize = 16_384;
+
+/// Biggest integer a spreadsheet can store as a number without losing precision.
+const MAX_SAFE_INTEGER: i64 = 1 << 53;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#;
+
+const ROOT_RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK_RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This enum represents the value of a cell of a sheet, as we write it.
+enum Cell {
+    Empty,
+    Boolean(bool),
+    Number(String),
+    Text(String),
+}
+
+/// This struct represents an element of a parsed XML file.
+#[derive(Default)]
+struct Element {
+
+    /// Name of the element, without namespace.
+    name: String,
+
+    /// Attributes of the element, without namespace, with their values.
+    attributes: Vec<(String, String)>,
+
+    /// Elements inside this one.
+    children: Vec<Element>,
+
+    /// Text inside this element.
+    text: String,
+}
+
+//---------------------------------------------------------------------------//
+//                          Implementation of Element
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Element`.
+impl Element {
+
+    /// This function returns the value of the provided attribute, if it exists.
+    fn get_attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(attribute, _)| attribute == name).map(|(_, value)| &**value)
+    }
+
+    /// This function returns an iterator over the children with the provided name.
+    fn get_children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// This function returns the text of all the `t` elements inside this one, ignoring the phonetic ones.
+    fn get_text(&self) -> String {
+        let mut text = String::new();
+        for child in &self.children {
+            match &*child.name {
+                "t" => text.push_str(&unescape(&child.text)),
+                "r" => text.push_str(&child.get_text()),
+                _ => {}
+            }
+        }
+        text
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Functions
+//---------------------------------------------------------------------------//
+
+/// This function reads all the rows of the first sheet of a XLSX file, as text.
+///
+/// Booleans are returned as `true` or `false`, and all rows are padded with empty cells to the length of the longest one.
+pub(super) fn read(path: &Path) -> Result<Vec<Vec<String>>> {
+    let files = read_zip(path)?;
+    let get_file = |path: &str| files.iter().find(|(name, _)| name == path).map(|(_, data)| &**data);
+    let get_xml = |path: &str| -> Result<Element> {
+        let data = get_file(path).ok_or_else(|| ErrorKind::XLSXError(format!("The file \"{}\" is missing.", path)))?;
+        parse_xml(&String::from_utf8_lossy(data))
+    };
+
+    // Find the workbook and its first sheet.
+    let workbook_path = get_relationship_targets(&get_xml("_rels/.rels")?, "", "/officeDocument").next().unwrap_or_else(|| "xl/workbook.xml".to_owned());
+    let (workbook_folder, workbook_name) = match workbook_path.rfind('/') {
+        Some(position) => (&workbook_path[..position], &workbook_path[position + 1..]),
+        None => ("", &*workbook_path),
+    };
+
+    let workbook = get_xml(&workbook_path)?;
+    let sheet_id = workbook.get_children("sheets").flat_map(|sheets| sheets.get_children("sheet")).next()
+        .and_then(|sheet| sheet.get_attribute("id"))
+        .ok_or_else(|| ErrorKind::XLSXError("The workbook has no sheets.".to_owned()))?;
+
+    let relationships_path = if workbook_folder.is_empty() { format!("_rels/{}.rels", workbook_name) } else { format!("{}/_rels/{}.rels", workbook_folder, workbook_name) };
+    let relationships = get_xml(&relationships_path)?;
+    let sheet_path = relationships.get_children("Relationship")
+        .find(|relationship| relationship.get_attribute("Id") == Some(sheet_id))
+        .and_then(|relationship| relationship.get_attribute("Target"))
+        .map(|target| resolve_target(workbook_folder, target))
+        .ok_or_else(|| ErrorKind::XLSXError("The first sheet of the workbook is missing.".to_owned()))?;
+
+    let shared_strings = match get_relationship_targets(&relationships, workbook_folder, "/sharedStrings").next() {
+        Some(path) => get_xml(&path)?.get_children("si").map(|string| string.get_text()).collect(),
+        None => vec![],
+    };
+
+    // Then read its rows, putting each cell in its place.
+    let sheet = get_xml(&sheet_path)?;
+    let mut records: Vec<Vec<String>> = vec![];
+    for row in sheet.get_children("sheetData").flat_map(|data| data.get_children("row")) {
+        let row_index = match row.get_attribute("r") {
+            Some(reference) => reference.parse::<usize>().ok().filter(|index| *index > 0).ok_or_else(|| ErrorKind::XLSXError(format!("Invalid row: {}.", reference)))? - 1,
+            None => records.len(),
+        };
+
+        if row_index < records.len() {
+            return Err(ErrorKind::XLSXError(format!("The row {} is duplicated or out of order.", row_index + 1)).into());
+        }
+
+        // Check the references before using them to allocate rows, so broken files don't make us allocate all the memory.
+        if row_index >= MAX_ROWS {
+            return Err(ErrorKind::XLSXError(format!("The row {} is beyond the last row of a sheet.", row_index + 1)).into());
+        }
+        records.resize(row_index + 1, vec![]);
+
+        let record = &mut records[row_index];
+        for cell in row.get_children("c") {
+            let column = match cell.get_attribute("r") {
+                Some(reference) => get_column_index(reference).ok_or_else(|| ErrorKind::XLSXError(format!("Invalid cell: {}.", reference)))?,
+                None => record.len(),
+            };
+
+            let value = cell.get_children("v").next().map(|value| value.text.to_owned()).unwrap_or_default();
+            let text = match cell.get_attribute("t") {
+                Some("s") => value.parse::<usize>().ok().and_then(|index| shared_strings.get(index)).cloned()
+                    .ok_or_else(|| ErrorKind::XLSXError(format!("Invalid shared string: {}.", value)))?,
+                Some("inlineStr") => cell.get_children("is").next().map(|string| string.get_text()).unwrap_or_default(),
+                Some("b") => (value == "1").to_string(),
+                Some("str") => unescape(&value),
+                _ => get_number_text(value),
+            };
+
+            if column < record.len() {
+                return Err(ErrorKind::XLSXError(format!("The cell {} is duplicated or out of order.", column + 1)).into());
+            }
+            if column >= MAX_COLUMNS {
+                return Err(ErrorKind::XLSXError(format!("The cell {} is beyond the last column of a sheet.", column + 1)).into());
+            }
+            record.resize(column, String::new());
+            record.push(text);
+        }
+    }
+
+    let width = records.iter().map(|record| record.len()).max().unwrap_or(0);
+    records.iter_mut().for_each(|record| record.resize(width, String::new()));
+    Ok(records)
+}
+
+/// This function writes a table to a XLSX file, with its name and version in the first row, and its column names in the second one.
+pub(super) fn write(path: &Path, table: &Table, table_name: &str) -> Result<()> {
+    let mut rows = Vec::with_capacity(table.entries.len() + 2);
+    rows.push(vec![Cell::Text(table_name.to_owned()), Cell::Number(table.definition.get_version().to_string())]);
+    rows.push(table.definition.get_fields_processed().iter().map(|field| Cell::Text(field.get_name().to_owned())).collect());
+    rows.extend(table.entries.iter().map(|row| row.iter().map(|cell| match cell {
+        DecodedData::Boolean(data) => Cell::Boolean(*data),
+        DecodedData::F32(data) if data.is_finite() => Cell::Number(cell_to_string(cell)),
+        DecodedData::F64(data) if data.is_finite() => Cell::Number(cell_to_string(cell)),
+        DecodedData::I8(_) |
+        DecodedData::I16(_) |
+        DecodedData::I32(_) |
+        DecodedData::U8(_) |
+        DecodedData::U16(_) |
+        DecodedData::U32(_) |
+        DecodedData::OptionalI16(Some(_)) |
+        DecodedData::OptionalI32(Some(_)) => Cell::Number(cell_to_string(cell)),
+
+        // Spreadsheets store numbers as doubles, so we keep as text the ones that don't fit in them.
+        DecodedData::I64(data) | DecodedData::OptionalI64(Some(data)) if data.abs() <= MAX_SAFE_INTEGER => Cell::Number(data.to_string()),
+        DecodedData::OptionalI16(None) |
+        DecodedData::OptionalI32(None) |
+        DecodedData::OptionalI64(None) => Cell::Empty,
+        _ => {
+            let text = cell_to_string(cell);
+            if text.is_empty() { Cell::Empty } else { Cell::Text(text) }
+        }
+    }).collect()));
+
+    let mut sheet = String::from(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#);
+    for (row_index, row) in rows.iter().enumerate() {
+        sheet.push_str(&format!("<row r=\"{}\">", row_index + 1));
+        for (column, cell) in row.iter().enumerate() {
+            let reference = format!("{}{}", get_column_name(column), row_index + 1);
+            match cell {
+                Cell::Empty => {},
+                Cell::Boolean(data) => sheet.push_str(&format!("<c r=\"{}\" t=\"b\"><v>{}</v></c>", reference, if *data { 1 } else { 0 })),
+                Cell::Number(data) => sheet.push_str(&format!("<c r=\"{}\"><v>{}</v></c>", reference, data)),
+                Cell::Text(data) => sheet.push_str(&format!("<c r=\"{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>", reference, escape(data))),
+            }
+        }
+        sheet.push_str("</row>");
+    }
+    sheet.push_str("</sheetData></worksheet>");
+
+    // Sheet names have a limited length, and some characters are not allowed in them.
+    let sheet_name = table_name.chars().filter(|character| !"[]:*?/\\".contains(*character)).take(MAX_SHEET_NAME_LENGTH).collect::<String>();
+    let workbook = format!(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#, escape(if sheet_name.is_empty() { "Sheet1" } else { &sheet_name }));
+
+    write_zip(path, &[
+        ("[Content_Types].xml", CONTENT_TYPES.as_bytes()),
+        ("_rels/.rels", ROOT_RELATIONSHIPS.as_bytes()),
+        ("xl/workbook.xml", workbook.as_bytes()),
+        ("xl/_rels/workbook.xml.rels", WORKBOOK_RELATIONSHIPS.as_bytes()),
+        ("xl/worksheets/sheet1.xml", sheet.as_bytes()),
+    ])
+}
+
+/// This function returns the paths of the targets of the relationships of the provided type.
+fn get_relationship_targets<'a>(relationships: &'a Element, folder: &'a str, relationship_type: &'a str) -> impl Iterator<Item = String> + 'a {
+    relationships.get_children("Relationship")
+        .filter(move |relationship| matches!(relationship.get_attribute("Type"), Some(value) if value.ends_with(relationship_type)))
+        .filter_map(move |relationship| relationship.get_attribute("Target").map(|target| resolve_target(folder, target)))
+}
+
+/// This function returns the path inside the ZIP file of the target of a relationship.
+fn resolve_target(folder: &str, target: &str) -> String {
+    if let Some(target) = target.strip_prefix('/') { target.to_owned() }
+    else if folder.is_empty() { target.to_owned() }
+    else { format!("{}/{}", folder, target) }
+}
+
+/// This function returns the name of a column from its index: A, B,... Z, AA, AB,...
+fn get_column_name(mut column: usize) -> String {
+    let mut name = vec![];
+    loop {
+        name.push((b'A' + (column % 26) as u8) as char);
+        if column < 26 { break; }
+        column = column / 26 - 1;
+    }
+    name.iter().rev().collect()
+}
+
+/// This function returns the index of the column of a cell from its reference (like `AB12`).
+fn get_column_index(reference: &str) -> Option<usize> {
+    let letters = reference.chars().take_while(|character| character.is_ascii_alphabetic()).collect::<String>();
+    if letters.is_empty() { return None }
+    letters.to_ascii_uppercase().bytes().try_fold(0usize, |index, letter| index.checked_mul(26)?.checked_add((letter - b'A') as usize + 1)).map(|index| index - 1)
+}
+
+/// This function returns the text of the value of a number cell.
+///
+/// Spreadsheets may write integers in exponent form, like `1.2E3`, so integral numbers are returned as plain integers,
+/// and can be imported into integer columns.
+fn get_number_text(value: String) -> String {
+    match value.parse::<f64>() {
+        Ok(number) if value.parse::<i64>().is_err() && number.fract() == 0.0 && (i64::MIN as f64..i64::MAX as f64).contains(&number) => (number as i64).to_string(),
+        _ => value,
+    }
+}
+
+/// This function escapes a string so it can be used as text or as the value of an attribute.
+///
+/// Characters XML cannot hold are written in the `_xHHHH_` form spreadsheets use for them, and carriage returns as references,
+/// so the parser doesn't turn them into line jumps.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (position, character) in text.char_indices() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' | '\n' => escaped.push(character),
+            '\u{0}'..='\u{1f}' => escaped.push_str(&format!("_x{:04X}_", character as u32)),
+
+            // Underscores that would be read as one of these escaped characters need to be escaped themselves.
+            '_' if get_escaped_character(&text[position..]).is_some() => escaped.push_str("_x005F_"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// This function turns back the characters escaped in the `_xHHHH_` form into what they were.
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut position = 0;
+    while position < text.len() {
+        match get_escaped_character(&text[position..]) {
+            Some(character) => {
+                unescaped.push(character);
+                position += 7;
+            }
+            None => {
+                let character = text[position..].chars().next().unwrap();
+                unescaped.push(character);
+                position += character.len_utf8();
+            }
+        }
+    }
+    unescaped
+}
+
+/// This function returns the character escaped at the start of the provided text, if it starts with one in the `_xHHHH_` form.
+fn get_escaped_character(text: &str) -> Option<char> {
+    let bytes = text.as_bytes();
+    if bytes.len() >= 7 && bytes.starts_with(b"_x") && bytes[6] == b'_' && bytes[2..6].iter().all(|byte| byte.is_ascii_hexdigit()) {
+        u32::from_str_radix(&text[2..6], 16).ok().and_then(std::char::from_u32)
+    } else { None }
+}
+
+/// This function parses a XML string into a tree of elements, returning the root one.
+fn parse_xml(xml: &str) -> Result<Element> {
+    let reader = ParserConfig::new()
+        .trim_whitespace(false)
+        .whitespace_to_characters(true)
+        .cdata_to_characters(true)
+        .ignore_comments(true)
+        .create_reader(xml.as_bytes());
+
+    let mut stack: Vec<Element> = vec![];
+    for event in reader {
+        match event.map_err(|error| ErrorKind::XLSXError(error.to_string()))? {
+            XmlEvent::StartElement { name, attributes, .. } => stack.push(Element {
+                name: name.local_name,
+                attributes: attributes.into_iter().map(|attribute| (attribute.name.local_name, attribute.value)).collect(),
+                ..Default::default()
+            }),
+
+            XmlEvent::EndElement { .. } => {
+                let element = stack.pop().ok_or_else(|| ErrorKind::XLSXError("Unexpected closing tag.".to_owned()))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+
+            XmlEvent::Characters(text) => if let Some(element) = stack.last_mut() {
+                element.text.push_str(&text);
+            }
+
+            _ => {}
+        }
+    }
+
+    Err(ErrorKind::XLSXError("The XML has no root element.".to_owned()).into())
+}
+
+/// This function reads the files inside a ZIP file, returning their paths and their uncompressed data.
+fn read_zip(path: &Path) -> Result<Vec<(String, Vec<u8>)>> {
+    let zip_error = |error: zip::result::ZipError| ErrorKind::XLSXError(format!("This file is not a valid XLSX file: {}.", error));
+    let mut archive = ZipArchive::new(BufReader::new(File::open(path)?)).map_err(zip_error)?;
+
+    let mut files = Vec::with_capacity(archive.len());
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(zip_error)?;
+        let mut data = vec![];
+        file.read_to_end(&mut data).map_err(|error| ErrorKind::XLSXError(format!("The file \"{}\" cannot be decompressed: {}.", file.name(), error)))?;
+        files.push((file.name().to_owned(), data));
+    }
+
+    Ok(files)
+}
+
+/// This function creates a ZIP file with the provided files, deflating them.
+fn write_zip(path: &Path, files: &[(&str, &[u8])]) -> Result<()> {
+    let zip_error = |error: zip::result::ZipError| ErrorKind::XLSXError(error.to_string());
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(path)?));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, data) in files {
+        zip.start_file(*name, options).map_err(zip_error)?;
+        zip.write_all(data)?;
+    }
+
+    zip.finish().map_err(zip_error)?.flush()?;
+    Ok(())
+}
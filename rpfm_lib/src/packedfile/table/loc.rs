@@ -21,7 +21,7 @@ use rpfm_error::{ErrorKind, Result};
 
 use crate::common::{decoder::Decoder, encoder::Encoder};
 use crate::packedfile::Dependencies;
-use super::DecodedData;
+use super::{DecodedData, MergeReport, TSVImportMode};
 use super::Table;
 
 use crate::SCHEMA;
@@ -248,14 +248,17 @@ impl Loc {
         None
     }
 
-    /// This function imports a TSV file into a decoded table.
+    /// This function imports a TSV file into this table, replacing its rows or merging the file into them, depending on the mode.
+    ///
+    /// Check `Table::merge_tsv` for the details of how rows are matched, updated, inserted and deleted when merging.
+    /// If anything in the file is wrong, the table is left untouched.
     pub fn import_tsv(
-        definition: &Definition,
+        &mut self,
         path: &PathBuf,
         name: &str,
-    ) -> Result<Self> {
-        let table = Table::import_tsv(definition, path, name)?;
-        Ok(Loc::from(table))
+        mode: TSVImportMode,
+    ) -> Result<MergeReport> {
+        self.table.import_tsv(path, name, mode)
     }
 
     /// This function exports the provided data to a TSV file.
@@ -140,6 +140,11 @@ impl MatchedCombat {
         to_string_pretty(&self).unwrap()
     }
 
+    /// This function creates a `MatchedCombat` from its JSON representation. It fails if the data doesn't follow the definition of the schema for its version.
+    pub fn from_json(json: &str, schema: &Schema) -> Result<Self> {
+        Table::from_json(json, schema.get_ref_versioned_file_matched_combat()?).map(From::from)
+    }
+
     /// This function takes a `MatchedCombat` and encodes it to `Vec<u8>`.
     pub fn save(&self) -> Result<Vec<u8>> {
 
@@ -18,8 +18,9 @@ use bincode::serialize;
 use csv::{QuoteStyle, ReaderBuilder, WriterBuilder};
 use serde_derive::{Serialize, Deserialize};
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::{fmt, fmt::Display};
+use std::ops::AddAssign;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::PathBuf;
@@ -32,10 +33,25 @@ use crate::schema::*;
 
 pub mod animtable;
 pub mod anim_fragment;
+pub mod ceo;
 pub mod db;
+pub mod expression;
+pub mod formats;
 pub mod loc;
 pub mod matched_combat;
 
+#[cfg(test)]
+mod ceo_test;
+
+#[cfg(test)]
+mod table_test;
+
+/// Name of the column of a TSV file with the action to do with each row when merging it into a table.
+pub const TSV_MERGE_ACTION_COLUMN: &str = "rpfm_merge_action";
+
+/// Action to delete the row with the same key from the table, when merging a TSV file into it.
+pub const TSV_MERGE_ACTION_DELETE: &str = "delete";
+
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
 //---------------------------------------------------------------------------//
@@ -57,21 +73,38 @@ pub struct Table {
 ///
 /// NOTE: `Sequence` it's a recursive type. A Sequence/List means you got a repeated sequence of fields
 /// inside a single field. Used, for example, in certain model tables.
+///
+/// NOTE: `ColourRGB` stores the colour as an hexadecimal `RRGGBB` String, and the optional integers use `None` for non-existant values.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum DecodedData {
     Boolean(bool),
     F32(f32),
+    F64(f64),
+    I8(i8),
     I16(i16),
     I32(i32),
     I64(i64),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    ColourRGB(String),
     StringU8(String),
     StringU16(String),
+    OptionalI16(Option<i16>),
+    OptionalI32(Option<i32>),
+    OptionalI64(Option<i64>),
     OptionalStringU8(String),
     OptionalStringU16(String),
     SequenceU16(Table),
     SequenceU32(Table)
 }
 
+/// This struct represents the JSON representation of a Table-like PackedFile, which is its `Table` under the `table` key.
+#[derive(Deserialize)]
+struct TableJson {
+    table: Table,
+}
+
 /// This holds the dependency data for a specific column of a table.
 #[derive(PartialEq, Clone, Default, Debug, Serialize, Deserialize)]
 pub struct DependencyData {
@@ -86,6 +119,57 @@ pub struct DependencyData {
     pub data: BTreeMap<String, String>,
 }
 
+/// This enum represents the ways a TSV file can be imported into a table.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum TSVImportMode {
+
+    /// The rows of the file replace all the rows of the table.
+    Replace,
+
+    /// The rows of the file are merged into the rows of the table, matching them by their keys.
+    Merge,
+}
+
+/// This holds what happened with each row of a TSV file after importing it into a table.
+///
+/// When the rows of the table are replaced, all the rows of the file are reported as inserted.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug, Serialize, Deserialize)]
+pub struct MergeReport {
+
+    /// Rows with keys not found in the table, which have been added to it.
+    pub inserted: usize,
+
+    /// Rows with keys found in the table, with at least one value different from the one in the table.
+    pub updated: usize,
+
+    /// Rows with keys found in the table and the same values, or marked for deletion with keys not found in the table.
+    pub unchanged: usize,
+
+    /// Rows marked for deletion with keys found in the table, which have been removed from it.
+    pub deleted: usize,
+}
+
+//----------------------------------------------------------------//
+// Implementations for `MergeReport`.
+//----------------------------------------------------------------//
+
+/// Display implementation of `MergeReport`.
+impl Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Inserted: {}, Updated: {}, Unchanged: {}, Deleted: {}.", self.inserted, self.updated, self.unchanged, self.deleted)
+    }
+}
+
+/// Implementation of `AddAssign` for `MergeReport`, to sum up the reports of merging multiple files.
+impl AddAssign for MergeReport {
+    fn add_assign(&mut self, other: Self) {
+        self.inserted += other.inserted;
+        self.updated += other.updated;
+        self.unchanged += other.unchanged;
+        self.deleted += other.deleted;
+    }
+}
+
 //----------------------------------------------------------------//
 // Implementations for `DecodedData`.
 //----------------------------------------------------------------//
@@ -96,11 +180,20 @@ impl Display for DecodedData {
         match self {
             DecodedData::Boolean(_) => write!(f, "Boolean"),
             DecodedData::F32(_) => write!(f, "F32"),
+            DecodedData::F64(_) => write!(f, "F64"),
+            DecodedData::I8(_) => write!(f, "I8"),
             DecodedData::I16(_) => write!(f, "I16"),
             DecodedData::I32(_) => write!(f, "I32"),
             DecodedData::I64(_) => write!(f, "I64"),
+            DecodedData::U8(_) => write!(f, "U8"),
+            DecodedData::U16(_) => write!(f, "U16"),
+            DecodedData::U32(_) => write!(f, "U32"),
+            DecodedData::ColourRGB(_) => write!(f, "ColourRGB"),
             DecodedData::StringU8(_) => write!(f, "StringU8"),
             DecodedData::StringU16(_) => write!(f, "StringU16"),
+            DecodedData::OptionalI16(_) => write!(f, "OptionalI16"),
+            DecodedData::OptionalI32(_) => write!(f, "OptionalI32"),
+            DecodedData::OptionalI64(_) => write!(f, "OptionalI64"),
             DecodedData::OptionalStringU8(_) => write!(f, "OptionalStringU8"),
             DecodedData::OptionalStringU16(_) => write!(f, "OptionalStringU16"),
             DecodedData::SequenceU16(_) => write!(f, "SequenceU16"),
@@ -115,11 +208,20 @@ impl PartialEq for DecodedData {
         match (self, other) {
             (DecodedData::Boolean(x), DecodedData::Boolean(y)) => x == y,
             (DecodedData::F32(x), DecodedData::F32(y)) => ((x * 1_000_000f32).round() / 1_000_000f32) == ((y * 1_000_000f32).round() / 1_000_000f32),
+            (DecodedData::F64(x), DecodedData::F64(y)) => ((x * 1_000_000f64).round() / 1_000_000f64) == ((y * 1_000_000f64).round() / 1_000_000f64),
+            (DecodedData::I8(x), DecodedData::I8(y)) => x == y,
             (DecodedData::I16(x), DecodedData::I16(y)) => x == y,
             (DecodedData::I32(x), DecodedData::I32(y)) => x == y,
             (DecodedData::I64(x), DecodedData::I64(y)) => x == y,
+            (DecodedData::U8(x), DecodedData::U8(y)) => x == y,
+            (DecodedData::U16(x), DecodedData::U16(y)) => x == y,
+            (DecodedData::U32(x), DecodedData::U32(y)) => x == y,
+            (DecodedData::ColourRGB(x), DecodedData::ColourRGB(y)) => x.to_uppercase() == y.to_uppercase(),
             (DecodedData::StringU8(x), DecodedData::StringU8(y)) => x == y,
             (DecodedData::StringU16(x), DecodedData::StringU16(y)) => x == y,
+            (DecodedData::OptionalI16(x), DecodedData::OptionalI16(y)) => x == y,
+            (DecodedData::OptionalI32(x), DecodedData::OptionalI32(y)) => x == y,
+            (DecodedData::OptionalI64(x), DecodedData::OptionalI64(y)) => x == y,
             (DecodedData::OptionalStringU8(x), DecodedData::OptionalStringU8(y)) => x == y,
             (DecodedData::OptionalStringU16(x), DecodedData::OptionalStringU16(y)) => x == y,
             (DecodedData::SequenceU16(x), DecodedData::SequenceU16(y)) => x == y,
@@ -137,11 +239,20 @@ impl DecodedData {
         match field_type {
             FieldType::Boolean => DecodedData::Boolean(false),
             FieldType::F32 => DecodedData::F32(0.0),
+            FieldType::F64 => DecodedData::F64(0.0),
+            FieldType::I8 => DecodedData::I8(0),
             FieldType::I16 => DecodedData::I16(0),
             FieldType::I32 => DecodedData::I32(0),
             FieldType::I64 => DecodedData::I64(0),
+            FieldType::U8 => DecodedData::U8(0),
+            FieldType::U16 => DecodedData::U16(0),
+            FieldType::U32 => DecodedData::U32(0),
+            FieldType::ColourRGB => DecodedData::ColourRGB("000000".to_owned()),
             FieldType::StringU8 => DecodedData::StringU8("".to_owned()),
             FieldType::StringU16 => DecodedData::StringU16("".to_owned()),
+            FieldType::OptionalI16 => DecodedData::OptionalI16(None),
+            FieldType::OptionalI32 => DecodedData::OptionalI32(None),
+            FieldType::OptionalI64 => DecodedData::OptionalI64(None),
             FieldType::OptionalStringU8 => DecodedData::OptionalStringU8("".to_owned()),
             FieldType::OptionalStringU16 => DecodedData::OptionalStringU16("".to_owned()),
             FieldType::SequenceU16(definition) => DecodedData::SequenceU16(Table::new(definition)),
@@ -154,11 +265,20 @@ impl DecodedData {
         match self {
             DecodedData::Boolean(_) => field_type == &FieldType::Boolean,
             DecodedData::F32(_) => field_type == &FieldType::F32,
+            DecodedData::F64(_) => field_type == &FieldType::F64,
+            DecodedData::I8(_) => field_type == &FieldType::I8,
             DecodedData::I16(_) => field_type == &FieldType::I16,
             DecodedData::I32(_) => field_type == &FieldType::I32,
             DecodedData::I64(_) => field_type == &FieldType::I64,
+            DecodedData::U8(_) => field_type == &FieldType::U8,
+            DecodedData::U16(_) => field_type == &FieldType::U16,
+            DecodedData::U32(_) => field_type == &FieldType::U32,
+            DecodedData::ColourRGB(_) => field_type == &FieldType::ColourRGB,
             DecodedData::StringU8(_) => field_type == &FieldType::StringU8,
             DecodedData::StringU16(_) => field_type == &FieldType::StringU16,
+            DecodedData::OptionalI16(_) => field_type == &FieldType::OptionalI16,
+            DecodedData::OptionalI32(_) => field_type == &FieldType::OptionalI32,
+            DecodedData::OptionalI64(_) => field_type == &FieldType::OptionalI64,
             DecodedData::OptionalStringU8(_) => field_type == &FieldType::OptionalStringU8,
             DecodedData::OptionalStringU16(_) => field_type == &FieldType::OptionalStringU16,
             DecodedData::SequenceU16(_) => matches!(field_type, FieldType::SequenceU16(_)),
@@ -168,108 +288,126 @@ impl DecodedData {
 
     /// This function tries to convert the provided data to the provided fieldtype. This can fail in so many ways you should always check the result.
     ///
+    /// Numbers are converted between them with casts, and to and from Strings by printing/parsing them. Non-existant optional integers
+    /// become the default value of the new type, unless it's another optional type. Sequences cannot be converted.
+    ///
     /// NOTE: If you pass the same type as it already has, this becomes an expensive way of cloning.
     pub fn convert_between_types(&self, new_field_type: &FieldType) -> Result<Self> {
         match self {
-            Self::Boolean(ref data) => match new_field_type {
-                FieldType::Boolean => Ok(self.clone()),
-                FieldType::F32 => Ok(Self::F32(if *data { 1.0 } else { 0.0 })),
-                FieldType::I16 => Ok(Self::I16(if *data { 1 } else { 0 })),
-                FieldType::I32 => Ok(Self::I32(if *data { 1 } else { 0 })),
-                FieldType::I64 => Ok(Self::I64(if *data { 1 } else { 0 })),
-                FieldType::StringU8 => Ok(Self::StringU8(data.to_string())),
-                FieldType::StringU16 => Ok(Self::StringU16(data.to_string())),
-                FieldType::OptionalStringU8 => Ok(Self::OptionalStringU8(data.to_string())),
-                FieldType::OptionalStringU16 => Ok(Self::OptionalStringU16(data.to_string())),
-                FieldType::SequenceU16(_) => Err(ErrorKind::Generic.into()),
-                FieldType::SequenceU32(_) => Err(ErrorKind::Generic.into()),
-            }
-
-            Self::F32(ref data) => match new_field_type {
-                FieldType::Boolean => Ok(Self::Boolean(data > &1.0)),
-                FieldType::F32 => Ok(self.clone()),
-                FieldType::I16 => Ok(Self::I16(*data as i16)),
-                FieldType::I32 => Ok(Self::I32(*data as i32)),
-                FieldType::I64 => Ok(Self::I64(*data as i64)),
-                FieldType::StringU8 => Ok(Self::StringU8(data.to_string())),
-                FieldType::StringU16 => Ok(Self::StringU16(data.to_string())),
-                FieldType::OptionalStringU8 => Ok(Self::OptionalStringU8(data.to_string())),
-                FieldType::OptionalStringU16 => Ok(Self::OptionalStringU16(data.to_string())),
-                FieldType::SequenceU16(_) => Err(ErrorKind::Generic.into()),
-                FieldType::SequenceU32(_) => Err(ErrorKind::Generic.into()),
-            }
+            Self::SequenceU16(_) | Self::SequenceU32(_) => Err(ErrorKind::Generic.into()),
+            _ if self.is_field_type_correct(new_field_type) => Ok(self.clone()),
 
-            Self::I16(ref data) => match new_field_type {
-                FieldType::Boolean => Ok(Self::Boolean(data > &1)),
-                FieldType::F32 => Ok(Self::F32(*data as f32)),
-                FieldType::I16 => Ok(self.clone()),
-                FieldType::I32 => Ok(Self::I32(*data as i32)),
-                FieldType::I64 => Ok(Self::I64(*data as i64)),
-                FieldType::StringU8 => Ok(Self::StringU8(data.to_string())),
-                FieldType::StringU16 => Ok(Self::StringU16(data.to_string())),
-                FieldType::OptionalStringU8 => Ok(Self::OptionalStringU8(data.to_string())),
-                FieldType::OptionalStringU16 => Ok(Self::OptionalStringU16(data.to_string())),
-                FieldType::SequenceU16(_) => Err(ErrorKind::Generic.into()),
-                FieldType::SequenceU32(_) => Err(ErrorKind::Generic.into()),
+            Self::Boolean(ref data) => match new_field_type {
+                FieldType::StringU8 |
+                FieldType::StringU16 |
+                FieldType::OptionalStringU8 |
+                FieldType::OptionalStringU16 => Self::new_from_string(&data.to_string(), new_field_type),
+                _ => Self::new_from_integer(if *data { 1 } else { 0 }, new_field_type),
             }
 
-            Self::I32(ref data) => match new_field_type {
-                FieldType::Boolean => Ok(Self::Boolean(data > &1)),
-                FieldType::F32 => Ok(Self::F32(*data as f32)),
-                FieldType::I16 => Ok(Self::I16(*data as i16)),
-                FieldType::I32 => Ok(self.clone()),
-                FieldType::I64 => Ok(Self::I64(*data as i64)),
-                FieldType::StringU8 => Ok(Self::StringU8(data.to_string())),
-                FieldType::StringU16 => Ok(Self::StringU16(data.to_string())),
-                FieldType::OptionalStringU8 => Ok(Self::OptionalStringU8(data.to_string())),
-                FieldType::OptionalStringU16 => Ok(Self::OptionalStringU16(data.to_string())),
-                FieldType::SequenceU16(_) => Err(ErrorKind::Generic.into()),
-                FieldType::SequenceU32(_) => Err(ErrorKind::Generic.into()),
+            Self::F32(ref data) => Self::new_from_float(*data as f64, &data.to_string(), new_field_type),
+            Self::F64(ref data) => Self::new_from_float(*data, &data.to_string(), new_field_type),
+            Self::I8(ref data) => Self::new_from_integer(*data as i64, new_field_type),
+            Self::I16(ref data) => Self::new_from_integer(*data as i64, new_field_type),
+            Self::I32(ref data) => Self::new_from_integer(*data as i64, new_field_type),
+            Self::I64(ref data) => Self::new_from_integer(*data, new_field_type),
+            Self::U8(ref data) => Self::new_from_integer(*data as i64, new_field_type),
+            Self::U16(ref data) => Self::new_from_integer(*data as i64, new_field_type),
+            Self::U32(ref data) => Self::new_from_integer(*data as i64, new_field_type),
+
+            Self::ColourRGB(ref data) => match new_field_type {
+                FieldType::StringU8 |
+                FieldType::StringU16 |
+                FieldType::OptionalStringU8 |
+                FieldType::OptionalStringU16 => Self::new_from_string(data, new_field_type),
+                _ => Self::new_from_integer(u32::from_str_radix(data, 16)? as i64, new_field_type),
             }
 
-            Self::I64(ref data) => match new_field_type {
-                FieldType::Boolean => Ok(Self::Boolean(data > &1)),
-                FieldType::F32 => Ok(Self::F32(*data as f32)),
-                FieldType::I16 => Ok(Self::I16(*data as i16)),
-                FieldType::I32 => Ok(Self::I32(*data as i32)),
-                FieldType::I64 => Ok(self.clone()),
-                FieldType::StringU8 => Ok(Self::StringU8(data.to_string())),
-                FieldType::StringU16 => Ok(Self::StringU16(data.to_string())),
-                FieldType::OptionalStringU8 => Ok(Self::OptionalStringU8(data.to_string())),
-                FieldType::OptionalStringU16 => Ok(Self::OptionalStringU16(data.to_string())),
-                FieldType::SequenceU16(_) => Err(ErrorKind::Generic.into()),
-                FieldType::SequenceU32(_) => Err(ErrorKind::Generic.into()),
-            }
+            Self::OptionalI16(ref data) => Self::new_from_optional_integer(data.map(|data| data as i64), new_field_type),
+            Self::OptionalI32(ref data) => Self::new_from_optional_integer(data.map(|data| data as i64), new_field_type),
+            Self::OptionalI64(ref data) => Self::new_from_optional_integer(*data, new_field_type),
 
             Self::StringU8(ref data) |
             Self::StringU16(ref data) |
             Self::OptionalStringU8(ref data) |
-            Self::OptionalStringU16(ref data) => match new_field_type {
-                FieldType::Boolean => Ok(Self::Boolean(parse_str_as_bool(data)?)),
-                FieldType::F32 => Ok(Self::F32(data.parse::<f32>()?)),
-                FieldType::I16 => Ok(Self::I16(data.parse::<i16>()?)),
-                FieldType::I32 => Ok(Self::I32(data.parse::<i32>()?)),
-                FieldType::I64 => Ok(Self::I64(data.parse::<i64>()?)),
-                FieldType::StringU8 => Ok(Self::StringU8(data.to_string())),
-                FieldType::StringU16 => Ok(Self::StringU16(data.to_string())),
-                FieldType::OptionalStringU8 => Ok(Self::OptionalStringU8(data.to_string())),
-                FieldType::OptionalStringU16 => Ok(Self::OptionalStringU16(data.to_string())),
-                FieldType::SequenceU16(_) => Err(ErrorKind::Generic.into()),
-                FieldType::SequenceU32(_) => Err(ErrorKind::Generic.into()),
-            }
-            /*
-            Self::SequenceU16(ref data) => match new_field_type {
-                FieldType::SequenceU16(ref definition) => Ok(self.clone()),
-                FieldType::SequenceU32(ref definition) => Err(ErrorKind::Generic.into()),
-                _ => Err(ErrorKind::Generic.into()),
-            }
+            Self::OptionalStringU16(ref data) => Self::new_from_string(data, new_field_type),
+        }
+    }
 
-            Self::SequenceU32(ref data) => match new_field_type {
-                FieldType::SequenceU16(ref definition) => Err(ErrorKind::Generic.into()),
-                FieldType::SequenceU32(ref definition) => Ok(self.clone()),
-                _ => Err(ErrorKind::Generic.into()),
-            }*/
-            _ => Err(ErrorKind::Generic.into()),
+    /// This function creates a `DecodedData` of the provided type from a String, parsing it if the type is not a String type.
+    ///
+    /// Empty Strings are parsed as non-existant values for optional integers.
+    pub fn new_from_string(data: &str, field_type: &FieldType) -> Result<Self> {
+        match field_type {
+            FieldType::Boolean => Ok(Self::Boolean(parse_str_as_bool(data)?)),
+            FieldType::F32 => Ok(Self::F32(data.parse::<f32>()?)),
+            FieldType::F64 => Ok(Self::F64(data.parse::<f64>()?)),
+            FieldType::I8 => Ok(Self::I8(data.parse::<i8>()?)),
+            FieldType::I16 => Ok(Self::I16(data.parse::<i16>()?)),
+            FieldType::I32 => Ok(Self::I32(data.parse::<i32>()?)),
+            FieldType::I64 => Ok(Self::I64(data.parse::<i64>()?)),
+            FieldType::U8 => Ok(Self::U8(data.parse::<u8>()?)),
+            FieldType::U16 => Ok(Self::U16(data.parse::<u16>()?)),
+            FieldType::U32 => Ok(Self::U32(data.parse::<u32>()?)),
+            FieldType::ColourRGB => Ok(Self::ColourRGB(format!("{:06X}", u32::from_str_radix(data, 16)?))),
+            FieldType::StringU8 => Ok(Self::StringU8(data.to_string())),
+            FieldType::StringU16 => Ok(Self::StringU16(data.to_string())),
+            FieldType::OptionalI16 => Ok(Self::OptionalI16(if data.is_empty() { None } else { Some(data.parse::<i16>()?) })),
+            FieldType::OptionalI32 => Ok(Self::OptionalI32(if data.is_empty() { None } else { Some(data.parse::<i32>()?) })),
+            FieldType::OptionalI64 => Ok(Self::OptionalI64(if data.is_empty() { None } else { Some(data.parse::<i64>()?) })),
+            FieldType::OptionalStringU8 => Ok(Self::OptionalStringU8(data.to_string())),
+            FieldType::OptionalStringU16 => Ok(Self::OptionalStringU16(data.to_string())),
+            FieldType::SequenceU16(_) => Err(ErrorKind::Generic.into()),
+            FieldType::SequenceU32(_) => Err(ErrorKind::Generic.into()),
+        }
+    }
+
+    /// This function creates a `DecodedData` of the provided type from an integer.
+    fn new_from_integer(data: i64, field_type: &FieldType) -> Result<Self> {
+        match field_type {
+            FieldType::Boolean => Ok(Self::Boolean(data > 1)),
+            FieldType::F32 => Ok(Self::F32(data as f32)),
+            FieldType::F64 => Ok(Self::F64(data as f64)),
+            FieldType::I8 => Ok(Self::I8(data as i8)),
+            FieldType::I16 => Ok(Self::I16(data as i16)),
+            FieldType::I32 => Ok(Self::I32(data as i32)),
+            FieldType::I64 => Ok(Self::I64(data)),
+            FieldType::U8 => Ok(Self::U8(data as u8)),
+            FieldType::U16 => Ok(Self::U16(data as u16)),
+            FieldType::U32 => Ok(Self::U32(data as u32)),
+            FieldType::ColourRGB => Ok(Self::ColourRGB(format!("{:06X}", data as u32))),
+            FieldType::OptionalI16 => Ok(Self::OptionalI16(Some(data as i16))),
+            FieldType::OptionalI32 => Ok(Self::OptionalI32(Some(data as i32))),
+            FieldType::OptionalI64 => Ok(Self::OptionalI64(Some(data))),
+            _ => Self::new_from_string(&data.to_string(), field_type),
+        }
+    }
+
+    /// This function creates a `DecodedData` of the provided type from a float. The float as String is needed to not lose precision when converting it to String types.
+    fn new_from_float(data: f64, data_string: &str, field_type: &FieldType) -> Result<Self> {
+        match field_type {
+            FieldType::Boolean => Ok(Self::Boolean(data > 1.0)),
+            FieldType::F32 => Ok(Self::F32(data as f32)),
+            FieldType::F64 => Ok(Self::F64(data)),
+            FieldType::StringU8 |
+            FieldType::StringU16 |
+            FieldType::OptionalStringU8 |
+            FieldType::OptionalStringU16 => Self::new_from_string(data_string, field_type),
+            _ => Self::new_from_integer(data as i64, field_type),
+        }
+    }
+
+    /// This function creates a `DecodedData` of the provided type from an optional integer.
+    fn new_from_optional_integer(data: Option<i64>, field_type: &FieldType) -> Result<Self> {
+        match data {
+            Some(data) => Self::new_from_integer(data, field_type),
+            None => match field_type {
+                FieldType::StringU8 |
+                FieldType::StringU16 |
+                FieldType::OptionalStringU8 |
+                FieldType::OptionalStringU16 => Self::new_from_string("", field_type),
+                _ => Ok(Self::default(field_type)),
+            }
         }
     }
 
@@ -278,9 +416,18 @@ impl DecodedData {
         match self {
             DecodedData::Boolean(data) => data.to_string(),
             DecodedData::F32(data) => format!("{:.3}", data),
+            DecodedData::F64(data) => format!("{:.3}", data),
+            DecodedData::I8(data) => data.to_string(),
             DecodedData::I16(data) => data.to_string(),
             DecodedData::I32(data) => data.to_string(),
             DecodedData::I64(data) => data.to_string(),
+            DecodedData::U8(data) => data.to_string(),
+            DecodedData::U16(data) => data.to_string(),
+            DecodedData::U32(data) => data.to_string(),
+            DecodedData::OptionalI16(data) => data.map(|data| data.to_string()).unwrap_or_default(),
+            DecodedData::OptionalI32(data) => data.map(|data| data.to_string()).unwrap_or_default(),
+            DecodedData::OptionalI64(data) => data.map(|data| data.to_string()).unwrap_or_default(),
+            DecodedData::ColourRGB(data) |
             DecodedData::StringU8(data) |
             DecodedData::StringU16(data) |
             DecodedData::OptionalStringU8(data) |
@@ -289,6 +436,24 @@ impl DecodedData {
             DecodedData::SequenceU32(_) => "SequenceU32".to_owned(),
         }
     }
+
+    /// This function turns the value of a `F32` into a `f64`, through its shortest representation.
+    ///
+    /// A plain cast keeps the binary error of the `f32`, so 0.1 would become 0.10000000149011612 instead of staying 0.1.
+    pub fn f32_to_f64(value: f32) -> f64 {
+        value.to_string().parse::<f64>().unwrap_or(value as f64)
+    }
+
+    /// This function returns a text that identifies the value of this `DecodedData`, to use it as part of the key of a row.
+    ///
+    /// Unlike `data_to_string`, floats are not rounded, so different values never get the same text.
+    fn data_to_key(&self) -> String {
+        match self {
+            DecodedData::F32(data) => data.to_bits().to_string(),
+            DecodedData::F64(data) => data.to_bits().to_string(),
+            _ => self.data_to_string(),
+        }
+    }
 }
 
 //----------------------------------------------------------------//
@@ -405,6 +570,64 @@ impl Table {
         Ok(())
     }
 
+    /// This function creates a new Table with the provided definition from the schema, and the entries of the provided table.
+    ///
+    /// It's used to validate tables that don't come from binary data, like the ones imported from JSON. It fails if the definition
+    /// of the provided table doesn't have the same fields as the one from the schema, or if any of its entries doesn't follow it.
+    pub fn new_validated(table: &Table, definition: &Definition) -> Result<Self> {
+        if !Self::is_definition_compatible(&table.definition, definition) {
+            return Err(ErrorKind::TableDefinitionDoesNotMatchSchema(definition.get_version()).into())
+        }
+
+        let fields_processed = definition.get_fields_processed();
+        let mut entries = Vec::with_capacity(table.entries.len());
+        for row in &table.entries {
+            if row.len() != fields_processed.len() { return Err(ErrorKind::TableRowWrongFieldCount(fields_processed.len() as u32, row.len() as u32).into()) }
+
+            let mut entry = Vec::with_capacity(row.len());
+            for (cell, field) in row.iter().zip(fields_processed.iter()) {
+                if !cell.is_field_type_correct(field.get_ref_field_type()) {
+                    return Err(ErrorKind::TableWrongFieldType(format!("{}", cell), format!("{}", field.get_ref_field_type())).into())
+                }
+
+                // Sequences contain their own tables, so we need to validate them too.
+                entry.push(match (cell, field.get_ref_field_type()) {
+                    (DecodedData::SequenceU16(table), FieldType::SequenceU16(definition)) => DecodedData::SequenceU16(Self::new_validated(table, definition)?),
+                    (DecodedData::SequenceU32(table), FieldType::SequenceU32(definition)) => DecodedData::SequenceU32(Self::new_validated(table, definition)?),
+                    _ => cell.clone(),
+                });
+            }
+            entries.push(entry);
+        }
+
+        Ok(Self {
+            definition: definition.clone(),
+            entries,
+        })
+    }
+
+    /// This function creates a new Table from the JSON representation of a Table-like PackedFile, like the ones returned by their `to_json`.
+    ///
+    /// The table is validated against the definition for its version in the provided `VersionedFile`, so only tables we can save are imported.
+    pub fn from_json(json: &str, versioned_file: &VersionedFile) -> Result<Self> {
+        let imported: TableJson = serde_json::from_str(json)?;
+        let definition = versioned_file.get_version(imported.table.definition.get_version())?;
+        Self::new_validated(&imported.table, definition)
+    }
+
+    /// This function checks if both definitions have the same fields, with the same names and types, in the same order.
+    fn is_definition_compatible(definition: &Definition, definition_schema: &Definition) -> bool {
+        let fields = definition.get_fields_processed();
+        let fields_schema = definition_schema.get_fields_processed();
+        fields.len() == fields_schema.len() && fields.iter().zip(fields_schema.iter()).all(|(field, field_schema)| {
+            field.get_name() == field_schema.get_name() && match (field.get_ref_field_type(), field_schema.get_ref_field_type()) {
+                (FieldType::SequenceU16(definition), FieldType::SequenceU16(definition_schema)) |
+                (FieldType::SequenceU32(definition), FieldType::SequenceU32(definition_schema)) => Self::is_definition_compatible(definition, definition_schema),
+                (field_type, field_type_schema) => field_type == field_type_schema,
+            }
+        })
+    }
+
     /// This function decodes all the fields of a table from raw bytes.
     ///
     /// If return_incomplete == true, this function will return an error with the incompletely decoded table when it fails.
@@ -430,6 +653,14 @@ impl Table {
                         if let Ok(data) = data.decode_packedfile_float_f32(*index, &mut index) { Ok(DecodedData::F32(data)) }
                         else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>F32</b></i> value: the value is not a valid F32, or there are insufficient bytes left to decode it as a F32 value.</p>", row + 1, column + 1))) }
                     }
+                    FieldType::F64 => {
+                        if let Ok(data) = data.decode_packedfile_float_f64(*index, index) { Ok(DecodedData::F64(data)) }
+                        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>F64</b></i> value: the value is not a valid F64, or there are insufficient bytes left to decode it as a F64 value.</p>", row + 1, column + 1))) }
+                    }
+                    FieldType::I8 => {
+                        if let Ok(data) = data.decode_packedfile_integer_i8(*index, index) { Ok(DecodedData::I8(data)) }
+                        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>I8</b></i> value: the value is not a valid I8, or there are insufficient bytes left to decode it as an I8 value.</p>", row + 1, column + 1))) }
+                    }
                     FieldType::I16 => {
                         if let Ok(data) = data.decode_packedfile_integer_i16(*index, &mut index) { Ok(DecodedData::I16(data)) }
                         else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>I16</b></i> value: the value is not a valid I16, or there are insufficient bytes left to decode it as an I16 value.</p>", row + 1, column + 1))) }
@@ -442,6 +673,22 @@ impl Table {
                         if let Ok(data) = data.decode_packedfile_integer_i64(*index, &mut index) { Ok(DecodedData::I64(data)) }
                         else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>I64</b></i> value: either the value is not a valid I64, or there are insufficient bytes left to decode it as an I64 value.</p>", row + 1, column + 1))) }
                     }
+                    FieldType::U8 => {
+                        if let Ok(data) = data.decode_packedfile_integer_u8(*index, index) { Ok(DecodedData::U8(data)) }
+                        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>U8</b></i> value: the value is not a valid U8, or there are insufficient bytes left to decode it as an U8 value.</p>", row + 1, column + 1))) }
+                    }
+                    FieldType::U16 => {
+                        if let Ok(data) = data.decode_packedfile_integer_u16(*index, index) { Ok(DecodedData::U16(data)) }
+                        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>U16</b></i> value: the value is not a valid U16, or there are insufficient bytes left to decode it as an U16 value.</p>", row + 1, column + 1))) }
+                    }
+                    FieldType::U32 => {
+                        if let Ok(data) = data.decode_packedfile_integer_u32(*index, index) { Ok(DecodedData::U32(data)) }
+                        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>U32</b></i> value: the value is not a valid U32, or there are insufficient bytes left to decode it as an U32 value.</p>", row + 1, column + 1))) }
+                    }
+                    FieldType::ColourRGB => {
+                        if let Ok(data) = data.decode_packedfile_colour_rgb(*index, index) { Ok(DecodedData::ColourRGB(data)) }
+                        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as a <b><i>RGB Colour</b></i> value: the value is not a valid RGB Colour, or there are insufficient bytes left to decode it as a RGB Colour value.</p>", row + 1, column + 1))) }
+                    }
                     FieldType::StringU8 => {
                         if let Ok(data) = data.decode_packedfile_string_u8(*index, &mut index) { Ok(DecodedData::StringU8(Self::escape_special_chars(&data))) }
                         else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>UTF-8 String</b></i> value: the value is not a valid UTF-8 String, or there are insufficient bytes left to decode it as an UTF-8 String.</p>", row + 1, column + 1))) }
@@ -450,6 +697,18 @@ impl Table {
                         if let Ok(data) = data.decode_packedfile_string_u16(*index, &mut index) { Ok(DecodedData::StringU16(Self::escape_special_chars(&data))) }
                         else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>UTF-16 String</b></i> value: the value is not a valid UTF-16 String, or there are insufficient bytes left to decode it as an UTF-16 String.</p>", row + 1, column + 1))) }
                     }
+                    FieldType::OptionalI16 => {
+                        if let Ok(data) = data.decode_packedfile_optional_integer_i16(*index, index) { Ok(DecodedData::OptionalI16(data)) }
+                        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>Optional I16</b></i> value: the value is not a valid Optional I16, or there are insufficient bytes left to decode it as an Optional I16 value.</p>", row + 1, column + 1))) }
+                    }
+                    FieldType::OptionalI32 => {
+                        if let Ok(data) = data.decode_packedfile_optional_integer_i32(*index, index) { Ok(DecodedData::OptionalI32(data)) }
+                        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>Optional I32</b></i> value: the value is not a valid Optional I32, or there are insufficient bytes left to decode it as an Optional I32 value.</p>", row + 1, column + 1))) }
+                    }
+                    FieldType::OptionalI64 => {
+                        if let Ok(data) = data.decode_packedfile_optional_integer_i64(*index, index) { Ok(DecodedData::OptionalI64(data)) }
+                        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>Optional I64</b></i> value: the value is not a valid Optional I64, or there are insufficient bytes left to decode it as an Optional I64 value.</p>", row + 1, column + 1))) }
+                    }
                     FieldType::OptionalStringU8 => {
                         if let Ok(data) = data.decode_packedfile_optional_string_u8(*index, &mut index) { Ok(DecodedData::OptionalStringU8(Self::escape_special_chars(&data))) }
                         else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode the <i><b>Row {}, Cell {}</b></i> as an <b><i>Optional UTF-8 String</b></i> value: the value is not a valid Optional UTF-8 String, or there are insufficient bytes left to decode it as an Optional UTF-8 String.</p>", row + 1, column + 1))) }
@@ -484,9 +743,13 @@ impl Table {
                         // If the field is a bitwise, split it into multiple fields. This is currently limited to integer types.
                         if field.get_is_bitwise() > 1 {
                             let data = match data {
+                                DecodedData::I8(ref data) => *data as i64,
                                 DecodedData::I16(ref data) => *data as i64,
                                 DecodedData::I32(ref data) => *data as i64,
                                 DecodedData::I64(ref data) => *data,
+                                DecodedData::U8(ref data) => *data as i64,
+                                DecodedData::U16(ref data) => *data as i64,
+                                DecodedData::U32(ref data) => *data as i64,
                                 _ => return Err(ErrorKind::Generic.into())
                             };
 
@@ -498,9 +761,13 @@ impl Table {
                         // If the field has enum values, we turn it into a string. Same as before, only for integer types.
                         else if !field.get_enum_values().is_empty() {
                             let data = match data {
+                                DecodedData::I8(ref data) => *data as i32,
                                 DecodedData::I16(ref data) => *data as i32,
                                 DecodedData::I32(ref data) => *data,
                                 DecodedData::I64(ref data) => *data as i32,
+                                DecodedData::U8(ref data) => *data as i32,
+                                DecodedData::U16(ref data) => *data as i32,
+                                DecodedData::U32(ref data) => *data as i32,
                                 _ => return Err(ErrorKind::Generic.into())
                             };
 
@@ -552,9 +819,13 @@ impl Table {
 
                     // If there are no problems, encode the data.
                     match field.get_field_type() {
+                        FieldType::I8 => packed_file.encode_integer_i8(data as i8),
                         FieldType::I16 => packed_file.encode_integer_i16(data as i16),
                         FieldType::I32 => packed_file.encode_integer_i32(data as i32),
                         FieldType::I64 => packed_file.encode_integer_i64(data),
+                        FieldType::U8 => packed_file.encode_integer_u8(data as u8),
+                        FieldType::U16 => packed_file.encode_integer_u16(data as u16),
+                        FieldType::U32 => packed_file.encode_integer_u32(data as u32),
                         _ => return Err(ErrorKind::TableWrongFieldType(format!("{}", row[data_column]), format!("{}", field.get_ref_field_type())).into())
                     }
                 }
@@ -564,9 +835,18 @@ impl Table {
                     match row[data_column] {
                         DecodedData::Boolean(data) => packed_file.encode_bool(data),
                         DecodedData::F32(data) => packed_file.encode_float_f32(data),
+                        DecodedData::F64(data) => packed_file.encode_float_f64(data),
+                        DecodedData::I8(data) => packed_file.encode_integer_i8(data),
                         DecodedData::I16(data) => packed_file.encode_integer_i16(data),
                         DecodedData::I32(data) => packed_file.encode_integer_i32(data),
                         DecodedData::I64(data) => packed_file.encode_integer_i64(data),
+                        DecodedData::U8(data) => packed_file.encode_integer_u8(data),
+                        DecodedData::U16(data) => packed_file.encode_integer_u16(data),
+                        DecodedData::U32(data) => packed_file.encode_integer_u32(data),
+                        DecodedData::ColourRGB(ref data) => packed_file.encode_colour_rgb(data)?,
+                        DecodedData::OptionalI16(data) => packed_file.encode_packedfile_optional_integer_i16(data),
+                        DecodedData::OptionalI32(data) => packed_file.encode_packedfile_optional_integer_i32(data),
+                        DecodedData::OptionalI64(data) => packed_file.encode_packedfile_optional_integer_i64(data),
                         DecodedData::StringU8(ref data) |
                         DecodedData::StringU16(ref data) |
                         DecodedData::OptionalStringU8(ref data) |
@@ -579,9 +859,13 @@ impl Table {
                                 let data = match values.iter().find(|(_, y)| y.to_lowercase() == data.to_lowercase()) {
                                     Some((x, _)) => {
                                         match field.get_field_type() {
+                                            FieldType::I8 => DecodedData::I8(*x as i8),
                                             FieldType::I16 => DecodedData::I16(*x as i16),
                                             FieldType::I32 => DecodedData::I32(*x),
                                             FieldType::I64 => DecodedData::I64(*x as i64),
+                                            FieldType::U8 => DecodedData::U8(*x as u8),
+                                            FieldType::U16 => DecodedData::U16(*x as u16),
+                                            FieldType::U32 => DecodedData::U32(*x as u32),
                                             _ => return Err(ErrorKind::TableWrongFieldType(format!("{}", row[data_column]), format!("{}", field.get_ref_field_type())).into())
                                         }
                                     }
@@ -593,9 +877,13 @@ impl Table {
 
                                 // If there are no problems, encode the data.
                                 match data {
+                                    DecodedData::I8(data) => packed_file.encode_integer_i8(data),
                                     DecodedData::I16(data) => packed_file.encode_integer_i16(data),
                                     DecodedData::I32(data) => packed_file.encode_integer_i32(data),
                                     DecodedData::I64(data) => packed_file.encode_integer_i64(data),
+                                    DecodedData::U8(data) => packed_file.encode_integer_u8(data),
+                                    DecodedData::U16(data) => packed_file.encode_integer_u16(data),
+                                    DecodedData::U32(data) => packed_file.encode_integer_u32(data),
                                     _ => return Err(ErrorKind::TableWrongFieldType(format!("{}", row[data_column]), format!("{}", field.get_ref_field_type())).into())
                                 }
                             }
@@ -702,6 +990,27 @@ impl Table {
                             vec![DecodedData::I64(0); 1]
                         }
                     },
+
+                    // For the newer numeric types, just try to parse the default value, if any.
+                    FieldType::I8 |
+                    FieldType::U8 |
+                    FieldType::U16 |
+                    FieldType::U32 if field.get_is_bitwise() > 1 => vec![DecodedData::Boolean(false); field.get_is_bitwise() as usize],
+                    FieldType::F64 |
+                    FieldType::I8 |
+                    FieldType::U8 |
+                    FieldType::U16 |
+                    FieldType::U32 |
+                    FieldType::ColourRGB |
+                    FieldType::OptionalI16 |
+                    FieldType::OptionalI32 |
+                    FieldType::OptionalI64 => {
+                        if let Some(Ok(default_value)) = field.get_default_value().as_ref().map(|default_value| DecodedData::new_from_string(default_value, field.get_ref_field_type())) {
+                            vec![default_value; 1]
+                        } else {
+                            vec![DecodedData::default(field.get_ref_field_type()); 1]
+                        }
+                    },
                     FieldType::StringU8 => {
                         if let Some(default_value) = field.get_default_value() {
                             vec![DecodedData::StringU8(default_value.to_owned()); 1]
@@ -828,8 +1137,8 @@ impl Table {
     // TSV Functions for PackedFiles.
     //----------------------------------------------------------------//
 
-    /// This function imports a TSV file into a decoded table.
-    fn import_tsv(
+    /// This function creates a new Table from a TSV file.
+    fn new_from_tsv(
         definition: &Definition,
         path: &PathBuf,
         name: &str,
@@ -866,7 +1175,8 @@ impl Table {
                 else if record.len() == definition.get_fields_processed().len() {
                     let mut entry = vec![];
                     for (column, field) in record.iter().enumerate() {
-                        match definition.get_fields_processed()[column].get_ref_field_type() {
+                        let field_type = definition.get_fields_processed()[column].get_field_type();
+                        match field_type {
                             FieldType::Boolean => {
                                 let value = field.to_lowercase();
                                 if value == "true" || value == "1" { entry.push(DecodedData::Boolean(true)); }
@@ -881,6 +1191,15 @@ impl Table {
                             FieldType::StringU16 => entry.push(DecodedData::StringU16(field.to_owned())),
                             FieldType::OptionalStringU8 => entry.push(DecodedData::OptionalStringU8(field.to_owned())),
                             FieldType::OptionalStringU16 => entry.push(DecodedData::OptionalStringU16(field.to_owned())),
+                            FieldType::F64 |
+                            FieldType::I8 |
+                            FieldType::U8 |
+                            FieldType::U16 |
+                            FieldType::U32 |
+                            FieldType::ColourRGB |
+                            FieldType::OptionalI16 |
+                            FieldType::OptionalI32 |
+                            FieldType::OptionalI64 => entry.push(DecodedData::new_from_string(field, &field_type).map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?),
 
                             // For now fail on Sequences. These are a bit special and I don't know if the're even possible in TSV.
                             FieldType::SequenceU16(_) => return Err(ErrorKind::ImportTSVIncorrectRow(row, column).into()),
@@ -943,7 +1262,8 @@ impl Table {
                 else if record.len() == definition.get_fields_processed().len() {
                     let mut entry = vec![];
                     for (column, field) in record.iter().enumerate() {
-                        match definition.get_fields_processed()[column].get_ref_field_type() {
+                        let field_type = definition.get_fields_processed()[column].get_field_type();
+                        match field_type {
                             FieldType::Boolean => {
                                 let value = field.to_lowercase();
                                 if value == "true" || value == "1" { entry.push(DecodedData::Boolean(true)); }
@@ -958,6 +1278,15 @@ impl Table {
                             FieldType::StringU16 => entry.push(DecodedData::StringU16(field.to_owned())),
                             FieldType::OptionalStringU8 => entry.push(DecodedData::OptionalStringU8(field.to_owned())),
                             FieldType::OptionalStringU16 => entry.push(DecodedData::OptionalStringU16(field.to_owned())),
+                            FieldType::F64 |
+                            FieldType::I8 |
+                            FieldType::U8 |
+                            FieldType::U16 |
+                            FieldType::U32 |
+                            FieldType::ColourRGB |
+                            FieldType::OptionalI16 |
+                            FieldType::OptionalI32 |
+                            FieldType::OptionalI64 => entry.push(DecodedData::new_from_string(field, &field_type).map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?),
                             FieldType::SequenceU16(_) |
                             FieldType::SequenceU32(_) => return Err(ErrorKind::ImportTSVIncorrectRow(row, column).into())
                         }
@@ -992,6 +1321,177 @@ impl Table {
         Ok(())
     }
 
+    /// This function imports a TSV file into the table, replacing its rows with the ones of the file or merging them, depending on the mode.
+    ///
+    /// If anything in the file is wrong, the table is left untouched.
+    fn import_tsv(
+        &mut self,
+        path: &PathBuf,
+        name: &str,
+        mode: TSVImportMode,
+    ) -> Result<MergeReport> {
+        match mode {
+            TSVImportMode::Replace => {
+                *self = Self::new_from_tsv(&self.definition, path, name)?;
+                Ok(MergeReport { inserted: self.entries.len(), ..Default::default() })
+            }
+            TSVImportMode::Merge => self.merge_tsv(path, name),
+        }
+    }
+
+    /// This function merges a TSV file into the table, matching its rows with the ones of the table by their keys.
+    ///
+    /// Unlike with normal imports, the TSV file doesn't need to have all the columns of the table, nor in the same order,
+    /// but it needs to have all its key columns. Rows with new keys are added to the table, with default values in the columns
+    /// not in the file, and rows with existing keys only update the columns in the file. Rows with `delete` in the
+    /// `TSV_MERGE_ACTION_COLUMN` column, if the file has it, remove the rows with the same key from the table instead.
+    ///
+    /// If the definition has no key columns, the `key` column is used as key, if there is one, so this works with Locs too.
+    /// If anything in the file is wrong, the table is left untouched.
+    fn merge_tsv(
+        &mut self,
+        path: &PathBuf,
+        name: &str,
+    ) -> Result<MergeReport> {
+        let mut reader = ReaderBuilder::new()
+            .delimiter(b'\t')
+            .quoting(false)
+            .has_headers(false)
+            .flexible(true)
+            .from_path(path)?;
+        let mut records = reader.records();
+
+        // The first line should contain the name and version of the table, like with normal imports.
+        let header = match records.next() {
+            Some(Ok(header)) => header,
+            _ => return Err(ErrorKind::ImportTSVWrongTypeTable.into()),
+        };
+
+        if header.get(0).unwrap_or("error") != name { return Err(ErrorKind::ImportTSVWrongTypeTable.into()); }
+        if header.get(1).unwrap_or("-1").parse::<i32>().map_err(|_| Error::from(ErrorKind::ImportTSVInvalidVersion))? != self.definition.get_version() {
+            return Err(ErrorKind::ImportTSVWrongVersion.into());
+        }
+
+        // The second line is not just for other programs here: it tells us to what column of the table each column of the file belongs.
+        let column_names = match records.next() {
+            Some(Ok(column_names)) => column_names,
+            _ => return Err(ErrorKind::ImportTSVIncorrectRow(1, 0).into()),
+        };
+
+        let fields = self.definition.get_fields_processed();
+        let mut columns: Vec<Option<usize>> = vec![];
+        let mut has_action_column = false;
+        for column_name in column_names.iter() {
+            if column_name == TSV_MERGE_ACTION_COLUMN {
+                if has_action_column { return Err(ErrorKind::ImportTSVMergeDuplicatedColumn(column_name.to_owned()).into()); }
+                has_action_column = true;
+                columns.push(None);
+            }
+            else {
+                match fields.iter().position(|field| field.get_name() == column_name) {
+                    Some(index) if columns.contains(&Some(index)) => return Err(ErrorKind::ImportTSVMergeDuplicatedColumn(column_name.to_owned()).into()),
+                    Some(index) => columns.push(Some(index)),
+                    None => return Err(ErrorKind::ImportTSVMergeUnknownColumn(column_name.to_owned()).into()),
+                }
+            }
+        }
+
+        let mut key_columns = fields.iter().enumerate().filter(|(_, field)| field.get_is_key()).map(|(index, _)| index).collect::<Vec<usize>>();
+        if key_columns.is_empty() {
+            key_columns = fields.iter().position(|field| field.get_name() == "key").into_iter().collect();
+        }
+
+        if key_columns.is_empty() { return Err(ErrorKind::ImportTSVMergeNoKeys.into()); }
+        if let Some(key_column) = key_columns.iter().find(|key_column| !columns.contains(&Some(**key_column))) {
+            return Err(ErrorKind::ImportTSVMergeMissingKeyColumn(fields[*key_column].get_name().to_owned()).into());
+        }
+
+        // Index the rows of the table by their keys. Keys may be repeated in broken tables, so we keep all the rows with the same key.
+        let mut entries = self.entries.clone();
+        let mut rows_by_key: HashMap<Vec<String>, Vec<usize>> = HashMap::new();
+        for (index, entry) in entries.iter().enumerate() {
+            let key = key_columns.iter().map(|column| entry[*column].data_to_key()).collect::<Vec<String>>();
+            rows_by_key.entry(key).or_default().push(index);
+        }
+
+        let mut report = MergeReport::default();
+        let mut keys_in_file = HashSet::new();
+        let mut rows_to_delete = vec![];
+        for (row, record) in records.enumerate() {
+            let row = row + 2;
+            let record = record.map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, 0)))?;
+            if record.len() != columns.len() { return Err(ErrorKind::ImportTSVIncorrectRow(row, record.len()).into()); }
+
+            let mut values = vec![];
+            let mut delete = false;
+            for (column, (field, field_index)) in record.iter().zip(columns.iter()).enumerate() {
+                match field_index {
+                    Some(field_index) => {
+                        let value = Self::parse_tsv_field(field, fields[*field_index].get_ref_field_type()).ok_or_else(|| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?;
+                        values.push((*field_index, value));
+                    }
+                    None => delete = match &*field.to_lowercase() {
+                        "" => false,
+                        TSV_MERGE_ACTION_DELETE => true,
+                        _ => return Err(ErrorKind::ImportTSVMergeInvalidAction(row, field.to_owned()).into()),
+                    },
+                }
+            }
+
+            let key = key_columns.iter().filter_map(|key_column| values.iter().find(|(field_index, _)| field_index == key_column)).map(|(_, value)| value.data_to_key()).collect::<Vec<String>>();
+            if !keys_in_file.insert(key.to_vec()) { return Err(ErrorKind::ImportTSVMergeDuplicatedKey(row).into()); }
+
+            match rows_by_key.get(&key) {
+                Some(indexes) if delete => {
+                    rows_to_delete.extend_from_slice(indexes);
+                    report.deleted += 1;
+                }
+                Some(indexes) => {
+                    let mut updated = false;
+                    for index in indexes {
+                        for (field_index, value) in &values {
+                            if entries[*index][*field_index] != *value {
+                                entries[*index][*field_index] = value.clone();
+                                updated = true;
+                            }
+                        }
+                    }
+
+                    if updated { report.updated += 1; } else { report.unchanged += 1; }
+                }
+                None if delete => report.unchanged += 1,
+                None => {
+                    let mut entry = Self::get_new_row(&self.definition);
+                    for (field_index, value) in values {
+                        entry[field_index] = value;
+                    }
+
+                    entries.push(entry);
+                    report.inserted += 1;
+                }
+            }
+        }
+
+        // Deletions go last, so the indexes of the rows are still valid until now.
+        rows_to_delete.sort_unstable();
+        rows_to_delete.dedup();
+        for index in rows_to_delete.iter().rev() {
+            entries.remove(*index);
+        }
+
+        self.entries = entries;
+        Ok(report)
+    }
+
+    /// This function parses a field of a TSV file as a value of the provided type. Sequences are not supported.
+    fn parse_tsv_field(field: &str, field_type: &FieldType) -> Option<DecodedData> {
+        match field_type {
+            FieldType::Boolean => parse_str_as_bool(field).ok().map(DecodedData::Boolean),
+            FieldType::SequenceU16(_) | FieldType::SequenceU32(_) => None,
+            _ => DecodedData::new_from_string(field, field_type).ok(),
+        }
+    }
+
     /// This function exports the provided data to a TSV file.
     fn export_tsv(
         &self,
@@ -1109,6 +1609,15 @@ impl From<&RawTable> for Table {
                                 FieldType::StringU16 => DecodedData::StringU16(if field.field_data == "Frodo Best Waifu" { String::new() } else { field.field_data.to_string() }),
                                 FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(if field.field_data == "Frodo Best Waifu" { String::new() } else { field.field_data.to_string() }),
                                 FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(if field.field_data == "Frodo Best Waifu" { String::new() } else { field.field_data.to_string() }),
+                                FieldType::F64 |
+                                FieldType::I8 |
+                                FieldType::U8 |
+                                FieldType::U16 |
+                                FieldType::U32 |
+                                FieldType::ColourRGB |
+                                FieldType::OptionalI16 |
+                                FieldType::OptionalI32 |
+                                FieldType::OptionalI64 => DecodedData::new_from_string(&field.field_data, field_def.get_ref_field_type()).unwrap_or_else(|_| DecodedData::default(field_def.get_ref_field_type())),
 
                                 // This type is not used in the raw tables so, if we find it, we skip it.
                                 FieldType::SequenceU16(_) | FieldType::SequenceU32(_) => continue,
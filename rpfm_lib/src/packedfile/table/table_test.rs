@@ -0,0 +1,343 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Table` module, to make sure all the field types are decoded, encoded, imported and exported correctly.
+!*/
+
+use std::fs::remove_file;
+
+use super::*;
+use super::anim_fragment::AnimFragment;
+use super::animtable::AnimTable;
+use super::matched_combat::MatchedCombat;
+
+/// This function builds a definition with one field of each numeric and colour type.
+fn build_definition() -> Definition {
+    let mut definition = Definition::new(1);
+    *definition.get_ref_mut_fields() = [
+        ("f64", FieldType::F64),
+        ("i8", FieldType::I8),
+        ("u8", FieldType::U8),
+        ("u16", FieldType::U16),
+        ("u32", FieldType::U32),
+        ("colour", FieldType::ColourRGB),
+        ("optional_i16", FieldType::OptionalI16),
+        ("optional_i32", FieldType::OptionalI32),
+        ("optional_i64", FieldType::OptionalI64),
+    ].iter().map(|(name, field_type)| {
+        let mut field = Field::default();
+        field.set_name(name);
+        field.set_field_type(field_type.clone());
+        field
+    }).collect();
+    definition
+}
+
+/// This function builds the raw data of a table with two entries.
+fn build_table_data() -> Vec<u8> {
+    let mut data = vec![];
+    data.encode_float_f64(-1.5);
+    data.encode_integer_i8(-3);
+    data.encode_integer_u8(200);
+    data.encode_integer_u16(60000);
+    data.encode_integer_u32(4_000_000_000);
+    data.encode_colour_rgb("FF8000").unwrap();
+    data.encode_packedfile_optional_integer_i16(Some(-7));
+    data.encode_packedfile_optional_integer_i32(None);
+    data.encode_packedfile_optional_integer_i64(Some(1 << 40));
+
+    data.encode_float_f64(0.25);
+    data.encode_integer_i8(127);
+    data.encode_integer_u8(0);
+    data.encode_integer_u16(1);
+    data.encode_integer_u32(2);
+    data.encode_colour_rgb("00aa11").unwrap();
+    data.encode_packedfile_optional_integer_i16(None);
+    data.encode_packedfile_optional_integer_i32(Some(42));
+    data.encode_packedfile_optional_integer_i64(None);
+    data
+}
+
+#[test]
+fn test_table_field_types() {
+    let data = build_table_data();
+    let mut table = Table::new(&build_definition());
+    let mut index = 0;
+    table.decode(&data, 2, &mut index, false).unwrap();
+    assert_eq!(index, data.len());
+    assert_eq!(table.get_ref_table_data()[0], [
+        DecodedData::F64(-1.5),
+        DecodedData::I8(-3),
+        DecodedData::U8(200),
+        DecodedData::U16(60000),
+        DecodedData::U32(4_000_000_000),
+        DecodedData::ColourRGB("FF8000".to_owned()),
+        DecodedData::OptionalI16(Some(-7)),
+        DecodedData::OptionalI32(None),
+        DecodedData::OptionalI64(Some(1 << 40)),
+    ]);
+    assert_eq!(table.get_ref_table_data()[1][5], DecodedData::ColourRGB("00AA11".to_owned()));
+
+    // Encoding must give us back the same data.
+    let mut encoded = vec![];
+    table.encode(&mut encoded).unwrap();
+    assert_eq!(encoded, data);
+
+    // Exporting and importing must not lose anything, not even the non-existant optional values.
+    let path = std::env::temp_dir().join("rpfm_test_table_field_types.tsv");
+    table.export_tsv(&path, "test_tables").unwrap();
+    let imported = Table::new_from_tsv(&build_definition(), &path, "test_tables").unwrap();
+    remove_file(&path).unwrap();
+    assert_eq!(imported, table);
+
+    // Broken colours cannot be encoded.
+    let mut row = table.get_table_data()[0].to_vec();
+    row[5] = DecodedData::ColourRGB("not a colour".to_owned());
+    table.set_table_data(&[row]).unwrap();
+    assert!(table.encode(&mut vec![]).is_err());
+}
+
+#[test]
+fn test_convert_between_types() {
+    assert_eq!(DecodedData::I32(300).convert_between_types(&FieldType::U8).unwrap(), DecodedData::U8(44));
+    assert_eq!(DecodedData::U32(255).convert_between_types(&FieldType::ColourRGB).unwrap(), DecodedData::ColourRGB("0000FF".to_owned()));
+    assert_eq!(DecodedData::ColourRGB("0000FF".to_owned()).convert_between_types(&FieldType::I64).unwrap(), DecodedData::I64(255));
+    assert_eq!(DecodedData::F32(2.5).convert_between_types(&FieldType::F64).unwrap(), DecodedData::F64(2.5));
+    assert_eq!(DecodedData::F64(2.5).convert_between_types(&FieldType::StringU8).unwrap(), DecodedData::StringU8("2.5".to_owned()));
+    assert_eq!(DecodedData::OptionalI32(None).convert_between_types(&FieldType::OptionalI64).unwrap(), DecodedData::OptionalI64(None));
+    assert_eq!(DecodedData::OptionalI32(None).convert_between_types(&FieldType::I16).unwrap(), DecodedData::I16(0));
+    assert_eq!(DecodedData::OptionalI32(None).convert_between_types(&FieldType::StringU8).unwrap(), DecodedData::StringU8(String::new()));
+    assert_eq!(DecodedData::StringU8("".to_owned()).convert_between_types(&FieldType::OptionalI16).unwrap(), DecodedData::OptionalI16(None));
+    assert_eq!(DecodedData::StringU8("-5".to_owned()).convert_between_types(&FieldType::OptionalI16).unwrap(), DecodedData::OptionalI16(Some(-5)));
+    assert!(DecodedData::StringU8("-5".to_owned()).convert_between_types(&FieldType::U16).is_err());
+    assert!(DecodedData::StringU8("red".to_owned()).convert_between_types(&FieldType::ColourRGB).is_err());
+}
+
+#[test]
+fn test_f32_to_f64() {
+    assert_eq!(DecodedData::f32_to_f64(0.1), 0.1);
+    assert_eq!(DecodedData::f32_to_f64(-2.5), -2.5);
+    assert!(DecodedData::f32_to_f64(f32::NAN).is_nan());
+    assert_eq!(DecodedData::f32_to_f64(f32::NEG_INFINITY), f64::NEG_INFINITY);
+}
+
+/// This function builds a table with a key column, to merge TSV files into it.
+fn build_merge_table() -> Table {
+    let mut definition = Definition::new(1);
+    *definition.get_ref_mut_fields() = vec![
+        Field::new("key".to_owned(), FieldType::StringU8, true, None, 0, false, None, None, None, String::new(), 0, 0, BTreeMap::new()),
+        Field::new("cost".to_owned(), FieldType::I32, false, None, 0, false, None, None, None, String::new(), 1, 0, BTreeMap::new()),
+        Field::new("enabled".to_owned(), FieldType::Boolean, false, Some("true".to_owned()), 0, false, None, None, None, String::new(), 2, 0, BTreeMap::new()),
+    ];
+
+    let mut table = Table::new(&definition);
+    table.set_table_data(&[
+        vec![DecodedData::StringU8("a".to_owned()), DecodedData::I32(1), DecodedData::Boolean(false)],
+        vec![DecodedData::StringU8("b".to_owned()), DecodedData::I32(2), DecodedData::Boolean(false)],
+        vec![DecodedData::StringU8("c".to_owned()), DecodedData::I32(3), DecodedData::Boolean(false)],
+    ]).unwrap();
+    table
+}
+
+/// This function merges the provided text as a TSV file into the provided table.
+fn merge_text(table: &mut Table, text: &str) -> Result<MergeReport> {
+    let path = std::env::temp_dir().join("rpfm_test_table_merge.tsv");
+    std::fs::write(&path, text).unwrap();
+    let report = table.import_tsv(&path, "test_tables", TSVImportMode::Merge);
+    remove_file(&path).unwrap();
+    report
+}
+
+/// This function imports the provided text as a TSV file into the provided table, replacing its rows.
+fn replace_text(table: &mut Table, text: &str) -> Result<MergeReport> {
+    let path = std::env::temp_dir().join("rpfm_test_table_replace.tsv");
+    std::fs::write(&path, text).unwrap();
+    let report = table.import_tsv(&path, "test_tables", TSVImportMode::Replace);
+    remove_file(&path).unwrap();
+    report
+}
+
+#[test]
+fn test_merge_tsv() {
+    let mut table = build_merge_table();
+    let report = merge_text(&mut table, "test_tables\t1\ncost\tkey\trpfm_merge_action\n10\ta\t\n2\tb\t\n5\td\t\n0\tc\tdelete\n0\tz\tDELETE\n").unwrap();
+    assert_eq!(report, MergeReport { inserted: 1, updated: 1, unchanged: 2, deleted: 1 });
+    assert_eq!(table.get_ref_table_data(), &[
+        vec![DecodedData::StringU8("a".to_owned()), DecodedData::I32(10), DecodedData::Boolean(false)],
+        vec![DecodedData::StringU8("b".to_owned()), DecodedData::I32(2), DecodedData::Boolean(false)],
+        vec![DecodedData::StringU8("d".to_owned()), DecodedData::I32(5), DecodedData::Boolean(true)],
+    ][..]);
+
+    // If anything is wrong in the file, nothing is merged.
+    let broken_files = [
+        "test_tables\t1\ncost\n10\n",
+        "test_tables\t1\nkey\tprice\na\t10\n",
+        "test_tables\t1\nkey\tkey\na\ta\n",
+        "test_tables\t1\nkey\tcost\na\t10\na\t11\n",
+        "test_tables\t1\nkey\tcost\tenabled\nb\t10\tmaybe\n",
+        "test_tables\t1\nkey\trpfm_merge_action\nb\tremove\n",
+        "test_tables\t2\nkey\na\n",
+    ];
+    let expected = table.clone();
+    for broken_file in &broken_files {
+        assert!(merge_text(&mut table, broken_file).is_err(), "{}", broken_file);
+        assert_eq!(table, expected);
+    }
+}
+
+#[test]
+fn test_merge_tsv_float_keys() {
+    let mut definition = Definition::new(1);
+    *definition.get_ref_mut_fields() = vec![
+        Field::new("x".to_owned(), FieldType::F32, true, None, 0, false, None, None, None, String::new(), 0, 0, BTreeMap::new()),
+        Field::new("name".to_owned(), FieldType::StringU8, false, None, 0, false, None, None, None, String::new(), 1, 0, BTreeMap::new()),
+    ];
+
+    let mut table = Table::new(&definition);
+    table.set_table_data(&[
+        vec![DecodedData::F32(0.1234), DecodedData::StringU8("a".to_owned())],
+        vec![DecodedData::F32(2.0), DecodedData::StringU8("b".to_owned())],
+    ]).unwrap();
+
+    // Keys that only differ after the third decimal are different keys.
+    let report = merge_text(&mut table, "test_tables\t1\nx\tname\n0.1235\tc\n2\td\n").unwrap();
+    assert_eq!(report, MergeReport { inserted: 1, updated: 1, unchanged: 0, deleted: 0 });
+    assert_eq!(table.get_ref_table_data(), &[
+        vec![DecodedData::F32(0.1234), DecodedData::StringU8("a".to_owned())],
+        vec![DecodedData::F32(2.0), DecodedData::StringU8("d".to_owned())],
+        vec![DecodedData::F32(0.1235), DecodedData::StringU8("c".to_owned())],
+    ][..]);
+}
+
+#[test]
+fn test_import_tsv_replace() {
+    let mut table = build_merge_table();
+    let report = replace_text(&mut table, "test_tables\t1\nkey\tcost\tenabled\nz\t9\ttrue\n").unwrap();
+    assert_eq!(report, MergeReport { inserted: 1, ..Default::default() });
+    assert_eq!(table.get_ref_table_data(), &[
+        vec![DecodedData::StringU8("z".to_owned()), DecodedData::I32(9), DecodedData::Boolean(true)],
+    ][..]);
+
+    // Replacing needs every column of the table, in order.
+    let expected = table.clone();
+    assert!(replace_text(&mut table, "test_tables\t1\ncost\tkey\n10\ta\n").is_err());
+    assert_eq!(table, expected);
+}
+
+/// This function builds a definition with the provided fields.
+fn build_fields_definition(version: i32, fields: &[(&str, FieldType)]) -> Definition {
+    let mut definition = Definition::new(version);
+    *definition.get_ref_mut_fields() = fields.iter().map(|(name, field_type)| {
+        let mut field = Field::default();
+        field.set_name(name);
+        field.set_field_type(field_type.clone());
+        field
+    }).collect();
+    definition
+}
+
+/// This function builds a table with one field of each type, sequences included.
+fn build_all_types_table() -> Table {
+    let sequence_definition = build_fields_definition(0, &[("name", FieldType::StringU8)]);
+    let definition = build_fields_definition(2, &[
+        ("boolean", FieldType::Boolean),
+        ("f32", FieldType::F32),
+        ("f64", FieldType::F64),
+        ("i8", FieldType::I8),
+        ("i16", FieldType::I16),
+        ("i32", FieldType::I32),
+        ("i64", FieldType::I64),
+        ("u8", FieldType::U8),
+        ("u16", FieldType::U16),
+        ("u32", FieldType::U32),
+        ("colour", FieldType::ColourRGB),
+        ("string_u8", FieldType::StringU8),
+        ("string_u16", FieldType::StringU16),
+        ("optional_i16", FieldType::OptionalI16),
+        ("optional_i32", FieldType::OptionalI32),
+        ("optional_i64", FieldType::OptionalI64),
+        ("optional_string_u8", FieldType::OptionalStringU8),
+        ("optional_string_u16", FieldType::OptionalStringU16),
+        ("sequence_u16", FieldType::SequenceU16(sequence_definition.clone())),
+        ("sequence_u32", FieldType::SequenceU32(sequence_definition.clone())),
+    ]);
+
+    let mut sequence = Table::new(&sequence_definition);
+    sequence.set_table_data(&[vec![DecodedData::StringU8("inner".to_owned())]]).unwrap();
+
+    let mut table = Table::new(&definition);
+    table.set_table_data(&[vec![
+        DecodedData::Boolean(true),
+        DecodedData::F32(0.1),
+        DecodedData::F64(-1.5),
+        DecodedData::I8(-3),
+        DecodedData::I16(-300),
+        DecodedData::I32(70000),
+        DecodedData::I64(1 << 40),
+        DecodedData::U8(200),
+        DecodedData::U16(60000),
+        DecodedData::U32(4_000_000_000),
+        DecodedData::ColourRGB("FF8000".to_owned()),
+        DecodedData::StringU8("hu1_sword_and_shield".to_owned()),
+        DecodedData::StringU16("3k_main_ceo_trait_brave".to_owned()),
+        DecodedData::OptionalI16(Some(-7)),
+        DecodedData::OptionalI32(None),
+        DecodedData::OptionalI64(Some(42)),
+        DecodedData::OptionalStringU8(String::new()),
+        DecodedData::OptionalStringU16("humanoid01".to_owned()),
+        DecodedData::SequenceU16(sequence.clone()),
+        DecodedData::SequenceU32(Table::new(&sequence_definition)),
+    ]]).unwrap();
+    table
+}
+
+#[test]
+fn test_table_json() {
+    let table = build_all_types_table();
+    let versioned_file = VersionedFile::AnimTable(vec![table.get_definition()]);
+
+    // Exported tables must be imported exactly as they were.
+    let json = serde_json::to_string_pretty(&serde_json::json!({ "table": table })).unwrap();
+    assert_eq!(Table::from_json(&json, &versioned_file).unwrap(), table);
+    assert!(Table::from_json("{}", &versioned_file).is_err());
+
+    // Imported cells must follow the definition of the schema, sequences included.
+    let broken = json.replacen("\"I16\": -300", "\"I32\": -300", 1);
+    assert_ne!(broken, json);
+    assert!(Table::from_json(&broken, &versioned_file).is_err());
+    let broken = json.replacen("\"StringU8\": \"inner\"", "\"StringU16\": \"inner\"", 1);
+    assert_ne!(broken, json);
+    assert!(Table::from_json(&broken, &versioned_file).is_err());
+
+    // Rows with missing cells cannot be imported.
+    let mut broken = table.clone();
+    broken.entries[0].pop();
+    let broken = serde_json::json!({ "table": broken }).to_string();
+    match Table::from_json(&broken, &versioned_file) {
+        Err(error) => assert_eq!(error.kind(), &ErrorKind::TableRowWrongFieldCount(20, 19)),
+        Ok(_) => panic!("Rows with missing cells must be an error."),
+    }
+
+    // Versions without a definition, or with a different one, cannot be imported.
+    assert!(Table::from_json(&json, &VersionedFile::AnimTable(vec![])).is_err());
+    let mut definition = table.get_definition();
+    definition.get_ref_mut_fields()[0].set_name("bool");
+    assert!(Table::from_json(&json, &VersionedFile::AnimTable(vec![definition])).is_err());
+
+    // The Table-like PackedFiles go through the same import.
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::AnimFragment(vec![table.get_definition()]));
+    schema.add_versioned_file(&VersionedFile::AnimTable(vec![table.get_definition()]));
+    schema.add_versioned_file(&VersionedFile::MatchedCombat(vec![table.get_definition()]));
+    assert_eq!(AnimFragment::from_json(&AnimFragment::from(table.clone()).to_json(), &schema).unwrap().get_ref_table_data(), table.get_ref_table_data());
+    assert_eq!(AnimTable::from_json(&AnimTable::from(table.clone()).to_json(), &schema).unwrap().get_ref_table_data(), table.get_ref_table_data());
+    assert_eq!(MatchedCombat::from_json(&MatchedCombat::from(table.clone()).to_json(), &schema).unwrap().get_ref_table_data(), table.get_ref_table_data());
+}
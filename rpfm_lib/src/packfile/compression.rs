@@ -10,22 +10,14 @@
 
 // Here should go all the functions related to the compresion/decompression of PackedFiles.
 
-use xz2::read::XzDecoder;
-use xz2::stream::Stream;
+use xz2::read::{XzDecoder, XzEncoder};
+use xz2::stream::{LzmaOptions, Stream};
 
-use std::env::temp_dir;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::{BufReader, Read, SeekFrom};
-
-use std::process::Command;
+use std::io::Read;
 use std::u64;
 
 use rpfm_error::{Error, ErrorKind, Result};
 use crate::common::encoder::Encoder;
-use crate::common::decoder::Decoder;
-use crate::SETTINGS;
-use crate::settings::ZIP_PATH;
 
 /// This function decompress the data of a PackedFile, returning the decompressed data.
 pub fn decompress_data(data: &[u8]) -> Result<Vec<u8>> {
@@ -58,50 +50,28 @@ pub fn decompress_data(data: &[u8]) -> Result<Vec<u8>> {
 
 /// This function compress the data of a PackedFile, returning the compressed data.
 ///
-/// Now, some explanation: CA uses Non-Streamed LZMA1 (or LZMA Alone) compressed files.
-/// Xz, the `standard` linux lib to deal with LZMA files has a fucking exception for
-/// Non-Streamed LZMA1 files. So we can decode from it, but not encode to it.
-/// So we do it the hard way: write the uncompressed file to disk, call 7z, compress it
-/// to 7z LZMA1 Level 3 format, read the compressed file, and remove the 7z part.
-/// Sadly, this means we have to ship 7z with RPFM. But hey, we're not the ones doing a
-/// fucking exception to a known format because we don't want to support the original format.
+/// Now, some explanation: CA uses Non-Streamed LZMA1 (or LZMA Alone) compressed files, with a tweaked header.
+/// The normal LZMA Alone header is `properties (1 byte) + dictionary size (4 bytes) + uncompressed size (8 bytes)`,
+/// while CA's one is `uncompressed size (4 bytes) + properties (1 byte) + dictionary size (4 bytes)`.
+/// So we compress the data as a normal LZMA Alone stream (level 3, like vanilla files), then swap the header.
+///
+/// As this doesn't touch the disk or any external program, it's safe to call it from multiple threads at once.
 pub fn compress_data(data: &[u8]) -> Result<Vec<u8>> {
+    let options = LzmaOptions::new_preset(3).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeCompressed))?;
+    let stream = Stream::new_lzma_encoder(&options).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeCompressed))?;
+    let mut encoder = XzEncoder::new_stream(data, stream);
+    let mut compressed_data = vec![];
+    encoder.read_to_end(&mut compressed_data).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeCompressed))?;
+
+    // The LZMA Alone header is 13 bytes. If we got less than that, something went very wrong.
+    if compressed_data.len() < 13 {
+        return Err(ErrorKind::PackedFileDataCouldNotBeCompressed.into());
+    }
 
-    match SETTINGS.read().unwrap().paths.get(ZIP_PATH) {
-        Some(Some(zip_path)) => {
-
-            // Prepare both paths, uncompressed and compressed.
-            let mut uncompressed_path = temp_dir();
-            let mut compressed_path = temp_dir();
-            uncompressed_path.push("frodo_best_waifu");
-            compressed_path.push("frodo_bestest_waifu.7z");
-
-            // Get the data into the uncompressed file, and launch 7z.
-            File::create(&uncompressed_path)?.write_all(data)?;
-            Command::new(zip_path).arg("a").arg("-m0=lzma").arg("-mx=3").arg(&compressed_path).arg(&uncompressed_path).output()?;
-
-            // Get the compressed LZMA data (and only that data) from the compressed file. To get it, we know:
-            // - The header of a 7z file is 32 bytes.
-            // - The bytes 12-16 are the offset of the footer from the end of the header.
-            // - We have just one file, so the offset is the exact lenght of that file.
-            // - Then we read the offset from the end of the header. And done.
-            let mut reader = BufReader::new(File::open(&compressed_path)?);
-            let mut footer_offset = vec![0; 4];
-            reader.seek(SeekFrom::Start(12))?;
-            reader.read_exact(&mut footer_offset)?;
-            let compressed_data_lenght = footer_offset.decode_integer_u32(0)?;
-
-            let mut compressed_data = vec![0; compressed_data_lenght as usize];
-            reader.seek(SeekFrom::Start(32))?;
-            reader.read_exact(&mut compressed_data)?;
-
-            let mut fixed_data = vec![];
-            fixed_data.encode_integer_i32(data.len() as i32);
-            fixed_data.extend_from_slice(&[0x5D, 0x00, 0x00, 0x40, 0x00]);
-            fixed_data.append(&mut compressed_data);
+    let mut fixed_data = Vec::with_capacity(compressed_data.len() - 4);
+    fixed_data.encode_integer_u32(data.len() as u32);
+    fixed_data.extend_from_slice(&compressed_data[..5]);
+    fixed_data.extend_from_slice(&compressed_data[13..]);
 
-            Ok(fixed_data)
-        }
-        _ => Err(ErrorKind::ZipFolderNotFound.into())
-    }
+    Ok(fixed_data)
 }
@@ -9,17 +9,21 @@
 //---------------------------------------------------------------------------//
 
 // Here should be all the functions related with encryption/decryption.
+//
+// All the encryption used in PackFiles is XOR-based, so the encryption functions are just the decryption ones in reverse.
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::num::Wrapping;
 
+use rpfm_error::{ErrorKind, Result};
+
 // Old key used in Arena, and all the way back to Shogun 2.
 // static INDEX_STRING_KEY: &str = "L2{B3dPL7L*v&+Q3ZsusUhy[BGQn(Uq$f>JQdnvdlf{-K:>OssVDr#TlYU|13B}r";
 
 // Old key used in Arena's encrypted PackFiles.
 // static INDEX_U32_KEY: u32 = 0x1509_1984;
 
-// Decryption keys. Each one for a piece of the PackFile. The commented ones are old keys no longer used, but valid for old PackFiles.
+// Encryption/Decryption keys. Each one for a piece of the PackFile. The commented ones are old keys no longer used, but valid for old PackFiles.
 static INDEX_STRING_KEY: [u8; 64] = *b"#:AhppdV-!PEfz&}[]Nv?6w4guU%dF5.fq:n*-qGuhBJJBm&?2tPy!geW/+k#pG?";
 static INDEX_U32_KEY: u32 = 0xE10B_73F4;
 static DATA_KEY: Wrapping<u64> = Wrapping(0x8FEB_2A67_40A6_920E);
@@ -35,42 +39,74 @@ pub fn decrypt_index_item_file_length(ciphertext: u32, packed_files_after_this_o
 /// - 'ciphertext': the encrypted data of the PackedFile, read from the begining of the encrypted path.
 /// - 'decrypted_size': the decrypted size of the PackedFile.
 /// - 'offset': offset to know in what position of the index we should continue decoding the next entry.
-pub fn decrypt_index_item_filename(ciphertext: &[u8], decrypted_size: u8, offset: &mut usize) -> String {
+///
+/// If the path is not terminated before the end of the data, this returns an error.
+pub fn decrypt_index_item_filename(ciphertext: &[u8], decrypted_size: u8, offset: &mut usize) -> Result<String> {
     let mut path: String = String::new();
     let mut index = 0;
     loop {
-        let character = ciphertext[index] ^ !decrypted_size ^ INDEX_STRING_KEY[index % INDEX_STRING_KEY.len()];
+        let character = ciphertext.get(index).ok_or(ErrorKind::PackFileIndexesNotComplete)? ^ !decrypted_size ^ INDEX_STRING_KEY[index % INDEX_STRING_KEY.len()];
         index += 1;
         if character == 0 { break; }
         path.push(character as char);
     }
     *offset += index;
-    path
+    Ok(path)
 }
 
 // Function to decrypt a PackedFile's data. Just needs the data to decrypt.
 pub fn decrypt_packed_file(ciphertext: &[u8]) -> Vec<u8> {
+    xor_packed_file(ciphertext)
+}
+
+/// This function encrypts the size of a PackedFile. Requires:
+/// - 'plaintext': the size of the PackedFile.
+/// - 'packed_files_after_this_one': the amount of items after this one in the Index.
+///
+/// This is also used to encrypt the timestamps of the PackedFiles in the Index.
+pub fn encrypt_index_item_file_length(plaintext: u32, packed_files_after_this_one: u32) -> u32 {
+    !packed_files_after_this_one ^ plaintext ^ INDEX_U32_KEY
+}
+
+/// This function encrypts the path of a PackedFile, including its 00 terminator. Requires:
+/// - 'path': the path of the PackedFile, with `\` as separator.
+/// - 'decrypted_size': the decrypted size of the PackedFile.
+pub fn encrypt_index_item_filename(path: &str, decrypted_size: u8) -> Vec<u8> {
+    path.as_bytes().iter()
+        .chain(&[0])
+        .enumerate()
+        .map(|(index, character)| character ^ !decrypted_size ^ INDEX_STRING_KEY[index % INDEX_STRING_KEY.len()])
+        .collect()
+}
+
+// Function to encrypt a PackedFile's data. Just needs the data to encrypt.
+pub fn encrypt_packed_file(plaintext: &[u8]) -> Vec<u8> {
+    xor_packed_file(plaintext)
+}
+
+/// This function XORs the data of a PackedFile with the data key. Doing it once encrypts the data, doing it twice decrypts it.
+fn xor_packed_file(input: &[u8]) -> Vec<u8> {
 
     // First, make sure the file ends in a multiple of 8. If not, extend it with zeros.
     // We need it because the decoding is done in packs of 8 bytes.
-    let mut ciphertext = Vec::from(ciphertext);
-    let size = ciphertext.len();
+    let mut input = Vec::from(input);
+    let size = input.len();
     let padding = 8 - (size % 8);
-    if padding < 8 { ciphertext.resize(size + padding, 0) };
+    if padding < 8 { input.resize(size + padding, 0) };
 
     // Then decrypt the file in packs of 8. It's faster than in packs of 4.
-    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    let mut output = Vec::with_capacity(input.len());
     let mut edi: u32 = 0;
-    for _ in 0..ciphertext.len()/8 {
+    for _ in 0..input.len()/8 {
 
         let mut prod = (DATA_KEY * Wrapping(u64::from(!edi))).0;
         let esi = edi as usize;
-        prod ^= (&ciphertext[esi..esi + 8]).read_u64::<LittleEndian>().unwrap();
-        plaintext.write_u64::<LittleEndian>(prod).unwrap();
+        prod ^= (&input[esi..esi + 8]).read_u64::<LittleEndian>().unwrap();
+        output.write_u64::<LittleEndian>(prod).unwrap();
         edi += 8
     }
 
     // Remove the extra bytes we added in the first step.
-    plaintext.truncate(size);
-    plaintext
+    output.truncate(size);
+    output
 }
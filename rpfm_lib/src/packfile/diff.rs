@@ -0,0 +1,337 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to get the differences between two `PackFiles`.
+
+The diff is done at file level (added, removed, renamed and changed files), and for changed DB and Loc tables,
+also at row level, using the key columns of their definitions to match the rows of both tables.
+!*/
+
+use rayon::prelude::*;
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::mem::discriminant;
+
+use rpfm_error::Result;
+use rpfm_macros::*;
+
+use crate::packedfile::{DecodedPackedFile, PackedFileType};
+use crate::packedfile::table::{DecodedData, Table};
+use crate::packfile::PackFile;
+use crate::packfile::packedfile::PackedFile;
+use crate::schema::Schema;
+use crate::SCHEMA;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the differences between two `PackFiles`: a base one and a new one.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, Serialize, Deserialize)]
+pub struct PackFileDiff {
+
+    /// Paths of the `PackedFiles` only in the new `PackFile`.
+    added: Vec<Vec<String>>,
+
+    /// Paths of the `PackedFiles` only in the base `PackFile`.
+    removed: Vec<Vec<String>>,
+
+    /// Base/new paths of the `PackedFiles` that have been moved without changing their data.
+    renamed: Vec<(Vec<String>, Vec<String>)>,
+
+    /// `PackedFiles` in both `PackFiles` with different data.
+    changed: Vec<PackedFileDiff>,
+}
+
+/// This struct contains the differences of a `PackedFile` present in both `PackFiles`.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, Serialize, Deserialize)]
+pub struct PackedFileDiff {
+
+    /// Path of the `PackedFile`.
+    path: Vec<String>,
+
+    /// Size of the data of the `PackedFile` in the base `PackFile`, uncompressed.
+    size_base: u64,
+
+    /// Size of the data of the `PackedFile` in the new `PackFile`, uncompressed.
+    size_new: u64,
+
+    /// Row-level differences, if the `PackedFile` is a table we can decode.
+    table: Option<TableDiff>,
+}
+
+/// This struct contains the row-level differences between two versions of the same table.
+///
+/// Rows are matched by the value of their key columns. Added rows follow the columns of the new table,
+/// and removed rows the columns of the base table.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, Serialize, Deserialize)]
+pub struct TableDiff {
+
+    /// Version of the definition of the base table.
+    version_base: i32,
+
+    /// Version of the definition of the new table.
+    version_new: i32,
+
+    /// Columns used to match rows between both tables.
+    key_columns: Vec<String>,
+
+    /// Columns of the base table.
+    columns_base: Vec<String>,
+
+    /// Columns of the new table.
+    columns_new: Vec<String>,
+
+    /// Rows only in the new table.
+    added_rows: Vec<Vec<String>>,
+
+    /// Rows only in the base table.
+    removed_rows: Vec<Vec<String>>,
+
+    /// Rows in both tables, with changes in one or more of their columns.
+    changed_rows: Vec<RowDiff>,
+}
+
+/// This struct contains the changes of a row present in both tables.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, Serialize, Deserialize)]
+pub struct RowDiff {
+
+    /// Values of the key columns of the row.
+    key: Vec<String>,
+
+    /// Changed cells of the row.
+    cells: Vec<CellDiff>,
+}
+
+/// This struct contains the change of a cell of a row present in both tables.
+#[derive(Clone, Debug, Default, PartialEq, GetRef, Serialize, Deserialize)]
+pub struct CellDiff {
+
+    /// Name of the column of the cell.
+    column: String,
+
+    /// Value of the cell in the base table.
+    value_base: String,
+
+    /// Value of the cell in the new table.
+    value_new: String,
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `PackFileDiff`.
+impl PackFileDiff {
+
+    /// This function gets the differences between the base and new `PackFiles`.
+    ///
+    /// If there is a schema loaded, changed DB and Loc tables are also compared row by row.
+    pub fn new(base: &PackFile, new: &PackFile) -> Result<Self> {
+        let schema = SCHEMA.read().unwrap();
+        let mut diff = Self::default();
+
+        let mut removed = base.get_ref_packed_files_all().into_par_iter()
+            .filter(|packed_file| !new.packedfile_exists(packed_file.get_path()))
+            .collect::<Vec<&PackedFile>>();
+
+        let mut added = new.get_ref_packed_files_all().into_par_iter()
+            .filter(|packed_file| !base.packedfile_exists(packed_file.get_path()))
+            .collect::<Vec<&PackedFile>>();
+
+        // Files with the same path only need their data compared.
+        diff.changed = base.get_ref_packed_files_all().into_par_iter()
+            .filter_map(|packed_file| new.get_ref_packed_file_by_path(packed_file.get_path()).map(|packed_file_new| (packed_file, packed_file_new)))
+            .map(|(packed_file_base, packed_file_new)| {
+                let data_base = get_data(packed_file_base)?;
+                let data_new = get_data(packed_file_new)?;
+                if data_base == data_new { return Ok(None) }
+
+                let table = match PackedFileType::get_packed_file_type(packed_file_base.get_path()) {
+                    PackedFileType::DB | PackedFileType::Loc => {
+                        match (get_table(packed_file_base, schema.as_ref()), get_table(packed_file_new, schema.as_ref())) {
+                            (Some(table_base), Some(table_new)) => Some(TableDiff::new(&table_base, &table_new)),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+
+                Ok(Some(PackedFileDiff {
+                    path: packed_file_base.get_path().to_vec(),
+                    size_base: data_base.len() as u64,
+                    size_new: data_new.len() as u64,
+                    table,
+                }))
+            })
+            .collect::<Result<Vec<Option<PackedFileDiff>>>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        // Files removed from one path and added in another with the same data are considered renamed.
+        // If there are multiple candidates, the first one available is used.
+        let removed_hashes = removed.par_iter().map(|packed_file| get_data(packed_file).map(|data| get_hash(&data))).collect::<Result<Vec<u64>>>()?;
+        let added_hashes = added.par_iter().map(|packed_file| get_data(packed_file).map(|data| get_hash(&data))).collect::<Result<Vec<u64>>>()?;
+        let mut added_by_hash: HashMap<u64, VecDeque<usize>> = HashMap::new();
+        added_hashes.iter().enumerate().for_each(|(index, hash)| added_by_hash.entry(*hash).or_default().push_back(index));
+
+        let mut renamed_added = vec![false; added.len()];
+        let mut renamed_removed = vec![false; removed.len()];
+        for (index, hash) in removed_hashes.iter().enumerate() {
+            if let Some(index_added) = added_by_hash.get_mut(hash).and_then(|indexes| indexes.pop_front()) {
+                diff.renamed.push((removed[index].get_path().to_vec(), added[index_added].get_path().to_vec()));
+                renamed_removed[index] = true;
+                renamed_added[index_added] = true;
+            }
+        }
+
+        let mut index = 0;
+        removed.retain(|_| { index += 1; !renamed_removed[index - 1] });
+        let mut index = 0;
+        added.retain(|_| { index += 1; !renamed_added[index - 1] });
+
+        diff.removed = removed.iter().map(|packed_file| packed_file.get_path().to_vec()).collect();
+        diff.added = added.iter().map(|packed_file| packed_file.get_path().to_vec()).collect();
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.renamed.sort();
+        diff.changed.sort_by(|x, y| x.path.cmp(&y.path));
+        Ok(diff)
+    }
+
+    /// This function returns if both `PackFiles` have the same contents.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.renamed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Implementation of `TableDiff`.
+impl TableDiff {
+
+    /// This function gets the row-level differences between two versions of the same table.
+    ///
+    /// If none of the columns in both tables is a key, the entire row is used as key.
+    pub fn new(base: &Table, new: &Table) -> Self {
+        let fields_base = base.get_ref_definition().get_fields_processed();
+        let fields_new = new.get_ref_definition().get_fields_processed();
+        let columns_base = fields_base.iter().map(|field| field.get_name().to_owned()).collect::<Vec<String>>();
+        let columns_new = fields_new.iter().map(|field| field.get_name().to_owned()).collect::<Vec<String>>();
+
+        // Columns in both tables, as (position in base, position in new).
+        let columns_shared = columns_new.iter().enumerate()
+            .filter_map(|(index_new, column)| columns_base.iter().position(|x| x == column).map(|index_base| (index_base, index_new)))
+            .collect::<Vec<(usize, usize)>>();
+
+        let mut columns_key = columns_shared.iter().copied().filter(|(_, index_new)| fields_new[*index_new].get_is_key()).collect::<Vec<(usize, usize)>>();
+        if columns_key.is_empty() {
+            columns_key = columns_shared.to_vec();
+        }
+
+        // Index the base rows by key. Duplicated keys are matched in order.
+        let mut rows_base: HashMap<Vec<String>, VecDeque<usize>> = HashMap::new();
+        for (index, row) in base.get_ref_table_data().iter().enumerate() {
+            let key = columns_key.iter().map(|(index_base, _)| row[*index_base].data_to_string()).collect::<Vec<String>>();
+            rows_base.entry(key).or_default().push_back(index);
+        }
+
+        let mut added_rows = vec![];
+        let mut changed_rows = vec![];
+        let mut matched_base = vec![false; base.get_entry_count()];
+        for row_new in new.get_ref_table_data() {
+            let key = columns_key.iter().map(|(_, index_new)| row_new[*index_new].data_to_string()).collect::<Vec<String>>();
+            match rows_base.get_mut(&key).and_then(|indexes| indexes.pop_front()) {
+                Some(index_base) => {
+                    matched_base[index_base] = true;
+                    let row_base = &base.get_ref_table_data()[index_base];
+                    let cells = columns_shared.iter()
+                        .filter(|(index_base, index_new)| !is_cell_equal(&row_base[*index_base], &row_new[*index_new]))
+                        .map(|(index_base, index_new)| CellDiff {
+                            column: columns_new[*index_new].to_owned(),
+                            value_base: row_base[*index_base].data_to_string(),
+                            value_new: row_new[*index_new].data_to_string(),
+                        })
+                        .collect::<Vec<CellDiff>>();
+
+                    if !cells.is_empty() {
+                        changed_rows.push(RowDiff { key, cells });
+                    }
+                }
+                None => added_rows.push(row_new.iter().map(|x| x.data_to_string()).collect()),
+            }
+        }
+
+        let removed_rows = base.get_ref_table_data().iter()
+            .zip(matched_base.iter())
+            .filter(|(_, matched)| !**matched)
+            .map(|(row, _)| row.iter().map(|x| x.data_to_string()).collect())
+            .collect();
+
+        Self {
+            version_base: base.get_ref_definition().get_version(),
+            version_new: new.get_ref_definition().get_version(),
+            key_columns: columns_key.iter().map(|(_, index_new)| columns_new[*index_new].to_owned()).collect(),
+            columns_base,
+            columns_new,
+            added_rows,
+            removed_rows,
+            changed_rows,
+        }
+    }
+
+    /// This function returns if both tables have the same rows.
+    pub fn is_empty(&self) -> bool {
+        self.added_rows.is_empty() && self.removed_rows.is_empty() && self.changed_rows.is_empty()
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Utility functions
+//---------------------------------------------------------------------------//
+
+/// This function returns the data of a `PackedFile` as it would be saved, decompressed and decrypted.
+fn get_data(packed_file: &PackedFile) -> Result<Vec<u8>> {
+    match packed_file.get_ref_decoded().encode() {
+        Some(data) => data,
+        None => packed_file.get_raw_data(),
+    }
+}
+
+/// This function returns the hash of the provided data.
+fn get_hash(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// This function returns the table inside a DB or Loc `PackedFile`, decoding it if needed.
+fn get_table(packed_file: &PackedFile, schema: Option<&Schema>) -> Option<Table> {
+    let decoded = match packed_file.get_ref_decoded() {
+        DecodedPackedFile::Unknown => DecodedPackedFile::decode_no_locks(&mut packed_file.get_raw(), schema?).ok()?,
+        decoded => decoded.clone(),
+    };
+
+    match decoded {
+        DecodedPackedFile::DB(data) => Some(data.get_ref_table().clone()),
+        DecodedPackedFile::Loc(data) => Some(data.get_ref_table().clone()),
+        _ => None,
+    }
+}
+
+/// This function checks if two cells have the same value. Cells of different types are compared by their text representation.
+fn is_cell_equal(base: &DecodedData, new: &DecodedData) -> bool {
+    if discriminant(base) == discriminant(new) { base == new }
+    else { base.data_to_string() == new.data_to_string() }
+}
@@ -0,0 +1,210 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to resolve the load order of the PackFiles of a game.
+
+This takes the `/data` folder of a game and a list of mods, and gets which PackFile provides each file the game
+ends up loading, and which PackFiles had that same file but got shadowed by it. The rules used are:
+- `Mod` PackFiles are only loaded if they are in the list of mods. Any other type of PackFile in `/data` is always loaded.
+- Between PackFiles of different types, the ones of the type loaded last win, following the `PFHFileType` order.
+- Between PackFiles of the same type, the first one wins. PackFiles in `/data` are sorted by name,
+  and go before the mods, which keep the order of the list of mods.
+- Paths are compared ignoring case.
+
+!*/
+
+use std::collections::BTreeMap;
+use std::fs::{read_dir, read_to_string};
+use std::path::{Path, PathBuf};
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_macros::*;
+
+use crate::packfile::{PackFile, PFHFileType};
+
+/// Name of the file the launcher uses to tell the game which mods to load.
+pub const USED_MODS_FILE: &str = "used_mods.txt";
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the resolved load order of a game, with the files the game will end up loading.
+#[derive(Clone, Debug, Default, PartialEq, GetRef)]
+pub struct LoadOrder {
+
+    /// PackFiles loaded by the game, from highest to lowest priority.
+    pack_files: Vec<LoadOrderPackFile>,
+
+    /// Mods in the list of mods we couldn't find.
+    missing_mods: Vec<String>,
+
+    /// Files loaded by the game, by their lowercased path.
+    files: BTreeMap<String, LoadOrderFile>,
+}
+
+/// This struct represents a PackFile loaded by the game.
+#[derive(Clone, Debug, PartialEq, GetRef)]
+pub struct LoadOrderPackFile {
+
+    /// Path of the PackFile on disk.
+    path: PathBuf,
+
+    /// Type of the PackFile.
+    pfh_file_type: PFHFileType,
+}
+
+/// This struct represents a file loaded by the game, and from where it got loaded.
+#[derive(Clone, Debug, PartialEq, GetRef)]
+pub struct LoadOrderFile {
+
+    /// Path of the file within the PackFile that won.
+    path: Vec<String>,
+
+    /// Path on disk of the PackFile the game loads this file from.
+    pack_file: PathBuf,
+
+    /// Paths on disk of the PackFiles with this file that lost against `pack_file`, from highest to lowest priority.
+    shadowed_pack_files: Vec<PathBuf>,
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `LoadOrder`.
+impl LoadOrder {
+
+    /// This function resolves the load order of the PackFiles in the provided `/data` folder, with the provided list of mods.
+    ///
+    /// The list of mods must be sorted from highest to lowest priority. Each mod can be either a path, or the name of a PackFile in `/data`.
+    pub fn new(data_path: &Path, mods: &[PathBuf]) -> Result<Self> {
+        let mod_paths = mods.iter()
+            .map(|path| if path.is_file() { Some(path.to_path_buf()) } else { Some(data_path.join(path)).filter(|path| path.is_file()) })
+            .collect::<Vec<Option<PathBuf>>>();
+
+        let missing_mods = mods.iter().zip(mod_paths.iter())
+            .filter(|(_, path)| path.is_none())
+            .map(|(name, _)| name.to_string_lossy().to_string())
+            .collect();
+
+        Self::new_from_paths(data_path, &mod_paths.into_iter().flatten().collect::<Vec<PathBuf>>(), missing_mods)
+    }
+
+    /// This function resolves the load order of the PackFiles in the provided `/data` folder, with the mods of the provided `used_mods.txt` file.
+    ///
+    /// Mods are searched in the working directories of the file, in order, then in `/data`.
+    pub fn new_from_used_mods(data_path: &Path, used_mods_path: &Path) -> Result<Self> {
+        let (working_directories, mods) = Self::parse_used_mods(&read_to_string(used_mods_path)?);
+
+        let mut mod_paths = vec![];
+        let mut missing_mods = vec![];
+        for mod_name in &mods {
+            match working_directories.iter().chain(std::iter::once(&data_path.to_path_buf())).map(|path| path.join(mod_name)).find(|path| path.is_file()) {
+                Some(path) => mod_paths.push(path),
+                None => missing_mods.push(mod_name.to_owned()),
+            }
+        }
+
+        Self::new_from_paths(data_path, &mod_paths, missing_mods)
+    }
+
+    /// This function parses the contents of a `used_mods.txt` file, returning its working directories and its mods, in order.
+    pub fn parse_used_mods(data: &str) -> (Vec<PathBuf>, Vec<String>) {
+        let mut working_directories = vec![];
+        let mut mods = vec![];
+        for line in data.lines() {
+            let line = line.trim().trim_end_matches(';');
+            if let Some(index) = line.find(char::is_whitespace) {
+                let (command, value) = line.split_at(index);
+                let value = value.trim().trim_matches('"');
+                match command {
+                    "mod" => mods.push(value.to_owned()),
+                    "add_working_directory" => working_directories.push(PathBuf::from(value)),
+                    _ => {}
+                }
+            }
+        }
+
+        (working_directories, mods)
+    }
+
+    /// This function returns the file the game loads in the provided path, if any. The path is not case sensitive.
+    pub fn get_file(&self, path: &[String]) -> Option<&LoadOrderFile> {
+        self.files.get(&path.join("/").to_lowercase())
+    }
+
+    /// This function returns the files the game loads from the provided PackFile.
+    pub fn get_files_by_pack_file(&self, pack_file: &Path) -> Vec<&LoadOrderFile> {
+        self.files.values().filter(|file| file.pack_file == pack_file).collect()
+    }
+
+    /// This function returns all the files that are in more than one PackFile.
+    pub fn get_shadowed_files(&self) -> Vec<&LoadOrderFile> {
+        self.files.values().filter(|file| !file.shadowed_pack_files.is_empty()).collect()
+    }
+
+    /// This function resolves the load order with the mods already found on disk.
+    fn new_from_paths(data_path: &Path, mod_paths: &[PathBuf], missing_mods: Vec<String>) -> Result<Self> {
+
+        // Read all the PackFiles, skipping any disabled mod in `/data`. Lazy-loading means we only read their indexes here.
+        let mut data_paths = read_dir(data_path)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && path.extension().and_then(|extension| extension.to_str()) == Some("pack"))
+            .filter(|path| !mod_paths.contains(path))
+            .collect::<Vec<PathBuf>>();
+        data_paths.sort();
+
+        let mut pack_files = vec![];
+        for path in &data_paths {
+            let pack_file = PackFile::read(path, true)?;
+            if pack_file.get_pfh_file_type() != PFHFileType::Mod {
+                pack_files.push(pack_file);
+            }
+        }
+
+        for path in mod_paths {
+            pack_files.push(PackFile::read(path, true)?);
+        }
+
+        // Sort them by priority. The sort is stable, so PackFiles of the same type keep their order.
+        if pack_files.iter().any(|pack_file| matches!(pack_file.get_pfh_file_type(), PFHFileType::Other(_))) {
+            return Err(ErrorKind::PackFileTypeUknown.into());
+        }
+        pack_files.sort_by_key(|pack_file| std::cmp::Reverse(pack_file.get_pfh_file_type().get_value()));
+
+        let mut files: BTreeMap<String, LoadOrderFile> = BTreeMap::new();
+        for pack_file in &pack_files {
+            for path in pack_file.get_ref_packed_files_all_paths() {
+                let key = path.join("/").to_lowercase();
+                match files.get_mut(&key) {
+                    Some(file) => file.shadowed_pack_files.push(pack_file.get_file_path().to_path_buf()),
+                    None => {
+                        files.insert(key, LoadOrderFile {
+                            path: path.to_vec(),
+                            pack_file: pack_file.get_file_path().to_path_buf(),
+                            shadowed_pack_files: vec![],
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            pack_files: pack_files.iter().map(|pack_file| LoadOrderPackFile {
+                path: pack_file.get_file_path().to_path_buf(),
+                pfh_file_type: pack_file.get_pfh_file_type(),
+            }).collect(),
+            missing_mods,
+            files,
+        })
+    }
+}
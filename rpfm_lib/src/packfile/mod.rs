@@ -26,7 +26,7 @@ use serde_derive::{Serialize, Deserialize};
 use serde_json::{from_slice, to_string_pretty};
 use rayon::prelude::*;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::{fmt, fmt::Display};
 use std::fs::{DirBuilder, File};
 use std::io::{prelude::*, BufReader, BufWriter, SeekFrom, Read, Write};
@@ -44,14 +44,23 @@ use crate::common::{*, decoder::Decoder, encoder::Encoder};
 use crate::dependencies::Dependencies;
 use crate::packfile::compression::*;
 use crate::packfile::crypto::*;
+use crate::packfile::diff::PackFileDiff;
 use crate::packfile::packedfile::*;
+use crate::packfile::path_index::PathIndex;
 use crate::packedfile::{DecodedPackedFile, PackedFileType};
+use crate::packedfile::bmd::{BMD, DEFENSIVE_HILL_HINT, FORT_PERIMETER_HINT, MapReport, SIEGE_AREA_NODE_HINT};
+use crate::packedfile::table::{MergeReport, Table, TSVImportMode};
 use crate::packedfile::table::db::DB;
+use crate::packedfile::table::expression::MassEdit;
+use crate::packedfile::table::formats::TableFormat;
 use crate::packedfile::table::loc::{Loc, TSV_NAME_LOC};
 
 mod compression;
 mod crypto;
+pub mod diff;
+pub mod load_order;
 pub mod packedfile;
+mod path_index;
 
 #[cfg(test)]
 mod packfile_test;
@@ -71,14 +80,6 @@ const PFH0_PREAMBLE: &str = "PFH0"; // PFH0
 /// This one is the path in a `PackFile` where all the maps generated by Terry end up.
 const TERRY_MAP_PATH: [&str; 4] = ["terrain", "tiles", "battle", "_assembly_kit"];
 
-/// This one is the name of the main BMD data file used by maps exported from Terry.
-const DEFAULT_BMD_DATA: &str = "bmd_data.bin";
-
-/// These three hints are neccesary for the map patching function.
-const FORT_PERIMETER_HINT: &[u8; 18] = b"AIH_FORT_PERIMETER";
-const DEFENSIVE_HILL_HINT: &[u8; 18] = b"AIH_DEFENSIVE_HILL";
-const SIEGE_AREA_NODE_HINT: &[u8; 19] = b"AIH_SIEGE_AREA_NODE";
-
 pub const RESERVED_NAME_EXTRA_PACKFILE: &str = "extra_packfile.rpfm_reserved";
 pub const RESERVED_NAME_SETTINGS: &str = "settings.rpfm_reserved";
 pub const RESERVED_NAME_NOTES: &str = "notes.rpfm_reserved";
@@ -93,6 +94,12 @@ const AUTHORING_TOOL_CA: &str = "CA_TOOL";
 const AUTHORING_TOOL_RPFM: &str = "RPFM";
 const AUTHORING_TOOL_SIZE: u32 = 8;
 
+/// Size of the extended header of PackFiles with the `HAS_EXTENDED_HEADER` flag.
+const EXTENDED_HEADER_SIZE: usize = 20;
+
+/// Size of the extra data at the end of PFH5 PackFiles with the `HAS_EXTENDED_HEADER` flag.
+const EXTENDED_HEADER_FOOTER_SIZE: usize = 256;
+
 /// These are the types the PackFiles can have.
 const FILE_TYPE_BOOT: u32 = 0;
 const FILE_TYPE_RELEASE: u32 = 1;
@@ -103,8 +110,7 @@ bitflags! {
 
     /// This represents the bitmasks a PackFile can have applied to his type.
     ///
-    /// Keep in mind that this lib supports decoding PackFiles with any of these flags enabled,
-    /// but it only supports enconding for the `HAS_INDEX_WITH_TIMESTAMPS` flag.
+    /// Keep in mind that this lib supports both, decoding and encoding PackFiles with any of these flags enabled.
     pub struct PFHFlags: u32 {
 
         /// Used to specify that the header of the PackFile is extended by 20 bytes. Used in Arena.
@@ -156,6 +162,9 @@ pub struct PackFile {
     /// Extra subheader data, in case it's used in the future.
     extra_subheader_data: Vec<u8>,
 
+    /// Extended header data. Only used in PackFiles with the `HAS_EXTENDED_HEADER` flag.
+    extended_header: PFHExtendedHeader,
+
     /// The list of PackFiles this PackFile requires to be loaded before himself when starting the game.
     ///
     /// In other places, we refer to this as the `Dependency List`.
@@ -164,6 +173,9 @@ pub struct PackFile {
     /// The list of PackedFiles this PackFile contains.
     packed_files: Vec<PackedFile>,
 
+    /// Index of the positions of the PackedFiles by path. It has to be kept in sync with the list of PackedFiles.
+    path_index: PathIndex,
+
     /// Notes added to the PackFile. Exclusive of this lib.
     notes: Option<String>,
 
@@ -199,6 +211,55 @@ pub struct PackFileInfo {
     pub timestamp: i64,
 }
 
+/// This struct represents the data of the extended header, used by PackFiles with the `HAS_EXTENDED_HEADER` flag (Arena).
+///
+/// The extended header is 20 bytes long, and goes right after the normal header. We don't know what most of its values mean,
+/// so we keep them as they are to write them back when saving. PFH5 PackFiles with this flag also have 256 bytes of extra data at the end.
+#[derive(Clone, Debug, PartialEq, Eq, GetRef, GetRefMut, Set)]
+pub struct PFHExtendedHeader {
+
+    /// First value of the extended header. Meaning unknown.
+    unknown_1: u32,
+
+    /// Second value of the extended header. Meaning unknown.
+    unknown_2: u32,
+
+    /// Third value of the extended header. Meaning unknown.
+    unknown_3: u32,
+
+    /// Fourth value of the extended header. Meaning unknown.
+    unknown_4: u32,
+
+    /// Fifth value of the extended header. Meaning unknown.
+    unknown_5: u32,
+
+    /// Data at the end of the PackFile, after the data of the last PackedFile. Only in PFH5 PackFiles.
+    footer: Vec<u8>,
+}
+
+/// This struct contains everything that was wrong with a PackFile read with `PackFile::read_and_recover`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, GetRef)]
+pub struct PackFileRecoveryReport {
+
+    /// PackedFiles in the index which data was not fully in the PackFile, with the size they should have.
+    lost_packed_files: Vec<(Vec<String>, u32)>,
+
+    /// Amount of PackedFiles we couldn't read from the index, according to the header.
+    unreadable_packed_files: u32,
+
+    /// If the indexes were truncated or had entries we couldn't decode.
+    damaged_index: bool,
+
+    /// If the subheader was invalid and had to be replaced with a default one. Only in PFH6 PackFiles.
+    invalid_subheader: bool,
+
+    /// PackedFile count in the header and amount of PackedFiles actually found in the index, if they don't match.
+    wrong_packed_file_count: Option<(u32, u32)>,
+
+    /// Size of the PackFile and size it should have according to its indexes, if they don't match.
+    wrong_size: Option<(u64, u64)>,
+}
+
 /// This struct represents the entire **Manifest.txt** from the /data folder.
 ///
 /// Private for now, because I see no public use for this.
@@ -290,8 +351,8 @@ pub enum PathType {
 
 /// This enum indicates the current state of the compression in the current PackFile.
 ///
-/// Despite compression being per-packedfile, we only support applying it to the full PackFile for now.
-/// Also, compression is only supported by `PFHVersion::PFH5` PackFiles.
+/// Compression is per-packedfile, so a PackFile can have only part of its PackedFiles compressed.
+/// Also, compression is only supported by `PFHVersion::PFH5` and `PFHVersion::PFH6` PackFiles.
 #[derive(Debug, Clone, PartialEq)]
 pub enum CompressionState {
 
@@ -305,6 +366,20 @@ pub enum CompressionState {
     Disabled,
 }
 
+/// This enum represents a rule of the compression policy of a PackFile, stored in its settings.
+///
+/// The policy is applied when saving, on top of the compression flag of each PackedFile: a PackedFile matching any rule
+/// is never compressed, even if it has been marked to be compressed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CompressionIgnoreRule {
+
+    /// Lowercased path of a file or folder. Any PackedFile in that path is never compressed.
+    Path(Vec<String>),
+
+    /// Lowercased extension. Any PackedFile with a name ending with it is never compressed.
+    Extension(String),
+}
+
 /// This struct hold PackFile-specific settings.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PackFileSettings {
@@ -504,9 +579,11 @@ impl PackFile {
             build_number: 0,
             authoring_tool: AUTHORING_TOOL_RPFM.to_owned(),
             extra_subheader_data: vec![0; 256],
+            extended_header: PFHExtendedHeader::default(),
 
             pack_files: vec![],
             packed_files: vec![],
+            path_index: PathIndex::default(),
 
             notes: None,
             settings: PackFileSettings::default(),
@@ -525,15 +602,32 @@ impl PackFile {
             build_number: 0,
             authoring_tool: AUTHORING_TOOL_RPFM.to_owned(),
             extra_subheader_data: vec![0; 256],
+            extended_header: PFHExtendedHeader::default(),
 
             pack_files: vec![],
             packed_files: vec![],
+            path_index: PathIndex::default(),
 
             notes: None,
             settings: PackFileSettings::default(),
         }
     }
 
+    /// This function returns a reference to the extended header of this PackFile. Only used if the PackFile has the `HAS_EXTENDED_HEADER` flag.
+    pub fn get_ref_extended_header(&self) -> &PFHExtendedHeader {
+        &self.extended_header
+    }
+
+    /// This function returns a mutable reference to the extended header of this PackFile. Only used if the PackFile has the `HAS_EXTENDED_HEADER` flag.
+    pub fn get_ref_mut_extended_header(&mut self) -> &mut PFHExtendedHeader {
+        &mut self.extended_header
+    }
+
+    /// This function sets the extended header of this PackFile. Only used if the PackFile has the `HAS_EXTENDED_HEADER` flag.
+    pub fn set_extended_header(&mut self, extended_header: PFHExtendedHeader) {
+        self.extended_header = extended_header;
+    }
+
     /// This function returns a list of reserved PackedFile names, used by RPFM for special porpouses.
     pub fn get_reserved_packed_file_names() -> Vec<Vec<String>> {
         RESERVED_PACKED_FILE_NAMES.iter().map(|x| vec![(*x).to_string()]).collect()
@@ -612,7 +706,7 @@ impl PackFile {
         let mut destination_paths = Vec::with_capacity(packed_files.len());
         let packed_files = packed_files.par_iter()
             .map(|x| (x.get_path(), *x,
-                self.packed_files.par_iter().position_any(|y| x.get_path() == y.get_path())
+                self.get_packed_file_position(x.get_path())
             )).collect::<Vec<(&[String], &PackedFile, Option<usize>)>>();
 
         // Get all the PackedFiles that are not in conflict with the ones we already have in our PackFile,
@@ -630,7 +724,7 @@ impl PackFile {
         destination_paths.append(&mut packed_files_new.par_iter()
             .map(|packed_file| packed_file.get_path().to_vec())
             .collect::<Vec<Vec<String>>>());
-        self.packed_files.append(&mut packed_files_new);
+        packed_files_new.drain(..).for_each(|packed_file| self.push_packed_file(packed_file));
 
 
         // Now we deal with the problematic ones. If we set them to overwrite the conflicting files...we just replace them.
@@ -679,7 +773,7 @@ impl PackFile {
             destination_paths.append(&mut packed_files_conflict.par_iter()
                 .map(|packed_file| packed_file.get_path().to_vec())
                 .collect::<Vec<Vec<String>>>());
-            self.packed_files.append(&mut packed_files_conflict);
+            packed_files_conflict.drain(..).for_each(|packed_file| self.push_packed_file(packed_file));
         }
 
         Ok(destination_paths)
@@ -886,7 +980,7 @@ impl PackFile {
     /// This function returns if the `PackFile` is editable or not.
     ///
     /// By *if is editable or not* I mean *If you can save it or not*. The conditions under which a PackFile is not editable are:
-    /// - All PackFiles with extended header or encrypted parts are not editable.
+    /// - All PackFiles of type `Other` are not editable.
     /// - All PackFiles of type `Mod` or `Movie` are editable.
     /// - If you say CA PackFiles are not editable:
     ///   - All PackFiles of type `Boot`, `Release` or `Patch` are not editable.
@@ -896,11 +990,6 @@ impl PackFile {
 
         // If it's this very specific type, don't save under any circunstance.
         if let PFHFileType::Other(_) = self.pfh_file_type { false }
-
-        // If ANY of these bitmask is detected in the PackFile, disable all saving.
-        else if self.bitmask.contains(PFHFlags::HAS_ENCRYPTED_DATA) ||
-            self.bitmask.contains(PFHFlags::HAS_ENCRYPTED_INDEX) ||
-            self.bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER) { false }
         else {
             self.pfh_file_type == PFHFileType::Mod ||
             self.pfh_file_type == PFHFileType::Movie ||
@@ -910,57 +999,60 @@ impl PackFile {
 
     /// This function returns a copy to the `PackedFile` with the provided path, if exists.
     pub fn get_packed_file_by_path(&self, path: &[String]) -> Option<PackedFile> {
-        self.packed_files.par_iter().filter(|x| x.get_path() == path).cloned().find_any(|x| x.get_path() == path)
+        self.get_packed_file_position(path).map(|position| self.packed_files[position].clone())
     }
 
     /// This function returns a reference to the `PackedFile` with the provided path, if exists.
     pub fn get_ref_packed_file_by_path(&self, path: &[String]) -> Option<&PackedFile> {
-        self.packed_files.par_iter().find_any(|x| x.get_path() == path)
+        self.get_packed_file_position(path).map(|position| &self.packed_files[position])
     }
 
     /// This function returns a mutable reference to the `PackedFile` with the provided path, if exists.
     pub fn get_ref_mut_packed_file_by_path(&mut self, path: &[String]) -> Option<&mut PackedFile> {
-        self.packed_files.par_iter_mut().find_any(|x| x.get_path() == path)
+        let position = self.get_packed_file_position(path)?;
+        Some(&mut self.packed_files[position])
     }
 
     /// This function returns a copy of all the `PackedFiles` in the provided paths.
     pub fn get_packed_files_by_paths(&self, paths: Vec<&[String]>) -> Vec<PackedFile> {
-        self.packed_files.par_iter().filter(|x| paths.contains(&x.get_path())).cloned().collect()
+        self.get_packed_files_positions(&paths).iter().map(|position| self.packed_files[*position].clone()).collect()
     }
 
     /// This function returns a reference of all the `PackedFiles` in the provided paths.
     pub fn get_ref_packed_files_by_paths(&self, paths: Vec<&[String]>) -> Vec<&PackedFile> {
-        self.packed_files.par_iter().filter(|x| paths.contains(&x.get_path())).collect()
+        self.get_packed_files_positions(&paths).iter().map(|position| &self.packed_files[*position]).collect()
     }
 
     /// This function returns a mutable reference of all the `PackedFiles` in the provided paths.
     pub fn get_ref_mut_packed_files_by_paths(&mut self, paths: Vec<&[String]>) -> Vec<&mut PackedFile> {
-        self.packed_files.par_iter_mut().filter(|x| paths.contains(&x.get_path())).collect()
+        let positions = self.get_packed_files_positions(&paths);
+        self.get_ref_mut_packed_files_by_positions(&positions)
     }
 
     /// This function returns a copy of all the `PackedFiles` starting with the provided path.
     pub fn get_packed_files_by_path_start(&self, path: &[String]) -> Vec<PackedFile> {
-        self.packed_files.par_iter().filter(|x| x.get_path().starts_with(path) && !path.is_empty() && x.get_path().len() > path.len()).cloned().collect()
+        self.get_packed_files_positions_by_path_start(path).iter().map(|position| self.packed_files[*position].clone()).collect()
     }
 
     /// This function returns a reference of all the `PackedFiles` starting with the provided path.
     pub fn get_ref_packed_files_by_path_start(&self, path: &[String]) -> Vec<&PackedFile> {
-        self.packed_files.par_iter().filter(|x| x.get_path().starts_with(path) && !path.is_empty() && x.get_path().len() > path.len()).collect()
+        self.get_packed_files_positions_by_path_start(path).iter().map(|position| &self.packed_files[*position]).collect()
     }
 
     /// This function returns a mutable reference of all the `PackedFiles` starting with the provided path.
     pub fn get_ref_mut_packed_files_by_path_start(&mut self, path: &[String]) -> Vec<&mut PackedFile> {
-        self.packed_files.par_iter_mut().filter(|x| x.get_path().starts_with(path) && !path.is_empty() && x.get_path().len() > path.len()).collect()
+        let positions = self.get_packed_files_positions_by_path_start(path);
+        self.get_ref_mut_packed_files_by_positions(&positions)
     }
 
     /// This function returns a copy of the paths of all the `PackedFiles` in the provided `PackFile` under the provided path.
     pub fn get_packed_files_paths_by_path_start(&self, path: &[String]) -> Vec<Vec<String>> {
-        self.packed_files.par_iter().map(|x| x.get_path()).filter(|x| x.starts_with(path) && !path.is_empty() && x.len() > path.len()).map(|x| x.to_vec()).collect()
+        self.get_packed_files_positions_by_path_start(path).iter().map(|position| self.packed_files[*position].get_path().to_vec()).collect()
     }
 
     /// This function returns a reference of the paths of all the `PackedFiles` in the provided `PackFile` under the provided path.
     pub fn get_ref_packed_files_paths_by_path_start(&self, path: &[String]) -> Vec<&[String]> {
-        self.packed_files.par_iter().map(|x| x.get_path()).filter(|x| x.starts_with(path) && !path.is_empty() && x.len() > path.len()).collect()
+        self.get_packed_files_positions_by_path_start(path).iter().map(|position| self.packed_files[*position].get_path()).collect()
     }
 
     /// This function returns a copy of all the `PackedFiles` ending with the provided path.
@@ -1097,7 +1189,7 @@ impl PackFile {
 
     /// This function returns a copy of the `PackedFileInfo` of the `Packedfile` in the provided path.
     pub fn get_packed_file_info_by_path(&self, path: &[String]) -> Option<PackedFileInfo> {
-        self.packed_files.par_iter().find_first(|x| x.get_path() == path).map(From::from)
+        self.get_packed_file_position(path).map(|position| From::from(&self.packed_files[position]))
     }
 
     pub fn get_packed_files_by_path_type(&mut self, path_types: &[PathType]) -> Vec<PackedFile> {
@@ -1148,21 +1240,15 @@ impl PackFile {
 
     /// This function removes, if exists, a `PackedFile` with the provided path from the `PackFile`.
     pub fn remove_packed_file_by_path(&mut self, path: &[String]) {
-        if let Some(position) = self.packed_files.par_iter().position_any(|x| x.get_path() == path) {
-            self.packed_files.remove(position);
+        if let Some(position) = self.get_packed_file_position(path) {
+            self.remove_packed_files_by_positions(&[position]);
         }
     }
 
     /// This function removes, if exists, all `PackedFile` starting with the provided path from the `PackFile`.
     pub fn remove_packed_files_by_path_start(&mut self, path: &[String]) {
-        let positions: Vec<usize> = self.packed_files.iter()
-            .enumerate()
-            .filter(|x| x.1.get_path().starts_with(path) && !path.is_empty() && x.1.get_path().len() > path.len())
-            .map(|x| x.0)
-            .collect();
-        for position in positions.iter().rev() {
-            self.packed_files.remove(*position);
-        }
+        let positions = self.get_packed_files_positions_by_path_start(path);
+        self.remove_packed_files_by_positions(&positions);
     }
 
     /// This function removes, if exists, all `PackedFile` ending with the provided path from the `PackFile`.
@@ -1172,9 +1258,7 @@ impl PackFile {
             .filter(|x| x.1.get_path().ends_with(path) && !path.is_empty())
             .map(|x| x.0)
             .collect();
-        for position in positions.iter().rev() {
-            self.packed_files.remove(*position);
-        }
+        self.remove_packed_files_by_positions(&positions);
     }
 
     /// This function removes, if exists, all `PackedFile` of the provided types from the `PackFile`.
@@ -1197,15 +1281,17 @@ impl PackFile {
         // Then we act, depending on the combination of items.
         match contents {
 
-            // Any combination of files and folders.
+            // Any combination of files and folders. We remove them all at once, so we only have to update the path index once.
             1 | 2 | 3 => {
+                let mut positions = vec![];
                 for item_type in &item_types_clean {
                     match item_type {
-                        PathType::File(path) => self.remove_packed_file_by_path(path),
-                        PathType::Folder(path) => self.remove_packed_files_by_path_start(path),
+                        PathType::File(path) => positions.extend(self.get_packed_file_position(path)),
+                        PathType::Folder(path) => positions.append(&mut self.get_packed_files_positions_by_path_start(path)),
                         _ => unreachable!(),
                     }
                 }
+                self.remove_packed_files_by_positions(&positions);
             },
 
             // If the `PackFile` is selected, just delete everything.
@@ -1337,11 +1423,38 @@ impl PackFile {
         Ok(files_extracted)
     }
 
-    /// This function enables/disables compression in all `PackedFiles` inside the `PackFile`. Partial compression is not supported.
+    /// This function enables/disables compression in all `PackedFiles` inside the `PackFile`.
     pub fn toggle_compression(&mut self, enable: bool) {
         self.packed_files.par_iter_mut().for_each(|x| x.get_ref_mut_raw().set_should_be_compressed(enable));
     }
 
+    /// This function enables/disables compression in all `PackedFiles` covered by the provided `PathTypes`.
+    ///
+    /// If any of the `PathTypes` is the whole `PackFile`, this is the same as `toggle_compression`. Empty folders match nothing.
+    pub fn set_compression_by_path_types(&mut self, path_types: &[PathType], enable: bool) {
+        let path_types = PathType::dedup(path_types);
+        if path_types.contains(&PathType::PackFile) {
+            return self.toggle_compression(enable);
+        }
+
+        self.packed_files.par_iter_mut()
+            .filter(|packed_file| path_types.iter().any(|path_type| match path_type {
+                PathType::File(path) => packed_file.get_path() == &**path,
+                PathType::Folder(path) => !path.is_empty() && packed_file.get_path().starts_with(path) && packed_file.get_path().len() > path.len(),
+                _ => false,
+            }))
+            .for_each(|packed_file| packed_file.get_ref_mut_raw().set_should_be_compressed(enable));
+    }
+
+    /// This function enables/disables compression in all `PackedFiles` of the provided `PackedFileTypes`.
+    ///
+    /// Subtypes are ignored, so any `Text` type covers every text PackedFile, regardless of its kind.
+    pub fn set_compression_by_packed_file_types(&mut self, packed_file_types: &[PackedFileType], enable: bool) {
+        self.packed_files.par_iter_mut()
+            .filter(|packed_file| PackedFileType::get_packed_file_type(packed_file.get_path()).eq_non_strict_slice(packed_file_types))
+            .for_each(|packed_file| packed_file.get_ref_mut_raw().set_should_be_compressed(enable));
+    }
+
     /// This function returns the notes contained within the provided `PackFile`.
     pub fn get_notes(&self) -> &Option<String> {
         &self.notes
@@ -1415,16 +1528,69 @@ impl PackFile {
     /// This function remove all `PackedFiles` from a `PackFile`.
     pub fn remove_all_packedfiles(&mut self) {
         self.packed_files = vec![];
+        self.path_index = PathIndex::default();
+    }
+
+    /// This function returns the position of the `PackedFile` with the provided path in the list of `PackedFiles`, if exists.
+    fn get_packed_file_position(&self, path: &[String]) -> Option<usize> {
+        self.path_index.get_positions(path).iter().copied().find(|position| self.packed_files[*position].get_path() == path)
+    }
+
+    /// This function returns the sorted positions of the `PackedFiles` with the provided paths in the list of `PackedFiles`.
+    fn get_packed_files_positions(&self, paths: &[&[String]]) -> Vec<usize> {
+        let mut positions = paths.iter().filter_map(|path| self.get_packed_file_position(path)).collect::<Vec<usize>>();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    /// This function returns the sorted positions of the `PackedFiles` inside the provided folder in the list of `PackedFiles`.
+    fn get_packed_files_positions_by_path_start(&self, path: &[String]) -> Vec<usize> {
+        self.path_index.get_positions_by_path_start(path)
+    }
+
+    /// This function returns a mutable reference to the `PackedFiles` in the provided sorted positions.
+    fn get_ref_mut_packed_files_by_positions(&mut self, positions: &[usize]) -> Vec<&mut PackedFile> {
+        self.packed_files.iter_mut()
+            .enumerate()
+            .filter(|(position, _)| positions.binary_search(position).is_ok())
+            .map(|(_, packed_file)| packed_file)
+            .collect()
+    }
+
+    /// This function adds a `PackedFile` at the end of the list of `PackedFiles`, updating the path index.
+    ///
+    /// This doesn't check for conflicts, so make sure there is no other `PackedFile` with the same path.
+    fn push_packed_file(&mut self, packed_file: PackedFile) {
+        self.path_index.insert(packed_file.get_path(), self.packed_files.len());
+        self.packed_files.push(packed_file);
+    }
+
+    /// This function removes the `PackedFiles` in the provided positions, then rebuilds the path index.
+    fn remove_packed_files_by_positions(&mut self, positions: &[usize]) {
+        if positions.is_empty() { return }
+        let positions = positions.iter().copied().collect::<HashSet<usize>>();
+        let mut position = 0;
+        self.packed_files.retain(|_| {
+            position += 1;
+            !positions.contains(&(position - 1))
+        });
+        self.rebuild_path_index();
+    }
+
+    /// This function rebuilds the path index from scratch. Needed after anything that changes the order of the `PackedFiles`.
+    fn rebuild_path_index(&mut self) {
+        self.path_index = PathIndex::new(&self.packed_files.iter().map(|x| x.get_path()).collect::<Vec<&[String]>>());
     }
 
     /// This function checks if a `PackedFile` with a certain path exists in a `PackFile`.
     pub fn packedfile_exists(&self, path: &[String]) -> bool {
-        self.packed_files.par_iter().any(|x| x.get_path() == path)
+        self.get_packed_file_position(path).is_some()
     }
 
     /// This function checks if a folder with `PackedFiles` in it exists in a `PackFile`.
     pub fn folder_exists(&self, path: &[String]) -> bool {
-        self.packed_files.par_iter().any(|x| x.get_path().starts_with(path) && !path.is_empty() && x.get_path().len() > path.len())
+        self.path_index.folder_exists(path)
     }
 
     /// This function takes an slice of PathTypes and turns it into a vector of individual PackedFile's paths.
@@ -1509,10 +1675,12 @@ impl PackFile {
             }
         }
 
-        // Then just change the path of the `PackedFile` if exists. Return error if it doesn't.
-        match self.get_ref_mut_packed_file_by_path(source_path) {
-            Some(packed_file) => {
-                packed_file.get_ref_mut_raw().set_path(&destination_path)?;
+        // Then just change the path of the `PackedFile` if exists, and update the path index. Return error if it doesn't.
+        match self.get_packed_file_position(source_path) {
+            Some(position) => {
+                self.packed_files[position].get_ref_mut_raw().set_path(&destination_path)?;
+                self.path_index.remove(source_path, position);
+                self.path_index.insert(&destination_path, position);
                 Ok(destination_path)
             },
             None => Err(ErrorKind::PackedFileNotFound.into())
@@ -1597,6 +1765,13 @@ impl PackFile {
         successes
     }
 
+    /// This function returns the differences between this `PackFile` and the provided one, using this one as the base `PackFile`.
+    ///
+    /// For changed DB and Loc tables, if there is a schema loaded, it also returns their row-level differences.
+    pub fn diff(&self, other: &Self) -> Result<PackFileDiff> {
+        PackFileDiff::new(self, other)
+    }
+
     /// This function merges (if possible) the provided DB and LOC tables into one with the provided name.
     ///
     /// NOTE: The merged table will be created in the folder of the first provided file.
@@ -1765,19 +1940,39 @@ impl PackFile {
             let name = path.last().unwrap().clone();
 
             // The files we need to process are `bmd_data.bin` and all the `catchment_` files the map has.
-            if name == DEFAULT_BMD_DATA || (name.starts_with("catchment_") && name.ends_with(".bin")) {
+            if BMD::is_bmd(&name) {
                 let data = packed_file.get_ref_mut_raw().get_ref_mut_data_and_keep_it()?;
+                let mut bmd = match BMD::read(data) {
+                    Ok(bmd) => bmd,
+
+                    // If we cannot decode the file (unsupported version, broken file,...) we fall back to patching its bytes directly.
+                    // Both hints have the same length, so replacing one with the other doesn't move anything else in the file.
+                    Err(_) => {
+                        if data.windows(SIEGE_AREA_NODE_HINT.len()).any(|window| window == SIEGE_AREA_NODE_HINT.as_bytes()) {
+                            if let Some(index) = data.windows(DEFENSIVE_HILL_HINT.len()).position(|window| window == DEFENSIVE_HILL_HINT.as_bytes()) {
+                                data.splice(index..index + DEFENSIVE_HILL_HINT.len(), FORT_PERIMETER_HINT.bytes());
+                                files_patched += 1;
+                            }
 
-                // The patching process it's simple. First, we check if there is SiegeAI stuff in the file by checking if there is an Area Node.
-                // If we find one, we check if there is a defensive hill hint in the same file, and patch it if there is one.
-                if data.windows(19).any(|window: &[u8]|window == SIEGE_AREA_NODE_HINT) {
-                    if let Some(index) = data.windows(18).position(|window: &[u8]|window == DEFENSIVE_HILL_HINT) {
-                        data.splice(index..index + 18, FORT_PERIMETER_HINT.iter().cloned());
+                            if data.windows(DEFENSIVE_HILL_HINT.len()).any(|window| window == DEFENSIVE_HILL_HINT.as_bytes()) {
+                                multiple_defensive_hill_hints = true;
+                            }
+                        }
+                        continue;
+                    }
+                };
+
+                // The patching process it's simple. First, we check if there is SiegeAI stuff in the AI hints of the file by checking if there is an Area Node.
+                // If we find one, we check if there is a defensive hill hint in the same file, and turn it into a fort perimeter if there is one.
+                // Only the AI hints section is re-encoded, so the rest of the file is saved as it was.
+                if bmd.has_ai_hint(SIEGE_AREA_NODE_HINT) {
+                    if bmd.replace_ai_hints(DEFENSIVE_HILL_HINT, FORT_PERIMETER_HINT, 1) > 0 {
+                        *data = bmd.save()?;
                         files_patched += 1;
                     }
 
                     // If there is more than one defensive hill in one file, is a valid file, but we want to warn the user about it.
-                    if data.windows(18).any(|window: &[u8]|window == DEFENSIVE_HILL_HINT) {
+                    if bmd.has_ai_hint(DEFENSIVE_HILL_HINT) {
                         multiple_defensive_hill_hints = true;
                     }
                 }
@@ -1841,13 +2036,39 @@ impl PackFile {
     }
 
 
-    /// This function is used to Mass-Import TSV files into a PackFile.
+    /// This function returns a report of what each map exported by Terry in the `PackFile` contains, built from their BMD files.
+    ///
+    /// Maps are identified by the folder their BMD files are in.
+    pub fn get_map_reports(&self) -> Result<Vec<MapReport>> {
+        let mut reports: BTreeMap<Vec<String>, MapReport> = BTreeMap::new();
+        for packed_file in self.get_ref_packed_files_by_path_start(&Self::get_terry_map_path()) {
+            let path = packed_file.get_path();
+            if BMD::is_bmd(path.last().unwrap()) {
+                let bmd = BMD::read(&packed_file.get_ref_raw().get_data()?)?;
+                let map_path = &path[..path.len() - 1];
+                reports.entry(map_path.to_vec()).or_insert_with(|| MapReport::new(map_path)).add_bmd(path, &bmd);
+            }
+        }
+
+        Ok(reports.values().cloned().collect())
+    }
+
+
+    /// This function is used to Mass-Import TSV, JSON, CSV or XLSX files into a PackFile.
+    ///
+    /// When replacing, each file is imported as a new table, which replaces the one in the same path if `overwrite` is true,
+    /// or gets a new name if it's false. When merging, each TSV file is merged into the table with the same path, which is created
+    /// empty if it doesn't exist yet, and files with the same path are merged on top of each other. Only TSV files can be merged.
+    /// If any of the files cannot be imported, the PackFile is left untouched.
+    ///
+    /// It returns the paths of the replaced tables, the paths of the imported tables, and the sum of the reports of all the files.
     pub fn mass_import_tsv(
         &mut self,
         tsv_paths: &[PathBuf],
         name: Option<String>,
-        overwrite: bool
-    ) -> Result<(Vec<Vec<String>>, Vec<Vec<String>>)> {
+        overwrite: bool,
+        mode: TSVImportMode,
+    ) -> Result<(Vec<Vec<String>>, Vec<Vec<String>>, MergeReport)> {
 
         // Create the following lists:
         // - PackedFiles to add.
@@ -1856,104 +2077,106 @@ impl PackFile {
         let mut packed_files: Vec<PackedFile> = vec![];
         let mut packed_files_to_remove = vec![];
         let mut error_files = vec![];
+        let mut report = MergeReport::default();
 
         // If there is not a schema, don't do anything.
         if let Some(ref schema) = *SCHEMA.read().unwrap() {
             for path in tsv_paths {
 
-                // The first row has the PackedFile Type (or name, in case of tables) and version.
-                // The second row contains the column names, and it can be ignored.
-                let mut tsv = String::new();
-                BufReader::new(File::open(&path)?).read_to_string(&mut tsv)?;
-
-                // We get his first line, if it has it. Otherwise, we return an error in this file.
-                if let Some(line) = tsv.lines().next() {
-
-                    // Split the first line by \t so we can get the info of the table.
-                    // We expect to have 2 items here. If we have more or less, stop.
-                    let tsv_info = line.split('\t').collect::<Vec<&str>>();
-                    if tsv_info.len() == 2 {
-
-                        // Get the type and the version of the table.
-                        let table_type = tsv_info[0];
-                        let table_version = match tsv_info[1].parse::<i32>() {
-                            Ok(version) => version,
-                            Err(_) => {
-                                error_files.push(path.to_string_lossy().to_string());
-                                continue
+                // The first row has the PackedFile Type (or name, in case of tables) and version, whatever the format of the file is.
+                // Files with unknown extensions are treated as TSV files, like before we supported other formats.
+                let format = TableFormat::from_path(path).unwrap_or(TableFormat::TSV);
+                let (table_type, table_version) = match Table::read_header(path, format) {
+                    Ok(header) => header,
+                    Err(_) => {
+                        error_files.push(path.to_string_lossy().to_string());
+                        continue
+                    }
+                };
+
+                // Depending on the name received, call it one thing or another.
+                // If the name is not specific for a type of file, we trat it as a DB Table.
+                let name = match name {
+                    Some(ref name) => name.to_string(),
+                    None => path.file_stem().unwrap().to_str().unwrap().to_string(),
+                };
+
+                let is_loc = table_type == TSV_NAME_LOC;
+                let mut packed_file_path = if is_loc { vec!["text".to_owned(), "db".to_owned(), format!("{}.loc", name)] }
+                else { vec!["db".to_owned(), table_type.to_owned(), name.to_owned()] };
+
+                let result = match mode {
+                    TSVImportMode::Replace => {
+
+                        // If that path already exists in the list of new PackedFiles to add, change it using the index.
+                        if !overwrite {
+                            let mut index = 1;
+                            while packed_files.iter().any(|x| x.get_path() == &*packed_file_path) {
+                                packed_file_path[2] = if is_loc { format!("{}_{}.loc", name, index) } else { format!("{}_{}", name, index) };
+                                index += 1;
                             }
+                        }
+
+                        let data = if is_loc {
+                            schema.get_ref_versioned_file_loc().and_then(|versioned_file| versioned_file.get_version(table_version))
+                                .and_then(|definition| Loc::import(definition, path, &table_type, format)).map(DecodedPackedFile::Loc)
+                        } else {
+                            schema.get_ref_versioned_file_db(&table_type).and_then(|versioned_file| versioned_file.get_version(table_version))
+                                .and_then(|definition| DB::import(definition, path, &table_type, format)).map(DecodedPackedFile::DB)
                         };
 
-                        // Get the definition, depending on the table type and version.
-                        // If the name is not specific for a type of file, we trat it as a DB Table.
-                        match table_type {
-                            TSV_NAME_LOC => {
-                                let definition = schema.get_ref_versioned_file_loc()?.get_version(table_version)?;
-                                if let Ok(table) = Loc::import_tsv(&definition, &path, &table_type) {
-
-                                    // Depending on the name received, call it one thing or another.
-                                    let name = match name {
-                                        Some(ref name) => name.to_string(),
-                                        None => path.file_stem().unwrap().to_str().unwrap().to_string(),
-                                    };
-
-                                    let mut path = vec!["text".to_owned(), "db".to_owned(), format!("{}.loc", name)];
-
-                                    // If that path already exists in the list of new PackedFiles to add, change it using the index.
-                                    if !overwrite {
-                                        let mut index = 1;
-                                        while packed_files.iter().any(|x| x.get_path() == &*path) {
-                                            path[2] = format!("{}_{}.loc", name, index);
-                                            index += 1;
-                                        }
-                                    }
-
-                                    // If that path already exist in the PackFile, add it to the "remove" list.
-                                    if self.packedfile_exists(&path) { packed_files_to_remove.push(path.to_vec()) }
-
-                                    // Create and add the new PackedFile to the list of PackedFiles to add.
-                                    let mut packed_file = PackedFile::new(path, self.get_file_name());
-                                    packed_file.set_decoded(&DecodedPackedFile::Loc(table));
-                                    packed_files.push(packed_file);
-                                }
-                                else { error_files.push(path.to_string_lossy().to_string()); }
-                            }
-                            _ => {
-                                let definition = schema.get_ref_versioned_file_db(&table_type)?.get_version(table_version)?;
-                                if let Ok(table) = DB::import_tsv(&definition, &path, &table_type) {
-
-                                    // Depending on the name received, call it one thing or another.
-                                    let name = match name {
-                                        Some(ref name) => name.to_string(),
-                                        None => path.file_stem().unwrap().to_str().unwrap().to_string(),
-                                    };
-
-                                    let mut path = vec!["db".to_owned(), table_type.to_owned(), name.to_owned()];
-
-                                    // If that path already exists in the list of new PackedFiles to add, change it using the index.
-                                    if !overwrite {
-                                        let mut index = 1;
-                                        while packed_files.iter().any(|x| x.get_path() == &*path) {
-                                            path[2] = format!("{}_{}", name, index);
-                                            index += 1;
-                                        }
-                                    }
-
-                                    // If that path already exists in the PackFile, add it to the "remove" list.
-                                    if self.packedfile_exists(&path) { packed_files_to_remove.push(path.to_vec()) }
-
-                                    // Create and add the new PackedFile to the list of PackedFiles to add.
-                                    let mut packed_file = PackedFile::new(path, self.get_file_name());
-                                    packed_file.set_decoded(&DecodedPackedFile::DB(table));
-                                    packed_files.push(packed_file);
-                                }
-                                else { error_files.push(path.to_string_lossy().to_string()); }
+                        data.map(|data| {
+                            let inserted = match data {
+                                DecodedPackedFile::DB(ref table) => table.get_entry_count(),
+                                DecodedPackedFile::Loc(ref table) => table.get_entry_count(),
+                                _ => 0,
+                            };
+                            (data, MergeReport { inserted, ..Default::default() })
+                        })
+                    }
+
+                    TSVImportMode::Merge => {
+
+                        // If we already merged another file into the same table, merge this one on top of it.
+                        let data = match packed_files.iter().position(|x| x.get_path() == &*packed_file_path) {
+                            Some(index) => Ok(packed_files.remove(index).get_decoded()),
+                            None => match self.get_ref_mut_packed_file_by_path(&packed_file_path) {
+                                Some(packed_file) => packed_file.decode_return_ref_no_locks(schema).map(Clone::clone),
+                                None => if is_loc {
+                                    schema.get_ref_versioned_file_loc().and_then(|versioned_file| versioned_file.get_version(table_version)).map(|definition| DecodedPackedFile::Loc(Loc::new(definition)))
+                                } else {
+                                    schema.get_ref_versioned_file_db(&table_type).and_then(|versioned_file| versioned_file.get_version(table_version)).map(|definition| DecodedPackedFile::DB(DB::new(&table_type, None, definition)))
+                                },
                             }
+                        };
+
+                        data.and_then(|mut data| {
+                            let file_report = match data {
+                                DecodedPackedFile::DB(ref mut table) => table.import_tsv(path, &table_type, mode),
+                                DecodedPackedFile::Loc(ref mut table) => table.import_tsv(path, &table_type, mode),
+                                _ => Err(ErrorKind::ImportTSVWrongTypeTable.into()),
+                            }?;
+                            Ok((data, file_report))
+                        })
+                    }
+                };
+
+                match result {
+                    Ok((data, file_report)) => {
+
+                        // If that path already exist in the PackFile, add it to the "remove" list.
+                        if self.packedfile_exists(&packed_file_path) && !packed_files_to_remove.contains(&packed_file_path) {
+                            packed_files_to_remove.push(packed_file_path.to_vec());
                         }
+
+                        // Create and add the new PackedFile to the list of PackedFiles to add.
+                        let mut packed_file = PackedFile::new(packed_file_path, self.get_file_name());
+                        packed_file.set_decoded(&data);
+                        packed_files.push(packed_file);
+                        report += file_report;
                     }
-                    else { error_files.push(path.to_string_lossy().to_string()); }
+                    Err(_) => error_files.push(path.to_string_lossy().to_string()),
                 }
-                else { error_files.push(path.to_string_lossy().to_string()); }
             }
 
             // If any of the files returned error, return error.
@@ -1973,17 +2196,72 @@ impl PackFile {
             // We add all the files to the PackFile, and return success.
             let packed_files_to_add = packed_files.iter().collect::<Vec<&PackedFile>>();
             self.add_packed_files(&packed_files_to_add, true)?;
-            Ok((packed_files_to_remove, tree_path))
+            Ok((packed_files_to_remove, tree_path, report))
         }
         else {
             Err(ErrorKind::SchemaNotFound.into())
         }
     }
 
+    /// This function applies a mass-edit statement to every DB Table with the provided name (like `land_units_tables`) in the PackFile.
+    ///
+    /// The dependencies are used by the reference functions of the statement. It returns the paths of the tables with edited rows.
+    /// If the statement fails on any table, none of them is edited.
+    pub fn mass_edit_tables(
+        &mut self,
+        table_name: &str,
+        mass_edit: &MassEdit,
+        dependencies: &Dependencies,
+    ) -> Result<Vec<Vec<String>>> {
+        let tables = if let Some(ref schema) = *SCHEMA.read().unwrap() {
+            self.get_ref_mut_packed_files_by_type(PackedFileType::DB, false).into_iter()
+                .filter(|packed_file| packed_file.get_path().len() == 3 && packed_file.get_path()[1] == table_name)
+                .map(|packed_file| {
+                    let path = packed_file.get_path().to_vec();
+                    packed_file.decode_return_ref_no_locks(schema).map(|data| (path, data.clone()))
+                }).collect::<Result<Vec<(Vec<String>, DecodedPackedFile)>>>()?
+        } else {
+            return Err(ErrorKind::SchemaNotFound.into())
+        };
+
+        // Edit copies of the tables first, so we don't leave the PackFile half-edited if one of them fails.
+        let mut edited_tables = vec![];
+        for (path, data) in tables {
+            if let DecodedPackedFile::DB(mut table) = data {
+                let dependency_data = DB::get_dependency_data(self, table.get_ref_table_name(), table.get_ref_definition(), dependencies, &[]);
+                let edited_rows = table.mass_edit(mass_edit, &dependency_data).map_err(|error| match error.kind() {
+                    ErrorKind::ExpressionError(_, row, reason) => Error::from(ErrorKind::ExpressionError(path.join("/"), *row, reason.to_owned())),
+                    _ => error,
+                })?;
+
+                if edited_rows > 0 {
+                    edited_tables.push((path, table));
+                }
+            }
+        }
+
+        let mut paths = vec![];
+        for (path, table) in edited_tables {
+            if let Some(packed_file) = self.get_ref_mut_packed_file_by_path(&path) {
+                packed_file.set_decoded(&DecodedPackedFile::DB(table));
+                paths.push(path);
+            }
+        }
+
+        Ok(paths)
+    }
+
     /// This function is used to Mass-Export TSV files from a PackFile.
     ///
     /// NOTE: this will OVERWRITE any existing file that has a name conflict with the TSV files provided.
     pub fn mass_export_tsv(&mut self, path_types: &[PathType], export_path: &PathBuf) -> Result<String> {
+        self.mass_export_tables(path_types, export_path, TableFormat::TSV)
+    }
+
+    /// This function is used to Mass-Export DB and Loc tables from a PackFile to files of the provided format.
+    ///
+    /// NOTE: this will OVERWRITE any existing file that has a name conflict with the files provided.
+    pub fn mass_export_tables(&mut self, path_types: &[PathType], export_path: &PathBuf, format: TableFormat) -> Result<String> {
 
         // Lists of PackedFiles that couldn't be exported for one thing or another and exported PackedFile names,
         // so we make sure we don't overwrite those with the following ones.
@@ -2008,19 +2286,19 @@ impl PackFile {
                         Ok(data) => match data {
                             DecodedPackedFile::DB(data) => {
 
-                                // His name will be "db_name_file_name.tsv". If that's taken, we'll add an index until we find one available.
-                                let mut name = format!("{}_{}.tsv", path[1], path.last().unwrap().to_owned());
+                                // His name will be "db_name_file_name.extension". If that's taken, we'll add an index until we find one available.
+                                let mut name = format!("{}_{}.{}", path[1], path.last().unwrap().to_owned(), format.get_extension());
                                 let mut export_path = export_path.to_path_buf();
 
                                 // Checks to avoid overwriting exported files go here, in an infinite loop of life and death.
                                 let mut index = 1;
                                 while exported_files.contains(&name) {
-                                    name = format!("{}_{}_{}.tsv", path[1], path.last().unwrap().to_owned(), index);
+                                    name = format!("{}_{}_{}.{}", path[1], path.last().unwrap().to_owned(), index, format.get_extension());
                                     index += 1;
                                 }
 
                                 export_path.push(name.to_owned());
-                                match data.export_tsv(&export_path, &path[1]) {
+                                match data.export(&export_path, &path[1], format) {
                                     Ok(_) => exported_files.push(name),
                                     Err(error) => error_list.push((packed_file.get_path().join("\\"), error)),
                                 }
@@ -2028,19 +2306,19 @@ impl PackFile {
                             }
                             DecodedPackedFile::Loc(data) => {
 
-                                // His name will be "db_name_file_name.tsv". If that's taken, we'll add an index until we find one available.
-                                let mut name = format!("{}.tsv", path.last().unwrap().to_owned());
+                                // His name will be "db_name_file_name.extension". If that's taken, we'll add an index until we find one available.
+                                let mut name = format!("{}.{}", path.last().unwrap().to_owned(), format.get_extension());
                                 let mut export_path = export_path.to_path_buf();
 
                                 // Checks to avoid overwriting exported files go here, in an infinite loop of life and death.
                                 let mut index = 1;
                                 while exported_files.contains(&name) {
-                                    name = format!("{}_{}.tsv", path.last().unwrap().to_owned(), index);
+                                    name = format!("{}_{}.{}", path.last().unwrap().to_owned(), index, format.get_extension());
                                     index += 1;
                                 }
 
                                 export_path.push(name.to_owned());
-                                match data.export_tsv(&export_path, &TSV_NAME_LOC) {
+                                match data.export(&export_path, TSV_NAME_LOC, format) {
                                     Ok(_) => exported_files.push(name),
                                     Err(error) => error_list.push((packed_file.get_path().join("\\"), error)),
                                 }
@@ -2297,6 +2575,24 @@ impl PackFile {
         file_path: &PathBuf,
         use_lazy_loading: bool
     ) -> Result<Self> {
+        Self::read_internal(file_path, use_lazy_loading, false).map(|(pack_file, _)| pack_file)
+    }
+
+    /// This function reads the content of a damaged PackFile into a `PackFile` struct, salvaging as much as it can from it.
+    ///
+    /// Unlike `read`, this doesn't fail on truncated indexes, PackedFiles with their data out of the PackFile, wrong PackedFile
+    /// counts or invalid subheaders. Instead, it keeps every PackedFile which data is fully in the PackFile, and returns a report
+    /// with everything it had to fix or leave out. The PackedFiles are loaded to memory, so the result can be saved over the damaged PackFile.
+    pub fn read_and_recover(file_path: &PathBuf) -> Result<(Self, PackFileRecoveryReport)> {
+        Self::read_internal(file_path, false, true)
+    }
+
+    /// This function reads the content of a PackFile into a `PackFile` struct, in recovery mode if `recover` is true.
+    fn read_internal(
+        file_path: &PathBuf,
+        use_lazy_loading: bool,
+        recover: bool,
+    ) -> Result<(Self, PackFileRecoveryReport)> {
 
         // Check if what we received is even a `PackFile`.
         if !file_path.file_name().unwrap().to_string_lossy().to_string().ends_with(".pack") { return Err(ErrorKind::OpenPackFileInvalidExtension.into()) }
@@ -2305,6 +2601,7 @@ impl PackFile {
         let mut pack_file = BufReader::new(File::open(&file_path)?);
         let pack_file_name = file_path.file_name().unwrap().to_string_lossy().to_string();
         let mut pack_file_decoded = Self::new();
+        let mut report = PackFileRecoveryReport::default();
 
         // First, we do some quick checkings to ensure it's a valid PackFile.
         // 24 is the bare minimum that we need to check how a PackFile should be internally, so any file with less than that is not a valid PackFile.
@@ -2328,23 +2625,24 @@ impl PackFile {
         let packed_file_index_size = buffer.decode_integer_u32(20)?;
 
         // Depending on the data we got, prepare to read the header and ensure we have all the bytes we need.
+        // If the PackFile has an extended header, it goes right after the timestamp, so everything after it gets moved by its size.
+        let extended_header_offset = match pack_file_decoded.pfh_version {
+            PFHVersion::PFH6 | PFHVersion::PFH5 | PFHVersion::PFH4 => 28,
+            PFHVersion::PFH3 | PFHVersion::PFH2 => 32,
+            PFHVersion::PFH0 => 24,
+        };
+        let extended_header_size = if pack_file_decoded.bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER) { EXTENDED_HEADER_SIZE } else { 0 };
         match pack_file_decoded.pfh_version {
 
             // PFH6 contains a subheader with some extra data we want to keep.
-            PFHVersion::PFH6 => buffer = vec![0; 308],
-
-            PFHVersion::PFH5 | PFHVersion::PFH4 => {
-                if (pack_file_decoded.bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER) && pack_file_len < 48) ||
-                    (!pack_file_decoded.bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER) && pack_file_len < 28) { return Err(ErrorKind::PackFileHeaderNotComplete.into()) }
-
-                if pack_file_decoded.bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER) { buffer = vec![0; 48]; }
-                else { buffer = vec![0; 28]; }
-            }
-
-            PFHVersion::PFH3 | PFHVersion::PFH2 => buffer = vec![0; 32],
-            PFHVersion::PFH0 => buffer = vec![0; 24],
+            PFHVersion::PFH6 => buffer = vec![0; 308 + extended_header_size],
+            PFHVersion::PFH5 | PFHVersion::PFH4 => buffer = vec![0; 28 + extended_header_size],
+            PFHVersion::PFH3 | PFHVersion::PFH2 => buffer = vec![0; 32 + extended_header_size],
+            PFHVersion::PFH0 => buffer = vec![0; 24 + extended_header_size],
         }
 
+        if pack_file_len < buffer.len() as u64 { return Err(ErrorKind::PackFileHeaderNotComplete.into()) }
+
         // Restore the cursor of the BufReader to 0, so we can read the full header in one go. The first 24 bytes are
         // already decoded but, for the sake of clarity in the positions of the rest of the header stuff, we do this.
         pack_file.seek(SeekFrom::Start(0))?;
@@ -2358,11 +2656,31 @@ impl PackFile {
             PFHVersion::PFH0 => 0
         };
 
+        // The extended header goes right after the timestamp.
+        if pack_file_decoded.bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER) {
+            pack_file_decoded.extended_header.unknown_1 = buffer.decode_integer_u32(extended_header_offset)?;
+            pack_file_decoded.extended_header.unknown_2 = buffer.decode_integer_u32(extended_header_offset + 4)?;
+            pack_file_decoded.extended_header.unknown_3 = buffer.decode_integer_u32(extended_header_offset + 8)?;
+            pack_file_decoded.extended_header.unknown_4 = buffer.decode_integer_u32(extended_header_offset + 12)?;
+            pack_file_decoded.extended_header.unknown_5 = buffer.decode_integer_u32(extended_header_offset + 16)?;
+        }
+
         if let PFHVersion::PFH6 = pack_file_decoded.pfh_version {
-            pack_file_decoded.game_version = buffer.decode_integer_u32(36)?;
-            pack_file_decoded.build_number = buffer.decode_integer_u32(40)?;
-            pack_file_decoded.authoring_tool = buffer.decode_string_u8_0padded(44, AUTHORING_TOOL_SIZE as usize)?.0;
-            pack_file_decoded.extra_subheader_data = buffer[52..].to_vec();
+            pack_file_decoded.game_version = buffer.decode_integer_u32(36 + extended_header_size)?;
+            pack_file_decoded.build_number = buffer.decode_integer_u32(40 + extended_header_size)?;
+            match buffer.decode_string_u8_0padded(44 + extended_header_size, AUTHORING_TOOL_SIZE as usize) {
+                Ok((authoring_tool, _)) => {
+                    pack_file_decoded.authoring_tool = authoring_tool;
+                    pack_file_decoded.extra_subheader_data = buffer[52 + extended_header_size..].to_vec();
+                }
+
+                // In recovery mode, an invalid subheader is replaced with the one of a new PackFile.
+                Err(error) => if recover {
+                    report.invalid_subheader = true;
+                    pack_file_decoded.game_version = 0;
+                    pack_file_decoded.build_number = 0;
+                } else { return Err(error) }
+            }
         }
 
         // Ensure the PackFile has all the data needed for the index. If the PackFile's data is encrypted
@@ -2373,11 +2691,15 @@ impl PackFile {
             pack_file_decoded.pfh_version == PFHVersion::PFH5 {
             data_position = if (data_position % 8) > 0 { data_position + 8 - (data_position % 8) } else { data_position };
         }
-        if pack_file_len < data_position { return Err(ErrorKind::PackFileIndexesNotComplete.into()) }
+        if pack_file_len < data_position {
+            if recover { report.damaged_index = true; }
+            else { return Err(ErrorKind::PackFileIndexesNotComplete.into()) }
+        }
 
-        // Create the buffers for the indexes data.
-        let mut pack_file_index = vec![0; pack_file_index_size as usize];
-        let mut packed_file_index = vec![0; packed_file_index_size as usize];
+        // Create the buffers for the indexes data. In recovery mode, if the indexes are truncated, we only read what's there.
+        let index_len = (pack_file_len - buffer.len() as u64) as usize;
+        let mut pack_file_index = vec![0; (pack_file_index_size as usize).min(index_len)];
+        let mut packed_file_index = vec![0; (packed_file_index_size as usize).min(index_len - pack_file_index.len())];
 
         // Get the data from both indexes to their buffers.
         pack_file.read_exact(&mut pack_file_index)?;
@@ -2390,8 +2712,13 @@ impl PackFile {
         // so we just read them char by char until hitting 0, then decode the next one and so on.
         // NOTE: This doesn't deal with encryption, as we haven't seen any encrypted PackFile with data in this index.
         for _ in 0..pack_file_count {
-            let pack_file_name = pack_file_index.decode_packedfile_string_u8_0terminated(pack_file_index_position, &mut pack_file_index_position)?;
-            pack_file_decoded.pack_files.push(pack_file_name);
+            match pack_file_index.decode_packedfile_string_u8_0terminated(pack_file_index_position, &mut pack_file_index_position) {
+                Ok(pack_file_name) => pack_file_decoded.pack_files.push(pack_file_name),
+                Err(error) => if recover {
+                    report.damaged_index = true;
+                    break;
+                } else { return Err(error) }
+            }
         }
 
         // Depending on the version of the PackFile and his bitmask, the PackedFile index has one format or another.
@@ -2418,86 +2745,116 @@ impl PackFile {
         };
 
         // Prepare the needed stuff to read the PackedFiles.
-        let mut index_position: usize = 0;
-        let pack_file = Arc::new(Mutex::new(pack_file));
-        for packed_files_to_decode in (0..packed_file_count).rev() {
+        let pfh_version = pack_file_decoded.pfh_version;
+        let bitmask = pack_file_decoded.bitmask;
+        let decode_index_entry = |index_position: &mut usize, packed_files_to_decode: u32| -> Result<(u32, i64, bool, Vec<String>)> {
 
             // Get his size. If it's encrypted, decrypt it first.
-            let size = if pack_file_decoded.bitmask.contains(PFHFlags::HAS_ENCRYPTED_INDEX) {
-                let encrypted_size = packed_file_index.decode_integer_u32(index_position)?;
-                decrypt_index_item_file_length(encrypted_size, packed_files_to_decode as u32)
+            let size = if bitmask.contains(PFHFlags::HAS_ENCRYPTED_INDEX) {
+                let encrypted_size = packed_file_index.decode_integer_u32(*index_position)?;
+                decrypt_index_item_file_length(encrypted_size, packed_files_to_decode)
             } else {
-                packed_file_index.decode_integer_u32(index_position)?
+                packed_file_index.decode_integer_u32(*index_position)?
             };
 
             // If we have the last modified date of the PackedFiles in the Index, get it. Otherwise, default to 0,
             // so we have something to write in case we want to enable them for our PackFile.
-            let timestamp = if pack_file_decoded.bitmask.contains(PFHFlags::HAS_INDEX_WITH_TIMESTAMPS) {
-                match pack_file_decoded.pfh_version {
+            let timestamp = if bitmask.contains(PFHFlags::HAS_INDEX_WITH_TIMESTAMPS) {
+                match pfh_version {
                     PFHVersion::PFH6 | PFHVersion::PFH5 | PFHVersion::PFH4 => {
-                        let timestamp = i64::from(packed_file_index.decode_integer_u32(index_position + 4)?);
-                        if pack_file_decoded.bitmask.contains(PFHFlags::HAS_ENCRYPTED_INDEX) {
-                            i64::from(decrypt_index_item_file_length(timestamp as u32, packed_files_to_decode as u32))
+                        let timestamp = i64::from(packed_file_index.decode_integer_u32(*index_position + 4)?);
+                        if bitmask.contains(PFHFlags::HAS_ENCRYPTED_INDEX) {
+                            i64::from(decrypt_index_item_file_length(timestamp as u32, packed_files_to_decode))
                         } else { timestamp }
                     }
 
                     // We haven't found a single encrypted PFH3/PFH0 PackFile to test, so always assume these are unencrypted. Also, PFH0 doesn't seem to have a timestamp.
-                    PFHVersion::PFH3 | PFHVersion::PFH2 => (packed_file_index.decode_integer_i64(index_position + 4)? / WINDOWS_TICK) - SEC_TO_UNIX_EPOCH,
+                    PFHVersion::PFH3 | PFHVersion::PFH2 => (packed_file_index.decode_integer_i64(*index_position + 4)? / WINDOWS_TICK) - SEC_TO_UNIX_EPOCH,
                     PFHVersion::PFH0 => 0,
                 }
             } else { 0 };
 
             // Update his offset, and get his compression data if it has it.
-            index_position += packed_file_index_path_offset;
-            let is_compressed = if let PFHVersion::PFH5 = pack_file_decoded.pfh_version {
-                matches!(packed_file_index.decode_bool(index_position - 1), Ok(true))
+            *index_position += packed_file_index_path_offset;
+            let is_compressed = if let PFHVersion::PFH6 | PFHVersion::PFH5 = pfh_version {
+                !bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER) && matches!(packed_file_index.decode_bool(*index_position - 1), Ok(true))
             } else { false };
 
             // Get his path. Like the PackFile index, it's a StringU8 terminated in 00. We get it and split it in folders for easy use.
-            let path = if pack_file_decoded.bitmask.contains(PFHFlags::HAS_ENCRYPTED_INDEX) {
-                decrypt_index_item_filename(&packed_file_index[index_position..], size as u8, &mut index_position)
+            let path = if bitmask.contains(PFHFlags::HAS_ENCRYPTED_INDEX) {
+                decrypt_index_item_filename(packed_file_index.get(*index_position..).unwrap_or(&[]), size as u8, index_position)?
+            }
+            else { packed_file_index.decode_packedfile_string_u8_0terminated(*index_position, index_position)? };
+            Ok((size, timestamp, is_compressed, path.split('\\').map(|x| x.to_owned()).collect::<Vec<String>>()))
+        };
+
+        // In recovery mode we don't trust the PackedFile count of the header, and read entries until we reach the end of the index.
+        // Except if the index is encrypted, because we need that count to decrypt it.
+        let mut index_position: usize = 0;
+        let mut packed_files_read = 0;
+        let pack_file = Arc::new(Mutex::new(pack_file));
+        loop {
+            if recover && !bitmask.contains(PFHFlags::HAS_ENCRYPTED_INDEX) {
+                if index_position >= packed_file_index.len() { break; }
+            }
+            else if packed_files_read == packed_file_count { break; }
+
+            let packed_files_to_decode = packed_file_count.wrapping_sub(packed_files_read + 1);
+            let (size, timestamp, is_compressed, path) = match decode_index_entry(&mut index_position, packed_files_to_decode) {
+                Ok(entry) => entry,
+                Err(error) => if recover {
+                    report.damaged_index = true;
+                    report.unreadable_packed_files = packed_file_count.saturating_sub(packed_files_read);
+                    break;
+                } else { return Err(error) }
+            };
+            packed_files_read += 1;
+
+            // In recovery mode, if the data of the PackedFile is not fully in the PackFile, we lose it.
+            if recover && data_position + u64::from(size) > pack_file_len {
+                report.lost_packed_files.push((path, size));
             }
-            else { packed_file_index.decode_packedfile_string_u8_0terminated(index_position, &mut index_position)? };
-            let path = path.split('\\').map(|x| x.to_owned()).collect::<Vec<String>>();
 
             // Once we are done, we create the PackedFile and add it to the PackedFile list.
-            let raw_data = RawPackedFile::read_from_data(
-                path,
-                pack_file_name.to_string(),
-                timestamp,
-                is_compressed,
-                if pack_file_decoded.bitmask.contains(PFHFlags::HAS_ENCRYPTED_DATA) { Some(pack_file_decoded.pfh_version) } else { None },
-                PackedFileData::OnDisk(RawOnDisk::new(
-                    pack_file.clone(),
-                    data_position,
-                    size,
+            else {
+                let raw_data = RawPackedFile::read_from_data(
+                    path,
+                    pack_file_name.to_string(),
+                    timestamp,
                     is_compressed,
                     if pack_file_decoded.bitmask.contains(PFHFlags::HAS_ENCRYPTED_DATA) { Some(pack_file_decoded.pfh_version) } else { None },
-                ))
-            );
-
-            let mut packed_file = PackedFile::new_from_raw(&raw_data);
-
-            // If this is a notes PackedFile, save the notes and forget about the PackedFile. Otherwise, save the PackedFile.
-            if packed_file.get_path() == [RESERVED_NAME_NOTES] {
-                if let Ok(data) = packed_file.get_raw_data_and_keep_it() {
-                    if let Ok(data) = data.decode_string_u8(0, data.len()) {
-                        pack_file_decoded.notes = Some(data);
+                    PackedFileData::OnDisk(RawOnDisk::new(
+                        pack_file.clone(),
+                        data_position,
+                        size,
+                        is_compressed,
+                        if pack_file_decoded.bitmask.contains(PFHFlags::HAS_ENCRYPTED_DATA) { Some(pack_file_decoded.pfh_version) } else { None },
+                    ))
+                );
+
+                let mut packed_file = PackedFile::new_from_raw(&raw_data);
+
+                // If this is a notes PackedFile, save the notes and forget about the PackedFile. Otherwise, save the PackedFile.
+                if packed_file.get_path() == [RESERVED_NAME_NOTES] {
+                    if let Ok(data) = packed_file.get_raw_data_and_keep_it() {
+                        if let Ok(data) = data.decode_string_u8(0, data.len()) {
+                            pack_file_decoded.notes = Some(data);
+                        }
                     }
                 }
-            }
 
-            else if packed_file.get_path() == [RESERVED_NAME_SETTINGS] {
-                if let Ok(data) = packed_file.get_raw_data_and_keep_it() {
-                    pack_file_decoded.settings = if let Ok(settings) = PackFileSettings::load(&data) {
-                        settings
-                    } else {
-                        PackFileSettings::default()
-                    };
+                else if packed_file.get_path() == [RESERVED_NAME_SETTINGS] {
+                    if let Ok(data) = packed_file.get_raw_data_and_keep_it() {
+                        pack_file_decoded.settings = if let Ok(settings) = PackFileSettings::load(&data) {
+                            settings
+                        } else {
+                            PackFileSettings::default()
+                        };
+                    }
+                }
+                else {
+                    pack_file_decoded.push_packed_file(packed_file);
                 }
-            }
-            else {
-                pack_file_decoded.packed_files.push(packed_file);
             }
 
             // Then we move our data position. For encrypted files in PFH5 PackFiles (only ARENA) we have to start the next one in a multiple of 8.
@@ -2514,15 +2871,33 @@ impl PackFile {
         // If at this point we have not reached the end of the PackFile, there is something wrong with it.
         // NOTE: Arena PackFiles have extra data at the end. If we detect one of those PackFiles, take that into account.
         if pack_file_decoded.pfh_version == PFHVersion::PFH5 && pack_file_decoded.bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER) {
-            if data_position + 256 != pack_file_len { return Err(ErrorKind::PackFileSizeIsNotWhatWeExpect(pack_file_len, data_position).into()) }
+            if data_position + EXTENDED_HEADER_FOOTER_SIZE as u64 != pack_file_len {
+                if recover { report.wrong_size = Some((pack_file_len, data_position + EXTENDED_HEADER_FOOTER_SIZE as u64)); }
+                else { return Err(ErrorKind::PackFileSizeIsNotWhatWeExpect(pack_file_len, data_position).into()) }
+            }
+
+            // In recovery mode, we only read the part of the footer we have. It'll be 0-padded when saving.
+            let mut footer = vec![0; pack_file_len.saturating_sub(data_position).min(EXTENDED_HEADER_FOOTER_SIZE as u64) as usize];
+            let mut pack_file = pack_file.lock().unwrap();
+            pack_file.seek(SeekFrom::Start(data_position))?;
+            pack_file.read_exact(&mut footer)?;
+            pack_file_decoded.extended_header.footer = footer;
+        }
+        else if data_position != pack_file_len {
+            if recover { report.wrong_size = Some((pack_file_len, data_position)); }
+            else { return Err(ErrorKind::PackFileSizeIsNotWhatWeExpect(pack_file_len, data_position).into()) }
+        }
+
+        // If the index had more or less PackedFiles than the header said, report it.
+        if recover && !report.damaged_index && packed_files_read != packed_file_count {
+            report.wrong_packed_file_count = Some((packed_file_count, packed_files_read));
         }
-        else if data_position != pack_file_len { return Err(ErrorKind::PackFileSizeIsNotWhatWeExpect(pack_file_len, data_position).into()) }
 
         // If we disabled lazy-loading, load every PackedFile to memory.
         if !use_lazy_loading { for packed_file in &mut pack_file_decoded.packed_files { packed_file.get_ref_mut_raw().load_data()?; }}
 
         // Return our PackFile.
-        Ok(pack_file_decoded)
+        Ok((pack_file_decoded, report))
     }
 
     /// This function tries to save a `PackFile` to a file in the filesystem.
@@ -2544,7 +2919,7 @@ impl PackFile {
             data.encode_string_u8(&note);
             let raw_data = RawPackedFile::read_from_vec(vec![RESERVED_NAME_NOTES.to_owned()], self.get_file_name(), 0, false, data);
             let packed_file = PackedFile::new_from_raw(&raw_data);
-            self.packed_files.push(packed_file);
+            self.push_packed_file(packed_file);
         }
 
         // Saving PackFile settings.
@@ -2552,27 +2927,56 @@ impl PackFile {
         data.write_all(&to_string_pretty(&self.settings)?.as_bytes())?;
         let raw_data = RawPackedFile::read_from_vec(vec![RESERVED_NAME_SETTINGS.to_owned()], self.get_file_name(), 0, false, data);
         let packed_file = PackedFile::new_from_raw(&raw_data);
-        self.packed_files.push(packed_file);
+        self.push_packed_file(packed_file);
 
         // For some bizarre reason, if the PackedFiles are not alphabetically sorted they may or may not crash the game for particular people.
         // So, to fix it, we have to sort all the PackedFiles here by path.
         // NOTE: This sorting has to be CASE INSENSITIVE. This means for "ac", "Ab" and "aa" it'll be "aa", "Ab", "ac".
         self.packed_files.sort_unstable_by_key(|a| a.get_path().join("\\").to_lowercase());
+        self.rebuild_path_index();
+
+        // Get the compression policy of the PackFile, so we know what we must never compress.
+        let is_compression_supported = matches!(self.pfh_version, PFHVersion::PFH6 | PFHVersion::PFH5) && !self.bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER);
+        let compression_files_to_ignore = self.settings.get_compression_files_to_ignore();
+        let allow_table_compression = self.settings.settings_bool.get("allow_table_compression").copied().unwrap_or(false);
+        let is_data_encrypted = self.bitmask.contains(PFHFlags::HAS_ENCRYPTED_DATA);
+        let pfh_version = self.pfh_version;
+
+        // We ensure that all the data is in his right form (compressed/encrypted) before attempting to save, as we need their final size for the index.
+        // Data still on disk that doesn't need a compression change is not loaded, so we can save big PackFiles without loading them entirely to memory.
+        // This is done in parallel, as compression is slow as hell with big PackFiles.
+        self.packed_files.par_iter_mut().try_for_each(|packed_file| -> Result<()> {
+
+            // If we decoded it, re-encode it. Otherwise, leave it where it is.
+            packed_file.encode_no_load()?;
+
+            // If, in any moment, we enabled/disabled the compression of the PackedFile, compress/decompress it.
+            // EXCEPT FOR TABLES, unless the PackFile allows it, and anything the compression policy excludes.
+            let raw_packed_file = packed_file.get_ref_mut_raw();
+            let mut should_be_compressed = raw_packed_file.get_should_be_compressed();
+            if !is_compression_supported || compression_files_to_ignore.iter().any(|x| x.matches(raw_packed_file.get_path())) {
+                should_be_compressed = false;
+            }
 
-        // We ensure that all the data is loaded and in his right form (compressed/encrypted) before attempting to save.
-        // We need to do this here because we need later on their compressed size.
-        for packed_file in &mut self.packed_files {
+            match PackedFileType::get_packed_file_type(raw_packed_file.get_path()) {
+                PackedFileType::DB | PackedFileType::Loc if !allow_table_compression => should_be_compressed = false,
+                _ => {}
+            }
 
-            // If we decoded it, re-encode it. Otherwise, just load it.
-            packed_file.encode()?;
+            raw_packed_file.set_should_be_compressed(should_be_compressed);
+            raw_packed_file.set_should_be_encrypted(if is_data_encrypted { Some(pfh_version) } else { None });
 
-            // Remember: first compress (only PFH5), then encrypt.
-            let (path, data, is_compressed, is_encrypted, should_be_compressed, should_be_encrypted) = packed_file.get_ref_mut_raw().get_data_and_info_from_memory()?;
+            // The encryption is done when writing the data to disk, so if the compression is right, we can copy the data from disk as it is.
+            if raw_packed_file.is_on_disk() && raw_packed_file.get_compression_state() == should_be_compressed {
+                return Ok(());
+            }
 
-            // If, in any moment, we enabled/disabled the PackFile compression, compress/decompress the PackedFile. EXCEPT FOR TABLES. NEVER COMPRESS TABLES.
-            match PackedFileType::get_packed_file_type(path) {
-                PackedFileType::DB | PackedFileType::Loc => *should_be_compressed = false,
-                _ => {}
+            // Remember: first compress (only PFH5), then encrypt. So we need to decrypt the data before touching its compression.
+            raw_packed_file.load_data()?;
+            let (_, data, is_compressed, is_encrypted, should_be_compressed, _) = raw_packed_file.get_data_and_info_from_memory()?;
+            if is_encrypted.is_some() {
+                *data = decrypt_packed_file(&data);
+                *is_encrypted = None;
             }
 
             if *should_be_compressed && !*is_compressed {
@@ -2584,13 +2988,8 @@ impl PackFile {
                 *is_compressed = false;
             }
 
-            // Encryption is not yet supported. Unencrypt everything.
-            if is_encrypted.is_some() {
-                *data = decrypt_packed_file(&data);
-                *is_encrypted = None;
-                *should_be_encrypted = None;
-            }
-        }
+            Ok(())
+        })?;
 
         // First we encode the indexes and the data (just in case we compressed it).
         let mut pack_file_index = vec![];
@@ -2601,18 +3000,27 @@ impl PackFile {
             pack_file_index.push(0);
         }
 
-        for packed_file in &self.packed_files {
-            packed_file_index.encode_integer_u32(packed_file.get_ref_raw().get_size());
+        // If the index is encrypted, each entry is encrypted using the amount of entries after it.
+        let is_index_encrypted = self.bitmask.contains(PFHFlags::HAS_ENCRYPTED_INDEX);
+        for (index, packed_file) in self.packed_files.iter().enumerate() {
+            let packed_files_after_this_one = (self.packed_files.len() - index - 1) as u32;
+            let size = packed_file.get_ref_raw().get_size();
+            if is_index_encrypted { packed_file_index.encode_integer_u32(encrypt_index_item_file_length(size, packed_files_after_this_one)); }
+            else { packed_file_index.encode_integer_u32(size); }
 
             // Depending on the version of the PackFile and his bitmask, the PackedFile index has one format or another.
-            // In PFH5 case, we don't support saving encrypted PackFiles for Arena. So we'll default to Warhammer 2 format.
+            // In PFH5 case, if it has the extended header, it's an Arena PackFile, which uses the PFH4 format.
             match self.pfh_version {
-                PFHVersion::PFH6 | PFHVersion::PFH5 => {
-                    if self.bitmask.contains(PFHFlags::HAS_INDEX_WITH_TIMESTAMPS) { packed_file_index.encode_integer_u32(packed_file.get_ref_raw().get_timestamp() as u32); }
-                    if packed_file.get_ref_raw().get_should_be_compressed() { packed_file_index.push(1); } else { packed_file_index.push(0); }
-                }
-                PFHVersion::PFH4 => {
-                    if self.bitmask.contains(PFHFlags::HAS_INDEX_WITH_TIMESTAMPS) { packed_file_index.encode_integer_u32(packed_file.get_ref_raw().get_timestamp() as u32); }
+                PFHVersion::PFH6 | PFHVersion::PFH5 | PFHVersion::PFH4 => {
+                    if self.bitmask.contains(PFHFlags::HAS_INDEX_WITH_TIMESTAMPS) {
+                        let timestamp = packed_file.get_ref_raw().get_timestamp() as u32;
+                        if is_index_encrypted { packed_file_index.encode_integer_u32(encrypt_index_item_file_length(timestamp, packed_files_after_this_one)); }
+                        else { packed_file_index.encode_integer_u32(timestamp); }
+                    }
+
+                    if self.pfh_version != PFHVersion::PFH4 && !self.bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER) {
+                        if packed_file.get_ref_raw().get_should_be_compressed() { packed_file_index.push(1); } else { packed_file_index.push(0); }
+                    }
                 }
                 PFHVersion::PFH3 | PFHVersion::PFH2 => {
                     if self.bitmask.contains(PFHFlags::HAS_INDEX_WITH_TIMESTAMPS) { packed_file_index.encode_integer_i64(packed_file.get_ref_raw().get_timestamp()); }
@@ -2622,13 +3030,14 @@ impl PackFile {
                 PFHVersion::PFH0 => {}
             }
 
-            packed_file_index.append(&mut packed_file.get_path().join("\\").as_bytes().to_vec());
-            packed_file_index.push(0);
+            let path = packed_file.get_path().join("\\");
+            if is_index_encrypted { packed_file_index.append(&mut encrypt_index_item_filename(&path, size as u8)); }
+            else {
+                packed_file_index.extend_from_slice(path.as_bytes());
+                packed_file_index.push(0);
+            }
         }
 
-        // Create the file to save to, and save the header and the indexes.
-        let mut file = BufWriter::new(File::create(&self.file_path)?);
-
         // Write the entire header.
         let mut header = vec![];
         header.encode_string_u8(&self.pfh_version.get_value());
@@ -2646,6 +3055,15 @@ impl PackFile {
             PFHVersion::PFH0 => {}
         };
 
+        // The extended header goes right after the timestamp, in every version of the PackFile.
+        if self.bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER) {
+            header.encode_integer_u32(self.extended_header.unknown_1);
+            header.encode_integer_u32(self.extended_header.unknown_2);
+            header.encode_integer_u32(self.extended_header.unknown_3);
+            header.encode_integer_u32(self.extended_header.unknown_4);
+            header.encode_integer_u32(self.extended_header.unknown_5);
+        }
+
         if let PFHVersion::PFH6 = self.pfh_version {
             header.encode_integer_u32(SUBHEADER_MARK);
             header.encode_integer_u32(SUBHEADER_VERSION);
@@ -2667,13 +3085,77 @@ impl PackFile {
             header.extend_from_slice(&self.extra_subheader_data);
         }
 
-        // Write the indexes and the data of the PackedFiles. No need to keep the data, as it has been preloaded before.
+        // We write everything to a temporary file next to the destination one, and replace the destination file with it once we're done.
+        // This is because PackedFiles not loaded to memory may be read from the same file we're overwriting.
+        let temp_folder = match self.file_path.parent() {
+            Some(folder) if !folder.as_os_str().is_empty() => folder.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let mut temp_file = tempfile::NamedTempFile::new_in(&temp_folder)?;
+        let mut file = BufWriter::new(temp_file.as_file_mut());
+
+        // Write the indexes and the data of the PackedFiles. The data is copied one PackedFile at a time, from memory or from its source on disk.
+        // For encrypted files in PFH5 PackFiles (only ARENA) the data of each PackedFile has to start in a multiple of 8.
+        let is_data_padded = is_data_encrypted && self.bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER) && self.pfh_version == PFHVersion::PFH5;
         file.write_all(&header)?;
         file.write_all(&pack_file_index)?;
         file.write_all(&packed_file_index)?;
-        for packed_file in &self.packed_files {
-            let data = packed_file.get_ref_raw().get_raw_data()?;
-            file.write_all(&data)?;
+
+        let mut data_position = (header.len() + pack_file_index.len() + packed_file_index.len()) as u64;
+        if is_data_padded {
+            let padding = (8 - data_position % 8) % 8;
+            file.write_all(&vec![0; padding as usize])?;
+            data_position += padding;
+        }
+
+        // Keep the position of the PackedFiles we copied from disk, so we can point them to the new file once it's done.
+        let mut packed_files_on_disk = vec![];
+        for (index, packed_file) in self.packed_files.iter().enumerate() {
+            let raw_packed_file = packed_file.get_ref_raw();
+            let data = raw_packed_file.get_raw_data()?;
+            match (raw_packed_file.get_encryption_state(), is_data_encrypted) {
+                (false, true) => file.write_all(&encrypt_packed_file(&data))?,
+                (true, false) => file.write_all(&decrypt_packed_file(&data))?,
+                _ => file.write_all(&data)?,
+            }
+
+            if raw_packed_file.is_on_disk() {
+                packed_files_on_disk.push((index, data_position));
+            }
+
+            data_position += data.len() as u64;
+            if is_data_padded {
+                let padding = (8 - data.len() % 8) % 8;
+                file.write_all(&vec![0; padding])?;
+                data_position += padding as u64;
+            }
+        }
+
+        // Arena PackFiles have extra data at the end. Make sure we write it back.
+        if self.bitmask.contains(PFHFlags::HAS_EXTENDED_HEADER) && self.pfh_version == PFHVersion::PFH5 {
+            let mut footer = self.extended_header.footer.to_vec();
+            footer.resize(EXTENDED_HEADER_FOOTER_SIZE, 0);
+            file.write_all(&footer)?;
+        }
+
+        file.flush()?;
+        drop(file);
+        temp_file.persist(&self.file_path).map_err(|error| error.error)?;
+
+        // The old file is gone, so the PackedFiles that were still on disk need to read from the new one.
+        if !packed_files_on_disk.is_empty() {
+            let reader = Arc::new(Mutex::new(BufReader::new(File::open(&self.file_path)?)));
+            for (index, data_position) in packed_files_on_disk {
+                let raw_packed_file = self.packed_files[index].get_ref_mut_raw();
+                let raw_on_disk = RawOnDisk::new(
+                    reader.clone(),
+                    data_position,
+                    raw_packed_file.get_size(),
+                    raw_packed_file.get_should_be_compressed(),
+                    *raw_packed_file.get_should_be_encrypted(),
+                );
+                raw_packed_file.set_data_on_disk(raw_on_disk);
+            }
         }
 
         // Remove again the reserved PackedFiles.
@@ -2818,7 +3300,9 @@ impl Default for PackFileSettings {
 
         settings_text.insert("diagnostics_files_to_ignore".to_owned(), "".to_owned());
         settings_text.insert("import_files_to_ignore".to_owned(), "".to_owned());
+        settings_text.insert("compression_files_to_ignore".to_owned(), "".to_owned());
         settings_bool.insert("disable_autosaves".to_owned(), false);
+        settings_bool.insert("allow_table_compression".to_owned(), false);
 
         Self {
             settings_text,
@@ -2865,6 +3349,63 @@ impl PackFileSettings {
 
         Ok(settings)
     }
+
+    /// This function returns the list of rules of the `compression_files_to_ignore` setting.
+    ///
+    /// The setting contains one rule per line: either a path to a file/folder (like `movies/intro.ca_vp8` or `audio`),
+    /// or an extension preceded by an asterisk (like `*.wem`). PackedFiles matching any rule are never compressed.
+    pub fn get_compression_files_to_ignore(&self) -> Vec<CompressionIgnoreRule> {
+        self.settings_text.get("compression_files_to_ignore").map(|files_to_ignore| {
+            files_to_ignore.split('\n')
+                .map(|x| x.trim())
+                .filter(|x| !x.is_empty())
+                .map(|x| if let Some(extension) = x.strip_prefix('*') {
+                    CompressionIgnoreRule::Extension(extension.to_lowercase())
+                } else {
+                    CompressionIgnoreRule::Path(x.split('/').filter(|y| !y.is_empty()).map(|y| y.to_lowercase()).collect())
+                })
+                .collect()
+        }).unwrap_or_else(Vec::new)
+    }
+}
+
+/// Implementation of `CompressionIgnoreRule`.
+impl CompressionIgnoreRule {
+
+    /// This function returns if the provided path matches this rule. Matches are case-insensitive.
+    pub fn matches(&self, path: &[String]) -> bool {
+        match self {
+            Self::Path(rule_path) => !rule_path.is_empty() && path.len() >= rule_path.len() && rule_path.iter().zip(path).all(|(x, y)| *x == y.to_lowercase()),
+            Self::Extension(extension) => path.last().map_or(false, |name| name.to_lowercase().ends_with(extension)),
+        }
+    }
+}
+
+/// Implementation of `PackFileRecoveryReport`.
+impl PackFileRecoveryReport {
+
+    /// This function returns if the PackFile read in recovery mode had nothing wrong with it.
+    pub fn is_clean(&self) -> bool {
+        self.lost_packed_files.is_empty() &&
+            !self.damaged_index &&
+            !self.invalid_subheader &&
+            self.wrong_packed_file_count.is_none() &&
+            self.wrong_size.is_none()
+    }
+}
+
+/// Implementaion of trait `Default` for `PFHExtendedHeader`.
+impl Default for PFHExtendedHeader {
+    fn default() -> Self {
+        Self {
+            unknown_1: 0,
+            unknown_2: 0,
+            unknown_3: 0,
+            unknown_4: 0,
+            unknown_5: 0,
+            footer: vec![0; EXTENDED_HEADER_FOOTER_SIZE],
+        }
+    }
 }
 
 /// Implementaion of trait `Default` for `PFHFlags`.
@@ -352,6 +352,16 @@ impl PackedFile {
         Ok(())
     }
 
+    /// This function tries to encode a `DecodedPackedFile` into a `RawPackedFile`, storing the results in the `Packedfile`.
+    ///
+    /// Unlike `encode`, if the PackedFile is not decoded or has no saving support, its data is not loaded to memory.
+    pub fn encode_no_load(&mut self) -> Result<()> {
+        if let Some(data) = self.decoded.encode() {
+            self.raw.set_data(&data?);
+        }
+        Ok(())
+    }
+
     /// This function tries to encode a `DecodedPackedFile` into a `RawPackedFile`, storing the results in the `Packedfile`.
     /// Then, it removes the decoded data from the cache.
     ///
@@ -600,6 +610,16 @@ impl RawPackedFile {
         self.data = PackedFileData::OnMemory(data.to_vec(), false, None);
     }
 
+    /// This function replaces the data on the `RawPackedFile` with a reference to data on disk.
+    pub fn set_data_on_disk(&mut self, raw_on_disk: RawOnDisk) {
+        self.data = PackedFileData::OnDisk(raw_on_disk);
+    }
+
+    /// This function returns if the data of the provided `RawPackedFile` is still on disk, or it has been loaded to memory.
+    pub fn is_on_disk(&self) -> bool {
+        matches!(self.data, PackedFileData::OnDisk(_))
+    }
+
     /// This function returns the size of the data of the provided `RawPackedFile`.
     pub fn get_size(&self) -> u32 {
         match self.data {
@@ -675,8 +695,9 @@ impl RawPackedFile {
     ///
     /// This can fail if you pass it an empty path, so make sure you check the result.
     ///
-    /// ***WARNING***: DON'T USE THIS IF YOUR PACKEDFILE IS INSIDE A PACKFILE. USE THE `move_packedfile` FUNCTION INSTEAD.
-    pub fn set_path(&mut self, path: &[String]) -> Result<()> {
+    /// It's crate-private because the `PackFile` keeps an index of the paths of its PackedFiles. From outside, renames must go
+    /// through `PackFile::move_packedfile`, or the index would stop finding the renamed PackedFile.
+    pub(crate) fn set_path(&mut self, path: &[String]) -> Result<()> {
         if path.is_empty() { return Err(ErrorKind::EmptyInput.into()) }
         self.path = path.to_vec();
         Ok(())
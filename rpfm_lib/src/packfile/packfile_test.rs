@@ -12,9 +12,20 @@
 Module containing test for the `PackFile` module, just to make sure we don't break it... again...
 !*/
 
+use std::collections::BTreeMap;
+use std::fs::{read, write};
 use std::path::PathBuf;
 
-use super::PackFile;
+use crate::packedfile::{DecodedPackedFile, PackedFileType};
+use crate::packedfile::table::DecodedData;
+use crate::packedfile::table::loc::Loc;
+use crate::packedfile::text::TextType;
+
+use super::{PackFile, PathType, PFHFileType, PFHFlags, PFHVersion};
+use super::load_order::LoadOrder;
+use super::packedfile::PackedFile;
+use crate::schema::{Definition, Field, FieldType};
+use super::compression::{compress_data, decompress_data};
 
 #[test]
 fn test_decode_pfh6() {
@@ -142,3 +153,356 @@ fn test_encode_pfh0() {
 
 	assert_eq!(pack_file_base, pack_file_new);
 }
+
+#[test]
+fn test_compression_roundtrip() {
+	let data = (0..100_000u32).flat_map(|x| (x % 251).to_le_bytes().to_vec()).collect::<Vec<u8>>();
+	let compressed_data = compress_data(&data).unwrap();
+
+	// CA's header: uncompressed size, then the LZMA properties, then the dictionary size.
+	assert_eq!(&compressed_data[0..4], &(data.len() as u32).to_le_bytes());
+	assert_eq!(&compressed_data[4..9], &[0x5D, 0x00, 0x00, 0x40, 0x00]);
+	assert!(compressed_data.len() < data.len());
+	assert_eq!(decompress_data(&compressed_data).unwrap(), data);
+}
+
+#[test]
+fn test_compression_per_packed_file() {
+    let mut pack_file = PackFile::read(&PathBuf::from("../test_files/PFH5_test.pack"), false).unwrap();
+    let mut settings = pack_file.get_settings().clone();
+    settings.settings_text.insert("compression_files_to_ignore".to_owned(), "*.tga".to_owned());
+    pack_file.set_settings(&settings);
+
+    // Tables and files in the ignore list must not be compressed, even if we ask for it.
+    pack_file.toggle_compression(true);
+    pack_file.set_compression_by_packed_file_types(&[PackedFileType::Text(TextType::Plain)], false);
+    pack_file.set_compression_by_path_types(&[PathType::File(vec!["test.lua".to_owned()])], true);
+    pack_file.save(Some(PathBuf::from("../test_files/PFH5_test_compression.pack"))).unwrap();
+
+    let pack_file_new = PackFile::read(&PathBuf::from("../test_files/PFH5_test_compression.pack"), false).unwrap();
+    for packed_file in pack_file_new.get_ref_packed_files_all() {
+        let path = packed_file.get_path().join("/");
+        let packed_file_base = pack_file.get_ref_packed_file_by_path(packed_file.get_path()).unwrap();
+        assert_eq!(packed_file.get_ref_raw().get_compression_state(), path == "test.lua", "{}", path);
+        assert_eq!(packed_file.get_ref_raw().get_data().unwrap(), packed_file_base.get_ref_raw().get_data().unwrap());
+    }
+}
+
+#[test]
+fn test_encode_pfh5_encrypted_index() {
+    test_encode_encrypted("PFH5", PFHFlags::HAS_ENCRYPTED_INDEX | PFHFlags::HAS_INDEX_WITH_TIMESTAMPS);
+}
+
+#[test]
+fn test_encode_pfh5_encrypted_data() {
+    test_encode_encrypted("PFH5", PFHFlags::HAS_ENCRYPTED_DATA);
+}
+
+#[test]
+fn test_encode_pfh4_encrypted_index_and_data() {
+    test_encode_encrypted("PFH4", PFHFlags::HAS_ENCRYPTED_INDEX | PFHFlags::HAS_ENCRYPTED_DATA);
+}
+
+/// This function saves the test PackFile of the provided version with the provided flags, then checks that it can be read back.
+fn test_encode_encrypted(version: &str, flags: PFHFlags) {
+    let mut pack_file_base = PackFile::read(&PathBuf::from(format!("../test_files/{}_test.pack", version)), false).unwrap();
+    let path = PathBuf::from(format!("../test_files/{}_test_encrypted_{}.pack", version, flags.bits()));
+    pack_file_base.set_bitmask(pack_file_base.get_bitmask() | flags);
+    pack_file_base.clone().save(Some(path.to_path_buf())).unwrap();
+
+    // The encrypted parts should not contain the paths/data in plain text.
+    let raw_data = std::fs::read(&path).unwrap();
+    let contains_path = raw_data.windows(8).any(|x| x == b"test.lua");
+    let contains_data = raw_data.windows(11).any(|x| x == b"Never gonna");
+    assert_eq!(contains_path, !flags.contains(PFHFlags::HAS_ENCRYPTED_INDEX));
+    assert_eq!(contains_data, !flags.contains(PFHFlags::HAS_ENCRYPTED_DATA));
+
+    let pack_file_new = PackFile::read(&path, false).unwrap();
+    assert_eq!(pack_file_new.get_bitmask(), pack_file_base.get_bitmask());
+    assert_eq!(pack_file_new.get_packedfiles_list(), pack_file_base.get_packedfiles_list());
+    for packed_file in pack_file_new.get_ref_packed_files_all() {
+        let packed_file_base = pack_file_base.get_ref_packed_file_by_path(packed_file.get_path()).unwrap();
+        assert_eq!(packed_file.get_raw_data().unwrap(), packed_file_base.get_raw_data().unwrap());
+        assert_eq!(packed_file.get_ref_raw().get_timestamp(), packed_file_base.get_ref_raw().get_timestamp());
+    }
+}
+
+#[test]
+fn test_encode_pfh5_extended_header() {
+    let mut pack_file_base = PackFile::read(&PathBuf::from("../test_files/PFH5_test.pack"), false).unwrap();
+    pack_file_base.set_bitmask(PFHFlags::HAS_EXTENDED_HEADER | PFHFlags::HAS_ENCRYPTED_INDEX | PFHFlags::HAS_ENCRYPTED_DATA | PFHFlags::HAS_INDEX_WITH_TIMESTAMPS);
+    let extended_header = pack_file_base.get_ref_mut_extended_header();
+    extended_header.set_unknown_1(1);
+    extended_header.set_unknown_2(2);
+    extended_header.set_unknown_3(3);
+    extended_header.set_unknown_4(4);
+    extended_header.set_unknown_5(5);
+    extended_header.set_footer((0..=255).collect());
+    assert!(pack_file_base.is_editable(false));
+    pack_file_base.save(Some(PathBuf::from("../test_files/PFH5_test_extended_header.pack"))).unwrap();
+
+    // If nothing changed, saving it again should result in the exact same file, except for the timestamp.
+    let mut pack_file_new = PackFile::read(&PathBuf::from("../test_files/PFH5_test_extended_header.pack"), false).unwrap();
+    assert_eq!(pack_file_new.get_ref_extended_header(), pack_file_base.get_ref_extended_header());
+    pack_file_new.save(Some(PathBuf::from("../test_files/PFH5_test_extended_header_encode.pack"))).unwrap();
+
+    let mut data_base = std::fs::read("../test_files/PFH5_test_extended_header.pack").unwrap();
+    let mut data_new = std::fs::read("../test_files/PFH5_test_extended_header_encode.pack").unwrap();
+    data_base[24..28].copy_from_slice(&[0; 4]);
+    data_new[24..28].copy_from_slice(&[0; 4]);
+    assert_eq!(data_base, data_new);
+}
+
+#[test]
+fn test_encode_pfh6_and_pfh3_extended_header() {
+	for version in &["PFH6", "PFH3"] {
+		let mut pack_file_base = PackFile::read(&PathBuf::from(format!("../test_files/{}_test.pack", version)), false).unwrap();
+		pack_file_base.set_bitmask(pack_file_base.get_bitmask() | PFHFlags::HAS_EXTENDED_HEADER);
+		let extended_header = pack_file_base.get_ref_mut_extended_header();
+		extended_header.set_unknown_1(1);
+		extended_header.set_unknown_2(2);
+		extended_header.set_unknown_3(3);
+		extended_header.set_unknown_4(4);
+		extended_header.set_unknown_5(5);
+		let path_base = PathBuf::from(format!("../test_files/{}_test_extended_header.pack", version));
+		let path_new = PathBuf::from(format!("../test_files/{}_test_extended_header_encode.pack", version));
+		pack_file_base.save(Some(path_base.to_path_buf())).unwrap();
+
+		// The extended header, and the subheader that goes after it in PFH6, must survive a read/save round-trip.
+		let mut pack_file_new = PackFile::read(&path_base, false).unwrap();
+		assert_eq!(pack_file_new.get_ref_extended_header(), pack_file_base.get_ref_extended_header());
+		assert_eq!(pack_file_new.get_game_version(), pack_file_base.get_game_version());
+		assert_eq!(pack_file_new.get_authoring_tool(), pack_file_base.get_authoring_tool());
+		assert_eq!(pack_file_new.get_packed_files_all_paths(), pack_file_base.get_packed_files_all_paths());
+		pack_file_new.save(Some(path_new.to_path_buf())).unwrap();
+
+		let mut data_base = std::fs::read(&path_base).unwrap();
+		let mut data_new = std::fs::read(&path_new).unwrap();
+		data_base[24..32].copy_from_slice(&[0; 8]);
+		data_new[24..32].copy_from_slice(&[0; 8]);
+		assert_eq!(data_base, data_new);
+	}
+}
+
+#[test]
+fn test_encode_lazy_loaded_over_itself() {
+    let pack_file_base = PackFile::read(&PathBuf::from("../test_files/PFH5_test.pack"), false).unwrap();
+    let path = PathBuf::from("../test_files/PFH5_test_lazy_loaded.pack");
+    let mut pack_file_encrypted = pack_file_base.clone();
+    pack_file_encrypted.set_bitmask(pack_file_base.get_bitmask() | PFHFlags::HAS_ENCRYPTED_DATA);
+    pack_file_encrypted.save(Some(path.to_path_buf())).unwrap();
+
+    // Save it over itself without loading its data, and decrypting it in the process.
+    let mut pack_file_lazy = PackFile::read(&path, true).unwrap();
+    assert!(pack_file_lazy.get_ref_packed_files_all().iter().all(|x| x.get_ref_raw().is_on_disk()));
+    pack_file_lazy.set_bitmask(pack_file_base.get_bitmask());
+    pack_file_lazy.save(None).unwrap();
+
+    // Both, the PackFile we saved and the one we read from disk, should have the same data as the original one.
+    let pack_file_new = PackFile::read(&path, false).unwrap();
+    assert_eq!(pack_file_new.get_packedfiles_list(), pack_file_base.get_packedfiles_list());
+    for packed_file in pack_file_new.get_ref_packed_files_all() {
+        let packed_file_base = pack_file_base.get_ref_packed_file_by_path(packed_file.get_path()).unwrap();
+        let packed_file_lazy = pack_file_lazy.get_ref_packed_file_by_path(packed_file.get_path()).unwrap();
+        assert_eq!(packed_file.get_raw_data().unwrap(), packed_file_base.get_raw_data().unwrap());
+        assert_eq!(packed_file_lazy.get_raw_data().unwrap(), packed_file_base.get_raw_data().unwrap());
+        assert!(!packed_file.get_ref_raw().get_encryption_state());
+    }
+}
+
+#[test]
+fn test_path_index() {
+    let path = |x: &str| x.split('/').map(|x| x.to_owned()).collect::<Vec<String>>();
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    let packed_files = ["db/a_tables/a", "db/a_tables/b", "db/b_tables/a", "text/a.txt", "script/a.lua"].iter()
+        .map(|x| PackedFile::new(path(x), "test.pack".to_owned()))
+        .collect::<Vec<PackedFile>>();
+    pack_file.add_packed_files(&packed_files.iter().collect::<Vec<&PackedFile>>(), false).unwrap();
+
+    assert!(pack_file.packedfile_exists(&path("db/a_tables/b")));
+    assert!(!pack_file.packedfile_exists(&path("DB/a_tables/b")));
+    assert!(pack_file.folder_exists(&path("db/a_tables")));
+    assert!(!pack_file.folder_exists(&path("db/a_tables/b")));
+    assert_eq!(pack_file.get_packed_files_paths_by_path_start(&path("db")).len(), 3);
+
+    // Conflicts should be renamed, and the renamed files found by their new path.
+    let added = pack_file.add_packed_file(&packed_files[0], false).unwrap();
+    assert_eq!(added, path("db/a_tables/a_0"));
+    assert!(pack_file.get_ref_packed_file_by_path(&added).is_some());
+
+    // Moves and renames should keep the index in sync.
+    pack_file.move_folder(&path("db/a_tables"), &path("db/c_tables"), false).unwrap();
+    assert!(!pack_file.folder_exists(&path("db/a_tables")));
+    assert_eq!(pack_file.get_packed_files_paths_by_path_start(&path("db/c_tables")), vec![path("db/c_tables/a"), path("db/c_tables/b"), path("db/c_tables/a_0")]);
+
+    pack_file.rename_packedfiles(&[(PathType::File(path("text/a.txt")), "b.txt".to_owned())], false);
+    assert!(pack_file.packedfile_exists(&path("text/b.txt")));
+    assert!(!pack_file.packedfile_exists(&path("text/a.txt")));
+
+    // And so should removals, even when they change the position of the rest of PackedFiles.
+    pack_file.remove_packed_files_by_type(&[PathType::File(path("db/c_tables/b")), PathType::Folder(path("db/b_tables"))]);
+    assert!(!pack_file.folder_exists(&path("db/b_tables")));
+    assert_eq!(pack_file.get_packed_files_paths_by_path_start(&path("db")), vec![path("db/c_tables/a"), path("db/c_tables/a_0")]);
+    assert_eq!(pack_file.get_ref_packed_file_by_path(&path("script/a.lua")).unwrap().get_path(), &*path("script/a.lua"));
+    assert_eq!(pack_file.get_ref_packed_files_by_paths(vec![&path("text/b.txt"), &path("script/a.lua")]).len(), 2);
+}
+
+#[test]
+fn test_diff() {
+    let path = |x: &str| x.split('/').map(|x| x.to_owned()).collect::<Vec<String>>();
+    let text = |x: &str, data: &[u8]| {
+        let mut packed_file = PackedFile::new(path(x), String::new());
+        packed_file.set_raw_data(data);
+        packed_file
+    };
+
+    let mut definition = Definition::new(1);
+    let fields = definition.get_ref_mut_fields();
+    fields.push(Field::new("key".to_owned(), FieldType::StringU16, true, None, 0, false, None, None, None, String::new(), -1, 0, BTreeMap::new()));
+    fields.push(Field::new("text".to_owned(), FieldType::StringU16, false, None, 0, false, None, None, None, String::new(), -1, 0, BTreeMap::new()));
+    fields.push(Field::new("tooltip".to_owned(), FieldType::Boolean, false, None, 0, false, None, None, None, String::new(), -1, 0, BTreeMap::new()));
+    let loc = |rows: &[(&str, &str)]| {
+        let mut loc = Loc::new(&definition);
+        loc.set_table_data(&rows.iter().map(|(key, text)| vec![
+            DecodedData::StringU16(key.to_string()),
+            DecodedData::StringU16(text.to_string()),
+            DecodedData::Boolean(true),
+        ]).collect::<Vec<Vec<DecodedData>>>()).unwrap();
+        PackedFile::new_from_decoded(&DecodedPackedFile::Loc(loc), &path("text/test.loc"))
+    };
+
+    let mut pack_file_base = PackFile::new_with_name("base.pack", PFHVersion::PFH5);
+    let mut pack_file_new = PackFile::new_with_name("new.pack", PFHVersion::PFH5);
+    pack_file_base.add_packed_files(&[&text("script/same.lua", b"same"), &text("script/changed.lua", b"old"), &text("script/removed.lua", b"removed"), &text("script/renamed.lua", b"renamed"), &loc(&[("a", "a"), ("b", "b"), ("c", "c")])], false).unwrap();
+    pack_file_new.add_packed_files(&[&text("script/same.lua", b"same"), &text("script/changed.lua", b"new"), &text("script/added.lua", b"added"), &text("script/new_name.lua", b"renamed"), &loc(&[("b", "b"), ("a", "z"), ("d", "d")])], false).unwrap();
+
+    let diff = pack_file_base.diff(&pack_file_new).unwrap();
+    assert_eq!(diff.get_ref_added(), &[path("script/added.lua")]);
+    assert_eq!(diff.get_ref_removed(), &[path("script/removed.lua")]);
+    assert_eq!(diff.get_ref_renamed(), &[(path("script/renamed.lua"), path("script/new_name.lua"))]);
+    assert_eq!(diff.get_ref_changed().iter().map(|x| x.get_ref_path().to_vec()).collect::<Vec<Vec<String>>>(), vec![path("script/changed.lua"), path("text/test.loc")]);
+
+    let table = diff.get_ref_changed()[1].get_ref_table().as_ref().unwrap();
+    assert_eq!(table.get_ref_key_columns(), &["key".to_owned()]);
+    assert_eq!(table.get_ref_added_rows(), &[vec!["d".to_owned(), "d".to_owned(), "true".to_owned()]]);
+    assert_eq!(table.get_ref_removed_rows(), &[vec!["c".to_owned(), "c".to_owned(), "true".to_owned()]]);
+    assert_eq!(table.get_ref_changed_rows().len(), 1);
+    assert_eq!(table.get_ref_changed_rows()[0].get_ref_key(), &["a".to_owned()]);
+    assert_eq!(table.get_ref_changed_rows()[0].get_ref_cells()[0].get_ref_column(), "text");
+    assert_eq!(table.get_ref_changed_rows()[0].get_ref_cells()[0].get_ref_value_base(), "a");
+    assert_eq!(table.get_ref_changed_rows()[0].get_ref_cells()[0].get_ref_value_new(), "z");
+
+    assert!(pack_file_base.diff(&pack_file_base).unwrap().is_empty());
+}
+
+#[test]
+fn test_read_and_recover() {
+    let pack_file_base = PackFile::read(&PathBuf::from("../test_files/PFH5_test.pack"), false).unwrap();
+    let data_base = read("../test_files/PFH5_test.pack").unwrap();
+    let path = PathBuf::from("../test_files/PFH5_test_recovered.pack");
+
+    // An undamaged PackFile should be read the same way in recovery mode.
+    let (pack_file, report) = PackFile::read_and_recover(&PathBuf::from("../test_files/PFH5_test.pack")).unwrap();
+    assert!(report.is_clean());
+    assert_eq!(pack_file.get_packedfiles_list(), pack_file_base.get_packedfiles_list());
+
+    // Truncated PackFiles should lose only the PackedFiles which data is not complete.
+    write(&path, &data_base[..data_base.len() - 1]).unwrap();
+    assert!(PackFile::read(&path, false).is_err());
+
+    let (mut pack_file, report) = PackFile::read_and_recover(&path).unwrap();
+    assert!(!report.is_clean());
+    assert_eq!(report.get_ref_lost_packed_files().len(), 1);
+    let lost_path = &report.get_ref_lost_packed_files()[0].0;
+    assert_eq!(report.get_ref_wrong_size(), &Some((data_base.len() as u64 - 1, data_base.len() as u64)));
+
+    let paths_expected = pack_file_base.get_packedfiles_list().into_iter().filter(|path| path != lost_path).collect::<Vec<Vec<String>>>();
+    assert_eq!(pack_file.get_packedfiles_list(), paths_expected);
+
+    // And the recovered PackFile should be saveable over the damaged one.
+    pack_file.save(None).unwrap();
+    assert_eq!(PackFile::read(&path, false).unwrap().get_packedfiles_list(), paths_expected);
+
+    // A wrong PackedFile count should be fixed by reading the entire index.
+    let mut data = data_base.to_vec();
+    let packed_file_count = u32::from_le_bytes([data[16], data[17], data[18], data[19]]);
+    data[16..20].copy_from_slice(&1000u32.to_le_bytes());
+    write(&path, &data).unwrap();
+    assert!(PackFile::read(&path, false).is_err());
+
+    let (pack_file, report) = PackFile::read_and_recover(&path).unwrap();
+    assert_eq!(report.get_ref_wrong_packed_file_count(), &Some((1000, packed_file_count)));
+    assert!(report.get_ref_lost_packed_files().is_empty());
+    assert_eq!(pack_file.get_packedfiles_list(), pack_file_base.get_packedfiles_list());
+
+    // An invalid subheader should be replaced with a default one.
+    let mut data = read("../test_files/PFH6_test.pack").unwrap();
+    data[44..52].copy_from_slice(&[0xFF; 8]);
+    let path = PathBuf::from("../test_files/PFH6_test_recovered.pack");
+    write(&path, &data).unwrap();
+    assert!(PackFile::read(&path, false).is_err());
+
+    let (pack_file, report) = PackFile::read_and_recover(&path).unwrap();
+    assert!(*report.get_ref_invalid_subheader());
+    assert_eq!(pack_file.get_packedfiles_list(), PackFile::read(&PathBuf::from("../test_files/PFH6_test.pack"), false).unwrap().get_packedfiles_list());
+}
+
+#[test]
+fn test_load_order() {
+    let path = |x: &str| x.split('/').map(|x| x.to_owned()).collect::<Vec<String>>();
+    let data_path = tempfile::tempdir().unwrap();
+    let mods_path = tempfile::tempdir().unwrap();
+
+    // Mod PackFiles are the only ones we can save, so we change their type directly in the header.
+    let save = |folder: &std::path::Path, name: &str, pfh_file_type: PFHFileType, paths: &[&str]| {
+        let mut pack_file = PackFile::new_with_name(name, PFHVersion::PFH5);
+        let packed_files = paths.iter().map(|x| PackedFile::new(path(x), name.to_owned())).collect::<Vec<PackedFile>>();
+        pack_file.add_packed_files(&packed_files.iter().collect::<Vec<&PackedFile>>(), false).unwrap();
+        pack_file.save(Some(folder.join(name))).unwrap();
+
+        let mut data = read(folder.join(name)).unwrap();
+        data[4] = pfh_file_type.get_value() as u8;
+        write(folder.join(name), data).unwrap();
+        folder.join(name)
+    };
+
+    let boot = save(data_path.path(), "boot.pack", PFHFileType::Boot, &["db/a_tables/a", "script/a.lua"]);
+    let data_b = save(data_path.path(), "data_b.pack", PFHFileType::Release, &["db/a_tables/a", "db/b_tables/b"]);
+    let data_a = save(data_path.path(), "data_a.pack", PFHFileType::Release, &["db/b_tables/b"]);
+    let patch = save(data_path.path(), "patch.pack", PFHFileType::Patch, &["db/B_tables/b"]);
+    let mod_data = save(data_path.path(), "mod_data.pack", PFHFileType::Mod, &["db/a_tables/a", "text/a.txt"]);
+    let mod_disabled = save(data_path.path(), "mod_disabled.pack", PFHFileType::Mod, &["script/a.lua"]);
+    let mod_working = save(mods_path.path(), "mod_working.pack", PFHFileType::Mod, &["text/a.txt"]);
+
+    let used_mods = format!("add_working_directory \"{}\";\nmod \"mod_working.pack\";\nmod \"mod_data.pack\";\nmod \"mod_missing.pack\";\n", mods_path.path().to_string_lossy());
+    write(data_path.path().join("used_mods.txt"), used_mods).unwrap();
+
+    let load_order = LoadOrder::new_from_used_mods(data_path.path(), &data_path.path().join("used_mods.txt")).unwrap();
+    assert_eq!(load_order.get_ref_pack_files().iter().map(|x| x.get_ref_path().to_path_buf()).collect::<Vec<PathBuf>>(), vec![
+        mod_working.to_path_buf(),
+        mod_data.to_path_buf(),
+        patch.to_path_buf(),
+        data_a.to_path_buf(),
+        data_b.to_path_buf(),
+        boot.to_path_buf(),
+    ]);
+    assert_eq!(load_order.get_ref_missing_mods(), &["mod_missing.pack".to_owned()]);
+
+    let file = load_order.get_file(&path("db/a_tables/a")).unwrap();
+    assert_eq!(file.get_ref_pack_file(), &mod_data);
+    assert_eq!(file.get_ref_shadowed_pack_files(), &[data_b.to_path_buf(), boot.to_path_buf()]);
+
+    let file = load_order.get_file(&path("db/b_tables/b")).unwrap();
+    assert_eq!(file.get_ref_path(), &path("db/B_tables/b"));
+    assert_eq!(file.get_ref_pack_file(), &patch);
+    assert_eq!(file.get_ref_shadowed_pack_files(), &[data_a.to_path_buf(), data_b.to_path_buf()]);
+
+    assert_eq!(load_order.get_file(&path("text/a.txt")).unwrap().get_ref_pack_file(), &mod_working);
+    assert_eq!(load_order.get_file(&path("script/a.lua")).unwrap().get_ref_pack_file(), &boot);
+    assert!(load_order.get_files_by_pack_file(&mod_disabled).is_empty());
+    assert_eq!(load_order.get_shadowed_files().len(), 3);
+
+    // Passing the mods directly should give us the same result.
+    let load_order_list = LoadOrder::new(data_path.path(), &[mod_working, PathBuf::from("mod_data.pack"), PathBuf::from("mod_missing.pack")]).unwrap();
+    assert_eq!(load_order, load_order_list);
+}
@@ -0,0 +1,153 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the path index of a `PackFile`.
+
+This index keeps the position of every `PackedFile` in the `PackFile` by path, so searching by path or by folder
+doesn't need to check every single `PackedFile`. Keep in mind that the positions returned by this index are *candidates*:
+the path map is case insensitive, so the caller still has to check the path of the `PackedFile` in that position.
+!*/
+
+use std::collections::HashMap;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents the path index of a `PackFile`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathIndex {
+
+    /// Positions of the `PackedFiles`, by their lowercased path.
+    files: HashMap<String, Vec<usize>>,
+
+    /// Root of the folder tree of the `PackFile`.
+    folders: FolderNode,
+}
+
+/// This struct represents a folder within the folder tree of a `PathIndex`.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct FolderNode {
+
+    /// Positions of the `PackedFiles` directly inside this folder.
+    files: Vec<usize>,
+
+    /// Subfolders of this folder, by name.
+    folders: HashMap<String, FolderNode>,
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `PathIndex`.
+impl PathIndex {
+
+    /// This function creates a new `PathIndex` from the paths of the `PackedFiles` of a `PackFile`, in order.
+    pub fn new(paths: &[&[String]]) -> Self {
+        let mut index = Self::default();
+        paths.iter().enumerate().for_each(|(position, path)| index.insert(path, position));
+        index
+    }
+
+    /// This function adds the `PackedFile` with the provided path and position to the index.
+    pub fn insert(&mut self, path: &[String], position: usize) {
+        if path.is_empty() { return }
+        self.files.entry(Self::get_key(path)).or_default().push(position);
+
+        let mut node = &mut self.folders;
+        for folder in &path[..path.len() - 1] {
+            node = node.folders.entry(folder.to_owned()).or_default();
+        }
+        node.files.push(position);
+    }
+
+    /// This function removes the `PackedFile` with the provided path and position from the index.
+    pub fn remove(&mut self, path: &[String], position: usize) {
+        if path.is_empty() { return }
+        let key = Self::get_key(path);
+        if let Some(positions) = self.files.get_mut(&key) {
+            positions.retain(|x| *x != position);
+            if positions.is_empty() {
+                self.files.remove(&key);
+            }
+        }
+
+        self.folders.remove(path, position);
+    }
+
+    /// This function returns the positions of the `PackedFiles` which path matches the provided one, ignoring case.
+    pub fn get_positions(&self, path: &[String]) -> &[usize] {
+        match self.files.get(&Self::get_key(path)) {
+            Some(positions) => positions,
+            None => &[],
+        }
+    }
+
+    /// This function returns the positions of all the `PackedFiles` inside the provided folder, sorted.
+    pub fn get_positions_by_path_start(&self, path: &[String]) -> Vec<usize> {
+        let mut positions = vec![];
+        if !path.is_empty() {
+            if let Some(node) = self.folders.get_folder(path) {
+                node.get_positions_recursive(&mut positions);
+                positions.sort_unstable();
+            }
+        }
+        positions
+    }
+
+    /// This function checks if a folder with `PackedFiles` in it exists in the index.
+    pub fn folder_exists(&self, path: &[String]) -> bool {
+        !path.is_empty() && self.folders.get_folder(path).is_some()
+    }
+
+    /// This function returns the key used to store the provided path in the path map.
+    fn get_key(path: &[String]) -> String {
+        path.join("/").to_lowercase()
+    }
+}
+
+/// Implementation of `FolderNode`.
+impl FolderNode {
+
+    /// This function returns the subfolder with the provided path, if exists.
+    fn get_folder(&self, path: &[String]) -> Option<&Self> {
+        let mut node = self;
+        for folder in path {
+            node = node.folders.get(folder)?;
+        }
+        Some(node)
+    }
+
+    /// This function adds the positions of all the `PackedFiles` inside this folder and its subfolders to the provided list.
+    fn get_positions_recursive(&self, positions: &mut Vec<usize>) {
+        positions.extend_from_slice(&self.files);
+        self.folders.values().for_each(|node| node.get_positions_recursive(positions));
+    }
+
+    /// This function removes the `PackedFile` with the provided path (relative to this folder) and position from the tree,
+    /// removing any folder that ends up empty.
+    ///
+    /// It returns true if this folder is empty after the removal.
+    fn remove(&mut self, path: &[String], position: usize) -> bool {
+        if path.len() == 1 {
+            self.files.retain(|x| *x != position);
+        }
+
+        else if let Some(node) = self.folders.get_mut(&path[0]) {
+            if node.remove(&path[1..], position) {
+                self.folders.remove(&path[0]);
+            }
+        }
+
+        self.files.is_empty() && self.folders.is_empty()
+    }
+}
@@ -16,7 +16,7 @@ This module contains all the code related with the schemas used by this lib to d
 The basic structure of an `Schema` is:
 ```rust
 (
-    version: 3,
+    version: 4,
     versioned_files: [
         DB("_kv_battle_ai_ability_usage_variables_tables", [
             (
@@ -88,6 +88,7 @@ use crate::config::get_config_path;
 use crate::packedfile::DecodedPackedFile;
 
 // Legacy Schemas, to keep backwards compatibility during updates.
+pub(crate) mod v3;
 pub(crate) mod v2;
 pub(crate) mod v1;
 pub(crate) mod v0;
@@ -102,7 +103,7 @@ pub const REMOTE: &str = "origin";
 pub const BRANCH: &str = "master";
 
 /// Current structural version of the Schema, for compatibility purpouses.
-const CURRENT_STRUCTURAL_VERSION: u16 = 3;
+const CURRENT_STRUCTURAL_VERSION: u16 = 4;
 
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
@@ -129,6 +130,9 @@ pub enum VersionedFile {
     /// It stores a `Vec<Definition>` with the definitions for each version of AnomTable files decoded.
     AnimTable(Vec<Definition>),
 
+    /// It stores a `Vec<Definition>` with the definitions for each version of CEO files decoded.
+    CEO(Vec<Definition>),
+
     /// It stores the name of the table, and a `Vec<Definition>` with the definitions for each version of that table decoded.
     DB(String, Vec<Definition>),
 
@@ -208,11 +212,20 @@ pub struct Field {
 pub enum FieldType {
     Boolean,
     F32,
+    F64,
+    I8,
     I16,
     I32,
     I64,
+    U8,
+    U16,
+    U32,
+    ColourRGB,
     StringU8,
     StringU16,
+    OptionalI16,
+    OptionalI32,
+    OptionalI64,
     OptionalStringU8,
     OptionalStringU16,
     SequenceU16(Definition),
@@ -290,6 +303,27 @@ impl Schema {
         self.versioned_files.par_iter_mut().find_any(|x| x.is_animtable()).ok_or_else(|| From::from(ErrorKind::SchemaVersionedFileNotFound))
     }
 
+    /// This function returns a copy of a specific `VersionedFile` of CEO Type from the provided `Schema`.
+    ///
+    /// By default, we assume there is only one CEO `VersionedFile` in the `Schema`, so we return that one if we find it.
+    pub fn get_versioned_file_ceo(&self) -> Result<VersionedFile> {
+        self.versioned_files.par_iter().find_any(|x| x.is_ceo()).cloned().ok_or_else(|| From::from(ErrorKind::SchemaVersionedFileNotFound))
+    }
+
+    /// This function returns a reference to a specific `VersionedFile` of CEO Type from the provided `Schema`.
+    ///
+    /// By default, we assume there is only one CEO `VersionedFile` in the `Schema`, so we return that one if we find it.
+    pub fn get_ref_versioned_file_ceo(&self) -> Result<&VersionedFile> {
+        self.versioned_files.par_iter().find_any(|x| x.is_ceo()).ok_or_else(|| From::from(ErrorKind::SchemaVersionedFileNotFound))
+    }
+
+    /// This function returns a mutable reference to a specific `VersionedFile` of CEO Type from the provided `Schema`.
+    ///
+    /// By default, we assume there is only one CEO `VersionedFile` in the `Schema`, so we return that one if we find it.
+    pub fn get_ref_mut_versioned_file_ceo(&mut self) -> Result<&mut VersionedFile> {
+        self.versioned_files.par_iter_mut().find_any(|x| x.is_ceo()).ok_or_else(|| From::from(ErrorKind::SchemaVersionedFileNotFound))
+    }
+
     /// This function returns a copy of a specific `VersionedFile` of DB Type from the provided `Schema`.
     pub fn get_versioned_file_db(&self, table_name: &str) -> Result<VersionedFile> {
         self.versioned_files.par_iter().filter(|x| x.is_db())
@@ -471,6 +505,7 @@ impl Schema {
             match x {
                 VersionedFile::AnimFragment(ref mut versions) |
                 VersionedFile::AnimTable(ref mut versions) |
+                VersionedFile::CEO(ref mut versions) |
                 VersionedFile::DB(_, ref mut versions) |
                 VersionedFile::DepManager(ref mut versions) |
                 VersionedFile::Loc(ref mut versions) |
@@ -526,10 +561,19 @@ impl Schema {
                         _ => Ordering::Less,
                     }
                 }
+                VersionedFile::CEO(_) => {
+                    match b {
+                        VersionedFile::AnimFragment(_) => Ordering::Greater,
+                        VersionedFile::AnimTable(_) => Ordering::Greater,
+                        VersionedFile::CEO(_) => Ordering::Equal,
+                        _ => Ordering::Less,
+                    }
+                }
                 VersionedFile::DB(table_name_a, _) => {
                     match b {
                         VersionedFile::AnimFragment(_) => Ordering::Greater,
                         VersionedFile::AnimTable(_) => Ordering::Greater,
+                        VersionedFile::CEO(_) => Ordering::Greater,
                         VersionedFile::DB(table_name_b, _) => table_name_a.cmp(&table_name_b),
                         _ => Ordering::Less,
                     }
@@ -538,6 +582,7 @@ impl Schema {
                     match b {
                         VersionedFile::AnimFragment(_) => Ordering::Greater,
                         VersionedFile::AnimTable(_) => Ordering::Greater,
+                        VersionedFile::CEO(_) => Ordering::Greater,
                         VersionedFile::DB(_,_) => Ordering::Greater,
                         VersionedFile::DepManager(_) => Ordering::Equal,
                         VersionedFile::Loc(_) => Ordering::Less,
@@ -585,6 +630,7 @@ impl Schema {
         v0::SchemaV0::update();
         v1::SchemaV1::update();
         v2::SchemaV2::update();
+        v3::SchemaV3::update();
     }
 
     /// This function checks if there is a new schema update in the schema repo.
@@ -750,6 +796,11 @@ impl VersionedFile {
         matches!(*self, VersionedFile::AnimTable(_))
     }
 
+    /// This function returns true if the provided `VersionedFile` is a CEO Definition. Otherwise, it returns false.
+    pub fn is_ceo(&self) -> bool {
+        matches!(*self, VersionedFile::CEO(_))
+    }
+
     /// This function returns true if the provided `VersionedFile` is a DB Definition. Otherwise, it returns false.
     pub fn is_db(&self) -> bool {
         matches!(*self, VersionedFile::DB(_,_))
@@ -775,6 +826,7 @@ impl VersionedFile {
         match &self {
             VersionedFile::AnimFragment(_) => secondary.is_anim_fragment(),
             VersionedFile::AnimTable(_) => secondary.is_animtable(),
+            VersionedFile::CEO(_) => secondary.is_ceo(),
             VersionedFile::DB(table_name,_) => match &secondary {
                 VersionedFile::DB(secondary_table_name, _) => table_name == secondary_table_name,
                 _ => false,
@@ -790,6 +842,7 @@ impl VersionedFile {
         match &self {
             VersionedFile::AnimFragment(versions) |
             VersionedFile::AnimTable(versions) |
+            VersionedFile::CEO(versions) |
             VersionedFile::DB(_, versions) |
             VersionedFile::DepManager(versions) |
             VersionedFile::Loc(versions) |
@@ -802,6 +855,7 @@ impl VersionedFile {
         match self {
             VersionedFile::AnimFragment(versions) |
             VersionedFile::AnimTable(versions) |
+            VersionedFile::CEO(versions) |
             VersionedFile::DB(_, versions) |
             VersionedFile::DepManager(versions) |
             VersionedFile::Loc(versions) |
@@ -815,6 +869,7 @@ impl VersionedFile {
         match &self {
             VersionedFile::AnimFragment(versions) |
             VersionedFile::AnimTable(versions) |
+            VersionedFile::CEO(versions) |
             VersionedFile::DB(_, versions) |
             VersionedFile::DepManager(versions) |
             VersionedFile::Loc(versions) |
@@ -827,6 +882,7 @@ impl VersionedFile {
         match self {
             VersionedFile::AnimFragment(ref mut versions) |
             VersionedFile::AnimTable(ref mut versions) |
+            VersionedFile::CEO(ref mut versions) |
             VersionedFile::DB(_, ref mut versions) |
             VersionedFile::DepManager(ref mut versions) |
             VersionedFile::Loc(ref mut versions) |
@@ -844,6 +900,7 @@ impl VersionedFile {
         match self {
             VersionedFile::AnimFragment(versions) |
             VersionedFile::AnimTable(versions) |
+            VersionedFile::CEO(versions) |
             VersionedFile::DB(_, versions) |
             VersionedFile::DepManager(versions) |
             VersionedFile::Loc(versions) |
@@ -1276,11 +1333,20 @@ impl Display for FieldType {
         match self {
             FieldType::Boolean => write!(f, "Boolean"),
             FieldType::F32 => write!(f, "F32"),
+            FieldType::F64 => write!(f, "F64"),
+            FieldType::I8 => write!(f, "I8"),
             FieldType::I16 => write!(f, "I16"),
             FieldType::I32 => write!(f, "I32"),
             FieldType::I64 => write!(f, "I64"),
+            FieldType::U8 => write!(f, "U8"),
+            FieldType::U16 => write!(f, "U16"),
+            FieldType::U32 => write!(f, "U32"),
+            FieldType::ColourRGB => write!(f, "ColourRGB"),
             FieldType::StringU8 => write!(f, "StringU8"),
             FieldType::StringU16 => write!(f, "StringU16"),
+            FieldType::OptionalI16 => write!(f, "OptionalI16"),
+            FieldType::OptionalI32 => write!(f, "OptionalI32"),
+            FieldType::OptionalI64 => write!(f, "OptionalI64"),
             FieldType::OptionalStringU8 => write!(f, "OptionalStringU8"),
             FieldType::OptionalStringU16 => write!(f, "OptionalStringU16"),
             FieldType::SequenceU16(sequence) => write!(f, "SequenceU16 of: {:#?}", sequence),
@@ -76,11 +76,7 @@ use crate::config::get_config_path;
 use crate::schema::SCHEMA_FOLDER;
 use crate::SUPPORTED_GAMES;
 
-use crate::schema::Schema as SchemaV3;
-use crate::schema::VersionedFile as VersionedFileV3;
-use crate::schema::Definition as DefinitionV3;
-use crate::schema::FieldType as FieldTypeV3;
-use crate::schema::Field as FieldV3;
+use super::v3::*;
 
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
@@ -0,0 +1,463 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to query DB Tables with SQL.
+
+This module contains the code needed to load the DB Tables of a `PackFile` and its dependencies into an in-memory SQLite database,
+query them, and write the changes of `UPDATE`/`INSERT`/`DELETE` statements back to the `PackFile`.
+
+The layout of the database is:
+- One SQL table per DB Table name (`land_units_tables`, `unit_stats_land_tables`,...) in the `main` schema, with the rows of all
+  the tables with that name in the `PackFile`. These are the ones that can be edited.
+- The same for the tables of the dependencies, but in the `dependencies` schema, and read-only.
+- The columns of each SQL table are the processed fields of its definitions, plus a `rpfm_path` column with the path of the
+  table each row belongs to. Sequences are stored as JSON text.
+
+If a table only exists in the dependencies, you can query it without the `dependencies.` prefix.
+!*/
+
+use rusqlite::{Connection, params};
+use rusqlite::types::{Value, ValueRef};
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use rpfm_error::{Error, ErrorKind, Result};
+
+use crate::dependencies::Dependencies;
+use crate::packedfile::{DecodedPackedFile, PackedFileType};
+use crate::packedfile::table::{DecodedData, Table, db::DB};
+use crate::packedfile::table::formats::{cell_from_json, cell_to_json};
+use crate::packfile::PackFile;
+use crate::packfile::packedfile::PackedFile;
+use crate::schema::{Definition, Field, FieldType, Schema};
+
+#[cfg(test)]
+mod sql_test;
+
+/// Name of the column with the path of the table each row belongs to.
+pub const SQL_PATH_COLUMN: &str = "rpfm_path";
+
+/// Name of the schema with the tables of the dependencies.
+pub const SQL_DEPENDENCIES_SCHEMA: &str = "dependencies";
+
+/// Name of the temporary table where we keep track of the tables edited by a query.
+const SQL_EDITED_TABLE: &str = "rpfm_edited_paths";
+
+//---------------------------------------------------------------------------//
+//                              Enums & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents an in-memory SQLite database with the DB Tables of a `PackFile` and its dependencies.
+pub struct SQLDatabase {
+
+    /// Connection to the in-memory database.
+    connection: Connection,
+
+    /// Newest definition of each editable SQL table, by table name. Used for tables created by queries.
+    definitions: BTreeMap<String, Definition>,
+}
+
+//---------------------------------------------------------------------------//
+//                           Implementation of SQLDatabase
+//---------------------------------------------------------------------------//
+
+/// Implementation of `SQLDatabase`.
+impl SQLDatabase {
+
+    /// This function creates a new `SQLDatabase` with the DB Tables of the provided `PackFile` and `Dependencies`.
+    ///
+    /// Tables that cannot be decoded with the provided `Schema` are ignored.
+    pub fn new(pack_file: &mut PackFile, dependencies: &Dependencies, schema: &Schema) -> Result<Self> {
+        let connection = Connection::open_in_memory()?;
+        connection.execute_batch(&format!("
+            ATTACH DATABASE ':memory:' AS {schema};
+            CREATE TEMP TABLE {edited} (table_name TEXT NOT NULL, path TEXT NOT NULL, PRIMARY KEY (table_name, path));",
+            schema = SQL_DEPENDENCIES_SCHEMA,
+            edited = SQL_EDITED_TABLE
+        ))?;
+
+        let mut database = Self {
+            connection,
+            definitions: BTreeMap::new(),
+        };
+
+        let tables = pack_file.get_ref_mut_packed_files_by_type(PackedFileType::DB, false).into_iter()
+            .filter_map(|packed_file| {
+                let path = packed_file.get_path().join("/");
+                match packed_file.decode_return_ref_no_locks(schema) {
+                    Ok(DecodedPackedFile::DB(table)) => Some((path, table.clone())),
+                    _ => None,
+                }
+            }).collect::<Vec<(String, DB)>>();
+        database.load_tables("main", &tables, true)?;
+
+        let tables = dependencies.get_ref_dependency_database().iter()
+            .filter_map(|packed_file| match packed_file.get_ref_decoded() {
+                DecodedPackedFile::DB(table) => Some((packed_file.get_path().join("/"), table.clone())),
+                _ => None,
+            }).collect::<Vec<(String, DB)>>();
+        database.load_tables(SQL_DEPENDENCIES_SCHEMA, &tables, false)?;
+
+        Ok(database)
+    }
+
+    /// This function runs a read-only query, returning its results as a `Table`.
+    ///
+    /// The type of each column of the result is the most specific one that can hold all its values.
+    pub fn query(&self, query: &str) -> Result<Table> {
+        self.connection.execute_batch("PRAGMA query_only = ON;")?;
+        let result = self.query_rows(query);
+        self.connection.execute_batch("PRAGMA query_only = OFF;")?;
+        let (names, rows) = result?;
+
+        let fields = names.iter().enumerate().map(|(column, name)| {
+            let mut field = Field::default();
+            field.set_name(name);
+            field.set_field_type(get_field_type_from_values(rows.iter().map(|row| &row[column])));
+            field
+        }).collect::<Vec<Field>>();
+
+        let mut definition = Definition::new(0);
+        *definition.get_ref_mut_fields() = fields.to_vec();
+
+        let mut table = Table::new(&definition);
+        table.set_table_data(&rows.iter().map(|row| {
+            row.iter().zip(fields.iter()).map(|(value, field)| value_to_query_cell(value, field.get_ref_field_type())).collect()
+        }).collect::<Vec<Vec<DecodedData>>>())?;
+        Ok(table)
+    }
+
+    /// This function runs one or more SQL statements, then writes the tables they edited back to the provided `PackFile`.
+    ///
+    /// It returns the paths of the edited tables. If any of them ends up with an invalid value, or in a path it cannot be, nothing is
+    /// changed, neither in the `PackFile` nor in the database. The `PackFile` must be the one used to create this `SQLDatabase`.
+    pub fn execute(&mut self, statements: &str, pack_file: &mut PackFile) -> Result<Vec<Vec<String>>> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute_batch(statements)?;
+
+        let edited = {
+            let mut statement = transaction.prepare(&format!("SELECT table_name, path FROM temp.{} ORDER BY table_name, path", SQL_EDITED_TABLE))?;
+            let edited = statement.query_map(params![], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+            edited.collect::<std::result::Result<Vec<(String, String)>, _>>()?
+        };
+
+        // Rebuild all the edited tables before touching the PackFile, so we don't leave it half-edited if one of them fails.
+        let mut tables = vec![];
+        for (table_name, path) in &edited {
+            let path_split = path.split('/').map(|x| x.to_owned()).collect::<Vec<String>>();
+            let mut table = match pack_file.get_ref_packed_file_by_path(&path_split).map(|packed_file| packed_file.get_ref_decoded()) {
+                Some(DecodedPackedFile::DB(table)) if table.get_ref_table_name() == table_name => table.clone(),
+                Some(_) => return Err(ErrorKind::SQLInvalidPath(path.to_owned(), table_name.to_owned()).into()),
+                None => {
+                    if path_split.len() != 3 || path_split[0] != "db" || path_split[1] != *table_name || path_split[2].is_empty() {
+                        return Err(ErrorKind::SQLInvalidPath(path.to_owned(), table_name.to_owned()).into());
+                    }
+                    DB::new(table_name, None, &self.definitions[table_name])
+                }
+            };
+
+            let fields = table.get_ref_definition().get_fields_processed();
+            let names = fields.iter().map(|field| quote(field.get_name())).collect::<Vec<String>>().join(", ");
+            let mut statement = transaction.prepare(&format!("SELECT {} FROM main.{} WHERE {} = ?1 ORDER BY rowid", names, quote(table_name), SQL_PATH_COLUMN))?;
+            let mut rows = statement.query(params![path])?;
+            let mut entries = vec![];
+            while let Some(row) = rows.next()? {
+                let mut entry = Vec::with_capacity(fields.len());
+                for (column, field) in fields.iter().enumerate() {
+                    let cell = value_to_cell(row.get_raw(column), field.get_ref_field_type())
+                        .map_err(|reason| Error::from(ErrorKind::SQLIncorrectValue(path.to_owned(), entries.len() + 1, field.get_name().to_owned(), reason)))?;
+                    entry.push(cell);
+                }
+                entries.push(entry);
+            }
+
+            table.set_table_data(&entries)?;
+            tables.push((path_split, table));
+        }
+
+        transaction.execute_batch(&format!("DELETE FROM temp.{};", SQL_EDITED_TABLE))?;
+
+        // Add the new tables first, as that's the only part of the write-back that can fail, and it doesn't change anything when it does.
+        // The transaction is only committed once the PackFile has been updated, so if anything fails, the database is rolled back.
+        let paths = tables.iter().map(|(path, _)| path.to_vec()).collect::<Vec<Vec<String>>>();
+        let (existing, new): (Vec<_>, Vec<_>) = tables.into_iter().partition(|(path, _)| pack_file.packedfile_exists(path));
+        let packed_files = new.into_iter().map(|(path, table)| {
+            let mut packed_file = PackedFile::new(path, pack_file.get_file_name());
+            packed_file.set_decoded(&DecodedPackedFile::DB(table));
+            packed_file
+        }).collect::<Vec<PackedFile>>();
+        pack_file.add_packed_files(&packed_files.iter().collect::<Vec<&PackedFile>>(), true)?;
+
+        for (path, table) in existing {
+            if let Some(packed_file) = pack_file.get_ref_mut_packed_file_by_path(&path) {
+                packed_file.set_decoded(&DecodedPackedFile::DB(table));
+            }
+        }
+
+        transaction.commit()?;
+        Ok(paths)
+    }
+
+    /// This function runs a query, returning the names of its columns and its rows.
+    fn query_rows(&self, query: &str) -> Result<(Vec<String>, Vec<Vec<Value>>)> {
+        let mut statement = self.connection.prepare(query)?;
+        let names = statement.column_names().iter().map(|name| name.to_string()).collect::<Vec<String>>();
+        let mut rows = statement.query(params![])?;
+        let mut values = vec![];
+        while let Some(row) = rows.next()? {
+            values.push((0..names.len()).map(|column| Value::from(row.get_raw(column))).collect::<Vec<Value>>());
+        }
+        Ok((names, values))
+    }
+
+    /// This function creates one SQL table for each table name in the provided schema, and fills it with the rows of the provided tables.
+    ///
+    /// If the tables are editable, it also adds the triggers to keep track of what gets edited. If not, it adds triggers to stop any edition.
+    fn load_tables(&mut self, sql_schema: &str, tables: &[(String, DB)], editable: bool) -> Result<()> {
+        let mut tables_by_name: BTreeMap<&str, Vec<&(String, DB)>> = BTreeMap::new();
+        for table in tables {
+            tables_by_name.entry(table.1.get_ref_table_name()).or_default().push(table);
+        }
+
+        let transaction = self.connection.transaction()?;
+        for (table_name, tables) in &tables_by_name {
+
+            // The columns are the ones of the newest version, plus the ones only older versions have, so we don't lose data on write-back.
+            let mut definitions = tables.iter().map(|(_, table)| table.get_ref_definition()).collect::<Vec<&Definition>>();
+            definitions.sort_by_key(|definition| -definition.get_version());
+            let mut columns: Vec<Field> = vec![];
+            for definition in &definitions {
+                for field in definition.get_fields_processed() {
+                    if !columns.iter().any(|column| column.get_name() == field.get_name()) {
+                        columns.push(field);
+                    }
+                }
+            }
+
+            let mut columns_sql = columns.iter().map(|field| format!("{} {}", quote(field.get_name()), get_sql_type(field.get_ref_field_type()))).collect::<Vec<String>>();
+            columns_sql.push(format!("{} TEXT NOT NULL DEFAULT {}", SQL_PATH_COLUMN, quote_text(&tables[0].0)));
+
+            let table_sql = format!("{}.{}", sql_schema, quote(table_name));
+            transaction.execute_batch(&format!("CREATE TABLE {} ({});", table_sql, columns_sql.join(", ")))?;
+
+            // Columns not in the definition of a table are left as NULL for its rows.
+            let names = columns.iter().map(|field| quote(field.get_name())).collect::<Vec<String>>().join(", ");
+            let placeholders = (1..=columns.len() + 1).map(|index| format!("?{}", index)).collect::<Vec<String>>().join(", ");
+            let mut statement = transaction.prepare(&format!("INSERT INTO {} ({}, {}) VALUES ({})", table_sql, names, SQL_PATH_COLUMN, placeholders))?;
+            for (path, table) in tables {
+                let positions = columns.iter().map(|column| table.get_ref_definition().get_fields_processed().iter().position(|field| field.get_name() == column.get_name())).collect::<Vec<Option<usize>>>();
+                for row in table.get_ref_table_data() {
+                    let mut values = positions.iter().map(|position| position.map_or(Value::Null, |position| cell_to_value(&row[position]))).collect::<Vec<Value>>();
+                    values.push(Value::Text(path.to_owned()));
+                    statement.execute(values)?;
+                }
+            }
+
+            if editable {
+                transaction.execute_batch(&format!("
+                    CREATE TEMP TRIGGER {insert} AFTER INSERT ON {table} BEGIN
+                        INSERT OR IGNORE INTO {edited} VALUES ({name}, NEW.{path});
+                    END;
+                    CREATE TEMP TRIGGER {update} AFTER UPDATE ON {table} BEGIN
+                        INSERT OR IGNORE INTO {edited} VALUES ({name}, OLD.{path});
+                        INSERT OR IGNORE INTO {edited} VALUES ({name}, NEW.{path});
+                    END;
+                    CREATE TEMP TRIGGER {delete} AFTER DELETE ON {table} BEGIN
+                        INSERT OR IGNORE INTO {edited} VALUES ({name}, OLD.{path});
+                    END;",
+                    insert = quote(&format!("rpfm_insert_{}", table_name)),
+                    update = quote(&format!("rpfm_update_{}", table_name)),
+                    delete = quote(&format!("rpfm_delete_{}", table_name)),
+                    table = table_sql,
+                    edited = SQL_EDITED_TABLE,
+                    name = quote_text(table_name),
+                    path = SQL_PATH_COLUMN,
+                ))?;
+
+                self.definitions.insert(table_name.to_string(), definitions[0].clone());
+            }
+            else {
+                for action in &["INSERT", "UPDATE", "DELETE"] {
+                    transaction.execute_batch(&format!(
+                        "CREATE TEMP TRIGGER {} BEFORE {} ON {} BEGIN SELECT RAISE(ABORT, 'The tables of the dependencies are read-only.'); END;",
+                        quote(&format!("rpfm_{}_{}_{}", sql_schema, action.to_lowercase(), table_name)),
+                        action,
+                        table_sql
+                    ))?;
+                }
+            }
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Utility functions
+//---------------------------------------------------------------------------//
+
+/// This function quotes an identifier (table or column name) so it can be used in SQL statements, whatever it contains.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// This function quotes a text so it can be used as a literal in SQL statements.
+fn quote_text(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// This function returns the SQL type (affinity) of the column for a field type.
+fn get_sql_type(field_type: &FieldType) -> &'static str {
+    match field_type {
+        FieldType::Boolean |
+        FieldType::I8 |
+        FieldType::I16 |
+        FieldType::I32 |
+        FieldType::I64 |
+        FieldType::U8 |
+        FieldType::U16 |
+        FieldType::U32 |
+        FieldType::OptionalI16 |
+        FieldType::OptionalI32 |
+        FieldType::OptionalI64 => "INTEGER",
+        FieldType::F32 |
+        FieldType::F64 => "REAL",
+        FieldType::ColourRGB |
+        FieldType::StringU8 |
+        FieldType::StringU16 |
+        FieldType::OptionalStringU8 |
+        FieldType::OptionalStringU16 |
+        FieldType::SequenceU16(_) |
+        FieldType::SequenceU32(_) => "TEXT",
+    }
+}
+
+/// This function turns a cell into a SQL value.
+fn cell_to_value(cell: &DecodedData) -> Value {
+    match cell {
+        DecodedData::Boolean(data) => Value::Integer(*data as i64),
+        DecodedData::F32(data) => Value::Real(DecodedData::f32_to_f64(*data)),
+        DecodedData::F64(data) => Value::Real(*data),
+        DecodedData::I8(data) => Value::Integer(*data as i64),
+        DecodedData::I16(data) => Value::Integer(*data as i64),
+        DecodedData::I32(data) => Value::Integer(*data as i64),
+        DecodedData::I64(data) => Value::Integer(*data),
+        DecodedData::U8(data) => Value::Integer(*data as i64),
+        DecodedData::U16(data) => Value::Integer(*data as i64),
+        DecodedData::U32(data) => Value::Integer(*data as i64),
+        DecodedData::OptionalI16(data) => data.map_or(Value::Null, |data| Value::Integer(data as i64)),
+        DecodedData::OptionalI32(data) => data.map_or(Value::Null, |data| Value::Integer(data as i64)),
+        DecodedData::OptionalI64(data) => data.map_or(Value::Null, Value::Integer),
+        DecodedData::ColourRGB(data) |
+        DecodedData::StringU8(data) |
+        DecodedData::StringU16(data) |
+        DecodedData::OptionalStringU8(data) |
+        DecodedData::OptionalStringU16(data) => Value::Text(data.to_owned()),
+        DecodedData::SequenceU16(_) |
+        DecodedData::SequenceU32(_) => Value::Text(cell_to_json(cell).to_string()),
+    }
+}
+
+/// This function turns a SQL value into a cell of the provided type.
+///
+/// There is no conversion here beyond the one SQLite does due to the type of the column: if the value is not valid for that type,
+/// it returns the reason why.
+fn value_to_cell(value: ValueRef, field_type: &FieldType) -> std::result::Result<DecodedData, String> {
+    let invalid = || format!("{} is not a valid {}.", value_to_string(value), field_type);
+    let integer = || if let ValueRef::Integer(data) = value { Ok(data) } else { Err(invalid()) };
+    match field_type {
+        FieldType::Boolean => match value {
+            ValueRef::Integer(0) => Ok(DecodedData::Boolean(false)),
+            ValueRef::Integer(1) => Ok(DecodedData::Boolean(true)),
+            _ => Err(invalid()),
+        },
+        FieldType::F32 | FieldType::F64 => {
+            let data = match value {
+                ValueRef::Integer(data) => data as f64,
+                ValueRef::Real(data) => data,
+                _ => return Err(invalid()),
+            };
+            if let FieldType::F32 = field_type { Ok(DecodedData::F32(data as f32)) } else { Ok(DecodedData::F64(data)) }
+        },
+        FieldType::I8 => i8::try_from(integer()?).map(DecodedData::I8).map_err(|_| invalid()),
+        FieldType::I16 => i16::try_from(integer()?).map(DecodedData::I16).map_err(|_| invalid()),
+        FieldType::I32 => i32::try_from(integer()?).map(DecodedData::I32).map_err(|_| invalid()),
+        FieldType::I64 => integer().map(DecodedData::I64),
+        FieldType::U8 => u8::try_from(integer()?).map(DecodedData::U8).map_err(|_| invalid()),
+        FieldType::U16 => u16::try_from(integer()?).map(DecodedData::U16).map_err(|_| invalid()),
+        FieldType::U32 => u32::try_from(integer()?).map(DecodedData::U32).map_err(|_| invalid()),
+        FieldType::OptionalI16 => if let ValueRef::Null = value { Ok(DecodedData::OptionalI16(None)) } else { i16::try_from(integer()?).map(|data| DecodedData::OptionalI16(Some(data))).map_err(|_| invalid()) },
+        FieldType::OptionalI32 => if let ValueRef::Null = value { Ok(DecodedData::OptionalI32(None)) } else { i32::try_from(integer()?).map(|data| DecodedData::OptionalI32(Some(data))).map_err(|_| invalid()) },
+        FieldType::OptionalI64 => if let ValueRef::Null = value { Ok(DecodedData::OptionalI64(None)) } else { integer().map(|data| DecodedData::OptionalI64(Some(data))) },
+        FieldType::OptionalStringU8 |
+        FieldType::OptionalStringU16 if matches!(value, ValueRef::Null) => DecodedData::new_from_string("", field_type).map_err(|_| invalid()),
+        FieldType::ColourRGB |
+        FieldType::StringU8 |
+        FieldType::StringU16 |
+        FieldType::OptionalStringU8 |
+        FieldType::OptionalStringU16 => match value {
+            ValueRef::Text(text) => DecodedData::new_from_string(&String::from_utf8_lossy(text), field_type).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        },
+        FieldType::SequenceU16(_) |
+        FieldType::SequenceU32(_) => match value {
+            ValueRef::Text(text) => match serde_json::from_slice(text) {
+                Ok(json) => cell_from_json(&json, field_type),
+                Err(_) => Err(format!("{} is not a JSON array of rows.", value_to_string(value))),
+            },
+            _ => Err(invalid()),
+        },
+    }
+}
+
+/// This function returns the most specific field type that can hold all the provided values.
+///
+/// Integers go to `I64` (or `OptionalI64` if there are NULLs), numbers to `F64`, and anything else to `StringU8`.
+fn get_field_type_from_values<'a>(values: impl Iterator<Item = &'a Value>) -> FieldType {
+    let (mut integers, mut reals, mut nulls, mut others) = (false, false, false, false);
+    for value in values {
+        match value {
+            Value::Integer(_) => integers = true,
+            Value::Real(_) => reals = true,
+            Value::Null => nulls = true,
+            Value::Text(_) | Value::Blob(_) => others = true,
+        }
+    }
+
+    if others || (reals && nulls) || (!integers && !reals) { FieldType::StringU8 }
+    else if reals { FieldType::F64 }
+    else if nulls { FieldType::OptionalI64 }
+    else { FieldType::I64 }
+}
+
+/// This function turns a value of the result of a query into a cell of the type returned by `get_field_type_from_values` for its column.
+fn value_to_query_cell(value: &Value, field_type: &FieldType) -> DecodedData {
+    match (field_type, value) {
+        (FieldType::I64, Value::Integer(data)) => DecodedData::I64(*data),
+        (FieldType::OptionalI64, Value::Integer(data)) => DecodedData::OptionalI64(Some(*data)),
+        (FieldType::OptionalI64, _) => DecodedData::OptionalI64(None),
+        (FieldType::F64, Value::Integer(data)) => DecodedData::F64(*data as f64),
+        (FieldType::F64, Value::Real(data)) => DecodedData::F64(*data),
+        (_, value) => DecodedData::StringU8(value_to_string(value.into())),
+    }
+}
+
+/// This function returns a value as text, as it would be shown to the user.
+fn value_to_string(value: ValueRef) -> String {
+    match value {
+        ValueRef::Null => String::new(),
+        ValueRef::Integer(data) => data.to_string(),
+        ValueRef::Real(data) => data.to_string(),
+        ValueRef::Text(data) | ValueRef::Blob(data) => String::from_utf8_lossy(data).to_string(),
+    }
+}
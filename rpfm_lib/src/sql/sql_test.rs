@@ -0,0 +1,115 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `SQL` module, to make sure queries see the right data and only write back what they should.
+!*/
+
+use crate::packfile::PFHVersion;
+
+use super::*;
+
+/// This function builds a `units_tables` DB Table with the provided rows of key, cost and (optional) upkeep.
+fn build_db(rows: &[(&str, i32, Option<i32>)]) -> DB {
+    let mut definition = Definition::new(1);
+    *definition.get_ref_mut_fields() = [("key", FieldType::StringU8), ("cost", FieldType::I32), ("upkeep", FieldType::OptionalI32)].iter().map(|(name, field_type)| {
+        let mut field = Field::default();
+        field.set_name(name);
+        field.set_field_type(field_type.clone());
+        field
+    }).collect();
+
+    let mut db = DB::new("units_tables", None, &definition);
+    db.set_table_data(&rows.iter().map(|(key, cost, upkeep)| vec![
+        DecodedData::StringU8(key.to_string()),
+        DecodedData::I32(*cost),
+        DecodedData::OptionalI32(*upkeep),
+    ]).collect::<Vec<Vec<DecodedData>>>()).unwrap();
+    db
+}
+
+/// This function builds a PackFile with one table, and dependencies with another one.
+fn build_data() -> (PackFile, Dependencies) {
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    let packed_file = PackedFile::new_from_decoded(&DecodedPackedFile::DB(build_db(&[("cavalry", 100, Some(10)), ("infantry", 50, None)])), &["db".to_owned(), "units_tables".to_owned(), "mod".to_owned()]);
+    pack_file.add_packed_files(&[&packed_file], true).unwrap();
+
+    let mut dependencies = Dependencies::default();
+    dependencies.get_ref_mut_dependency_database().push(PackedFile::new_from_decoded(&DecodedPackedFile::DB(build_db(&[("cavalry", 80, Some(8)), ("archers", 60, Some(5))])), &["db".to_owned(), "units_tables".to_owned(), "data__".to_owned()]));
+    (pack_file, dependencies)
+}
+
+/// This function returns the rows of the DB Table in the provided path of the PackFile.
+fn get_rows(pack_file: &PackFile, path: &str) -> Vec<Vec<DecodedData>> {
+    match pack_file.get_ref_packed_file_by_path(&path.split('/').map(|x| x.to_owned()).collect::<Vec<String>>()).unwrap().get_ref_decoded() {
+        DecodedPackedFile::DB(table) => table.get_table_data(),
+        _ => panic!("Not a DB Table."),
+    }
+}
+
+#[test]
+fn test_query() {
+    let (mut pack_file, dependencies) = build_data();
+    let database = SQLDatabase::new(&mut pack_file, &dependencies, &Schema::default()).unwrap();
+
+    let table = database.query("
+        SELECT m.key, m.cost - d.cost AS difference, m.upkeep, m.rpfm_path
+        FROM units_tables m LEFT JOIN dependencies.units_tables d ON m.key = d.key
+        ORDER BY m.key").unwrap();
+
+    let fields = table.get_ref_definition().get_fields_processed();
+    assert_eq!(fields.iter().map(|field| field.get_name()).collect::<Vec<&str>>(), vec!["key", "difference", "upkeep", "rpfm_path"]);
+    assert_eq!(fields.iter().map(|field| field.get_field_type()).collect::<Vec<FieldType>>(), vec![FieldType::StringU8, FieldType::OptionalI64, FieldType::OptionalI64, FieldType::StringU8]);
+    assert_eq!(table.get_ref_table_data(), &[
+        vec![DecodedData::StringU8("cavalry".to_owned()), DecodedData::OptionalI64(Some(20)), DecodedData::OptionalI64(Some(10)), DecodedData::StringU8("db/units_tables/mod".to_owned())],
+        vec![DecodedData::StringU8("infantry".to_owned()), DecodedData::OptionalI64(None), DecodedData::OptionalI64(None), DecodedData::StringU8("db/units_tables/mod".to_owned())],
+    ]);
+
+    // Queries must not be able to edit anything.
+    assert!(database.query("UPDATE units_tables SET cost = 0").is_err());
+    assert_eq!(database.query("SELECT SUM(cost) FROM units_tables").unwrap().get_ref_table_data(), &[vec![DecodedData::I64(150)]]);
+}
+
+#[test]
+fn test_execute() {
+    let (mut pack_file, dependencies) = build_data();
+    let mut database = SQLDatabase::new(&mut pack_file, &dependencies, &Schema::default()).unwrap();
+
+    let paths = database.execute("
+        UPDATE units_tables SET cost = cost * 2, upkeep = NULL WHERE key = 'cavalry';
+        INSERT INTO units_tables (key, cost, upkeep) SELECT key, cost, upkeep FROM dependencies.units_tables WHERE key = 'archers';
+        INSERT INTO units_tables (key, cost, upkeep, rpfm_path) VALUES ('elephants', 300, 30, 'db/units_tables/new');", &mut pack_file).unwrap();
+
+    assert_eq!(paths, vec![vec!["db".to_owned(), "units_tables".to_owned(), "mod".to_owned()], vec!["db".to_owned(), "units_tables".to_owned(), "new".to_owned()]]);
+    assert_eq!(get_rows(&pack_file, "db/units_tables/mod"), build_db(&[("cavalry", 200, None), ("infantry", 50, None), ("archers", 60, Some(5))]).get_table_data());
+    assert_eq!(get_rows(&pack_file, "db/units_tables/new"), build_db(&[("elephants", 300, Some(30))]).get_table_data());
+
+    // Statements that don't edit anything don't return any path.
+    assert!(database.execute("UPDATE units_tables SET cost = 0 WHERE key = 'nothing';", &mut pack_file).unwrap().is_empty());
+}
+
+#[test]
+fn test_execute_errors() {
+    let (mut pack_file, dependencies) = build_data();
+    let mut database = SQLDatabase::new(&mut pack_file, &dependencies, &Schema::default()).unwrap();
+    let rows = get_rows(&pack_file, "db/units_tables/mod");
+
+    // Values that don't fit in their column, invalid paths, and edits to the dependencies must fail without changing anything.
+    match database.execute("UPDATE units_tables SET cost = 'cheap' WHERE key = 'infantry';", &mut pack_file) {
+        Err(error) => assert_eq!(error.kind(), &ErrorKind::SQLIncorrectValue("db/units_tables/mod".to_owned(), 2, "cost".to_owned(), "cheap is not a valid I32.".to_owned())),
+        Ok(_) => panic!("Invalid values must not be written back."),
+    }
+    assert!(database.execute("UPDATE units_tables SET cost = 3000000000;", &mut pack_file).is_err());
+    assert!(database.execute("UPDATE units_tables SET rpfm_path = 'db/other_tables/mod';", &mut pack_file).is_err());
+    assert!(database.execute("UPDATE dependencies.units_tables SET cost = 0;", &mut pack_file).is_err());
+
+    assert_eq!(get_rows(&pack_file, "db/units_tables/mod"), rows);
+    assert_eq!(database.query("SELECT cost FROM units_tables ORDER BY rowid").unwrap().get_ref_table_data(), &[vec![DecodedData::I64(100)], vec![DecodedData::I64(50)]]);
+}
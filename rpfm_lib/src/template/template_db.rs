@@ -117,6 +117,15 @@ impl TemplateDB {
                             FieldType::StringU16 => DecodedData::StringU16(template_field.get_field_value().to_owned()),
                             FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(template_field.get_field_value().to_owned()),
                             FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(template_field.get_field_value().to_owned()),
+                            FieldType::F64 |
+                            FieldType::I8 |
+                            FieldType::U8 |
+                            FieldType::U16 |
+                            FieldType::U32 |
+                            FieldType::ColourRGB |
+                            FieldType::OptionalI16 |
+                            FieldType::OptionalI32 |
+                            FieldType::OptionalI64 => DecodedData::new_from_string(template_field.get_field_value(), field.get_ref_field_type())?,
 
                             // For now fail on Sequences. These are a bit special and I don't know if the're even possible in TSV.
                             FieldType::SequenceU16(_) => unimplemented!(),
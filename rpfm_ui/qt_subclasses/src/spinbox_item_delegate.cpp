@@ -6,7 +6,7 @@
 #include <QLineEdit>
 
 // Function to be called from any other language. This assing to the provided column of the provided TableView a QSpinBoxItemDelegate.
-// We have to pass it the integer type (16, 32 or 64) too for later checks.
+// We have to pass it the integer type (8, 16, 32 or 64) too for later checks.
 extern "C" void new_spinbox_item_delegate(QObject *parent, const int column, const int integer_type, const bool is_optional) {
     QSpinBoxItemDelegate* delegate = new QSpinBoxItemDelegate(parent, integer_type, is_optional);
     dynamic_cast<QAbstractItemView*>(parent)->setItemDelegateForColumn(column, delegate);
@@ -42,6 +42,9 @@ QWidget* QSpinBoxItemDelegate::createEditor(QWidget *parent, const QStyleOptionV
             else if (type == 16) {
                 spinBox->setRange(-32768, 32767);
             }
+            else if (type == 8) {
+                spinBox->setRange(-128, 127);
+            }
             return spinBox;
         }
     }
@@ -35,6 +35,7 @@ use rpfm_lib::GAME_SELECTED;
 use rpfm_lib::packfile::PFHFileType;
 use rpfm_lib::packedfile::*;
 use rpfm_lib::packedfile::animpack::AnimPack;
+use rpfm_lib::packedfile::table::TSVImportMode;
 use rpfm_lib::packedfile::table::db::DB;
 use rpfm_lib::packedfile::table::loc::{Loc, TSV_NAME_LOC};
 use rpfm_lib::packedfile::text::{Text, TextType};
@@ -104,7 +105,7 @@ pub fn background_loop() {
 
                         // Force decoding of table/locs, so they're in memory for the diagnostics to work.
                         if let Some(ref schema) = *SCHEMA.read().unwrap() {
-                            let mut packed_files = pack_file_decoded.get_ref_mut_packed_files_by_types(&[PackedFileType::DB, PackedFileType::Loc], false);
+                            let mut packed_files = pack_file_decoded.get_ref_mut_packed_files_by_types(&[PackedFileType::CEO, PackedFileType::DB, PackedFileType::Loc], false);
                             packed_files.par_iter_mut().for_each(|x| {
                                 let _ = x.decode_no_locks(schema);
                             });
@@ -429,7 +430,7 @@ pub fn background_loop() {
                     Some(ref packed_file) => {
                         let packed_file_decoded = packed_file.get_ref_decoded();
                         match packed_file_decoded {
-                            DecodedPackedFile::AnimPack(anim_pack) => anim_pack.get_anim_packed_as_packed_files(&paths),
+                            DecodedPackedFile::AnimPack(anim_pack) => anim_pack.get_packed_files_by_path_type(&paths),
                             _ => {
                                 CENTRAL_COMMAND.send_message_rust(Response::Error(ErrorKind::PackedFileTypeIsNotWhatWeExpected(PackedFileType::AnimPack.to_string(), PackedFileType::from(&*packed_file_decoded).to_string()).into()));
                                 continue;
@@ -544,8 +545,8 @@ pub fn background_loop() {
 
             // In case we want to Mass-Import TSV Files...
             Command::MassImportTSV(paths, name) => {
-                match pack_file_decoded.mass_import_tsv(&paths, name, true) {
-                    Ok(result) => CENTRAL_COMMAND.send_message_rust(Response::VecVecStringVecVecString(result)),
+                match pack_file_decoded.mass_import_tsv(&paths, name, true, TSVImportMode::Replace) {
+                    Ok((removed_paths, added_paths, _)) => CENTRAL_COMMAND.send_message_rust(Response::VecVecStringVecVecString((removed_paths, added_paths))),
                     Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
                 }
             }
@@ -699,12 +700,12 @@ pub fn background_loop() {
             Command::ImportTSV((internal_path, external_path)) => {
                 match pack_file_decoded.get_ref_mut_packed_file_by_path(&internal_path) {
                     Some(packed_file) => match packed_file.get_decoded() {
-                        DecodedPackedFile::DB(data) => match DB::import_tsv(&data.get_definition(), &external_path, &internal_path[1]) {
-                            Ok(data) => CENTRAL_COMMAND.send_message_rust(Response::TableType(TableType::DB(data))),
+                        DecodedPackedFile::DB(mut data) => match data.import_tsv(&external_path, &internal_path[1], TSVImportMode::Replace) {
+                            Ok(_) => CENTRAL_COMMAND.send_message_rust(Response::TableType(TableType::DB(data))),
                             Err(error) =>  CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
                         },
-                        DecodedPackedFile::Loc(data) => match Loc::import_tsv(&data.get_definition(), &external_path, &TSV_NAME_LOC) {
-                            Ok(data) => CENTRAL_COMMAND.send_message_rust(Response::TableType(TableType::Loc(data))),
+                        DecodedPackedFile::Loc(mut data) => match data.import_tsv(&external_path, &TSV_NAME_LOC, TSVImportMode::Replace) {
+                            Ok(_) => CENTRAL_COMMAND.send_message_rust(Response::TableType(TableType::Loc(data))),
                             Err(error) =>  CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
                         },
                         /*
@@ -821,9 +822,8 @@ pub fn background_loop() {
                                 match packed_file.decode_return_ref_mut() {
                                     Ok(data) => {
                                         if let DecodedPackedFile::DB(ref mut data) = data {
-                                            match DB::import_tsv(&data.get_definition(), &external_path, &path[1]) {
-                                                Ok(new_data) => {
-                                                    *data = new_data;
+                                            match data.import_tsv(&external_path, &path[1], TSVImportMode::Replace) {
+                                                Ok(_) => {
                                                     match packed_file.encode_and_clean_cache() {
                                                         Ok(_) => CENTRAL_COMMAND.send_message_save_packedfile(Response::Success),
                                                         Err(error) => CENTRAL_COMMAND.send_message_save_packedfile(Response::Error(error)),
@@ -833,9 +833,8 @@ pub fn background_loop() {
                                             }
                                         }
                                         else if let DecodedPackedFile::Loc(ref mut data) = data {
-                                            match Loc::import_tsv(&data.get_definition(), &external_path, &TSV_NAME_LOC) {
-                                                Ok(new_data) => {
-                                                    *data = new_data;
+                                            match data.import_tsv(&external_path, &TSV_NAME_LOC, TSVImportMode::Replace) {
+                                                Ok(_) => {
                                                     match packed_file.encode_and_clean_cache() {
                                                         Ok(_) => CENTRAL_COMMAND.send_message_save_packedfile(Response::Success),
                                                         Err(error) => CENTRAL_COMMAND.send_message_save_packedfile(Response::Error(error)),
@@ -402,6 +402,7 @@ impl DiagnosticsUI {
                 }
 
                 match diagnostic_type {
+                    DiagnosticType::CEO(ref diagnostic) |
                     DiagnosticType::DB(ref diagnostic) |
                     DiagnosticType::Loc(ref diagnostic) => {
                         for result in diagnostic.get_ref_result() {
@@ -643,6 +644,7 @@ impl DiagnosticsUI {
     ) {
 
         let path = match diagnostic {
+            DiagnosticType::CEO(ref diagnostic) |
             DiagnosticType::DB(ref diagnostic) |
             DiagnosticType::Loc(ref diagnostic) => diagnostic.get_path(),
             DiagnosticType::DependencyManager(_) => &[],
@@ -660,6 +662,7 @@ impl DiagnosticsUI {
                     let blocker = QSignalBlocker::from_q_object(table_model.static_upcast::<QObject>());
 
                     match diagnostic {
+                        DiagnosticType::CEO(ref diagnostic) |
                         DiagnosticType::DB(ref diagnostic) |
                         DiagnosticType::Loc(ref diagnostic) => {
                             for result in diagnostic.get_ref_result() {
@@ -846,6 +849,7 @@ impl DiagnosticsUI {
     pub unsafe fn update_level_counts(diagnostics_ui: &Rc<Self>, diagnostics: &[DiagnosticType]) {
         let info = diagnostics.iter().map(|x|
             match x {
+                DiagnosticType::CEO(ref diag) |
                 DiagnosticType::DB(ref diag) |
                 DiagnosticType::Loc(ref diag) => diag.get_ref_result()
                     .iter()
@@ -863,6 +867,7 @@ impl DiagnosticsUI {
 
         let warning = diagnostics.iter().map(|x|
             match x {
+                DiagnosticType::CEO(ref diag) |
                 DiagnosticType::DB(ref diag) |
                 DiagnosticType::Loc(ref diag) => diag.get_ref_result()
                     .iter()
@@ -881,6 +886,7 @@ impl DiagnosticsUI {
 
         let error = diagnostics.iter().map(|x|
             match x {
+                DiagnosticType::CEO(ref diag) |
                 DiagnosticType::DB(ref diag) |
                 DiagnosticType::Loc(ref diag) => diag.get_ref_result()
                     .iter()
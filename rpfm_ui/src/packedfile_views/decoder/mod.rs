@@ -65,6 +65,7 @@ use rpfm_lib::GAME_SELECTED;
 use rpfm_lib::packedfile::PackedFileType;
 use rpfm_lib::packedfile::table::{animtable, animtable::AnimTable};
 use rpfm_lib::packedfile::table::{anim_fragment, anim_fragment::AnimFragment};
+use rpfm_lib::packedfile::table::{ceo, ceo::CEO};
 use rpfm_lib::packedfile::table::db::DB;
 use rpfm_lib::packedfile::table::{loc, loc::Loc};
 use rpfm_lib::packedfile::table::{matched_combat, matched_combat::MatchedCombat};
@@ -92,6 +93,7 @@ pub mod slots;
 const SUPPORTED_PACKED_FILE_TYPES: [PackedFileType; 5] = [
     PackedFileType::AnimTable,
     PackedFileType::AnimFragment,
+    PackedFileType::CEO,
     PackedFileType::DB,
     PackedFileType::Loc,
     PackedFileType::MatchedCombat,
@@ -630,6 +632,9 @@ impl PackedFileDecoderView {
             PackedFileType::MatchedCombat => {
                 if let Ok((version, entry_count)) = MatchedCombat::read_header(&self.packed_file_data) { (version, entry_count ) } else { unimplemented!() }
             }
+            PackedFileType::CEO => {
+                if let Ok((version, entry_count)) = CEO::read_header(&self.packed_file_data) { (version, entry_count ) } else { unimplemented!() }
+            }
             _ => unimplemented!()
         };
 
@@ -984,6 +989,15 @@ impl PackedFileDecoderView {
             FieldType::I16 => "I16",
             FieldType::I32 => "I32",
             FieldType::I64 => "I64",
+            FieldType::F64 => "F64",
+            FieldType::I8 => "I8",
+            FieldType::U8 => "U8",
+            FieldType::U16 => "U16",
+            FieldType::U32 => "U32",
+            FieldType::ColourRGB => "ColourRGB",
+            FieldType::OptionalI16 => "OptionalI16",
+            FieldType::OptionalI32 => "OptionalI32",
+            FieldType::OptionalI64 => "OptionalI64",
             FieldType::StringU8 => "StringU8",
             FieldType::StringU16 => "StringU16",
             FieldType::OptionalStringU8 => "OptionalStringU8",
@@ -1150,6 +1164,60 @@ impl PackedFileDecoderView {
                     Err(_) => "Error".to_owned(),
                 }
             },
+            FieldType::F64 => {
+                match packed_file_data.decode_packedfile_float_f64(*index, &mut index) {
+                    Ok(result) => result.to_string(),
+                    Err(_) => "Error".to_owned(),
+                }
+            },
+            FieldType::I8 => {
+                match packed_file_data.decode_packedfile_integer_i8(*index, &mut index) {
+                    Ok(result) => result.to_string(),
+                    Err(_) => "Error".to_owned(),
+                }
+            },
+            FieldType::U8 => {
+                match packed_file_data.decode_packedfile_integer_u8(*index, &mut index) {
+                    Ok(result) => result.to_string(),
+                    Err(_) => "Error".to_owned(),
+                }
+            },
+            FieldType::U16 => {
+                match packed_file_data.decode_packedfile_integer_u16(*index, &mut index) {
+                    Ok(result) => result.to_string(),
+                    Err(_) => "Error".to_owned(),
+                }
+            },
+            FieldType::U32 => {
+                match packed_file_data.decode_packedfile_integer_u32(*index, &mut index) {
+                    Ok(result) => result.to_string(),
+                    Err(_) => "Error".to_owned(),
+                }
+            },
+            FieldType::ColourRGB => {
+                match packed_file_data.decode_packedfile_colour_rgb(*index, &mut index) {
+                    Ok(result) => result.to_string(),
+                    Err(_) => "Error".to_owned(),
+                }
+            },
+            FieldType::OptionalI16 => {
+                match packed_file_data.decode_packedfile_optional_integer_i16(*index, &mut index) {
+                    Ok(result) => result.map(|x| x.to_string()).unwrap_or_default(),
+                    Err(_) => "Error".to_owned(),
+                }
+            },
+            FieldType::OptionalI32 => {
+                match packed_file_data.decode_packedfile_optional_integer_i32(*index, &mut index) {
+                    Ok(result) => result.map(|x| x.to_string()).unwrap_or_default(),
+                    Err(_) => "Error".to_owned(),
+                }
+            },
+            FieldType::OptionalI64 => {
+                match packed_file_data.decode_packedfile_optional_integer_i64(*index, &mut index) {
+                    Ok(result) => result.map(|x| x.to_string()).unwrap_or_default(),
+                    Err(_) => "Error".to_owned(),
+                }
+            },
             FieldType::StringU8 => {
                 match packed_file_data.decode_packedfile_string_u8(*index, &mut index) {
                     Ok(result) => result,
@@ -1232,6 +1300,15 @@ impl PackedFileDecoderView {
                         "I16" => FieldType::I16,
                         "I32" => FieldType::I32,
                         "I64" => FieldType::I64,
+                        "F64" => FieldType::F64,
+                        "I8" => FieldType::I8,
+                        "U8" => FieldType::U8,
+                        "U16" => FieldType::U16,
+                        "U32" => FieldType::U32,
+                        "ColourRGB" => FieldType::ColourRGB,
+                        "OptionalI16" => FieldType::OptionalI16,
+                        "OptionalI32" => FieldType::OptionalI32,
+                        "OptionalI64" => FieldType::OptionalI64,
                         "StringU8" => FieldType::StringU8,
                         "StringU16" => FieldType::StringU16,
                         "OptionalStringU8" => FieldType::OptionalStringU8,
@@ -1285,6 +1362,7 @@ impl PackedFileDecoderView {
                 PackedFileType::DB => schema.get_ref_versioned_file_db(&self.packed_file_path[1]),
                 PackedFileType::Loc => schema.get_ref_versioned_file_loc(),
                 PackedFileType::MatchedCombat => schema.get_ref_versioned_file_matched_combat(),
+                PackedFileType::CEO => schema.get_ref_versioned_file_ceo(),
                 _ => unimplemented!(),
             };
 
@@ -1373,6 +1451,15 @@ impl PackedFileDecoderView {
                     "I16" => FieldType::I16,
                     "I32" => FieldType::I32,
                     "I64" => FieldType::I64,
+                    "F64" => FieldType::F64,
+                    "I8" => FieldType::I8,
+                    "U8" => FieldType::U8,
+                    "U16" => FieldType::U16,
+                    "U32" => FieldType::U32,
+                    "ColourRGB" => FieldType::ColourRGB,
+                    "OptionalI16" => FieldType::OptionalI16,
+                    "OptionalI32" => FieldType::OptionalI32,
+                    "OptionalI64" => FieldType::OptionalI64,
                     "StringU8" => FieldType::StringU8,
                     "StringU16" => FieldType::StringU16,
                     "OptionalStringU8" => FieldType::OptionalStringU8,
@@ -1428,6 +1515,7 @@ impl PackedFileDecoderView {
             PackedFileType::DB => DB::read_header(&self.packed_file_data).unwrap().0,
             PackedFileType::Loc => Loc::read_header(&self.packed_file_data).unwrap().0,
             PackedFileType::MatchedCombat => MatchedCombat::read_header(&self.packed_file_data).unwrap().0,
+            PackedFileType::CEO => CEO::read_header(&self.packed_file_data).unwrap().0,
             _ => unimplemented!(),
         };
 
@@ -1437,6 +1525,7 @@ impl PackedFileDecoderView {
             PackedFileType::DB => schema.get_ref_mut_versioned_file_db(&self.packed_file_path[1]),
             PackedFileType::Loc => schema.get_ref_mut_versioned_file_loc(),
             PackedFileType::MatchedCombat => schema.get_ref_mut_versioned_file_matched_combat(),
+            PackedFileType::CEO => schema.get_ref_mut_versioned_file_ceo(),
             _ => unimplemented!(),
         };
 
@@ -1462,6 +1551,7 @@ impl PackedFileDecoderView {
                     PackedFileType::DB => VersionedFile::DB(self.packed_file_path[1].to_owned(), definitions),
                     PackedFileType::Loc => VersionedFile::Loc(definitions),
                     PackedFileType::MatchedCombat => VersionedFile::MatchedCombat(definitions),
+                    PackedFileType::CEO => VersionedFile::CEO(definitions),
                     PackedFileType::DependencyPackFilesList => VersionedFile::DepManager(definitions),
                     _ => unimplemented!()
                 };
@@ -1650,6 +1740,7 @@ fn get_header_size(
         PackedFileType::DB => Ok(DB::read_header(packed_file_data)?.4),
         PackedFileType::Loc => Ok(loc::HEADER_SIZE),
         PackedFileType::MatchedCombat => Ok(matched_combat::HEADER_SIZE),
+        PackedFileType::CEO => Ok(ceo::HEADER_SIZE),
         _ => unimplemented!()
     }
 }
@@ -1670,6 +1761,7 @@ fn get_definition(
             PackedFileType::DB => schema.get_ref_versioned_file_db(&packed_file_path[1]),
             PackedFileType::Loc => schema.get_ref_versioned_file_loc(),
             PackedFileType::MatchedCombat => schema.get_ref_versioned_file_matched_combat(),
+            PackedFileType::CEO => schema.get_ref_versioned_file_ceo(),
             _ => unimplemented!(),
         };
 
@@ -1681,6 +1773,7 @@ fn get_definition(
                 PackedFileType::DB => DB::read_header(packed_file_data).ok()?.0,
                 PackedFileType::Loc => Loc::read_header(packed_file_data).ok()?.0,
                 PackedFileType::MatchedCombat => MatchedCombat::read_header(packed_file_data).ok()?.0,
+                PackedFileType::CEO => CEO::read_header(packed_file_data).ok()?.0,
                 _ => unimplemented!(),
             }};
 
@@ -1720,6 +1813,15 @@ unsafe fn configure_table_view(table_view: &QBox<QTreeView>) {
     list.append_q_string(&QString::from_std_str("I16"));
     list.append_q_string(&QString::from_std_str("I32"));
     list.append_q_string(&QString::from_std_str("I64"));
+    list.append_q_string(&QString::from_std_str("F64"));
+    list.append_q_string(&QString::from_std_str("I8"));
+    list.append_q_string(&QString::from_std_str("U8"));
+    list.append_q_string(&QString::from_std_str("U16"));
+    list.append_q_string(&QString::from_std_str("U32"));
+    list.append_q_string(&QString::from_std_str("ColourRGB"));
+    list.append_q_string(&QString::from_std_str("OptionalI16"));
+    list.append_q_string(&QString::from_std_str("OptionalI32"));
+    list.append_q_string(&QString::from_std_str("OptionalI64"));
     list.append_q_string(&QString::from_std_str("StringU8"));
     list.append_q_string(&QString::from_std_str("StringU16"));
     list.append_q_string(&QString::from_std_str("OptionalStringU8"));
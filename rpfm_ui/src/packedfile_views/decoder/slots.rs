@@ -31,6 +31,7 @@ use rpfm_error::ErrorKind;
 
 use rpfm_lib::packedfile::table::animtable::AnimTable;
 use rpfm_lib::packedfile::table::anim_fragment::AnimFragment;
+use rpfm_lib::packedfile::table::ceo::CEO;
 use rpfm_lib::packedfile::table::db::DB;
 use rpfm_lib::packedfile::table::loc::Loc;
 use rpfm_lib::packedfile::table::matched_combat::MatchedCombat;
@@ -494,6 +495,7 @@ impl PackedFileDecoderViewSlots {
                             PackedFileType::DB => schema.get_ref_mut_versioned_file_db(&view.packed_file_path[1]),
                             PackedFileType::Loc => schema.get_ref_mut_versioned_file_loc(),
                             PackedFileType::MatchedCombat => schema.get_ref_mut_versioned_file_matched_combat(),
+                            PackedFileType::CEO => schema.get_ref_mut_versioned_file_ceo(),
                             _ => unimplemented!(),
                         }.unwrap();
 
@@ -559,6 +561,16 @@ impl PackedFileDecoderViewSlots {
                         }
                     }
 
+                    PackedFileType::CEO => match CEO::read(&view.packed_file_data, &schema, true) {
+                        Ok(_) => show_dialog(&view.table_view, "Seems ok.", true),
+                        Err(error) => {
+                            if let ErrorKind::TableIncompleteError(_, data) = error.kind() {
+                                let data: Table = deserialize(data).unwrap();
+                                show_debug_dialog(&app_ui.main_window, &format!("{:#?}", data.get_table_data()));
+                            }
+                        }
+                    }
+
                     PackedFileType::DB => match DB::read(&view.packed_file_data, &view.packed_file_path[1], &schema, true) {
                         Ok(_) => show_dialog(&view.table_view, "Seems ok.", true),
                         Err(error) => {
@@ -253,7 +253,7 @@ impl PackFileContentsUI {
                     &qtr("mass_import_select"),
                 );
 
-                file_dialog.set_name_filter(&QString::from_std_str("TSV Files (*.tsv)"));
+                file_dialog.set_name_filter(&QString::from_std_str("Table Files (*.tsv *.json *.csv *.xlsx)"));
                 file_dialog.set_file_mode(FileMode::ExistingFiles);
 
                 if file_dialog.exec() == 1 {
@@ -315,9 +315,13 @@ impl TemplateUI {
                         grid.add_widget_5a(&field_widget, 0, 1, 1, 1);
                         ui.params.borrow_mut().push((param.get_ref_key().to_owned(), field_widget.static_upcast(), param.get_ref_param_type().clone(), *param.get_ref_is_required()));
                     }
+                    FieldType::I8 |
                     FieldType::I16 |
                     FieldType::I32 |
-                    FieldType::I64 => {
+                    FieldType::I64 |
+                    FieldType::U8 |
+                    FieldType::U16 |
+                    FieldType::U32 => {
                         let field_widget = QSpinBox::new_1a(&widget);
 
                         let data = if let Some(default_value) = field.get_default_value() {
@@ -336,7 +340,8 @@ impl TemplateUI {
                         grid.add_widget_5a(&field_widget, 0, 1, 1, 1);
                         ui.params.borrow_mut().push((param.get_ref_key().to_owned(), field_widget.static_upcast(), param.get_ref_param_type().clone(), *param.get_ref_is_required()));
                     }
-                    FieldType::F32 => {
+                    FieldType::F32 |
+                    FieldType::F64 => {
                         let field_widget = QDoubleSpinBox::new_1a(&widget);
 
                         let data = if let Some(default_value) = field.get_default_value() {
@@ -357,6 +362,26 @@ impl TemplateUI {
                         ui.params.borrow_mut().push((param.get_ref_key().to_owned(), field_widget.static_upcast(), param.get_ref_param_type().clone(), *param.get_ref_is_required()));
                     }
 
+                    // Colours and optional numbers are written as text.
+                    FieldType::ColourRGB |
+                    FieldType::OptionalI16 |
+                    FieldType::OptionalI32 |
+                    FieldType::OptionalI64 => {
+                        let field_widget = QLineEdit::from_q_widget(&widget);
+
+                        let text = if let Some(default_value) = field.get_default_value() {
+                            default_value.to_owned()
+                        } else {
+                            String::new()
+                        };
+                        field_widget.set_text(&QString::from_std_str(&text));
+
+                        field_widget.set_minimum_width(250);
+                        grid.add_widget_5a(&label, 0, 0, 1, 1);
+                        grid.add_widget_5a(&field_widget, 0, 1, 1, 1);
+                        ui.params.borrow_mut().push((param.get_ref_key().to_owned(), field_widget.static_upcast(), param.get_ref_param_type().clone(), *param.get_ref_is_required()));
+                    }
+
                     FieldType::StringU8 |
                     FieldType::StringU16 |
                     FieldType::OptionalStringU8 |
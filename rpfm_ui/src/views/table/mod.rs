@@ -1360,6 +1360,15 @@ impl TableSearch {
                         FieldType::I16 => if replaced_text.parse::<i16>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
                         FieldType::I32 => if replaced_text.parse::<i32>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
                         FieldType::I64 => if replaced_text.parse::<i64>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
+                        FieldType::F64 => if replaced_text.parse::<f64>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
+                        FieldType::I8 => if replaced_text.parse::<i8>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
+                        FieldType::U8 => if replaced_text.parse::<u8>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
+                        FieldType::U16 => if replaced_text.parse::<u16>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
+                        FieldType::U32 => if replaced_text.parse::<u32>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
+                        field_type @ FieldType::ColourRGB |
+                        field_type @ FieldType::OptionalI16 |
+                        field_type @ FieldType::OptionalI32 |
+                        field_type @ FieldType::OptionalI64 => if DecodedData::new_from_string(&replaced_text, field_type).is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
                         _ =>  {}
                     }
                 } else { return }
@@ -1372,6 +1381,11 @@ impl TableSearch {
                 FieldType::I16 => item.set_data_2a(&QVariant::from_int(replaced_text.parse::<i16>().unwrap().into()), 2),
                 FieldType::I32 => item.set_data_2a(&QVariant::from_int(replaced_text.parse::<i32>().unwrap()), 2),
                 FieldType::I64 => item.set_data_2a(&QVariant::from_i64(replaced_text.parse::<i64>().unwrap()), 2),
+                FieldType::F64 => item.set_data_2a(&QVariant::from_double(replaced_text.parse::<f64>().unwrap()), 2),
+                FieldType::I8 => item.set_data_2a(&QVariant::from_int(replaced_text.parse::<i8>().unwrap().into()), 2),
+                FieldType::U8 => item.set_data_2a(&QVariant::from_int(replaced_text.parse::<u8>().unwrap().into()), 2),
+                FieldType::U16 => item.set_data_2a(&QVariant::from_int(replaced_text.parse::<u16>().unwrap().into()), 2),
+                FieldType::U32 => item.set_data_2a(&QVariant::from_i64(replaced_text.parse::<u32>().unwrap().into()), 2),
                 _ => item.set_text(&QString::from_std_str(&replaced_text)),
             }
 
@@ -1418,6 +1432,11 @@ impl TableSearch {
                             FieldType::I16 => item.data_0a().to_int_0a().to_string(),
                             FieldType::I32 => item.data_0a().to_int_0a().to_string(),
                             FieldType::I64 => item.data_0a().to_long_long_0a().to_string(),
+                            FieldType::F64 => item.data_0a().to_double_0a().to_string(),
+                            FieldType::I8 |
+                            FieldType::U8 |
+                            FieldType::U16 => item.data_0a().to_int_0a().to_string(),
+                            FieldType::U32 => item.data_0a().to_long_long_0a().to_string(),
                             _ => item.text().to_std_string(),
                         };
 
@@ -1441,6 +1460,15 @@ impl TableSearch {
                             FieldType::I16 => if replaced_text.parse::<i16>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
                             FieldType::I32 => if replaced_text.parse::<i32>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
                             FieldType::I64 => if replaced_text.parse::<i64>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
+                            FieldType::F64 => if replaced_text.parse::<f64>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
+                            FieldType::I8 => if replaced_text.parse::<i8>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
+                            FieldType::U8 => if replaced_text.parse::<u8>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
+                            FieldType::U16 => if replaced_text.parse::<u16>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
+                            FieldType::U32 => if replaced_text.parse::<u32>().is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
+                            field_type @ FieldType::ColourRGB |
+                            field_type @ FieldType::OptionalI16 |
+                            field_type @ FieldType::OptionalI32 |
+                            field_type @ FieldType::OptionalI64 => if DecodedData::new_from_string(&replaced_text, field_type).is_err() { return show_dialog(&parent.table_view_primary, ErrorKind::DBTableReplaceInvalidData, false) }
                             _ =>  {}
                         }
 
@@ -1458,6 +1486,11 @@ impl TableSearch {
                     FieldType::I16 => item.set_data_2a(&QVariant::from_int(replaced_text.parse::<i16>().unwrap().into()), 2),
                     FieldType::I32 => item.set_data_2a(&QVariant::from_int(replaced_text.parse::<i32>().unwrap()), 2),
                     FieldType::I64 => item.set_data_2a(&QVariant::from_i64(replaced_text.parse::<i64>().unwrap()), 2),
+                    FieldType::F64 => item.set_data_2a(&QVariant::from_double(replaced_text.parse::<f64>().unwrap()), 2),
+                    FieldType::I8 => item.set_data_2a(&QVariant::from_int(replaced_text.parse::<i8>().unwrap().into()), 2),
+                    FieldType::U8 => item.set_data_2a(&QVariant::from_int(replaced_text.parse::<u8>().unwrap().into()), 2),
+                    FieldType::U16 => item.set_data_2a(&QVariant::from_int(replaced_text.parse::<u16>().unwrap().into()), 2),
+                    FieldType::U32 => item.set_data_2a(&QVariant::from_i64(replaced_text.parse::<u32>().unwrap().into()), 2),
                     _ => item.set_text(&QString::from_std_str(&replaced_text)),
                 }
             }
@@ -44,7 +44,7 @@ use std::collections::BTreeMap;
 use std::sync::atomic::Ordering;
 
 use rpfm_lib::packedfile::table::db::CascadeEdition;
-use rpfm_lib::packedfile::table::Table;
+use rpfm_lib::packedfile::table::{DecodedData, Table};
 
 use crate::locale::tr;
 use crate::packedfile_views::utils::set_modified;
@@ -510,6 +510,17 @@ impl TableView {
                         FieldType::I16 => text.parse::<i16>().is_ok() || text.parse::<f32>().is_ok(),
                         FieldType::I32 => text.parse::<i32>().is_ok() || text.parse::<f32>().is_ok(),
                         FieldType::I64 => text.parse::<i64>().is_ok() || text.parse::<f32>().is_ok(),
+                        FieldType::F64 => text.parse::<f64>().is_ok(),
+                        FieldType::I8 => text.parse::<i8>().is_ok() || text.parse::<f32>().is_ok(),
+                        FieldType::U8 => text.parse::<u8>().is_ok() || text.parse::<f32>().is_ok(),
+                        FieldType::U16 => text.parse::<u16>().is_ok() || text.parse::<f32>().is_ok(),
+                        FieldType::U32 => text.parse::<u32>().is_ok() || text.parse::<f32>().is_ok(),
+
+                        // Colours and optional numbers are text, but they need to be parseable.
+                        FieldType::ColourRGB |
+                        FieldType::OptionalI16 |
+                        FieldType::OptionalI32 |
+                        FieldType::OptionalI64 => DecodedData::new_from_string(text, field.get_ref_field_type()).is_ok(),
 
                         // All these are Strings, so we can skip their checks....
                         FieldType::StringU8 |
@@ -839,11 +850,33 @@ impl TableView {
                 }
                 else { data_str }
             },
+            FieldType::F64 => {
+                let data_str = format!("{}", item.data_1a(2).to_double_0a());
+                if let Some(position) = data_str.find('.') {
+                    let decimals = &data_str[position..].len();
+                    if *decimals > 3 { format!("{}", format!("{:.3}", item.data_1a(2).to_double_0a()).parse::<f64>().unwrap()) }
+                    else { data_str }
+                }
+                else { data_str }
+            },
+            FieldType::I8 |
             FieldType::I16 |
             FieldType::I32 |
-            FieldType::I64 => format!("{}", item.data_1a(2).to_long_long_0a()),
+            FieldType::I64 |
+            FieldType::U8 |
+            FieldType::U16 |
+            FieldType::U32 => format!("{}", item.data_1a(2).to_long_long_0a()),
+
+            // Empty optional numbers are nil in Lua.
+            FieldType::OptionalI16 |
+            FieldType::OptionalI32 |
+            FieldType::OptionalI64 => {
+                let data_str = item.text().to_std_string();
+                if data_str.is_empty() { "nil".to_owned() } else { data_str }
+            },
 
             // All these are Strings, so they need to escape certain chars and include commas in Lua.
+            FieldType::ColourRGB |
             FieldType::StringU8 |
             FieldType::StringU16 |
             FieldType::OptionalStringU8 |
@@ -1140,6 +1173,7 @@ impl TableView {
             let default_f32 = "0.0".to_owned();
             let default_i32 = "0".to_owned();
             let default_bool = "false".to_owned();
+            let default_colour = "000000".to_owned();
 
             let mut real_cells = vec![];
             let mut values = vec![];
@@ -1149,10 +1183,19 @@ impl TableView {
                     if index.is_valid() {
                         match self.get_ref_table_definition().get_fields_processed()[*column as usize].get_ref_field_type() {
                             FieldType::Boolean => values.push(&*default_bool),
-                            FieldType::F32 => values.push(&*default_f32),
+                            FieldType::F32 |
+                            FieldType::F64 => values.push(&*default_f32),
+                            FieldType::I8 |
                             FieldType::I16 |
                             FieldType::I32 |
-                            FieldType::I64 => values.push(&*default_i32),
+                            FieldType::I64 |
+                            FieldType::U8 |
+                            FieldType::U16 |
+                            FieldType::U32 => values.push(&*default_i32),
+                            FieldType::ColourRGB => values.push(&*default_colour),
+                            FieldType::OptionalI16 |
+                            FieldType::OptionalI32 |
+                            FieldType::OptionalI64 |
                             FieldType::StringU8 |
                             FieldType::StringU16 |
                             FieldType::OptionalStringU8 |
@@ -1263,6 +1306,74 @@ impl TableView {
                     }
                 },
 
+                FieldType::F64 => {
+                    let current_value = format!("{:.3}", self.table_model.data_2a(real_cell, 2).to_double_0a());
+                    if let Ok(new_value) = text.parse::<f64>() {
+                        let new_value_txt = format!("{:.3}", new_value);
+                        if current_value != new_value_txt {
+                            self.table_model.set_data_3a(real_cell, &QVariant::from_double(new_value), 2);
+                            changed_cells += 1;
+                            self.process_edition(self.table_model.item_from_index(real_cell));
+                        }
+                    }
+                },
+
+                FieldType::I8 => {
+
+                    // To the stupid float conversion problem avoid, this we do.
+                    let new_value = if let Ok(new_value) = text.parse::<i8>() { new_value }
+                    else if let Ok(new_value) = text.parse::<f32>() { new_value.round() as i8 }
+                    else { continue };
+
+                    if current_value != new_value.to_string() {
+                        self.table_model.set_data_3a(real_cell, &QVariant::from_int(new_value as i32), 2);
+                        changed_cells += 1;
+                        self.process_edition(self.table_model.item_from_index(real_cell));
+                    }
+                },
+
+                FieldType::U8 => {
+
+                    // To the stupid float conversion problem avoid, this we do.
+                    let new_value = if let Ok(new_value) = text.parse::<u8>() { new_value }
+                    else if let Ok(new_value) = text.parse::<f32>() { new_value.round() as u8 }
+                    else { continue };
+
+                    if current_value != new_value.to_string() {
+                        self.table_model.set_data_3a(real_cell, &QVariant::from_int(new_value as i32), 2);
+                        changed_cells += 1;
+                        self.process_edition(self.table_model.item_from_index(real_cell));
+                    }
+                },
+
+                FieldType::U16 => {
+
+                    // To the stupid float conversion problem avoid, this we do.
+                    let new_value = if let Ok(new_value) = text.parse::<u16>() { new_value }
+                    else if let Ok(new_value) = text.parse::<f32>() { new_value.round() as u16 }
+                    else { continue };
+
+                    if current_value != new_value.to_string() {
+                        self.table_model.set_data_3a(real_cell, &QVariant::from_int(new_value as i32), 2);
+                        changed_cells += 1;
+                        self.process_edition(self.table_model.item_from_index(real_cell));
+                    }
+                },
+
+                FieldType::U32 => {
+
+                    // To the stupid float conversion problem avoid, this we do.
+                    let new_value = if let Ok(new_value) = text.parse::<u32>() { new_value }
+                    else if let Ok(new_value) = text.parse::<f32>() { new_value.round() as u32 }
+                    else { continue };
+
+                    if current_value != new_value.to_string() {
+                        self.table_model.set_data_3a(real_cell, &QVariant::from_i64(new_value as i64), 2);
+                        changed_cells += 1;
+                        self.process_edition(self.table_model.item_from_index(real_cell));
+                    }
+                },
+
                 _ => {
                     if current_value != *text {
                         self.table_model.set_data_3a(real_cell, &QVariant::from_q_string(&QString::from_std_str(text)), 2);
@@ -285,6 +285,24 @@ pub unsafe fn get_default_item_from_field(field: &Field) -> CppBox<QStandardItem
             item.set_data_2a(&QVariant::from_i64(data), 2);
             item
         },
+
+        // For the rest of the numeric types, we just build the default data and turn it into an item.
+        FieldType::F64 |
+        FieldType::I8 |
+        FieldType::U8 |
+        FieldType::U16 |
+        FieldType::U32 |
+        FieldType::ColourRGB |
+        FieldType::OptionalI16 |
+        FieldType::OptionalI32 |
+        FieldType::OptionalI64 => {
+            let data = if let Some(Ok(default_value)) = field.get_default_value().as_ref().map(|default_value| DecodedData::new_from_string(default_value, field.get_ref_field_type())) {
+                default_value
+            } else {
+                DecodedData::default(field.get_ref_field_type())
+            };
+            get_item_from_decoded_data(&data)
+        },
         FieldType::StringU8 |
         FieldType::StringU16 |
         FieldType::OptionalStringU8 |
@@ -461,6 +479,80 @@ pub unsafe fn get_item_from_decoded_data(data: &DecodedData) -> CppBox<QStandard
             item.set_data_2a(&QVariant::from_i64(*data), 2);
             item
         },
+
+        // Doubles get the same treatment as floats.
+        DecodedData::F64(ref data) => {
+            let data = {
+                let data_str = format!("{}", data);
+                if let Some(position) = data_str.find('.') {
+                    let decimals = &data_str[position..].len();
+                    if *decimals > 3 { format!("{:.3}", data).parse::<f64>().unwrap() }
+                    else { *data }
+                }
+                else { *data }
+            };
+
+            let item = QStandardItem::new();
+            item.set_tool_tip(&QString::from_std_str(&tre("original_data", &[&data.to_string()])));
+            item.set_data_2a(&QVariant::from_bool(true), ITEM_HAS_SOURCE_VALUE);
+            item.set_data_2a(&QVariant::from_bool(false), ITEM_IS_SEQUENCE);
+            item.set_data_2a(&QVariant::from_double(data), ITEM_SOURCE_VALUE);
+            item.set_data_2a(&QVariant::from_double(data), 2);
+            item
+        },
+        DecodedData::I8(ref data) => {
+            let item = QStandardItem::new();
+            item.set_tool_tip(&QString::from_std_str(tre("original_data", &[&data.to_string()])));
+            item.set_data_2a(&QVariant::from_bool(true), ITEM_HAS_SOURCE_VALUE);
+            item.set_data_2a(&QVariant::from_bool(false), ITEM_IS_SEQUENCE);
+            item.set_data_2a(&QVariant::from_int(*data as i32), ITEM_SOURCE_VALUE);
+            item.set_data_2a(&QVariant::from_int(*data as i32), 2);
+            item
+        },
+        DecodedData::U8(ref data) => {
+            let item = QStandardItem::new();
+            item.set_tool_tip(&QString::from_std_str(tre("original_data", &[&data.to_string()])));
+            item.set_data_2a(&QVariant::from_bool(true), ITEM_HAS_SOURCE_VALUE);
+            item.set_data_2a(&QVariant::from_bool(false), ITEM_IS_SEQUENCE);
+            item.set_data_2a(&QVariant::from_int(*data as i32), ITEM_SOURCE_VALUE);
+            item.set_data_2a(&QVariant::from_int(*data as i32), 2);
+            item
+        },
+        DecodedData::U16(ref data) => {
+            let item = QStandardItem::new();
+            item.set_tool_tip(&QString::from_std_str(tre("original_data", &[&data.to_string()])));
+            item.set_data_2a(&QVariant::from_bool(true), ITEM_HAS_SOURCE_VALUE);
+            item.set_data_2a(&QVariant::from_bool(false), ITEM_IS_SEQUENCE);
+            item.set_data_2a(&QVariant::from_int(*data as i32), ITEM_SOURCE_VALUE);
+            item.set_data_2a(&QVariant::from_int(*data as i32), 2);
+            item
+        },
+
+        // U32 doesn't fit in an i32, so we use the i64 variant for it.
+        DecodedData::U32(ref data) => {
+            let item = QStandardItem::new();
+            item.set_tool_tip(&QString::from_std_str(tre("original_data", &[&data.to_string()])));
+            item.set_data_2a(&QVariant::from_bool(true), ITEM_HAS_SOURCE_VALUE);
+            item.set_data_2a(&QVariant::from_bool(false), ITEM_IS_SEQUENCE);
+            item.set_data_2a(&QVariant::from_i64(*data as i64), ITEM_SOURCE_VALUE);
+            item.set_data_2a(&QVariant::from_i64(*data as i64), 2);
+            item
+        },
+
+        // Colours and optional numbers are edited as text, so they can be empty or hexadecimal.
+        DecodedData::ColourRGB(_) |
+        DecodedData::OptionalI16(_) |
+        DecodedData::OptionalI32(_) |
+        DecodedData::OptionalI64(_) => {
+            let text = data.data_to_string();
+            let item = QStandardItem::from_q_string(&QString::from_std_str(&text));
+            item.set_tool_tip(&QString::from_std_str(&tre("original_data", &[&text])));
+            item.set_data_2a(&QVariant::from_bool(true), ITEM_HAS_SOURCE_VALUE);
+            item.set_data_2a(&QVariant::from_bool(false), ITEM_IS_SEQUENCE);
+            item.set_data_2a(&QVariant::from_q_string(&QString::from_std_str(&text)), ITEM_SOURCE_VALUE);
+            item
+        },
+
         // All these are Strings, so it can be together,
         DecodedData::StringU8(ref data) |
         DecodedData::StringU16(ref data) |
@@ -510,9 +602,18 @@ pub unsafe fn build_columns(
         match field.get_ref_field_type() {
             FieldType::Boolean => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_BOOLEAN),
             FieldType::F32 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
+            FieldType::F64 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
+            FieldType::I8 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
             FieldType::I16 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
             FieldType::I32 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
             FieldType::I64 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
+            FieldType::U8 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
+            FieldType::U16 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
+            FieldType::U32 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
+            FieldType::ColourRGB => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
+            FieldType::OptionalI16 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
+            FieldType::OptionalI32 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
+            FieldType::OptionalI64 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_NUMBER),
             FieldType::StringU8 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_STRING),
             FieldType::StringU16 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_STRING),
             FieldType::OptionalStringU8 => table_view_primary.set_column_width(index as i32, COLUMN_SIZE_STRING),
@@ -735,24 +836,32 @@ pub unsafe fn setup_item_delegates(
         else {
             match field.get_ref_field_type() {
                 FieldType::Boolean => {},
-                FieldType::F32 => {
+                FieldType::F32 | FieldType::F64 => {
                     new_doublespinbox_item_delegate_safe(&table_view_primary.static_upcast::<QObject>().as_ptr(), column as i32);
                     new_doublespinbox_item_delegate_safe(&table_view_frozen.static_upcast::<QObject>().as_ptr(), column as i32);
                 },
+                FieldType::I8 => {
+                    new_spinbox_item_delegate_safe(&table_view_primary.static_upcast::<QObject>().as_ptr(), column as i32, 8);
+                    new_spinbox_item_delegate_safe(&table_view_frozen.static_upcast::<QObject>().as_ptr(), column as i32, 8);
+                },
                 FieldType::I16 => {
                     new_spinbox_item_delegate_safe(&table_view_primary.static_upcast::<QObject>().as_ptr(), column as i32, 16);
                     new_spinbox_item_delegate_safe(&table_view_frozen.static_upcast::<QObject>().as_ptr(), column as i32, 16);
                 },
-                FieldType::I32 => {
+                FieldType::I32 | FieldType::U8 | FieldType::U16 => {
                     new_spinbox_item_delegate_safe(&table_view_primary.static_upcast::<QObject>().as_ptr(), column as i32, 32);
                     new_spinbox_item_delegate_safe(&table_view_frozen.static_upcast::<QObject>().as_ptr(), column as i32, 32);
                 },
 
-                // LongInteger uses normal string controls due to QSpinBox being limited to i32.
-                FieldType::I64 => {
+                // LongInteger and u32 use normal string controls due to QSpinBox being limited to i32.
+                FieldType::I64 | FieldType::U32 => {
                     new_spinbox_item_delegate_safe(&table_view_primary.static_upcast::<QObject>().as_ptr(), column as i32, 64);
                     new_spinbox_item_delegate_safe(&table_view_frozen.static_upcast::<QObject>().as_ptr(), column as i32, 64);
                 },
+                FieldType::ColourRGB |
+                FieldType::OptionalI16 |
+                FieldType::OptionalI32 |
+                FieldType::OptionalI64 |
                 FieldType::StringU8 |
                 FieldType::StringU16 |
                 FieldType::OptionalStringU8 |
@@ -818,6 +927,20 @@ pub unsafe fn get_table_from_view(
                 FieldType::I16 => DecodedData::I16(model.item_2a(row as i32, column as i32).data_1a(2).to_int_0a() as i16),
                 FieldType::I32 => DecodedData::I32(model.item_2a(row as i32, column as i32).data_1a(2).to_int_0a()),
                 FieldType::I64 => DecodedData::I64(model.item_2a(row as i32, column as i32).data_1a(2).to_long_long_0a()),
+                FieldType::F64 => DecodedData::F64(model.item_2a(row as i32, column as i32).data_1a(2).to_double_0a()),
+                FieldType::I8 => DecodedData::I8(model.item_2a(row as i32, column as i32).data_1a(2).to_int_0a() as i8),
+                FieldType::U8 => DecodedData::U8(model.item_2a(row as i32, column as i32).data_1a(2).to_int_0a() as u8),
+                FieldType::U16 => DecodedData::U16(model.item_2a(row as i32, column as i32).data_1a(2).to_int_0a() as u16),
+                FieldType::U32 => DecodedData::U32(model.item_2a(row as i32, column as i32).data_1a(2).to_long_long_0a() as u32),
+
+                // Colours and optional numbers are stored as text, so we need to parse them. If they're invalid, we use the default value.
+                FieldType::ColourRGB |
+                FieldType::OptionalI16 |
+                FieldType::OptionalI32 |
+                FieldType::OptionalI64 => {
+                    let text = QString::to_std_string(&model.item_2a(row as i32, column as i32).text());
+                    DecodedData::new_from_string(&text, field.get_ref_field_type()).unwrap_or_else(|_| DecodedData::default(field.get_ref_field_type()))
+                },
 
                 // All these are just normal Strings.
                 FieldType::StringU8 => DecodedData::StringU8(QString::to_std_string(&model.item_2a(row as i32, column as i32).text())),